@@ -0,0 +1,95 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+use intl_benchmarks::{
+    build_corpus_database, definitions_file_content, extract_definitions, extract_translations,
+    insert_corpus, translations_file_content, CORPUS_SIZE, DEFINITIONS_FILE_NAME,
+    TRANSLATION_LOCALE,
+};
+use intl_database_core::{key_symbol, MessagesDatabase, DEFAULT_LOCALE};
+use intl_database_exporter::{IntlMessageBundler, IntlMessageBundlerOptions};
+use intl_database_service::IntlDatabaseService;
+use intl_validator::validate_message;
+
+fn extraction(c: &mut Criterion) {
+    let definitions = definitions_file_content(CORPUS_SIZE);
+    let translations = translations_file_content(CORPUS_SIZE);
+
+    let mut group = c.benchmark_group("extract file");
+    group.throughput(Throughput::Elements(CORPUS_SIZE as u64));
+    group.bench_function("js definitions", |b| {
+        b.iter(|| extract_definitions(black_box(&definitions)))
+    });
+    group.bench_function("json translations", |b| {
+        b.iter(|| extract_translations(black_box(&translations)))
+    });
+    group.finish();
+}
+
+fn insertion(c: &mut Criterion) {
+    let definitions = definitions_file_content(CORPUS_SIZE);
+    let translations = translations_file_content(CORPUS_SIZE);
+
+    let mut group = c.benchmark_group("database");
+    group.throughput(Throughput::Elements(CORPUS_SIZE as u64));
+    group.bench_function("insert corpus", |b| {
+        b.iter_batched(
+            || {
+                (
+                    extract_definitions(&definitions),
+                    extract_translations(&translations),
+                )
+            },
+            |(definitions, translations)| {
+                let mut database = MessagesDatabase::new();
+                insert_corpus(&mut database, definitions, translations);
+                database
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn validation(c: &mut Criterion) {
+    let database = build_corpus_database(CORPUS_SIZE);
+
+    let mut group = c.benchmark_group("database");
+    group.throughput(Throughput::Elements(CORPUS_SIZE as u64));
+    group.bench_function("validate all messages", |b| {
+        b.iter(|| {
+            for message in database.messages.values() {
+                black_box(validate_message(message));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bundling(c: &mut Criterion) {
+    let database = build_corpus_database(CORPUS_SIZE);
+    let source_key = key_symbol(DEFINITIONS_FILE_NAME);
+
+    let mut group = c.benchmark_group("bundle");
+    group.throughput(Throughput::Elements(CORPUS_SIZE as u64));
+    for locale in [DEFAULT_LOCALE, TRANSLATION_LOCALE] {
+        group.bench_function(locale, |b| {
+            b.iter(|| {
+                let mut output = Vec::with_capacity(CORPUS_SIZE * 80);
+                IntlMessageBundler::new(
+                    &database,
+                    &mut output,
+                    source_key,
+                    key_symbol(locale),
+                    IntlMessageBundlerOptions::default(),
+                )
+                .run()
+                .unwrap();
+                output
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, extraction, insertion, validation, bundling);
+criterion_main!(benches);
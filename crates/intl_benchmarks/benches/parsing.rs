@@ -0,0 +1,28 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use intl_benchmarks::{ICU_HEAVY_MESSAGE, LONG_MESSAGE, SHORT_MESSAGE};
+use intl_markdown::parse_intl_message;
+use intl_message_utils::message_may_have_blocks;
+
+fn parse(content: &str) {
+    black_box(parse_intl_message(
+        content,
+        message_may_have_blocks(content),
+    ));
+}
+
+fn message_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse message");
+    for (name, content) in [
+        ("short", SHORT_MESSAGE),
+        ("long", LONG_MESSAGE),
+        ("icu heavy", ICU_HEAVY_MESSAGE),
+    ] {
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_function(name, |b| b.iter(|| parse(black_box(content))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, message_parsing);
+criterion_main!(benches);
@@ -0,0 +1,166 @@
+//! Shared fixtures for the benchmarks in `benches/`. Everything here is deterministic, so that
+//! every run measures exactly the same work.
+use intl_database_core::{
+    key_symbol, DefinitionFile, FilePosition, KeySymbolSet, MessageDefinitionSource,
+    MessageTranslationSource, MessagesDatabase, RawMessageDefinition, RawMessageTranslation,
+    SourceFile, SourceFileMeta, TranslationFile,
+};
+use intl_database_js_source::JsMessageSource;
+use intl_database_json_source::JsonMessageSource;
+
+/// Number of messages in the synthetic corpus.
+pub const CORPUS_SIZE: usize = 10_000;
+pub const DEFINITIONS_FILE_NAME: &str = "benchmark.messages.js";
+pub const TRANSLATIONS_FILE_NAME: &str = "fr.messages.json";
+pub const TRANSLATION_LOCALE: &str = "fr";
+
+pub const SHORT_MESSAGE: &str = "Welcome back, {username}!";
+pub const LONG_MESSAGE: &str = "Your **server** has reached the limit for [custom emoji](https://support.example.com/emoji). To add more, you can *remove* some of the existing ones from **Server Settings**, or boost the server to unlock more slots. Boosting also unlocks `higher quality` audio, a custom invite background, animated server icons, and much more. Learn about everything that's included on the $[perks page](perksHook), and thanks for being part of the community!";
+pub const ICU_HEAVY_MESSAGE: &str = "{count, plural, =0 {No one is typing} one {{firstUser} is typing...} =2 {{firstUser} and {secondUser} are typing...} other {{firstUser}, {secondUser}, and {count, number} others are typing...}} {gender, select, female {She} male {He} other {They}} joined {joinedAt, date, short} at {joinedAt, time, short} as the {position, selectordinal, one {#st} two {#nd} few {#rd} other {#th}} member.";
+
+/// Shapes of message that the corpus cycles through, roughly matching the mix of a real project:
+/// mostly plain text, with a fair amount of markdown and ICU syntax.
+const MESSAGE_TEMPLATES: [&str; 6] = [
+    "Message number {index}",
+    "Click **here** to open item {index}",
+    "{count, plural, one {# reply} other {# replies}} to thread {index}",
+    "Hello {username}, you have *{count, number}* new messages in $[channel {index}](channelHook)",
+    "{gender, select, female {She} male {He} other {They}} reacted to message {index}",
+    "Visit [the settings page](https://example.com/settings/{index}) to change `option {index}`",
+];
+
+pub fn corpus_key(index: usize) -> String {
+    format!("MESSAGE_{index:05}")
+}
+
+/// Returns the source content of the message at `index` in the corpus.
+pub fn corpus_message(index: usize) -> String {
+    MESSAGE_TEMPLATES[index % MESSAGE_TEMPLATES.len()].replace("{index}", &index.to_string())
+}
+
+/// Returns the translated content of the message at `index` in the corpus. Translations have the
+/// same structure as the source, with different text.
+pub fn corpus_translation(index: usize) -> String {
+    format!("[fr] {}", corpus_message(index))
+}
+
+/// Returns the content of a JS definitions file containing the first `size` corpus messages.
+pub fn definitions_file_content(size: usize) -> String {
+    let mut content = format!(
+        "import {{defineMessages}} from '{}';\n\nexport default defineMessages({{\n",
+        intl_message_utils::RUNTIME_PACKAGE_NAME
+    );
+    for index in 0..size {
+        // JSON string literals are also valid JS string literals.
+        let value = serde_json::to_string(&corpus_message(index)).unwrap();
+        content.push_str(&format!("  {}: {value},\n", corpus_key(index)));
+    }
+    content.push_str("});\n");
+    content
+}
+
+/// Returns the content of a JSON translations file containing the first `size` corpus messages.
+pub fn translations_file_content(size: usize) -> String {
+    let translations: serde_json::Map<String, serde_json::Value> = (0..size)
+        .map(|index| (corpus_key(index), corpus_translation(index).into()))
+        .collect();
+    serde_json::to_string_pretty(&translations).unwrap()
+}
+
+pub fn extract_definitions(content: &str) -> (SourceFileMeta, Vec<RawMessageDefinition>) {
+    let data = JsMessageSource
+        .extract_definitions(key_symbol(DEFINITIONS_FILE_NAME), content)
+        .expect("benchmark definitions should be valid");
+    (data.meta, data.definitions.collect())
+}
+
+pub fn extract_translations(content: &str) -> Vec<RawMessageTranslation> {
+    JsonMessageSource
+        .extract_translations(key_symbol(TRANSLATIONS_FILE_NAME), content.into())
+        .expect("benchmark translations should be valid")
+        .collect()
+}
+
+/// Insert already-extracted definitions and translations into `database`, registering their
+/// source files the same way that processing real files does.
+pub fn insert_corpus(
+    database: &mut MessagesDatabase,
+    (meta, definitions): (SourceFileMeta, Vec<RawMessageDefinition>),
+    translations: Vec<RawMessageTranslation>,
+) {
+    let definitions_key = key_symbol(DEFINITIONS_FILE_NAME);
+    let translations_key = key_symbol(TRANSLATIONS_FILE_NAME);
+    let source_locale = key_symbol(intl_database_core::DEFAULT_LOCALE);
+    let translation_locale = key_symbol(TRANSLATION_LOCALE);
+
+    let mut definition_keys = KeySymbolSet::default();
+    for definition in definitions {
+        let position = FilePosition {
+            file: definitions_key,
+            line: definition.position.line,
+            col: definition.position.col,
+        };
+        definition_keys.insert(definition.name);
+        database
+            .insert_definition(
+                &definition.name,
+                definition.value.with_file_position(position),
+                source_locale,
+                definition.meta,
+                true,
+            )
+            .unwrap();
+    }
+
+    let mut translation_keys = KeySymbolSet::default();
+    for translation in translations {
+        let position = FilePosition {
+            file: translations_key,
+            line: translation.position.line,
+            col: translation.position.col,
+        };
+        translation_keys.insert(translation.name);
+        database
+            .insert_translation(
+                translation.name,
+                translation_locale,
+                translation.value.with_file_position(position),
+                true,
+            )
+            .unwrap();
+    }
+
+    database.get_or_create_source_file(
+        definitions_key,
+        SourceFile::Definition(DefinitionFile::new(
+            DEFINITIONS_FILE_NAME.into(),
+            meta,
+            KeySymbolSet::default(),
+        )),
+    );
+    database
+        .set_source_file_keys(definitions_key, definition_keys)
+        .unwrap();
+    database.get_or_create_source_file(
+        translations_key,
+        SourceFile::Translation(TranslationFile::new(
+            TRANSLATIONS_FILE_NAME.into(),
+            translation_locale,
+            KeySymbolSet::default(),
+        )),
+    );
+    database
+        .set_source_file_keys(translations_key, translation_keys)
+        .unwrap();
+}
+
+/// Build a database containing the first `size` corpus messages and their translations.
+pub fn build_corpus_database(size: usize) -> MessagesDatabase {
+    let mut database = MessagesDatabase::new();
+    insert_corpus(
+        &mut database,
+        extract_definitions(&definitions_file_content(size)),
+        extract_translations(&translations_file_content(size)),
+    );
+    database
+}
@@ -1,15 +1,21 @@
+use std::sync::Arc;
+
+use intl_markdown::ParserOptions;
 use serde::Serialize;
+use xxhash_rust::xxh64::Xxh64;
 
 use intl_message_utils::hash_message_key;
 
 use crate::database::symbol::{KeySymbol, KeySymbolMap};
+use crate::message::history::{MessageRevision, MESSAGE_HISTORY_LIMIT};
 use crate::message::meta::MessageMeta;
+use crate::message::review_state::ReviewState;
 use crate::message::value::MessageValue;
 use crate::message::variables::MessageVariables;
 
 /// Any message that is defined through `defineMessage` will be a `Normal`
 /// message definition.
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct Message {
     /// Original, plain text name of the message given in its definition.
     key: KeySymbol,
@@ -23,6 +29,14 @@ pub struct Message {
     source_locale: Option<KeySymbol>,
     /// Meta information about how to handle and process this message.
     meta: MessageMeta,
+    /// Hash of the source value and meta of this message, which only changes when the definition
+    /// does. `None` when the message has no definition.
+    #[serde(rename = "contentHash")]
+    content_hash: Option<String>,
+    /// Every version of the definition of this message since it was first processed, oldest
+    /// first, up to [MESSAGE_HISTORY_LIMIT] of them.
+    #[serde(skip)]
+    history: Vec<MessageRevision>,
 }
 
 impl Message {
@@ -38,8 +52,11 @@ impl Message {
             translations: KeySymbolMap::default(),
             source_locale: Some(source_locale),
             meta,
+            content_hash: None,
+            history: vec![],
         };
         message.translations.insert(source_locale, value);
+        message.update_content_hash();
         message
     }
 
@@ -50,6 +67,8 @@ impl Message {
             translations: KeySymbolMap::default(),
             source_locale: None,
             meta: MessageMeta::default(),
+            content_hash: None,
+            history: vec![],
         };
         message.translations.insert(locale, value);
         message
@@ -71,15 +90,33 @@ impl Message {
     pub fn meta(&self) -> &MessageMeta {
         &self.meta
     }
+    /// Return a stable hash of the source value, source locale, and meta of this message, for
+    /// detecting which messages changed between runs without comparing their full content.
+    pub fn content_hash(&self) -> Option<&str> {
+        self.content_hash.as_deref()
+    }
+    /// Return the versions of the definition of this message that have been processed, oldest
+    /// first. The definition is kept even after it's removed, so the last version isn't
+    /// necessarily the current one.
+    pub fn history(&self) -> &[MessageRevision] {
+        &self.history
+    }
     //#endregion
 
     //#region Mutation
 
-    /// Create or update the definition for this message with the given information.
+    /// Create or update the definition for this message with the given information. If the
+    /// definition moves to a different source locale, like when its file changes its
+    /// `sourceLocale`, the previous source value is removed rather than left behind as a
+    /// translation.
     pub fn set_definition(&mut self, source: MessageValue, locale: KeySymbol, meta: MessageMeta) {
+        if let Some(previous) = self.source_locale.filter(|previous| *previous != locale) {
+            self.translations.remove(&previous);
+        }
         self.translations.insert(locale, source);
         self.source_locale = Some(locale);
         self.meta = meta;
+        self.update_content_hash();
     }
 
     /// Removes the source definition of this message, including both the translation and the
@@ -89,15 +126,113 @@ impl Message {
             Some(locale) => self.translations.remove(locale),
             None => None,
         };
+        self.content_hash = None;
         (translation, self.source_locale.take())
     }
 
-    pub fn set_translation(&mut self, locale: KeySymbol, value: MessageValue) {
+    /// Set the value of this message in `locale`. Translations are marked with the current content
+    /// hash of the definition as the version they were written for, unless they already have one
+    /// or their content is the same as the translation they replace, which keeps its version.
+    pub fn set_translation(&mut self, locale: KeySymbol, mut value: MessageValue) {
+        if self.source_locale == Some(locale) {
+            self.translations.insert(locale, value);
+            self.update_content_hash();
+            return;
+        }
+        if value.source_hash.is_none() {
+            value.source_hash = match self.translations.get(&locale) {
+                Some(previous) if previous.raw == value.raw => previous.source_hash.clone(),
+                _ => self.content_hash.clone(),
+            };
+        }
         self.translations.insert(locale, value);
     }
 
     pub fn remove_translation(&mut self, locale: KeySymbol) -> Option<MessageValue> {
-        self.translations.remove(&locale)
+        let removed = self.translations.remove(&locale);
+        if self.source_locale == Some(locale) {
+            self.update_content_hash();
+        }
+        removed
+    }
+
+    /// Recompute [Message::content_hash] from the current definition. Every field is written with
+    /// a terminator so that moving content from one field to the next changes the hash.
+    fn update_content_hash(&mut self) {
+        let Some(source) = self.get_source_translation() else {
+            self.content_hash = None;
+            return;
+        };
+        let mut hasher = Xxh64::new(0);
+        for field in [
+            source.raw.as_str(),
+            self.source_locale.as_deref().unwrap_or_default(),
+            if self.meta.secret { "1" } else { "0" },
+            if self.meta.translate { "1" } else { "0" },
+            if self.meta.description.is_some() { "1" } else { "0" },
+            self.meta.description.as_deref().unwrap_or_default(),
+            self.meta.strictness.as_str(),
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update(&[0]);
+        }
+        // Declared variable types change the generated types of the message.
+        for (name, kind) in &self.meta.variables {
+            for field in [name.as_str(), kind.kind_name()] {
+                hasher.update(field.as_bytes());
+                hasher.update(&[0]);
+            }
+        }
+        let content_hash = format!("{:016x}", hasher.digest());
+        self.record_revision(&content_hash);
+        self.content_hash = Some(content_hash);
+    }
+
+    /// Add the current definition to the history of this message if `content_hash` is different
+    /// from the latest version. Translations that were set before the message had a definition are
+    /// marked as written for this one, since there's nothing older they could have been for.
+    fn record_revision(&mut self, content_hash: &str) {
+        if self
+            .history
+            .last()
+            .is_some_and(|latest| latest.content_hash == content_hash)
+        {
+            return;
+        }
+        let Some(source_locale) = self.source_locale else {
+            return;
+        };
+        for (locale, value) in self.translations.iter_mut() {
+            if *locale != source_locale && value.source_hash.is_none() {
+                value.source_hash = Some(content_hash.to_string());
+            }
+        }
+        let raw = self.translations[&source_locale].raw.to_string();
+        self.history
+            .push(MessageRevision::new(raw, content_hash.to_string()));
+        if self.history.len() > MESSAGE_HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
+
+    /// Set the options that every value of this message is parsed with. See
+    /// [MessageValue::set_parser_options].
+    pub fn set_parser_options(&mut self, parser_options: &Option<Arc<ParserOptions>>) {
+        for value in self.translations.values_mut() {
+            value.set_parser_options(parser_options.clone());
+        }
+    }
+
+    /// Set the review state of the translation in `locale`. Returns false if the message has no
+    /// translation in that locale.
+    pub fn set_review_state(&mut self, locale: KeySymbol, review_state: ReviewState) -> bool {
+        match self.translations.get_mut(&locale) {
+            Some(value) => {
+                value.review_state = review_state;
+                true
+            }
+            None => false,
+        }
     }
 
     //#endregion
@@ -107,7 +242,30 @@ impl Message {
         self.source_locale.is_some()
     }
 
-    /// Return the translation entry for the default locale for this message.
+    /// Returns true if the translation in `locale` was written for an older version of the
+    /// definition of this message than the current one.
+    pub fn is_translation_outdated(&self, locale: KeySymbol) -> bool {
+        let Some(content_hash) = &self.content_hash else {
+            return false;
+        };
+        self.translations
+            .get(&locale)
+            .and_then(|value| value.source_hash.as_ref())
+            .is_some_and(|source_hash| source_hash != content_hash)
+    }
+
+    /// Return the version of the definition that the translation in `locale` was written for, if
+    /// it is still in the history of this message.
+    pub fn translation_revision(&self, locale: KeySymbol) -> Option<&MessageRevision> {
+        let source_hash = self.translations.get(&locale)?.source_hash.as_ref()?;
+        self.history
+            .iter()
+            .rev()
+            .find(|revision| revision.content_hash == *source_hash)
+    }
+
+    /// Return the translation entry for the source locale of this message, which is the locale its
+    /// definitions file is written in, and not necessarily the source locale of the database.
     pub fn get_source_translation(&self) -> Option<&MessageValue> {
         self.source_locale
             .as_ref()
@@ -126,19 +284,20 @@ impl Message {
     /// Returns a set of variables present in the source translation of this message.
     pub fn source_variables(&self) -> Option<&MessageVariables> {
         match self.get_source_translation() {
-            Some(translation) => translation.variables.as_ref(),
+            Some(translation) => translation.variables(),
             _ => None,
         }
     }
 
-    /// Returns a merged set of all variables present in the message, across all translations.
+    /// Returns a merged set of all variables present in the message, across all translations, with
+    /// the types declared in the message's meta taking precedence over the inferred ones.
     pub fn all_variables(&self) -> MessageVariables {
         let mut merged = self
             .source_variables()
             .map_or_else(|| MessageVariables::new(), Clone::clone);
 
         for (_, translation) in self.translations() {
-            match &translation.variables {
+            match translation.variables() {
                 Some(variables) => {
                     merged.merge(variables);
                 }
@@ -146,6 +305,7 @@ impl Message {
             }
         }
 
+        merged.apply_declared_types(&self.meta.variables);
         merged
     }
 
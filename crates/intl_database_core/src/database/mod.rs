@@ -1,16 +1,33 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use intl_markdown::ParserOptions;
+use intl_message_utils::{is_message_definitions_file_with_extensions, normalize_unicode};
 use rustc_hash::FxHashMap;
 
 use crate::error::{DatabaseError, DatabaseResult};
 use crate::message::meta::MessageMeta;
+use crate::message::review_state::ReviewState;
 use crate::message::source_file::SourceFile;
-use crate::message::value::MessageValue;
+use crate::message::value::{default_parser_options, MessageValue};
 
 use self::message::Message;
+use self::performance::{MessageTiming, PerformancePhase, PerformanceRecorder};
+use self::project::Project;
+use self::source::RawMessageError;
 use self::symbol::{get_key_symbol, key_symbol, KeySymbol, KeySymbolMap, KeySymbolSet};
+use self::transaction::DatabaseSnapshot;
 
 pub mod message;
+pub mod performance;
+pub mod project;
+pub mod query;
+pub mod references;
 pub mod source;
 pub mod symbol;
+pub mod transaction;
 
 #[derive(Debug)]
 pub struct MessagesDatabase {
@@ -18,6 +35,34 @@ pub struct MessagesDatabase {
     pub sources: KeySymbolMap<SourceFile>,
     pub hash_lookup: FxHashMap<String, KeySymbol>,
     pub known_locales: KeySymbolSet,
+    /// The locale that definitions are written in, unless their source file sets its own
+    /// `sourceLocale` in its meta. Defaults to [crate::DEFAULT_LOCALE].
+    pub source_locale: KeySymbol,
+    /// Extensions of files that contain message definitions in addition to the built-in
+    /// `.messages.js` variants, like `.messages.mjs`. Files ending with any of these are found when
+    /// scanning for messages files and are processed as JS definitions.
+    pub definitions_extensions: Vec<String>,
+    /// The options that every message value in the database is parsed with, so that validation,
+    /// type generation, and bundling all see the same content. `None` uses the default options.
+    /// See [MessagesDatabase::set_parser_options].
+    parser_options: Option<Arc<ParserOptions>>,
+    /// Names of the hooks that the app provides, like `tooltipHook` in `$[text](tooltipHook)`.
+    /// Validation reports hooks with any other name, and doesn't check them when this isn't set.
+    pub hook_names: Option<Vec<String>>,
+    /// Names of the link handlers that the app provides, like `onClick` in `[text](onClick)`.
+    /// Validation reports handlers with any other name, and doesn't check them when this isn't set.
+    pub link_handler_names: Option<Vec<String>>,
+    /// Packages that share this database, keyed by name. See [Project].
+    pub projects: KeySymbolMap<Project>,
+    /// Timings of parsing and compiling messages, only present while performance recording is
+    /// enabled.
+    pub performance: Option<PerformanceRecorder>,
+    /// Diagnostics from earlier validations, so that values that haven't changed since then don't
+    /// have to be validated again. The cache belongs to the validator, so its type isn't known
+    /// here.
+    pub validation_cache: Mutex<Option<Box<dyn Any + Send>>>,
+    /// Snapshots of the database from when each transaction in progress began, innermost last.
+    transactions: Vec<DatabaseSnapshot>,
 }
 
 impl MessagesDatabase {
@@ -27,9 +72,120 @@ impl MessagesDatabase {
             sources: KeySymbolMap::default(),
             hash_lookup: FxHashMap::default(),
             known_locales: KeySymbolSet::default(),
+            source_locale: key_symbol(crate::DEFAULT_LOCALE),
+            definitions_extensions: vec![],
+            parser_options: None,
+            hook_names: None,
+            link_handler_names: None,
+            projects: KeySymbolMap::default(),
+            performance: None,
+            validation_cache: Mutex::new(None),
+            transactions: vec![],
         }
     }
 
+    pub fn with_source_locale(mut self, source_locale: KeySymbol) -> Self {
+        self.source_locale = source_locale;
+        self
+    }
+
+    pub fn with_definitions_extensions(mut self, definitions_extensions: Vec<String>) -> Self {
+        self.definitions_extensions = definitions_extensions;
+        self
+    }
+
+    pub fn with_parser_options(mut self, parser_options: ParserOptions) -> Self {
+        self.set_parser_options(parser_options);
+        self
+    }
+
+    pub fn with_hook_names(mut self, hook_names: Option<Vec<String>>) -> Self {
+        self.hook_names = hook_names;
+        self
+    }
+
+    pub fn with_link_handler_names(mut self, link_handler_names: Option<Vec<String>>) -> Self {
+        self.link_handler_names = link_handler_names;
+        self
+    }
+
+    /// Returns true if `file_name` contains message definitions, including files that end with
+    /// any of the configured [MessagesDatabase::definitions_extensions].
+    pub fn is_definitions_file(&self, file_name: &str) -> bool {
+        is_message_definitions_file_with_extensions(file_name, &self.definitions_extensions)
+    }
+
+    /// Returns the options that every message value in the database is parsed with.
+    pub fn parser_options(&self) -> &ParserOptions {
+        self.parser_options
+            .as_deref()
+            .unwrap_or_else(|| default_parser_options())
+    }
+
+    /// Returns a shared handle to the options that every message value in the database is parsed
+    /// with, or None for the defaults, for parsing values before they are inserted with
+    /// [MessageValue::parse_with_options].
+    pub fn shared_parser_options(&self) -> Option<Arc<ParserOptions>> {
+        self.parser_options.clone()
+    }
+
+    /// Set the options that every message value is parsed with, including the values that are
+    /// already in the database, which are parsed again the next time their content is needed.
+    pub fn set_parser_options(&mut self, parser_options: ParserOptions) {
+        let parser_options =
+            (parser_options != ParserOptions::default()).then(|| Arc::new(parser_options));
+        self.apply_parser_options(parser_options);
+    }
+
+    /// Store `parser_options` and set them on every message value, unless they are the same as
+    /// the ones already stored. Cached validation results are dropped when they change.
+    fn apply_parser_options(&mut self, parser_options: Option<Arc<ParserOptions>>) {
+        if self.parser_options == parser_options {
+            return;
+        }
+        self.parser_options = parser_options;
+        for message in self.messages.values_mut() {
+            message.set_parser_options(&self.parser_options);
+        }
+        // Every cached diagnostic was found from content parsed with the old options.
+        *self
+            .validation_cache
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Enable or disable recording how long each message takes to parse and compile. Enabling
+    /// recording again after it was disabled starts over with no timings.
+    pub fn set_performance_recording(&mut self, enabled: bool) {
+        match (enabled, &self.performance) {
+            (true, None) => self.performance = Some(PerformanceRecorder::default()),
+            (false, _) => self.performance = None,
+            _ => {}
+        }
+    }
+
+    /// Returns true if the database is recording how long each message takes to parse and compile.
+    pub fn is_recording_performance(&self) -> bool {
+        self.performance.is_some()
+    }
+
+    /// When performance recording is enabled, parse the given value immediately and record how
+    /// long it took, rather than leaving it to be parsed lazily.
+    fn record_parse_time(&self, key: KeySymbol, locale: KeySymbol, value: &MessageValue) {
+        let Some(recorder) = &self.performance else {
+            return;
+        };
+        let start = Instant::now();
+        value.variables();
+        recorder.record(MessageTiming {
+            key,
+            locale,
+            file: value.file_position.map(|position| position.file),
+            phase: PerformancePhase::Parse,
+            duration: start.elapsed(),
+        });
+    }
+
     /// Return the complete message definition under a given key.
     pub fn get_message(&self, key: &str) -> Option<&Message> {
         get_key_symbol(key).and_then(|symbol| self.messages.get(&symbol))
@@ -76,6 +232,21 @@ impl MessagesDatabase {
             .ok_or(DatabaseError::UnknownSourceFile(file_key))
     }
 
+    /// Replace the list of entries that could not be extracted from the given definitions file.
+    pub fn set_definition_file_errors(
+        &mut self,
+        file_key: KeySymbol,
+        errors: Vec<RawMessageError>,
+    ) -> DatabaseResult<()> {
+        match self.sources.get_mut(&file_key) {
+            Some(SourceFile::Definition(file)) => {
+                file.set_errors(errors);
+                Ok(())
+            }
+            _ => Err(DatabaseError::UnknownSourceFile(file_key)),
+        }
+    }
+
     /// Return an iterator over all of the message values owned by the given
     /// source file. The returned values are Options of references to the
     /// message for each key. Keys with no value will still be returned in the
@@ -119,22 +290,29 @@ impl MessagesDatabase {
     pub fn insert_definition(
         &mut self,
         name: &str,
-        value: MessageValue,
+        mut value: MessageValue,
         locale: KeySymbol,
         meta: MessageMeta,
         replace_existing: bool,
     ) -> DatabaseResult<&Message> {
         let key = key_symbol(name);
+        // Complete messages that already exist can not be re-added, since
+        // that would mean two definitions exist. Instead, they can be
+        // _updated_, for example when a definition file changes.
+        if self
+            .messages
+            .get(&key)
+            .is_some_and(|existing| existing.is_defined() && !replace_existing)
+        {
+            return Err(DatabaseError::AlreadyDefined(key));
+        }
+
+        value.set_parser_options(self.parser_options.clone());
+        self.record_parse_time(key, locale, &value);
         match self.messages.get_mut(&key) {
             Some(existing) => {
-                // Complete messages that already exist can not be re-added, since
-                // that would mean two definitions exist. Instead, they can be
-                // _updated_, for example when a definition file changes.
-                if existing.is_defined() && !replace_existing {
-                    return Err(DatabaseError::AlreadyDefined(key));
-                }
-
                 existing.set_definition(value, locale, meta);
+                self.known_locales.insert(locale);
             }
             _ => {
                 // Otherwise this is an entirely new message that gets created.
@@ -169,18 +347,25 @@ impl MessagesDatabase {
         &mut self,
         key: KeySymbol,
         locale: KeySymbol,
-        value: MessageValue,
+        mut value: MessageValue,
         replace_existing: bool,
     ) -> DatabaseResult<&Message> {
+        if self
+            .messages
+            .get(&key)
+            .is_some_and(|message| message.translations().contains_key(&locale))
+            && !replace_existing
+        {
+            return Err(DatabaseError::TranslationAlreadySet(key, locale));
+        }
+
+        value.set_parser_options(self.parser_options.clone());
+        self.record_parse_time(key, locale, &value);
         match self.messages.get_mut(&key) {
             // If the key has an existing message at all, it just gets a new
             // translation entry in the map. The type of the entry does not
             // change here.
             Some(message) => {
-                if message.translations().contains_key(&locale) && !replace_existing {
-                    return Err(DatabaseError::TranslationAlreadySet(key, locale));
-                }
-
                 self.known_locales.insert(locale);
                 message.set_translation(locale, value);
             }
@@ -208,6 +393,66 @@ impl MessagesDatabase {
             .and_then(|message| message.remove_translation(locale))
     }
 
+    /// Set the review state of an existing translation, like when a reviewer approves it. The new
+    /// state is only kept in the database until the translations are exported.
+    pub fn set_translation_review_state(
+        &mut self,
+        message_key: KeySymbol,
+        locale: KeySymbol,
+        review_state: ReviewState,
+    ) -> DatabaseResult<()> {
+        let is_set = self
+            .messages
+            .get_mut(&message_key)
+            .is_some_and(|message| message.set_review_state(locale, review_state));
+        match is_set {
+            true => Ok(()),
+            false => Err(DatabaseError::MissingTranslation(message_key, locale)),
+        }
+    }
+
+    /// Remove stray zero-width characters from every translation and normalize it to NFC, or only
+    /// the translations for `locale` if given. Neither changes how the translations are displayed,
+    /// so they keep their review state. Source messages are never changed, since they are owned
+    /// by the code that defines them. Returns the message key and locale of every translation
+    /// that changed, which are only kept in the database until the translations are exported.
+    pub fn fix_translation_unicode(
+        &mut self,
+        locale: Option<KeySymbol>,
+    ) -> Vec<(KeySymbol, KeySymbol)> {
+        let mut fixed = vec![];
+        for message in self.messages.values_mut() {
+            let fixes: Vec<(KeySymbol, MessageValue)> = message
+                .translations()
+                .iter()
+                .filter(|(translation_locale, _)| {
+                    *message.source_locale() != Some(**translation_locale)
+                        && locale.is_none_or(|locale| locale == **translation_locale)
+                })
+                .filter_map(|(translation_locale, value)| {
+                    let normalized = normalize_unicode(&value.raw);
+                    let Cow::Owned(normalized) = normalized else {
+                        return None;
+                    };
+                    // Offsets into the old content no longer line up, so they are dropped.
+                    let mut fixed_value = MessageValue::from_raw(&normalized)
+                        .with_machine_recovered(value.machine_recovered)
+                        .with_review_state(value.review_state)
+                        .with_comments(value.comments.clone())
+                        .with_source_hash(value.source_hash.clone());
+                    fixed_value.file_position = value.file_position;
+                    fixed_value.set_parser_options(self.parser_options.clone());
+                    Some((*translation_locale, fixed_value))
+                })
+                .collect();
+            for (translation_locale, value) in fixes {
+                message.set_translation(translation_locale, value);
+                fixed.push((message.key(), translation_locale));
+            }
+        }
+        fixed
+    }
+
     //#endregion
 }
 
@@ -215,9 +460,11 @@ impl MessagesDatabase {
 mod tests {
     use std::fmt::Write;
 
+    use intl_markdown::{ApostropheMode, ParserOptions};
     use intl_message_utils::RUNTIME_PACKAGE_NAME;
 
     use crate::database::MessagesDatabase;
+    use crate::{key_symbol, FilePosition, MarkdownStrictness, MessageMeta, MessageValue};
 
     fn new_database() -> MessagesDatabase {
         MessagesDatabase::new()
@@ -300,6 +547,136 @@ mod tests {
             .with_message("ANOTHER_STATUS", "This one is a _separate_ message")
     }
 
+    fn value_in_file(content: &str, file: &str) -> MessageValue {
+        MessageValue::from_raw(content).with_file_position(FilePosition {
+            file: key_symbol(file),
+            line: 1,
+            col: 0,
+        })
+    }
+
+    #[test]
+    fn test_rejected_duplicates_do_not_replace_parse_timings() {
+        let mut database = new_database();
+        database.set_performance_recording(true);
+        let key = "GREETING";
+        let locale = key_symbol("en-US");
+        database
+            .insert_definition(
+                key,
+                value_in_file("Hello", "a.messages.js"),
+                locale,
+                MessageMeta::default(),
+                false,
+            )
+            .unwrap();
+        database
+            .insert_translation(
+                key_symbol(key),
+                key_symbol("fr"),
+                value_in_file("Bonjour", "fr.messages.json"),
+                false,
+            )
+            .unwrap();
+
+        assert!(database
+            .insert_definition(
+                key,
+                value_in_file("Hi", "b.messages.js"),
+                locale,
+                MessageMeta::default(),
+                false,
+            )
+            .is_err());
+        assert!(database
+            .insert_translation(
+                key_symbol(key),
+                key_symbol("fr"),
+                value_in_file("Salut", "other/fr.messages.json"),
+                false,
+            )
+            .is_err());
+
+        let report = database.performance.as_ref().unwrap().report(10);
+        let mut files: Vec<&str> = report
+            .messages
+            .iter()
+            .map(|timing| timing.file.unwrap().as_str())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["a.messages.js", "fr.messages.json"]);
+    }
+
+    #[test]
+    fn test_content_hash_covers_strictness() {
+        let mut database = new_database();
+        let locale = key_symbol("en-US");
+        let mut define = |meta: MessageMeta| {
+            database
+                .insert_definition(
+                    "GREETING",
+                    MessageValue::from_raw("Hello"),
+                    locale,
+                    meta,
+                    true,
+                )
+                .unwrap()
+                .content_hash()
+                .unwrap()
+                .to_string()
+        };
+        let full_blocks = define(MessageMeta {
+            strictness: MarkdownStrictness::FullBlocks,
+            ..MessageMeta::default()
+        });
+        let plain = define(MessageMeta {
+            strictness: MarkdownStrictness::Plain,
+            ..MessageMeta::default()
+        });
+        assert_ne!(full_blocks, plain);
+    }
+
+    #[test]
+    fn test_parser_options_apply_to_every_value() {
+        let mut database = new_database();
+        let locale = key_symbol("en-US");
+        let literal = ParserOptions::default().with_apostrophe_mode(ApostropheMode::Literal);
+        let variable_count = |database: &MessagesDatabase, key: &str, locale: &str| {
+            database.get_message(key).unwrap().translations()[&key_symbol(locale)]
+                .variables()
+                .map_or(0, |variables| variables.count())
+        };
+        database
+            .insert_definition(
+                "GREETING",
+                MessageValue::from_raw("Hi '{name}'"),
+                locale,
+                MessageMeta::default(),
+                false,
+            )
+            .unwrap();
+        assert_eq!(variable_count(&database, "GREETING", "en-US"), 0);
+
+        // Values that were already parsed are parsed again with the new options.
+        database.set_parser_options(literal.clone());
+        assert_eq!(variable_count(&database, "GREETING", "en-US"), 1);
+
+        // Values inserted afterward are parsed with them too.
+        database
+            .insert_translation(
+                key_symbol("GREETING"),
+                key_symbol("fr"),
+                MessageValue::from_raw("Salut '{name}'"),
+                false,
+            )
+            .unwrap();
+        assert_eq!(variable_count(&database, "GREETING", "fr"), 1);
+        assert_eq!(database.parser_options(), &literal);
+
+        database.set_parser_options(ParserOptions::default());
+        assert_eq!(variable_count(&database, "GREETING", "fr"), 0);
+    }
+
     // #[test]
     // fn test_definitions_removed_message() {
     //     let mut database = new_database();
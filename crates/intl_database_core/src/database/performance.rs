@@ -0,0 +1,139 @@
+//! Optional instrumentation for finding pathological messages, like huge Markdown blocks, that
+//! take much longer than the rest to parse or compile and end up stalling builds.
+//!
+//! Recording is disabled by default and only enabled with
+//! [`crate::MessagesDatabase::set_performance_recording`]. While enabled, every inserted message
+//! is parsed immediately rather than lazily so that its parse time can be attributed to it, and
+//! anything that compiles messages from the database records how long each one took. Validating
+//! the database also records how long each validation rule took across all of the values it
+//! checked, for finding the rules that are slow on large catalogs.
+use std::cmp::Reverse;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rustc_hash::FxHashMap;
+
+use super::symbol::KeySymbol;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PerformancePhase {
+    /// Parsing the raw content of a message and collecting its variables.
+    Parse,
+    /// Compiling a parsed message into its bundled output format.
+    Compile,
+}
+
+/// The time spent on a single phase for a single value of a message.
+#[derive(Clone, Copy, Debug)]
+pub struct MessageTiming {
+    pub key: KeySymbol,
+    pub locale: KeySymbol,
+    /// The source file that the value came from, when known.
+    pub file: Option<KeySymbol>,
+    pub phase: PerformancePhase,
+    pub duration: Duration,
+}
+
+/// Total time spent on a single phase for all of the recorded values from a source file.
+#[derive(Clone, Copy, Debug)]
+pub struct FileTiming {
+    pub file: KeySymbol,
+    pub phase: PerformancePhase,
+    pub message_count: usize,
+    pub total: Duration,
+    pub slowest: Duration,
+}
+
+/// Total time spent running a single validation rule on every value that it checked.
+#[derive(Clone, Copy, Debug)]
+pub struct RuleTiming {
+    pub rule: &'static str,
+    pub value_count: usize,
+    pub total: Duration,
+    pub slowest: Duration,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PerformanceReport {
+    /// The slowest individual message values, slowest first.
+    pub messages: Vec<MessageTiming>,
+    /// Totals for every file with recorded timings, slowest first.
+    pub files: Vec<FileTiming>,
+    /// Totals for every rule from the most recent validation, slowest first.
+    pub rules: Vec<RuleTiming>,
+}
+
+type TimingKey = (KeySymbol, KeySymbol, PerformancePhase);
+
+/// Collects message timings while performance recording is enabled.
+///
+/// Only the latest timing for each phase of each message value is kept, so processing a file again
+/// replaces its timings rather than adding to them.
+#[derive(Debug, Default)]
+pub struct PerformanceRecorder {
+    timings: Mutex<FxHashMap<TimingKey, MessageTiming>>,
+    rules: Mutex<Vec<RuleTiming>>,
+}
+
+impl PerformanceRecorder {
+    pub fn record(&self, timing: MessageTiming) {
+        // A poisoned lock only means that another recording panicked, and timings are purely
+        // informational, so the existing map is still used as-is.
+        let mut timings = self
+            .timings
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        timings.insert((timing.key, timing.locale, timing.phase), timing);
+    }
+
+    /// Replace the rule timings with the ones from a new validation.
+    pub fn record_rules(&self, mut rules: Vec<RuleTiming>) {
+        rules.sort_by_key(|timing| Reverse(timing.total));
+        *self.rules.lock().unwrap_or_else(|error| error.into_inner()) = rules;
+    }
+
+    /// Return the `limit` slowest message values, along with the totals for every file and rule.
+    pub fn report(&self, limit: usize) -> PerformanceReport {
+        let timings = self
+            .timings
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+
+        let mut messages: Vec<MessageTiming> = timings.values().copied().collect();
+        messages.sort_by_key(|timing| Reverse(timing.duration));
+        messages.truncate(limit);
+
+        let mut files: FxHashMap<(KeySymbol, PerformancePhase), FileTiming> = FxHashMap::default();
+        for timing in timings.values() {
+            let Some(file) = timing.file else {
+                continue;
+            };
+            let entry = files
+                .entry((file, timing.phase))
+                .or_insert_with(|| FileTiming {
+                    file,
+                    phase: timing.phase,
+                    message_count: 0,
+                    total: Duration::ZERO,
+                    slowest: Duration::ZERO,
+                });
+            entry.message_count += 1;
+            entry.total += timing.duration;
+            entry.slowest = entry.slowest.max(timing.duration);
+        }
+        let mut files: Vec<FileTiming> = files.into_values().collect();
+        files.sort_by_key(|timing| Reverse(timing.total));
+
+        let rules = self
+            .rules
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone();
+
+        PerformanceReport {
+            messages,
+            files,
+            rules,
+        }
+    }
+}
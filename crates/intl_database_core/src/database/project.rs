@@ -0,0 +1,147 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::database::message::Message;
+use crate::database::symbol::KeySymbol;
+use crate::database::MessagesDatabase;
+use crate::error::{DatabaseError, DatabaseResult};
+
+/// A package within a monorepo, like `packages/app` and `packages/admin`.
+///
+/// Projects keep their messages in the same database as every other package. Every file under the
+/// project's `root` belongs to it, unless it's also inside of a project nested deeper within it, so
+/// queries, exports, and validation can be limited to the messages defined in one project at a
+/// time.
+#[derive(Clone, Debug, Serialize)]
+pub struct Project {
+    pub name: KeySymbol,
+    /// The directory containing every file in the project.
+    pub root: PathBuf,
+    /// The locale that definitions in this project are written in when they aren't processed with
+    /// an explicit locale. Uses the database's source locale when not set.
+    #[serde(rename = "sourceLocale")]
+    pub source_locale: Option<KeySymbol>,
+    /// The directory where translations for this project are kept, relative to `root`. Definitions
+    /// files in the project keep their translations here unless they set their own
+    /// `translationsPath`, and loaders can use it to find them before any definitions have been
+    /// processed.
+    #[serde(rename = "translationsPath")]
+    pub translations_path: Option<PathBuf>,
+}
+
+impl Project {
+    pub fn new(name: KeySymbol, root: impl Into<PathBuf>) -> Self {
+        Self {
+            name,
+            root: root.into(),
+            source_locale: None,
+            translations_path: None,
+        }
+    }
+
+    pub fn with_source_locale(mut self, source_locale: Option<KeySymbol>) -> Self {
+        self.source_locale = source_locale;
+        self
+    }
+
+    pub fn with_translations_path(mut self, translations_path: Option<PathBuf>) -> Self {
+        self.translations_path = translations_path;
+        self
+    }
+
+    /// Returns true if `file_path` is inside of this project's root. Files inside of a nested
+    /// project are included, so use [MessagesDatabase::is_file_in_project] to check whether the
+    /// file actually belongs to this project.
+    pub fn contains_file(&self, file_path: impl AsRef<Path>) -> bool {
+        file_path.as_ref().starts_with(&self.root)
+    }
+
+    /// Returns the absolute directory where translations for this project are kept, if it has one.
+    pub fn translations_directory(&self) -> Option<PathBuf> {
+        self.translations_path
+            .as_ref()
+            .map(|translations_path| self.root.join(translations_path))
+    }
+}
+
+impl MessagesDatabase {
+    /// Add `project` to the database, replacing any existing project with the same name.
+    /// Projects can be nested, in which case files belong to the project with the deepest root.
+    pub fn register_project(&mut self, project: Project) {
+        self.projects.insert(project.name, project);
+    }
+
+    pub fn get_project(&self, name: KeySymbol) -> DatabaseResult<&Project> {
+        self.projects
+            .get(&name)
+            .ok_or_else(|| DatabaseError::UnknownProject(name.to_string()))
+    }
+
+    /// Returns the project that `file_path` belongs to, if any. When project roots are nested,
+    /// the project with the deepest root is used.
+    pub fn get_project_for_file(&self, file_path: impl AsRef<Path>) -> Option<&Project> {
+        let file_path = file_path.as_ref();
+        self.projects
+            .values()
+            .filter(|project| project.contains_file(file_path))
+            .max_by_key(|project| project.root.components().count())
+    }
+
+    /// Returns true if `file_path` belongs to `project`, and not to another project nested inside of
+    /// it.
+    pub fn is_file_in_project(&self, file_path: impl AsRef<Path>, project: &Project) -> bool {
+        self.get_project_for_file(file_path)
+            .is_some_and(|owner| owner.name == project.name)
+    }
+
+    /// Returns true if `message` is defined in a file that belongs to `project`. Messages that only
+    /// have translations don't belong to any project.
+    pub fn is_message_in_project(&self, message: &Message, project: &Project) -> bool {
+        message
+            .get_source_translation()
+            .and_then(|source| source.file_position)
+            .is_some_and(|position| self.is_file_in_project(position.file.as_str(), project))
+    }
+
+    /// Returns the locale that definitions in `file_path` are written in when no locale is given
+    /// for them, which is the source locale of its project, or the database's when it has none.
+    pub fn get_source_locale_for_file(&self, file_path: impl AsRef<Path>) -> KeySymbol {
+        self.get_project_for_file(file_path)
+            .and_then(|project| project.source_locale)
+            .unwrap_or(self.source_locale)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::database::symbol::key_symbol;
+
+    use super::*;
+
+    #[test]
+    fn test_files_belong_to_deepest_project() {
+        let mut database = MessagesDatabase::new();
+        database.register_project(Project::new(key_symbol("app"), "/repo/app"));
+        database.register_project(Project::new(key_symbol("admin"), "/repo/app/admin"));
+        let app = database.get_project(key_symbol("app")).unwrap();
+        let admin = database.get_project(key_symbol("admin")).unwrap();
+
+        assert!(app.contains_file("/repo/app/admin/a.messages.js"));
+        assert!(!database.is_file_in_project("/repo/app/admin/a.messages.js", app));
+        assert!(database.is_file_in_project("/repo/app/admin/a.messages.js", admin));
+        assert!(database.is_file_in_project("/repo/app/b.messages.js", app));
+        assert!(!database.is_file_in_project("/repo/application/c.messages.js", app));
+    }
+
+    #[test]
+    fn test_translations_directory() {
+        let project = Project::new(key_symbol("app"), "/repo/app");
+        assert_eq!(project.translations_directory(), None);
+        let project = project.with_translations_path(Some("i18n".into()));
+        assert_eq!(
+            project.translations_directory(),
+            Some(PathBuf::from("/repo/app/i18n"))
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use regex::Regex;
+use serde::Serialize;
+
+use crate::database::message::Message;
+use crate::database::symbol::KeySymbol;
+use crate::database::MessagesDatabase;
+use crate::error::{DatabaseError, DatabaseResult};
+
+/// A pattern for matching text content within messages, either as a plain substring or as a
+/// regular expression.
+#[derive(Clone, Debug)]
+pub enum MessageQueryPattern {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl MessageQueryPattern {
+    pub fn substring(pattern: &str) -> Self {
+        Self::Substring(pattern.into())
+    }
+
+    pub fn regex(pattern: &str) -> DatabaseResult<Self> {
+        Regex::new(pattern)
+            .map(Self::Regex)
+            .map_err(|error| DatabaseError::InvalidQueryPattern(error.to_string()))
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            MessageQueryPattern::Substring(pattern) => text.contains(pattern.as_str()),
+            MessageQueryPattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// A set of filters for finding messages in the database. Every filter that is set must match for
+/// a message to be included in the results. An empty query matches every message.
+#[derive(Clone, Debug)]
+pub struct MessageQuery {
+    /// Text pattern to match against the key and/or the values of the message.
+    pub pattern: Option<MessageQueryPattern>,
+    /// When true, `pattern` is matched against the plain text key of the message.
+    pub search_keys: bool,
+    /// When true, `pattern` is matched against the raw content of every translation of the
+    /// message, including the source.
+    pub search_values: bool,
+    /// Only include messages that have a value in this locale.
+    pub with_locale: Option<KeySymbol>,
+    /// Only include messages that do _not_ have a value in this locale.
+    pub without_locale: Option<KeySymbol>,
+    /// Only include messages whose `secret` meta flag matches this value.
+    pub secret: Option<bool>,
+    /// Only include messages whose `translate` meta flag matches this value.
+    pub translate: Option<bool>,
+    /// Only include messages defined in this definitions file.
+    pub definitions_file: Option<KeySymbol>,
+    /// Only include messages that use a variable with this name in any translation.
+    pub variable: Option<KeySymbol>,
+    /// Only include messages defined in a file inside of the registered project with this name.
+    /// Projects are stored in the database, so this is only checked by
+    /// [MessagesDatabase::find_messages] and not by [MessageQuery::matches].
+    pub project: Option<KeySymbol>,
+    /// Number of matching messages to skip before collecting results.
+    pub offset: usize,
+    /// Maximum number of messages to return. When None, all matches are returned.
+    pub limit: Option<usize>,
+}
+
+impl Default for MessageQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageQuery {
+    pub fn new() -> Self {
+        Self {
+            pattern: None,
+            search_keys: true,
+            search_values: true,
+            with_locale: None,
+            without_locale: None,
+            secret: None,
+            translate: None,
+            definitions_file: None,
+            variable: None,
+            project: None,
+            offset: 0,
+            limit: None,
+        }
+    }
+
+    pub fn with_pattern(mut self, pattern: Option<MessageQueryPattern>) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    pub fn with_search_keys(mut self, search_keys: bool) -> Self {
+        self.search_keys = search_keys;
+        self
+    }
+
+    pub fn with_search_values(mut self, search_values: bool) -> Self {
+        self.search_values = search_values;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: Option<KeySymbol>) -> Self {
+        self.with_locale = locale;
+        self
+    }
+
+    pub fn without_locale(mut self, locale: Option<KeySymbol>) -> Self {
+        self.without_locale = locale;
+        self
+    }
+
+    pub fn with_secret(mut self, secret: Option<bool>) -> Self {
+        self.secret = secret;
+        self
+    }
+
+    pub fn with_translate(mut self, translate: Option<bool>) -> Self {
+        self.translate = translate;
+        self
+    }
+
+    pub fn with_definitions_file(mut self, definitions_file: Option<KeySymbol>) -> Self {
+        self.definitions_file = definitions_file;
+        self
+    }
+
+    pub fn with_variable(mut self, variable: Option<KeySymbol>) -> Self {
+        self.variable = variable;
+        self
+    }
+
+    pub fn with_project(mut self, project: Option<KeySymbol>) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_page(mut self, offset: usize, limit: Option<usize>) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+        self
+    }
+
+    /// Returns true if the given message satisfies every filter in this query.
+    pub fn matches(&self, message: &Message) -> bool {
+        let translations = message.translations();
+        if self
+            .with_locale
+            .is_some_and(|locale| !translations.contains_key(&locale))
+        {
+            return false;
+        }
+        if self
+            .without_locale
+            .is_some_and(|locale| translations.contains_key(&locale))
+        {
+            return false;
+        }
+        if self
+            .secret
+            .is_some_and(|secret| message.meta().secret != secret)
+        {
+            return false;
+        }
+        if self
+            .translate
+            .is_some_and(|translate| message.meta().translate != translate)
+        {
+            return false;
+        }
+        if let Some(definitions_file) = self.definitions_file {
+            let defined_in_file = message
+                .get_source_translation()
+                .and_then(|source| source.file_position)
+                .is_some_and(|position| position.file == definitions_file);
+            if !defined_in_file {
+                return false;
+            }
+        }
+        if let Some(variable) = self.variable {
+            let uses_variable = translations.values().any(|value| {
+                value
+                    .variables()
+                    .is_some_and(|variables| variables.contains_key(&variable))
+            });
+            if !uses_variable {
+                return false;
+            }
+        }
+
+        match &self.pattern {
+            None => true,
+            Some(pattern) => {
+                (self.search_keys && pattern.is_match(&message.key()))
+                    || (self.search_values
+                        && translations
+                            .values()
+                            .any(|value| pattern.is_match(&value.raw)))
+            }
+        }
+    }
+}
+
+/// A single page of results from a [MessageQuery].
+#[derive(Debug, Serialize)]
+pub struct MessageQueryResult<'a> {
+    /// The messages on the requested page, ordered by key.
+    pub messages: Vec<&'a Message>,
+    /// The total number of messages that matched the query, across all pages.
+    pub total: usize,
+}
+
+impl MessagesDatabase {
+    /// Find all messages in the database that match the given query. Results are sorted by their
+    /// keys so that pagination is stable across calls, as long as the database is not modified.
+    ///
+    /// Returns an error if the query is limited to a project that hasn't been registered.
+    pub fn find_messages(&self, query: &MessageQuery) -> DatabaseResult<MessageQueryResult<'_>> {
+        let project = query
+            .project
+            .map(|project| self.get_project(project))
+            .transpose()?;
+        let mut matches: Vec<&Message> = self
+            .messages
+            .values()
+            .filter(|message| {
+                project.is_none_or(|project| self.is_message_in_project(message, project))
+            })
+            .filter(|message| query.matches(message))
+            .collect();
+        matches.sort_unstable_by(|a, b| a.key().as_str().cmp(b.key().as_str()));
+
+        let total = matches.len();
+        let messages = matches
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect();
+
+        Ok(MessageQueryResult { messages, total })
+    }
+}
@@ -0,0 +1,108 @@
+//! Messages can include the content of other messages with references like `{@PRODUCT_NAME}`, so
+//! that shared fragments only need to be written and translated once. References are resolved for
+//! one locale at a time, using the translation of the referenced message in that same locale.
+use std::collections::HashMap;
+
+use intl_markdown::{
+    document_inline_content, message_references, resolve_message_references, Document,
+    InlineContent,
+};
+
+use crate::database::symbol::{key_symbol, KeySymbol, KeySymbolSet};
+use crate::database::MessagesDatabase;
+use crate::error::{DatabaseError, DatabaseResult};
+
+impl MessagesDatabase {
+    /// Replace every reference in `document`, the content of the message `key`, with the content
+    /// of the referenced message in `locale`, or its source value when it has no translation in
+    /// that locale. References inside of referenced messages are resolved the same way.
+    ///
+    /// Returns an error if a referenced message isn't defined, has block content that can't be
+    /// used inline, or if the references form a cycle back to a message that is being resolved.
+    pub fn resolve_message_references(
+        &self,
+        key: KeySymbol,
+        document: &Document,
+        locale: KeySymbol,
+    ) -> DatabaseResult<Document> {
+        let resolved = self.resolve_references_in(document, locale, &mut vec![key])?;
+        Ok(resolve_message_references(document.clone(), &resolved))
+    }
+
+    /// Resolve the content of every message referenced in `document`, where `stack` is the chain
+    /// of messages whose references are currently being resolved, ending with the one that
+    /// `document` belongs to.
+    fn resolve_references_in(
+        &self,
+        document: &Document,
+        locale: KeySymbol,
+        stack: &mut Vec<KeySymbol>,
+    ) -> DatabaseResult<HashMap<String, Vec<InlineContent>>> {
+        let mut resolved = HashMap::new();
+        let referencing_key = *stack
+            .last()
+            .expect("Resolving references requires a message");
+        for reference in message_references(document) {
+            if resolved.contains_key(reference) {
+                continue;
+            }
+            let reference_key = key_symbol(reference);
+            if let Some(start) = stack.iter().position(|key| *key == reference_key) {
+                let mut cycle = stack[start..].to_vec();
+                cycle.push(reference_key);
+                return Err(DatabaseError::MessageReferenceCycle(cycle));
+            }
+            let value = self
+                .messages
+                .get(&reference_key)
+                .and_then(|message| {
+                    message
+                        .translations()
+                        .get(&locale)
+                        .or_else(|| message.get_source_translation())
+                })
+                .ok_or(DatabaseError::UnknownMessageReference(
+                    referencing_key,
+                    reference_key,
+                ))?;
+
+            stack.push(reference_key);
+            let nested = self.resolve_references_in(value.parsed(), locale, stack)?;
+            stack.pop();
+            let content = document_inline_content(resolve_message_references(
+                value.parsed().clone(),
+                &nested,
+            ))
+            .ok_or(DatabaseError::BlockMessageReference(
+                referencing_key,
+                reference_key,
+            ))?;
+            resolved.insert(reference.to_string(), content);
+        }
+        Ok(resolved)
+    }
+
+    /// Return the keys of every message that the message `key` references in any of its values,
+    /// either directly or through the messages it references, in the order they are found. Keys
+    /// of messages that aren't defined are included, and cycles are only followed once.
+    pub fn referenced_message_keys(&self, key: KeySymbol) -> Vec<KeySymbol> {
+        let mut keys = vec![];
+        let mut seen = KeySymbolSet::from_iter([key]);
+        let mut pending = vec![key];
+        while let Some(current) = pending.pop() {
+            let Some(message) = self.messages.get(&current) else {
+                continue;
+            };
+            for value in message.translations().values() {
+                for reference in message_references(value.parsed()) {
+                    let reference = key_symbol(reference);
+                    if seen.insert(reference) {
+                        keys.push(reference);
+                        pending.push(reference);
+                    }
+                }
+            }
+        }
+        keys
+    }
+}
@@ -1,17 +1,24 @@
+use std::sync::Arc;
+
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
-use crate::{KeySymbol, MessageMeta, MessageValue, SourceFileKind, SourceFileMeta};
+use crate::{KeySymbol, MessageMeta, MessageValue, SourceFileKind, SourceFileMeta, TextPointer};
 
-#[derive(Debug, Error)]
+#[derive(Clone, Debug, Error)]
 pub enum MessageSourceError {
     #[error("Failed to parse message {0} source: {1}")]
     ParseError(SourceFileKind, String),
+    #[error("Failed to parse message {0} source: {1} at line {line} column {column}", line = .2.line, column = .2.col + 1)]
+    SyntaxError(SourceFileKind, String, RawPosition),
     #[error("Semantic restriction for definitions was violated: {0}")]
     DefinitionRestrictionViolated(String),
     #[error("Semantic restriction for translations was violated: {0}")]
     TranslationRestrictionViolated(String),
     #[error("Message {0} did not contain a message value")]
     NoMessageValue(KeySymbol),
+    #[error("Message {0} includes the fragment {1}, which is not defined in the meta of its source file")]
+    UnknownFragment(KeySymbol, String),
     #[error("Source file meta descriptor is invalid")]
     InvalidSourceFileMeta,
     #[error("Message meta descriptor for message {0} is invalid")]
@@ -26,12 +33,37 @@ pub trait RawMessage {
     fn name(&self) -> KeySymbol;
 }
 
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default, Serialize)]
 pub struct RawPosition {
     pub line: u32,
     pub col: u32,
 }
 
+/// An entry in a source file that could not be extracted as a message.
+///
+/// This happens for entries like a definition with a computed key or a non-static value. The rest
+/// of the file is still extracted as usual, so these are kept alongside the source file to let
+/// authors know the entry was skipped.
+#[derive(Clone, Debug, Serialize)]
+pub struct RawMessageError {
+    pub position: RawPosition,
+    #[serde(rename = "message", serialize_with = "serialize_source_error")]
+    pub error: MessageSourceError,
+}
+
+impl RawMessageError {
+    pub fn new(position: RawPosition, error: MessageSourceError) -> Self {
+        Self { position, error }
+    }
+}
+
+fn serialize_source_error<S: Serializer>(
+    error: &MessageSourceError,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(error)
+}
+
 pub struct RawMessageDefinition {
     pub name: KeySymbol,
     pub value: MessageValue,
@@ -40,13 +72,13 @@ pub struct RawMessageDefinition {
 }
 
 impl RawMessageDefinition {
-    pub fn new<V: AsRef<str>>(
+    pub fn new<V: Into<TextPointer>>(
         name: KeySymbol,
         position: RawPosition,
         value: V,
         meta: MessageMeta,
     ) -> Self {
-        let value = MessageValue::from_raw(value.as_ref());
+        let value = MessageValue::from_text(value.into());
         Self {
             name,
             value,
@@ -69,8 +101,8 @@ pub struct RawMessageTranslation {
 }
 
 impl RawMessageTranslation {
-    pub fn new<V: AsRef<str>>(name: KeySymbol, position: RawPosition, value: V) -> Self {
-        let value = MessageValue::from_raw(value.as_ref());
+    pub fn new<V: Into<TextPointer>>(name: KeySymbol, position: RawPosition, value: V) -> Self {
+        let value = MessageValue::from_text(value.into());
         Self {
             name,
             position,
@@ -85,21 +117,27 @@ impl RawMessage for RawMessageTranslation {
     }
 }
 
-pub trait MessageDefinitionSource {
-    /// Return the default locale for which definitions in the given `file_name` should be applied.
-    fn get_default_locale(&self, file_name: &str) -> KeySymbol;
+/// Everything extracted from a single definitions source file, ready to be inserted into the
+/// database.
+pub struct SourceFileInsertionData<I: Iterator<Item = RawMessageDefinition>> {
+    pub meta: SourceFileMeta,
+    pub definitions: I,
+    /// Entries in the file that could not be extracted as definitions.
+    pub errors: Vec<RawMessageError>,
+}
 
+pub trait MessageDefinitionSource {
     /// Return an [`Iterator`] over all of the message definitions contained in the source file.
     /// Any kind of iterator is valid, so long as it yields complete [`RawMessageDefinition`]
-    /// structs for the database to handle inserting and updating as needed.
+    /// structs for the database to handle inserting and updating as needed. Entries that can't be
+    /// extracted should be returned as `errors` rather than failing the entire file.
     fn extract_definitions(
         self,
         file_name: KeySymbol,
         content: &str,
-    ) -> MessageSourceResult<(
-        SourceFileMeta,
-        impl Iterator<Item = RawMessageDefinition> + '_,
-    )>;
+    ) -> MessageSourceResult<
+        SourceFileInsertionData<impl Iterator<Item = RawMessageDefinition> + '_>,
+    >;
 }
 
 pub trait MessageTranslationSource {
@@ -109,9 +147,12 @@ pub trait MessageTranslationSource {
     /// Return an [`Iterator`] over all of the message translations contained in the source file.
     /// Any kind of iterator is valid, so long as it yields complete [`RawMessageTranslation`]
     /// structs for the database to handle inserting and updating as needed.
+    ///
+    /// The content is shared so that translation values can point into it directly rather than
+    /// copying each value out of the file, see [`crate::TextPointer`].
     fn extract_translations(
         self,
         file_name: KeySymbol,
-        content: &str,
-    ) -> MessageSourceResult<impl Iterator<Item = RawMessageTranslation> + '_>;
+        content: Arc<str>,
+    ) -> MessageSourceResult<impl Iterator<Item = RawMessageTranslation>>;
 }
@@ -0,0 +1,344 @@
+//! Transactions let a series of changes to the database be undone all at once, so that operations
+//! spanning many files, like bulk edits, either fully apply or leave the database as it was.
+//!
+//! Beginning a transaction takes a snapshot of everything that can change, and rolling it back
+//! restores that snapshot. Transactions can be nested, each one rolling back to where it began.
+//! Operations that only change a few known files can use [MessagesDatabase::begin_file_transaction]
+//! instead, which only copies those files and their messages rather than the whole database.
+use std::sync::Arc;
+
+use intl_markdown::ParserOptions;
+use rustc_hash::FxHashMap;
+
+use crate::database::symbol::{KeySymbol, KeySymbolMap, KeySymbolSet};
+use crate::database::MessagesDatabase;
+use crate::error::{DatabaseError, DatabaseResult};
+use crate::{Message, Project, SourceFile};
+
+/// The messages and source files of a [MessagesDatabase] when a transaction began.
+#[derive(Debug)]
+enum SnapshotContent {
+    /// Every message and source file.
+    Full {
+        messages: KeySymbolMap<Message>,
+        sources: KeySymbolMap<SourceFile>,
+        hash_lookup: FxHashMap<String, KeySymbol>,
+    },
+    /// Only the source files of a file transaction and their messages, along with the keys of
+    /// every message and source file that existed, so that the ones added since can be removed.
+    Files {
+        messages: KeySymbolMap<Message>,
+        sources: KeySymbolMap<SourceFile>,
+        message_keys: KeySymbolSet,
+        source_keys: KeySymbolSet,
+    },
+}
+
+/// The content of a [MessagesDatabase] when a transaction began. Performance timings and cached
+/// diagnostics aren't included, since they don't change what is in the database.
+#[derive(Debug)]
+pub(crate) struct DatabaseSnapshot {
+    content: SnapshotContent,
+    known_locales: KeySymbolSet,
+    source_locale: KeySymbol,
+    definitions_extensions: Vec<String>,
+    parser_options: Option<Arc<ParserOptions>>,
+    projects: KeySymbolMap<Project>,
+}
+
+impl MessagesDatabase {
+    /// Begin a transaction, which can later be committed to keep every change made since, or
+    /// rolled back to undo them. Beginning another transaction before this one ends nests it
+    /// inside of this one.
+    ///
+    /// This copies every message and source file in the database. Prefer
+    /// [MessagesDatabase::begin_file_transaction] when the files that will change are known.
+    pub fn begin_transaction(&mut self) {
+        let snapshot = DatabaseSnapshot {
+            content: SnapshotContent::Full {
+                messages: self.messages.clone(),
+                sources: self.sources.clone(),
+                hash_lookup: self.hash_lookup.clone(),
+            },
+            known_locales: self.known_locales.clone(),
+            source_locale: self.source_locale,
+            definitions_extensions: self.definitions_extensions.clone(),
+            parser_options: self.parser_options.clone(),
+            projects: self.projects.clone(),
+        };
+        self.transactions.push(snapshot);
+    }
+
+    /// Begin a transaction that only covers the source files in `files`, like
+    /// [MessagesDatabase::begin_transaction], but only copying those files and the messages they
+    /// define or translate. Files that don't exist yet are removed again by a rollback.
+    ///
+    /// Only changes made by processing these files can be rolled back. Any other message that
+    /// changes during the transaction keeps its changes, and messages that are added are removed.
+    pub fn begin_file_transaction(&mut self, files: &[KeySymbol]) {
+        let mut messages = KeySymbolMap::default();
+        let mut sources = KeySymbolMap::default();
+        for file in files {
+            let Some(source) = self.sources.get(file) else {
+                continue;
+            };
+            for key in source.message_keys() {
+                if let Some(message) = self.messages.get(key) {
+                    messages.insert(*key, message.clone());
+                }
+            }
+            sources.insert(*file, source.clone());
+        }
+        let snapshot = DatabaseSnapshot {
+            content: SnapshotContent::Files {
+                messages,
+                sources,
+                message_keys: self.messages.keys().copied().collect(),
+                source_keys: self.sources.keys().copied().collect(),
+            },
+            known_locales: self.known_locales.clone(),
+            source_locale: self.source_locale,
+            definitions_extensions: self.definitions_extensions.clone(),
+            parser_options: self.parser_options.clone(),
+            projects: self.projects.clone(),
+        };
+        self.transactions.push(snapshot);
+    }
+
+    /// End the innermost transaction, keeping every change made during it. Changes from a nested
+    /// transaction can still be undone by rolling back the one it is nested in.
+    pub fn commit_transaction(&mut self) -> DatabaseResult<()> {
+        self.transactions
+            .pop()
+            .map(|_| ())
+            .ok_or(DatabaseError::NoTransaction)
+    }
+
+    /// End the innermost transaction, undoing every change made since it began.
+    pub fn rollback_transaction(&mut self) -> DatabaseResult<()> {
+        let snapshot = self
+            .transactions
+            .pop()
+            .ok_or(DatabaseError::NoTransaction)?;
+        match snapshot.content {
+            SnapshotContent::Full {
+                messages,
+                sources,
+                hash_lookup,
+            } => {
+                self.messages = messages;
+                self.sources = sources;
+                self.hash_lookup = hash_lookup;
+            }
+            SnapshotContent::Files {
+                messages,
+                sources,
+                message_keys,
+                source_keys,
+            } => {
+                self.messages.retain(|key, _| message_keys.contains(key));
+                self.sources.retain(|key, _| source_keys.contains(key));
+                self.hash_lookup.retain(|_, key| message_keys.contains(key));
+                for (key, message) in messages {
+                    self.hash_lookup.insert(message.hashed_key().clone(), key);
+                    self.messages.insert(key, message);
+                }
+                self.sources.extend(sources);
+            }
+        }
+        self.known_locales = snapshot.known_locales;
+        self.source_locale = snapshot.source_locale;
+        self.definitions_extensions = snapshot.definitions_extensions;
+        self.projects = snapshot.projects;
+        // Values that weren't part of a file snapshot may have been given different options since.
+        self.apply_parser_options(snapshot.parser_options);
+        Ok(())
+    }
+
+    /// How many transactions are in progress, counting each nested one.
+    pub fn transaction_depth(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Run `operation` inside of a transaction that is committed if it succeeds and rolled back
+    /// if it fails.
+    pub fn in_transaction<T, E>(
+        &mut self,
+        operation: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.begin_transaction();
+        self.end_transaction_after(operation)
+    }
+
+    /// Run `operation` inside of a transaction covering `files`, like
+    /// [MessagesDatabase::in_transaction]. See [MessagesDatabase::begin_file_transaction] for what
+    /// can be rolled back.
+    pub fn in_file_transaction<T, E>(
+        &mut self,
+        files: &[KeySymbol],
+        operation: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.begin_file_transaction(files);
+        self.end_transaction_after(operation)
+    }
+
+    /// Run `operation`, then commit the transaction that was just begun if it succeeds or roll it
+    /// back if it fails. Transactions that the operation leaves open are ended the same way first,
+    /// and if the operation already ended this transaction itself, it isn't ended again so that
+    /// the one it's nested in stays open.
+    fn end_transaction_after<T, E>(
+        &mut self,
+        operation: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let depth = self.transaction_depth();
+        let result = operation(self);
+        let end = match result {
+            Ok(_) => Self::commit_transaction,
+            Err(_) => Self::rollback_transaction,
+        };
+        while self.transaction_depth() >= depth {
+            // The depth was just checked, so there is always a transaction to end.
+            let _ = end(self);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{key_symbol, MessageMeta, MessageValue};
+
+    fn database() -> MessagesDatabase {
+        let mut database = MessagesDatabase::new();
+        define(&mut database, "GREETING", "Hello");
+        database
+    }
+
+    fn define(database: &mut MessagesDatabase, key: &str, value: &str) {
+        let locale = database.source_locale;
+        database
+            .insert_definition(
+                key,
+                MessageValue::from_raw(value),
+                locale,
+                MessageMeta::default(),
+                true,
+            )
+            .unwrap();
+    }
+
+    fn value(database: &MessagesDatabase, key: &str) -> Option<String> {
+        database
+            .messages
+            .get(&key_symbol(key))
+            .and_then(|message| message.get_source_translation())
+            .map(|value| value.raw.to_string())
+    }
+
+    #[test]
+    fn test_nested_rollback_keeps_outer_changes() {
+        let mut database = database();
+        database.begin_transaction();
+        define(&mut database, "GREETING", "Hi");
+        database.begin_transaction();
+        define(&mut database, "GREETING", "Hey");
+        define(&mut database, "FAREWELL", "Bye");
+        assert_eq!(database.transaction_depth(), 2);
+
+        database.rollback_transaction().unwrap();
+        assert_eq!(value(&database, "GREETING").as_deref(), Some("Hi"));
+        assert_eq!(value(&database, "FAREWELL"), None);
+
+        database.rollback_transaction().unwrap();
+        assert_eq!(value(&database, "GREETING").as_deref(), Some("Hello"));
+        assert_eq!(database.transaction_depth(), 0);
+        assert!(matches!(
+            database.rollback_transaction(),
+            Err(DatabaseError::NoTransaction)
+        ));
+    }
+
+    #[test]
+    fn test_outer_rollback_undoes_nested_commits() {
+        let mut database = database();
+        database.begin_transaction();
+        database.begin_transaction();
+        define(&mut database, "FAREWELL", "Bye");
+        database.commit_transaction().unwrap();
+        assert_eq!(value(&database, "FAREWELL").as_deref(), Some("Bye"));
+
+        database.rollback_transaction().unwrap();
+        assert_eq!(value(&database, "FAREWELL"), None);
+        assert!(database
+            .hash_lookup
+            .values()
+            .all(|key| key.as_str() != "FAREWELL"));
+        assert!(matches!(
+            database.commit_transaction(),
+            Err(DatabaseError::NoTransaction)
+        ));
+    }
+
+    #[test]
+    fn test_file_transactions() {
+        let mut database = database();
+        let file = key_symbol("greeting.messages.js");
+        database.create_source_file(
+            file,
+            SourceFile::Definition(crate::DefinitionFile::new(
+                file.to_string(),
+                crate::SourceFileMeta::new(file.as_str()),
+                KeySymbolSet::from_iter([key_symbol("GREETING")]),
+            )),
+        );
+
+        database.begin_file_transaction(&[file, key_symbol("new.messages.js")]);
+        define(&mut database, "GREETING", "Hi");
+        define(&mut database, "FAREWELL", "Bye");
+        database
+            .set_source_file_keys(file, KeySymbolSet::default())
+            .unwrap();
+        database.rollback_transaction().unwrap();
+
+        assert_eq!(value(&database, "GREETING").as_deref(), Some("Hello"));
+        assert_eq!(value(&database, "FAREWELL"), None);
+        assert_eq!(
+            database.sources[&file].message_keys(),
+            &KeySymbolSet::from_iter([key_symbol("GREETING")])
+        );
+        assert_eq!(
+            database.hash_lookup.values().collect::<Vec<_>>(),
+            vec![&key_symbol("GREETING")]
+        );
+    }
+
+    #[test]
+    fn test_in_transaction_ends_only_its_own_transactions() {
+        let mut database = database();
+        database.begin_transaction();
+
+        // Transactions left open by a failed operation are rolled back with it.
+        let result: Result<(), ()> = database.in_transaction(|database| {
+            define(database, "GREETING", "Hi");
+            database.begin_transaction();
+            define(database, "FAREWELL", "Bye");
+            Err(())
+        });
+        assert!(result.is_err());
+        assert_eq!(database.transaction_depth(), 1);
+        assert_eq!(value(&database, "GREETING").as_deref(), Some("Hello"));
+        assert_eq!(value(&database, "FAREWELL"), None);
+
+        // An operation that ends its own transaction doesn't end the one it's nested in.
+        let result: Result<(), DatabaseError> = database.in_transaction(|database| {
+            define(database, "FAREWELL", "Bye");
+            database.commit_transaction()
+        });
+        assert!(result.is_ok());
+        assert_eq!(database.transaction_depth(), 1);
+
+        database.rollback_transaction().unwrap();
+        assert_eq!(value(&database, "FAREWELL"), None);
+    }
+}
@@ -8,6 +8,8 @@ use crate::MessageSourceError;
 pub enum DatabaseError {
     #[error(transparent)]
     SourceError(MessageSourceError),
+    #[error("Failed to read {0}: {1}")]
+    ReadError(String, std::io::Error),
     #[error("Processing {0} yielded neither message definitions nor translations")]
     NoExtractableValues(String),
     #[error("{0} has no matching source implementation")]
@@ -16,6 +18,8 @@ pub enum DatabaseError {
     AlreadyDefined(KeySymbol),
     #[error("{0} already has a translation in the locale {1} and cannot be set again")]
     TranslationAlreadySet(KeySymbol, KeySymbol),
+    #[error("{0} has no translation in the locale {1}")]
+    MissingTranslation(KeySymbol, KeySymbol),
 
     // Database errors
     #[error("Expected source file {file_name} to be a {expected} but found {found}")]
@@ -32,6 +36,24 @@ pub enum DatabaseError {
     ValueNotInterned(String),
     #[error("Source file {0} is not a known source file in the database")]
     UnknownSourceFile(KeySymbol),
+    #[error("Locale {0} has no values in the database")]
+    UnknownLocale(String),
+    #[error("Message {0} is not defined in the database")]
+    UnknownMessage(String),
+    #[error("Project {0} has not been registered in the database")]
+    UnknownProject(String),
+    #[error("Query pattern is not a valid regular expression: {0}")]
+    InvalidQueryPattern(String),
+    #[error("{0} references {1}, which is not defined in the database")]
+    UnknownMessageReference(KeySymbol, KeySymbol),
+    #[error(
+        "{0} references {1}, which has block content that can't be used inside of another message"
+    )]
+    BlockMessageReference(KeySymbol, KeySymbol),
+    #[error("Message references form a cycle: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(" -> "))]
+    MessageReferenceCycle(Vec<KeySymbol>),
+    #[error("There is no transaction in progress to commit or roll back")]
+    NoTransaction,
 }
 
 pub type DatabaseResult<T> = Result<T, DatabaseError>;
@@ -1,23 +1,40 @@
 pub use database::message::Message;
+pub use database::performance::{
+    FileTiming, MessageTiming, PerformancePhase, PerformanceRecorder, PerformanceReport, RuleTiming,
+};
+pub use database::project::Project;
+pub use database::query::{MessageQuery, MessageQueryPattern, MessageQueryResult};
 pub use database::source::{
     MessageDefinitionSource, MessageSourceError, MessageSourceResult, MessageTranslationSource,
-    RawMessage, RawMessageDefinition, RawMessageTranslation, RawPosition,
+    RawMessage, RawMessageDefinition, RawMessageError, RawMessageTranslation, RawPosition,
+    SourceFileInsertionData,
 };
 pub use database::symbol::{get_key_symbol, key_symbol, KeySymbol, KeySymbolMap, KeySymbolSet};
 pub use database::MessagesDatabase;
 pub use error::{DatabaseError, DatabaseResult};
+pub use message::fragments::{FragmentExpansion, FragmentExpansionList};
+pub use message::history::{MessageRevision, MESSAGE_HISTORY_LIMIT};
 pub use message::meta::{MessageMeta, SourceFileMeta};
+pub use message::review_state::ReviewState;
 pub use message::source_file::{
     DefinitionFile, FilePosition, SourceFile, SourceFileKind, TranslationFile,
 };
-pub use message::value::MessageValue;
+pub use message::source_offsets::{SourceOffset, SourceOffsetList};
+pub use message::strictness::MarkdownStrictness;
+pub use message::string_parts::{StringPart, StringPartList};
+pub use message::text_pointer::TextPointer;
+pub use message::value::{MessageValue, ValueComments};
 pub use message::variables::{
     collect_message_variables, MessageVariableInstance, MessageVariableType, MessageVariables,
+    NumberStyle,
 };
 
 mod database;
 mod error;
 mod message;
 
-// TODO: Allow this to be configurable, or determined by source files themselves through `meta`.
+/// The source locale of a database when none is configured.
+///
+/// Databases can set their own with [MessagesDatabase::with_source_locale], and definitions files
+/// can override it with `sourceLocale` in their meta.
 pub static DEFAULT_LOCALE: &str = "en-US";
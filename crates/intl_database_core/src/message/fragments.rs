@@ -0,0 +1,46 @@
+use crate::RawPosition;
+
+/// A fragment from the meta of a definitions file that was expanded into a message value, in
+/// place of a reference like `{>PRODUCT_NAME}`.
+#[derive(Clone, Debug)]
+pub struct FragmentExpansion {
+    /// Name of the fragment that was expanded.
+    pub name: String,
+    /// Byte offset in the expanded value where the content of the fragment starts.
+    pub expanded_offset: usize,
+    /// Length in bytes of the content of the fragment in the expanded value.
+    pub expanded_len: usize,
+    /// Position of the value of the fragment in the definitions file.
+    pub position: RawPosition,
+}
+
+/// The fragments that were expanded into a message value while it was extracted, in order.
+///
+/// Positions reported against the expanded value, like diagnostic spans, can be looked up in this
+/// list to find the fragment they came from.
+#[derive(Clone, Debug, Default)]
+pub struct FragmentExpansionList {
+    expansions: Vec<FragmentExpansion>,
+}
+
+impl FragmentExpansionList {
+    pub fn push(&mut self, expansion: FragmentExpansion) {
+        self.expansions.push(expansion);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expansions.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FragmentExpansion> {
+        self.expansions.iter()
+    }
+
+    /// Returns the expansion containing the given byte offset in the expanded value, if any.
+    pub fn expansion_at(&self, expanded_offset: usize) -> Option<&FragmentExpansion> {
+        self.expansions.iter().find(|expansion| {
+            (expansion.expanded_offset..expansion.expanded_offset + expansion.expanded_len)
+                .contains(&expanded_offset)
+        })
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// How many revisions of its definition each message keeps. Once there are more, the oldest ones
+/// are dropped.
+pub const MESSAGE_HISTORY_LIMIT: usize = 20;
+
+/// A version of the definition of a message, recorded whenever its content hash changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MessageRevision {
+    /// The source value of the message in this version.
+    pub raw: String,
+    /// The content hash of the message in this version, see [crate::Message::content_hash].
+    #[serde(rename = "contentHash")]
+    pub content_hash: String,
+    /// When this version was first processed into the database, in milliseconds since the Unix
+    /// epoch.
+    #[serde(rename = "recordedAt")]
+    pub recorded_at: u64,
+}
+
+impl MessageRevision {
+    pub fn new(raw: String, content_hash: String) -> Self {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        Self {
+            raw,
+            content_hash,
+            recorded_at,
+        }
+    }
+}
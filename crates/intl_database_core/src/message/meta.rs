@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
 use std::path;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use super::strictness::MarkdownStrictness;
+use super::variables::MessageVariableType;
+
 /// Meta information about how a _set_ of messages should be handled and processed. SourceFileMeta
 /// has the same attributes as [MessageMeta], and acts as the source of default values for it, but
 /// also provides additional higher-level information like the name of the source file and the path
@@ -32,16 +36,34 @@ pub struct SourceFileMeta {
     /// Optional additional context for the source file, giving more information  about where its
     /// messages may be used or how the messages are intended to be grouped.
     pub description: Option<String>,
+    /// The locale that the messages in this source file are written in, overriding the source
+    /// locale of the database.
+    #[serde(rename = "sourceLocale")]
+    pub source_locale: Option<String>,
+    /// Named snippets of message content that messages in this source file can include with
+    /// `{>NAME}`, like a product name or a legal phrase. They are expanded into the values of the
+    /// messages when the file is extracted.
+    #[serde(default)]
+    pub fragments: BTreeMap<String, String>,
+    /// How much markdown syntax messages in this source file are allowed to use.
+    #[serde(default)]
+    pub strictness: MarkdownStrictness,
 }
 
 impl SourceFileMeta {
+    /// Where translations are kept when a source file doesn't say otherwise, relative to the file.
+    pub const DEFAULT_TRANSLATIONS_PATH: &'static str = "./messages";
+
     pub fn new(source_file_path: &str) -> Self {
         Self {
             secret: false,
             translate: true,
-            translations_path: "./messages".into(),
+            translations_path: Self::DEFAULT_TRANSLATIONS_PATH.into(),
             source_file_path: source_file_path.into(),
             description: None,
+            source_locale: None,
+            fragments: BTreeMap::new(),
+            strictness: MarkdownStrictness::default(),
         }
     }
 
@@ -65,6 +87,14 @@ impl SourceFileMeta {
         self.description = Some(String::from(description));
         self
     }
+    pub fn with_source_locale(mut self, source_locale: &str) -> Self {
+        self.source_locale = Some(String::from(source_locale));
+        self
+    }
+    pub fn with_strictness(mut self, strictness: MarkdownStrictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
 
     /// Return an absolute, canonical path where translations for messages in this source file in
     /// the given `locale` should reside. If `extension` is given, it will be applied to the
@@ -86,7 +116,7 @@ impl SourceFileMeta {
 }
 
 /// Meta information about how a message should be handled and processed. MessageMeta
-#[derive(Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MessageMeta {
     /// Whether the message should be considered private and not suitable for  inclusion in
     /// production builds. Message consumers can use this  information to control how messages are
@@ -102,6 +132,15 @@ pub struct MessageMeta {
     /// Optional additional context for the source file, giving more information about where its
     /// messages may be used or how the messages are intended to be grouped.
     pub description: Option<String>,
+    /// How much markdown syntax the message is allowed to use, matching what the surfaces that
+    /// render it can display.
+    #[serde(default)]
+    pub strictness: MarkdownStrictness,
+    /// Types declared for variables of the message, like `variables: {count: 'number'}`. These
+    /// take precedence over the types inferred from how each variable is used in the message, for
+    /// when inference can't tell what kind of value is expected.
+    #[serde(default)]
+    pub variables: BTreeMap<String, MessageVariableType>,
 }
 
 impl Default for MessageMeta {
@@ -110,6 +149,8 @@ impl Default for MessageMeta {
             secret: false,
             translate: true,
             description: None,
+            strictness: MarkdownStrictness::default(),
+            variables: BTreeMap::new(),
         }
     }
 }
@@ -127,6 +168,14 @@ impl MessageMeta {
         self.description = Some(String::from(description));
         self
     }
+    pub fn with_strictness(mut self, strictness: MarkdownStrictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+    pub fn with_variable_type(mut self, name: &str, kind: MessageVariableType) -> Self {
+        self.variables.insert(String::from(name), kind);
+        self
+    }
 }
 
 impl From<&SourceFileMeta> for MessageMeta {
@@ -135,6 +184,8 @@ impl From<&SourceFileMeta> for MessageMeta {
             secret: value.secret,
             translate: value.translate,
             description: None,
+            strictness: value.strictness,
+            variables: BTreeMap::new(),
         }
     }
 }
@@ -1,4 +1,11 @@
+pub mod fragments;
+pub mod history;
 pub mod meta;
+pub mod review_state;
 pub mod source_file;
+pub mod source_offsets;
+pub mod strictness;
+pub mod string_parts;
+pub mod text_pointer;
 pub mod value;
 pub mod variables;
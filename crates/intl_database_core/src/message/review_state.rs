@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+/// Where a translation is in the review workflow. Translation files only record the state for
+/// values that aren't `New`, so every plain translation starts out as `New`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewState {
+    /// Written by a person, but not reviewed yet.
+    #[default]
+    New,
+    /// Filled in by a machine translation provider, and not reviewed by a person yet.
+    Machine,
+    /// Reviewed by a person, but still open to changes.
+    Reviewed,
+    /// Reviewed and approved to ship as-is.
+    Final,
+}
+
+impl ReviewState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewState::New => "new",
+            ReviewState::Machine => "machine",
+            ReviewState::Reviewed => "reviewed",
+            ReviewState::Final => "final",
+        }
+    }
+
+    /// Return the state with the given name, like `reviewed`, or None if there is no such state.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "new" => Some(ReviewState::New),
+            "machine" => Some(ReviewState::Machine),
+            "reviewed" => Some(ReviewState::Reviewed),
+            "final" => Some(ReviewState::Final),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ReviewState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt::Formatter;
 
 use serde::Serialize;
 
+use crate::database::source::RawMessageError;
 use crate::database::symbol::{KeySymbol, KeySymbolSet};
 
 use super::meta::SourceFileMeta;
@@ -34,13 +35,16 @@ impl std::fmt::Display for SourceFileKind {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(tag = "type", rename = "definition")]
 pub struct DefinitionFile {
     file: String,
     meta: SourceFileMeta,
     #[serde(rename = "messageKeys")]
     message_keys: KeySymbolSet,
+    /// Entries in the file that could not be extracted as definitions the last time it was
+    /// processed.
+    errors: Vec<RawMessageError>,
 }
 
 impl DefinitionFile {
@@ -49,6 +53,7 @@ impl DefinitionFile {
             file,
             meta,
             message_keys,
+            errors: vec![],
         }
     }
     pub fn file(&self) -> &String {
@@ -60,13 +65,22 @@ impl DefinitionFile {
     pub fn meta(&self) -> &SourceFileMeta {
         &self.meta
     }
+    pub fn errors(&self) -> &Vec<RawMessageError> {
+        &self.errors
+    }
+    pub fn set_errors(&mut self, errors: Vec<RawMessageError>) {
+        self.errors = errors;
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(tag = "type", rename = "translation")]
 pub struct TranslationFile {
     file: String,
     locale: KeySymbol,
+    /// The locale tag exactly as it was given for the file, before it was normalized to `locale`.
+    #[serde(rename = "localeTag")]
+    locale_tag: String,
     #[serde(rename = "messageKeys")]
     message_keys: KeySymbolSet,
 }
@@ -76,15 +90,23 @@ impl TranslationFile {
         Self {
             file,
             locale,
+            locale_tag: locale.to_string(),
             message_keys,
         }
     }
+    pub fn with_locale_tag(mut self, locale_tag: String) -> Self {
+        self.locale_tag = locale_tag;
+        self
+    }
     pub fn file(&self) -> &String {
         &self.file
     }
     pub fn locale(&self) -> &KeySymbol {
         &self.locale
     }
+    pub fn locale_tag(&self) -> &String {
+        &self.locale_tag
+    }
     pub fn message_keys(&self) -> &KeySymbolSet {
         &self.message_keys
     }
@@ -96,7 +118,7 @@ impl TranslationFile {
 ///
 /// SourceFiles allow interactive editing of files to automatically update all
 /// of the affected messages safely and efficiently.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(untagged)]
 pub enum SourceFile {
     Definition(DefinitionFile),
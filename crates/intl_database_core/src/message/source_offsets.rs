@@ -0,0 +1,59 @@
+/// A single escape sequence in the source text of a message value, like `\n` or `\u00e9`. Every
+/// escape decodes to exactly one character in the value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceOffset {
+    /// Byte offset in the decoded value where the escaped character starts.
+    pub decoded_offset: usize,
+    /// Length in bytes of the escaped character in the decoded value.
+    pub decoded_len: usize,
+    /// Length in bytes of the escape sequence in the source text.
+    pub source_len: usize,
+}
+
+/// The escape sequences that were decoded while reading a message value from its source file.
+///
+/// They are kept in order. Positions reported against the decoded value, like diagnostic spans, can
+/// be mapped back through this list to land on the exact characters in the source file.
+///
+/// Offsets are relative to the start of the string _content_, not including any opening quote.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceOffsetList {
+    offsets: Vec<SourceOffset>,
+}
+
+impl SourceOffsetList {
+    pub fn push(&mut self, offset: SourceOffset) {
+        self.offsets.push(offset);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns every escape that starts before the given decoded byte offset.
+    fn escapes_before(&self, decoded_offset: usize) -> impl Iterator<Item = &SourceOffset> {
+        self.offsets
+            .iter()
+            .take_while(move |offset| offset.decoded_offset < decoded_offset)
+    }
+
+    /// Convert a byte offset in the decoded value into the byte offset of the same character in
+    /// the source text.
+    pub fn to_source_offset(&self, decoded_offset: usize) -> usize {
+        self.escapes_before(decoded_offset)
+            .fold(decoded_offset, |offset, escape| {
+                offset + escape.source_len - escape.decoded_len
+            })
+    }
+
+    /// Convert a byte offset in the decoded `value` into the number of characters that precede
+    /// it in the source text. Escape sequences are always ASCII, so each byte of an escape
+    /// occupies one column in the source.
+    pub fn to_source_column(&self, value: &str, decoded_offset: usize) -> usize {
+        let decoded_chars = value[..decoded_offset].chars().count();
+        self.escapes_before(decoded_offset)
+            .fold(decoded_chars, |column, escape| {
+                column + escape.source_len - 1
+            })
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// How much markdown syntax a message is allowed to use, matching what the surfaces that render
+/// it can display. Set with `strictness` in the meta of a definitions file or of a single message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkdownStrictness {
+    /// Only text and ICU syntax, for surfaces that render plain text, like push notifications.
+    Plain,
+    /// Inline formatting like emphasis, links and hooks, but no block content like headings or
+    /// multiple paragraphs.
+    InlineOnly,
+    /// Any markdown syntax, including block content.
+    #[default]
+    FullBlocks,
+}
+
+impl MarkdownStrictness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarkdownStrictness::Plain => "plain",
+            MarkdownStrictness::InlineOnly => "inline-only",
+            MarkdownStrictness::FullBlocks => "full-blocks",
+        }
+    }
+
+    /// Return the strictness with the given name, like `inline-only`, or None if there is no such
+    /// strictness.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "plain" => Some(MarkdownStrictness::Plain),
+            "inline-only" => Some(MarkdownStrictness::InlineOnly),
+            "full-blocks" => Some(MarkdownStrictness::FullBlocks),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MarkdownStrictness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
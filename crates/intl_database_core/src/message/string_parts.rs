@@ -0,0 +1,50 @@
+use crate::RawPosition;
+
+/// One of the string literals that a message value written as several strings joined with `+` was
+/// made from, like `'second'` in `'first ' + 'second'`.
+#[derive(Clone, Debug)]
+pub struct StringPart {
+    /// Byte offset in the value where the content of the literal starts.
+    pub value_offset: usize,
+    /// Position of the start of the literal in the definitions file.
+    pub position: RawPosition,
+}
+
+/// The string literals that a message value was joined from while it was extracted, in order.
+///
+/// Positions reported against the value, like diagnostic spans, can be looked up in this list to
+/// find the literal they came from.
+#[derive(Clone, Debug, Default)]
+pub struct StringPartList {
+    parts: Vec<StringPart>,
+}
+
+impl StringPartList {
+    pub fn push(&mut self, part: StringPart) {
+        self.parts.push(part);
+    }
+
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    pub fn first(&self) -> Option<&StringPart> {
+        self.parts.first()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StringPart> {
+        self.parts.iter()
+    }
+
+    /// Returns the literal containing the given byte offset in the value, if any.
+    pub fn part_at(&self, value_offset: usize) -> Option<&StringPart> {
+        self.parts
+            .iter()
+            .take_while(|part| part.value_offset <= value_offset)
+            .last()
+    }
+}
@@ -0,0 +1,118 @@
+use std::borrow::Cow;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
+use serde::{Serialize, Serializer};
+
+/// A cheaply-cloneable slice of shared text content, usually the content of the source file that
+/// a message value was read from.
+///
+/// Translation files contain tens of thousands of values, and most of them can be used exactly as
+/// written in the file. Pointing into the shared file content lets every value in the file use a
+/// single allocation, rather than each one copying its own String out of the content. Values that
+/// need to be changed when they're read, like strings containing escape sequences, can still own
+/// their text by pointing at the entirety of their own allocation.
+#[derive(Clone, Default)]
+pub struct TextPointer {
+    source: Arc<str>,
+    range: Range<usize>,
+}
+
+impl TextPointer {
+    /// Create a pointer to the given `range` of `source`. The range must lie on character
+    /// boundaries within the source.
+    pub fn new(source: Arc<str>, range: Range<usize>) -> Self {
+        assert!(
+            source.get(range.clone()).is_some(),
+            "TextPointer range {range:?} is not a valid slice of its source"
+        );
+        Self { source, range }
+    }
+
+    /// Create a pointer to the entirety of `source`.
+    pub fn full(source: Arc<str>) -> Self {
+        let range = 0..source.len();
+        Self { source, range }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source[self.range.clone()]
+    }
+
+    /// Returns true if this pointer shares its allocation with `other`, rather than owning its own
+    /// copy of the text.
+    pub fn shares_source_with(&self, other: &Arc<str>) -> bool {
+        Arc::ptr_eq(&self.source, other)
+    }
+}
+
+impl Deref for TextPointer {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for TextPointer {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for TextPointer {
+    fn from(value: &str) -> Self {
+        Self::full(value.into())
+    }
+}
+
+impl From<String> for TextPointer {
+    fn from(value: String) -> Self {
+        Self::full(value.into())
+    }
+}
+
+impl From<Cow<'_, str>> for TextPointer {
+    fn from(value: Cow<'_, str>) -> Self {
+        Self::full(value.into())
+    }
+}
+
+impl PartialEq for TextPointer {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for TextPointer {}
+
+impl PartialEq<str> for TextPointer {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for TextPointer {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Debug for TextPointer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for TextPointer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for TextPointer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
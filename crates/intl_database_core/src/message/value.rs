@@ -1,42 +1,229 @@
-use serde::Serialize;
+use std::sync::{Arc, OnceLock};
 
-use intl_markdown::{parse_intl_message, Document};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use intl_markdown::{
+    documents_equivalent, parse_intl_message_with_options, Document, ParserOptions,
+};
 use intl_message_utils::message_may_have_blocks;
 
+use super::fragments::FragmentExpansionList;
+use super::review_state::ReviewState;
 use super::source_file::FilePosition;
+use super::source_offsets::SourceOffsetList;
+use super::string_parts::StringPartList;
+use super::text_pointer::TextPointer;
 use super::variables::{collect_message_variables, MessageVariables};
 
-#[derive(Debug, Serialize)]
+/// Comments written around a value in a translation file, each exactly as written including the
+/// comment markers, so that they can be kept when the file is exported again.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ValueComments {
+    /// Comments on the lines above the entry.
+    pub leading: Option<String>,
+    /// Comments between the key and the value, like `"key": /* note */ "value"`.
+    pub inline: Option<String>,
+    /// Comments after the value on the same line, like `"key": "value", // note`. For the last
+    /// entry in a file, this also includes any comments on the lines after it. Comments that
+    /// start on a new line are preceded by the line break and indentation before them.
+    pub trailing: Option<String>,
+}
+
+/// The options that values are parsed with when they aren't given any.
+pub(crate) fn default_parser_options() -> &'static ParserOptions {
+    static DEFAULT: OnceLock<ParserOptions> = OnceLock::new();
+    DEFAULT.get_or_init(ParserOptions::default)
+}
+
+#[derive(Debug)]
 pub struct MessageValue {
-    pub raw: String,
-    pub parsed: Document,
-    pub variables: Option<MessageVariables>,
+    /// The original content of the message. This usually points into the content of the file
+    /// that the message was read from, rather than owning a copy of it.
+    pub raw: TextPointer,
+    /// The parsed content of the message and the variables it uses are only computed the first
+    /// time they're needed, since many operations only ever look at the raw content, like
+    /// exporting translations. See [`MessageValue::parsed`] and [`MessageValue::variables`].
+    parsed: OnceLock<Document>,
+    variables: OnceLock<Option<MessageVariables>>,
+    /// The options that the content is parsed with, shared with the database that the value
+    /// belongs to. `None` parses with the default options. See [`MessageValue::set_parser_options`].
+    parser_options: Option<Arc<ParserOptions>>,
     pub file_position: Option<FilePosition>,
+    /// Escape sequences that were decoded from the source text of this value, used to map
+    /// positions in `raw` back to the original file. Only present for sources that track them.
+    pub source_offsets: Option<SourceOffsetList>,
+    /// Fragments from the meta of the definitions file that were expanded into `raw`, used to map
+    /// positions in `raw` back to the fragment or to the value as it was written.
+    pub fragment_expansions: Option<FragmentExpansionList>,
+    /// The string literals that this value was joined from when it was written as several strings
+    /// joined with `+`, used to map positions in `raw` back to the literal they came from.
+    pub string_parts: Option<StringPartList>,
+    /// True when this value was recovered from a compiled artifact rather than written by a
+    /// person. The raw content is generated from the compiled form, so it may not match the
+    /// original source text exactly and should be reviewed before being treated as authoritative.
+    pub machine_recovered: bool,
+    /// Where this value is in the review workflow. Values filled in by a machine translation
+    /// provider are in the `Machine` state until a person reviews them. Unlike
+    /// `machine_recovered`, those are real translations, but still need a human review before they
+    /// should be considered final.
+    pub review_state: ReviewState,
+    /// Comments written around this value in its translation file.
+    pub comments: Option<Box<ValueComments>>,
+    /// The content hash of the definition of the message when this translation was set, used to
+    /// tell when the source has changed since it was translated. `None` for source values and for
+    /// translations that were set before the message had a definition.
+    pub source_hash: Option<String>,
 }
 
 impl MessageValue {
-    /// Creates a new value including the original raw content as given and
-    /// parsing the content to a compiled AST.
+    /// Creates a new value including the original raw content as given. The content is parsed
+    /// lazily, the first time the parsed AST is requested.
     pub fn from_raw(content: &str) -> Self {
-        let document = parse_intl_message(&content, message_may_have_blocks(content));
-
-        let variables = match collect_message_variables(&document) {
-            Ok(variables) => Some(variables),
-            _ => None,
-        };
+        Self::from_text(content.into())
+    }
 
+    /// Same as [`MessageValue::from_raw`], but keeping the given pointer to the raw content rather
+    /// than copying it.
+    pub fn from_text(raw: TextPointer) -> Self {
         Self {
-            raw: content.into(),
-            parsed: document,
-            variables,
+            raw,
+            parsed: OnceLock::new(),
+            variables: OnceLock::new(),
+            parser_options: None,
             file_position: None,
+            source_offsets: None,
+            fragment_expansions: None,
+            string_parts: None,
+            machine_recovered: false,
+            review_state: ReviewState::default(),
+            comments: None,
+            source_hash: None,
+        }
+    }
+
+    /// Returns the parsed AST of the raw content, parsing it first if that hasn't happened yet.
+    pub fn parsed(&self) -> &Document {
+        self.parsed.get_or_init(|| {
+            parse_intl_message_with_options(
+                &self.raw,
+                message_may_have_blocks(&self.raw),
+                self.parser_options().clone(),
+            )
+        })
+    }
+
+    /// Returns the options that the content is parsed with.
+    pub fn parser_options(&self) -> &ParserOptions {
+        self.parser_options
+            .as_deref()
+            .unwrap_or_else(|| default_parser_options())
+    }
+
+    /// Set the options that the content is parsed with. The database sets these on every value
+    /// inserted into it, so that every value is parsed the same way. If the options change, the
+    /// parsed content and variables are computed again the next time they're needed.
+    pub fn set_parser_options(&mut self, parser_options: Option<Arc<ParserOptions>>) {
+        let changed = self.parser_options()
+            != parser_options
+                .as_deref()
+                .unwrap_or_else(|| default_parser_options());
+        self.parser_options = parser_options;
+        if changed {
+            self.parsed = OnceLock::new();
+            self.variables = OnceLock::new();
         }
     }
 
+    /// Set the options that the content is parsed with, then parse it and collect its variables
+    /// now rather than the first time they're needed. Values that are given the same options the
+    /// database has keep their parsed content when they are inserted, so this lets many values be
+    /// parsed in parallel before being inserted one at a time.
+    pub fn parse_with_options(&mut self, parser_options: Option<Arc<ParserOptions>>) {
+        self.set_parser_options(parser_options);
+        self.variables();
+    }
+
+    /// Returns true if the content has already been parsed.
+    pub fn is_parsed(&self) -> bool {
+        self.parsed.get().is_some()
+    }
+
+    /// Returns the variables used in the message, or None if they could not be determined,
+    /// parsing the content first if that hasn't happened yet.
+    pub fn variables(&self) -> Option<&MessageVariables> {
+        self.variables
+            .get_or_init(|| collect_message_variables(self.parsed()).ok())
+            .as_ref()
+    }
+
+    /// Returns true if this value says the same thing as `other`, even if it is written
+    /// differently, like with different escapes or surrounding whitespace. Unlike `==`, which only
+    /// compares the raw content, this compares the parsed content of both values.
+    pub fn is_equivalent(&self, other: &MessageValue) -> bool {
+        self.raw == other.raw || documents_equivalent(self.parsed(), other.parsed())
+    }
+
     pub fn with_file_position(mut self, position: FilePosition) -> Self {
         self.file_position = Some(position);
         self
     }
+
+    pub fn with_source_offsets(mut self, source_offsets: SourceOffsetList) -> Self {
+        self.source_offsets = Some(source_offsets);
+        self
+    }
+
+    pub fn with_fragment_expansions(mut self, fragment_expansions: FragmentExpansionList) -> Self {
+        self.fragment_expansions = Some(fragment_expansions);
+        self
+    }
+
+    pub fn with_string_parts(mut self, string_parts: StringPartList) -> Self {
+        self.string_parts = Some(string_parts);
+        self
+    }
+
+    pub fn with_machine_recovered(mut self, machine_recovered: bool) -> Self {
+        self.machine_recovered = machine_recovered;
+        self
+    }
+
+    pub fn with_review_state(mut self, review_state: ReviewState) -> Self {
+        self.review_state = review_state;
+        self
+    }
+
+    pub fn with_comments(mut self, comments: Option<Box<ValueComments>>) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    pub fn with_source_hash(mut self, source_hash: Option<String>) -> Self {
+        self.source_hash = source_hash;
+        self
+    }
+}
+
+// The parsed content isn't copied, since it's only a cache that is computed again from the raw
+// content the first time it's needed.
+impl Clone for MessageValue {
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+            parsed: OnceLock::new(),
+            variables: OnceLock::new(),
+            parser_options: self.parser_options.clone(),
+            file_position: self.file_position,
+            source_offsets: self.source_offsets.clone(),
+            fragment_expansions: self.fragment_expansions.clone(),
+            string_parts: self.string_parts.clone(),
+            machine_recovered: self.machine_recovered,
+            review_state: self.review_state,
+            comments: self.comments.clone(),
+            source_hash: self.source_hash.clone(),
+        }
+    }
 }
 
 // Messages are equal if they have the same starting raw content. Everything
@@ -46,3 +233,18 @@ impl PartialEq for MessageValue {
         self.raw == other.raw
     }
 }
+
+impl Serialize for MessageValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("MessageValue", 8)?;
+        state.serialize_field("raw", &self.raw)?;
+        state.serialize_field("parsed", self.parsed())?;
+        state.serialize_field("variables", &self.variables())?;
+        state.serialize_field("file_position", &self.file_position)?;
+        state.serialize_field("machineRecovered", &self.machine_recovered)?;
+        state.serialize_field("reviewState", &self.review_state)?;
+        state.serialize_field("comments", &self.comments)?;
+        state.serialize_field("sourceHash", &self.source_hash)?;
+        state.end()
+    }
+}
@@ -1,23 +1,28 @@
+use std::collections::BTreeMap;
 use std::ops::Deref;
 
 use rustc_hash::FxHashSet;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use intl_markdown_visitor::visit_with_mut;
 
-use crate::database::symbol::{KeySymbol, KeySymbolMap};
+use crate::database::symbol::{key_symbol, KeySymbol, KeySymbolMap};
 use crate::error::DatabaseResult;
 use crate::message::variables::visitor::MessageVariablesVisitor;
 
 mod visitor;
 
-#[derive(Clone, Debug, Serialize, Hash, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(into = "SerializedVariableType", from = "SerializedVariableType")]
 pub enum MessageVariableType {
     /// Any value is accepted for this variable. Generally used when the
     /// required type of the variable can't be determined.
     Any,
     /// Any type of numeric value is valid. Accepts both integers and floats.
-    Number,
+    /// If the variable is formatted with a style that affects how the value
+    /// is interpreted, like a percentage or a currency, that style is kept
+    /// as well.
+    Number(Option<NumberStyle>),
     /// A value used for a Plural evaluation. Generally a number, or something
     /// that can be directly cast to a number.
     Plural,
@@ -43,6 +48,156 @@ pub enum MessageVariableType {
     HandlerFunction,
 }
 
+impl MessageVariableType {
+    /// A short name for this kind of variable, like `number` or `select`, matching the keyword
+    /// that introduces it in a message where there is one.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            MessageVariableType::Any => "any",
+            MessageVariableType::Number(_) => "number",
+            MessageVariableType::Plural => "plural",
+            MessageVariableType::Enum(_) => "select",
+            MessageVariableType::Date => "date",
+            MessageVariableType::Time => "time",
+            MessageVariableType::HookFunction => "hook",
+            MessageVariableType::LinkFunction => "link",
+            MessageVariableType::HandlerFunction => "handler",
+        }
+    }
+
+    /// The kind of variable named by `name`, as returned by [MessageVariableType::kind_name], for
+    /// declaring the types of variables explicitly. Selects can't be declared this way, since
+    /// their type depends on the options written in the message.
+    pub fn from_kind_name(name: &str) -> Option<Self> {
+        match name {
+            "any" => Some(MessageVariableType::Any),
+            "number" => Some(MessageVariableType::Number(None)),
+            "plural" => Some(MessageVariableType::Plural),
+            "date" => Some(MessageVariableType::Date),
+            "time" => Some(MessageVariableType::Time),
+            "hook" => Some(MessageVariableType::HookFunction),
+            "link" => Some(MessageVariableType::LinkFunction),
+            "handler" => Some(MessageVariableType::HandlerFunction),
+            _ => None,
+        }
+    }
+
+    /// Return an example of how a variable called `name` with this type is written in a message,
+    /// like `{count, plural, one {#} other {#}}`. Select options and number styles are kept, so
+    /// the example matches how the variable is used in the message it came from.
+    pub fn example_usage(&self, name: &str) -> String {
+        match self {
+            MessageVariableType::Any => format!("{{{name}}}"),
+            MessageVariableType::Number(None) => format!("{{{name}, number}}"),
+            MessageVariableType::Number(Some(style)) => {
+                let style = match style {
+                    NumberStyle::Integer => "integer".to_string(),
+                    NumberStyle::Percent => "percent".to_string(),
+                    NumberStyle::Currency(None) => "currency".to_string(),
+                    NumberStyle::Currency(Some(code)) => format!("::currency/{code}"),
+                };
+                format!("{{{name}, number, {style}}}")
+            }
+            MessageVariableType::Plural => format!("{{{name}, plural, one {{#}} other {{#}}}}"),
+            MessageVariableType::Enum(options) => {
+                let arms: Vec<String> = options
+                    .iter()
+                    .map(|option| format!("{option} {{}}"))
+                    .collect();
+                format!("{{{name}, select, {}}}", arms.join(" "))
+            }
+            MessageVariableType::Date => format!("{{{name}, date}}"),
+            MessageVariableType::Time => format!("{{{name}, time}}"),
+            MessageVariableType::HookFunction => format!("$[content]({name})"),
+            MessageVariableType::LinkFunction => "[content](destination)".to_string(),
+            MessageVariableType::HandlerFunction => format!("[content]({name})"),
+        }
+    }
+}
+
+/// How [MessageVariableType] is serialized. Numbers without a style are written as the plain
+/// `"Number"` variant, the same as before number styles were kept, and only styled numbers are
+/// written as `{"Number": style}`.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SerializedVariableType {
+    Plain(PlainVariableType),
+    StyledNumber {
+        #[serde(rename = "Number")]
+        style: NumberStyle,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "MessageVariableType")]
+enum PlainVariableType {
+    Any,
+    Number,
+    Plural,
+    Enum(Vec<String>),
+    Date,
+    Time,
+    HookFunction,
+    LinkFunction,
+    HandlerFunction,
+}
+
+impl From<MessageVariableType> for SerializedVariableType {
+    fn from(value: MessageVariableType) -> Self {
+        let plain = match value {
+            MessageVariableType::Number(Some(style)) => {
+                return SerializedVariableType::StyledNumber { style }
+            }
+            MessageVariableType::Any => PlainVariableType::Any,
+            MessageVariableType::Number(None) => PlainVariableType::Number,
+            MessageVariableType::Plural => PlainVariableType::Plural,
+            MessageVariableType::Enum(options) => PlainVariableType::Enum(options),
+            MessageVariableType::Date => PlainVariableType::Date,
+            MessageVariableType::Time => PlainVariableType::Time,
+            MessageVariableType::HookFunction => PlainVariableType::HookFunction,
+            MessageVariableType::LinkFunction => PlainVariableType::LinkFunction,
+            MessageVariableType::HandlerFunction => PlainVariableType::HandlerFunction,
+        };
+        SerializedVariableType::Plain(plain)
+    }
+}
+
+impl From<SerializedVariableType> for MessageVariableType {
+    fn from(value: SerializedVariableType) -> Self {
+        let plain = match value {
+            SerializedVariableType::StyledNumber { style } => {
+                return MessageVariableType::Number(Some(style))
+            }
+            SerializedVariableType::Plain(plain) => plain,
+        };
+        match plain {
+            PlainVariableType::Any => MessageVariableType::Any,
+            PlainVariableType::Number => MessageVariableType::Number(None),
+            PlainVariableType::Plural => MessageVariableType::Plural,
+            PlainVariableType::Enum(options) => MessageVariableType::Enum(options),
+            PlainVariableType::Date => MessageVariableType::Date,
+            PlainVariableType::Time => MessageVariableType::Time,
+            PlainVariableType::HookFunction => MessageVariableType::HookFunction,
+            PlainVariableType::LinkFunction => MessageVariableType::LinkFunction,
+            PlainVariableType::HandlerFunction => MessageVariableType::HandlerFunction,
+        }
+    }
+}
+
+/// Number formatting styles that change how a numeric value is interpreted
+/// or presented, taken from the style argument of a `{var, number, style}`
+/// placeholder.
+#[derive(Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub enum NumberStyle {
+    /// The value is rounded to an integer.
+    Integer,
+    /// The value is a ratio, scaled by 100 when formatted.
+    Percent,
+    /// The value is an amount of money. The currency code is included when
+    /// the style specifies one explicitly, like `::currency/USD`.
+    Currency(Option<String>),
+}
+
 /// A representation of a single _instance_ of a variable in a message. Each
 /// time a variable appears in a message, even if it is a variable that has
 /// already been seen, a new MessageVariable is created.
@@ -50,14 +205,17 @@ pub enum MessageVariableType {
 pub struct MessageVariableInstance {
     /// The location in the message where this variable is used. Each instance
     /// of a variable in a string has its own struct, so each stores its own
-    /// span as well.
-    /// TODO: Add this back
+    /// span as well. This is the byte offset of the variable's name, and is only known for plain
+    /// placeholders like `{name}` so far.
     pub span: Option<usize>,
     /// `true` if this variable is a system-defined variable, typically for
     /// rich text formatting tags like `$b` and `$link`, which are almost never
     /// intended for a user to provide and/or only represent formatting points,
     /// but can be given as an override regardless.
     pub is_builtin: bool,
+    /// `true` if this instance has a default value, like `{name|Someone}`, which is shown when
+    /// no value is given for the variable.
+    pub has_default: bool,
     /// The specific kind of the variable, used for generating types.
     pub kind: MessageVariableType,
 }
@@ -89,14 +247,43 @@ impl MessageVariables {
         let instance = MessageVariableInstance {
             kind,
             is_builtin,
+            has_default: false,
             span,
         };
+        self.push_instance(name, instance);
+    }
+
+    /// Add an instance of a variable that has a default value, like `{name|Someone}`.
+    pub fn add_instance_with_default(
+        &mut self,
+        name: KeySymbol,
+        kind: MessageVariableType,
+        span: Option<usize>,
+    ) {
+        let instance = MessageVariableInstance {
+            kind,
+            is_builtin: false,
+            has_default: true,
+            span,
+        };
+        self.push_instance(name, instance);
+    }
+
+    fn push_instance(&mut self, name: KeySymbol, instance: MessageVariableInstance) {
         self.variables
             .entry(name)
             .or_insert_with(|| vec![])
             .push(instance);
     }
 
+    /// Returns true if every instance of the variable `name` has a default value, meaning the
+    /// message can be formatted without giving a value for it.
+    pub fn has_default(&self, name: &KeySymbol) -> bool {
+        self.variables
+            .get(name)
+            .is_some_and(|instances| instances.iter().all(|instance| instance.has_default))
+    }
+
     /// Merge the variables from `other` into self by copying them over.
     pub fn merge(&mut self, other: &Self) {
         for (symbol, instances) in other.iter() {
@@ -107,6 +294,20 @@ impl MessageVariables {
         }
     }
 
+    /// Replace the kind of every instance of each variable in `declared` with its declared type,
+    /// since declarations take precedence over the types inferred from the message. Declared
+    /// variables that the message doesn't use are not added.
+    pub fn apply_declared_types(&mut self, declared: &BTreeMap<String, MessageVariableType>) {
+        for (name, kind) in declared {
+            let Some(instances) = self.variables.get_mut(&key_symbol(name)) else {
+                continue;
+            };
+            for instance in instances {
+                instance.kind = kind.clone();
+            }
+        }
+    }
+
     /// Returns a HashSet of the names of all variables in this message.
     pub fn get_keys(&self) -> FxHashSet<&KeySymbol> {
         self.variables.keys().collect::<FxHashSet<&KeySymbol>>()
@@ -137,3 +338,35 @@ pub fn collect_message_variables(
     visit_with_mut(&ast, &mut visitor);
     Ok(visitor.into_variables())
 }
+
+#[cfg(test)]
+mod test {
+    use super::{MessageVariableType, NumberStyle};
+
+    #[test]
+    fn test_serialize_number_types() {
+        let cases = [
+            (MessageVariableType::Number(None), r#""Number""#),
+            (
+                MessageVariableType::Number(Some(NumberStyle::Percent)),
+                r#"{"Number":"Percent"}"#,
+            ),
+            (
+                MessageVariableType::Number(Some(NumberStyle::Currency(Some("USD".into())))),
+                r#"{"Number":{"Currency":"USD"}}"#,
+            ),
+            (
+                MessageVariableType::Enum(vec!["a".into(), "other".into()]),
+                r#"{"Enum":["a","other"]}"#,
+            ),
+            (MessageVariableType::HookFunction, r#""HookFunction""#),
+        ];
+        for (kind, json) in cases {
+            assert_eq!(serde_json::to_string(&kind).unwrap(), json);
+            assert_eq!(
+                serde_json::from_str::<MessageVariableType>(json).unwrap(),
+                kind
+            );
+        }
+    }
+}
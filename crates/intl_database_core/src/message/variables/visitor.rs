@@ -1,14 +1,14 @@
 use intl_markdown::{
-    CodeBlock, CodeSpan, Emphasis, Heading, Hook, IcuDate, IcuNumber, IcuPlural, IcuSelect,
-    IcuTime, IcuVariable, Link, LinkDestination, Paragraph, Strikethrough, Strong,
-    DEFAULT_TAG_NAMES,
+    CodeBlock, CodeSpan, Emoji, Emphasis, Heading, Hook, IcuDate, IcuNumber, IcuNumberStyleKind,
+    IcuPlural, IcuSelect, IcuTime, IcuVariable, KeyboardKey, Link, LinkDestination, Mention,
+    Paragraph, Strikethrough, Strong, DEFAULT_TAG_NAMES,
 };
 use intl_markdown_visitor::{Visit, VisitWith};
 
 use crate::database::symbol::key_symbol;
 use crate::KeySymbol;
 
-use super::{MessageVariableType, MessageVariables};
+use super::{MessageVariableType, MessageVariables, NumberStyle};
 
 pub struct MessageVariablesVisitor {
     variables: MessageVariables,
@@ -50,6 +50,15 @@ impl Visit for MessageVariablesVisitor {
         );
     }
 
+    fn visit_emoji(&mut self, _node: &Emoji) {
+        self.variables.add_instance(
+            key_symbol(DEFAULT_TAG_NAMES.emoji()),
+            MessageVariableType::HookFunction,
+            true,
+            None,
+        );
+    }
+
     fn visit_emphasis(&mut self, node: &Emphasis) {
         self.variables.add_instance(
             key_symbol(DEFAULT_TAG_NAMES.emphasis()),
@@ -88,7 +97,16 @@ impl Visit for MessageVariablesVisitor {
     }
 
     fn visit_icu_number(&mut self, number: &IcuNumber) {
-        self.current_variable_type = Some(MessageVariableType::Number);
+        let style = number
+            .style()
+            .as_ref()
+            .and_then(|style| match style.kind() {
+                IcuNumberStyleKind::Integer => Some(NumberStyle::Integer),
+                IcuNumberStyleKind::Percent => Some(NumberStyle::Percent),
+                IcuNumberStyleKind::Currency(code) => Some(NumberStyle::Currency(code.clone())),
+                IcuNumberStyleKind::Other => None,
+            });
+        self.current_variable_type = Some(MessageVariableType::Number(style));
         number.visit_children_with(self);
     }
 
@@ -103,7 +121,7 @@ impl Visit for MessageVariablesVisitor {
     fn visit_icu_select(&mut self, select: &IcuSelect) {
         let name_symbol = key_symbol(select.name());
         self.current_plural_variable_name = Some(name_symbol);
-        let selectors = select.arms().iter().map(|arm| arm.selector().clone());
+        let selectors = select.arms().iter().map(|arm| arm.selector().to_owned());
         self.current_variable_type = Some(MessageVariableType::Enum(selectors.collect()));
         select.visit_children_with(self);
     }
@@ -114,12 +132,24 @@ impl Visit for MessageVariablesVisitor {
     }
 
     fn visit_icu_variable(&mut self, variable: &IcuVariable) {
+        let kind = self
+            .current_variable_type
+            .take()
+            .unwrap_or(MessageVariableType::Any);
+        let name = key_symbol(variable.name());
+        let span = variable.name_span().map(|span| span.start);
+        if variable.default_value().is_some() {
+            self.variables.add_instance_with_default(name, kind, span);
+        } else {
+            self.variables.add_instance(name, kind, false, span);
+        }
+    }
+
+    fn visit_keyboard_key(&mut self, _node: &KeyboardKey) {
         self.variables.add_instance(
-            key_symbol(variable.name()),
-            self.current_variable_type
-                .take()
-                .unwrap_or(MessageVariableType::Any),
-            false,
+            key_symbol(DEFAULT_TAG_NAMES.keyboard_key()),
+            MessageVariableType::HookFunction,
+            true,
             None,
         );
     }
@@ -152,6 +182,16 @@ impl Visit for MessageVariablesVisitor {
         }
     }
 
+    fn visit_mention(&mut self, mention: &Mention) {
+        self.variables.add_instance(
+            key_symbol(DEFAULT_TAG_NAMES.mention()),
+            MessageVariableType::HookFunction,
+            true,
+            None,
+        );
+        mention.visit_children_with(self);
+    }
+
     fn visit_paragraph(&mut self, node: &Paragraph) {
         self.variables.add_instance(
             key_symbol(DEFAULT_TAG_NAMES.paragraph()),
@@ -207,7 +247,7 @@ impl Visit for MessageVariablesVisitor {
         );
         self.variables.add_instance(
             self.current_plural_variable_name.unwrap(),
-            MessageVariableType::Number,
+            MessageVariableType::Number(None),
             false,
             None,
         );
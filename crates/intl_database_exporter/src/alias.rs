@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use intl_database_core::{KeySymbol, KeySymbolMap, MessagesDatabase};
+
+/// How messages are keyed in compiled bundles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BundleKeyMode {
+    /// Key each message by the hash of its key, which the runtime can compute on its own.
+    #[default]
+    Hashed,
+    /// Key each message by a short, sequential alias like `a`, `b`, or `aB`.
+    ///
+    /// Aliases are smaller than hashes and reveal nothing about the keys. They are assigned in the
+    /// order of the sorted keys of every defined message, so adding or removing a message shifts
+    /// the aliases after it, and every bundle and definitions module has to be built from the same
+    /// database. The runtime needs the manifest from [MessageKeyAliases::manifest] to look messages
+    /// up by their aliases. These bundles are marked with a `$keyMode` entry.
+    Alias,
+    /// Key each message by its original key, for reading bundles while debugging. Only meant for
+    /// development builds, since the runtime has to hash every key itself when the bundle is
+    /// loaded. These bundles are marked with a `$keyMode` entry so the runtime knows to do that.
+    Original,
+    /// Key each message by the hash of its key like [BundleKeyMode::Hashed], and add a `$keys`
+    /// entry mapping each hashed key in the bundle back to its original key, so that development
+    /// builds can show readable keys while still working with the usual runtime.
+    HashedWithKeys,
+}
+
+/// Key of the entry mapping hashed keys to original keys in bundles written with
+/// [BundleKeyMode::HashedWithKeys].
+pub(crate) const KEYS_BUNDLE_KEY: &str = "$keys";
+
+/// Key of the entry marking bundles written with [BundleKeyMode::Original] or
+/// [BundleKeyMode::Alias], whose value is `"original"` or `"alias"` respectively.
+pub(crate) const KEY_MODE_BUNDLE_KEY: &str = "$keyMode";
+
+/// Letters that aliases are built from. Digits and symbols are left out so that every alias is a
+/// valid identifier and can never collide with reserved bundle keys like `$plurals`.
+const ALIAS_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Return the alias at `index` in the sequence `a`, ..., `Z`, `aa`, `ab`, ..., `ZZ`, `aaa`, ...
+fn alias_for_index(mut index: usize) -> String {
+    let base = ALIAS_ALPHABET.len();
+    let mut alias = Vec::new();
+    loop {
+        alias.push(ALIAS_ALPHABET[index % base]);
+        if index < base {
+            break;
+        }
+        index = index / base - 1;
+    }
+    alias.reverse();
+    // The alphabet is entirely ASCII.
+    String::from_utf8(alias).unwrap()
+}
+
+/// A short, sequential alias for every message key, assigned in the order of the sorted keys.
+#[derive(Debug, Default)]
+pub struct MessageKeyAliases {
+    aliases: KeySymbolMap<String>,
+}
+
+impl MessageKeyAliases {
+    pub fn new(keys: impl IntoIterator<Item = KeySymbol>) -> Self {
+        let mut keys: Vec<KeySymbol> = keys.into_iter().collect();
+        // Sorting by the key strings rather than the symbols keeps the aliases independent of the
+        // order that messages were interned in.
+        keys.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
+        keys.dedup();
+
+        let aliases = keys
+            .into_iter()
+            .enumerate()
+            .map(|(index, key)| (key, alias_for_index(index)))
+            .collect();
+        Self { aliases }
+    }
+
+    /// Assign aliases to every defined message in the database, so that bundles of different
+    /// definitions files never give two messages the same alias.
+    pub fn from_database(database: &MessagesDatabase) -> Self {
+        Self::new(
+            database
+                .messages
+                .values()
+                .filter(|message| message.is_defined())
+                .map(|message| message.key()),
+        )
+    }
+
+    pub fn get(&self, key: KeySymbol) -> Option<&str> {
+        self.aliases.get(&key).map(String::as_str)
+    }
+
+    /// Return the map of every alias to the message key it was assigned to.
+    pub fn manifest(&self) -> BTreeMap<&str, KeySymbol> {
+        self.aliases
+            .iter()
+            .map(|(key, alias)| (alias.as_str(), *key))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::key_symbol;
+
+    use super::*;
+
+    fn keys(count: usize) -> Vec<KeySymbol> {
+        (0..count)
+            .map(|index| key_symbol(&format!("MESSAGE_{index}")))
+            .collect()
+    }
+
+    #[test]
+    fn test_alias_for_index() {
+        assert_eq!(alias_for_index(0), "a");
+        assert_eq!(alias_for_index(25), "z");
+        assert_eq!(alias_for_index(26), "A");
+        assert_eq!(alias_for_index(51), "Z");
+        assert_eq!(alias_for_index(52), "aa");
+        assert_eq!(alias_for_index(53), "ab");
+        assert_eq!(alias_for_index(52 + 52 * 52 - 1), "ZZ");
+        assert_eq!(alias_for_index(52 + 52 * 52), "aaa");
+    }
+
+    #[test]
+    fn test_aliases_follow_sorted_keys() {
+        let aliases = MessageKeyAliases::new(["C", "A", "B", "A"].map(key_symbol));
+        assert_eq!(aliases.get(key_symbol("A")), Some("a"));
+        assert_eq!(aliases.get(key_symbol("B")), Some("b"));
+        assert_eq!(aliases.get(key_symbol("C")), Some("c"));
+        assert_eq!(
+            aliases.manifest(),
+            BTreeMap::from([
+                ("a", key_symbol("A")),
+                ("b", key_symbol("B")),
+                ("c", key_symbol("C")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_aliases_are_unique() {
+        let keys = keys(500);
+        let aliases = MessageKeyAliases::new(keys.clone());
+        let manifest = aliases.manifest();
+        assert_eq!(manifest.len(), keys.len());
+        for (alias, key) in manifest {
+            assert_eq!(aliases.get(key), Some(alias));
+        }
+    }
+
+    #[test]
+    fn test_aliases_ignore_key_order() {
+        let keys = keys(100);
+        let aliases = MessageKeyAliases::new(keys.clone());
+        let reversed = MessageKeyAliases::new(keys.iter().rev().copied());
+        assert_eq!(aliases.manifest(), reversed.manifest());
+    }
+}
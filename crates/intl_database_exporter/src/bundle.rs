@@ -1,10 +1,27 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::Instant;
+
+use keyless_json::SerializeOptions;
+use rustc_hash::FxHashMap;
+use serde::Serialize;
 use thiserror::Error;
 
-use intl_database_core::{KeySymbol, Message, MessageValue, MessagesDatabase};
-use intl_database_service::IntlDatabaseService;
+use intl_database_core::{
+    KeySymbol, Message, MessageTiming, MessageValue, MessagesDatabase, PerformancePhase,
+};
+use intl_database_service::{IntlDatabaseService, ServiceContext};
 use intl_markdown::{
-    compile_to_format_js, raw_string_to_document, BlockNode, Document, InlineContent,
+    compile_to_format_js, format_mf2_string, message_references, parse_intl_message_with_options,
+    raw_string_to_document, BlockNode, CompileArena, Document, InlineContent, ParserOptions,
+    ReactIntlAst,
 };
+use intl_message_utils::message_may_have_blocks;
+
+use crate::alias::{BundleKeyMode, MessageKeyAliases, KEYS_BUNDLE_KEY, KEY_MODE_BUNDLE_KEY};
+use crate::plural_rules::{PluralRulesUsage, PLURAL_RULES_BUNDLE_KEY};
+use crate::transform::{MessagePlaceholderRenames, TransformPipeline};
+use crate::SecretRedaction;
 
 #[derive(Debug, Error)]
 pub enum IntlMessageBundlerError {
@@ -14,9 +31,20 @@ pub enum IntlMessageBundlerError {
     MessageNotFound(KeySymbol),
 }
 
+/// The key used to store where each bundled message was defined when the bundler runs with
+/// [IntlMessageBundlerOptions::with_spans].
+pub(crate) const SPANS_BUNDLE_KEY: &str = "$spans";
+
 pub struct IntlMessageBundlerOptions {
     format: CompiledMessageFormat,
-    bundle_secrets: bool,
+    secret_redaction: SecretRedaction,
+    plural_rules: bool,
+    parser_options: Option<ParserOptions>,
+    transforms: TransformPipeline,
+    placeholder_renames: MessagePlaceholderRenames,
+    key_mode: BundleKeyMode,
+    serialize_options: SerializeOptions,
+    spans: bool,
 }
 
 impl IntlMessageBundlerOptions {
@@ -24,8 +52,64 @@ impl IntlMessageBundlerOptions {
         self.format = format;
         self
     }
-    pub fn with_bundle_secrets(mut self, bundle_secrets: bool) -> Self {
-        self.bundle_secrets = bundle_secrets;
+    /// Shorthand for either keeping secret messages as-is or masking them with a placeholder.
+    pub fn with_bundle_secrets(self, bundle_secrets: bool) -> Self {
+        self.with_secret_redaction(match bundle_secrets {
+            true => SecretRedaction::Keep,
+            false => SecretRedaction::Mask,
+        })
+    }
+    pub fn with_secret_redaction(mut self, secret_redaction: SecretRedaction) -> Self {
+        self.secret_redaction = secret_redaction;
+        self
+    }
+    /// When true, the bundle includes an extra entry listing the plural categories used by its
+    /// messages, so the runtime only needs to load the plural rules that are actually required.
+    pub fn with_plural_rules(mut self, plural_rules: bool) -> Self {
+        self.plural_rules = plural_rules;
+        self
+    }
+    /// Messages are normally bundled as they were parsed into the database, with
+    /// [MessagesDatabase::parser_options]. When these options differ from the database's, each
+    /// message is parsed again with them before being compiled, e.g. to treat apostrophes as
+    /// literal characters rather than ICU escapes in only some bundles.
+    pub fn with_parser_options(mut self, parser_options: ParserOptions) -> Self {
+        self.parser_options = Some(parser_options);
+        self
+    }
+    /// Transforms to apply to every message after it is parsed and before it is compiled, like
+    /// replacing straight quotes with typographic ones.
+    pub fn with_transforms(mut self, transforms: TransformPipeline) -> Self {
+        self.transforms = transforms;
+        self
+    }
+    /// Rename the variables of individual messages while compiling them, after every other
+    /// transform, so the runtime can use new names before the translations are updated.
+    pub fn with_placeholder_renames(
+        mut self,
+        placeholder_renames: MessagePlaceholderRenames,
+    ) -> Self {
+        self.placeholder_renames = placeholder_renames;
+        self
+    }
+    /// Key messages by something other than their hashed key, like a short alias for smaller
+    /// production bundles, or their original key for debugging development bundles.
+    pub fn with_key_mode(mut self, key_mode: BundleKeyMode) -> Self {
+        self.key_mode = key_mode;
+        self
+    }
+    /// How compiled values are written. Pretty output puts every entry of the bundle on its own
+    /// line and indents values under it, which makes debug artifacts readable but is much larger
+    /// than the default compact output.
+    pub fn with_serialize_options(mut self, serialize_options: SerializeOptions) -> Self {
+        self.serialize_options = serialize_options;
+        self
+    }
+    /// When true, the bundle includes an extra entry mapping each bundle key to the
+    /// `file:line:column` that its value was read from, for tracing debug output back to its
+    /// source.
+    pub fn with_spans(mut self, spans: bool) -> Self {
+        self.spans = spans;
         self
     }
 }
@@ -34,11 +118,79 @@ impl Default for IntlMessageBundlerOptions {
     fn default() -> Self {
         Self {
             format: CompiledMessageFormat::KeylessJson,
-            bundle_secrets: false,
+            secret_redaction: SecretRedaction::Mask,
+            plural_rules: false,
+            parser_options: None,
+            transforms: TransformPipeline::default(),
+            placeholder_renames: MessagePlaceholderRenames::default(),
+            key_mode: BundleKeyMode::default(),
+            serialize_options: SerializeOptions::default(),
+            spans: false,
         }
     }
 }
 
+/// Write the key of an entry in the bundle object, preceded by a separator unless it is the first
+/// entry, and on its own line when `pretty` is set.
+fn write_entry_key<W: Write>(
+    output: &mut W,
+    pretty: bool,
+    is_first: &mut bool,
+    key: &str,
+) -> std::io::Result<()> {
+    if !*is_first {
+        output.write_all(b",")?;
+    }
+    *is_first = false;
+    if pretty {
+        output.write_all(b"\n")?;
+    }
+    output.write_all(b"\"")?;
+    keyless_json::write_escaped_str_contents(output, key)?;
+    output.write_all(if pretty { b"\": " } else { b"\":" })
+}
+
+/// Write a value as plain JSON, pretty printed when `pretty` is set.
+fn write_json<W: Write, T: ?Sized + Serialize>(
+    output: &mut W,
+    pretty: bool,
+    value: &T,
+) -> serde_json::Result<()> {
+    if pretty {
+        serde_json::to_writer_pretty(output, value)
+    } else {
+        serde_json::to_writer(output, value)
+    }
+}
+
+/// Writes everything through to `inner`, indenting each new line when `indent` is set so that
+/// pretty values are nested under their entry in the bundle.
+struct IndentedWriter<W> {
+    inner: W,
+    indent: bool,
+}
+
+impl<W: Write> Write for IndentedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !self.indent {
+            return self.inner.write(buf);
+        }
+        let mut lines = buf.split(|byte| *byte == b'\n');
+        if let Some(first) = lines.next() {
+            self.inner.write_all(first)?;
+        }
+        for line in lines {
+            self.inner.write_all(b"\n  ")?;
+            self.inner.write_all(line)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// A struct for managing the pre-compilation of messages as a part of bundling to be compatible
 /// with the `@discord/intl` runtime. The output of this service is a complete JSON object
 /// representing all messages that are _intentionally_ included in bundled output, including
@@ -46,15 +198,31 @@ impl Default for IntlMessageBundlerOptions {
 /// left out of the bundle.
 pub struct IntlMessageBundler<'a, W: std::io::Write> {
     database: &'a MessagesDatabase,
-    output: &'a mut W,
+    output: IndentedWriter<&'a mut W>,
     source_key: KeySymbol,
     locale_key: KeySymbol,
     options: IntlMessageBundlerOptions,
+    plural_rules: PluralRulesUsage,
+    /// Reused for compiling every message in the bundle, so that compiled nodes don't need to be
+    /// allocated individually for each message.
+    arena: CompileArena,
+    /// Every file that a bundled value was read from, with the key and value of each message that
+    /// was bundled from it, in the order they were bundled.
+    dependencies: FxHashMap<KeySymbol, Vec<(KeySymbol, &'a MessageValue)>>,
+    /// Assigned when the bundler runs with [BundleKeyMode::Alias].
+    key_aliases: Option<MessageKeyAliases>,
 }
 
 pub enum CompiledMessageFormat {
     Json,
     KeylessJson,
+    /// Each message is a string in Unicode MessageFormat 2.0 syntax.
+    Mf2,
+    /// Each message is a list of elements matching the AST from
+    /// `@formatjs/icu-messageformat-parser` exactly, so bundles can be used directly by react-intl
+    /// and other FormatJS-based renderers. Static messages are written as a single literal element
+    /// rather than a plain string.
+    ReactIntl,
 }
 
 impl<'a, W: std::io::Write> IntlMessageBundler<'a, W> {
@@ -67,13 +235,31 @@ impl<'a, W: std::io::Write> IntlMessageBundler<'a, W> {
     ) -> Self {
         Self {
             database,
-            output,
+            output: IndentedWriter {
+                inner: output,
+                indent: options.serialize_options.pretty,
+            },
             source_key,
             locale_key,
             options,
+            plural_rules: PluralRulesUsage::default(),
+            arena: CompileArena::new(),
+            dependencies: FxHashMap::default(),
+            key_aliases: None,
         }
     }
 
+    /// Return every file that contributed a value to the bundle, sorted by path, along with the
+    /// key and value of each message bundled from it, sorted by key. This includes the
+    /// definitions file itself when the bundle is for the source locale. Only valid after the
+    /// bundler has run.
+    pub fn dependencies(&self) -> BTreeMap<KeySymbol, &[(KeySymbol, &'a MessageValue)]> {
+        self.dependencies
+            .iter()
+            .map(|(file, values)| (*file, values.as_slice()))
+            .collect()
+    }
+
     /// Returns true if the message should be bundled as part of the given locale, according to its
     /// meta information and other general semantics.
     fn should_bundle(&self, message: &Message, locale: KeySymbol) -> bool {
@@ -84,19 +270,39 @@ impl<'a, W: std::io::Write> IntlMessageBundler<'a, W> {
             return false;
         }
 
+        // Messages that fall back to their definition in the database's source locale are treated
+        // the same as the source locale itself.
         let is_source = message
             .source_locale()
-            .is_some_and(|source| source == locale);
+            .is_some_and(|source| source == locale)
+            || (locale == self.database.source_locale
+                && !message.translations().contains_key(&locale));
         let should_translate = message.meta().translate;
         // If the message is marked as not ready for translation and this _isn't_ the source locale,
         // then don't include it.
         if !is_source && !should_translate {
             return false;
         }
+        // Secret messages can be left out entirely, rather than just having their value hidden.
+        if message.meta().secret && self.options.secret_redaction == SecretRedaction::Omit {
+            return false;
+        }
 
         true
     }
 
+    /// Return the value of the message to bundle for the current locale. Messages from files that
+    /// are written in a different source locale, like a shared library written in `en-GB`, may not
+    /// have a value in the database's own source locale, so their definition is used for that
+    /// locale instead to keep the message available.
+    fn get_bundled_value<'b>(&self, message: &'b Message) -> Option<&'b MessageValue> {
+        message.translations().get(&self.locale_key).or_else(|| {
+            (self.locale_key == self.database.source_locale)
+                .then(|| message.get_source_translation())
+                .flatten()
+        })
+    }
+
     /// Returns true if the message _value_ should be obfuscated in the generated bundle.
     /// Obfuscated  messages are just given a non-empty placeholder value. Note that this only
     /// applies to the  _value_ of a message because the keys will _always_ be obfuscated as the
@@ -104,7 +310,7 @@ impl<'a, W: std::io::Write> IntlMessageBundler<'a, W> {
     fn should_obfuscate(&self, message: &Message) -> bool {
         // Secret messages are obfuscated by default, but if the caller requests them to be bundled
         // then they are preserved as-is, i.e. for development builds testing out a new feature.
-        message.meta().secret && !self.options.bundle_secrets
+        message.meta().secret && self.options.secret_redaction == SecretRedaction::Mask
     }
 
     fn maybe_serialize_static_document(&mut self, document: &Document) -> anyhow::Result<bool> {
@@ -135,18 +341,42 @@ impl<'a, W: std::io::Write> IntlMessageBundler<'a, W> {
 
     fn serialize_document(&mut self, document: &Document) -> anyhow::Result<()> {
         // Serialize static documents as single strings, both for space savings and faster runtime
-        // evaluation.
-        if let Ok(true) = self.maybe_serialize_static_document(document) {
-            return Ok(());
+        // evaluation. MF2 messages are always strings already, but their text still needs to be
+        // escaped, and react-intl messages always have to be an AST, so they can't take this
+        // shortcut.
+        if !matches!(
+            self.options.format,
+            CompiledMessageFormat::Mf2 | CompiledMessageFormat::ReactIntl
+        ) {
+            if let Ok(true) = self.maybe_serialize_static_document(document) {
+                return Ok(());
+            }
         }
 
         // For any other document, just serialize it as-is.
+        let pretty = self.options.serialize_options.pretty;
         match self.options.format {
-            CompiledMessageFormat::Json => Ok(serde_json::to_writer(&mut self.output, &document)?),
-            CompiledMessageFormat::KeylessJson => Ok(keyless_json::to_writer(
+            CompiledMessageFormat::Json => Ok(write_json(&mut self.output, pretty, &document)?),
+            CompiledMessageFormat::KeylessJson => {
+                self.arena.reset();
+                Ok(keyless_json::to_writer_with_options(
+                    &mut self.output,
+                    &compile_to_format_js(document, &self.arena),
+                    self.options.serialize_options,
+                )?)
+            }
+            CompiledMessageFormat::Mf2 => Ok(serde_json::to_writer(
                 &mut self.output,
-                &compile_to_format_js(&document),
+                &format_mf2_string(document)?,
             )?),
+            CompiledMessageFormat::ReactIntl => {
+                self.arena.reset();
+                Ok(write_json(
+                    &mut self.output,
+                    pretty,
+                    &ReactIntlAst(&compile_to_format_js(document, &self.arena)),
+                )?)
+            }
         }
     }
 
@@ -155,10 +385,60 @@ impl<'a, W: std::io::Write> IntlMessageBundler<'a, W> {
     fn serialize_value(&mut self, message: &Message, value: &MessageValue) -> anyhow::Result<()> {
         let document = if self.should_obfuscate(message) {
             &raw_string_to_document(message.hashed_key())
+        } else if let Some(parser_options) = self
+            .options
+            .parser_options
+            .as_ref()
+            .filter(|parser_options| *parser_options != value.parser_options())
+        {
+            &parse_intl_message_with_options(
+                &value.raw,
+                message_may_have_blocks(&value.raw),
+                parser_options.clone(),
+            )
         } else {
-            &value.parsed
+            value.parsed()
         };
-        self.serialize_document(document)
+        let resolved;
+        let document = if message_references(document).is_empty() {
+            document
+        } else {
+            resolved = self.database.resolve_message_references(
+                message.key(),
+                document,
+                self.locale_key,
+            )?;
+            &resolved
+        };
+        let transformed;
+        let document =
+            if self.options.transforms.is_empty() && self.options.placeholder_renames.is_empty() {
+                document
+            } else {
+                let document = self.options.transforms.apply(document.clone());
+                transformed = self
+                    .options
+                    .placeholder_renames
+                    .apply(message.key(), document);
+                &transformed
+            };
+        if self.options.plural_rules {
+            self.plural_rules.collect(document);
+        }
+
+        let Some(recorder) = &self.database.performance else {
+            return self.serialize_document(document);
+        };
+        let start = Instant::now();
+        self.serialize_document(document)?;
+        recorder.record(MessageTiming {
+            key: message.key(),
+            locale: self.locale_key,
+            file: value.file_position.map(|position| position.file),
+            phase: PerformancePhase::Compile,
+            duration: start.elapsed(),
+        });
+        Ok(())
     }
 }
 
@@ -166,6 +446,10 @@ impl<W: std::io::Write> IntlDatabaseService for IntlMessageBundler<'_, W> {
     type Result = anyhow::Result<()>;
 
     fn run(&mut self) -> Self::Result {
+        self.run_with_context(&ServiceContext::default())
+    }
+
+    fn run_with_context(&mut self, context: &ServiceContext) -> Self::Result {
         let message_keys = self
             .database
             .get_source_file(self.source_key)
@@ -178,9 +462,19 @@ impl<W: std::io::Write> IntlDatabaseService for IntlMessageBundler<'_, W> {
             .collect_into(&mut sorted_message_keys)
             .sort();
 
+        if self.options.key_mode == BundleKeyMode::Alias {
+            self.key_aliases = Some(MessageKeyAliases::from_database(self.database));
+        }
+
+        let pretty = self.options.serialize_options.pretty;
         write!(self.output, "{{")?;
         let mut is_first = true;
-        for key in sorted_message_keys {
+        let mut bundled_keys = BTreeMap::new();
+        let mut spans = BTreeMap::new();
+        let total = sorted_message_keys.len();
+        for (index, key) in sorted_message_keys.into_iter().enumerate() {
+            context.check_cancelled()?;
+            context.report_progress(index, total);
             let message = self
                 .database
                 .messages
@@ -191,17 +485,146 @@ impl<W: std::io::Write> IntlDatabaseService for IntlMessageBundler<'_, W> {
                 continue;
             }
 
-            if let Some(translation) = message.translations().get(&self.locale_key) {
-                if !is_first {
-                    write!(self.output, ",")?;
-                } else {
-                    is_first = false;
+            if let Some(translation) = self.get_bundled_value(message) {
+                let bundle_key = match (self.options.key_mode, &self.key_aliases) {
+                    (BundleKeyMode::Alias, Some(aliases)) => {
+                        aliases.get(message.key()).unwrap_or(message.hashed_key())
+                    }
+                    (BundleKeyMode::Original, _) => message.key().as_str(),
+                    _ => message.hashed_key(),
+                };
+                if self.options.spans {
+                    if let Some(position) = translation.file_position {
+                        let span =
+                            format!("{}:{}:{}", position.file, position.line, position.col + 1);
+                        spans.insert(bundle_key.to_string(), span);
+                    }
                 }
-                write!(self.output, "\"{}\":", message.hashed_key())?;
+                write_entry_key(&mut self.output, pretty, &mut is_first, bundle_key)?;
                 self.serialize_value(message, translation)?;
+                if let Some(position) = translation.file_position {
+                    self.dependencies
+                        .entry(position.file)
+                        .or_default()
+                        .push((message.key(), translation));
+                }
+                if self.options.key_mode == BundleKeyMode::HashedWithKeys {
+                    bundled_keys.insert(message.hashed_key().as_str(), message.key());
+                }
             }
         }
+        context.report_progress(total, total);
+        if self.options.key_mode == BundleKeyMode::HashedWithKeys {
+            write_entry_key(&mut self.output, pretty, &mut is_first, KEYS_BUNDLE_KEY)?;
+            write_json(&mut self.output, pretty, &bundled_keys)?;
+        }
+        let key_mode_marker = match self.options.key_mode {
+            BundleKeyMode::Original => Some("original"),
+            BundleKeyMode::Alias => Some("alias"),
+            _ => None,
+        };
+        if let Some(marker) = key_mode_marker {
+            write_entry_key(&mut self.output, pretty, &mut is_first, KEY_MODE_BUNDLE_KEY)?;
+            write_json(&mut self.output, pretty, &marker)?;
+        }
+        if self.options.plural_rules {
+            write_entry_key(
+                &mut self.output,
+                pretty,
+                &mut is_first,
+                PLURAL_RULES_BUNDLE_KEY,
+            )?;
+            write_json(&mut self.output, pretty, &self.plural_rules.to_json())?;
+        }
+        if self.options.spans {
+            write_entry_key(&mut self.output, pretty, &mut is_first, SPANS_BUNDLE_KEY)?;
+            write_json(&mut self.output, pretty, &spans)?;
+        }
+        // The closing brace is the only line that isn't nested in the bundle object.
+        if pretty && !is_first {
+            self.output.inner.write_all(b"\n")?;
+        }
         write!(self.output, "}}")?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{
+        key_symbol, DefinitionFile, FilePosition, KeySymbolSet, MessageMeta, SourceFile,
+        SourceFileMeta, DEFAULT_LOCALE,
+    };
+    use intl_message_utils::hash_message_key;
+
+    use super::*;
+
+    /// Bundle the given definitions, all from a single file, in the source locale.
+    fn bundle(definitions: &[(&str, &str)]) -> String {
+        bundle_with_key_mode(definitions, BundleKeyMode::Original)
+    }
+
+    fn bundle_with_key_mode(definitions: &[(&str, &str)], key_mode: BundleKeyMode) -> String {
+        let mut database = MessagesDatabase::new();
+        let file = key_symbol("test.messages.js");
+        let locale = key_symbol(DEFAULT_LOCALE);
+        let mut keys = KeySymbolSet::default();
+        for (key, value) in definitions {
+            let value = MessageValue::from_raw(value).with_file_position(FilePosition {
+                file,
+                line: 1,
+                col: 0,
+            });
+            database
+                .insert_definition(key, value, locale, MessageMeta::default(), false)
+                .unwrap();
+            keys.insert(key_symbol(key));
+        }
+        database.create_source_file(
+            file,
+            SourceFile::Definition(DefinitionFile::new(
+                file.to_string(),
+                SourceFileMeta::new(&file),
+                keys,
+            )),
+        );
+
+        let mut output = vec![];
+        let options = IntlMessageBundlerOptions::default().with_key_mode(key_mode);
+        IntlMessageBundler::new(&database, &mut output, file, locale, options)
+            .run()
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_default_values() {
+        let output = bundle(&[
+            ("DEFAULT", "Hello, {name | friend  }!"),
+            ("PLAIN", "Hello, {name}!"),
+        ]);
+        assert!(output.contains(r#""DEFAULT":["Hello, ",[1,"name","friend"],"!"]"#));
+        assert!(output.contains(r#""PLAIN":["Hello, ",[1,"name"],"!"]"#));
+    }
+
+    #[test]
+    fn test_key_modes() {
+        let definitions = [("GREETING", "Hello!")];
+        let hashed = hash_message_key("GREETING");
+
+        let output = bundle_with_key_mode(&definitions, BundleKeyMode::Hashed);
+        assert_eq!(output, format!(r#"{{"{hashed}":"Hello!"}}"#));
+
+        let output = bundle_with_key_mode(&definitions, BundleKeyMode::Original);
+        assert_eq!(output, r#"{"GREETING":"Hello!","$keyMode":"original"}"#);
+
+        let output = bundle_with_key_mode(&definitions, BundleKeyMode::Alias);
+        assert_eq!(output, r#"{"a":"Hello!","$keyMode":"alias"}"#);
+
+        let output = bundle_with_key_mode(&definitions, BundleKeyMode::HashedWithKeys);
+        assert_eq!(
+            output,
+            format!(r#"{{"{hashed}":"Hello!","$keys":{{"{hashed}":"GREETING"}}}}"#)
+        );
+    }
+}
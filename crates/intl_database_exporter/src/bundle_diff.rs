@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use serde_json::value::RawValue;
+use thiserror::Error;
+
+use intl_database_core::{get_key_symbol, KeySymbol, MessagesDatabase};
+
+use crate::alias::{MessageKeyAliases, KEYS_BUNDLE_KEY, KEY_MODE_BUNDLE_KEY};
+use crate::bundle::SPANS_BUNDLE_KEY;
+use crate::plural_rules::PLURAL_RULES_BUNDLE_KEY;
+
+#[derive(Debug, Error)]
+pub enum BundleDiffError {
+    #[error("The {0} bundle is not a JSON object of compiled messages: {1}")]
+    InvalidBundle(&'static str, serde_json::Error),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// An entry of a compiled bundle that is different between two versions of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleEntryChange {
+    /// The key of the entry as it's written in the bundle, which is usually a hashed key.
+    pub bundle_key: String,
+    /// The message that the entry belongs to, once it has been found with
+    /// [BundleDiff::resolve_keys]. Entries that aren't messages, like `$plurals`, never have one.
+    pub key: Option<KeySymbol>,
+    /// The definitions file of the message, found along with `key`.
+    pub file: Option<KeySymbol>,
+    pub kind: BundleChangeKind,
+    /// Size of the entry in the old bundle, including its key, or 0 when it was added.
+    pub before_bytes: usize,
+    /// Size of the entry in the new bundle, including its key, or 0 when it was removed.
+    pub after_bytes: usize,
+}
+
+impl BundleEntryChange {
+    /// How many bytes the entry added to the bundle, which is negative when it got smaller.
+    pub fn byte_delta(&self) -> i64 {
+        self.after_bytes as i64 - self.before_bytes as i64
+    }
+}
+
+/// The differences between two versions of a compiled bundle, like the bundles of the same
+/// definitions file and locale from two releases.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BundleDiff {
+    /// Every entry that was added, removed, or changed, with the largest changes in size first.
+    pub changes: Vec<BundleEntryChange>,
+    /// How many entries are exactly the same in both bundles.
+    pub unchanged: usize,
+    /// Total size of the old bundle in bytes.
+    pub before_bytes: usize,
+    /// Total size of the new bundle in bytes.
+    pub after_bytes: usize,
+}
+
+impl BundleDiff {
+    /// How many bytes the new bundle is larger than the old one, which is negative when it got
+    /// smaller. Adds up to more than the sizes of the changes, since it includes the separators
+    /// between entries.
+    pub fn byte_delta(&self) -> i64 {
+        self.after_bytes as i64 - self.before_bytes as i64
+    }
+
+    /// Find the message and definitions file that each changed entry belongs to, so that growth
+    /// can be attributed to the features that the messages are part of. Bundles can be keyed by
+    /// hashed keys, aliases, or original keys, and entries are matched by whichever one they use.
+    /// Messages that aren't in `database`, like ones that were removed since the old bundle was
+    /// compiled, are left unresolved.
+    pub fn resolve_keys(&mut self, database: &MessagesDatabase) {
+        let aliases = MessageKeyAliases::from_database(database);
+        let aliases = aliases.manifest();
+        for change in &mut self.changes {
+            let bundle_key = change.bundle_key.as_str();
+            if [
+                KEYS_BUNDLE_KEY,
+                KEY_MODE_BUNDLE_KEY,
+                PLURAL_RULES_BUNDLE_KEY,
+                SPANS_BUNDLE_KEY,
+            ]
+            .contains(&bundle_key)
+            {
+                continue;
+            }
+            let key = database
+                .hash_lookup
+                .get(bundle_key)
+                .or_else(|| aliases.get(bundle_key))
+                .copied()
+                .or_else(|| {
+                    get_key_symbol(bundle_key).filter(|key| database.messages.contains_key(key))
+                });
+            let Some(key) = key else {
+                continue;
+            };
+            change.key = Some(key);
+            change.file = database
+                .messages
+                .get(&key)
+                .and_then(|message| message.get_source_translation())
+                .and_then(|value| value.file_position)
+                .map(|position| position.file);
+        }
+    }
+}
+
+/// Return the size of the entry `"key":value` as it is written in a bundle.
+fn entry_bytes(key: &str, value: &RawValue) -> usize {
+    // Keys are measured as they are escaped in the bundle. Serializing a string can't fail.
+    let key_bytes = serde_json::to_string(key).map_or(key.len() + 2, |key| key.len());
+    key_bytes + 1 + value.get().len()
+}
+
+fn parse_bundle<'a>(
+    which: &'static str,
+    bundle: &'a [u8],
+) -> Result<BTreeMap<String, &'a RawValue>, BundleDiffError> {
+    serde_json::from_slice(bundle).map_err(|error| BundleDiffError::InvalidBundle(which, error))
+}
+
+/// Compare two compiled bundles entry by entry.
+///
+/// Every message that was added, removed, or changed is reported along with how its size changed.
+/// Both bundles must be in the same format, since the compiled values are compared exactly as
+/// they are written.
+pub fn diff_compiled_bundles(before: &[u8], after: &[u8]) -> Result<BundleDiff, BundleDiffError> {
+    let before_entries = parse_bundle("old", before)?;
+    let mut after_entries = parse_bundle("new", after)?;
+
+    let mut diff = BundleDiff {
+        before_bytes: before.len(),
+        after_bytes: after.len(),
+        ..BundleDiff::default()
+    };
+    for (bundle_key, before_value) in before_entries {
+        let before_bytes = entry_bytes(&bundle_key, before_value);
+        let (kind, after_bytes) = match after_entries.remove(&bundle_key) {
+            Some(after_value) if after_value.get() == before_value.get() => {
+                diff.unchanged += 1;
+                continue;
+            }
+            Some(after_value) => (
+                BundleChangeKind::Changed,
+                entry_bytes(&bundle_key, after_value),
+            ),
+            None => (BundleChangeKind::Removed, 0),
+        };
+        diff.changes.push(BundleEntryChange {
+            bundle_key,
+            key: None,
+            file: None,
+            kind,
+            before_bytes,
+            after_bytes,
+        });
+    }
+    for (bundle_key, after_value) in after_entries {
+        let after_bytes = entry_bytes(&bundle_key, after_value);
+        diff.changes.push(BundleEntryChange {
+            bundle_key,
+            key: None,
+            file: None,
+            kind: BundleChangeKind::Added,
+            before_bytes: 0,
+            after_bytes,
+        });
+    }
+    diff.changes.sort_by(|a, b| {
+        b.byte_delta()
+            .abs()
+            .cmp(&a.byte_delta().abs())
+            .then_with(|| a.bundle_key.cmp(&b.bundle_key))
+    });
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, FilePosition, MessageMeta, MessageValue, DEFAULT_LOCALE};
+
+    use super::*;
+
+    fn summary(diff: &BundleDiff) -> Vec<(&str, BundleChangeKind, i64)> {
+        diff.changes
+            .iter()
+            .map(|change| (change.bundle_key.as_str(), change.kind, change.byte_delta()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_entries() {
+        let before = br#"{"A":"same","B":"yy","C":"z"}"#;
+        let after = br#"{"A":"same","B":"yyyy","D":"w"}"#;
+        let diff = diff_compiled_bundles(before, after).unwrap();
+        // The largest changes come first, and changes of the same size are ordered by key.
+        assert_eq!(
+            summary(&diff),
+            vec![
+                ("C", BundleChangeKind::Removed, -7),
+                ("D", BundleChangeKind::Added, 7),
+                ("B", BundleChangeKind::Changed, 2),
+            ]
+        );
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.byte_delta(), 2);
+        assert_eq!(diff.changes[2].before_bytes, 8);
+        assert_eq!(diff.changes[2].after_bytes, 10);
+    }
+
+    #[test]
+    fn test_invalid_bundles() {
+        assert!(matches!(
+            diff_compiled_bundles(b"{}", b"[]"),
+            Err(BundleDiffError::InvalidBundle("new", _))
+        ));
+        assert!(matches!(
+            diff_compiled_bundles(b"not json", b"{}"),
+            Err(BundleDiffError::InvalidBundle("old", _))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_keys() {
+        let mut database = MessagesDatabase::new();
+        let file = key_symbol("greeting.messages.js");
+        for key in ["GREETING", "FAREWELL"] {
+            let value = MessageValue::from_raw("Hello").with_file_position(FilePosition {
+                file,
+                line: 1,
+                col: 0,
+            });
+            database
+                .insert_definition(
+                    key,
+                    value,
+                    key_symbol(DEFAULT_LOCALE),
+                    MessageMeta::default(),
+                    false,
+                )
+                .unwrap();
+        }
+        let hashed_key = database.messages[&key_symbol("GREETING")]
+            .hashed_key()
+            .clone();
+
+        let after = format!(
+            r#"{{"{hashed_key}":"Hello","FAREWELL":"Bye","{PLURAL_RULES_BUNDLE_KEY}":1,"REMOVED":"x"}}"#
+        );
+        let mut diff = diff_compiled_bundles(b"{}", after.as_bytes()).unwrap();
+        diff.resolve_keys(&database);
+        let resolved = |bundle_key: &str| {
+            let change = diff
+                .changes
+                .iter()
+                .find(|change| change.bundle_key == bundle_key)
+                .unwrap();
+            (change.key, change.file)
+        };
+        // Entries are matched by hashed keys and by original keys.
+        assert_eq!(
+            resolved(&hashed_key),
+            (Some(key_symbol("GREETING")), Some(file))
+        );
+        assert_eq!(
+            resolved("FAREWELL"),
+            (Some(key_symbol("FAREWELL")), Some(file))
+        );
+        assert_eq!(resolved(PLURAL_RULES_BUNDLE_KEY), (None, None));
+        assert_eq!(resolved("REMOVED"), (None, None));
+    }
+}
@@ -1,28 +1,83 @@
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{self, Path, PathBuf};
 
-use intl_database_core::{DEFAULT_LOCALE, KeySymbol, MessagesDatabase, SourceFile};
+use intl_database_core::{
+    key_symbol, DefinitionFile, KeySymbol, MessageValue, MessagesDatabase, Project, ReviewState,
+    SourceFile, ValueComments,
+};
 use intl_database_service::IntlDatabaseService;
-use rustc_hash::FxHashMap;
-
-/// A service for persisting the current contents of a [MessagesDatabase] into a set of translation
-/// files, organized according to the configuration of each message's meta information for where
-/// each of its translations should be stored. Because messages can share translation files across
-/// multiple definition files, this process operates on the entire database at once, ensuring every
-/// message is included in each file when it is returned, without clobbering messages from other
-/// sources.
+use intl_markdown::{count_combinations, expand_combinations, format_icu_string};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{MessagePlaceholderRenames, SecretRedaction};
+
+/// A service for persisting the translations in a [MessagesDatabase] into translation files.
+///
+/// Each definitions file's meta information says where the translations of its messages are kept,
+/// and since several definitions files can share the same translation files, the whole database is
+/// exported at once so that every file includes all of the messages that belong in it. Source
+/// messages are never exported, since definitions always come from a different format.
+///
+/// By default, the export writes over the translation files that the database was read from, and
+/// keeps everything that reading them again needs: values in any review state other than `new`
+/// are written as an object like `{"message": "...", "reviewState": "reviewed"}`, and comments are
+/// written back around their entries in `.jsona` files, where comments are allowed. Files whose
+/// content wouldn't change are left untouched so that watchers don't rebuild for nothing, and
+/// [ExportTranslations::with_dry_run] only reports which files would change.
 ///
-/// The result of this service is the list of file paths that were written. After running, that list
-/// can be used by the consumer to prune any extra files in the project, check for empty values to
-/// save on total file counts, or do any other operation with full confidence that all translations
-/// for messages in the database are covered by those files.
+/// Exports meant for vendors can mask or omit secret messages, only include values in certain
+/// review states, expand nested plurals and selects into one entry per combination, or rename
+/// placeholders. Those files can't stand in for the real ones, so these options are only allowed
+/// when writing to a separate directory with [ExportTranslations::with_output_directory], and the
+/// export fails before writing anything otherwise. Messages marked with `translate: false` are
+/// also left out of those files, since they shouldn't be sent out for translation yet, but are
+/// kept when writing over the translation files so that their existing values aren't lost.
 ///
-/// Importantly, _only_ translations are processed by this export, source messages and definition
-/// files are _not_ included, since those always come from a different format.
+/// The result lists every file covered by the export, so that consumers can prune any others.
 pub struct ExportTranslations<'a> {
     database: &'a MessagesDatabase,
     file_extension: String,
+    secret_redaction: SecretRedaction,
+    review_states: Option<Vec<ReviewState>>,
+    dry_run: bool,
+    expand_combinations_above: Option<usize>,
+    project: Option<&'a Project>,
+    placeholder_renames: Option<&'a MessagePlaceholderRenames>,
+    output_directory: Option<OutputDirectory>,
+}
+
+/// The most entries that a single message can be expanded into.
+///
+/// This limits [ExportTranslations::with_expanded_combinations]. Messages with more combinations
+/// than this would bury vendors in entries, and should be split up instead.
+pub const MAX_EXPANDED_COMBINATIONS: usize = 1000;
+
+struct OutputDirectory {
+    directory: PathBuf,
+    root: PathBuf,
+}
+
+#[derive(Debug, Error)]
+pub enum ExportTranslationsError {
+    #[error("{0} can't be written over the translation files that the database reads from. Set an output directory to export them separately")]
+    InPlaceRewrite(&'static str),
+    #[error("Translation file {file} is not inside of the output root {root}")]
+    OutsideOutputRoot { file: String, root: String },
+    #[error("Message {key} has {combinations} combinations of plural and select arms, more than the {MAX_EXPANDED_COMBINATIONS} that can be expanded")]
+    TooManyCombinations { key: KeySymbol, combinations: usize },
+}
+
+/// The files covered by a run of [ExportTranslations], sorted by path.
+#[derive(Debug, Default)]
+pub struct ExportedTranslationFiles {
+    /// Every file that holds exported translations, whether or not it changed.
+    pub files: Vec<String>,
+    /// The files whose content changed and were written, or would have been written in a dry run.
+    pub changed_files: Vec<String>,
 }
 
 impl<'a> ExportTranslations<'a> {
@@ -30,73 +85,820 @@ impl<'a> ExportTranslations<'a> {
         Self {
             database,
             file_extension: file_extension.unwrap_or("messages.json".into()),
+            secret_redaction: SecretRedaction::Keep,
+            review_states: None,
+            dry_run: false,
+            expand_combinations_above: None,
+            project: None,
+            placeholder_renames: None,
+            output_directory: None,
         }
     }
+
+    pub fn with_secret_redaction(mut self, secret_redaction: SecretRedaction) -> Self {
+        self.secret_redaction = secret_redaction;
+        self
+    }
+
+    /// Only export values in one of the given `review_states`. Every other value is left out of
+    /// the written files entirely, as if it hadn't been translated yet.
+    pub fn with_review_states(mut self, review_states: Vec<ReviewState>) -> Self {
+        self.review_states = Some(review_states);
+        self
+    }
+
+    /// Only determine which files would change, without writing any of them.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Write each message with more than `threshold` combinations of plural and select arms as
+    /// one entry per combination, keyed by the arms chosen for it. The export fails if any
+    /// message has more than [MAX_EXPANDED_COMBINATIONS].
+    pub fn with_expanded_combinations(mut self, threshold: usize) -> Self {
+        self.expand_combinations_above = Some(threshold);
+        self
+    }
+
+    /// Only export the translation files used by definitions in `project`. Translation files that
+    /// are shared with definitions outside of the project are still written in full, including
+    /// the messages from those definitions.
+    pub fn with_project(mut self, project: &'a Project) -> Self {
+        self.project = Some(project);
+        self
+    }
+
+    /// Rename the variables of individual messages in the exported translations, rewriting only
+    /// the placeholders so the rest of each translation is kept exactly as it was written, like
+    /// to match renames that were applied while bundling.
+    pub fn with_placeholder_renames(
+        mut self,
+        placeholder_renames: &'a MessagePlaceholderRenames,
+    ) -> Self {
+        self.placeholder_renames = Some(placeholder_renames);
+        self
+    }
+
+    /// Write the exported files into `directory` instead of over the translation files they come
+    /// from. Each file keeps its path relative to `root`, so `root/src/i18n/fr.messages.json` is
+    /// written to `directory/src/i18n/fr.messages.json`.
+    pub fn with_output_directory(
+        mut self,
+        directory: impl Into<PathBuf>,
+        root: impl Into<PathBuf>,
+    ) -> Self {
+        self.output_directory = Some(OutputDirectory {
+            directory: directory.into(),
+            root: root.into(),
+        });
+        self
+    }
+
+    /// Returns a description of the first option that makes the exported files differ from the
+    /// translations in the database, if any is set.
+    fn lossy_option(&self) -> Option<&'static str> {
+        if self.secret_redaction != SecretRedaction::Keep {
+            Some("Redacted secret messages")
+        } else if self.review_states.is_some() {
+            Some("Translations filtered by review state")
+        } else if self.expand_combinations_above.is_some() {
+            Some("Expanded combinations")
+        } else if self.placeholder_renames.is_some() {
+            Some("Renamed placeholders")
+        } else {
+            None
+        }
+    }
+
+    /// Returns the locale tag that each existing translation file was named with, keyed by the
+    /// directory it's in and its normalized locale, so that exports write back to the same file
+    /// even when it's named with another spelling of the locale, like `en_GB`.
+    fn existing_locale_tags(&self) -> FxHashMap<(PathBuf, KeySymbol), &str> {
+        self.database
+            .sources
+            .values()
+            .filter_map(|source| match source {
+                SourceFile::Translation(translation) => {
+                    let path = Path::new(translation.file());
+                    let directory = path::absolute(path.parent()?).ok()?;
+                    Some((
+                        (directory, *translation.locale()),
+                        translation.locale_tag().as_str(),
+                    ))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns each locale that the messages in `file` are translated into, along with the path of
+    /// the translation file for that locale, without an extension. Locales that already have a
+    /// translation file keep the name it was given, and others are named by their locale.
+    fn translation_paths(
+        &self,
+        file: &DefinitionFile,
+        locale_tags: &FxHashMap<(PathBuf, KeySymbol), &str>,
+    ) -> Vec<(KeySymbol, PathBuf)> {
+        // Definitions are written in the file's own source locale when it sets one, and otherwise
+        // in the database's. Messages inserted with another locale are skipped individually.
+        let source_locale = file
+            .meta()
+            .source_locale
+            .as_deref()
+            .map_or(self.database.source_locale, key_symbol);
+        self.database
+            .known_locales
+            .iter()
+            .filter(|locale| **locale != source_locale)
+            .map(|locale| {
+                let path = file.meta().get_translations_path(locale, None);
+                let existing_tag = path
+                    .parent()
+                    .and_then(|directory| locale_tags.get(&(directory.to_path_buf(), *locale)));
+                match existing_tag {
+                    Some(tag) => (*locale, path.with_file_name(tag)),
+                    None => (*locale, path),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the path that the translations for `file` are written to.
+    fn output_path(&self, file: PathBuf) -> Result<PathBuf, ExportTranslationsError> {
+        let Some(output) = &self.output_directory else {
+            return Ok(file);
+        };
+        let relative = file.strip_prefix(&output.root).map_err(|_| {
+            ExportTranslationsError::OutsideOutputRoot {
+                file: file.display().to_string(),
+                root: output.root.display().to_string(),
+            }
+        })?;
+        Ok(output.directory.join(relative))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ExportedTranslation<'a> {
+    Text(Cow<'a, str>),
+    WithState {
+        message: Cow<'a, str>,
+        #[serde(rename = "reviewState")]
+        review_state: ReviewState,
+        #[serde(
+            rename = "machineRecovered",
+            skip_serializing_if = "std::ops::Not::not"
+        )]
+        machine_recovered: bool,
+    },
+}
+
+struct ExportedEntry<'a> {
+    translation: ExportedTranslation<'a>,
+    comments: Option<&'a ValueComments>,
+}
+
+impl<'a> ExportedEntry<'a> {
+    fn new(content: Cow<'a, str>, value: &'a MessageValue) -> Self {
+        let translation = match (value.review_state, value.machine_recovered) {
+            (ReviewState::New, false) => ExportedTranslation::Text(content),
+            (review_state, machine_recovered) => ExportedTranslation::WithState {
+                message: content,
+                review_state,
+                machine_recovered,
+            },
+        };
+        Self {
+            translation,
+            comments: value.comments.as_deref(),
+        }
+    }
+}
+
+/// Serialize `values` the same way as `serde_json::to_string_pretty`, but with the comments of each
+/// entry written back where they were read from when `include_comments` is set.
+fn serialize_translations(
+    values: &BTreeMap<KeySymbol, ExportedEntry>,
+    include_comments: bool,
+) -> serde_json::Result<String> {
+    if values.is_empty() {
+        return Ok("{}".into());
+    }
+
+    let mut content = String::from("{\n");
+    for (index, (key, entry)) in values.iter().enumerate() {
+        let comments = entry.comments.filter(|_| include_comments);
+        if let Some(leading) = comments.and_then(|comments| comments.leading.as_deref()) {
+            content.push_str("  ");
+            content.push_str(leading);
+            content.push('\n');
+        }
+        content.push_str("  ");
+        content.push_str(&serde_json::to_string(key.as_str())?);
+        content.push_str(": ");
+        if let Some(inline) = comments.and_then(|comments| comments.inline.as_deref()) {
+            content.push_str(inline);
+            content.push(' ');
+        }
+        let translation = serde_json::to_string_pretty(&entry.translation)?;
+        content.push_str(&translation.replace('\n', "\n  "));
+        if index + 1 < values.len() {
+            content.push(',');
+        }
+        if let Some(trailing) = comments.and_then(|comments| comments.trailing.as_deref()) {
+            content.push_str(trailing);
+        }
+        content.push('\n');
+    }
+    content.push('}');
+    Ok(content)
 }
 
 impl IntlDatabaseService for ExportTranslations<'_> {
-    type Result = anyhow::Result<Vec<String>>;
+    type Result = anyhow::Result<ExportedTranslationFiles>;
 
     fn run(&mut self) -> Self::Result {
-        let definition_files = self
+        if self.output_directory.is_none() {
+            if let Some(option) = self.lossy_option() {
+                return Err(ExportTranslationsError::InPlaceRewrite(option).into());
+            }
+        }
+
+        let definition_files: Vec<&DefinitionFile> = self
             .database
             .sources
             .values()
             .filter_map(|source| match source {
                 SourceFile::Definition(definition) => Some(definition),
                 _ => None,
-            });
+            })
+            .collect();
+        let locale_tags = self.existing_locale_tags();
+        // A project's export only covers the translation files its definitions use, but those are
+        // still written in full, so messages from other projects that share them aren't lost.
+        let project_paths: Option<FxHashSet<PathBuf>> = self.project.map(|project| {
+            definition_files
+                .iter()
+                .filter(|file| self.database.is_file_in_project(file.file(), project))
+                .flat_map(|file| self.translation_paths(file, &locale_tags))
+                .map(|(_, path)| path)
+                .collect()
+        });
 
-        let mut result: FxHashMap<PathBuf, BTreeMap<KeySymbol, &String>> = FxHashMap::default();
+        let mut result: FxHashMap<PathBuf, BTreeMap<KeySymbol, ExportedEntry>> =
+            FxHashMap::default();
         for file in definition_files {
-            for locale in &self.database.known_locales {
-                // TODO: Make DEFAULT_LOCALE configurable. This assumes all definitions are
-                // in the default locale, but it's possible for definitions to use a different
-                // locale as the source, and this is arguably something that can be set per-message.
-                if *locale == DEFAULT_LOCALE {
+            for (locale, path) in self.translation_paths(file, &locale_tags) {
+                if project_paths
+                    .as_ref()
+                    .is_some_and(|paths| !paths.contains(&path))
+                {
                     continue;
                 }
 
-                let path = file.meta().get_translations_path(&locale, None);
                 let values = result.entry(path).or_default();
                 for key in file.message_keys() {
-                    let Some(message) = self.database.get_message(&key) else {
+                    let Some(message) = self.database.get_message(key) else {
                         continue;
                     };
                     if message
                         .source_locale()
-                        .is_some_and(|source| source == *locale)
+                        .is_some_and(|source| source == locale)
                     {
                         continue;
                     }
-                    let Some(value) = self
-                        .database
-                        .get_message(&key)
-                        .and_then(|message| message.translations().get(&locale))
-                    else {
+                    // Messages that aren't ready for translation shouldn't be sent out yet, even
+                    // if some locales already have a value for them.
+                    if self.output_directory.is_some() && !message.meta().translate {
+                        continue;
+                    }
+                    let Some(value) = message.translations().get(&locale) else {
                         continue;
                     };
 
-                    values.insert(*key, &value.raw);
+                    if self
+                        .review_states
+                        .as_ref()
+                        .is_some_and(|states| !states.contains(&value.review_state))
+                    {
+                        continue;
+                    }
+
+                    let (content, is_masked) = match (message.meta().secret, self.secret_redaction)
+                    {
+                        (true, SecretRedaction::Omit) => continue,
+                        (true, SecretRedaction::Mask) => {
+                            (Cow::Borrowed(message.hashed_key().as_str()), true)
+                        }
+                        _ => match self.placeholder_renames {
+                            Some(renames) => (renames.apply_to_raw(*key, value), false),
+                            None => (Cow::Borrowed(value.raw.as_str()), false),
+                        },
+                    };
+                    // Masked secrets don't include any of the content, so there's nothing to
+                    // expand in them.
+                    let should_expand = !is_masked
+                        && self.expand_combinations_above.is_some_and(|threshold| {
+                            count_combinations(value.parsed()) > threshold
+                        });
+                    if !should_expand {
+                        values.insert(*key, ExportedEntry::new(content, value));
+                        continue;
+                    }
+                    let renamed;
+                    let document = match self.placeholder_renames {
+                        Some(renames) => {
+                            renamed = renames.apply(*key, value.parsed().clone());
+                            &renamed
+                        }
+                        None => value.parsed(),
+                    };
+                    let combinations = expand_combinations(document, MAX_EXPANDED_COMBINATIONS)
+                        .ok_or_else(|| ExportTranslationsError::TooManyCombinations {
+                            key: *key,
+                            combinations: count_combinations(document),
+                        })?;
+                    for combination in combinations {
+                        let selections = combination
+                            .selections
+                            .iter()
+                            .map(|(name, selector)| format!("{name}={selector}"))
+                            .collect::<Vec<_>>()
+                            .join(",");
+                        let content = format_icu_string(&combination.document)?;
+                        values.insert(
+                            key_symbol(&format!("{key}[{selections}]")),
+                            ExportedEntry::new(Cow::Owned(content), value),
+                        );
+                    }
                 }
             }
         }
 
-        let mut affected_files = vec![];
+        let mut exported = ExportedTranslationFiles::default();
+        let include_comments = self.file_extension.ends_with("jsona");
 
+        // Check every path before writing anything, so that a misconfigured root doesn't leave the
+        // export half written.
+        let result = result
+            .into_iter()
+            .map(|(file, values)| Ok((self.output_path(file)?, values)))
+            .collect::<Result<Vec<_>, ExportTranslationsError>>()?;
         for (file, values) in result {
             let path = file.with_extension(&self.file_extension);
-            affected_files.push(path.display().to_string());
+            exported.files.push(path.display().to_string());
+
+            let content = serialize_translations(&values, include_comments)?;
+            if std::fs::read_to_string(&path).is_ok_and(|existing| existing == content) {
+                continue;
+            }
+            exported.changed_files.push(path.display().to_string());
+            if self.dry_run {
+                continue;
+            }
 
             if let Some(directory) = path.parent() {
                 std::fs::create_dir_all(directory)?;
             }
-
-            let content = serde_json::to_string_pretty(&values)?;
             let mut output = std::fs::File::create(path)?;
             output.write_all(content.as_bytes())?;
         }
 
-        Ok(affected_files)
+        exported.files.sort();
+        exported.changed_files.sort();
+        Ok(exported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use intl_database_core::{
+        KeySymbolSet, MessageMeta, MessageTranslationSource, SourceFileMeta, TranslationFile,
+    };
+    use intl_database_json_source::JsonMessageSource;
+
+    use super::*;
+
+    /// Create an empty directory that's unique to the calling test.
+    fn test_directory(test_name: &str) -> PathBuf {
+        let directory = std::env::temp_dir()
+            .join("intl_database_exporter")
+            .join(test_name);
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Add a definitions file at `path` to `database`, along with a French translation for each of
+    /// the given messages.
+    fn add_definitions_file(
+        database: &mut MessagesDatabase,
+        path: &Path,
+        meta: impl FnOnce(SourceFileMeta) -> SourceFileMeta,
+        translations: &[(&str, MessageValue)],
+    ) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+        let path = path.to_str().unwrap();
+        let mut keys = KeySymbolSet::default();
+        for (key, translation) in translations {
+            database
+                .insert_definition(
+                    key,
+                    MessageValue::from_raw(key),
+                    database.source_locale,
+                    MessageMeta::default(),
+                    false,
+                )
+                .unwrap();
+            database
+                .insert_translation(
+                    key_symbol(key),
+                    key_symbol("fr"),
+                    translation.clone(),
+                    false,
+                )
+                .unwrap();
+            keys.insert(key_symbol(key));
+        }
+        database.create_source_file(
+            key_symbol(path),
+            SourceFile::Definition(DefinitionFile::new(
+                path.to_string(),
+                meta(SourceFileMeta::new(path)),
+                keys,
+            )),
+        );
+    }
+
+    /// Create a database with one definitions file, in a directory that's unique to the calling
+    /// test, and a French translation for each of the given messages.
+    fn database(
+        test_name: &str,
+        translations: &[(&str, MessageValue)],
+    ) -> (MessagesDatabase, PathBuf) {
+        let directory = test_directory(test_name);
+        let mut database = MessagesDatabase::new();
+        add_definitions_file(
+            &mut database,
+            &directory.join("test.messages.js"),
+            |meta| meta,
+            translations,
+        );
+        (database, directory)
+    }
+
+    /// Read the translations in `path` the same way the database does.
+    fn read_translations(path: &Path) -> Vec<(String, MessageValue)> {
+        let content: Arc<str> = std::fs::read_to_string(path).unwrap().into();
+        JsonMessageSource
+            .extract_translations(key_symbol(path.to_str().unwrap()), content)
+            .unwrap()
+            .map(|translation| (translation.name.to_string(), translation.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_writes_over_translation_files() {
+        let (database, directory) = database(
+            "in_place",
+            &[("GREETING", MessageValue::from_raw("Bonjour"))],
+        );
+        let exported = ExportTranslations::new(&database, None).run().unwrap();
+        let path = directory.join("messages/fr.messages.json");
+        assert_eq!(exported.files, vec![path.display().to_string()]);
+        assert_eq!(exported.changed_files, exported.files);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\n  \"GREETING\": \"Bonjour\"\n}"
+        );
+
+        let exported = ExportTranslations::new(&database, None).run().unwrap();
+        assert!(exported.changed_files.is_empty());
+    }
+
+    #[test]
+    fn test_comments_round_trip() {
+        let content = "{\n  // Keep it short\n  \"A\": \"un\", // Reviewed\n  \"B\": /* inline */ \"deux\"\n  // Trailing\n}";
+        let directory = test_directory("comments_source");
+        let source = directory.join("fr.messages.jsona");
+        std::fs::write(&source, content).unwrap();
+        let translations = read_translations(&source);
+        let translations: Vec<(&str, MessageValue)> = translations
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+
+        let (database, directory) = database("comments", &translations);
+        ExportTranslations::new(&database, Some("messages.jsona".into()))
+            .run()
+            .unwrap();
+        let path = directory.join("messages/fr.messages.jsona");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+
+        // Plain JSON doesn't allow comments, so they are left out.
+        ExportTranslations::new(&database, None).run().unwrap();
+        let path = directory.join("messages/fr.messages.json");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\n  \"A\": \"un\",\n  \"B\": \"deux\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_keeps_locale_tags_of_existing_files() {
+        let (mut database, directory) = database(
+            "locale_tags",
+            &[("GREETING", MessageValue::from_raw("Bonjour"))],
+        );
+        // Files named with another spelling of their locale are read as the normalized locale.
+        let path = directory.join("messages/en_GB.messages.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{}").unwrap();
+        let locale = key_symbol("en-GB");
+        database
+            .insert_translation(
+                key_symbol("GREETING"),
+                locale,
+                MessageValue::from_raw("Hello, mate"),
+                false,
+            )
+            .unwrap();
+        let file = path.to_str().unwrap();
+        database.create_source_file(
+            key_symbol(file),
+            SourceFile::Translation(
+                TranslationFile::new(
+                    file.to_string(),
+                    locale,
+                    KeySymbolSet::from_iter([key_symbol("GREETING")]),
+                )
+                .with_locale_tag("en_GB".into()),
+            ),
+        );
+
+        let exported = ExportTranslations::new(&database, None).run().unwrap();
+        let mut files = exported.files.clone();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                path.display().to_string(),
+                directory
+                    .join("messages/fr.messages.json")
+                    .display()
+                    .to_string(),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\n  \"GREETING\": \"Hello, mate\"\n}"
+        );
+        assert!(!directory.join("messages/en-GB.messages.json").exists());
+    }
+
+    #[test]
+    fn test_lossy_exports_need_output_directory() {
+        let (database, directory) =
+            database("lossy", &[("GREETING", MessageValue::from_raw("Bonjour"))]);
+        let error = ExportTranslations::new(&database, None)
+            .with_review_states(vec![ReviewState::Final])
+            .run()
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(ExportTranslationsError::InPlaceRewrite(_))
+        ));
+        assert!(!directory.join("messages").exists());
+
+        let output = directory.join("vendor");
+        let exported = ExportTranslations::new(&database, None)
+            .with_review_states(vec![ReviewState::Final])
+            .with_output_directory(&output, &directory)
+            .run()
+            .unwrap();
+        let path = output.join("messages/fr.messages.json");
+        assert_eq!(exported.files, vec![path.display().to_string()]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+        assert!(!directory.join("messages").exists());
+    }
+
+    #[test]
+    fn test_output_root_must_contain_files() {
+        let (database, directory) = database(
+            "outside_root",
+            &[("GREETING", MessageValue::from_raw("Bonjour"))],
+        );
+        let error = ExportTranslations::new(&database, None)
+            .with_output_directory(directory.join("vendor"), directory.join("other"))
+            .run()
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(ExportTranslationsError::OutsideOutputRoot { .. })
+        ));
+        assert!(!directory.join("vendor").exists());
+    }
+
+    #[test]
+    fn test_review_states_round_trip() {
+        let (database, directory) = database(
+            "review_states",
+            &[
+                (
+                    "FINAL",
+                    MessageValue::from_raw("Fini").with_review_state(ReviewState::Final),
+                ),
+                ("NEW", MessageValue::from_raw("Nouveau")),
+                (
+                    "RECOVERED",
+                    MessageValue::from_raw("Récupéré")
+                        .with_review_state(ReviewState::Machine)
+                        .with_machine_recovered(true),
+                ),
+                (
+                    "REVIEWED",
+                    MessageValue::from_raw("Relu").with_review_state(ReviewState::Reviewed),
+                ),
+            ],
+        );
+        ExportTranslations::new(&database, None).run().unwrap();
+        let translations = read_translations(&directory.join("messages/fr.messages.json"));
+        let states: Vec<_> = translations
+            .iter()
+            .map(|(key, value)| {
+                (
+                    key.as_str(),
+                    value.raw.as_str(),
+                    value.review_state,
+                    value.machine_recovered,
+                )
+            })
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("FINAL", "Fini", ReviewState::Final, false),
+                ("NEW", "Nouveau", ReviewState::New, false),
+                ("RECOVERED", "Récupéré", ReviewState::Machine, true),
+                ("REVIEWED", "Relu", ReviewState::Reviewed, false),
+            ]
+        );
+
+        let output = directory.join("vendor");
+        ExportTranslations::new(&database, None)
+            .with_review_states(vec![ReviewState::Reviewed, ReviewState::Final])
+            .with_output_directory(&output, &directory)
+            .run()
+            .unwrap();
+        let translations = read_translations(&output.join("messages/fr.messages.json"));
+        let states: Vec<_> = translations
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.review_state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("FINAL", ReviewState::Final),
+                ("REVIEWED", ReviewState::Reviewed)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_messages_not_ready_for_translation() {
+        let (mut database, directory) = database(
+            "not_ready",
+            &[
+                ("DRAFT", MessageValue::from_raw("Brouillon")),
+                ("GREETING", MessageValue::from_raw("Bonjour")),
+            ],
+        );
+        let locale = database.source_locale;
+        let meta = MessageMeta {
+            translate: false,
+            ..MessageMeta::default()
+        };
+        database
+            .insert_definition("DRAFT", MessageValue::from_raw("DRAFT"), locale, meta, true)
+            .unwrap();
+
+        // Writing over the translation files keeps the values they already have.
+        ExportTranslations::new(&database, None).run().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(directory.join("messages/fr.messages.json")).unwrap(),
+            "{\n  \"DRAFT\": \"Brouillon\",\n  \"GREETING\": \"Bonjour\"\n}"
+        );
+
+        // But they aren't sent out for translation.
+        let output = directory.join("vendor");
+        ExportTranslations::new(&database, None)
+            .with_output_directory(&output, &directory)
+            .run()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(output.join("messages/fr.messages.json")).unwrap(),
+            "{\n  \"GREETING\": \"Bonjour\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_expanded_combinations() {
+        let (database, directory) = database(
+            "expanded",
+            &[(
+                "ITEMS",
+                MessageValue::from_raw("{count, plural, one {# article} other {# articles}}"),
+            )],
+        );
+        let error = ExportTranslations::new(&database, None)
+            .with_expanded_combinations(1)
+            .run()
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(ExportTranslationsError::InPlaceRewrite(_))
+        ));
+
+        let output = directory.join("vendor");
+        ExportTranslations::new(&database, None)
+            .with_expanded_combinations(1)
+            .with_output_directory(&output, &directory)
+            .run()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(output.join("messages/fr.messages.json")).unwrap(),
+            "{\n  \"ITEMS[count=one]\": \"{count, number} article\",\n  \"ITEMS[count=other]\": \"{count, number} articles\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_too_many_combinations() {
+        let selects = "{a, select, x {1} other {2}} ".repeat(10);
+        let (database, directory) =
+            database("too_many", &[("HUGE", MessageValue::from_raw(&selects))]);
+        let error = ExportTranslations::new(&database, None)
+            .with_expanded_combinations(1)
+            .with_output_directory(directory.join("vendor"), &directory)
+            .run()
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(ExportTranslationsError::TooManyCombinations {
+                combinations: 1024,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_project_keeps_shared_files_whole() {
+        let directory = test_directory("project");
+        let shared = directory.join("messages");
+        let shared_meta =
+            |meta: SourceFileMeta| meta.with_translations_path(shared.to_str().unwrap());
+        let mut database = MessagesDatabase::new();
+        add_definitions_file(
+            &mut database,
+            &directory.join("app/app.messages.js"),
+            shared_meta,
+            &[("APP", MessageValue::from_raw("Application"))],
+        );
+        add_definitions_file(
+            &mut database,
+            &directory.join("app/nested/nested.messages.js"),
+            shared_meta,
+            &[("NESTED", MessageValue::from_raw("Imbriqué"))],
+        );
+        add_definitions_file(
+            &mut database,
+            &directory.join("app/nested/own/own.messages.js"),
+            |meta| meta,
+            &[("OWN", MessageValue::from_raw("Propre"))],
+        );
+        database.register_project(Project::new(key_symbol("app"), directory.join("app")));
+        database.register_project(Project::new(
+            key_symbol("nested"),
+            directory.join("app/nested"),
+        ));
+
+        let project = database.get_project(key_symbol("app")).unwrap();
+        let exported = ExportTranslations::new(&database, None)
+            .with_project(project)
+            .run()
+            .unwrap();
+        let path = shared.join("fr.messages.json");
+        assert_eq!(exported.files, vec![path.display().to_string()]);
+        let keys: Vec<_> = read_translations(&path)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec!["APP", "NESTED"]);
+        assert!(!directory.join("app/nested/own/messages").exists());
     }
 }
@@ -1,9 +1,29 @@
 #![feature(iter_collect_into)]
 
+pub use alias::{BundleKeyMode, MessageKeyAliases};
 pub use bundle::{
     CompiledMessageFormat, IntlMessageBundler, IntlMessageBundlerError, IntlMessageBundlerOptions,
 };
-pub use export::ExportTranslations;
+pub use bundle_diff::{
+    diff_compiled_bundles, BundleChangeKind, BundleDiff, BundleDiffError, BundleEntryChange,
+};
+pub use export::{
+    ExportTranslations, ExportTranslationsError, ExportedTranslationFiles,
+    MAX_EXPANDED_COMBINATIONS,
+};
+pub use machine_translation::{MachineTranslationProvider, PrefillMachineTranslations};
+pub use redaction::SecretRedaction;
+pub use transform::{
+    BidiIsolation, CjkWordBreaks, MessagePlaceholderRenames, MessageTransform,
+    MessageTransformError, NormalizeUnicode, RenamePlaceholders, SmartQuotes, SubstituteConstants,
+    TransformPipeline,
+};
 
+mod alias;
 mod bundle;
+mod bundle_diff;
 mod export;
+mod machine_translation;
+mod plural_rules;
+mod redaction;
+mod transform;
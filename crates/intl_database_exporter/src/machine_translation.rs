@@ -0,0 +1,127 @@
+use anyhow::bail;
+use rustc_hash::FxHashMap;
+
+use intl_database_core::{
+    FilePosition, KeySymbol, MessageValue, MessagesDatabase, ReviewState, TextPointer,
+};
+use intl_database_service::IntlDatabaseService;
+
+/// An external service that can translate messages automatically, like a machine translation API.
+pub trait MachineTranslationProvider {
+    /// The name of the provider, used in error messages.
+    fn name(&self) -> &str;
+
+    /// Translate every message in `sources` from `source_locale` into `target_locale`, returning
+    /// exactly one result for each source, in the same order. Sources that the provider can't
+    /// translate, like ones with syntax it doesn't understand, return `None` and are left missing.
+    fn translate(
+        &self,
+        source_locale: &str,
+        target_locale: &str,
+        sources: &[&str],
+    ) -> anyhow::Result<Vec<Option<String>>>;
+}
+
+/// A service for filling in missing translations using a [MachineTranslationProvider].
+///
+/// Every value that is filled in is put in the `machine` review state, which is kept when the
+/// translations are exported and read again, until a person reviews the translation.
+///
+/// Existing translations are never replaced, even if they were machine translated before. Messages
+/// marked with `translate: false` and secret messages are never sent to the provider.
+///
+/// The result of this service is the number of translations that were filled in.
+pub struct PrefillMachineTranslations<'a> {
+    database: &'a mut MessagesDatabase,
+    provider: &'a dyn MachineTranslationProvider,
+    locales: Vec<KeySymbol>,
+}
+
+impl<'a> PrefillMachineTranslations<'a> {
+    pub fn new(
+        database: &'a mut MessagesDatabase,
+        provider: &'a dyn MachineTranslationProvider,
+        locales: Vec<KeySymbol>,
+    ) -> Self {
+        Self {
+            database,
+            provider,
+            locales,
+        }
+    }
+
+    /// Return every message that should be translated into `locale` but isn't yet, grouped by
+    /// the locale of its source definition.
+    fn collect_missing(&self, locale: KeySymbol) -> FxHashMap<KeySymbol, Vec<MissingTranslation>> {
+        let mut missing: FxHashMap<KeySymbol, Vec<MissingTranslation>> = FxHashMap::default();
+        for message in self.database.messages.values() {
+            let meta = message.meta();
+            if !meta.translate || meta.secret || message.translations().contains_key(&locale) {
+                continue;
+            }
+            let (Some(source_locale), Some(source)) =
+                (*message.source_locale(), message.get_source_translation())
+            else {
+                continue;
+            };
+            missing
+                .entry(source_locale)
+                .or_default()
+                .push(MissingTranslation {
+                    key: message.key(),
+                    source: source.raw.clone(),
+                    file_position: source.file_position,
+                });
+        }
+        for messages in missing.values_mut() {
+            messages.sort_by_key(|message| message.key);
+        }
+        missing
+    }
+}
+
+struct MissingTranslation {
+    key: KeySymbol,
+    source: TextPointer,
+    file_position: Option<FilePosition>,
+}
+
+impl IntlDatabaseService for PrefillMachineTranslations<'_> {
+    type Result = anyhow::Result<usize>;
+
+    fn run(&mut self) -> Self::Result {
+        let mut filled = 0;
+        for locale in self.locales.clone() {
+            for (source_locale, messages) in self.collect_missing(locale) {
+                let sources: Vec<&str> = messages
+                    .iter()
+                    .map(|message| message.source.as_str())
+                    .collect();
+                let translations = self.provider.translate(&source_locale, &locale, &sources)?;
+                if translations.len() != messages.len() {
+                    bail!(
+                        "{} returned {} translations for {} messages in {locale}",
+                        self.provider.name(),
+                        translations.len(),
+                        messages.len()
+                    );
+                }
+
+                for (message, translation) in messages.into_iter().zip(translations) {
+                    let Some(translation) = translation else {
+                        continue;
+                    };
+                    let mut value = MessageValue::from_raw(&translation)
+                        .with_review_state(ReviewState::Machine);
+                    // Prefilled values don't come from any file until they are exported, so they
+                    // point at the definition they were translated from instead.
+                    value.file_position = message.file_position;
+                    self.database
+                        .insert_translation(message.key, locale, value, false)?;
+                    filled += 1;
+                }
+            }
+        }
+        Ok(filled)
+    }
+}
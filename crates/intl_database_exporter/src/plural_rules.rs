@@ -0,0 +1,51 @@
+use std::collections::BTreeSet;
+
+use intl_markdown::{Document, IcuPlural, IcuPluralKind};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+/// The key used to store plural rule usage in a bundle. Hashed keys and aliases never contain `$`,
+/// so this can't collide with a message in those modes, but a message whose original key is
+/// `$plurals` would be overwritten by it in bundles written with [crate::BundleKeyMode::Original].
+pub(crate) const PLURAL_RULES_BUNDLE_KEY: &str = "$plurals";
+
+/// The set of CLDR plural categories that are actually referenced by the messages in a bundle,
+/// separated by the type of plural rules needed to select them.
+///
+/// Exact selectors like `=0` are matched directly by the runtime, and `other` is always the
+/// fallback, so neither requires any plural rule data and both are left out. A bundle with no
+/// categories for a type can skip loading the rules for that type entirely.
+#[derive(Default)]
+pub(crate) struct PluralRulesUsage {
+    cardinal: BTreeSet<String>,
+    ordinal: BTreeSet<String>,
+}
+
+impl PluralRulesUsage {
+    pub(crate) fn collect(&mut self, document: &Document) {
+        visit_with_mut(document, self);
+    }
+
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "cardinal": self.cardinal,
+            "ordinal": self.ordinal,
+        })
+    }
+}
+
+impl Visit for PluralRulesUsage {
+    fn visit_icu_plural(&mut self, node: &IcuPlural) {
+        let categories = match node.kind() {
+            IcuPluralKind::Plural => &mut self.cardinal,
+            IcuPluralKind::SelectOrdinal => &mut self.ordinal,
+        };
+        for arm in node.arms() {
+            let selector = arm.selector();
+            if selector.starts_with('=') || selector == "other" {
+                continue;
+            }
+            categories.insert(selector.to_owned());
+        }
+        node.visit_children_with(self);
+    }
+}
@@ -0,0 +1,13 @@
+/// How messages marked as `secret` should be handled when they are written out of the database,
+/// either to bundles or to exported translation files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SecretRedaction {
+    /// Write secret messages with their real content, i.e. for internal or development builds.
+    #[default]
+    Keep,
+    /// Replace the content of secret messages with their hashed key, so that the message still
+    /// exists in the output but its value can't be recovered.
+    Mask,
+    /// Leave secret messages out of the output entirely.
+    Omit,
+}
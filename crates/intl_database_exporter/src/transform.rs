@@ -0,0 +1,552 @@
+//! Transforms rewrite messages after they are parsed and before they are compiled into a bundle,
+//! for changes that only matter to the final output, like typographic quotes, directional
+//! isolation around placeholders, or line break hints in CJK text. The source and translation
+//! files are never changed, though [MessagePlaceholderRenames] can also be applied to a separate
+//! export of the translation files.
+//!
+//! Transforms are applied in the order they are added to a [TransformPipeline], each receiving the
+//! result of the one before it.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use rustc_hash::FxHashMap;
+use thiserror::Error;
+
+use intl_database_core::{KeySymbol, MessageValue};
+
+use intl_markdown::fold::{
+    fold_block_node_children, fold_icu_children, fold_icu_plural_arm_children,
+    fold_inline_content_children,
+};
+use intl_markdown::{
+    fold_document, is_cjk_letter, is_cjk_punctuation, substitute_constants, BlockNode,
+    ConstantValue, Document, Fold, Icu, IcuPluralArm, IcuVariable, InlineContent,
+};
+use intl_markdown_visitor::{visit_with_mut, Visit};
+use intl_message_utils::normalize_unicode;
+
+#[derive(Debug, Error)]
+pub enum MessageTransformError {
+    #[error("Unknown message transform {0}")]
+    UnknownTransform(String),
+}
+
+/// A single pass over every bundled message.
+pub trait MessageTransform: Send + Sync {
+    /// The name of the transform, like `smart-quotes`. Built-in transforms can be added to a
+    /// pipeline by this name.
+    fn name(&self) -> &str;
+    fn transform(&self, document: Document) -> Document;
+}
+
+#[derive(Default)]
+pub struct TransformPipeline {
+    transforms: Vec<Box<dyn MessageTransform>>,
+}
+
+impl TransformPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_transform(mut self, transform: impl MessageTransform + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Add the built-in transform with the given name. Transforms that need to be configured,
+    /// like [RenamePlaceholders], have to be added with [TransformPipeline::with_transform].
+    pub fn with_named_transform(self, name: &str) -> Result<Self, MessageTransformError> {
+        match name {
+            SmartQuotes::NAME => Ok(self.with_transform(SmartQuotes)),
+            BidiIsolation::NAME => Ok(self.with_transform(BidiIsolation)),
+            CjkWordBreaks::NAME => Ok(self.with_transform(CjkWordBreaks::new())),
+            NormalizeUnicode::NAME => Ok(self.with_transform(NormalizeUnicode)),
+            _ => Err(MessageTransformError::UnknownTransform(name.into())),
+        }
+    }
+
+    /// The names of every transform in the pipeline, in the order they are applied.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.transforms.iter().map(|transform| transform.name())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    pub fn apply(&self, document: Document) -> Document {
+        self.transforms
+            .iter()
+            .fold(document, |document, transform| {
+                transform.transform(document)
+            })
+    }
+}
+
+/// Replace straight quotes and apostrophes in visible text with typographic ones.
+///
+/// For example, `"hi"` becomes `“hi”` and `don't` becomes `don’t`. Whether a quote opens or closes
+/// is decided by the character before it, including across placeholders and formatting.
+pub struct SmartQuotes;
+
+impl SmartQuotes {
+    pub const NAME: &'static str = "smart-quotes";
+}
+
+impl MessageTransform for SmartQuotes {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn transform(&self, document: Document) -> Document {
+        fold_document(document, &mut SmartQuotesFolder::default())
+    }
+}
+
+struct SmartQuotesFolder {
+    /// True when the next quote would open a quotation, like at the start of a block or after
+    /// whitespace.
+    is_opening_context: bool,
+    /// The context that each arm of the innermost plural or select starts in, since every arm
+    /// follows the same text before it.
+    arm_context: bool,
+}
+
+impl Default for SmartQuotesFolder {
+    fn default() -> Self {
+        Self {
+            is_opening_context: true,
+            arm_context: true,
+        }
+    }
+}
+
+impl Fold for SmartQuotesFolder {
+    fn fold_block_node(&mut self, node: BlockNode) -> BlockNode {
+        self.is_opening_context = true;
+        fold_block_node_children(self, node)
+    }
+
+    fn fold_inline_content(&mut self, node: InlineContent) -> InlineContent {
+        let node = fold_inline_content_children(self, node);
+        match node {
+            InlineContent::HardLineBreak => self.is_opening_context = true,
+            InlineContent::CodeSpan(_)
+            | InlineContent::Emoji(_)
+            | InlineContent::Mention(_)
+            | InlineContent::KeyboardKey(_)
+            | InlineContent::IcuPound => self.is_opening_context = false,
+            _ => {}
+        }
+        node
+    }
+
+    fn fold_text(&mut self, text: String) -> String {
+        text.chars()
+            .map(|c| {
+                let c = match c {
+                    '"' if self.is_opening_context => '“',
+                    '"' => '”',
+                    '\'' if self.is_opening_context => '‘',
+                    '\'' => '’',
+                    c => c,
+                };
+                self.is_opening_context =
+                    c.is_whitespace() || matches!(c, '(' | '[' | '“' | '‘' | '—' | '–');
+                c
+            })
+            .collect()
+    }
+
+    fn fold_icu(&mut self, node: Icu) -> Icu {
+        let outer_arm_context = std::mem::replace(&mut self.arm_context, self.is_opening_context);
+        let node = fold_icu_children(self, node);
+        self.arm_context = outer_arm_context;
+        self.is_opening_context = false;
+        node
+    }
+
+    fn fold_icu_plural_arm(&mut self, node: IcuPluralArm) -> IcuPluralArm {
+        self.is_opening_context = self.arm_context;
+        fold_icu_plural_arm_children(self, node)
+    }
+}
+
+/// Wrap every placeholder value in Unicode directional isolates.
+///
+/// This keeps a value written in one direction, like an English username, from reordering the text
+/// around it in a message written in the other direction.
+pub struct BidiIsolation;
+
+impl BidiIsolation {
+    pub const NAME: &'static str = "bidi-isolation";
+}
+
+impl MessageTransform for BidiIsolation {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn transform(&self, document: Document) -> Document {
+        fold_document(document, &mut BidiIsolationFolder)
+    }
+}
+
+const FIRST_STRONG_ISOLATE: &str = "\u{2068}";
+const POP_DIRECTIONAL_ISOLATE: &str = "\u{2069}";
+
+struct BidiIsolationFolder;
+
+impl Fold for BidiIsolationFolder {
+    fn fold_inline_content_list(&mut self, nodes: Vec<InlineContent>) -> Vec<InlineContent> {
+        let mut result = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let node = self.fold_inline_content(node);
+            if is_placeholder_value(&node) {
+                push_text(&mut result, FIRST_STRONG_ISOLATE);
+                result.push(node);
+                push_text(&mut result, POP_DIRECTIONAL_ISOLATE);
+            } else if let InlineContent::Text(text) = &node {
+                push_text(&mut result, text);
+            } else {
+                result.push(node);
+            }
+        }
+        result
+    }
+}
+
+/// Plurals and selects are written as part of the message itself, so only the placeholders that
+/// format a value passed in at runtime need to be isolated.
+fn is_placeholder_value(node: &InlineContent) -> bool {
+    matches!(
+        node,
+        InlineContent::IcuPound
+            | InlineContent::Icu(
+                Icu::IcuVariable(_) | Icu::IcuNumber(_) | Icu::IcuDate(_) | Icu::IcuTime(_)
+            )
+    )
+}
+
+/// Add `text` to the end of `content`, merging it into the last element if that is also text.
+fn push_text(content: &mut Vec<InlineContent>, text: &str) {
+    match content.last_mut() {
+        Some(InlineContent::Text(last)) => last.push_str(text),
+        _ => content.push(InlineContent::Text(text.into())),
+    }
+}
+
+/// Insert a marker at every place a line can break within CJK text.
+///
+/// CJK text has no spaces to break at, so the markers let runtimes wrap it in narrow layouts or
+/// replace them with `<wbr>` tags. The marker is a zero-width space unless another is given.
+///
+/// Breaks are never placed before closing punctuation or small kana, or after opening brackets,
+/// since those can't start or end a line. Hangul is written with spaces between words already, so
+/// Korean text is left as it is.
+pub struct CjkWordBreaks {
+    marker: String,
+}
+
+impl CjkWordBreaks {
+    pub const NAME: &'static str = "cjk-word-breaks";
+
+    pub fn new() -> Self {
+        Self {
+            marker: ZERO_WIDTH_SPACE.into(),
+        }
+    }
+
+    pub fn with_marker(mut self, marker: impl Into<String>) -> Self {
+        self.marker = marker.into();
+        self
+    }
+}
+
+impl Default for CjkWordBreaks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageTransform for CjkWordBreaks {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn transform(&self, document: Document) -> Document {
+        fold_document(document, &mut CjkWordBreaksFolder(&self.marker))
+    }
+}
+
+const ZERO_WIDTH_SPACE: &str = "\u{200B}";
+
+/// Characters that can't start a line in Japanese and Chinese text.
+const NO_BREAK_BEFORE: &str = "ぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶーゝゞヽヾ々〻";
+/// Characters that can't end a line in Japanese and Chinese text.
+const NO_BREAK_AFTER: &str = "([{（［｛「『【〈《〔〘〖‘“";
+
+fn is_breakable_cjk_letter(c: char) -> bool {
+    is_cjk_letter(c)
+        && !matches!(c, '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}')
+}
+
+fn can_break_between(before: char, after: char) -> bool {
+    // Opening brackets can start a line like any letter, but all other punctuation closes the text
+    // before it.
+    let is_closing_punctuation = (is_cjk_punctuation(after) || after.is_ascii_punctuation())
+        && !NO_BREAK_AFTER.contains(after);
+    (is_breakable_cjk_letter(before) || is_breakable_cjk_letter(after))
+        && !before.is_whitespace()
+        && !after.is_whitespace()
+        && !NO_BREAK_AFTER.contains(before)
+        && !NO_BREAK_BEFORE.contains(after)
+        && !is_closing_punctuation
+}
+
+struct CjkWordBreaksFolder<'a>(&'a str);
+
+impl Fold for CjkWordBreaksFolder<'_> {
+    fn fold_text(&mut self, text: String) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut previous = None;
+        for c in text.chars() {
+            if previous.is_some_and(|previous| can_break_between(previous, c)) {
+                result.push_str(self.0);
+            }
+            result.push(c);
+            previous = Some(c);
+        }
+        result
+    }
+}
+
+/// Remove stray zero-width characters from visible text and normalize it to NFC.
+///
+/// Text that looks the same is then always compiled the same. This removes the markers that
+/// [CjkWordBreaks] inserts, so it has to be added to the pipeline before it.
+pub struct NormalizeUnicode;
+
+impl NormalizeUnicode {
+    pub const NAME: &'static str = "normalize-unicode";
+}
+
+impl MessageTransform for NormalizeUnicode {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn transform(&self, document: Document) -> Document {
+        fold_document(document, &mut NormalizeUnicodeFolder)
+    }
+}
+
+struct NormalizeUnicodeFolder;
+
+impl Fold for NormalizeUnicodeFolder {
+    fn fold_text(&mut self, text: String) -> String {
+        match normalize_unicode(&text) {
+            Cow::Borrowed(_) => text,
+            Cow::Owned(normalized) => normalized,
+        }
+    }
+}
+
+/// Rename the variables used by placeholders.
+///
+/// This is for when a message is bundled for a runtime that passes values with different names than
+/// the source messages use. Variables without a new name are kept as they are.
+pub struct RenamePlaceholders {
+    renames: FxHashMap<String, String>,
+}
+
+impl RenamePlaceholders {
+    pub const NAME: &'static str = "rename-placeholders";
+
+    pub fn new(renames: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            renames: renames.into_iter().collect(),
+        }
+    }
+}
+
+impl MessageTransform for RenamePlaceholders {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn transform(&self, document: Document) -> Document {
+        fold_document(document, &mut RenamePlaceholdersFolder(&self.renames))
+    }
+}
+
+struct RenamePlaceholdersFolder<'a>(&'a FxHashMap<String, String>);
+
+impl Fold for RenamePlaceholdersFolder<'_> {
+    fn fold_icu_variable(&mut self, node: IcuVariable) -> IcuVariable {
+        match self.0.get(node.name()) {
+            Some(name) => node.with_name(name),
+            None => node,
+        }
+    }
+}
+
+/// Substitute placeholders for values that are constant for the whole build.
+///
+/// Constants are values like a product name or an upload limit, which the runtime then doesn't have
+/// to format. Placeholders are only replaced where the result is the same in every locale, so a
+/// constant can still be needed at runtime, like for `{MAX_UPLOAD_MB, number}`.
+pub struct SubstituteConstants {
+    constants: HashMap<String, ConstantValue>,
+}
+
+impl SubstituteConstants {
+    pub const NAME: &'static str = "substitute-constants";
+
+    pub fn new(constants: impl IntoIterator<Item = (String, ConstantValue)>) -> Self {
+        Self {
+            constants: constants.into_iter().collect(),
+        }
+    }
+}
+
+impl MessageTransform for SubstituteConstants {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn transform(&self, document: Document) -> Document {
+        substitute_constants(document, &self.constants)
+    }
+}
+
+/// Renames for the placeholders of individual messages, keyed by message, like when a variable is
+/// renamed in the code that formats one message before its translations have been updated.
+///
+/// Bundles apply these while compiling, and generated types use the new names, so the runtime sees
+/// them right away. Exports to a separate directory can apply them to the translation files as
+/// well, like to send the updated translations back through the vendor, so that the renames can
+/// be dropped once the translations come back with the new names.
+#[derive(Clone, Debug, Default)]
+pub struct MessagePlaceholderRenames {
+    renames: FxHashMap<KeySymbol, FxHashMap<String, String>>,
+}
+
+impl MessagePlaceholderRenames {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rename the variables of the message `key`, adding to any renames it already has.
+    pub fn with_message_renames(
+        mut self,
+        key: KeySymbol,
+        renames: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.renames.entry(key).or_default().extend(renames);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renames.values().all(|renames| renames.is_empty())
+    }
+
+    /// Rename the variables in the parsed `document` of the message `key`.
+    pub fn apply(&self, key: KeySymbol, document: Document) -> Document {
+        match self.renames.get(&key) {
+            Some(renames) if !renames.is_empty() => {
+                fold_document(document, &mut RenamePlaceholdersFolder(renames))
+            }
+            _ => document,
+        }
+    }
+
+    /// Rename the variables in the raw content of a value of the message `key`, keeping the rest
+    /// of the content exactly as it was written. The placeholders are found in the parsed value,
+    /// so only real placeholder names are replaced, and never text that happens to look like one,
+    /// like a quoted `'{name}'`.
+    pub fn apply_to_raw<'a>(&self, key: KeySymbol, value: &'a MessageValue) -> Cow<'a, str> {
+        let raw = value.raw.as_str();
+        let Some(renames) = self.renames.get(&key).filter(|renames| !renames.is_empty()) else {
+            return Cow::Borrowed(raw);
+        };
+        let mut names = PlaceholderNames {
+            renames,
+            spans: vec![],
+        };
+        visit_with_mut(value.parsed(), &mut names);
+        if names.spans.is_empty() {
+            return Cow::Borrowed(raw);
+        }
+        names.spans.sort_by_key(|(span, _)| span.start);
+        names.spans.dedup_by_key(|(span, _)| span.start);
+
+        let mut result = String::with_capacity(raw.len());
+        let mut copied = 0;
+        for (span, new_name) in names.spans {
+            result.push_str(&raw[copied..span.start]);
+            result.push_str(new_name);
+            copied = span.end;
+        }
+        result.push_str(&raw[copied..]);
+        Cow::Owned(result)
+    }
+}
+
+/// Finds where each renamed variable is written in the source text of a message.
+struct PlaceholderNames<'a> {
+    renames: &'a FxHashMap<String, String>,
+    spans: Vec<(Range<usize>, &'a str)>,
+}
+
+impl Visit for PlaceholderNames<'_> {
+    fn visit_icu_variable(&mut self, node: &IcuVariable) {
+        if let (Some(new_name), Some(span)) = (self.renames.get(node.name()), node.name_span()) {
+            self.spans.push((span, new_name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::key_symbol;
+
+    use super::*;
+
+    fn rename_raw(raw: &str) -> String {
+        let key = key_symbol("MESSAGE");
+        let renames = MessagePlaceholderRenames::new().with_message_renames(
+            key,
+            [
+                ("name".to_string(), "userName".to_string()),
+                ("count".to_string(), "total".to_string()),
+            ],
+        );
+        renames
+            .apply_to_raw(key, &MessageValue::from_raw(raw))
+            .into_owned()
+    }
+
+    #[test]
+    fn test_apply_to_raw_renames_placeholders() {
+        assert_eq!(rename_raw("Hello, { name }!"), "Hello, { userName }!");
+        assert_eq!(
+            rename_raw("{count, plural, one {# by {name}} other {# by {name}}}"),
+            "{total, plural, one {# by {userName}} other {# by {userName}}}"
+        );
+        assert_eq!(
+            rename_raw("**{name}** [{name}](onClick)"),
+            "**{userName}** [{userName}](onClick)"
+        );
+    }
+
+    #[test]
+    fn test_apply_to_raw_keeps_other_text() {
+        assert_eq!(rename_raw("name and count"), "name and count");
+        assert_eq!(rename_raw("'{name}' is {other}"), "'{name}' is {other}");
+        assert_eq!(rename_raw("`{name}` and {name}"), "`{name}` and {userName}");
+        assert_eq!(rename_raw("{username}"), "{username}");
+    }
+}
@@ -1,20 +1,25 @@
 use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
 use swc_common::source_map::SmallPos;
 use swc_common::sync::Lrc;
 use swc_common::{BytePos, FileName, SourceMap, Spanned};
 use swc_core::ecma::ast::{
-    ExportDecl, ExportDefaultExpr, Expr, Id, ImportDecl, ImportSpecifier, Lit, Module, ObjectLit,
+    BinExpr, BinaryOp, ExportDecl, ExportDefaultExpr, Expr, Id, ImportDecl, ImportSpecifier, Lit,
+    Module, ObjectLit, Tpl,
 };
 use swc_core::ecma::parser::{lexer::Lexer, PResult, Parser, StringInput, Syntax};
 use swc_core::ecma::visit::{noop_visit_type, Visit, VisitWith};
 use unescape_zero_copy::unescape_default;
 
 use intl_database_core::{
-    MessageMeta, MessageSourceError, MessageSourceResult, RawMessageDefinition, RawPosition,
-    SourceFileMeta,
+    MarkdownStrictness, MessageMeta, MessageSourceError, MessageSourceResult, MessageValue,
+    MessageVariableType, RawMessageDefinition, RawMessageError, RawPosition, SourceFileMeta,
+    StringPart, StringPartList,
 };
 use intl_message_utils::RUNTIME_PACKAGE_NAME;
 
+use crate::fragments::{expand_fragments, Fragment};
+
 pub fn parse_message_definitions_file(
     file_name: &str,
     source: &str,
@@ -47,9 +52,10 @@ pub fn extract_message_definitions(
 /// A Visitor to extract message definitions from a source AST.
 pub struct MessageDefinitionsExtractor {
     pub message_definitions: Vec<RawMessageDefinition>,
-    pub failed_definitions: Vec<MessageSourceError>,
+    pub failed_definitions: Vec<RawMessageError>,
     pub root_meta: SourceFileMeta,
     define_messages_id: Option<Id>,
+    fragments: HashMap<String, Fragment>,
     source_map: Lrc<SourceMap>,
 }
 
@@ -60,6 +66,7 @@ impl MessageDefinitionsExtractor {
             message_definitions: vec![],
             failed_definitions: vec![],
             root_meta: SourceFileMeta::new(source_file_path),
+            fragments: HashMap::new(),
             source_map,
         }
     }
@@ -69,6 +76,12 @@ impl MessageDefinitionsExtractor {
     fn parse_definitions_object(&mut self, object: &ObjectLit) {
         for property in object.props.iter() {
             let Some(keyvalue) = property.as_prop().and_then(|prop| prop.as_key_value()) else {
+                self.failed_definitions.push(RawMessageError::new(
+                    self.position_of(property.span_lo()),
+                    MessageSourceError::DefinitionRestrictionViolated(
+                        "Message definitions must be written as `key: value` properties. Spreads, shorthand properties, and methods can't be extracted".into(),
+                    ),
+                ));
                 continue;
             };
             let name = if let Some(name) = keyvalue.key.as_ident() {
@@ -76,35 +89,39 @@ impl MessageDefinitionsExtractor {
             } else if let Some(name) = keyvalue.key.as_str() {
                 &name.value
             } else {
+                self.failed_definitions.push(RawMessageError::new(
+                    self.position_of(keyvalue.key.span_lo()),
+                    MessageSourceError::DefinitionRestrictionViolated(
+                        "Message keys must be identifiers or string literals. Computed and numeric keys can't be extracted".into(),
+                    ),
+                ));
                 continue;
             };
 
             let parse_result = if let Some(object) = keyvalue.value.as_object() {
-                self.parse_complete_definition(&name, &object)
-            } else if let Some(lit @ Lit::Str(string)) = keyvalue.value.as_lit() {
-                self.parse_oneline_definition(&name, &string.value, lit.span_lo())
-            } else if let Some(template) = keyvalue.value.as_tpl() {
-                // With JS, you can write static strings as template strings to
-                // avoid needing to escape different quotes, like:
-                //     SOME_STRING: `"this" is valid, isn't it?`
-                // We want to support that syntax, but we can't allow templates
-                // that have embedded expressions or multiple elements.
-                let string_value = template.quasis.get(0).map(|expr| &expr.raw);
-                let is_static = template.quasis.len() == 1 && template.exprs.len() == 0;
-
-                match string_value {
-                    Some(string) if is_static => self.parse_oneline_definition(&name, &string, template.span_lo()),
-                    _ => Err(MessageSourceError::DefinitionRestrictionViolated("Encountered non-static template string. Interpolations are currently invalid".into()))
-                }
+                self.parse_complete_definition(name, object)
+            } else if let Some((value, parts)) = self.parse_string_parts(&keyvalue.value) {
+                // Along with plain strings, static values can be written as templates to avoid
+                // escaping quotes, like `"this" is valid, isn't it?`, or split across multiple
+                // strings joined with `+`, which formatters like prettier do for long lines.
+                Ok(self.create_definition(name, value, parts, self.clone_meta()))
+            } else if is_template(&keyvalue.value) {
+                Err(MessageSourceError::DefinitionRestrictionViolated(
+                    "Encountered non-static template string. Interpolations are currently invalid"
+                        .into(),
+                ))
             } else {
                 Err(MessageSourceError::DefinitionRestrictionViolated(
                     "Encountered an unknown message definition structure".into(),
                 ))
             };
 
-            match parse_result {
+            match parse_result.and_then(|definition| self.expand_definition_fragments(definition)) {
                 Ok(definition) => self.message_definitions.push(definition),
-                Err(error) => self.failed_definitions.push(error),
+                Err(error) => self.failed_definitions.push(RawMessageError::new(
+                    self.position_of(keyvalue.value.span_lo()),
+                    error,
+                )),
             }
         }
     }
@@ -116,9 +133,8 @@ impl MessageDefinitionsExtractor {
         key: &str,
         object: &ObjectLit,
     ) -> MessageSourceResult<RawMessageDefinition> {
-        let mut default_value: Option<String> = None;
+        let mut default_value: Option<(String, StringPartList)> = None;
         let mut local_meta = self.clone_meta();
-        let mut message_loc = BytePos::default();
 
         for property in object.props.iter() {
             let Some(keyvalue) = property.as_prop().and_then(|prop| prop.as_key_value()) else {
@@ -129,11 +145,7 @@ impl MessageDefinitionsExtractor {
             };
 
             match name.sym.as_str() {
-                "message" => {
-                    message_loc = keyvalue.value.span_lo();
-                    self.parse_string_value(keyvalue.value.borrow())
-                        .map(|value| default_value = Some(value));
-                }
+                "message" => default_value = self.parse_string_parts(keyvalue.value.borrow()),
                 name => {
                     self.parse_message_meta_property(name, keyvalue.value.borrow(), &mut local_meta)
                 }
@@ -142,40 +154,57 @@ impl MessageDefinitionsExtractor {
 
         // If no `message` was provided in the object definition, it's invalid
         // and can't be used.
-        let Some(default_value) = default_value else {
+        let Some((value, parts)) = default_value else {
             return Err(MessageSourceError::NoMessageValue(key.into()));
         };
 
-        let loc = self.source_map.lookup_char_pos(message_loc);
-
-        Ok(RawMessageDefinition::new(
-            key.into(),
-            RawPosition {
-                line: loc.line as u32,
-                col: loc.col.to_u32(),
-            },
-            default_value,
-            local_meta,
-        ))
+        Ok(self.create_definition(key, value, parts, local_meta))
     }
 
-    /// Parse a message definition using the shorthand `name: "value"`
-    fn parse_oneline_definition(
+    /// Create a definition of `key`, positioned at the first of the string literals in `parts`
+    /// that `value` was joined from.
+    fn create_definition(
         &self,
         key: &str,
-        value: &str,
-        pos: BytePos,
+        value: String,
+        parts: StringPartList,
+        meta: MessageMeta,
+    ) -> RawMessageDefinition {
+        // SAFETY: Every static string has at least one literal.
+        let position = parts.first().unwrap().position;
+        let mut definition = RawMessageDefinition::new(key.into(), position, value, meta);
+        if parts.len() > 1 {
+            definition.value = definition.value.with_string_parts(parts);
+        }
+        definition
+    }
+
+    /// Expand any fragments that the value of `definition` includes, keeping track of where they
+    /// were expanded so that positions in the value can be mapped back to the fragments.
+    fn expand_definition_fragments(
+        &self,
+        mut definition: RawMessageDefinition,
     ) -> MessageSourceResult<RawMessageDefinition> {
+        match expand_fragments(&definition.value.raw, &self.fragments) {
+            Ok(None) => {}
+            // Offsets in the expanded value no longer line up with the literals it was joined
+            // from, so positions in it are only mapped through the expansions.
+            Ok(Some((value, expansions))) => {
+                definition.value =
+                    MessageValue::from_text(value.into()).with_fragment_expansions(expansions);
+            }
+            Err(name) => return Err(MessageSourceError::UnknownFragment(definition.name, name)),
+        }
+        Ok(definition)
+    }
+
+    /// Resolve the line and column of the given position in the source file.
+    fn position_of(&self, pos: BytePos) -> RawPosition {
         let loc = self.source_map.lookup_char_pos(pos);
-        Ok(RawMessageDefinition::new(
-            key.into(),
-            RawPosition {
-                line: loc.line as u32,
-                col: loc.col.to_u32(),
-            },
-            self.apply_string_escapes(value),
-            self.clone_meta(),
-        ))
+        RawPosition {
+            line: loc.line as u32,
+            col: loc.col.to_u32(),
+        }
     }
 
     /// Return a clone of the root meta, or a new object with the default
@@ -219,10 +248,45 @@ impl MessageDefinitionsExtractor {
             "description" => self
                 .parse_string_value(value)
                 .map(|value| self.root_meta.description = Some(value)),
+            "sourceLocale" => self
+                .parse_string_value(value)
+                .map(|value| self.root_meta.source_locale = Some(value)),
+            "fragments" => value.as_object().map(|object| self.parse_fragments(object)),
+            "strictness" => self
+                .parse_strictness_value(value)
+                .map(|value| self.root_meta.strictness = value),
             _ => None,
         };
     }
 
+    /// Parses the given `object` as the fragments that messages in this file can include, storing
+    /// them in both `self.fragments` and `self.root_meta`.
+    fn parse_fragments(&mut self, object: &ObjectLit) {
+        for property in object.props.iter() {
+            let Some(keyvalue) = property.as_prop().and_then(|prop| prop.as_key_value()) else {
+                continue;
+            };
+            let name = if let Some(name) = keyvalue.key.as_ident() {
+                name.sym.to_string()
+            } else if let Some(name) = keyvalue.key.as_str() {
+                name.value.to_string()
+            } else {
+                continue;
+            };
+            let Some(value) = self.parse_string_value(&keyvalue.value) else {
+                self.failed_definitions.push(RawMessageError::new(
+                    self.position_of(keyvalue.value.span_lo()),
+                    MessageSourceError::InvalidSourceFileMeta,
+                ));
+                continue;
+            };
+
+            self.root_meta.fragments.insert(name.clone(), value.clone());
+            let position = self.position_of(keyvalue.value.span_lo());
+            self.fragments.insert(name, Fragment { value, position });
+        }
+    }
+
     /// Interpret a given name/value pair to see if it represents a MessageMeta
     /// property. If it does, apply the value to the corresponding field in
     /// `target`. Otherwise, nothing is done.
@@ -240,10 +304,41 @@ impl MessageDefinitionsExtractor {
             "description" => self
                 .parse_string_value(value)
                 .map(|value| target.description = Some(value)),
+            "strictness" => self
+                .parse_strictness_value(value)
+                .map(|value| target.strictness = value),
+            "variables" => value
+                .as_object()
+                .map(|object| self.parse_variable_types(object, target)),
             _ => None,
         };
     }
 
+    /// Parses the given `object` as the declared types of a message's variables, like
+    /// `{count: 'number'}`, storing them in `target`. Entries that don't name a known kind of
+    /// variable are ignored, the same as other meta properties with invalid values.
+    fn parse_variable_types(&self, object: &ObjectLit, target: &mut MessageMeta) {
+        for property in object.props.iter() {
+            let Some(keyvalue) = property.as_prop().and_then(|prop| prop.as_key_value()) else {
+                continue;
+            };
+            let name = if let Some(name) = keyvalue.key.as_ident() {
+                name.sym.to_string()
+            } else if let Some(name) = keyvalue.key.as_str() {
+                name.value.to_string()
+            } else {
+                continue;
+            };
+            let Some(kind) = self
+                .parse_string_value(&keyvalue.value)
+                .and_then(|kind| MessageVariableType::from_kind_name(&kind))
+            else {
+                continue;
+            };
+            target.variables.insert(name, kind);
+        }
+    }
+
     /// If the given expression is a boolean literal, it is interpreted into an
     /// actual boolean value. Any other expression will return None.
     fn parse_boolean_value(&self, expr: &Expr) -> Option<bool> {
@@ -253,13 +348,65 @@ impl MessageDefinitionsExtractor {
         }
     }
 
-    /// If the given expression is a string literal, the value of that literal
-    /// is returned. Any other expression will return None.
+    /// If the given expression is a static string naming a markdown strictness, like
+    /// `'inline-only'`, it is interpreted into that strictness. Any other expression will return
+    /// None.
+    fn parse_strictness_value(&self, expr: &Expr) -> Option<MarkdownStrictness> {
+        self.parse_string_value(expr)
+            .and_then(|name| MarkdownStrictness::parse(&name))
+    }
+
+    /// If the given expression is a static string, the value of that string is
+    /// returned. Static strings are string literals, templates without any
+    /// interpolations, `String.raw` templates, and concatenations of any of
+    /// those using `+`. Any other expression will return None.
     fn parse_string_value(&self, expr: &Expr) -> Option<String> {
-        match expr.as_lit() {
-            Some(Lit::Str(string)) => Some(self.apply_string_escapes(&string.value).to_string()),
-            _ => None,
-        }
+        self.parse_string_parts(expr).map(|(value, _)| value)
+    }
+
+    /// Same as [`Self::parse_string_value`], but also returning the position of each string
+    /// literal that the value was joined from.
+    fn parse_string_parts(&self, expr: &Expr) -> Option<(String, StringPartList)> {
+        let mut value = String::new();
+        let mut parts = StringPartList::default();
+        self.collect_string_parts(expr, &mut value, &mut parts)?;
+        Some((value, parts))
+    }
+
+    /// Append the value of each string literal in `expr` to `value`, recording where each one
+    /// starts in `parts`.
+    fn collect_string_parts(
+        &self,
+        expr: &Expr,
+        value: &mut String,
+        parts: &mut StringPartList,
+    ) -> Option<()> {
+        let literal = match expr {
+            Expr::Lit(Lit::Str(string)) => self.apply_string_escapes(&string.value),
+            Expr::Tpl(template) => self.apply_string_escapes(static_template_value(template)?),
+            // `String.raw` returns the template exactly as written, without
+            // applying any escapes.
+            Expr::TaggedTpl(tagged) if is_string_raw(&tagged.tag) => {
+                Cow::from(static_template_value(&tagged.tpl)?)
+            }
+            Expr::Bin(BinExpr {
+                op: BinaryOp::Add,
+                left,
+                right,
+                ..
+            }) => {
+                self.collect_string_parts(left, value, parts)?;
+                return self.collect_string_parts(right, value, parts);
+            }
+            Expr::Paren(paren) => return self.collect_string_parts(&paren.expr, value, parts),
+            _ => return None,
+        };
+        parts.push(StringPart {
+            value_offset: value.len(),
+            position: self.position_of(expr.span_lo()),
+        });
+        value.push_str(&literal);
+        Some(())
     }
 
     /// Apply literal escape sequences like `\n` from the string value.
@@ -268,6 +415,40 @@ impl MessageDefinitionsExtractor {
     }
 }
 
+/// Returns the raw content of the given template if it has no interpolations.
+fn static_template_value(template: &Tpl) -> Option<&str> {
+    match template.quasis.as_slice() {
+        [quasi] if template.exprs.is_empty() => Some(quasi.raw.as_str()),
+        _ => None,
+    }
+}
+
+/// Returns true if `tag` is the builtin `String.raw` template tag.
+fn is_string_raw(tag: &Expr) -> bool {
+    let Some(member) = tag.as_member() else {
+        return false;
+    };
+    member
+        .obj
+        .as_ident()
+        .is_some_and(|obj| obj.sym.as_str() == "String")
+        && member
+            .prop
+            .as_ident()
+            .is_some_and(|prop| prop.sym.as_str() == "raw")
+}
+
+/// Returns true if the expression is any kind of template string, static or
+/// not, including tagged templates.
+fn is_template(expr: &Expr) -> bool {
+    match expr {
+        Expr::Tpl(_) | Expr::TaggedTpl(_) => true,
+        Expr::Bin(BinExpr { left, right, .. }) => is_template(left) || is_template(right),
+        Expr::Paren(paren) => is_template(&paren.expr),
+        _ => false,
+    }
+}
+
 impl Visit for MessageDefinitionsExtractor {
     noop_visit_type!();
 
@@ -291,8 +472,10 @@ impl Visit for MessageDefinitionsExtractor {
             } else {
                 // We've found the meta and determined it didn't have an
                 // initializer, so we don't need to continue iterating.
-                // TODO: Use this error.
-                drop(MessageSourceError::InvalidSourceFileMeta);
+                self.failed_definitions.push(RawMessageError::new(
+                    self.position_of(decl.span_lo()),
+                    MessageSourceError::InvalidSourceFileMeta,
+                ));
                 break;
             }
         }
@@ -347,9 +530,9 @@ impl Visit for MessageDefinitionsExtractor {
 
 #[cfg(test)]
 mod tests {
-    use intl_database_core::key_symbol;
+    use intl_database_core::{key_symbol, MarkdownStrictness, MessageVariableType};
 
-    use super::parse_message_definitions_file;
+    use super::{extract_message_definitions, parse_message_definitions_file};
 
     #[test]
     fn test_parsing() {
@@ -378,4 +561,221 @@ mod tests {
 
         let file_symbol = key_symbol("testing.js");
     }
+
+    #[test]
+    fn test_static_string_forms() {
+        let source = format!(
+            r#"import {{defineMessages}} from '{}';
+
+export default defineMessages({{
+  TEMPLATED: `"quoted" template`,
+  CONCATENATED: 'first ' + "second " +
+    `third`,
+  RAW: String.raw`keeps \n as written`,
+  FULL: {{
+    message: ('a long message ' + 'split in two'),
+  }},
+  INVALID: 'prefix ' + `${{interpolated}}`,
+}});
+"#,
+            intl_message_utils::RUNTIME_PACKAGE_NAME
+        );
+        let (source_map, module) = parse_message_definitions_file("testing.js", &source)
+            .expect("failed to parse source code");
+        let extractor = extract_message_definitions("testing.js", source_map, module);
+
+        let definitions: Vec<_> = extractor
+            .message_definitions
+            .iter()
+            .map(|definition| {
+                (
+                    definition.name.as_str(),
+                    definition.value.raw.as_str(),
+                    definition.position.line,
+                    definition.position.col,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("TEMPLATED", "\"quoted\" template", 4, 13),
+                ("CONCATENATED", "first second third", 5, 16),
+                ("RAW", "keeps \\n as written", 7, 7),
+                ("FULL", "a long message split in two", 9, 14),
+            ],
+            definitions
+        );
+        assert_eq!(1, extractor.failed_definitions.len());
+
+        let parts: Vec<_> = extractor.message_definitions[1]
+            .value
+            .string_parts
+            .as_ref()
+            .expect("concatenated strings should have parts")
+            .iter()
+            .map(|part| (part.value_offset, part.position.line, part.position.col))
+            .collect();
+        assert_eq!(vec![(0, 5, 16), (6, 5, 27), (13, 6, 4)], parts);
+        // Values written as a single literal don't need to be mapped through parts.
+        assert!(extractor.message_definitions[0]
+            .value
+            .string_parts
+            .is_none());
+    }
+
+    #[test]
+    fn test_failed_definitions() {
+        let source = format!(
+            r#"import {{defineMessages}} from '{}';
+
+export default defineMessages({{
+  VALID: 'valid',
+  [COMPUTED]: 'computed key',
+  ...spread,
+  DYNAMIC: someVariable,
+  NO_MESSAGE: {{ description: 'missing' }},
+}});
+"#,
+            intl_message_utils::RUNTIME_PACKAGE_NAME
+        );
+        let (source_map, module) = parse_message_definitions_file("testing.js", &source)
+            .expect("failed to parse source code");
+        let extractor = extract_message_definitions("testing.js", source_map, module);
+
+        assert_eq!(1, extractor.message_definitions.len());
+        let positions: Vec<_> = extractor
+            .failed_definitions
+            .iter()
+            .map(|error| (error.position.line, error.position.col))
+            .collect();
+        assert_eq!(vec![(5, 2), (6, 2), (7, 11), (8, 14)], positions);
+    }
+
+    #[test]
+    fn test_fragments() {
+        let source = format!(
+            r#"import {{defineMessages}} from '{}';
+
+export const meta = {{
+  fragments: {{
+    NITRO: 'Discord Nitro',
+    PLAN: '{{plan, select, yearly {{yearly}} other {{monthly}}}}',
+  }},
+}};
+
+export default defineMessages({{
+  UPSELL: 'Get more with {{>NITRO}}, now {{>PLAN}}!',
+  PLAIN: 'No {{>}} fragments here {{> NITRO}}',
+  UNKNOWN: 'Get {{>MISSING}}',
+}});
+"#,
+            intl_message_utils::RUNTIME_PACKAGE_NAME
+        );
+        let (source_map, module) = parse_message_definitions_file("testing.js", &source)
+            .expect("failed to parse source code");
+        let extractor = extract_message_definitions("testing.js", source_map, module);
+
+        assert_eq!(2, extractor.root_meta.fragments.len());
+        let upsell = &extractor.message_definitions[0].value;
+        assert_eq!(
+            "Get more with Discord Nitro, now {plan, select, yearly {yearly} other {monthly}}!",
+            upsell.raw.as_str()
+        );
+        let expansions: Vec<_> = upsell
+            .fragment_expansions
+            .as_ref()
+            .expect("fragments should be expanded")
+            .iter()
+            .map(|expansion| {
+                (
+                    expansion.name.as_str(),
+                    expansion.expanded_offset,
+                    expansion.position.line,
+                    expansion.position.col,
+                )
+            })
+            .collect();
+        assert_eq!(vec![("NITRO", 14, 5, 11), ("PLAN", 33, 6, 10)], expansions);
+
+        let plain = &extractor.message_definitions[1].value;
+        assert_eq!("No {>} fragments here {> NITRO}", plain.raw.as_str());
+        assert!(plain.fragment_expansions.is_none());
+
+        assert_eq!(1, extractor.failed_definitions.len());
+    }
+
+    #[test]
+    fn test_strictness() {
+        let source = format!(
+            r#"import {{defineMessages}} from '{}';
+
+export const meta = {{
+  strictness: 'inline-only',
+}};
+
+export default defineMessages({{
+  INHERITED: 'from the file',
+  PLAIN: {{ message: 'plain text only', strictness: 'plain' }},
+  UNKNOWN: {{ message: 'unknown strictness', strictness: 'none' }},
+}});
+"#,
+            intl_message_utils::RUNTIME_PACKAGE_NAME
+        );
+        let (source_map, module) = parse_message_definitions_file("testing.js", &source)
+            .expect("failed to parse source code");
+        let extractor = extract_message_definitions("testing.js", source_map, module);
+
+        assert_eq!(
+            MarkdownStrictness::InlineOnly,
+            extractor.root_meta.strictness
+        );
+        let strictness: Vec<_> = extractor
+            .message_definitions
+            .iter()
+            .map(|definition| definition.meta.strictness)
+            .collect();
+        assert_eq!(
+            vec![
+                MarkdownStrictness::InlineOnly,
+                MarkdownStrictness::Plain,
+                MarkdownStrictness::InlineOnly,
+            ],
+            strictness
+        );
+    }
+
+    #[test]
+    fn test_variable_types() {
+        let source = format!(
+            r#"import {{defineMessages}} from '{}';
+
+export default defineMessages({{
+  INFERRED: 'Sent {{count}} times',
+  DECLARED: {{
+    message: 'Sent {{count}} times on {{day}}',
+    variables: {{ count: 'number', 'day': 'date', other: 'unknown' }},
+  }},
+}});
+"#,
+            intl_message_utils::RUNTIME_PACKAGE_NAME
+        );
+        let (source_map, module) = parse_message_definitions_file("testing.js", &source)
+            .expect("failed to parse source code");
+        let extractor = extract_message_definitions("testing.js", source_map, module);
+
+        assert!(extractor.message_definitions[0].meta.variables.is_empty());
+        let declared: Vec<_> = extractor.message_definitions[1]
+            .meta
+            .variables
+            .iter()
+            .map(|(name, kind)| (name.as_str(), kind.clone()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("count", MessageVariableType::Number(None)),
+                ("day", MessageVariableType::Date),
+            ],
+            declared
+        );
+    }
 }
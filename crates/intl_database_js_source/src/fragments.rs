@@ -0,0 +1,71 @@
+//! Fragments are named snippets of message content defined in the `fragments` meta of a
+//! definitions file, like a product name or a legal phrase, that messages in the same file can
+//! include with `{>NAME}`:
+//!
+//! ```js
+//! export const meta = {
+//!   fragments: {
+//!     NITRO: 'Discord Nitro',
+//!   },
+//! };
+//!
+//! export default defineMessages({
+//!   UPSELL: 'Get more with {>NITRO}!',
+//! });
+//! ```
+//!
+//! Unlike message references, fragments are expanded as text when the file is extracted, so the
+//! rest of the system only ever sees the expanded value. Fragments can contain any message syntax,
+//! but can't include other fragments.
+use std::collections::HashMap;
+
+use intl_database_core::{FragmentExpansion, FragmentExpansionList, RawPosition};
+
+/// The value of a fragment and the position where that value is written.
+pub(crate) struct Fragment {
+    pub value: String,
+    pub position: RawPosition,
+}
+
+/// Replace every `{>NAME}` in `value` with the content of the fragment `NAME`. Returns None if
+/// `value` doesn't include any fragments, or the name of the first fragment that isn't defined.
+pub(crate) fn expand_fragments(
+    value: &str,
+    fragments: &HashMap<String, Fragment>,
+) -> Result<Option<(String, FragmentExpansionList)>, String> {
+    let mut expanded = String::new();
+    let mut expansions = FragmentExpansionList::default();
+    let mut rest = value;
+    while let Some(start) = rest.find("{>") {
+        let name_start = start + 2;
+        let name_len = rest[name_start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len() - name_start);
+        let name = &rest[name_start..name_start + name_len];
+        if name.is_empty() || !rest[name_start + name_len..].starts_with('}') {
+            // Not a fragment reference, so it's left for the message parser to handle.
+            expanded.push_str(&rest[..name_start]);
+            rest = &rest[name_start..];
+            continue;
+        }
+        let Some(fragment) = fragments.get(name) else {
+            return Err(name.to_string());
+        };
+
+        expanded.push_str(&rest[..start]);
+        expansions.push(FragmentExpansion {
+            name: name.to_string(),
+            expanded_offset: expanded.len(),
+            expanded_len: fragment.value.len(),
+            position: fragment.position,
+        });
+        expanded.push_str(&fragment.value);
+        rest = &rest[name_start + name_len + 1..];
+    }
+
+    if expansions.is_empty() {
+        return Ok(None);
+    }
+    expanded.push_str(rest);
+    Ok(Some((expanded, expansions)))
+}
@@ -1,11 +1,14 @@
+use std::sync::Arc;
+
 use intl_database_core::{
-    KeySymbol, MessageSourceError, MessageSourceResult, MessageTranslationSource,
-    RawMessageTranslation, SourceFileKind,
+    KeySymbol, MessageDefinitionSource, MessageSourceResult, MessageTranslationSource,
+    RawMessageDefinition, RawMessageTranslation, SourceFileInsertionData, SourceFileKind,
+    SourceFileMeta,
 };
 
-use crate::deserialize::Translations;
+use crate::scanner::MessagesScanner;
 
-mod deserialize;
+mod scanner;
 
 pub struct JsonMessageSource;
 
@@ -14,14 +17,69 @@ impl MessageTranslationSource for JsonMessageSource {
         file_name.split('.').next().unwrap_or("en-US").into()
     }
 
+    /// `.jsona` files are JSON with comments, and their comments are kept with the translations.
+    /// Comments in any other file are syntax errors, like in plain JSON.
     fn extract_translations(
         self,
-        _file_name: KeySymbol,
+        file_name: KeySymbol,
+        content: Arc<str>,
+    ) -> MessageSourceResult<impl Iterator<Item = RawMessageTranslation>> {
+        Ok(
+            MessagesScanner::with_shared_source(&content, SourceFileKind::Translation)
+                .with_comments(file_name.ends_with(".jsona"))
+                .scan_translations()?
+                .into_iter(),
+        )
+    }
+}
+
+/// Definitions source for the JSON output of `formatjs extract`.
+///
+/// That output maps each message id to an object containing its `defaultMessage` and `description`.
+/// This lets messages from an existing react-intl codebase live in the same database as regular
+/// definitions while it is migrated.
+pub struct FormatJsMessageSource;
+
+impl MessageDefinitionSource for FormatJsMessageSource {
+    fn extract_definitions(
+        self,
+        file_name: KeySymbol,
         content: &str,
+    ) -> MessageSourceResult<SourceFileInsertionData<impl Iterator<Item = RawMessageDefinition>>>
+    {
+        let meta = SourceFileMeta::new(&file_name);
+        let definitions = MessagesScanner::new(content, SourceFileKind::Definition)
+            .scan_formatjs_definitions(&meta)?;
+        Ok(SourceFileInsertionData {
+            meta,
+            definitions: definitions.into_iter(),
+            errors: vec![],
+        })
+    }
+}
+
+/// Translations source for compiled keyless JSON bundles (`.compiled.messages.*` files).
+///
+/// This recovers message content from the compiled output when the original translations are no
+/// longer available. Values are decompiled back into message source text and marked as
+/// machine-recovered.
+///
+/// Bundles only contain hashed message keys, so the names of the extracted translations are those
+/// hashes, and must be resolved to real message keys by the caller.
+pub struct CompiledBundleSource;
+
+impl MessageTranslationSource for CompiledBundleSource {
+    fn get_locale_from_file_name(&self, file_name: &str) -> KeySymbol {
+        file_name.split('.').next().unwrap_or("en-US").into()
+    }
+
+    fn extract_translations(
+        self,
+        _file_name: KeySymbol,
+        content: Arc<str>,
     ) -> MessageSourceResult<impl Iterator<Item = RawMessageTranslation>> {
-        let translations: Translations = serde_json::from_str(content).map_err(|error| {
-            MessageSourceError::ParseError(SourceFileKind::Translation, error.to_string())
-        })?;
-        Ok(translations.into_iter())
+        Ok(MessagesScanner::new(&content, SourceFileKind::Translation)
+            .scan_compiled_bundle()?
+            .into_iter())
     }
 }
@@ -0,0 +1,869 @@
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::ops::Range;
+use std::sync::Arc;
+
+use intl_database_core::{
+    key_symbol, MessageMeta, MessageSourceError, MessageSourceResult, RawMessageDefinition,
+    RawMessageTranslation, RawPosition, ReviewState, SourceFileKind, SourceFileMeta, SourceOffset,
+    SourceOffsetList, TextPointer, ValueComments,
+};
+use intl_markdown::decompile_format_js;
+use memchr::memchr2;
+
+/// A minimal scanner for JSON message files. Translation files are always a single flat JSON
+/// object mapping message keys to string values, like `{"key": "value"}`, while FormatJS
+/// extraction files map each key to an object with a `defaultMessage` and optional `description`.
+///
+/// Using a generic JSON parser would lose track of where each value is in the file, and would
+/// decode escape sequences without remembering them. This scanner records both, so that positions
+/// in the decoded values (like diagnostic spans) can be mapped back to the exact characters in the
+/// original file, even after escapes like `\n` or `\u00e9`.
+///
+/// When scanning with [`MessagesScanner::with_comments`], like for `.messages.jsona` files,
+/// `//` and `/* */` comments are allowed anywhere whitespace is. Comments in translation files are
+/// kept with the entry they belong to, so that they can be written back when the file is
+/// exported. See [`ValueComments`] for how they are attached.
+pub(crate) struct MessagesScanner<'a> {
+    source: &'a str,
+    /// Shared allocation of `source`, when available. Values that don't need any decoding are
+    /// kept as pointers into it rather than being copied.
+    shared_source: Option<&'a Arc<str>>,
+    /// The kind of file being scanned, used when reporting errors.
+    kind: SourceFileKind,
+    position: usize,
+    /// One-based line number of the current position.
+    line: u32,
+    /// Byte offset where the current line starts.
+    line_start: usize,
+    /// Byte offset and column of the last position reported on the current line, so that columns
+    /// are counted from there rather than from the start of the line every time. Minified files
+    /// are a single line, where counting from the start would be quadratic.
+    last_column: Cell<(usize, u32)>,
+    /// Whether comments are allowed where whitespace is.
+    allow_comments: bool,
+    /// Every comment skipped since the last call to [`MessagesScanner::take_comments`].
+    comments: Vec<ScannedComment>,
+    /// Byte offset of the key of the entry that [`MessagesScanner::scan_object`] is scanning.
+    entry_start: usize,
+}
+
+/// A comment skipped by the scanner.
+struct ScannedComment {
+    range: Range<usize>,
+    /// True when nothing but spaces and other comments are between the token before this comment
+    /// and the comment itself, meaning the comment is on the same line as that token.
+    after_token: bool,
+}
+
+impl<'a> MessagesScanner<'a> {
+    pub(crate) fn new(source: &'a str, kind: SourceFileKind) -> Self {
+        Self {
+            source,
+            shared_source: None,
+            kind,
+            position: 0,
+            line: 1,
+            line_start: 0,
+            last_column: Cell::new((0, 0)),
+            allow_comments: false,
+            comments: vec![],
+            entry_start: 0,
+        }
+    }
+
+    /// Allow `//` and `/* */` comments anywhere whitespace is. Plain JSON doesn't allow comments,
+    /// so they are syntax errors otherwise.
+    pub(crate) fn with_comments(mut self, allow_comments: bool) -> Self {
+        self.allow_comments = allow_comments;
+        self
+    }
+
+    /// Create a scanner over shared content, letting scanned values point directly into it.
+    pub(crate) fn with_shared_source(source: &'a Arc<str>, kind: SourceFileKind) -> Self {
+        Self {
+            shared_source: Some(source),
+            ..Self::new(source, kind)
+        }
+    }
+
+    /// Scan a translations file, where every entry is a message key mapped to its translated value.
+    /// Values may also be an object like `{"message": "...", "reviewState": "reviewed"}` when they
+    /// carry extra information about the translation. `"machineTranslated": true` is also accepted
+    /// in place of the `machine` review state, and `"machineRecovered": true` marks values that
+    /// were recovered from a compiled bundle. Any other fields on the object are ignored.
+    pub(crate) fn scan_translations(mut self) -> MessageSourceResult<Vec<RawMessageTranslation>> {
+        let mut entries: Vec<RawMessageTranslation> = vec![];
+        self.skip_whitespace();
+        self.scan_object(|scanner, key| {
+            let mut before_key = scanner.take_comments();
+            let key_index =
+                before_key.partition_point(|comment| comment.range.start < scanner.entry_start);
+            let mut inline = before_key.split_off(key_index);
+            // Comments on the same line as the end of the previous entry belong to it, and the
+            // rest to the entry that follows them.
+            let mut leading = &before_key[..];
+            if let Some(previous) = entries.last_mut() {
+                let same_line = before_key.partition_point(|comment| comment.after_token);
+                scanner
+                    .add_trailing_comments(&mut previous.value.comments, &before_key[..same_line]);
+                leading = &before_key[same_line..];
+            }
+            let leading = scanner.join_comments(leading);
+
+            let name = key_symbol(&key);
+            let mut review_state = None;
+            let mut machine_translated = false;
+            let mut machine_recovered = false;
+            let (position, value, offsets) = match scanner.peek() {
+                Some(b'{') => {
+                    let mut message = None;
+                    scanner.scan_object(|scanner, field| {
+                        match field.as_ref() {
+                            "message" => {
+                                let position = scanner.current_position();
+                                let (value, offsets) = scanner.scan_string()?;
+                                message = Some((position, value, offsets));
+                            }
+                            "reviewState" => review_state = Some(scanner.scan_review_state(&key)?),
+                            "machineTranslated" => machine_translated = scanner.scan_bool()?,
+                            "machineRecovered" => machine_recovered = scanner.scan_bool()?,
+                            _ => scanner.skip_value()?,
+                        }
+                        Ok(())
+                    })?;
+                    message.ok_or(MessageSourceError::NoMessageValue(name))?
+                }
+                _ => {
+                    let position = scanner.current_position();
+                    let (value, offsets) = scanner.scan_string()?;
+                    (position, value, offsets)
+                }
+            };
+
+            // Offsets are kept even when empty, since that still tells consumers that positions
+            // within the value map directly to the file.
+            let review_state = review_state.unwrap_or(match machine_translated {
+                true => ReviewState::Machine,
+                false => ReviewState::New,
+            });
+            // Comments inside of a value object are kept with the ones before the value.
+            inline.extend(scanner.take_comments());
+            let comments = ValueComments {
+                leading,
+                inline: scanner.join_comments(&inline),
+                trailing: None,
+            };
+            let comments = (comments != ValueComments::default()).then(|| Box::new(comments));
+
+            let value = scanner.text_pointer(value);
+            let mut translation = RawMessageTranslation::new(name, position, value);
+            translation.value = translation
+                .value
+                .with_source_offsets(offsets)
+                .with_review_state(review_state)
+                .with_machine_recovered(machine_recovered)
+                .with_comments(comments);
+            entries.push(translation);
+            Ok(())
+        })?;
+        // Every comment after the last entry is kept with it, even those on later lines.
+        self.skip_whitespace();
+        let comments = self.take_comments();
+        if let Some(last) = entries.last_mut() {
+            self.add_trailing_comments(&mut last.value.comments, &comments);
+        }
+        self.finish(entries)
+    }
+
+    /// Scan the output of `formatjs extract`, where every entry is a message key mapped to an
+    /// object like `{"defaultMessage": "...", "description": "..."}`. Descriptions may also be
+    /// arbitrary objects, in which case their JSON source is kept as the description. Any other
+    /// fields on the entry, like source locations, are ignored.
+    pub(crate) fn scan_formatjs_definitions(
+        mut self,
+        file_meta: &SourceFileMeta,
+    ) -> MessageSourceResult<Vec<RawMessageDefinition>> {
+        let mut entries = vec![];
+        self.skip_whitespace();
+        self.scan_object(|scanner, key| {
+            let name = key_symbol(&key);
+            let mut message = None;
+            let mut description = None;
+            scanner.scan_object(|scanner, field| {
+                match field.as_ref() {
+                    "defaultMessage" => {
+                        let position = scanner.current_position();
+                        let (value, offsets) = scanner.scan_string()?;
+                        message = Some((position, value, offsets));
+                    }
+                    "description" => description = Some(scanner.scan_description()?),
+                    _ => scanner.skip_value()?,
+                }
+                Ok(())
+            })?;
+
+            let Some((position, value, offsets)) = message else {
+                return Err(MessageSourceError::NoMessageValue(name));
+            };
+            let mut meta = MessageMeta::from(file_meta);
+            if let Some(description) = description {
+                meta = meta.with_description(&description);
+            }
+            let mut definition = RawMessageDefinition::new(name, position, value.as_ref(), meta);
+            definition.value = definition.value.with_source_offsets(offsets);
+            entries.push(definition);
+            Ok(())
+        })?;
+        self.finish(entries)
+    }
+
+    /// Scan a compiled keyless JSON bundle, decompiling each entry back into message source text.
+    /// Entries are keyed by the hashed message key, and keys starting with `$` are bundle metadata
+    /// rather than messages, since hashed keys never contain that character.
+    pub(crate) fn scan_compiled_bundle(
+        mut self,
+    ) -> MessageSourceResult<Vec<RawMessageTranslation>> {
+        let mut entries = vec![];
+        self.skip_whitespace();
+        self.scan_object(|scanner, key| {
+            if key.starts_with('$') {
+                return scanner.skip_value();
+            }
+
+            let position = scanner.current_position();
+            let start = scanner.position;
+            scanner.skip_value()?;
+            let compiled: serde_json::Value =
+                serde_json::from_str(&scanner.source[start..scanner.position])
+                    .map_err(|error| scanner.entry_error(&key, &error.to_string()))?;
+            let raw = decompile_format_js(&compiled)
+                .map_err(|error| scanner.entry_error(&key, error.message()))?;
+
+            let mut translation = RawMessageTranslation::new(key_symbol(&key), position, raw);
+            translation.value = translation.value.with_machine_recovered(true);
+            entries.push(translation);
+            Ok(())
+        })?;
+        self.finish(entries)
+    }
+
+    /// Scan an object starting at the opening brace, calling `on_entry` with the key of each entry
+    /// once the scanner is positioned at the start of its value. `on_entry` must consume the value.
+    fn scan_object(
+        &mut self,
+        mut on_entry: impl FnMut(&mut Self, Cow<'a, str>) -> MessageSourceResult<()>,
+    ) -> MessageSourceResult<()> {
+        self.expect(b'{')?;
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return Ok(());
+        }
+
+        loop {
+            self.entry_start = self.position;
+            let (key, _) = self.scan_string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            on_entry(self, key)?;
+
+            self.skip_whitespace();
+            if self.eat(b',') {
+                self.skip_whitespace();
+                continue;
+            }
+            return self.expect(b'}');
+        }
+    }
+
+    fn scan_description(&mut self) -> MessageSourceResult<Cow<'a, str>> {
+        if self.peek() == Some(b'"') {
+            return Ok(self.scan_string()?.0);
+        }
+        let start = self.position;
+        self.skip_value()?;
+        Ok(Cow::Borrowed(&self.source[start..self.position]))
+    }
+
+    /// Skip over any JSON value without keeping its content, only checking that it is well-formed
+    /// enough to find where it ends.
+    fn skip_value(&mut self) -> MessageSourceResult<()> {
+        match self.peek() {
+            Some(b'"') => self.scan_string().map(|_| ()),
+            Some(b'{') => self.scan_object(|scanner, _| scanner.skip_value()),
+            Some(b'[') => {
+                self.position += 1;
+                self.skip_whitespace();
+                if self.eat(b']') {
+                    return Ok(());
+                }
+                loop {
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    if self.eat(b',') {
+                        self.skip_whitespace();
+                        continue;
+                    }
+                    return self.expect(b']');
+                }
+            }
+            _ => {
+                // Numbers, booleans, and null are all made of these characters, and don't need to
+                // be validated any further since their values are never used.
+                let start = self.position;
+                while self.peek().is_some_and(|byte| {
+                    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'+' | b'.')
+                }) {
+                    self.position += 1;
+                }
+                match self.position > start {
+                    true => Ok(()),
+                    false => Err(self.error("Expected a value")),
+                }
+            }
+        }
+    }
+
+    fn scan_review_state(&mut self, key: &str) -> MessageSourceResult<ReviewState> {
+        let (name, _) = self.scan_string()?;
+        ReviewState::parse(&name)
+            .ok_or_else(|| self.entry_error(key, &format!("Unknown review state {name}")))
+    }
+
+    fn scan_bool(&mut self) -> MessageSourceResult<bool> {
+        for (literal, value) in [("true", true), ("false", false)] {
+            if self.source[self.position..].starts_with(literal) {
+                self.position += literal.len();
+                return Ok(value);
+            }
+        }
+        Err(self.error("Expected a boolean"))
+    }
+
+    /// Convert a value returned from [`MessagesScanner::scan_string`] into a pointer, sharing the
+    /// source allocation when the value was borrowed directly from it.
+    fn text_pointer(&self, value: Cow<'a, str>) -> TextPointer {
+        match (value, self.shared_source) {
+            (Cow::Borrowed(value), Some(shared)) => {
+                let start = value.as_ptr() as usize - self.source.as_ptr() as usize;
+                TextPointer::new(shared.clone(), start..start + value.len())
+            }
+            (value, _) => value.into(),
+        }
+    }
+
+    fn finish<T>(mut self, entries: Vec<T>) -> MessageSourceResult<Vec<T>> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(entries),
+            Some(_) => Err(self.error("Unexpected content after the end of the object")),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> MessageSourceResult<()> {
+        match self.eat(byte) {
+            true => Ok(()),
+            false => Err(self.error(&format!("Expected '{}'", byte as char))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let mut after_token = true;
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'\n' => {
+                    self.position += 1;
+                    self.line += 1;
+                    self.line_start = self.position;
+                    after_token = false;
+                }
+                b' ' | b'\t' | b'\r' => self.position += 1,
+                b'/' if self.allow_comments => {
+                    let end = match self.source.as_bytes().get(self.position + 1) {
+                        Some(b'/') => {
+                            let end = self.find_in_rest(b"\n").unwrap_or(self.source.len());
+                            // The line break isn't part of the comment, even on Windows.
+                            Some(end - usize::from(self.source[..end].ends_with('\r')))
+                        }
+                        Some(b'*') => self.find_in_rest(b"*/").map(|end| end + 2),
+                        _ => None,
+                    };
+                    // Unterminated block comments are left for the caller to report.
+                    let Some(end) = end else {
+                        break;
+                    };
+                    let start = self.position;
+                    self.comments.push(ScannedComment {
+                        range: start..end,
+                        after_token,
+                    });
+                    for (offset, _) in self.source[start..end].match_indices('\n') {
+                        self.line += 1;
+                        self.line_start = start + offset + 1;
+                        after_token = false;
+                    }
+                    self.position = end;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Return the offset of the first occurrence of `needle` after the two-character comment
+    /// opener at the current position.
+    fn find_in_rest(&self, needle: &[u8]) -> Option<usize> {
+        memchr::memmem::find(&self.source.as_bytes()[self.position + 2..], needle)
+            .map(|offset| self.position + 2 + offset)
+    }
+
+    /// Return every comment skipped since this was last called.
+    fn take_comments(&mut self) -> Vec<ScannedComment> {
+        std::mem::take(&mut self.comments)
+    }
+
+    /// Join the text of `comments`, each preceded by a space when it follows other content on its
+    /// line, or by a line break and its indentation when it starts its own line, so that they can
+    /// be written back exactly as they were.
+    fn join_comments_with_separators(&self, comments: &[ScannedComment]) -> Option<String> {
+        if comments.is_empty() {
+            return None;
+        }
+        let mut text = String::new();
+        for comment in comments {
+            let line_start = self.source[..comment.range.start]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            let line_prefix = &self.source[line_start..comment.range.start];
+            match line_prefix.trim().is_empty() {
+                true => {
+                    text.push('\n');
+                    text.push_str(line_prefix);
+                }
+                false => text.push(' '),
+            }
+            text.push_str(&self.source[comment.range.clone()]);
+        }
+        Some(text)
+    }
+
+    /// Join the text of `comments` like [`MessagesScanner::join_comments_with_separators`], but
+    /// without a separator before the first one.
+    fn join_comments(&self, comments: &[ScannedComment]) -> Option<String> {
+        self.join_comments_with_separators(comments)
+            .map(|text| text.trim_start().to_string())
+    }
+
+    /// Add `comments` to the end of the trailing comments in `value_comments`.
+    fn add_trailing_comments(
+        &self,
+        value_comments: &mut Option<Box<ValueComments>>,
+        comments: &[ScannedComment],
+    ) {
+        let Some(text) = self.join_comments_with_separators(comments) else {
+            return;
+        };
+        let value_comments = value_comments.get_or_insert_with(Default::default);
+        match &mut value_comments.trailing {
+            Some(trailing) => trailing.push_str(&text),
+            None => value_comments.trailing = Some(text),
+        }
+    }
+
+    fn current_position(&self) -> RawPosition {
+        let (mut offset, mut col) = self.last_column.get();
+        if offset < self.line_start || offset > self.position {
+            (offset, col) = (self.line_start, 0);
+        }
+        col += self.source[offset..self.position].chars().count() as u32;
+        self.last_column.set((self.position, col));
+        RawPosition {
+            line: self.line,
+            col,
+        }
+    }
+
+    fn error(&self, message: &str) -> MessageSourceError {
+        MessageSourceError::SyntaxError(
+            self.kind.clone(),
+            message.to_string(),
+            self.current_position(),
+        )
+    }
+
+    fn entry_error(&self, key: &str, message: &str) -> MessageSourceError {
+        MessageSourceError::ParseError(
+            self.kind.clone(),
+            format!("Entry {key} could not be read: {message}"),
+        )
+    }
+
+    /// Scan a complete string literal, including the surrounding quotes, returning the decoded
+    /// content and the list of escapes that were decoded along the way. Strings without any
+    /// escapes are borrowed directly from the source.
+    fn scan_string(&mut self) -> MessageSourceResult<(Cow<'a, str>, SourceOffsetList)> {
+        self.expect(b'"')?;
+        let content_start = self.position;
+        let mut decoded: Option<String> = None;
+        let mut offsets = SourceOffsetList::default();
+        // Start of the content that has not yet been copied into `decoded`.
+        let mut chunk_start = content_start;
+
+        loop {
+            // Most of a string is plain text, so jump straight to the next quote or escape and
+            // only check the skipped content for control characters, rather than inspecting every
+            // byte individually. Large translation files are dominated by string content.
+            let rest = &self.source.as_bytes()[self.position..];
+            let next = memchr2(b'"', b'\\', rest).unwrap_or(rest.len());
+            if let Some(control) = rest[..next].iter().position(|byte| *byte < 0x20) {
+                self.position += control;
+                return Err(self.error("Control characters must be escaped"));
+            }
+            self.position += next;
+            let Some(&byte) = rest.get(next) else {
+                return Err(self.error("Unterminated string"));
+            };
+            match byte {
+                b'"' => {
+                    let content = &self.source[chunk_start..self.position];
+                    self.position += 1;
+                    let value = match decoded {
+                        Some(mut decoded) => {
+                            decoded.push_str(content);
+                            Cow::Owned(decoded)
+                        }
+                        None => Cow::Borrowed(content),
+                    };
+                    return Ok((value, offsets));
+                }
+                b'\\' => {
+                    let decoded = decoded.get_or_insert_with(String::new);
+                    decoded.push_str(&self.source[chunk_start..self.position]);
+                    let escape_start = self.position;
+                    let character = self.scan_escape()?;
+                    offsets.push(SourceOffset {
+                        decoded_offset: decoded.len(),
+                        decoded_len: character.len_utf8(),
+                        source_len: self.position - escape_start,
+                    });
+                    decoded.push(character);
+                    chunk_start = self.position;
+                }
+                // `memchr2` only stops at quotes and backslashes, but a scanner bug shouldn't take
+                // down the whole process that's reading the file.
+                _ => return Err(self.error("Unexpected character in string")),
+            }
+        }
+    }
+
+    /// Scan a single escape sequence, starting at the backslash, returning the decoded character.
+    fn scan_escape(&mut self) -> MessageSourceResult<char> {
+        self.expect(b'\\')?;
+        let Some(byte) = self.peek() else {
+            return Err(self.error("Unterminated escape sequence"));
+        };
+        // Invalid escapes are reported before advancing, since the escaped character may not be
+        // a single byte and the error position must stay on a character boundary.
+        let character = match byte {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                self.position += 1;
+                return self.scan_unicode_escape();
+            }
+            _ => return Err(self.error("Invalid escape sequence")),
+        };
+        self.position += 1;
+        Ok(character)
+    }
+
+    /// Scan the hex digits of a `\u` escape, including a following low surrogate escape when the
+    /// first code unit is a high surrogate.
+    fn scan_unicode_escape(&mut self) -> MessageSourceResult<char> {
+        let first = self.scan_hex_code_unit()?;
+        if (0xd800..0xdc00).contains(&first) {
+            // High surrogates must be followed by an escaped low surrogate to form a complete
+            // character.
+            if !(self.eat(b'\\') && self.eat(b'u')) {
+                return Err(self.error("Expected a low surrogate escape"));
+            }
+            let second = self.scan_hex_code_unit()?;
+            char::decode_utf16([first, second])
+                .next()
+                .and_then(Result::ok)
+                .ok_or_else(|| self.error("Invalid surrogate pair"))
+        } else {
+            char::from_u32(first as u32).ok_or_else(|| self.error("Invalid unicode escape"))
+        }
+    }
+
+    fn scan_hex_code_unit(&mut self) -> MessageSourceResult<u16> {
+        let digits = self
+            .source
+            .get(self.position..self.position + 4)
+            .ok_or_else(|| self.error("Unterminated unicode escape"))?;
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(self.error("Invalid unicode escape"));
+        }
+        let value =
+            u16::from_str_radix(digits, 16).map_err(|_| self.error("Invalid unicode escape"))?;
+        self.position += 4;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_translations(source: &str) -> MessageSourceResult<Vec<RawMessageTranslation>> {
+        MessagesScanner::new(source, SourceFileKind::Translation).scan_translations()
+    }
+
+    fn scan_commented_translations(
+        source: &str,
+    ) -> MessageSourceResult<Vec<RawMessageTranslation>> {
+        MessagesScanner::new(source, SourceFileKind::Translation)
+            .with_comments(true)
+            .scan_translations()
+    }
+
+    fn scan_formatjs(source: &str) -> MessageSourceResult<Vec<RawMessageDefinition>> {
+        MessagesScanner::new(source, SourceFileKind::Definition)
+            .scan_formatjs_definitions(&SourceFileMeta::new("legacy.messages.formatjs.json"))
+    }
+
+    #[test]
+    fn positions() {
+        let entries = scan_translations("{\n  \"a\": \"one\",\n  \"b\":  \"two\"\n}").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].position.line, entries[0].position.col), (2, 7));
+        assert_eq!((entries[1].position.line, entries[1].position.col), (3, 8));
+        assert_eq!(entries[1].value.raw, "two");
+        assert!(entries[1]
+            .value
+            .source_offsets
+            .as_ref()
+            .is_some_and(SourceOffsetList::is_empty));
+    }
+
+    #[test]
+    fn single_line_positions() {
+        let entries = scan_translations("{\"a\": \"é\", \"b\": \"x\",\n \"c\": \"y\"}").unwrap();
+        let positions: Vec<(u32, u32)> = entries
+            .iter()
+            .map(|entry| (entry.position.line, entry.position.col))
+            .collect();
+        assert_eq!(positions, vec![(1, 6), (1, 16), (2, 6)]);
+    }
+
+    #[test]
+    fn escape_offsets() {
+        let entries = scan_translations(r#"{"a": "\u00e9\n*\ud83d\ude00*"}"#).unwrap();
+        let value = &entries[0].value;
+        assert_eq!(value.raw, "é\n*😀*");
+        let offsets = value.source_offsets.as_ref().unwrap();
+        let first_star = value.raw.find('*').unwrap();
+        let last_star = value.raw.rfind('*').unwrap();
+        assert_eq!(offsets.to_source_offset(first_star), 8);
+        assert_eq!(offsets.to_source_column(&value.raw, first_star), 8);
+        assert_eq!(offsets.to_source_column(&value.raw, last_star), 21);
+    }
+
+    #[test]
+    fn shared_values() {
+        let source: Arc<str> = r#"{"a": "plain", "b": "escaped\n"}"#.into();
+        let entries = MessagesScanner::with_shared_source(&source, SourceFileKind::Translation)
+            .scan_translations()
+            .unwrap();
+        assert_eq!(entries[0].value.raw, "plain");
+        assert!(entries[0].value.raw.shares_source_with(&source));
+        assert_eq!(entries[1].value.raw, "escaped\n");
+        assert!(!entries[1].value.raw.shares_source_with(&source));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(scan_translations(r#"{"a": "b""#).is_err());
+        assert!(scan_translations(r#"{"a": 1}"#).is_err());
+        assert!(scan_translations(r#"{"a": "\x"}"#).is_err());
+        assert!(scan_translations("{\"a\": \"\\\u{e9}\"}").is_err());
+        assert!(scan_translations(r#"{"a": "\u+123"}"#).is_err());
+        assert!(scan_translations(r#"{"a": "b"} extra"#).is_err());
+        assert!(scan_translations("{\"a\": \"line\nbreak\"}").is_err());
+    }
+
+    #[test]
+    fn review_states() {
+        let entries = scan_translations(
+            r#"{"a": "plain", "b": {"machineTranslated": true, "message": "machine", "note": [1]}, "c": {"message": "done", "reviewState": "final"}}"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].value.review_state, ReviewState::New);
+        assert_eq!(entries[1].value.raw, "machine");
+        assert_eq!(entries[1].position.col, 59);
+        assert_eq!(entries[1].value.review_state, ReviewState::Machine);
+        assert_eq!(entries[2].value.review_state, ReviewState::Final);
+        assert!(scan_translations(r#"{"a": {"machineTranslated": true}}"#).is_err());
+        assert!(scan_translations(r#"{"a": {"message": "b", "machineTranslated": 1}}"#).is_err());
+        assert!(scan_translations(r#"{"a": {"message": "b", "reviewState": "done"}}"#).is_err());
+    }
+
+    #[test]
+    fn machine_recovered() {
+        let entries = scan_translations(
+            r#"{"a": "plain", "b": {"message": "back", "reviewState": "machine", "machineRecovered": true}}"#,
+        )
+        .unwrap();
+        assert!(!entries[0].value.machine_recovered);
+        assert!(entries[1].value.machine_recovered);
+        assert_eq!(entries[1].value.review_state, ReviewState::Machine);
+        assert!(
+            scan_translations(r#"{"a": {"message": "b", "machineRecovered": "yes"}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let entries = scan_commented_translations(
+            "// File header\n{\n  // Keep it short\n  \"a\": \"one\", // After a\n  /* Formal,\n     not casual */\n  // Reviewed\n  \"b\": /* inline */ \"two\" // After b\n  // Trailing\n}",
+        )
+        .unwrap();
+        let comments = |index: usize| entries[index].value.comments.as_deref().unwrap();
+        assert_eq!(
+            comments(0),
+            &ValueComments {
+                leading: Some("// File header\n  // Keep it short".into()),
+                inline: None,
+                trailing: Some(" // After a".into()),
+            }
+        );
+        assert_eq!(
+            comments(1),
+            &ValueComments {
+                leading: Some("/* Formal,\n     not casual */\n  // Reviewed".into()),
+                inline: Some("/* inline */".into()),
+                trailing: Some(" // After b\n  // Trailing".into()),
+            }
+        );
+        assert_eq!((entries[1].position.line, entries[1].position.col), (8, 20));
+    }
+
+    #[test]
+    fn comments_around_entries() {
+        // Only comments on the line the previous entry ends on are kept with it.
+        let entries = scan_commented_translations(
+            "{\"a\": \"one\" /* a */, // a\r\n// b\r\n\"b\": \"two\"\n}",
+        )
+        .unwrap();
+        let comments = entries[0].value.comments.as_deref().unwrap();
+        assert_eq!(comments.trailing.as_deref(), Some(" /* a */ // a"));
+        let comments = entries[1].value.comments.as_deref().unwrap();
+        assert_eq!(comments.leading.as_deref(), Some("// b"));
+        assert_eq!(comments.trailing, None);
+
+        // Comments inside of a value object are kept between the key and the value, and the
+        // trailing comments of the last entry start on their own line.
+        let entries =
+            scan_commented_translations("{\"a\": {\"message\": \"one\" /* a */}\n  // Trailing\n}")
+                .unwrap();
+        let comments = entries[0].value.comments.as_deref().unwrap();
+        assert_eq!(comments.inline.as_deref(), Some("/* a */"));
+        assert_eq!(comments.trailing.as_deref(), Some("\n  // Trailing"));
+
+        let entries = scan_commented_translations("{\"a\": \"one\"}").unwrap();
+        assert_eq!(entries[0].value.comments, None);
+    }
+
+    #[test]
+    fn comments_are_not_json() {
+        assert!(scan_translations("{\"a\": \"one\" // note\n}").is_err());
+        assert!(scan_translations("{/* note */ \"a\": \"one\"}").is_err());
+        assert!(scan_commented_translations(r#"{"a": "b"} /* unterminated"#).is_err());
+    }
+
+    #[test]
+    fn syntax_error_position() {
+        let result = scan_translations("{\n  \"a\": \"b\",\n  \"c\" \"d\"\n}");
+        let Err(MessageSourceError::SyntaxError(_, message, position)) = result else {
+            panic!("Expected a syntax error");
+        };
+        assert_eq!(message, "Expected ':'");
+        assert_eq!((position.line, position.col), (3, 6));
+    }
+
+    #[test]
+    fn formatjs_definitions() {
+        let entries = scan_formatjs(
+            r#"{
+  "greeting": {
+    "defaultMessage": "Hello, {name}!",
+    "description": "Shown on the home page",
+    "file": "src/Home.tsx",
+    "start": 120,
+    "end": [1, 2.5e3, true, null]
+  },
+  "farewell": {"description": {"context": "Logout"}, "defaultMessage": "Bye"}
+}"#,
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].value.raw, "Hello, {name}!");
+        assert_eq!((entries[0].position.line, entries[0].position.col), (3, 22));
+        assert_eq!(
+            entries[0].meta.description.as_deref(),
+            Some("Shown on the home page")
+        );
+        assert_eq!(entries[1].value.raw, "Bye");
+        assert_eq!(
+            entries[1].meta.description.as_deref(),
+            Some(r#"{"context": "Logout"}"#)
+        );
+    }
+
+    #[test]
+    fn formatjs_invalid() {
+        assert!(matches!(
+            scan_formatjs(r#"{"a": {"description": "no message"}}"#),
+            Err(MessageSourceError::NoMessageValue(_))
+        ));
+        assert!(scan_formatjs(r#"{"a": "b"}"#).is_err());
+        assert!(scan_formatjs(r#"{"a": {"defaultMessage": 1}}"#).is_err());
+        assert!(scan_formatjs(r#"{"a": {"defaultMessage": "b", "end": }}"#).is_err());
+    }
+
+    #[test]
+    fn compiled_bundle() {
+        let entries = MessagesScanner::new(
+            r#"{"$plurals": {"cardinal": ["one"]}, "abc123": "Static", "def456": [[1, "name"], "!"]}"#,
+            SourceFileKind::Translation,
+        )
+        .scan_compiled_bundle()
+        .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, key_symbol("abc123"));
+        assert_eq!(entries[0].value.raw, "Static");
+        assert_eq!(entries[1].value.raw, "{name}!");
+        assert_eq!(entries[1].position.col, 66);
+        assert!(entries[1].value.machine_recovered);
+    }
+}
@@ -1,5 +1,122 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
 pub trait IntlDatabaseService {
     type Result;
 
     fn run(&mut self) -> Self::Result;
+
+    /// Run the service like [IntlDatabaseService::run], reporting progress to `context` and
+    /// stopping early with [ServiceCancelled] once it is cancelled. This lets long-running services
+    /// be run on another thread while the caller keeps track of them.
+    ///
+    /// Services that can't be split into smaller units of work ignore the context by default and
+    /// always run to completion.
+    fn run_with_context(&mut self, context: &ServiceContext) -> Self::Result {
+        let _ = context;
+        self.run()
+    }
+}
+
+/// A shared flag for cancelling services that are running with a [ServiceContext].
+///
+/// Clones of a token all share the same flag, so one can be kept by the caller while another is
+/// given to the service, even on another thread.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request that every service using this token stops as soon as possible. Cancelling can't be
+    /// undone, so a new token is needed to run services again.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a service has gotten through its work, like the number of messages that have been
+/// validated so far out of every message in the database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ServiceProgress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// The error returned by services that stopped early because their [CancellationToken] was
+/// cancelled.
+#[derive(Debug)]
+pub struct ServiceCancelled;
+
+impl std::fmt::Display for ServiceCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The operation was cancelled before it completed")
+    }
+}
+
+impl std::error::Error for ServiceCancelled {}
+
+type ProgressCallback<'a> = Box<dyn Fn(ServiceProgress) + Send + Sync + 'a>;
+
+/// Everything a service needs to report its progress and check whether it should keep running.
+///
+/// This is given to [IntlDatabaseService::run_with_context]. The default context is never cancelled
+/// and discards all progress.
+#[derive(Default)]
+pub struct ServiceContext<'a> {
+    cancellation: CancellationToken,
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl<'a> ServiceContext<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Call `on_progress` as the service works through its units of work. Services report after
+    /// every unit, so reports are only passed on at most once for every percent of the total, plus
+    /// once more when all of the work is done, to keep callers that cross threads from being
+    /// flooded with updates.
+    pub fn with_progress(
+        mut self,
+        on_progress: impl Fn(ServiceProgress) + Send + Sync + 'a,
+    ) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Return [ServiceCancelled] if the service should stop, so that services can check for
+    /// cancellation between units of work with `context.check_cancelled()?`.
+    pub fn check_cancelled(&self) -> Result<(), ServiceCancelled> {
+        match self.is_cancelled() {
+            true => Err(ServiceCancelled),
+            false => Ok(()),
+        }
+    }
+
+    /// Report that `completed` out of `total` units of work are done.
+    pub fn report_progress(&self, completed: usize, total: usize) {
+        let Some(on_progress) = &self.on_progress else {
+            return;
+        };
+        let step = (total / 100).max(1);
+        if completed == total || completed % step == 0 {
+            on_progress(ServiceProgress { completed, total });
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use intl_database_core::KeySymbol;
+
 use crate::writer::{
     write_doc, AlphabeticSymbolMap, AlphabeticSymbolSet, TypeDocFormat, TypeDocWriter, WriteResult,
 };
@@ -7,10 +9,16 @@ pub struct DocComment<'a> {
     pub(super) key: &'a str,
     /// Raw text of the definition of the message
     pub(super) value: Option<&'a str>,
+    /// Locale that the definition is written in, only set when it differs from the source locale
+    /// of the database, like for messages from a library that is written in another locale.
+    pub(super) source_locale: Option<KeySymbol>,
     /// Optional description of the message provided from the definition
     pub(super) description: Option<&'a str>,
     /// Locales where the message expected a translation but was not found
     pub(super) missing_translations: AlphabeticSymbolSet,
+    /// Locales where the translation says the same thing as the definition, which usually means
+    /// it was copied from the definition and never translated
+    pub(super) untranslated: AlphabeticSymbolSet,
     /// Whether this message is marked as is_secret
     pub(super) is_secret: bool,
     /// Whether this message is marked as ready for translation
@@ -25,22 +33,26 @@ impl TypeDocFormat for DocComment<'_> {
     fn fmt(&self, mut w: &mut TypeDocWriter) -> WriteResult {
         w.push_prefix(" * ");
         write_doc!(w, ["/**\nKey: `", &self.key, "`"])?;
-        write_doc!(
-            w,
-            ["\n\n### Definition\n", "```text\n", &self.value, "\n```"]
-        )?;
+        write_doc!(w, ["\n\n### Definition"])?;
+        if let Some(source_locale) = &self.source_locale {
+            write_doc!(w, [" (`", source_locale, "`)"])?;
+        }
+        write_doc!(w, ["\n```text\n", &self.value, "\n```"])?;
 
         if !self.ready_to_translate {
             write_doc!(w, ["\n\n**Not ready for translation**"])?;
         }
 
-        let is_missing_translations = !self.missing_translations.is_empty();
+        // Messages that aren't ready for translation are expected to be missing translations.
+        let is_missing_translations =
+            self.ready_to_translate && !self.missing_translations.is_empty();
+        let is_untranslated = self.ready_to_translate && !self.untranslated.is_empty();
         let has_spurious_variables = !self.spurious_variables.is_empty();
-        let has_problems = is_missing_translations || has_spurious_variables;
+        let has_problems = is_missing_translations || is_untranslated || has_spurious_variables;
 
         if has_problems {
             write_doc!(w, ["\n\n### Problems"])?;
-            if self.ready_to_translate && is_missing_translations {
+            if is_missing_translations {
                 let locales = self
                     .missing_translations
                     .iter()
@@ -56,6 +68,14 @@ impl TypeDocFormat for DocComment<'_> {
                     write_doc!(w, [&locale])?;
                 }
             }
+            if is_untranslated {
+                write_doc!(w, ["\n\nSame as the definition: "])?;
+                let mut is_first = true;
+                for locale in &self.untranslated {
+                    write_doc!(w, [&(!is_first).then_some(", "), "`", &locale, "`"])?;
+                    is_first = false;
+                }
+            }
             if has_spurious_variables {
                 write_doc!(w, ["\n\nSpurious variables from translations:"])?;
                 for (variable, locales) in &self.spurious_variables {
@@ -94,3 +114,45 @@ impl TypeDocFormat for DocComment<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::key_symbol;
+
+    use super::*;
+    use crate::writer::TypesOutputFormat;
+
+    fn format(ready_to_translate: bool) -> String {
+        let comment = DocComment {
+            key: "abc123",
+            value: Some("Hello"),
+            source_locale: None,
+            description: None,
+            missing_translations: AlphabeticSymbolSet::from_iter([key_symbol("fr")]),
+            untranslated: AlphabeticSymbolSet::from_iter([key_symbol("de")]),
+            is_secret: false,
+            ready_to_translate,
+            spurious_variables: AlphabeticSymbolMap::default(),
+        };
+        let mut writer = TypeDocWriter::new(TypesOutputFormat::TypeScript);
+        comment.fmt(&mut writer).unwrap();
+        writer.take_buffer()
+    }
+
+    #[test]
+    fn test_missing_translations() {
+        let output = format(true);
+        assert!(output.contains("Missing translations: `fr`"), "{output}");
+        assert!(output.contains("Same as the definition: `de`"), "{output}");
+    }
+
+    #[test]
+    fn test_not_ready_for_translation() {
+        // Messages that aren't ready for translation are expected to be missing translations.
+        let output = format(false);
+        assert!(output.contains("**Not ready for translation**"), "{output}");
+        assert!(!output.contains("### Problems"), "{output}");
+        assert!(!output.contains("Missing translations"), "{output}");
+        assert!(!output.contains("Translated in all locales"), "{output}");
+    }
+}
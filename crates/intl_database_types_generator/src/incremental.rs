@@ -0,0 +1,245 @@
+//! Regenerating a types file by patching only the entries of messages that changed since it was
+//! last written. Every other entry is copied from the previous output as-is, along with its source
+//! map mappings moved to its new line, which produces the same output as generating the whole
+//! file again while skipping the work of building the types and doc comments of each message.
+use std::collections::HashMap;
+
+use sourcemap::SourceMap;
+
+use intl_database_core::{key_symbol, KeySymbol, KeySymbolSet};
+
+use crate::writer::TypesOutputFormat;
+
+/// A source map mapping, as `(dst_line, dst_col, src_line, src_col)`.
+pub(crate) type Mapping = (u32, u32, u32, u32);
+
+/// The text of a single message entry in a previously generated types file, including the newline
+/// that starts it, along with the source map mappings that point into it.
+pub(crate) struct PreviousEntry {
+    pub text: String,
+    /// Line in the previous output where the entry starts, counted the same way as the writer.
+    pub line: u32,
+    pub mappings: Vec<Mapping>,
+}
+
+/// The entries of a previously generated types file, and the keys of the messages that need to be
+/// generated again rather than copied from it.
+pub(crate) struct PreviousOutput {
+    output: String,
+    source_map: String,
+    pub changed_keys: KeySymbolSet,
+}
+
+/// Markers for where each entry starts and where the list of entries ends in the output of each
+/// format. Lines inside of entries always start with the line prefix of the entry list, so these
+/// can't appear anywhere else.
+fn entry_markers(format: TypesOutputFormat) -> (&'static str, &'static str) {
+    match format {
+        TypesOutputFormat::JsDoc => ("\n * @property {", "\n */"),
+        _ => ("\n  /**", "\n};"),
+    }
+}
+
+/// Find the message key that an entry was written for.
+fn entry_key(text: &str, format: TypesOutputFormat) -> Option<&str> {
+    match format {
+        // `@property {TypedIntlMessageGetter<{...}>} KEY - value`
+        TypesOutputFormat::JsDoc => {
+            let (_, rest) = text.split_once(">} ")?;
+            let end = rest.find([' ', '\n']).unwrap_or(rest.len());
+            Some(&rest[..end])
+        }
+        // The entry ends with `'KEY': TypedIntlMessageGetter<{...}>,` on its own line.
+        _ => {
+            let (_, line) = text.rsplit_once("\n  '")?;
+            let (key, _) = line.split_once("': ")?;
+            Some(key)
+        }
+    }
+}
+
+impl PreviousOutput {
+    pub fn new(output: String, source_map: String, changed_keys: KeySymbolSet) -> Self {
+        Self {
+            output,
+            source_map,
+            changed_keys,
+        }
+    }
+
+    /// Split the previous output into its entries by message key. `prelude` is everything that
+    /// the writer outputs before the first entry. Returns None if the previous output doesn't
+    /// start with the same prelude or can't be read, meaning it was generated differently and
+    /// can't be patched.
+    pub fn entries(
+        &self,
+        prelude: &str,
+        format: TypesOutputFormat,
+    ) -> Option<HashMap<KeySymbol, PreviousEntry>> {
+        let body = self.output.strip_prefix(prelude)?;
+        let (entry_marker, end_marker) = entry_markers(format);
+        let body = &body[..body.find(end_marker)?];
+        let source_map = SourceMap::from_slice(self.source_map.as_bytes()).ok()?;
+        // Mappings are ordered by their line in the output, so each entry's mappings are a slice.
+        let mappings: Vec<Mapping> = source_map
+            .tokens()
+            .map(|token| {
+                (
+                    token.get_dst_line(),
+                    token.get_dst_col(),
+                    token.get_src_line(),
+                    token.get_src_col(),
+                )
+            })
+            .collect();
+
+        let mut starts: Vec<usize> = body.match_indices(entry_marker).map(|(i, _)| i).collect();
+        if starts.first().map_or(!body.is_empty(), |first| *first != 0) {
+            return None;
+        }
+        starts.push(body.len());
+
+        let mut line = 1 + prelude.matches('\n').count() as u32;
+        let mut entries = HashMap::new();
+        for range in starts.windows(2) {
+            let text = &body[range[0]..range[1]];
+            let end_line = line + text.matches('\n').count() as u32;
+            // The previous entry ends on the line that this one starts on, so mappings on that
+            // line belong to it.
+            let first = mappings.partition_point(|mapping| mapping.0 <= line);
+            let last = mappings.partition_point(|mapping| mapping.0 <= end_line);
+            entries.insert(
+                key_symbol(entry_key(text, format)?),
+                PreviousEntry {
+                    text: text.to_string(),
+                    line,
+                    mappings: mappings[first..last].to_vec(),
+                },
+            );
+            line = end_line;
+        }
+        Some(entries)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{
+        DefinitionFile, FilePosition, MessageMeta, MessageValue, MessagesDatabase, SourceFile,
+        SourceFileMeta, DEFAULT_LOCALE,
+    };
+    use intl_database_service::IntlDatabaseService;
+
+    use super::*;
+    use crate::{IntlTypesGenerator, IntlTypesGeneratorOptions};
+
+    /// Create a database with the given definitions, each on its own line of a single file.
+    fn database(definitions: &[(&str, &str)]) -> MessagesDatabase {
+        let mut database = MessagesDatabase::new();
+        let file = key_symbol("test.messages.js");
+        let mut keys = KeySymbolSet::default();
+        for (line, (key, value)) in definitions.iter().enumerate() {
+            let value = MessageValue::from_raw(value).with_file_position(FilePosition {
+                file,
+                line: line as u32 + 2,
+                col: 2,
+            });
+            database
+                .insert_definition(
+                    key,
+                    value,
+                    key_symbol(DEFAULT_LOCALE),
+                    MessageMeta::default(),
+                    false,
+                )
+                .unwrap();
+            keys.insert(key_symbol(key));
+        }
+        database.create_source_file(
+            file,
+            SourceFile::Definition(DefinitionFile::new(
+                file.to_string(),
+                SourceFileMeta::new(&file),
+                keys,
+            )),
+        );
+        database
+    }
+
+    /// Generate the types and source map of `database`, patching `previous` if it's given.
+    fn generate(
+        database: &MessagesDatabase,
+        format: TypesOutputFormat,
+        previous: Option<(String, String, &[&str])>,
+    ) -> (String, String) {
+        let mut generator = IntlTypesGenerator::new(
+            database,
+            key_symbol("test.messages.js"),
+            "test.messages.d.ts".into(),
+            IntlTypesGeneratorOptions::default().with_format(format),
+        );
+        if let Some((output, source_map, changed_keys)) = previous {
+            let changed_keys = changed_keys.iter().map(|key| key_symbol(key)).collect();
+            generator = generator.with_previous_output(output, source_map, changed_keys);
+        }
+        generator.run().unwrap();
+        let output = generator.take_buffer();
+        (output, generator.into_sourcemap().unwrap())
+    }
+
+    #[test]
+    fn test_patched_output_matches_full_regeneration() {
+        let before = database(&[
+            ("BYE", "Goodbye"),
+            ("GREETING", "Hello"),
+            ("WELCOME", "Welcome, {name}!"),
+        ]);
+        // Changing a message can add lines to its entry, moving every entry after it.
+        let after = database(&[
+            ("BYE", "Goodbye"),
+            (
+                "GREETING",
+                "Hello {name}, you have {count, plural, one {# message} other {# messages}}",
+            ),
+            ("NEW", "Brand new"),
+            ("WELCOME", "Welcome, {name}!"),
+        ]);
+
+        for format in [
+            TypesOutputFormat::TypeScript,
+            TypesOutputFormat::Flow,
+            TypesOutputFormat::JsDoc,
+        ] {
+            let (output, source_map) = generate(&before, format, None);
+            let patched = generate(
+                &after,
+                format,
+                Some((output, source_map, &["GREETING", "NEW"])),
+            );
+            assert_eq!(patched, generate(&after, format, None), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_unreadable_output_is_generated_in_full() {
+        let database = database(&[("GREETING", "Hello")]);
+        let (output, source_map) = generate(&database, TypesOutputFormat::TypeScript, None);
+        // Output from a different format doesn't start with the same prelude.
+        let patched = generate(
+            &database,
+            TypesOutputFormat::JsDoc,
+            Some((output, source_map, &[])),
+        );
+        assert_eq!(patched, generate(&database, TypesOutputFormat::JsDoc, None));
+
+        let patched = generate(
+            &database,
+            TypesOutputFormat::TypeScript,
+            Some(("not a types file".into(), "{}".into(), &[])),
+        );
+        assert_eq!(
+            patched,
+            generate(&database, TypesOutputFormat::TypeScript, None)
+        );
+    }
+}
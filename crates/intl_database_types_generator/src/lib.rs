@@ -1,25 +1,99 @@
 mod comment;
+mod incremental;
+mod split;
 mod type_def;
 mod writer;
 
+pub use split::{plan_split_types, SplitTypesFile, SplitTypesPlan, TypesSplitMode};
+pub use writer::TypesOutputFormat;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
 use rustc_hash::FxHashSet;
-use std::fmt::Write;
 use thiserror::Error;
 
 use crate::comment::DocComment;
+use crate::incremental::{PreviousEntry, PreviousOutput};
+use crate::split::relative_path;
 use crate::type_def::TypeDef;
 use crate::writer::{
-    source_map_entry, write_doc, AlphabeticSymbolMap, AlphabeticSymbolSet, TypeDocFormat,
-    TypeDocWriter, WriteResult,
+    write_doc, AlphabeticSymbolMap, AlphabeticSymbolSet, TypeDocFormat, TypeDocWriter, WriteResult,
 };
-use intl_database_core::{KeySymbol, KeySymbolSet, Message, MessagesDatabase};
+use intl_database_core::{
+    collect_message_variables, KeySymbol, KeySymbolSet, Message, MessageValue, MessageVariables,
+    MessagesDatabase,
+};
+use intl_database_exporter::MessagePlaceholderRenames;
 use intl_database_service::IntlDatabaseService;
+use intl_markdown::{substitute_constants, ConstantValue};
+
+/// Settings for generating types that stay the same for every definitions file, like when
+/// generating split types for a whole directory.
+#[derive(Clone, Default)]
+pub struct IntlTypesGeneratorOptions {
+    format: TypesOutputFormat,
+    include_locale_map: bool,
+    include_key_map: bool,
+    constants: HashMap<String, ConstantValue>,
+    placeholder_renames: MessagePlaceholderRenames,
+}
+
+impl IntlTypesGeneratorOptions {
+    pub fn format(&self) -> TypesOutputFormat {
+        self.format
+    }
+
+    /// Set the kind of type definitions file to generate. TypeScript is generated by default.
+    pub fn with_format(mut self, format: TypesOutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Also declare a `messageLocales` map from each message key to the list of locales that
+    /// define a value for it, so that coverage of specific messages can be checked without
+    /// loading the database.
+    pub fn with_locale_map(mut self, include_locale_map: bool) -> Self {
+        self.include_locale_map = include_locale_map;
+        self
+    }
+
+    /// Also declare a `messageKeys` map from the hashed key of each message to its original key,
+    /// so that devtools can show readable keys for the hashed ones in compiled bundles.
+    pub fn with_key_map(mut self, include_key_map: bool) -> Self {
+        self.include_key_map = include_key_map;
+        self
+    }
+
+    /// Leave out variables that are substituted with constants when messages are compiled, using
+    /// the same constants given to the bundler. Variables that are still needed at runtime for
+    /// some use of them, like `{MAX_UPLOAD_MB, number}`, are kept.
+    pub fn with_constants(mut self, constants: HashMap<String, ConstantValue>) -> Self {
+        self.constants = constants;
+        self
+    }
+
+    /// Use the new names of placeholders that are renamed in individual messages, using the same
+    /// renames given to the bundler, so that translations that still use the old names don't add
+    /// them to the types.
+    pub fn with_placeholder_renames(
+        mut self,
+        placeholder_renames: MessagePlaceholderRenames,
+    ) -> Self {
+        self.placeholder_renames = placeholder_renames;
+        self
+    }
+}
 
 pub struct IntlTypesGenerator<'a> {
     database: &'a MessagesDatabase,
     source_file_key: KeySymbol,
     output: TypeDocWriter,
     output_file_path: String,
+    options: IntlTypesGeneratorOptions,
+    relative_sources: bool,
+    previous_output: Option<PreviousOutput>,
 }
 
 impl<'a> IntlTypesGenerator<'a> {
@@ -27,15 +101,42 @@ impl<'a> IntlTypesGenerator<'a> {
         database: &'a MessagesDatabase,
         source_file_key: KeySymbol,
         output_file_path: String,
+        options: IntlTypesGeneratorOptions,
     ) -> Self {
         Self {
             database,
             source_file_key,
-            output: TypeDocWriter::new(),
+            output: TypeDocWriter::new(options.format),
             output_file_path,
+            options,
+            relative_sources: false,
+            previous_output: None,
         }
     }
 
+    /// Refer to the definitions file in the source map by its path relative to the output file,
+    /// and to the output file by its name, so that the files can be moved together.
+    pub fn with_relative_sources(mut self, relative_sources: bool) -> Self {
+        self.relative_sources = relative_sources;
+        self
+    }
+
+    /// Patch the output and source map of a previous run with the same settings instead of
+    /// generating every entry again. Only the entries of messages in `changed_keys`, and of
+    /// messages that reference them, are generated, while the rest are copied from the previous
+    /// output. Any message whose translations or meta changed must be included, and every message
+    /// should be included when the set of known locales changes. If the previous output can't be
+    /// patched, like when it was generated with a different format, it is generated in full.
+    pub fn with_previous_output(
+        mut self,
+        output: String,
+        source_map: String,
+        changed_keys: KeySymbolSet,
+    ) -> Self {
+        self.previous_output = Some(PreviousOutput::new(output, source_map, changed_keys));
+        self
+    }
+
     pub fn take_buffer(&mut self) -> String {
         self.output.take_buffer()
     }
@@ -48,14 +149,25 @@ impl<'a> IntlTypesGenerator<'a> {
     ) -> DocComment<'b> {
         let found_locales: KeySymbolSet = message.translations().keys().map(Clone::clone).collect();
         let missing_locales = known_locales.difference(&found_locales).map(Clone::clone);
+        let source = message.get_source_translation();
+        let untranslated_locales = message
+            .translations()
+            .iter()
+            .filter(|(locale, translation)| {
+                *message.source_locale() != Some(**locale)
+                    && source.is_some_and(|source| translation.is_equivalent(source))
+            })
+            .map(|(locale, _)| *locale);
 
         DocComment {
             key: message.hashed_key(),
-            value: message
-                .get_source_translation()
-                .map(|definition| definition.raw.as_str()),
+            value: source.map(|definition| definition.raw.as_str()),
+            source_locale: message
+                .source_locale()
+                .filter(|locale| *locale != self.database.source_locale),
             description: None,
             missing_translations: AlphabeticSymbolSet::from_iter(missing_locales),
+            untranslated: AlphabeticSymbolSet::from_iter(untranslated_locales),
             is_secret: message.meta().secret,
             ready_to_translate: message.meta().translate,
             spurious_variables,
@@ -74,8 +186,7 @@ impl<'a> IntlTypesGenerator<'a> {
         };
 
         let source_variables = source
-            .variables
-            .as_ref()
+            .variables()
             .map(|variables| variables.get_keys())
             .unwrap_or(FxHashSet::default());
 
@@ -88,11 +199,14 @@ impl<'a> IntlTypesGenerator<'a> {
             AlphabeticSymbolMap::default();
 
         for (locale_key, translation) in message.translations() {
-            if translation == source {
+            if message
+                .source_locale()
+                .is_some_and(|locale| locale == *locale_key)
+            {
                 continue;
             }
 
-            let Some(variables) = &translation.variables else {
+            let Some(variables) = self.value_runtime_variables(message, translation) else {
                 continue;
             };
 
@@ -119,14 +233,163 @@ impl<'a> IntlTypesGenerator<'a> {
     ) -> TypeDef {
         TypeDef {
             name: message.key(),
-            variables: message.all_variables(),
+            variables: self.runtime_variables(message),
             spurious_variable_keys,
+            definition_position: message
+                .get_source_translation()
+                .and_then(|definition| definition.file_position),
+        }
+    }
+
+    /// Return the variables that the runtime needs to format the message, including the ones used
+    /// by every message it references, since their content is included when bundling.
+    fn runtime_variables(&self, message: &Message) -> MessageVariables {
+        let mut merged = self.own_runtime_variables(message);
+        for key in self.database.referenced_message_keys(message.key()) {
+            if let Some(referenced) = self.database.messages.get(&key) {
+                merged.merge(&self.own_runtime_variables(referenced));
+            }
+        }
+        merged
+    }
+
+    /// Return the variables that the runtime needs to format the content of the message itself,
+    /// which is all of them unless some are substituted with constants or renamed.
+    fn own_runtime_variables(&self, message: &Message) -> MessageVariables {
+        if !self.transforms_variables() {
+            return message.all_variables();
+        }
+        let mut merged = message
+            .get_source_translation()
+            .and_then(|source| self.value_runtime_variables(message, source))
+            .map(Cow::into_owned)
+            .unwrap_or_else(MessageVariables::new);
+        for translation in message.translations().values() {
+            if let Some(variables) = self.value_runtime_variables(message, translation) {
+                merged.merge(&variables);
+            }
+        }
+        merged.apply_declared_types(&message.meta().variables);
+        merged
+    }
+
+    /// Returns true if the bundler changes the variables of some messages, by substituting
+    /// constants or renaming placeholders.
+    fn transforms_variables(&self) -> bool {
+        !self.options.constants.is_empty() || !self.options.placeholder_renames.is_empty()
+    }
+
+    /// Return the variables of a single `value` of `message` once it's bundled, with constants
+    /// substituted before placeholders are renamed, the same way the bundler applies them.
+    fn value_runtime_variables<'v>(
+        &self,
+        message: &Message,
+        value: &'v MessageValue,
+    ) -> Option<Cow<'v, MessageVariables>> {
+        if !self.transforms_variables() {
+            return value.variables().map(Cow::Borrowed);
+        }
+        let document = substitute_constants(value.parsed().clone(), &self.options.constants);
+        let document = self
+            .options
+            .placeholder_renames
+            .apply(message.key(), document);
+        collect_message_variables(&document).ok().map(Cow::Owned)
+    }
+
+    /// Returns true if the entry for `message` has to be generated again rather than copied from
+    /// the previous output, because it or a message whose variables it includes has changed.
+    fn needs_regeneration(&self, message: &Message, changed_keys: &KeySymbolSet) -> bool {
+        changed_keys.contains(&message.key())
+            || self
+                .database
+                .referenced_message_keys(message.key())
+                .iter()
+                .any(|key| changed_keys.contains(key))
+    }
+
+    /// Copy `entry` from the previous output to the current position, moving its source map
+    /// entries along with it. The only mapping in an entry points at the definition of `message`,
+    /// which moves whenever messages before it are added to or removed from the definitions file,
+    /// so it is pointed at where the definition is now.
+    fn write_previous_entry(&mut self, entry: &PreviousEntry, message: &Message) {
+        let line_offset = self.output.line as i64 - entry.line as i64;
+        let definition_position = message
+            .get_source_translation()
+            .and_then(|definition| definition.file_position);
+        if let Some(position) = definition_position {
+            for (dst_line, dst_col, _, _) in &entry.mappings {
+                let dst_line = (*dst_line as i64 + line_offset) as u32;
+                self.output.add_raw_source_map_entry(
+                    dst_line,
+                    *dst_col,
+                    position.line - 1,
+                    position.col,
+                );
+            }
+        }
+        self.output.write_verbatim(&entry.text);
+    }
+
+    /// Write the `messageLocales` declaration, listing the locales that define each of the given
+    /// messages in alphabetical order.
+    fn write_locale_map(&mut self, message_keys: &[&KeySymbol]) -> WriteResult {
+        // Flow has no readonly modifier for tuple types.
+        let tuple_prefix = match self.output.format {
+            TypesOutputFormat::Flow => "[",
+            _ => "readonly [",
+        };
+        self.output.write_locale_map_start()?;
+        for message_key in message_keys {
+            let Some(message) = self.database.messages.get(message_key) else {
+                continue;
+            };
+            let locales = AlphabeticSymbolSet::from_iter(message.translations().keys().copied());
+            write_doc!(self.output, ["\n'", *message_key, "': ", tuple_prefix])?;
+            for (index, locale) in locales.iter().enumerate() {
+                if index > 0 {
+                    write_doc!(self.output, [", "])?;
+                }
+                write_doc!(self.output, ["'", locale, "'"])?;
+            }
+            write_doc!(self.output, ["],"])?;
+        }
+        self.output.write_locale_map_end()
+    }
+
+    /// Write the `messageKeys` declaration, mapping the hashed key of each of the given messages
+    /// to its original key.
+    fn write_key_map(&mut self, message_keys: &[&KeySymbol]) -> WriteResult {
+        self.output.write_key_map_start()?;
+        for message_key in message_keys {
+            let Some(message) = self.database.messages.get(message_key) else {
+                continue;
+            };
+            write_doc!(
+                self.output,
+                [
+                    "\n'",
+                    message.hashed_key().as_str(),
+                    "': '",
+                    *message_key,
+                    "',"
+                ]
+            )?;
         }
+        self.output.write_key_map_end()
     }
 
     pub fn into_sourcemap(mut self) -> anyhow::Result<String> {
         let mut result = Vec::with_capacity(self.database.messages.len() * 10);
-        self.output.source_map.set_file(Some(self.output_file_path));
+        let file = match self.relative_sources {
+            true => Path::new(&self.output_file_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            false => None,
+        };
+        self.output
+            .source_map
+            .set_file(Some(file.unwrap_or(self.output_file_path)));
         self.output
             .source_map
             .into_sourcemap()
@@ -155,9 +418,24 @@ impl IntlDatabaseService for IntlTypesGenerator<'_> {
     type Result = WriteResult;
 
     fn run(&mut self) -> Self::Result {
-        self.output.source_map.add_source(&self.source_file_key);
+        let output_directory = Path::new(&self.output_file_path).parent();
+        match output_directory.filter(|_| self.relative_sources) {
+            Some(directory) => {
+                let source = relative_path(directory, Path::new(self.source_file_key.as_str()));
+                self.output
+                    .source_map
+                    .add_source(&source.to_string_lossy().replace('\\', "/"));
+            }
+            None => {
+                self.output.source_map.add_source(&self.source_file_key);
+            }
+        }
         self.output.write_prelude()?;
-        self.output.indent();
+
+        let previous_output = self.previous_output.take();
+        let previous_entries = previous_output
+            .as_ref()
+            .and_then(|previous| previous.entries(self.output.buffer(), self.output.format));
 
         let known_locales = &self.database.known_locales;
         let Some(source_file) = self.database.sources.get(&self.source_file_key) else {
@@ -165,44 +443,171 @@ impl IntlDatabaseService for IntlTypesGenerator<'_> {
         };
 
         let source_message_keys = get_sorted_message_keys(source_file.message_keys());
-        for message_key in source_message_keys {
+        for message_key in &source_message_keys {
             let message = self
                 .database
                 .messages
-                .get(&message_key)
+                .get(message_key)
                 .expect("Expected all source file message keys to have values in the database");
 
+            if let (Some(previous_output), Some(previous_entries)) =
+                (&previous_output, &previous_entries)
+            {
+                let previous_entry = previous_entries.get(*message_key);
+                if let Some(entry) = previous_entry
+                    .filter(|_| !self.needs_regeneration(message, &previous_output.changed_keys))
+                {
+                    self.write_previous_entry(entry, message);
+                    continue;
+                }
+            }
+
             let spurious_variables = self.build_spurious_variables(message);
             let type_def = self.make_getter_type_def(
                 message,
                 spurious_variables.keys().map(Clone::clone).collect(),
             );
             let doc_comment = self.make_doc_comment(message, known_locales, spurious_variables);
-            let definition_position = message
-                .get_source_translation()
-                .and_then(|definition| definition.file_position);
 
             // Ordering is important here. `self.output` tracks the line and column position in the
             // written output, and we want to know that number precisely when adding the source map
-            // entry here. So the doc comment is written first to get it out of the way, then we
-            // know we're at the start of the name token for the message entry, so we can add that
-            // current position to the map.
-            write_doc!(
-                self.output,
-                [
-                    "\n",
-                    &doc_comment,
-                    "\n",
-                    &definition_position.map(source_map_entry),
-                    &type_def,
-                    ","
-                ]
-            )?;
+            // entry here. So the doc comment is written first to get it out of the way, then the
+            // type definition adds the entry itself once it reaches the name token.
+            match self.output.format {
+                // JSDoc properties can't have doc comments of their own, since they are already
+                // inside of one, so the definition becomes the description of the property.
+                TypesOutputFormat::JsDoc => {
+                    write_doc!(self.output, ["\n", &type_def])?;
+                    if let Some(value) = doc_comment.value {
+                        write_doc!(self.output, [" - ", value])?;
+                    }
+                }
+                _ => write_doc!(self.output, ["\n", &doc_comment, "\n", &type_def, ","])?,
+            }
         }
-        self.output.dedent();
 
-        write!(self.output, "\n}};\nexport default messages;")?;
+        self.output.write_messages_end()?;
+
+        if self.options.include_locale_map {
+            self.write_locale_map(&source_message_keys)?;
+        }
+        if self.options.include_key_map {
+            self.write_key_map(&source_message_keys)?;
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{
+        key_symbol, DefinitionFile, FilePosition, MessageMeta, SourceFile, SourceFileMeta,
+        DEFAULT_LOCALE,
+    };
+
+    use super::*;
+
+    /// Generate the types for the given definitions, all from a single file.
+    fn generate(definitions: &[(&str, &str)]) -> String {
+        generate_with(definitions, &[], IntlTypesGeneratorOptions::default())
+    }
+
+    /// Generate the types for the given definitions, along with French `translations` of them.
+    fn generate_with(
+        definitions: &[(&str, &str)],
+        translations: &[(&str, &str)],
+        options: IntlTypesGeneratorOptions,
+    ) -> String {
+        let mut database = MessagesDatabase::new();
+        let file = key_symbol("test.messages.js");
+        let locale = key_symbol(DEFAULT_LOCALE);
+        let mut keys = KeySymbolSet::default();
+        for (key, value) in definitions {
+            let value = MessageValue::from_raw(value).with_file_position(FilePosition {
+                file,
+                line: 1,
+                col: 0,
+            });
+            database
+                .insert_definition(key, value, locale, MessageMeta::default(), false)
+                .unwrap();
+            keys.insert(key_symbol(key));
+        }
+        for (key, value) in translations {
+            database
+                .insert_translation(
+                    key_symbol(key),
+                    key_symbol("fr"),
+                    MessageValue::from_raw(value),
+                    false,
+                )
+                .unwrap();
+        }
+        database.create_source_file(
+            file,
+            SourceFile::Definition(DefinitionFile::new(
+                file.to_string(),
+                SourceFileMeta::new(&file),
+                keys,
+            )),
+        );
+
+        let mut generator =
+            IntlTypesGenerator::new(&database, file, "test.messages.d.ts".into(), options);
+        generator.run().unwrap();
+        generator.take_buffer()
+    }
+
+    #[test]
+    fn test_default_values_are_optional() {
+        let output = generate(&[
+            ("DEFAULT", "Hello, {name|friend}!"),
+            ("MIXED", "{name|friend} and {name}"),
+            ("PLAIN", "Hello, {name}!"),
+        ]);
+        let getter = |key: &str| {
+            let start = output.find(&format!("'{key}'")).unwrap();
+            let end = start + output[start..].find('\n').unwrap();
+            output[start..end].to_string()
+        };
+        assert!(getter("DEFAULT").contains("name?: "), "{output}");
+        // A default only makes the variable optional when every use of it has one.
+        assert!(getter("MIXED").contains("name: "), "{output}");
+        assert!(getter("PLAIN").contains("name: "), "{output}");
+    }
+
+    #[test]
+    fn test_placeholder_renames() {
+        let definitions = [("GREETING", "Hello, {userName}!")];
+        let translations = [("GREETING", "Bonjour, {name} !")];
+        let getter = |output: &str| {
+            let start = output.find("'GREETING'").unwrap();
+            let end = start + output[start..].find('\n').unwrap();
+            output[start..end].to_string()
+        };
+
+        let output = generate_with(
+            &definitions,
+            &translations,
+            IntlTypesGeneratorOptions::default(),
+        );
+        // Variables that only translations use are spurious, and optional in the types.
+        assert!(
+            getter(&output).contains("{name?: any, userName: any}"),
+            "{output}"
+        );
+
+        let renames = MessagePlaceholderRenames::new().with_message_renames(
+            key_symbol("GREETING"),
+            [("name".to_string(), "userName".to_string())],
+        );
+        let output = generate_with(
+            &definitions,
+            &translations,
+            IntlTypesGeneratorOptions::default().with_placeholder_renames(renames),
+        );
+        assert!(getter(&output).contains("{userName: any}"), "{output}");
+        assert!(!output.contains("Spurious variables"), "{output}");
+    }
+}
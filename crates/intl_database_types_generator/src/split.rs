@@ -0,0 +1,384 @@
+//! Generating types for every definitions file in a directory at once, with one declaration file
+//! per definitions file rather than a single file covering all of them, which keeps each file
+//! small enough for editors to load quickly. Barrel index files re-export the declarations so
+//! they can still be imported from one place.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use intl_database_core::{KeySymbol, MessagesDatabase, SourceFile};
+
+use crate::writer::TypesOutputFormat;
+
+/// How the barrel index files of split types are laid out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TypesSplitMode {
+    /// A single index at the root of the output re-exports every declaration file.
+    #[default]
+    File,
+    /// Every directory of the output has an index that re-exports the declaration files in it,
+    /// along with the index of each of its subdirectories.
+    Directory,
+}
+
+/// A declaration file to generate for a single definitions file.
+#[derive(Debug)]
+pub struct SplitTypesFile {
+    pub source_file_key: KeySymbol,
+    pub output_path: PathBuf,
+    /// Name that the barrel index re-exports the messages of the file as, unique across all of
+    /// the split files.
+    pub export_name: String,
+}
+
+/// Every file to write when splitting the types of all definitions files in a directory.
+#[derive(Debug, Default)]
+pub struct SplitTypesPlan {
+    pub files: Vec<SplitTypesFile>,
+    /// Path and content of each barrel index file.
+    pub indexes: Vec<(PathBuf, String)>,
+}
+
+impl TypesOutputFormat {
+    /// Return the path of the declaration file for the definitions file at `source_path`, which
+    /// replaces its extension like `SomeFeature.messages.js` becoming `SomeFeature.messages.d.ts`.
+    pub fn declaration_file_path(&self, source_path: &Path) -> PathBuf {
+        let extension = match self {
+            TypesOutputFormat::TypeScript => "d.ts",
+            TypesOutputFormat::Flow => "js.flow",
+            TypesOutputFormat::JsDoc => "types.js",
+        };
+        source_path.with_extension(extension)
+    }
+
+    fn index_file_name(&self) -> &'static str {
+        match self {
+            TypesOutputFormat::TypeScript => "index.d.ts",
+            TypesOutputFormat::Flow => "index.js.flow",
+            TypesOutputFormat::JsDoc => "index.types.js",
+        }
+    }
+
+    /// Return the specifier that imports the declaration file at `path` from `directory`.
+    /// TypeScript and Flow resolve declarations from the name of the module they describe, while
+    /// JSDoc imports name the typedef file itself.
+    fn import_specifier(&self, directory: &Path, path: &Path) -> String {
+        let module = match self {
+            TypesOutputFormat::JsDoc => path.to_path_buf(),
+            _ => path.with_extension("").with_extension(""),
+        };
+        let specifier = relative_path(directory, &module)
+            .to_string_lossy()
+            .replace('\\', "/");
+        match specifier.starts_with("../") {
+            true => specifier,
+            false => format!("./{specifier}"),
+        }
+    }
+}
+
+/// Plan the declaration files for every definitions file in `database` under `source_root`,
+/// placing each one at the same path relative to `output_root`, along with the barrel index files
+/// for `mode`.
+pub fn plan_split_types(
+    database: &MessagesDatabase,
+    source_root: &Path,
+    output_root: &Path,
+    mode: TypesSplitMode,
+    format: TypesOutputFormat,
+) -> SplitTypesPlan {
+    let mut sources: Vec<(KeySymbol, PathBuf)> = database
+        .sources
+        .iter()
+        .filter(|(_, source)| matches!(source, SourceFile::Definition(_)))
+        .filter_map(|(key, source)| {
+            let relative = Path::new(source.file()).strip_prefix(source_root).ok()?;
+            Some((
+                *key,
+                format.declaration_file_path(&output_root.join(relative)),
+            ))
+        })
+        .collect();
+    sources.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let mut used_names = HashSet::new();
+    let files: Vec<SplitTypesFile> = sources
+        .into_iter()
+        .map(|(source_file_key, output_path)| {
+            let export_name = unique_export_name(&output_path, &mut used_names);
+            SplitTypesFile {
+                source_file_key,
+                output_path,
+                export_name,
+            }
+        })
+        .collect();
+
+    let indexes = match mode {
+        TypesSplitMode::File => {
+            let all: Vec<&SplitTypesFile> = files.iter().collect();
+            vec![write_index(output_root, &all, &[], format)]
+        }
+        TypesSplitMode::Directory => directory_indexes(output_root, &files, format),
+    };
+    SplitTypesPlan { files, indexes }
+}
+
+/// Write an index for every directory that contains a declaration file at any depth, where each
+/// index re-exports the files directly inside of it and the indexes of its subdirectories.
+fn directory_indexes(
+    output_root: &Path,
+    files: &[SplitTypesFile],
+    format: TypesOutputFormat,
+) -> Vec<(PathBuf, String)> {
+    let mut directories: BTreeMap<PathBuf, BTreeSet<PathBuf>> = BTreeMap::new();
+    directories.entry(output_root.to_path_buf()).or_default();
+    for file in files {
+        // Register every directory between the file and the root, so each one links to the next.
+        let mut child = file.output_path.as_path();
+        while let Some(directory) = child.parent() {
+            if child != file.output_path {
+                directories
+                    .entry(directory.to_path_buf())
+                    .or_default()
+                    .insert(child.to_path_buf());
+            }
+            directories.entry(directory.to_path_buf()).or_default();
+            if directory == output_root {
+                break;
+            }
+            child = directory;
+        }
+    }
+
+    directories
+        .iter()
+        .map(|(directory, subdirectories)| match format {
+            // Typedefs can't be re-exported from another file, so each JSDoc index names every
+            // file beneath it directly instead of linking to the indexes of its subdirectories.
+            TypesOutputFormat::JsDoc => {
+                let nested: Vec<&SplitTypesFile> = files
+                    .iter()
+                    .filter(|file| file.output_path.starts_with(directory))
+                    .collect();
+                write_index(directory, &nested, &[], format)
+            }
+            _ => {
+                let direct: Vec<&SplitTypesFile> = files
+                    .iter()
+                    .filter(|file| file.output_path.parent() == Some(directory.as_path()))
+                    .collect();
+                let subdirectories: Vec<PathBuf> = subdirectories.iter().cloned().collect();
+                write_index(directory, &direct, &subdirectories, format)
+            }
+        })
+        .collect()
+}
+
+/// Write the index file in `directory`, re-exporting the messages of each of `files` by their
+/// export name, and everything from the index of each of `subdirectories`.
+fn write_index(
+    directory: &Path,
+    files: &[&SplitTypesFile],
+    subdirectories: &[PathBuf],
+    format: TypesOutputFormat,
+) -> (PathBuf, String) {
+    let mut content = String::from("/* THIS FILE IS AUTOGENERATED. DO NOT EDIT MANUALLY. */\n");
+    if format == TypesOutputFormat::Flow {
+        content.insert_str(0, "// @flow\n");
+    }
+    for file in files {
+        let specifier = format.import_specifier(directory, &file.output_path);
+        let line = match format {
+            TypesOutputFormat::JsDoc => format!(
+                "/** @typedef {{import('{specifier}').Messages}} {} */\n",
+                file.export_name
+            ),
+            _ => format!(
+                "export {{ default as {} }} from '{specifier}';\n",
+                file.export_name
+            ),
+        };
+        content.push_str(&line);
+    }
+    for subdirectory in subdirectories {
+        let index = subdirectory.join(format.index_file_name());
+        let specifier = format.import_specifier(directory, &index);
+        content.push_str(&format!("export * from '{specifier}';\n"));
+    }
+    if format == TypesOutputFormat::JsDoc {
+        content.push_str("\nexport {};\n");
+    }
+    (directory.join(format.index_file_name()), content)
+}
+
+/// Create an identifier from the name of the declaration file at `path`, like `SomeFeatureMessages`
+/// for `SomeFeature.messages.d.ts`, adding a number to the end if another file already uses it.
+fn unique_export_name(path: &Path, used_names: &mut HashSet<String>) -> String {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let stem = file_name
+        .strip_suffix(".d.ts")
+        .or_else(|| file_name.strip_suffix(".js.flow"))
+        .or_else(|| file_name.strip_suffix(".types.js"))
+        .unwrap_or(&file_name);
+    let mut base: String = stem
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '$')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect::<String>()
+        })
+        .collect();
+    if base.is_empty() || base.starts_with(|c: char| c.is_ascii_digit()) {
+        base.insert(0, '_');
+    }
+
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while !used_names.insert(name.clone()) {
+        name = format!("{base}{suffix}");
+        suffix += 1;
+    }
+    name
+}
+
+/// Return the path to `target` relative to the directory `base`. Both paths are expected to be
+/// absolute, or both relative to the same directory.
+pub(crate) fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, DefinitionFile, KeySymbolSet, SourceFileMeta};
+
+    use super::*;
+
+    fn database(paths: &[&str]) -> MessagesDatabase {
+        let mut database = MessagesDatabase::new();
+        for path in paths {
+            let key = key_symbol(path);
+            database.create_source_file(
+                key,
+                SourceFile::Definition(DefinitionFile::new(
+                    path.to_string(),
+                    SourceFileMeta::new(path),
+                    KeySymbolSet::default(),
+                )),
+            );
+        }
+        database
+    }
+
+    fn plan_for(paths: &[&str], mode: TypesSplitMode, format: TypesOutputFormat) -> SplitTypesPlan {
+        plan_split_types(
+            &database(paths),
+            Path::new("/src"),
+            Path::new("/types"),
+            mode,
+            format,
+        )
+    }
+
+    fn index<'a>(plan: &'a SplitTypesPlan, path: &str) -> &'a str {
+        let (_, content) = plan
+            .indexes
+            .iter()
+            .find(|(index, _)| index == Path::new(path))
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_files_keep_their_relative_paths() {
+        let plan = plan_for(
+            &[
+                "/src/settings/Settings.messages.js",
+                "/src/Chat.messages.js",
+                "/src/chat/Chat.messages.js",
+                "/elsewhere/Other.messages.js",
+            ],
+            TypesSplitMode::File,
+            TypesOutputFormat::TypeScript,
+        );
+        let files: Vec<(&Path, &str)> = plan
+            .files
+            .iter()
+            .map(|file| (file.output_path.as_path(), file.export_name.as_str()))
+            .collect();
+        // Files outside of the source root are skipped, and names are made unique.
+        assert_eq!(
+            files,
+            vec![
+                (Path::new("/types/Chat.messages.d.ts"), "ChatMessages"),
+                (Path::new("/types/chat/Chat.messages.d.ts"), "ChatMessages2"),
+                (
+                    Path::new("/types/settings/Settings.messages.d.ts"),
+                    "SettingsMessages"
+                ),
+            ]
+        );
+        assert_eq!(plan.indexes.len(), 1);
+        assert_eq!(
+            index(&plan, "/types/index.d.ts"),
+            "/* THIS FILE IS AUTOGENERATED. DO NOT EDIT MANUALLY. */\n\
+            export { default as ChatMessages } from './Chat.messages';\n\
+            export { default as ChatMessages2 } from './chat/Chat.messages';\n\
+            export { default as SettingsMessages } from './settings/Settings.messages';\n"
+        );
+    }
+
+    #[test]
+    fn test_directory_indexes() {
+        let paths = [
+            "/src/Chat.messages.js",
+            "/src/settings/Settings.messages.js",
+        ];
+        let plan = plan_for(
+            &paths,
+            TypesSplitMode::Directory,
+            TypesOutputFormat::TypeScript,
+        );
+        assert_eq!(plan.indexes.len(), 2);
+        assert_eq!(
+            index(&plan, "/types/index.d.ts"),
+            "/* THIS FILE IS AUTOGENERATED. DO NOT EDIT MANUALLY. */\n\
+            export { default as ChatMessages } from './Chat.messages';\n\
+            export * from './settings/index';\n"
+        );
+        assert_eq!(
+            index(&plan, "/types/settings/index.d.ts"),
+            "/* THIS FILE IS AUTOGENERATED. DO NOT EDIT MANUALLY. */\n\
+            export { default as SettingsMessages } from './Settings.messages';\n"
+        );
+
+        // JSDoc indexes name every file beneath them, since typedefs can't be re-exported.
+        let plan = plan_for(&paths, TypesSplitMode::Directory, TypesOutputFormat::JsDoc);
+        assert_eq!(
+            index(&plan, "/types/index.types.js"),
+            "/* THIS FILE IS AUTOGENERATED. DO NOT EDIT MANUALLY. */\n\
+            /** @typedef {import('./Chat.messages.types.js').Messages} ChatMessages */\n\
+            /** @typedef {import('./settings/Settings.messages.types.js').Messages} SettingsMessages */\n\
+            \n\
+            export {};\n"
+        );
+    }
+}
@@ -1,14 +1,19 @@
 use crate::writer::{
-    write_doc, AlphabeticSymbolMap, AlphabeticSymbolSet, TypeDocFormat, TypeDocWriter, WriteResult,
+    source_map_entry, write_doc, AlphabeticSymbolMap, AlphabeticSymbolSet, TypeDocFormat,
+    TypeDocWriter, TypesOutputFormat, WriteResult,
 };
 use intl_database_core::{
-    KeySymbol, KeySymbolSet, MessageVariableInstance, MessageVariableType, MessageVariables,
+    FilePosition, KeySymbol, KeySymbolSet, MessageVariableInstance, MessageVariableType,
+    MessageVariables,
 };
 
 pub struct TypeDef {
     pub name: KeySymbol,
     pub variables: MessageVariables,
     pub spurious_variable_keys: KeySymbolSet,
+    /// Position of the message's definition, which the name of the entry is mapped to in the
+    /// source map.
+    pub definition_position: Option<FilePosition>,
 }
 
 impl TypeDef {
@@ -26,11 +31,34 @@ impl TypeDef {
 
 impl TypeDocFormat for TypeDef {
     fn fmt(&self, mut w: &mut TypeDocWriter) -> WriteResult {
-        write_doc!(w, ["'", &self.name, "': TypedIntlMessageGetter<{"])?;
+        let map_entry = self.definition_position.map(source_map_entry);
+        match w.format {
+            TypesOutputFormat::JsDoc => write_doc!(
+                w,
+                [
+                    "@property {",
+                    &GetterType(self),
+                    "} ",
+                    &map_entry,
+                    &self.name
+                ]
+            ),
+            _ => write_doc!(w, [&map_entry, "'", &self.name, "': ", &GetterType(self)]),
+        }
+    }
+}
+
+/// The `TypedIntlMessageGetter` type of a message, including all of its variables.
+struct GetterType<'a>(&'a TypeDef);
+
+impl TypeDocFormat for GetterType<'_> {
+    fn fmt(&self, mut w: &mut TypeDocWriter) -> WriteResult {
+        let GetterType(def) = self;
+        write_doc!(w, ["TypedIntlMessageGetter<{"])?;
 
         let mut sorted_map: AlphabeticSymbolMap<AlphabeticSymbolSet> = AlphabeticSymbolMap::new();
-        for (name, variable) in self.variables.iter() {
-            sorted_map.insert(*name, self.get_total_type_from_variable_instances(variable));
+        for (name, variable) in def.variables.iter() {
+            sorted_map.insert(*name, def.get_total_type_from_variable_instances(variable));
         }
 
         let mut is_first = true;
@@ -46,8 +74,10 @@ impl TypeDocFormat for TypeDef {
             let is_builtin = name.starts_with("$");
             // TODO: These types shouldn't actually be optional, as they'll crash at runtime.
             // Optionality is just a migration step.
-            let is_optional = self.spurious_variable_keys.contains(&name);
-            let undefinable = is_optional || is_builtin;
+            let is_optional = def.spurious_variable_keys.contains(&name);
+            // Variables with a default value everywhere they are used can be left out entirely.
+            let has_default = def.variables.has_default(&name);
+            let undefinable = is_optional || is_builtin || has_default;
             write_doc!(w, [&name, &undefinable.then_some("?"), ": "])?;
             let mut is_first_type = true;
             for ty in types {
@@ -65,7 +95,7 @@ fn add_strict_type_name(set: &mut AlphabeticSymbolSet, kind: &MessageVariableTyp
         MessageVariableType::Any => {
             set.insert("any".into());
         }
-        MessageVariableType::Number => {
+        MessageVariableType::Number(_) => {
             // Number allows both `number` and `string`, because `Intl.NumberFormat` is able to
             // internally parse the string into a number before formatting. Note that this _only_
             // applies to number formatting and does not happen for dates or times or other values.
@@ -6,6 +6,29 @@ use std::fmt::Write;
 pub(crate) type AlphabeticSymbolSet = BTreeSet<KeySymbol>;
 pub(crate) type AlphabeticSymbolMap<V> = BTreeMap<KeySymbol, V>;
 
+/// Types from the runtime package that are referenced by the generated definitions.
+const RUNTIME_TYPE_NAMES: [&str; 5] = [
+    "MessageLoader",
+    "TypedIntlMessageGetter",
+    "HandlerFunction",
+    "HookFunction",
+    "LinkFunction",
+];
+
+/// The kind of type definitions file to generate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TypesOutputFormat {
+    /// A TypeScript declaration file, like `SomeFeature.messages.d.ts`.
+    #[default]
+    TypeScript,
+    /// A Flow declaration file, like `SomeFeature.messages.js.flow`.
+    Flow,
+    /// A plain JavaScript file with JSDoc typedefs, which TypeScript can also read when checking
+    /// JavaScript files. Only the types are declared, as `Messages`, `MessageLocales`, and
+    /// `MessageKeys`.
+    JsDoc,
+}
+
 /// Struct for writing code to an output buffer with some basic utilities to help with writing
 /// documentation comments. This is not a _formatter_, as in it does not process text to decide
 /// how it should look. Callers are responsible for laying out text in terms of lines and columns.
@@ -17,10 +40,11 @@ pub(crate) struct TypeDocWriter {
     line_prefix: String,
     prefix_stack: Vec<usize>,
     pub source_map: SourceMapBuilder,
+    pub format: TypesOutputFormat,
 }
 
 impl TypeDocWriter {
-    pub fn new() -> Self {
+    pub fn new(format: TypesOutputFormat) -> Self {
         Self {
             output: String::new(),
             // This is primarily for sourcemaps, which _seem_ to use 1-based indexing for the
@@ -31,6 +55,7 @@ impl TypeDocWriter {
             line_prefix: String::with_capacity(8),
             prefix_stack: Vec::with_capacity(8),
             source_map: SourceMapBuilder::new(None),
+            format,
         }
     }
 
@@ -38,6 +63,24 @@ impl TypeDocWriter {
         std::mem::take(&mut self.output)
     }
 
+    /// Everything written so far.
+    pub fn buffer(&self) -> &str {
+        &self.output
+    }
+
+    /// Write `text` exactly as given, without adding the line prefix after each newline, like for
+    /// content copied from a previous output that already includes its prefixes.
+    pub fn write_verbatim(&mut self, text: &str) {
+        self.output.push_str(text);
+        match text.rfind('\n') {
+            Some(last_newline) => {
+                self.line += text.matches('\n').count();
+                self.col = text.len() - last_newline - 1;
+            }
+            None => self.col += text.len(),
+        }
+    }
+
     pub fn add_source_map_entry(&mut self, definition_position: FilePosition) {
         self.source_map.add_raw(
             self.line as u32,
@@ -52,20 +95,153 @@ impl TypeDocWriter {
         );
     }
 
+    /// Add a source map entry that was already computed, like one copied from a previous output.
+    pub fn add_raw_source_map_entry(
+        &mut self,
+        dst_line: u32,
+        dst_col: u32,
+        src_line: u32,
+        src_col: u32,
+    ) {
+        self.source_map
+            .add_raw(dst_line, dst_col, src_line, src_col, Some(0), None, false);
+    }
+
+    /// Write the header of the file and open the declaration of the messages object, leaving the
+    /// writer ready for each message entry to be written on a new line.
     pub fn write_prelude(&mut self) -> WriteResult {
-        write!(self,
+        let package = intl_message_utils::RUNTIME_PACKAGE_NAME;
+        if self.format == TypesOutputFormat::Flow {
+            writeln!(self, "// @flow")?;
+        }
+        write!(
+            self,
             "/* THIS FILE IS AUTOGENERATED. DO NOT EDIT MANUALLY. */
 /* eslint-disable */
 /* prettier-ignore */
 
-import {{MessageLoader, TypedIntlMessageGetter, HandlerFunction, HookFunction, LinkFunction}} from '{}';
+"
+        )?;
+
+        let type_names = RUNTIME_TYPE_NAMES.join(", ");
+        match self.format {
+            TypesOutputFormat::TypeScript => write!(
+                self,
+                "import {{{type_names}}} from '{package}';
 
 export declare const messagesLoader: MessageLoader;
 
-declare const messages: {{",
-            intl_message_utils::RUNTIME_PACKAGE_NAME
+declare const messages: {{"
+            )?,
+            TypesOutputFormat::Flow => write!(
+                self,
+                "import type {{{type_names}}} from '{package}';
+
+declare export var messagesLoader: MessageLoader;
+
+declare export default {{"
+            )?,
+            TypesOutputFormat::JsDoc => write!(
+                self,
+                "/** @import {{{type_names}}} from '{package}' */
+
+export {{}};
+
+/**
+ * @typedef {{Object}} Messages"
+            )?,
+        }
+        self.begin_entries();
+        Ok(())
+    }
+
+    /// Close the declaration of the messages object opened by [Self::write_prelude].
+    pub fn write_messages_end(&mut self) -> WriteResult {
+        self.end_entries();
+        match self.format {
+            TypesOutputFormat::TypeScript => write!(self, "\n}};\nexport default messages;"),
+            TypesOutputFormat::Flow => write!(self, "\n}};"),
+            TypesOutputFormat::JsDoc => write!(self, "\n */"),
+        }
+    }
+
+    /// Write the opening of the `messageLocales` declaration, leaving the writer ready for each
+    /// entry to be written on a new line.
+    pub fn write_locale_map_start(&mut self) -> WriteResult {
+        self.write_map_start(
+            "Locales that define a value for each message.",
+            "messageLocales",
         )
     }
+
+    /// Close the `messageLocales` declaration opened by [Self::write_locale_map_start].
+    pub fn write_locale_map_end(&mut self) -> WriteResult {
+        self.write_map_end("MessageLocales")
+    }
+
+    /// Write the opening of the `messageKeys` declaration, leaving the writer ready for each
+    /// entry to be written on a new line.
+    pub fn write_key_map_start(&mut self) -> WriteResult {
+        self.write_map_start(
+            "The original key of each hashed message key.",
+            "messageKeys",
+        )
+    }
+
+    /// Close the `messageKeys` declaration opened by [Self::write_key_map_start].
+    pub fn write_key_map_end(&mut self) -> WriteResult {
+        self.write_map_end("MessageKeys")
+    }
+
+    /// Open the declaration of a constant object called `name`. JSDoc can only declare types, so
+    /// it opens a typedef instead, which is named when [Self::write_map_end] closes it.
+    fn write_map_start(&mut self, description: &str, name: &str) -> WriteResult {
+        match self.format {
+            TypesOutputFormat::TypeScript => write!(
+                self,
+                "\n\n/** {description} */\nexport declare const {name}: {{"
+            )?,
+            TypesOutputFormat::Flow => write!(
+                self,
+                "\n\n/** {description} */\ndeclare export var {name}: {{"
+            )?,
+            TypesOutputFormat::JsDoc => {
+                write!(self, "\n\n/**\n * {description}\n * @typedef {{{{")?
+            }
+        }
+        self.begin_entries();
+        if self.format == TypesOutputFormat::JsDoc {
+            self.indent();
+        }
+        Ok(())
+    }
+
+    fn write_map_end(&mut self, typedef_name: &str) -> WriteResult {
+        if self.format == TypesOutputFormat::JsDoc {
+            self.dedent();
+        }
+        self.end_entries();
+        match self.format {
+            TypesOutputFormat::JsDoc => write!(self, "\n * }}}} {typedef_name}\n */"),
+            _ => write!(self, "\n}};"),
+        }
+    }
+
+    /// Entries are indented inside of TypeScript and Flow declarations, while JSDoc entries are
+    /// each a line of the surrounding comment.
+    fn begin_entries(&mut self) {
+        match self.format {
+            TypesOutputFormat::JsDoc => self.push_prefix(" * "),
+            _ => self.indent(),
+        }
+    }
+
+    fn end_entries(&mut self) {
+        match self.format {
+            TypesOutputFormat::JsDoc => self.pop_prefix(),
+            _ => self.dedent(),
+        }
+    }
 }
 
 // Internal API
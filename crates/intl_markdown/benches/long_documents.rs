@@ -8,7 +8,7 @@ fn parse_to_ast(content: &str, include_blocks: bool) -> Document {
     let mut parser = ICUMarkdownParser::new(content, include_blocks);
     let source = parser.source().clone();
     parser.parse();
-    let document = parser.into_cst();
+    let document = parser.into_cst().unwrap();
     process_cst_to_ast(source, &document)
 }
 
@@ -0,0 +1,17 @@
+//! Classification of CJK characters, using the same tables that the parser uses for emphasis next
+//! to CJK text.
+//!
+//! The tables treat every ASCII character as CJK punctuation, so ASCII is always excluded here.
+
+/// Returns true if `c` is a letter from a CJK script, like a Han ideograph, kana, or hangul, but
+/// not CJK punctuation or full-width forms.
+pub fn is_cjk_letter(c: char) -> bool {
+    !c.is_ascii()
+        && (cjk::is_japanese_codepoint(c) || cjk::is_korean_codepoint(c))
+        && !cjk::is_cjk_punctuation_codepoint(c)
+}
+
+/// Returns true if `c` is CJK punctuation, like `。` or `「`, including full-width forms.
+pub fn is_cjk_punctuation(c: char) -> bool {
+    !c.is_ascii() && cjk::is_cjk_punctuation_codepoint(c)
+}
@@ -0,0 +1,170 @@
+//! Counting and expanding the distinct sentences that a message can produce.
+//!
+//! Every plural and select chooses one of its arms, so a message with a plural of 3 arms next to a
+//! select of 2 arms can produce 6 different sentences, and nesting multiplies them further within
+//! each arm.
+//!
+//! Translators have to write every one of those sentences, and some translation vendors can't
+//! work with nested plurals and selects at all, so messages can be expanded into one document per
+//! combination of arms, with all of the plurals and selects removed.
+use super::fold::{fold_document, Fold};
+use super::{BlockNode, Document, Icu, IcuNumber, IcuPluralArm, IcuVariable, InlineContent};
+
+/// Returns the number of distinct combinations of plural and select arms in `document`.
+///
+/// Sibling plurals and selects multiply the count, while the arms of a single one add to it, so
+/// nested values are only counted within the arm that contains them.
+pub fn count_combinations(document: &Document) -> usize {
+    document
+        .blocks()
+        .iter()
+        .map(|block| match block {
+            BlockNode::Paragraph(paragraph) => count_content(paragraph.content()),
+            BlockNode::Heading(heading) => count_content(heading.content()),
+            BlockNode::InlineContent(content) => count_content(content),
+            BlockNode::CodeBlock(_) | BlockNode::ThematicBreak | BlockNode::Error(_) => 1,
+        })
+        .fold(1, usize::saturating_mul)
+}
+
+fn count_content(content: &[InlineContent]) -> usize {
+    content
+        .iter()
+        .map(|node| match node {
+            InlineContent::Emphasis(emphasis) => count_content(emphasis.content()),
+            InlineContent::Strong(strong) => count_content(strong.content()),
+            InlineContent::Strikethrough(strikethrough) => count_content(strikethrough.content()),
+            InlineContent::Link(link) => count_content(link.label()),
+            InlineContent::Hook(hook) => count_content(hook.content()),
+            InlineContent::Icu(Icu::IcuPlural(plural)) => count_arms(plural.arms()),
+            InlineContent::Icu(Icu::IcuSelect(select)) => count_arms(select.arms()),
+            _ => 1,
+        })
+        .fold(1, usize::saturating_mul)
+}
+
+fn count_arms(arms: &[IcuPluralArm]) -> usize {
+    arms.iter()
+        .map(|arm| count_content(arm.content()))
+        .fold(0, usize::saturating_add)
+}
+
+/// A single sentence that a message can produce, with every plural and select replaced by the
+/// content of one of its arms.
+#[derive(Clone, Debug)]
+pub struct MessageCombination {
+    /// The name of each plural and select variable along with the selector of the arm chosen for
+    /// it, in the order they appear in the message.
+    pub selections: Vec<(String, String)>,
+    pub document: Document,
+}
+
+/// Expand `document` into every combination of its plural and select arms.
+///
+/// Combinations are in the order the arms are written. The `#` inside of plural arms is replaced
+/// with a number placeholder for the plural's variable, like `{count, number}`, so that each
+/// combination still shows the value.
+///
+/// The number of results is [count_combinations], which grows quickly with nesting, so documents
+/// with more than `limit` combinations return `None` without expanding any of them.
+pub fn expand_combinations(document: &Document, limit: usize) -> Option<Vec<MessageCombination>> {
+    if count_combinations(document) > limit {
+        return None;
+    }
+
+    let mut combinations = vec![];
+    let mut choices = vec![];
+    loop {
+        let mut folder = CombinationFolder {
+            choices: &choices,
+            chosen: vec![],
+            selections: vec![],
+            pound_variables: vec![],
+        };
+        let expanded = fold_document(document.clone(), &mut folder);
+        let CombinationFolder {
+            chosen, selections, ..
+        } = folder;
+        combinations.push(MessageCombination {
+            selections,
+            document: expanded,
+        });
+
+        // Advance the last choice that has another arm left, like an odometer. Any choices after
+        // it belong to arms that may no longer be chosen, so they start over from the first arm.
+        let Some(next) = chosen
+            .iter()
+            .rposition(|(choice, arm_count)| choice + 1 < *arm_count)
+        else {
+            return Some(combinations);
+        };
+        choices = chosen[..next].iter().map(|(choice, _)| *choice).collect();
+        choices.push(chosen[next].0 + 1);
+    }
+}
+
+struct CombinationFolder<'a> {
+    /// The arm to choose for each plural or select, in the order they are encountered. Any past
+    /// the end of the list choose their first arm.
+    choices: &'a [usize],
+    /// The arm that was chosen for each plural or select that was encountered, along with how
+    /// many arms it had.
+    chosen: Vec<(usize, usize)>,
+    selections: Vec<(String, String)>,
+    /// Variables of the plurals that the folder is currently inside of, which `#` refers to.
+    pound_variables: Vec<(IcuVariable, bool)>,
+}
+
+impl Fold for CombinationFolder<'_> {
+    fn fold_inline_content_list(&mut self, nodes: Vec<InlineContent>) -> Vec<InlineContent> {
+        let mut result = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let (variable, arms, is_plural) = match node {
+                InlineContent::Icu(Icu::IcuPlural(plural)) => {
+                    let is_unsafe = plural.is_unsafe();
+                    (plural.variable, plural.arms, Some(is_unsafe))
+                }
+                InlineContent::Icu(Icu::IcuSelect(select)) => (select.variable, select.arms, None),
+                InlineContent::IcuPound => {
+                    result.push(match self.pound_variables.last() {
+                        Some((variable, is_unsafe)) => {
+                            InlineContent::Icu(Icu::IcuNumber(IcuNumber {
+                                variable: variable.clone(),
+                                style: None,
+                                is_unsafe: *is_unsafe,
+                            }))
+                        }
+                        None => InlineContent::IcuPound,
+                    });
+                    continue;
+                }
+                node => {
+                    result.push(self.fold_inline_content(node));
+                    continue;
+                }
+            };
+
+            let choice = self
+                .choices
+                .get(self.chosen.len())
+                .copied()
+                .unwrap_or(0)
+                .min(arms.len().saturating_sub(1));
+            self.chosen.push((choice, arms.len()));
+            let Some(arm) = arms.into_iter().nth(choice) else {
+                continue;
+            };
+            self.selections
+                .push((variable.name().to_string(), arm.selector().to_string()));
+
+            if let Some(is_unsafe) = is_plural {
+                self.pound_variables.push((variable, is_unsafe));
+            }
+            result.extend(self.fold_inline_content_list(arm.content));
+            if is_plural.is_some() {
+                self.pound_variables.pop();
+            }
+        }
+        result
+    }
+}
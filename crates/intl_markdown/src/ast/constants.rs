@@ -0,0 +1,142 @@
+//! Substituting constants replaces placeholders with values known when messages are compiled.
+//!
+//! Constants are values like a product name or an upload limit. Once they are substituted, the
+//! runtime doesn't need to be given them and doesn't have to format them for every message.
+//!
+//! Compiling happens without any locale data, so constants are only substituted where the result
+//! is the same in every locale:
+//!
+//! - Plain placeholders, like `{PRODUCT_NAME}`, become text, the same way the runtime turns a
+//!   value into a string.
+//! - Selects are replaced by the content of the arm matching the value, or `other`.
+//! - Plurals are replaced by the content of the exact arm matching the value, like `=0`, as long
+//!   as that arm doesn't show the number with `#`.
+//!
+//! Anything else, like `{MAX_UPLOAD_MB, number}` or a plural that needs the locale's plural rules
+//! to choose an arm, is left in place and still needs the value at runtime.
+use std::collections::HashMap;
+
+use super::fold::{fold_document, fold_inline_content_children, Fold};
+use super::{Document, Icu, IcuPluralArm, InlineContent};
+
+/// The value of a constant that can be substituted into messages.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstantValue {
+    Text(String),
+    Number(f64),
+}
+
+impl ConstantValue {
+    /// The text of the value as the runtime would show it in a plain placeholder, which for
+    /// numbers matches how JavaScript turns them into strings, like `5` rather than `5.0`.
+    pub fn to_text(&self) -> String {
+        match self {
+            ConstantValue::Text(text) => text.clone(),
+            ConstantValue::Number(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+                format!("{}", *number as i64)
+            }
+            ConstantValue::Number(number) => number.to_string(),
+        }
+    }
+}
+
+/// Replace every placeholder for one of the given `constants` in `document` with its value,
+/// wherever the result doesn't depend on the locale.
+pub fn substitute_constants(
+    document: Document,
+    constants: &HashMap<String, ConstantValue>,
+) -> Document {
+    if constants.is_empty() {
+        return document;
+    }
+    fold_document(document, &mut ConstantsFolder(constants))
+}
+
+struct ConstantsFolder<'a>(&'a HashMap<String, ConstantValue>);
+
+impl ConstantsFolder<'_> {
+    /// Return the content that `icu` should be replaced with, or give it back when it can't be
+    /// substituted.
+    fn substitute(&self, icu: Icu) -> Result<Vec<InlineContent>, Icu> {
+        match icu {
+            Icu::IcuVariable(variable) => match self.0.get(variable.name()) {
+                Some(value) => Ok(vec![InlineContent::Text(value.to_text())]),
+                None => Err(Icu::IcuVariable(variable)),
+            },
+            Icu::IcuSelect(select) => {
+                let Some(value) = self.0.get(select.name()) else {
+                    return Err(Icu::IcuSelect(select));
+                };
+                let value = value.to_text();
+                let position = select
+                    .arms
+                    .iter()
+                    .position(|arm| arm.selector() == value)
+                    .or_else(|| select.arms.iter().position(|arm| arm.selector() == "other"));
+                match position {
+                    Some(position) => Ok(select.arms.into_iter().nth(position).unwrap().content),
+                    None => Err(Icu::IcuSelect(select)),
+                }
+            }
+            Icu::IcuPlural(plural) => {
+                let Some(ConstantValue::Number(value)) = self.0.get(plural.name()) else {
+                    return Err(Icu::IcuPlural(plural));
+                };
+                let position = plural.arms.iter().position(|arm| {
+                    is_exact_match(arm, *value) && !shows_plural_value(&arm.content)
+                });
+                match position {
+                    Some(position) => Ok(plural.arms.into_iter().nth(position).unwrap().content),
+                    None => Err(Icu::IcuPlural(plural)),
+                }
+            }
+            icu => Err(icu),
+        }
+    }
+}
+
+impl Fold for ConstantsFolder<'_> {
+    fn fold_inline_content_list(&mut self, nodes: Vec<InlineContent>) -> Vec<InlineContent> {
+        let mut result = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let InlineContent::Icu(icu) = node else {
+                result.push(fold_inline_content_children(self, node));
+                continue;
+            };
+            match self.substitute(icu) {
+                // The chosen arm can contain constants of its own.
+                Ok(content) => result.extend(self.fold_inline_content_list(content)),
+                Err(icu) => {
+                    result.push(fold_inline_content_children(self, InlineContent::Icu(icu)))
+                }
+            }
+        }
+        result
+    }
+}
+
+fn is_exact_match(arm: &IcuPluralArm, value: f64) -> bool {
+    arm.selector()
+        .strip_prefix('=')
+        .and_then(|exact| exact.parse::<f64>().ok())
+        .is_some_and(|exact| exact == value)
+}
+
+/// Returns true if `content` shows the value of the plural it belongs to with `#`, including
+/// inside of formatting and selects, but not inside of nested plurals, where `#` refers to their
+/// own value instead.
+fn shows_plural_value(content: &[InlineContent]) -> bool {
+    content.iter().any(|node| match node {
+        InlineContent::IcuPound => true,
+        InlineContent::Emphasis(emphasis) => shows_plural_value(emphasis.content()),
+        InlineContent::Strong(strong) => shows_plural_value(strong.content()),
+        InlineContent::Strikethrough(strikethrough) => shows_plural_value(strikethrough.content()),
+        InlineContent::Link(link) => shows_plural_value(link.label()),
+        InlineContent::Hook(hook) => shows_plural_value(hook.content()),
+        InlineContent::Icu(Icu::IcuSelect(select)) => select
+            .arms()
+            .iter()
+            .any(|arm| shows_plural_value(arm.content())),
+        _ => false,
+    })
+}
@@ -0,0 +1,50 @@
+//! Detection of emoji shortcodes like `:smile:` in plain text, following the same rules that the
+//! parser uses when the `emoji_shortcodes` option is enabled.
+//!
+//! Messages are usually parsed without that option, leaving shortcodes as plain text, so detecting
+//! them directly is useful on its own, like checking that translations keep every shortcode from
+//! their source message.
+use std::ops::Range;
+
+use crate::byte_lookup::byte_is_emoji_name;
+
+/// Returns an iterator over the byte ranges of every emoji shortcode in `text`, including the
+/// surrounding colons.
+pub fn find_emoji_shortcodes(text: &str) -> EmojiShortcodes<'_> {
+    EmojiShortcodes { text, position: 0 }
+}
+
+pub struct EmojiShortcodes<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Iterator for EmojiShortcodes<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        while let Some(offset) = self.text[self.position..].find(':') {
+            let start = self.position + offset;
+            self.position = start + 1;
+            // Shortcodes can't directly follow a letter or digit, like in `10:30:00`.
+            if self.text[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric)
+            {
+                continue;
+            }
+
+            let name_length = self.text.as_bytes()[self.position..]
+                .iter()
+                .take_while(|byte| byte_is_emoji_name(**byte))
+                .count();
+            let end = self.position + name_length;
+            if name_length > 0 && self.text.as_bytes().get(end) == Some(&b':') {
+                self.position = end + 1;
+                return Some(start..self.position);
+            }
+        }
+        None
+    }
+}
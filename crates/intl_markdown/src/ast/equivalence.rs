@@ -0,0 +1,102 @@
+//! Comparing messages by what they say rather than how they are written.
+//!
+//! The same message can be written in many ways that all mean exactly the same thing, like escaping
+//! a character that didn't need it, writing a heading with `#` rather than underlining it, or
+//! leaving a trailing newline at the end of the value. Comparing the raw content of two messages
+//! treats all of those as different, so comparisons that care about what a message says, like
+//! whether a translation is just a copy of its source, compare the parsed documents instead.
+//!
+//! Two documents are equivalent when they are the same after ignoring this trivia:
+//!
+//! - How text is split up by escapes and entities, since the decoded text is the same.
+//! - Whitespace at the very start and end of the message, and `\r\n` line endings.
+//! - Whether a message with a single paragraph was parsed as a block or as inline content.
+//! - Setext or ATX headings, and indented or fenced code blocks without an info string, along with
+//!   any blank lines at the end of a code block.
+use super::fold::{fold_document, fold_inline_content_children, Fold};
+use super::{BlockNode, Document, InlineContent};
+
+/// Returns true if `a` and `b` have the same content, ignoring any differences in how that
+/// content was written.
+pub fn documents_equivalent(a: &Document, b: &Document) -> bool {
+    let a = normalized_blocks(a);
+    let b = normalized_blocks(b);
+    a.len() == b.len() && a.iter().zip(&b).all(|(a, b)| blocks_equivalent(a, b))
+}
+
+fn blocks_equivalent(a: &BlockNode, b: &BlockNode) -> bool {
+    match (a, b) {
+        (BlockNode::Paragraph(a), BlockNode::Paragraph(b)) => a.content() == b.content(),
+        (BlockNode::InlineContent(a), BlockNode::InlineContent(b)) => a == b,
+        (BlockNode::Heading(a), BlockNode::Heading(b)) => {
+            a.level() == b.level() && a.content() == b.content()
+        }
+        (BlockNode::CodeBlock(a), BlockNode::CodeBlock(b)) => {
+            a.info_string() == b.info_string()
+                && a.content().trim_end_matches('\n') == b.content().trim_end_matches('\n')
+        }
+        (BlockNode::ThematicBreak, BlockNode::ThematicBreak) => true,
+        (BlockNode::Error(a), BlockNode::Error(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Return the blocks of `document` with all of its trivia removed, ready to be compared.
+fn normalized_blocks(document: &Document) -> Vec<BlockNode> {
+    let mut blocks = fold_document(document.clone(), &mut TriviaFolder).blocks;
+    if let [BlockNode::Paragraph(paragraph)] = blocks.as_mut_slice() {
+        blocks = vec![BlockNode::InlineContent(std::mem::take(&mut paragraph.0))];
+    }
+
+    if let Some(content) = blocks.first_mut().and_then(block_content_mut) {
+        if let Some(InlineContent::Text(text)) = content.first_mut() {
+            *text = text.trim_start().to_string();
+        }
+        content.retain(|node| !matches!(node, InlineContent::Text(text) if text.is_empty()));
+    }
+    if let Some(content) = blocks.last_mut().and_then(block_content_mut) {
+        if let Some(InlineContent::Text(text)) = content.last_mut() {
+            text.truncate(text.trim_end().len());
+        }
+        content.retain(|node| !matches!(node, InlineContent::Text(text) if text.is_empty()));
+    }
+    blocks
+}
+
+fn block_content_mut(block: &mut BlockNode) -> Option<&mut Vec<InlineContent>> {
+    match block {
+        BlockNode::Paragraph(paragraph) => Some(&mut paragraph.0),
+        BlockNode::Heading(heading) => Some(&mut heading.content),
+        BlockNode::InlineContent(content) => Some(content),
+        BlockNode::CodeBlock(_) | BlockNode::ThematicBreak | BlockNode::Error(_) => None,
+    }
+}
+
+/// Joins adjacent text nodes and normalizes line endings, so that text reads the same no matter
+/// how it was split up while parsing.
+struct TriviaFolder;
+
+impl Fold for TriviaFolder {
+    fn fold_inline_content_list(&mut self, nodes: Vec<InlineContent>) -> Vec<InlineContent> {
+        let mut result: Vec<InlineContent> = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let node = fold_inline_content_children(self, node);
+            if let (Some(InlineContent::Text(previous)), InlineContent::Text(text)) =
+                (result.last_mut(), &node)
+            {
+                previous.push_str(text);
+                continue;
+            }
+            result.push(node);
+        }
+        result
+    }
+
+    fn fold_text(&mut self, text: String) -> String {
+        if text.contains('\r') {
+            text.replace("\r\n", "\n")
+        } else {
+            text
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Folding transforms a Document by taking ownership of each node and returning a replacement.
+//!
+//! The tree is rebuilt from the bottom up. This is the mutable counterpart to visiting, used for
+//! rewriting messages after they are parsed, like replacing straight quotes with typographic ones
+//! or renaming placeholders before a message is compiled.
+//!
+//! Every method of [Fold] defaults to folding the children of the node and returning it otherwise
+//! unchanged. Implementations override only the nodes they care about, and can call the matching
+//! `fold_*_children` function to keep folding into the children of those nodes as well.
+use super::{
+    BlockNode, Document, Emphasis, Heading, Hook, Icu, IcuDate, IcuNumber, IcuPlural, IcuPluralArm,
+    IcuSelect, IcuTime, IcuVariable, InlineContent, Link, LinkDestination, Mention, Paragraph,
+    Strikethrough, Strong,
+};
+
+pub trait Fold {
+    fn fold_document(&mut self, node: Document) -> Document {
+        fold_document_children(self, node)
+    }
+    fn fold_block_node(&mut self, node: BlockNode) -> BlockNode {
+        fold_block_node_children(self, node)
+    }
+    /// Fold a list of inline content, like the content of a paragraph or a link label. Folding
+    /// the whole list at once allows adding or removing elements around the folded nodes.
+    fn fold_inline_content_list(&mut self, nodes: Vec<InlineContent>) -> Vec<InlineContent> {
+        nodes
+            .into_iter()
+            .map(|node| self.fold_inline_content(node))
+            .collect()
+    }
+    fn fold_inline_content(&mut self, node: InlineContent) -> InlineContent {
+        fold_inline_content_children(self, node)
+    }
+    /// Fold the visible text of a message. Code spans and code blocks are never passed here, since
+    /// their content is meant to be shown exactly as written.
+    fn fold_text(&mut self, text: String) -> String {
+        text
+    }
+    fn fold_link(&mut self, node: Link) -> Link {
+        fold_link_children(self, node)
+    }
+    fn fold_hook(&mut self, node: Hook) -> Hook {
+        fold_hook_children(self, node)
+    }
+    fn fold_mention(&mut self, node: Mention) -> Mention {
+        fold_mention_children(self, node)
+    }
+    fn fold_icu(&mut self, node: Icu) -> Icu {
+        fold_icu_children(self, node)
+    }
+    fn fold_icu_plural_arm(&mut self, node: IcuPluralArm) -> IcuPluralArm {
+        fold_icu_plural_arm_children(self, node)
+    }
+    fn fold_icu_variable(&mut self, node: IcuVariable) -> IcuVariable {
+        node
+    }
+}
+
+/// Fold every node of `document` with `folder`, returning the transformed document.
+pub fn fold_document<F: Fold>(document: Document, folder: &mut F) -> Document {
+    folder.fold_document(document)
+}
+
+pub fn fold_document_children<F: Fold + ?Sized>(folder: &mut F, node: Document) -> Document {
+    Document {
+        blocks: node
+            .blocks
+            .into_iter()
+            .map(|block| folder.fold_block_node(block))
+            .collect(),
+        diagnostics: node.diagnostics,
+    }
+}
+
+pub fn fold_block_node_children<F: Fold + ?Sized>(folder: &mut F, node: BlockNode) -> BlockNode {
+    match node {
+        BlockNode::Paragraph(paragraph) => {
+            BlockNode::Paragraph(Paragraph(folder.fold_inline_content_list(paragraph.0)))
+        }
+        BlockNode::Heading(heading) => BlockNode::Heading(Heading {
+            content: folder.fold_inline_content_list(heading.content),
+            ..heading
+        }),
+        BlockNode::InlineContent(content) => {
+            BlockNode::InlineContent(folder.fold_inline_content_list(content))
+        }
+        BlockNode::CodeBlock(_) | BlockNode::ThematicBreak | BlockNode::Error(_) => node,
+    }
+}
+
+pub fn fold_inline_content_children<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: InlineContent,
+) -> InlineContent {
+    match node {
+        InlineContent::Text(text) => InlineContent::Text(folder.fold_text(text)),
+        InlineContent::Emphasis(emphasis) => {
+            InlineContent::Emphasis(Emphasis(folder.fold_inline_content_list(emphasis.0)))
+        }
+        InlineContent::Strong(strong) => {
+            InlineContent::Strong(Strong(folder.fold_inline_content_list(strong.0)))
+        }
+        InlineContent::Strikethrough(strikethrough) => InlineContent::Strikethrough(Strikethrough(
+            folder.fold_inline_content_list(strikethrough.0),
+        )),
+        InlineContent::Link(link) => InlineContent::Link(folder.fold_link(link)),
+        InlineContent::Hook(hook) => InlineContent::Hook(folder.fold_hook(hook)),
+        InlineContent::Mention(mention) => InlineContent::Mention(folder.fold_mention(mention)),
+        InlineContent::Icu(icu) => InlineContent::Icu(folder.fold_icu(icu)),
+        InlineContent::CodeSpan(_)
+        | InlineContent::HardLineBreak
+        | InlineContent::Emoji(_)
+        | InlineContent::KeyboardKey(_)
+        | InlineContent::MessageReference(_)
+        | InlineContent::IcuPound => node,
+    }
+}
+
+pub fn fold_link_children<F: Fold + ?Sized>(folder: &mut F, node: Link) -> Link {
+    let destination = match node.destination {
+        LinkDestination::Placeholder(icu) => LinkDestination::Placeholder(folder.fold_icu(icu)),
+        destination => destination,
+    };
+    Link {
+        label: folder.fold_inline_content_list(node.label),
+        destination,
+        ..node
+    }
+}
+
+pub fn fold_hook_children<F: Fold + ?Sized>(folder: &mut F, node: Hook) -> Hook {
+    Hook {
+        content: folder.fold_inline_content_list(node.content),
+        ..node
+    }
+}
+
+pub fn fold_mention_children<F: Fold + ?Sized>(folder: &mut F, node: Mention) -> Mention {
+    Mention {
+        variable: folder.fold_icu_variable(node.variable),
+        ..node
+    }
+}
+
+pub fn fold_icu_children<F: Fold + ?Sized>(folder: &mut F, node: Icu) -> Icu {
+    match node {
+        Icu::IcuVariable(variable) => Icu::IcuVariable(folder.fold_icu_variable(variable)),
+        Icu::IcuPlural(plural) => Icu::IcuPlural(IcuPlural {
+            variable: folder.fold_icu_variable(plural.variable),
+            arms: fold_arms(folder, plural.arms),
+            ..plural
+        }),
+        Icu::IcuSelect(select) => Icu::IcuSelect(IcuSelect {
+            variable: folder.fold_icu_variable(select.variable),
+            arms: fold_arms(folder, select.arms),
+            ..select
+        }),
+        Icu::IcuDate(date) => Icu::IcuDate(IcuDate {
+            variable: folder.fold_icu_variable(date.variable),
+            ..date
+        }),
+        Icu::IcuTime(time) => Icu::IcuTime(IcuTime {
+            variable: folder.fold_icu_variable(time.variable),
+            ..time
+        }),
+        Icu::IcuNumber(number) => Icu::IcuNumber(IcuNumber {
+            variable: folder.fold_icu_variable(number.variable),
+            ..number
+        }),
+    }
+}
+
+pub fn fold_icu_plural_arm_children<F: Fold + ?Sized>(
+    folder: &mut F,
+    node: IcuPluralArm,
+) -> IcuPluralArm {
+    IcuPluralArm {
+        content: folder.fold_inline_content_list(node.content),
+        ..node
+    }
+}
+
+fn fold_arms<F: Fold + ?Sized>(folder: &mut F, arms: Vec<IcuPluralArm>) -> Vec<IcuPluralArm> {
+    arms.into_iter()
+        .map(|arm| folder.fold_icu_plural_arm(arm))
+        .collect()
+}
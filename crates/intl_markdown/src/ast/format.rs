@@ -1,13 +1,15 @@
 use std::fmt::Write;
 
 use crate::ast::{
-    BlockNode, CodeBlock, CodeSpan, Document, Emphasis, Heading, Hook, Icu, IcuDate,
+    BlockNode, CodeBlock, CodeSpan, Document, Emoji, Emphasis, Heading, Hook, Icu, IcuDate,
     IcuDateTimeStyle, IcuNumber, IcuNumberStyle, IcuPlural, IcuPluralArm, IcuPluralKind, IcuSelect,
-    IcuTime, IcuVariable, InlineContent, Link, LinkKind, Paragraph, Strikethrough, Strong,
-    LinkDestination,
+    IcuTime, IcuVariable, InlineContent, KeyboardKey, Link, LinkKind, Mention, MessageReference,
+    Paragraph, Strikethrough, Strong, LinkDestination,
 };
 
-use super::util::{escape_body_text, escape_href, format_plain_text};
+use crate::options::HtmlFormatOptions;
+
+use super::util::{write_escaped_body_text, write_escaped_href, write_plain_text};
 
 macro_rules! write {
     ($dst:expr, [$($arg:expr),+ $(,)?]) => {{
@@ -81,24 +83,110 @@ impl<T: FormatHtml> FormatHtml for [T] {
     }
 }
 
+/// Text that is escaped for html as it is written, rather than through an intermediate string.
+struct EscapedText<'a>(&'a str);
+impl FormatHtml for EscapedText<'_> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut dyn Write) -> FormatResult<()> {
+        write_escaped_body_text(f, self.0)
+    }
+}
+
+/// A url that is percent-encoded as it is written.
+struct EscapedHref<'a>(&'a str);
+impl FormatHtml for EscapedHref<'_> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut dyn Write) -> FormatResult<()> {
+        write_escaped_href(f, self.0)
+    }
+}
+
+/// Only the visual text of the given elements, without any markup.
+struct PlainText<'a>(&'a [InlineContent]);
+impl FormatHtml for PlainText<'_> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut dyn Write) -> FormatResult<()> {
+        write_plain_text(f, self.0)
+    }
+}
+
 pub fn format_ast(document: &Document) -> FormatResult<String> {
+    format_ast_with_options(document, HtmlFormatOptions::default())
+}
+
+/// Format the document as html like [format_ast], with extra output configured by `options`.
+pub fn format_ast_with_options(
+    document: &Document,
+    options: HtmlFormatOptions,
+) -> FormatResult<String> {
     let mut f = String::new();
+    format_ast_to_with_options(document, &mut f, options)?;
+    Ok(f)
+}
 
+/// Format the document as html directly into `f`, without allocating any intermediate strings.
+/// This is the same output as [format_ast], for callers that already have a buffer to write to.
+pub fn format_ast_to<W: Write>(document: &Document, f: &mut W) -> FormatResult<()> {
+    format_ast_to_with_options(document, f, HtmlFormatOptions::default())
+}
+
+/// Format the document as html directly into `f`, like [format_ast_to], with extra output
+/// configured by `options`.
+pub fn format_ast_to_with_options<W: Write>(
+    document: &Document,
+    f: &mut W,
+    options: HtmlFormatOptions,
+) -> FormatResult<()> {
+    let mut f: &mut dyn Write = f;
     for (index, block) in document.blocks().iter().enumerate() {
         if index > 0 {
-            f.push('\n');
+            f.write_char('\n')?;
         }
 
         match block {
             BlockNode::Paragraph(paragraph) => write!(f, [paragraph])?,
+            BlockNode::Heading(heading) if options.heading_ids => {
+                write!(f, [HeadingWithId(heading)])?
+            }
             BlockNode::Heading(heading) => write!(f, [heading])?,
             BlockNode::CodeBlock(code_block) => write!(f, [code_block])?,
             BlockNode::ThematicBreak => write!(f, ["<hr />"])?,
             BlockNode::InlineContent(content) => write!(f, [content])?,
+            BlockNode::Error(text) => write!(f, [EscapedText(text)])?,
         }
     }
 
-    Ok(f)
+    Ok(())
+}
+
+/// Format the document as html directly into an io sink, like [format_ast_to].
+pub fn format_ast_to_io<W: std::io::Write>(document: &Document, w: &mut W) -> std::io::Result<()> {
+    let mut adapter = IoAdapter {
+        inner: w,
+        error: None,
+    };
+    match format_ast_to(document, &mut adapter) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(adapter
+            .error
+            .unwrap_or_else(|| std::io::Error::other("failed to format document"))),
+    }
+}
+
+/// Forwards formatted text to an io sink, keeping the underlying error when a write fails, since
+/// `std::fmt::Error` can't carry one.
+struct IoAdapter<'a, W: std::io::Write> {
+    inner: &'a mut W,
+    error: Option<std::io::Error>,
+}
+
+impl<W: std::io::Write> Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            std::fmt::Error
+        })
+    }
 }
 
 impl FormatHtml for Paragraph {
@@ -115,20 +203,30 @@ impl FormatHtml for Heading {
     }
 }
 
+/// A heading with its id written as an attribute, for linking directly to it.
+struct HeadingWithId<'a>(&'a Heading);
+impl FormatHtml for HeadingWithId<'_> {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        std::write!(f, "<h{} id=\"", self.0.level)?;
+        write!(f, [EscapedText(self.0.id()), "\">", self.0.content])?;
+        std::write!(f, "</h{}>", self.0.level)
+    }
+}
+
 impl FormatHtml for CodeBlock {
     fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
         std::write!(f, "<pre><code")?;
         if let Some(language) = self.language() {
             write!(f, [" class=\"language-", language, '"'])?;
         }
-        std::write!(f, ">{}</code></pre>", escape_body_text(self.content()))
+        write!(f, [">", EscapedText(self.content()), "</code></pre>"])
     }
 }
 
 impl FormatHtml for InlineContent {
     fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
         match self {
-            InlineContent::Text(text) => write!(f, [escape_body_text(text)]),
+            InlineContent::Text(text) => write!(f, [EscapedText(text)]),
             InlineContent::Emphasis(emphasis) => write!(f, [emphasis]),
             InlineContent::Strong(strong) => write!(f, [strong]),
             InlineContent::Link(link) => write!(f, [link]),
@@ -136,6 +234,10 @@ impl FormatHtml for InlineContent {
             InlineContent::HardLineBreak => write!(f, ["<br />\n"]),
             InlineContent::Hook(hook) => write!(f, [hook]),
             InlineContent::Strikethrough(strikethrough) => write!(f, [strikethrough]),
+            InlineContent::Emoji(emoji) => write!(f, [emoji]),
+            InlineContent::Mention(mention) => write!(f, [mention]),
+            InlineContent::KeyboardKey(key) => write!(f, [key]),
+            InlineContent::MessageReference(reference) => write!(f, [reference]),
             InlineContent::Icu(icu) => write!(f, [icu]),
             InlineContent::IcuPound => write!(f, ['#']),
         }
@@ -156,54 +258,34 @@ impl FormatHtml for Strong {
 
 impl FormatHtml for Link {
     fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
-        match self.kind {
-            LinkKind::Image => {
-                let title = self
-                    .title
-                    .as_ref()
-                    .map(|title| format!(" title=\"{}\"", escape_body_text(&title)));
-
-                write!(f, ["<img src=\""])?;
-
-                match self.destination() {
-                    LinkDestination::Text(text) => write!(f, [escape_href(&text)])?,
-                    LinkDestination::Placeholder(icu) => write!(f, [icu])?,
-                    LinkDestination::Handler(handler) => write!(f, [handler])?,
-                }
-
-                write!(
-                    f,
-                    [
-                        '"',
-                        " alt=\"",
-                        format_plain_text(&self.label),
-                        '"',
-                        title,
-                        " />"
-                    ]
-                )
-            }
-            _ => {
-                let title = self
-                    .title
-                    .as_ref()
-                    .map(|title| format!(" title=\"{}\"", escape_body_text(&title)));
-
-                write!(f, ["<a href=\""])?;
-                match self.destination() {
-                    LinkDestination::Text(text) => write!(f, [escape_href(&text)])?,
-                    LinkDestination::Placeholder(icu) => write!(f, [icu])?,
-                    LinkDestination::Handler(handler) => write!(f, [handler])?,
-                }
-                write!(f, ['"', title, ">", self.label, "</a>"])
-            }
+        let is_image = matches!(self.kind, LinkKind::Image);
+        let open = if is_image { "<img src=\"" } else { "<a href=\"" };
+        write!(f, [open])?;
+        match self.destination() {
+            LinkDestination::Text(text) => write!(f, [EscapedHref(text)])?,
+            LinkDestination::Placeholder(icu) => write!(f, [icu])?,
+            LinkDestination::Handler(handler) => write!(f, [handler.as_str()])?,
+        }
+        write!(f, ['"'])?;
+
+        if is_image {
+            write!(f, [" alt=\"", PlainText(&self.label), '"'])?;
+        }
+        if let Some(title) = &self.title {
+            write!(f, [" title=\"", EscapedText(title), '"'])?;
+        }
+
+        if is_image {
+            write!(f, [" />"])
+        } else {
+            write!(f, [">", self.label, "</a>"])
         }
     }
 }
 
 impl FormatHtml for CodeSpan {
     fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
-        write!(f, ["<code>", escape_body_text(self.content()), "</code>"])
+        write!(f, ["<code>", EscapedText(self.content()), "</code>"])
     }
 }
 
@@ -219,6 +301,48 @@ impl FormatHtml for Strikethrough {
     }
 }
 
+impl FormatHtml for Emoji {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        write!(
+            f,
+            [
+                "<span data-emoji=\"",
+                EscapedText(self.name()),
+                "\">",
+                EscapedText(self.shortcode()),
+                "</span>"
+            ]
+        )
+    }
+}
+
+impl FormatHtml for Mention {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        write!(
+            f,
+            [
+                "<span data-mention=\"",
+                EscapedText(self.sigil()),
+                "\">{",
+                self.variable(),
+                "}</span>"
+            ]
+        )
+    }
+}
+
+impl FormatHtml for KeyboardKey {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        write!(f, ["<kbd>", EscapedText(self.name()), "</kbd>"])
+    }
+}
+
+impl FormatHtml for MessageReference {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        write!(f, ["{@", EscapedText(self.key()), "}"])
+    }
+}
+
 impl FormatHtml for Icu {
     fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
         f.write_str("{")?;
@@ -235,8 +359,12 @@ impl FormatHtml for Icu {
 }
 
 impl FormatHtml for IcuVariable {
-    fn fmt(&self, f: &mut dyn Write) -> FormatResult<()> {
-        f.write_str(&self.name())
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        f.write_str(self.name())?;
+        if let Some(default_value) = self.default_value() {
+            write!(f, ["|", EscapedText(default_value)])?;
+        }
+        Ok(())
     }
 }
 
@@ -0,0 +1,45 @@
+//! Detection of keyboard keys like `[[Ctrl]]` in plain text, following the same rules that the
+//! parser uses when the `keyboard_keys` option is enabled.
+//!
+//! Messages are usually parsed without that option, leaving keys as plain text, so detecting them
+//! directly is useful on its own, like checking that translations don't translate key names.
+use std::ops::Range;
+
+use crate::byte_lookup::byte_ends_keyboard_key;
+
+/// Returns an iterator over the byte ranges of the names of every keyboard key in `text`, not
+/// including the surrounding brackets.
+pub fn find_keyboard_keys(text: &str) -> KeyboardKeys<'_> {
+    KeyboardKeys { text, position: 0 }
+}
+
+pub struct KeyboardKeys<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Iterator for KeyboardKeys<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        while let Some(offset) = self.text[self.position..].find("[[") {
+            let start = self.position + offset + 2;
+            let name_length = self.text.as_bytes()[start..]
+                .iter()
+                .take_while(|byte| !byte_ends_keyboard_key(**byte))
+                .count();
+            let end = start + name_length;
+            let name = &self.text[start..end];
+            if name.is_empty() || name.trim() != name || !self.text[end..].starts_with("]]") {
+                // Only skip the first bracket, since the second could start another key, like in
+                // `[[[Ctrl]]`.
+                self.position = start - 1;
+                continue;
+            }
+
+            self.position = end + 2;
+            return Some(start..end);
+        }
+        None
+    }
+}
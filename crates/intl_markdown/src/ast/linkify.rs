@@ -0,0 +1,150 @@
+//! Detection of bare urls in plain text, like `https://discord.com` or `www.discord.com`, following
+//! the rules of GFM's [extended autolinks](https://github.github.com/gfm/#autolinks-extension-).
+//!
+//! Bare urls are only turned into links when the `autolink_bare_urls` parser option is enabled,
+//! but detecting them is also useful on its own, like checking that translations keep every url
+//! from their source message.
+use std::ops::Range;
+
+use crate::ast::{InlineContent, Link, LinkDestination, LinkKind};
+
+/// Returns an iterator over the byte ranges of every bare url in `text`.
+pub fn find_bare_urls(text: &str) -> BareUrls<'_> {
+    BareUrls { text, position: 0 }
+}
+
+pub struct BareUrls<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Iterator for BareUrls<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        while let Some(c) = self.text[self.position..].chars().next() {
+            let start = self.position;
+            self.position += c.len_utf8();
+            if !matches!(c, 'h' | 'H' | 'w' | 'W') || !is_url_boundary(&self.text[..start]) {
+                continue;
+            }
+            if let Some(length) = match_bare_url(&self.text[start..]) {
+                self.position = start + length;
+                return Some(start..self.position);
+            }
+        }
+        None
+    }
+}
+
+/// Urls can only start at the beginning of the text, after whitespace, or after the delimiters
+/// that are allowed to wrap them, like `(` or `*`.
+fn is_url_boundary(preceding: &str) -> bool {
+    preceding
+        .chars()
+        .next_back()
+        .is_none_or(|c| c.is_whitespace() || matches!(c, '*' | '_' | '~' | '('))
+}
+
+/// Returns the byte length of the bare url at the start of `text`, if there is one.
+fn match_bare_url(text: &str) -> Option<usize> {
+    let domain_start = if starts_with_ignore_case(text, "https://") {
+        8
+    } else if starts_with_ignore_case(text, "http://") {
+        7
+    } else if starts_with_ignore_case(text, "www.") {
+        0
+    } else {
+        return None;
+    };
+
+    let mut end = text
+        .find(|c: char| c.is_whitespace() || c == '<')
+        .unwrap_or(text.len());
+    // Trailing punctuation is almost always part of the surrounding sentence rather than the url,
+    // and closing parentheses are only kept when they balance an opening one within the url.
+    loop {
+        let url = &text[..end];
+        match url.chars().next_back() {
+            Some('?' | '!' | '.' | ',' | ':' | '*' | '_' | '~' | '\'' | '"') => end -= 1,
+            Some(')') if url.matches(')').count() > url.matches('(').count() => end -= 1,
+            _ => break,
+        }
+    }
+    if end <= domain_start {
+        return None;
+    }
+
+    let domain = &text[domain_start..end];
+    let domain_length = domain
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(domain.len());
+    is_valid_domain(&domain[..domain_length]).then_some(end)
+}
+
+/// A valid domain has at least two non-empty segments separated by periods, and the last two
+/// segments can't contain underscores.
+fn is_valid_domain(domain: &str) -> bool {
+    let segments: Vec<&str> = domain.split('.').collect();
+    segments.len() >= 2
+        && segments.iter().all(|segment| !segment.is_empty())
+        && segments[segments.len() - 2..]
+            .iter()
+            .all(|segment| !segment.contains('_'))
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// Split every text element in `content` around the bare urls it contains, with each url becoming
+/// an autolink. A url that runs up to the end of its text and directly into a following element,
+/// like the placeholder in `https://discord.com/{path}`, is left as text, since its destination
+/// can't be known until the message is formatted.
+pub(crate) fn linkify_bare_urls(content: Vec<InlineContent>) -> Vec<InlineContent> {
+    let mut result = Vec::with_capacity(content.len());
+    let mut content = content.into_iter().peekable();
+    while let Some(element) = content.next() {
+        let InlineContent::Text(text) = element else {
+            result.push(element);
+            continue;
+        };
+
+        let is_followed_by_element = content.peek().is_some();
+        let mut last_end = 0;
+        for range in find_bare_urls(&text) {
+            if range.end == text.len() && is_followed_by_element {
+                break;
+            }
+            if range.start > last_end {
+                result.push(InlineContent::Text(text[last_end..range.start].to_string()));
+            }
+            result.push(InlineContent::Link(bare_url_link(&text[range.clone()])));
+            last_end = range.end;
+        }
+
+        if last_end == 0 {
+            result.push(InlineContent::Text(text));
+        } else if last_end < text.len() {
+            result.push(InlineContent::Text(text[last_end..].to_string()));
+        }
+    }
+    result
+}
+
+fn bare_url_link(url: &str) -> Link {
+    let destination = if starts_with_ignore_case(url, "www.") {
+        format!("http://{url}")
+    } else {
+        url.to_string()
+    };
+    Link {
+        kind: LinkKind::Autolink,
+        label: vec![InlineContent::Text(url.to_string())],
+        destination: LinkDestination::Text(destination),
+        title: None,
+        span: None,
+        handler_span: None,
+    }
+}
@@ -14,13 +14,29 @@
 //! other nodes merged into single representations, like AtxHeading and SetextHeading becoming a
 //! single Heading struct with a `kind` property indicating which it came from.
 
+use std::ops::Range;
+
+use ustr::{ustr, Ustr};
+
+use crate::ParseDiagnostic;
+
+pub mod cjk;
+pub mod combinations;
+pub mod constants;
+pub mod emoji;
+pub mod equivalence;
+pub mod fold;
 pub mod format;
+pub mod keyboard;
+pub mod linkify;
 pub mod process;
+pub mod references;
 pub mod util;
 
 #[derive(Clone, Debug)]
 pub struct Document {
     blocks: Vec<BlockNode>,
+    diagnostics: Vec<ParseDiagnostic>,
 }
 impl Document {
     /// Return a new Document with the given content as the only value, treated as a raw string with
@@ -30,11 +46,33 @@ impl Document {
             blocks: vec![BlockNode::InlineContent(vec![InlineContent::Text(
                 content.into(),
             )])],
+            diagnostics: vec![],
+        }
+    }
+    /// Return a new Document where the entire content failed to parse, kept as a single Error node
+    /// along with the diagnostic explaining why.
+    pub fn from_error(content: &str, diagnostic: ParseDiagnostic) -> Self {
+        Self {
+            blocks: vec![BlockNode::Error(content.into())],
+            diagnostics: vec![diagnostic],
         }
     }
     pub fn blocks(&self) -> &Vec<BlockNode> {
         &self.blocks
     }
+    /// Problems that were recovered from while parsing this document. An empty list means the
+    /// content parsed cleanly.
+    pub fn diagnostics(&self) -> &Vec<ParseDiagnostic> {
+        &self.diagnostics
+    }
+    pub fn has_errors(&self) -> bool {
+        !self.diagnostics.is_empty()
+    }
+    pub(crate) fn extend_diagnostics(&mut self, diagnostics: Vec<ParseDiagnostic>) {
+        self.diagnostics.extend(diagnostics);
+        self.diagnostics
+            .sort_by_key(|diagnostic| diagnostic.span().start);
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -46,6 +84,9 @@ pub enum BlockNode {
     /// Inline content directly added to a Document, generally only in the case of using inline
     /// mode, where the content is intentionally _not_ placed inside a paragraph.
     InlineContent(Vec<InlineContent>),
+    /// Source text that could not be parsed into any other node. The content is kept verbatim so
+    /// that it is still rendered, and a matching diagnostic is added to the Document.
+    Error(String),
 }
 
 #[derive(Clone, Debug)]
@@ -68,6 +109,7 @@ pub struct Heading {
     kind: HeadingKind,
     level: u8,
     content: Vec<InlineContent>,
+    id: String,
 }
 impl Heading {
     pub fn kind(&self) -> &HeadingKind {
@@ -81,6 +123,12 @@ impl Heading {
     pub fn content(&self) -> &Vec<InlineContent> {
         &self.content
     }
+
+    /// A slug of the heading's text for use as an anchor, like `getting-started`. Ids are unique
+    /// within a document, with repeated headings getting a numbered suffix like `getting-started-1`.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -121,6 +169,10 @@ pub enum InlineContent {
     HardLineBreak,
     Hook(Hook),
     Strikethrough(Strikethrough),
+    Emoji(Emoji),
+    Mention(Mention),
+    KeyboardKey(KeyboardKey),
+    MessageReference(MessageReference),
     Icu(Icu),
     /// IcuPound is a special case for the `#` token inside an ICU plural value, such as
     /// `{count, plural, one {# item} other {# items}}`. It represents a placeholder for the same
@@ -170,7 +222,7 @@ pub enum LinkDestination {
     /// A name for a variable handler to call to resolve this value. This is
     /// currently used only  for link click handlers, which use an implicit,
     /// non-icu variable name  as the link target, like `[click me!](onClick)`.
-    Handler(String),
+    Handler(Ustr),
 }
 
 impl LinkDestination {
@@ -185,12 +237,14 @@ impl LinkDestination {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Link {
     kind: LinkKind,
     label: Vec<InlineContent>,
     destination: LinkDestination,
     title: Option<String>,
+    span: Option<Range<usize>>,
+    handler_span: Option<Range<usize>>,
 }
 
 impl Link {
@@ -206,23 +260,65 @@ impl Link {
     pub fn title(&self) -> &Option<String> {
         &self.title
     }
+    /// The byte range of the whole link in the source text, or None for links that weren't
+    /// written explicitly, like bare urls. Not part of the link's value, like
+    /// [IcuVariable::name_span].
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+    /// The byte range of the handler name in the source text when the destination is a
+    /// [LinkDestination::Handler], like `onClick` in `[text](onClick)`.
+    pub fn handler_span(&self) -> Option<Range<usize>> {
+        self.handler_span.clone()
+    }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+impl PartialEq for Link {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.label == other.label
+            && self.destination == other.destination
+            && self.title == other.title
+    }
+}
+
+impl Eq for Link {}
+
+#[derive(Clone, Debug)]
 pub struct Hook {
     content: Vec<InlineContent>,
-    name: String,
+    name: Ustr,
+    span: Option<Range<usize>>,
+    name_span: Option<Range<usize>>,
 }
 
 impl Hook {
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         &self.name
     }
     pub fn content(&self) -> &Vec<InlineContent> {
         &self.content
     }
+    /// The byte range of the whole hook in the source text. Not part of the hook's value, like
+    /// [IcuVariable::name_span].
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+    /// The byte range of the hook's name in the source text, like `tooltipHook` in
+    /// `$[text](tooltipHook)`.
+    pub fn name_span(&self) -> Option<Range<usize>> {
+        self.name_span.clone()
+    }
 }
 
+impl PartialEq for Hook {
+    fn eq(&self, other: &Self) -> bool {
+        self.content == other.content && self.name == other.name
+    }
+}
+
+impl Eq for Hook {}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[repr(transparent)]
 pub struct Strikethrough(Vec<InlineContent>);
@@ -232,6 +328,61 @@ impl Strikethrough {
     }
 }
 
+/// An emoji shortcode like `:smile:`. No emoji data is included, so the name is kept exactly as
+/// written and it's up to the renderer to decide what it represents.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Emoji(String);
+impl Emoji {
+    /// The full shortcode, including the surrounding colons, like `:smile:`.
+    pub fn shortcode(&self) -> &str {
+        &self.0
+    }
+    /// The name of the emoji without the surrounding colons, like `smile`.
+    pub fn name(&self) -> &str {
+        &self.0[1..self.0.len() - 1]
+    }
+}
+
+/// A mention placeholder like `<@{userId}>`, where the sigil determines what kind of thing is
+/// being mentioned and the variable provides its id when the message is formatted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mention {
+    sigil: String,
+    variable: IcuVariable,
+}
+impl Mention {
+    pub fn sigil(&self) -> &str {
+        &self.sigil
+    }
+    pub fn variable(&self) -> &IcuVariable {
+        &self.variable
+    }
+}
+
+/// A keyboard key or other inline UI element like `[[Ctrl]]`. The name refers to the key itself
+/// rather than any visible text, so it is kept the same in every translation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct KeyboardKey(String);
+impl KeyboardKey {
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A reference to another message like `{@PRODUCT_NAME}`, which is replaced by the content of
+/// that message when bundling. See [references::resolve_message_references].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct MessageReference(Ustr);
+impl MessageReference {
+    /// The key of the referenced message, like `PRODUCT_NAME`.
+    pub fn key(&self) -> &str {
+        &self.0
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[repr(transparent)]
 pub struct CodeSpan(String);
@@ -265,21 +416,51 @@ impl Icu {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct IcuVariable {
-    name: String,
+    name: Ustr,
+    default_value: Option<String>,
     is_unsafe: bool,
+    name_span: Option<Range<usize>>,
 }
 impl IcuVariable {
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// The byte range of the name in the source text that the variable was parsed from. Not part
+    /// of the variable's value, so variables written in different places are still equal.
+    pub fn name_span(&self) -> Option<Range<usize>> {
+        self.name_span.clone()
+    }
+
+    /// The text shown in place of the variable when no value is given for it, like `Someone` in
+    /// `{name|Someone}`. Only plain placeholders can have a default value.
+    pub fn default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+
     pub fn is_unsafe(&self) -> bool {
         self.is_unsafe
     }
+
+    /// Return this variable with its name replaced, like when renaming placeholders.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = ustr(name);
+        self
+    }
+}
+
+impl PartialEq for IcuVariable {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.default_value == other.default_value
+            && self.is_unsafe == other.is_unsafe
+    }
 }
 
+impl Eq for IcuVariable {}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum IcuPluralKind {
     Plural,
@@ -298,7 +479,7 @@ impl IcuPlural {
         &self.variable
     }
 
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         self.variable.name()
     }
 
@@ -326,7 +507,7 @@ impl IcuSelect {
         &self.variable
     }
 
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         self.variable.name()
     }
 
@@ -341,11 +522,11 @@ impl IcuSelect {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IcuPluralArm {
-    selector: String,
+    selector: Ustr,
     content: Vec<InlineContent>,
 }
 impl IcuPluralArm {
-    pub fn selector(&self) -> &String {
+    pub fn selector(&self) -> &str {
         &self.selector
     }
 
@@ -364,7 +545,7 @@ impl IcuDate {
     pub fn variable(&self) -> &IcuVariable {
         &self.variable
     }
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         self.variable.name()
     }
     pub fn style(&self) -> &Option<IcuDateTimeStyle> {
@@ -385,7 +566,7 @@ impl IcuTime {
     pub fn variable(&self) -> &IcuVariable {
         &self.variable
     }
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         self.variable.name()
     }
     pub fn style(&self) -> &Option<IcuDateTimeStyle> {
@@ -398,10 +579,10 @@ impl IcuTime {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IcuDateTimeStyle {
-    text: String,
+    text: Ustr,
 }
 impl IcuDateTimeStyle {
-    pub fn text(&self) -> &String {
+    pub fn text(&self) -> &str {
         &self.text
     }
 }
@@ -416,7 +597,7 @@ impl IcuNumber {
     pub fn variable(&self) -> &IcuVariable {
         &self.variable
     }
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         self.variable.name()
     }
     pub fn style(&self) -> &Option<IcuNumberStyle> {
@@ -427,12 +608,42 @@ impl IcuNumber {
     }
 }
 
+/// The kind of formatting that a number style argument applies.
+///
+/// Only the styles that change how the value itself is interpreted are distinguished here, like
+/// percentages (which scale the value) and currencies (which may name a specific currency code).
+/// Everything else is purely presentational and is left to the runtime to interpret from the style
+/// text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IcuNumberStyleKind {
+    /// `{var, number, integer}`
+    Integer,
+    /// `{var, number, percent}`, or a skeleton including `percent` or `%`.
+    Percent,
+    /// `{var, number, currency}`, or a skeleton including `currency/XXX`. The currency code is
+    /// only present when it was given explicitly in a skeleton.
+    Currency(Option<String>),
+    /// Any other named style or skeleton.
+    Other,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IcuNumberStyle {
-    text: String,
+    text: Ustr,
+    kind: IcuNumberStyleKind,
 }
 impl IcuNumberStyle {
-    pub fn text(&self) -> &String {
+    pub fn text(&self) -> &str {
         &self.text
     }
+    pub fn kind(&self) -> &IcuNumberStyleKind {
+        &self.kind
+    }
+    /// Returns the ISO 4217 currency code given in the style, like `USD` in `::currency/USD`.
+    pub fn currency(&self) -> Option<&str> {
+        match &self.kind {
+            IcuNumberStyleKind::Currency(code) => code.as_deref(),
+            _ => None,
+        }
+    }
 }
@@ -1,28 +1,61 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use ustr::{ustr, Ustr};
 
 use crate::ast::{CodeBlockKind, HeadingKind, IcuPluralKind, LinkDestination, LinkKind};
 use crate::html_entities::get_html_entity;
 use crate::token::{SourceText, Token};
 use crate::tree_builder::{cst, TokenSpan};
 use crate::util::unescape_cow;
-use crate::{ast, SyntaxKind};
+use crate::{ast, ParseDiagnostic, ParserOptions, SyntaxKind};
 
-use super::util::unescape;
+use super::linkify::linkify_bare_urls;
+use super::util::{heading_slug, unescape};
 
 #[derive(Clone, Debug)]
 pub struct AstProcessingContext {
     source: SourceText,
     allow_hard_line_breaks: bool,
     allow_icu_pound: bool,
+    autolink_bare_urls: bool,
+    /// True while processing the label of a link, where bare urls can't become nested links.
+    is_inside_link_label: bool,
+    /// Ids that have already been given to headings in the document, to keep them unique.
+    heading_ids: HashSet<String>,
+    /// Problems found in the shape of the tree, like nodes that can't appear where they are.
+    diagnostics: Vec<ParseDiagnostic>,
 }
 
 impl AstProcessingContext {
-    fn new(source: SourceText) -> Self {
+    fn new(source: SourceText, options: ParserOptions) -> Self {
         Self {
             source,
             allow_hard_line_breaks: false,
             allow_icu_pound: false,
+            autolink_bare_urls: options.autolink_bare_urls,
+            is_inside_link_label: false,
+            heading_ids: HashSet::new(),
+            diagnostics: vec![],
+        }
+    }
+
+    /// Returns an id for a heading with the given content that no other heading in the document
+    /// has used yet, adding a numbered suffix to the slug of the content when it is repeated.
+    fn unique_heading_id(&mut self, content: &[ast::InlineContent]) -> String {
+        let mut slug = heading_slug(content);
+        if slug.is_empty() {
+            slug.push_str("heading");
+        }
+        let mut id = slug.clone();
+        let mut suffix = 1;
+        while self.heading_ids.contains(&id) {
+            id = format!("{slug}-{suffix}");
+            suffix += 1;
         }
+        self.heading_ids.insert(id.clone());
+        id
     }
 
     fn with_context<M, F, T>(&mut self, mut mutator: M, func: F) -> T
@@ -32,9 +65,11 @@ impl AstProcessingContext {
     {
         let old_allow_hard_line_breaks = self.allow_hard_line_breaks;
         let old_allow_icu_pound = self.allow_icu_pound;
+        let old_is_inside_link_label = self.is_inside_link_label;
         mutator(self);
 
         let result = func(self);
+        self.is_inside_link_label = old_is_inside_link_label;
         self.allow_icu_pound = old_allow_icu_pound;
         self.allow_hard_line_breaks = old_allow_hard_line_breaks;
         result
@@ -42,7 +77,17 @@ impl AstProcessingContext {
 }
 
 pub fn process_cst_to_ast(source: SourceText, cst: &cst::Document) -> ast::Document {
-    let mut context = AstProcessingContext::new(source);
+    process_cst_to_ast_with_options(source, cst, ParserOptions::default())
+}
+
+/// Process the CST into an AST like [process_cst_to_ast], applying the parts of `options` that
+/// only affect the semantics of the content, like turning bare urls into links.
+pub fn process_cst_to_ast_with_options(
+    source: SourceText,
+    cst: &cst::Document,
+    options: ParserOptions,
+) -> ast::Document {
+    let mut context = AstProcessingContext::new(source, options);
     let mut blocks = vec![];
     for node in cst.children() {
         match node {
@@ -69,17 +114,37 @@ pub fn process_cst_to_ast(source: SourceText, cst: &cst::Document) -> ast::Docum
                     cst::Node::FencedCodeBlock(code_block) => ast::BlockNode::CodeBlock(
                         process_fenced_code_block(&mut context, code_block),
                     ),
-                    node => unreachable!(
-                        "Inline nodes can't appear directly under a document. Found:\n{:#?}",
-                        node
-                    ),
+                    // Inline nodes can't appear directly under a document. If one does, the
+                    // parser lost track of a block boundary, so the node's source text is kept
+                    // verbatim as an error rather than guessing at what it was meant to be.
+                    node => {
+                        let Some(span) = node_source_span(node) else {
+                            continue;
+                        };
+                        context.diagnostics.push(ParseDiagnostic::new(
+                            "Inline content was found outside of any block",
+                            span.clone(),
+                        ));
+                        ast::BlockNode::Error(context.source[span].to_string())
+                    }
                 };
                 blocks.push(ast_node);
             }
         }
     }
 
-    ast::Document { blocks }
+    ast::Document {
+        blocks,
+        diagnostics: context.diagnostics,
+    }
+}
+
+/// Returns the range of source text covered by `node`, or None if it has no tokens at all.
+fn node_source_span(node: &cst::Node) -> Option<Range<usize>> {
+    match (node.first_token(), node.last_token()) {
+        (Some(first), Some(last)) => Some(first.range_usize().start..last.range_usize().end),
+        _ => None,
+    }
 }
 
 pub fn process_paragraph(
@@ -96,10 +161,12 @@ pub fn process_atx_heading(
     context: &mut AstProcessingContext,
     atx_heading: &cst::AtxHeading,
 ) -> ast::Heading {
+    let content = process_inline_content(context, &atx_heading.children);
     ast::Heading {
         kind: HeadingKind::Atx,
         level: atx_heading.level() as u8,
-        content: process_inline_content(context, &atx_heading.children),
+        id: context.unique_heading_id(&content),
+        content,
     }
 }
 
@@ -107,10 +174,12 @@ pub fn process_setext_heading(
     context: &mut AstProcessingContext,
     setext_heading: &cst::SetextHeading,
 ) -> ast::Heading {
+    let content = process_inline_content(context, &setext_heading.children);
     ast::Heading {
         kind: HeadingKind::Atx,
         level: setext_heading.underline.level() as u8,
-        content: process_inline_content(context, &setext_heading.children),
+        id: context.unique_heading_id(&content),
+        content,
     }
 }
 
@@ -289,6 +358,9 @@ pub fn process_inline_children(
         };
     }
 
+    if context.autolink_bare_urls && !context.is_inside_link_label {
+        return linkify_bare_urls(children);
+    }
     children
 }
 
@@ -317,8 +389,30 @@ pub fn process_inline_node(
         cst::Node::Strikethrough(strikethrough) => {
             ast::InlineContent::Strikethrough(process_strikethrough(context, strikethrough))
         }
+        cst::Node::Emoji(emoji) => ast::InlineContent::Emoji(process_emoji(context, emoji)),
+        cst::Node::Mention(mention) => {
+            ast::InlineContent::Mention(process_mention(context, mention))
+        }
+        cst::Node::KeyboardKey(key) => {
+            ast::InlineContent::KeyboardKey(process_keyboard_key(context, key))
+        }
+        cst::Node::MessageReference(reference) => {
+            ast::InlineContent::MessageReference(process_message_reference(context, reference))
+        }
         cst::Node::Icu(icu) => ast::InlineContent::Icu(process_icu(context, icu)),
-        node => unreachable!("Inline nodes cannot be block nodes. found: {:?}", node),
+        // Block nodes can't appear inside of inline content. Like inline nodes at the top level of
+        // a document, the node's source text is kept as plain text rather than guessing at what
+        // it was meant to be.
+        node => {
+            let Some(span) = node_source_span(node) else {
+                return ast::InlineContent::Text(String::new());
+            };
+            context.diagnostics.push(ParseDiagnostic::new(
+                "Block content was found inside of inline content",
+                span.clone(),
+            ));
+            ast::InlineContent::Text(context.source[span].to_string())
+        }
     }
 }
 
@@ -413,28 +507,40 @@ pub fn process_strong(context: &mut AstProcessingContext, strong: &cst::Strong)
 }
 
 pub fn process_link(context: &mut AstProcessingContext, link: &cst::Link) -> ast::Link {
-    let label = process_inline_content(context, &link.content);
+    let label = context.with_context(
+        |context| context.is_inside_link_label = true,
+        |context| process_inline_content(context, &link.content),
+    );
     let destination = process_link_destination(context, &link.resource.destination);
     let title = process_link_title(context, &link.resource.title);
+    let handler_span = handler_span(&link.resource.destination);
 
     ast::Link {
         kind: LinkKind::Link,
         label,
         destination,
         title,
+        span: Some(link.l_square.range_usize().start..link.resource.r_paren.range_usize().end),
+        handler_span,
     }
 }
 
 pub fn process_image(context: &mut AstProcessingContext, image: &cst::Image) -> ast::Link {
-    let label = process_inline_content(context, &image.content);
+    let label = context.with_context(
+        |context| context.is_inside_link_label = true,
+        |context| process_inline_content(context, &image.content),
+    );
     let destination = process_link_destination(context, &image.resource.destination);
     let title = process_link_title(context, &image.resource.title);
+    let handler_span = handler_span(&image.resource.destination);
 
     ast::Link {
         kind: LinkKind::Image,
         label,
         destination,
         title,
+        span: Some(image.exclaim.range_usize().start..image.resource.r_paren.range_usize().end),
+        handler_span,
     }
 }
 
@@ -457,6 +563,8 @@ pub fn process_autolink(_: &mut AstProcessingContext, image: &cst::Autolink) ->
         label,
         destination: LinkDestination::Text(destination),
         title: None,
+        span: Some(image.l_angle.range_usize().start..image.r_angle.range_usize().end),
+        handler_span: None,
     }
 }
 
@@ -476,12 +584,21 @@ fn process_link_destination(
             LinkDestination::Placeholder(process_icu(context, &destination.url))
         }
         Some(cst::LinkDestination::ClickHandlerLinkDestination(destination)) => {
-            LinkDestination::Handler(destination.name.text().to_owned())
+            LinkDestination::Handler(ustr(destination.name.text()))
         }
         None => LinkDestination::Text("".into()),
     }
 }
 
+fn handler_span(destination: &Option<cst::LinkDestination>) -> Option<Range<usize>> {
+    match destination {
+        Some(cst::LinkDestination::ClickHandlerLinkDestination(destination)) => {
+            Some(destination.name.range_usize())
+        }
+        _ => None,
+    }
+}
+
 fn process_link_title(
     context: &mut AstProcessingContext,
     title: &Option<cst::LinkTitle>,
@@ -520,9 +637,7 @@ pub fn process_code_span(
     if code_span
         .close_backticks
         .first_token()
-        .unwrap()
-        .flags()
-        .is_escaped()
+        .is_some_and(|token| token.flags().is_escaped())
     {
         text.push('\\');
     }
@@ -546,11 +661,13 @@ pub fn process_hook(context: &mut AstProcessingContext, hook: &cst::Hook) -> ast
     ast::Hook {
         content: process_inline_content(context, &hook.content),
         name: process_hook_name(context, &hook.name),
+        span: Some(hook.dollar.range_usize().start..hook.name.r_paren.range_usize().end),
+        name_span: Some(hook.name.name.range_usize()),
     }
 }
 
-fn process_hook_name(_: &mut AstProcessingContext, hook_name: &cst::HookName) -> String {
-    hook_name.name.text().to_string()
+fn process_hook_name(_: &mut AstProcessingContext, hook_name: &cst::HookName) -> Ustr {
+    ustr(hook_name.name.text())
 }
 
 fn process_strikethrough(
@@ -560,6 +677,28 @@ fn process_strikethrough(
     ast::Strikethrough(process_inline_content(context, &strikethrough.content))
 }
 
+fn process_emoji(_: &mut AstProcessingContext, emoji: &cst::Emoji) -> ast::Emoji {
+    ast::Emoji(format!(":{}:", emoji.name.text()))
+}
+
+fn process_keyboard_key(_: &mut AstProcessingContext, key: &cst::KeyboardKey) -> ast::KeyboardKey {
+    ast::KeyboardKey(key.name.text().to_string())
+}
+
+fn process_message_reference(
+    _: &mut AstProcessingContext,
+    reference: &cst::MessageReference,
+) -> ast::MessageReference {
+    ast::MessageReference(ustr(reference.key.text()))
+}
+
+fn process_mention(context: &mut AstProcessingContext, mention: &cst::Mention) -> ast::Mention {
+    ast::Mention {
+        sigil: mention.sigil.text().to_string(),
+        variable: process_icu_variable(context, &mention.variable, false),
+    }
+}
+
 //#region ICU nodes
 pub fn process_icu(context: &mut AstProcessingContext, icu: &cst::Icu) -> ast::Icu {
     let is_unsafe = matches!(icu.l_curly.kind(), SyntaxKind::UNSAFE_LCURLY);
@@ -567,6 +706,11 @@ pub fn process_icu(context: &mut AstProcessingContext, icu: &cst::Icu) -> ast::I
         cst::IcuPlaceholder::IcuVariable(variable) => {
             ast::Icu::IcuVariable(process_icu_variable(context, variable, is_unsafe))
         }
+        cst::IcuPlaceholder::IcuVariableWithDefault(variable) => {
+            let mut processed = process_icu_variable(context, &variable.variable, is_unsafe);
+            processed.default_value = Some(variable.default_text.text().trim_end().to_string());
+            ast::Icu::IcuVariable(processed)
+        }
         cst::IcuPlaceholder::IcuSelect(select) => {
             ast::Icu::IcuSelect(process_icu_select(context, select, is_unsafe))
         }
@@ -602,8 +746,10 @@ pub fn process_icu_variable(
     is_unsafe: bool,
 ) -> ast::IcuVariable {
     ast::IcuVariable {
-        name: variable.ident.text().to_owned(),
+        name: ustr(variable.ident.text()),
+        default_value: None,
         is_unsafe,
+        name_span: Some(variable.ident.range_usize()),
     }
 }
 
@@ -633,7 +779,7 @@ pub fn process_icu_time(
 
 pub fn process_icu_date_time_style(style: &cst::IcuDateTimeStyle) -> ast::IcuDateTimeStyle {
     ast::IcuDateTimeStyle {
-        text: style.style_text.text().trim().into(),
+        text: ustr(style.style_text.text().trim()),
     }
 }
 
@@ -650,9 +796,36 @@ pub fn process_icu_number(
 }
 
 pub fn process_icu_number_style(style: &cst::IcuNumberStyle) -> ast::IcuNumberStyle {
+    let text = style.style_text.text().trim();
     ast::IcuNumberStyle {
-        text: style.style_text.text().trim().into(),
+        text: ustr(text),
+        kind: get_icu_number_style_kind(text),
+    }
+}
+
+/// Determine the kind of number style from its text, either as one of the named styles that
+/// FormatJS supports by default, or by looking through the stems of a number skeleton.
+/// https://unicode-org.github.io/icu/userguide/format_parse/numbers/skeletons.html
+fn get_icu_number_style_kind(text: &str) -> ast::IcuNumberStyleKind {
+    let Some(skeleton) = text.strip_prefix("::") else {
+        return match text {
+            "integer" => ast::IcuNumberStyleKind::Integer,
+            "percent" => ast::IcuNumberStyleKind::Percent,
+            "currency" => ast::IcuNumberStyleKind::Currency(None),
+            _ => ast::IcuNumberStyleKind::Other,
+        };
+    };
+
+    for token in skeleton.split_whitespace() {
+        match token.split_once('/') {
+            Some(("currency", code)) => {
+                return ast::IcuNumberStyleKind::Currency(Some(code.into()))
+            }
+            None if token == "percent" || token == "%" => return ast::IcuNumberStyleKind::Percent,
+            _ => {}
+        }
     }
+    ast::IcuNumberStyleKind::Other
 }
 
 pub fn process_icu_plural(
@@ -697,7 +870,7 @@ pub fn process_plural_arm(
     context.with_context(
         |context| context.allow_icu_pound = true,
         |context| ast::IcuPluralArm {
-            selector: arm.selector.text().into(),
+            selector: ustr(arm.selector.text()),
             content: process_inline_content(context, &arm.value.content),
         },
     )
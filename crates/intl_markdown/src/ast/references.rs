@@ -0,0 +1,101 @@
+//! Message references like `{@PRODUCT_NAME}` include the content of another message.
+//!
+//! They let messages share common fragments, like product names or legal phrases, rather than
+//! duplicating them. References are resolved before a message is compiled, so the runtime never
+//! sees them.
+//!
+//! Looking up the referenced messages is up to the caller, since only the messages database knows
+//! which value of a message to use for each locale. This module only finds the references in a
+//! document and replaces them with the content that the caller looked up.
+use std::collections::HashMap;
+
+use super::fold::{fold_document, fold_inline_content_children, Fold};
+use super::{BlockNode, Document, Icu, InlineContent};
+
+/// Return the keys of every message referenced in `document`, in the order they are written.
+/// Keys that are referenced multiple times are repeated.
+pub fn message_references(document: &Document) -> Vec<&str> {
+    let mut references = vec![];
+    for block in document.blocks() {
+        match block {
+            BlockNode::Paragraph(paragraph) => {
+                collect_references(paragraph.content(), &mut references)
+            }
+            BlockNode::Heading(heading) => collect_references(heading.content(), &mut references),
+            BlockNode::InlineContent(content) => collect_references(content, &mut references),
+            BlockNode::CodeBlock(_) | BlockNode::ThematicBreak | BlockNode::Error(_) => {}
+        }
+    }
+    references
+}
+
+fn collect_references<'a>(content: &'a [InlineContent], references: &mut Vec<&'a str>) {
+    for node in content {
+        match node {
+            InlineContent::MessageReference(reference) => references.push(reference.key()),
+            InlineContent::Emphasis(emphasis) => collect_references(emphasis.content(), references),
+            InlineContent::Strong(strong) => collect_references(strong.content(), references),
+            InlineContent::Strikethrough(strikethrough) => {
+                collect_references(strikethrough.content(), references)
+            }
+            InlineContent::Link(link) => collect_references(link.label(), references),
+            InlineContent::Hook(hook) => collect_references(hook.content(), references),
+            InlineContent::Icu(Icu::IcuPlural(plural)) => {
+                for arm in plural.arms() {
+                    collect_references(arm.content(), references);
+                }
+            }
+            InlineContent::Icu(Icu::IcuSelect(select)) => {
+                for arm in select.arms() {
+                    collect_references(arm.content(), references);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Return the content of `document` as inline content that can replace a reference to it, or None
+/// if it has block content like multiple paragraphs or a heading, which can't be used inline.
+pub fn document_inline_content(document: Document) -> Option<Vec<InlineContent>> {
+    let mut blocks = document.blocks.into_iter();
+    let content = match blocks.next() {
+        Some(BlockNode::Paragraph(paragraph)) => paragraph.0,
+        Some(BlockNode::InlineContent(content)) => content,
+        None => vec![],
+        _ => return None,
+    };
+    blocks.next().is_none().then_some(content)
+}
+
+/// Replace every reference in `document` with the content given for its key in `resolved`.
+///
+/// References to keys that aren't given are kept as they are. The given content is used exactly as
+/// it is, so any references inside of it have to be resolved already.
+pub fn resolve_message_references(
+    document: Document,
+    resolved: &HashMap<String, Vec<InlineContent>>,
+) -> Document {
+    if resolved.is_empty() {
+        return document;
+    }
+    fold_document(document, &mut ReferencesFolder(resolved))
+}
+
+struct ReferencesFolder<'a>(&'a HashMap<String, Vec<InlineContent>>);
+
+impl Fold for ReferencesFolder<'_> {
+    fn fold_inline_content_list(&mut self, nodes: Vec<InlineContent>) -> Vec<InlineContent> {
+        let mut result = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            match node {
+                InlineContent::MessageReference(reference) => match self.0.get(reference.key()) {
+                    Some(content) => result.extend(content.iter().cloned()),
+                    None => result.push(InlineContent::MessageReference(reference)),
+                },
+                node => result.push(fold_inline_content_children(self, node)),
+            }
+        }
+        result
+    }
+}
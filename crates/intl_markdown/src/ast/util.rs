@@ -1,8 +1,9 @@
 use std::borrow::Cow;
+use std::fmt::Write;
 
 use memchr::memchr;
 
-use crate::ast::InlineContent;
+use crate::ast::{Icu, IcuPluralArm, IcuPluralKind, InlineContent};
 
 fn icu_markdown_escape_handler(s: &str) -> Result<(Option<char>, &str), unescape_zero_copy::Error> {
     let mut chars = s.chars();
@@ -59,23 +60,30 @@ static HREF_SAFE: [u8; 128] = [
 /// than the percent encoding `%26` that it would normally have.
 pub(crate) fn escape_href(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
+    let _ = write_escaped_href(&mut result, text);
+    result
+}
+
+/// Write `text` into `f` with the same encoding as [escape_href], without allocating. Runs of safe
+/// characters are written as a single slice.
+pub(crate) fn write_escaped_href(f: &mut dyn Write, text: &str) -> std::fmt::Result {
+    let mut run_start = 0;
     for (index, c) in text.char_indices() {
-        if !c.is_ascii() || HREF_SAFE[c as usize] == 0 {
-            match c {
-                '&' => result.push_str("&amp;"),
-                _ => {
-                    for byte_index in index..index + c.len_utf8() {
-                        result.push('%');
-                        result.push_str(&format!("{:X}", text.as_bytes()[byte_index]));
-                    }
+        if c.is_ascii() && HREF_SAFE[c as usize] != 0 {
+            continue;
+        }
+        f.write_str(&text[run_start..index])?;
+        match c {
+            '&' => f.write_str("&amp;")?,
+            _ => {
+                for byte in &text.as_bytes()[index..index + c.len_utf8()] {
+                    std::write!(f, "%{byte:X}")?;
                 }
             }
-        } else {
-            result.push(c);
         }
+        run_start = index + c.len_utf8();
     }
-
-    result
+    f.write_str(&text[run_start..])
 }
 
 #[inline]
@@ -93,49 +101,160 @@ fn get_special_entity_replacement(c: char) -> Option<&'static str> {
     }
 }
 
-fn replace_special_entities(text: &str) -> String {
+pub(crate) fn escape_body_text(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
-
-    for c in text.chars() {
-        match get_special_entity_replacement(c) {
-            Some(entity) => result.push_str(entity),
-            _ => result.push(c),
-        }
-    }
-
+    let _ = write_escaped_body_text(&mut result, text);
     result
 }
 
-pub(crate) fn escape_body_text(text: &str) -> String {
-    replace_special_entities(text)
+/// Write `text` into `f` with special characters replaced by their entities, like
+/// [escape_body_text], without allocating. Runs of plain characters are written as a single slice.
+pub(crate) fn write_escaped_body_text(f: &mut dyn Write, text: &str) -> std::fmt::Result {
+    let mut run_start = 0;
+    for (index, c) in text.char_indices() {
+        if let Some(entity) = get_special_entity_replacement(c) {
+            f.write_str(&text[run_start..index])?;
+            f.write_str(entity)?;
+            run_start = index + c.len_utf8();
+        }
+    }
+    f.write_str(&text[run_start..])
 }
 
 /// Processes the list of inline elements by taking only the visual text that appears within each
 /// item. For example, a `Strong` element like `**hello**` would just be written as `hello` rather
 /// than `<strong>hello</strong>` as it might in an html format.
-pub(crate) fn format_plain_text(elements: &Vec<InlineContent>) -> String {
-    let mut buffer = String::new();
-    format_plain_text_inner(&mut buffer, &elements);
-    buffer
+pub(crate) fn write_plain_text(f: &mut dyn Write, elements: &[InlineContent]) -> std::fmt::Result {
+    for element in elements {
+        match element {
+            InlineContent::Text(text) => f.write_str(text)?,
+            InlineContent::Strong(strong) => write_plain_text(f, strong.content())?,
+            InlineContent::Emphasis(emphasis) => write_plain_text(f, emphasis.content())?,
+            InlineContent::Link(link) => write_plain_text(f, link.label())?,
+            InlineContent::CodeSpan(code_span) => f.write_str(code_span.content())?,
+            InlineContent::HardLineBreak => {}
+            InlineContent::Hook(hook) => write_plain_text(f, hook.content())?,
+            InlineContent::Strikethrough(strikethrough) => {
+                write_plain_text(f, strikethrough.content())?
+            }
+            InlineContent::Emoji(emoji) => f.write_str(emoji.shortcode())?,
+            InlineContent::Mention(mention) => {
+                write!(f, "<{}{{{}}}>", mention.sigil(), mention.variable().name())?
+            }
+            InlineContent::KeyboardKey(key) => f.write_str(key.name())?,
+            InlineContent::MessageReference(reference) => write!(f, "{{@{}}}", reference.key())?,
+            InlineContent::Icu(icu) => write_plain_icu(f, icu)?,
+            InlineContent::IcuPound => f.write_char('#')?,
+        }
+    }
+    Ok(())
 }
 
-fn format_plain_text_inner(buffer: &mut String, elements: &Vec<InlineContent>) {
+/// Write an ICU placeholder in its source syntax, so that it is still filled in when the text is
+/// formatted. Only the visual text of plural and select arms is kept, like [write_plain_text].
+fn write_plain_icu(f: &mut dyn Write, icu: &Icu) -> std::fmt::Result {
+    f.write_char('{')?;
+    match icu {
+        Icu::IcuVariable(variable) => {
+            f.write_str(variable.name())?;
+            if let Some(default_value) = variable.default_value() {
+                write!(f, "|{default_value}")?;
+            }
+        }
+        Icu::IcuPlural(plural) => {
+            let kind = match plural.kind() {
+                IcuPluralKind::Plural => "plural",
+                IcuPluralKind::SelectOrdinal => "selectordinal",
+            };
+            write!(f, "{}, {kind},", plural.name())?;
+            write_plain_icu_arms(f, plural.arms())?;
+        }
+        Icu::IcuSelect(select) => {
+            write!(f, "{}, select,", select.name())?;
+            write_plain_icu_arms(f, select.arms())?;
+        }
+        Icu::IcuDate(date) => {
+            write!(f, "{}, date", date.name())?;
+            if let Some(style) = date.style() {
+                write!(f, ", {}", style.text())?;
+            }
+        }
+        Icu::IcuTime(time) => {
+            write!(f, "{}, time", time.name())?;
+            if let Some(style) = time.style() {
+                write!(f, ", {}", style.text())?;
+            }
+        }
+        Icu::IcuNumber(number) => {
+            write!(f, "{}, number", number.name())?;
+            if let Some(style) = number.style() {
+                write!(f, ", {}", style.text())?;
+            }
+        }
+    }
+    f.write_char('}')
+}
+
+fn write_plain_icu_arms(f: &mut dyn Write, arms: &[IcuPluralArm]) -> std::fmt::Result {
+    for arm in arms {
+        write!(f, " {} {{", arm.selector())?;
+        write_plain_text(f, arm.content())?;
+        f.write_char('}')?;
+    }
+    Ok(())
+}
+
+/// Returns a url-safe anchor for a heading with the given content, like `getting-started` for
+/// `## Getting *Started*!`. Only the visible text of the content is used, lowercased, with runs of
+/// whitespace and dashes collapsed into a single `-` and all other punctuation removed. ICU
+/// placeholders contribute their variable name rather than any value, so the anchor stays the same
+/// no matter what the message is formatted with.
+pub(crate) fn heading_slug(elements: &[InlineContent]) -> String {
+    let mut text = String::new();
+    write_slug_text(&mut text, elements);
+
+    let mut slug = String::with_capacity(text.len());
+    let mut pending_separator = false;
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            if pending_separator && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_separator = false;
+            slug.extend(c.to_lowercase());
+        } else if c.is_whitespace() || c == '-' {
+            pending_separator = true;
+        }
+    }
+    slug
+}
+
+fn write_slug_text(f: &mut String, elements: &[InlineContent]) {
     for element in elements {
         match element {
-            InlineContent::Text(text) => buffer.push_str(&text),
-            InlineContent::Strong(strong) => format_plain_text_inner(buffer, strong.content()),
-            InlineContent::Emphasis(emphasis) => {
-                format_plain_text_inner(buffer, emphasis.content())
-            }
-            InlineContent::Link(link) => format_plain_text_inner(buffer, link.label()),
-            InlineContent::CodeSpan(code_span) => buffer.push_str(code_span.content()),
-            InlineContent::HardLineBreak => {}
-            InlineContent::Hook(hook) => format_plain_text_inner(buffer, hook.content()),
+            InlineContent::Text(text) => f.push_str(text),
+            InlineContent::Strong(strong) => write_slug_text(f, strong.content()),
+            InlineContent::Emphasis(emphasis) => write_slug_text(f, emphasis.content()),
+            InlineContent::Link(link) => write_slug_text(f, link.label()),
+            InlineContent::CodeSpan(code_span) => f.push_str(code_span.content()),
+            InlineContent::HardLineBreak => f.push(' '),
+            InlineContent::Hook(hook) => write_slug_text(f, hook.content()),
             InlineContent::Strikethrough(strikethrough) => {
-                format_plain_text_inner(buffer, strikethrough.content())
+                write_slug_text(f, strikethrough.content())
             }
-            InlineContent::Icu(_) => todo!(),
-            InlineContent::IcuPound => buffer.push('#'),
+            InlineContent::Emoji(emoji) => f.push_str(emoji.name()),
+            InlineContent::Mention(mention) => f.push_str(mention.variable().name()),
+            InlineContent::KeyboardKey(key) => f.push_str(key.name()),
+            InlineContent::MessageReference(reference) => f.push_str(reference.key()),
+            InlineContent::Icu(icu) => f.push_str(match icu {
+                Icu::IcuVariable(variable) => variable.name(),
+                Icu::IcuPlural(plural) => plural.name(),
+                Icu::IcuSelect(select) => select.name(),
+                Icu::IcuDate(date) => date.name(),
+                Icu::IcuTime(time) => time.name(),
+                Icu::IcuNumber(number) => number.name(),
+            }),
+            InlineContent::IcuPound => {}
         }
     }
 }
@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 
 use super::syntax::SyntaxKind;
+use crate::options::ParserOptions;
 
 /// An indicator of the start or end of a block, including the byte position in
 /// the source text where the bound occurs, and the syntax kind it represents.
@@ -39,8 +40,11 @@ impl BlockBound {
 struct Line {
     offset: usize,
     leading_offset: usize,
-    /// The length of the line, excluding any trailing newline character.
+    /// The length of the line, excluding any trailing line ending.
     line_length: usize,
+    /// The length of the line ending after this line, either 1 for `\n` or
+    /// `\r`, or 2 for `\r\n`. This is 0 for the last line of the input.
+    line_ending_length: usize,
     leading_spaces: usize,
     /// True if this is the last line of the input, meaning there is no
     /// trailing newline character after it.
@@ -273,11 +277,11 @@ pub(crate) struct BlockParser<'a> {
 }
 
 impl<'a> BlockParser<'a> {
-    pub(crate) fn new(text: &'a str) -> Self {
+    pub(crate) fn new(text: &'a str, options: &ParserOptions) -> Self {
         Self {
             text,
             bounds: vec![],
-            lines: create_lines(text),
+            lines: create_lines(text, options),
             previous_line: None,
         }
     }
@@ -374,28 +378,27 @@ impl<'a> BlockParser<'a> {
     fn consume_atx_heading(&mut self) {
         self.push_start(SyntaxKind::ATX_HEADING);
         let offset = self.current_line().content_offset();
+        let content = self.current_line().get_content(self.text);
         let inline_start = offset
-            + self
-                .current_line()
-                .get_content(self.text)
+            + content
                 .find(|c: char| c.is_ascii_whitespace())
-                .map_or(self.current_line().line_length, |index| index + 1);
+                .map_or(content.len(), |index| index + 1);
 
         self.push_inline_start(inline_start);
 
         // Check for ICU content inside the heading and consume lines until it is fully closed
         // before attempting to find the end of the inline content. If a line ends in an ICU
         // context, the next line must by definition start in the same context, so that does not
-        // need to be checked here.
-        while self.current_line().ends_inside_icu_context() {
+        // need to be checked here. An ICU context that is never closed runs until the end of the
+        // input, so the last line always ends the heading.
+        while self.lines.len() > 1 && self.current_line().ends_inside_icu_context() {
             self.advance();
         }
 
         // Once the ICU content is fully passed, the now-current line can be checked to find the
         // end bound of the inline content.
-        let mut end_iter = self
-            .current_line()
-            .get_content(self.text)
+        let content = self.current_line().get_content(self.text);
+        let mut end_iter = content
             .char_indices()
             .rev()
             .skip_while(|(_, c)| c.is_ascii_whitespace())
@@ -405,9 +408,7 @@ impl<'a> BlockParser<'a> {
         // inline content.
         let mut inline_end = end_iter
             .peek()
-            .map_or(self.current_line().line_length, |(index, c)| {
-                index + c.len_utf8()
-            });
+            .map_or(content.len(), |(index, c)| index + c.len_utf8());
 
         // Then, if that next one is a hash, collect all the following hashes, then check that the
         // character after that is another space to signify it as the closing hash sequence.
@@ -576,17 +577,14 @@ impl<'a> BlockParser<'a> {
     /// line. This is useful for blocks that include trailing line endings, like
     /// indented code blocks.
     fn push_end_after_line(&mut self, kind: SyntaxKind, line: Line) {
-        let mut position = line.end_offset();
-        if !line.is_last_line {
-            position += 1;
-        }
+        let position = line.end_offset() + line.line_ending_length;
         self.bounds.push(BlockBound::End(position, kind));
     }
 }
 
 /// Iterate the given text, creating a new Line struct with information about
 /// position, length, and other characteristics for each line it contains.
-fn create_lines(text: &str) -> VecDeque<Line> {
+fn create_lines(text: &str, options: &ParserOptions) -> VecDeque<Line> {
     let mut offset = 0;
 
     let mut lines = vec![];
@@ -599,6 +597,7 @@ fn create_lines(text: &str) -> VecDeque<Line> {
         let mut leading_offset = 0;
         let mut leading_spaces = 0;
         let mut newline_index: Option<usize> = None;
+        let mut line_ending_length = 0;
         let mut has_found_content = false;
 
         let line_bytes = line_text[0..].as_bytes();
@@ -620,13 +619,30 @@ fn create_lines(text: &str) -> VecDeque<Line> {
                 }
                 b'\n' => {
                     newline_index = Some(index);
+                    line_ending_length = 1;
+                    break;
+                }
+                // Carriage returns are also line endings, either on their own or as part of a
+                // `\r\n` pair, matching how the lexer consumes them.
+                b'\r' => {
+                    newline_index = Some(index);
+                    line_ending_length = match line_bytes.get(index + 1) {
+                        Some(b'\n') => 2,
+                        _ => 1,
+                    };
                     break;
                 }
                 // ICU segment matching. If a line contains an open brace, then the icu context
                 // depth increases. For every closing brace, it decreases. ICU also uses `'` to
                 // escape brace characters, so if we see one and the following character is a brace,
-                // it will not affect the current balance.
-                b'\'' if matches!(line_bytes.get(index + 1), Some(b'{' | b'}')) => index += 1,
+                // it will not affect the current balance, unless apostrophes are configured to
+                // always be literal.
+                b'\''
+                    if options.apostrophes_escape_braces()
+                        && matches!(line_bytes.get(index + 1), Some(b'{' | b'}')) =>
+                {
+                    index += 1
+                }
                 b'{' => icu_brace_balance += 1,
                 b'}' => icu_brace_balance = icu_brace_balance.saturating_sub(1),
                 _ => has_found_content = true,
@@ -640,13 +656,14 @@ fn create_lines(text: &str) -> VecDeque<Line> {
             offset,
             leading_offset,
             line_length,
+            line_ending_length,
             leading_spaces,
             is_last_line: newline_index.is_none(),
             starting_icu_brace_balance,
             ending_icu_brace_balance: icu_brace_balance,
         });
 
-        offset += line_length + 1;
+        offset += line_length + line_ending_length;
     }
 
     // If the last line ended with a newline character, then there is one last
@@ -656,6 +673,7 @@ fn create_lines(text: &str) -> VecDeque<Line> {
             offset,
             leading_offset: 0,
             line_length: 0,
+            line_ending_length: 0,
             leading_spaces: 0,
             is_last_line: true,
             starting_icu_brace_balance: icu_brace_balance,
@@ -671,12 +689,12 @@ mod test {
     use test_case::test_case;
 
     use super::{create_lines, BlockBound, BlockParser};
-    use crate::SyntaxKind;
+    use crate::{ParserOptions, SyntaxKind};
 
     #[test]
     fn print_test() {
         let text = "some text\n\n\non multiple\n  lines";
-        let lines = create_lines(text);
+        let lines = create_lines(text, &ParserOptions::default());
 
         println!("{:#?}", lines)
     }
@@ -684,7 +702,7 @@ mod test {
     #[test]
     fn creates_lines() {
         let text = "some text\non multiple\n  lines\n";
-        let lines = create_lines(text);
+        let lines = create_lines(text, &ParserOptions::default());
 
         let known_lines = vec!["some text", "on multiple", "  lines", ""];
 
@@ -711,7 +729,7 @@ mod test {
             })
             .collect::<Vec<BlockBound>>();
 
-        let parser = BlockParser::new(text);
+        let parser = BlockParser::new(text, &ParserOptions::default());
         let bounds = parser.parse_into_block_bounds();
 
         assert_eq!(bounds, expected);
@@ -20,6 +20,20 @@ pub(crate) fn byte_is_significant_punctuation(byte: u8) -> bool {
     SIGNIFICANT_PUNCTUATION_BYTES[byte as usize] != 0
 }
 
+/// Returns true if the given byte can be part of the name in an emoji shortcode, like `smile` or
+/// `thumbs_up` or `+1`.
+#[inline(always)]
+pub(crate) fn byte_is_emoji_name(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'+' | b'-')
+}
+
+/// Returns true if the given byte can't be part of the name of a keyboard key like `[[Ctrl]]`,
+/// meaning the name has to end before it.
+#[inline(always)]
+pub(crate) fn byte_ends_keyboard_key(byte: u8) -> bool {
+    matches!(byte, b'[' | b']' | b'{' | b'}' | b'\n' | b'\r')
+}
+
 // Learned from: https://nullprogram.com/blog/2017/10/06/
 #[rustfmt::skip]
 static UTF8_LENGTH_LOOKUP: [usize; 32] = [
@@ -0,0 +1,40 @@
+use std::ops::Range;
+
+/// A problem found while parsing a message that the parser was able to recover from.
+///
+/// Recovered content is kept in the resulting Document as well as possible, either as trivia or as
+/// an [crate::BlockNode::Error] node containing the unparsed source text, and the diagnostic
+/// describes what was wrong and where.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    message: String,
+    span: Range<usize>,
+}
+
+impl ParseDiagnostic {
+    pub fn new(message: impl Into<String>, span: Range<usize>) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The byte range in the message source that this diagnostic applies to.
+    pub fn span(&self) -> &Range<usize> {
+        &self.span
+    }
+}
+
+impl std::fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
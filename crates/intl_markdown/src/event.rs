@@ -2,6 +2,8 @@ use std::fmt::Write;
 use std::rc::Rc;
 
 use crate::token::{SourceText, Token, TriviaList, TriviaPointer};
+use crate::tree_builder::SyntaxResult;
+use crate::ParseDiagnostic;
 
 use super::{ICUMarkdownParser, SyntaxKind, SyntaxToken};
 
@@ -116,6 +118,9 @@ pub(crate) struct EventBuffer<I> {
     trivia_list: Rc<TriviaList>,
     trivia_cursor: usize,
     peeked: Option<Option<Event>>,
+    /// Where the most recently read token ended, since Start and Finish events don't have a
+    /// position of their own.
+    position: usize,
 }
 
 impl<I> EventBuffer<I>
@@ -129,6 +134,7 @@ where
             trivia_list: Rc::new(trivia_list),
             trivia_cursor: 0,
             peeked: None,
+            position: 0,
         }
     }
 
@@ -150,57 +156,58 @@ where
     ///
     /// This method also consumes leading and trailing trivia.
     ///
-    /// ## Panics
-    ///
-    /// Panics if the event does not contain a token (i.e., is a block event).
-    pub(crate) fn next_as_token(&mut self) -> Token {
+    /// Returns an error if the event does not contain a token (i.e., is a
+    /// block event), like when a required token is missing from a node.
+    pub(crate) fn next_as_token(&mut self) -> SyntaxResult<Token> {
         match self.next() {
             Some(Event::Token(syntax_token)) => {
+                self.position = syntax_token.span_end() as usize;
                 let trivia_pointer = TriviaPointer::from_token(
                     &syntax_token,
                     &mut self.trivia_list,
                     &mut self.trivia_cursor,
                 );
-                Token::from_syntax(syntax_token, self.source.clone(), trivia_pointer)
+                Ok(Token::from_syntax(
+                    syntax_token,
+                    self.source.clone(),
+                    trivia_pointer,
+                ))
             }
-            found => panic!(
-                "Attempted to read next event as a token, but got {:?} instead",
-                found
-            ),
+            _ => Err(self.unexpected_event_diagnostic()),
         }
     }
 
     /// Consumes the next event from the buffer, asserts that it is a Start
-    /// event for the given kind, and returns that event.
-    ///
-    /// ## Panics
+    /// event, and returns that event.
     ///
-    /// Panics if the event is not a matching Start event.
-    pub(crate) fn next_as_start(&mut self) -> Event {
+    /// Returns an error if the event is not a Start event.
+    pub(crate) fn next_as_start(&mut self) -> SyntaxResult<Event> {
         match self.next() {
-            Some(event @ Event::Start(_)) => event,
-            found => panic!(
-                "Attempted to read next event as a Start event, but got {:?} instead",
-                found
-            ),
+            Some(event @ Event::Start(_)) => Ok(event),
+            _ => Err(self.unexpected_event_diagnostic()),
         }
     }
 
     /// Consumes the next event from the buffer, asserts that it is a Finish
     /// event for the given kind, and returns that event.
     ///
-    /// ## Panics
-    ///
-    /// Panics if the event is not a matching Finish event.
-    pub(crate) fn next_as_finish(&mut self, expected_kind: SyntaxKind) -> Event {
+    /// Returns an error if the event is not a matching Finish event, like
+    /// when the node has more children than expected.
+    pub(crate) fn next_as_finish(&mut self, expected_kind: SyntaxKind) -> SyntaxResult<Event> {
         match self.next() {
-            Some(event @ Event::Finish(_)) if event.kind() == expected_kind => event,
-            found => panic!(
-                "Attempted to read next event as a Finish event for {:?}, but got {:?} instead",
-                expected_kind, found
-            ),
+            Some(event @ Event::Finish(_)) if event.kind() == expected_kind => Ok(event),
+            _ => Err(self.unexpected_event_diagnostic()),
         }
     }
+
+    /// Returns a diagnostic for an event that doesn't fit the node being
+    /// read, spanning from the last read token to the end of the source.
+    pub(crate) fn unexpected_event_diagnostic(&self) -> ParseDiagnostic {
+        ParseDiagnostic::new(
+            "Message structure could not be determined",
+            self.position..self.source.len(),
+        )
+    }
 }
 
 #[allow(unused)]
@@ -4,13 +4,18 @@
 //! serialized to JSON. However, this format also allows more compact representations like keyless
 //! JSON or even binary formats, and includes extensions to support additional features like link
 //! attributes.
+//!
+//! Compiled nodes never own any data. Strings are borrowed directly from the source Document, and
+//! lists of nodes are allocated in a [CompileArena], which can be reset and reused between messages
+//! so that compiling many messages in a row doesn't need to allocate for every individual node.
+use bumpalo::Bump;
 use serde::ser::SerializeMap;
 use serde::{self, Serialize, Serializer};
 
 use crate::ast::{
-    BlockNode, CodeBlock, CodeSpan, Document, Emphasis, Heading, Hook, Icu, IcuDate, IcuNumber,
-    IcuPlural, IcuPluralArm, IcuPluralKind, IcuSelect, IcuTime, IcuVariable, InlineContent, Link,
-    LinkDestination, Paragraph, Strikethrough, Strong,
+    BlockNode, CodeBlock, CodeSpan, Document, Emoji, Emphasis, Heading, Hook, Icu, IcuDate,
+    IcuNumber, IcuPlural, IcuPluralArm, IcuPluralKind, IcuSelect, IcuTime, IcuVariable,
+    InlineContent, Link, LinkDestination, Mention, Paragraph, Strikethrough, Strong,
 };
 use crate::icu::tags::DEFAULT_TAG_NAMES;
 
@@ -29,10 +34,37 @@ pub enum FormatJsElementType {
     Tag,
 }
 
+/// Storage for the nodes of compiled messages.
+///
+/// Nodes compiled into an arena live as long as the arena does, and [CompileArena::reset] frees all
+/// of them at once while keeping the allocated memory around for the next message.
+#[derive(Default)]
+pub struct CompileArena(Bump);
+
+impl CompileArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Free every node compiled into this arena so that its memory can be reused.
+    pub fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    fn list<'a, I>(&'a self, nodes: I) -> FormatJsNode<'a>
+    where
+        I: IntoIterator<Item = FormatJsNode<'a>>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        FormatJsNode::ListNode(self.0.alloc_slice_fill_iter(nodes))
+    }
+}
+
 /// Compile a parsed ICU-Markdown document into a FormatJS Node tree, that can then be directly
-/// serialized to any format and back with any other FormatJS-compatible tools.
-pub fn compile_to_format_js(document: &Document) -> FormatJsNode {
-    FormatJsNode::from(document)
+/// serialized to any format and back with any other FormatJS-compatible tools. All nodes of the
+/// tree are allocated in `arena`.
+pub fn compile_to_format_js<'a>(document: &'a Document, arena: &'a CompileArena) -> FormatJsNode<'a> {
+    FormatJsCompiler { arena }.document(document)
 }
 
 /// A mono-morphed type capable of representing any node in an ICU tree following the FormatJS JSON
@@ -43,17 +75,7 @@ pub fn compile_to_format_js(document: &Document) -> FormatJsNode {
 pub enum FormatJsNode<'a> {
     Literal(&'a str),
     SingleNode(FormatJsSingleNode<'a>),
-    ListNode(Vec<FormatJsNode<'a>>),
-}
-
-impl<'a> FormatJsNode<'a> {
-    fn list(values: Vec<FormatJsNode<'a>>) -> Self {
-        Self::from(values)
-    }
-
-    fn literal(value: &'a str) -> Self {
-        Self::Literal(value)
-    }
+    ListNode(&'a [FormatJsNode<'a>]),
 }
 
 #[derive(Debug, Default, Eq, PartialEq, Serialize)]
@@ -63,7 +85,7 @@ pub struct FormatJsSingleNode<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub children: Option<Box<FormatJsNode<'a>>>,
+    pub children: Option<&'a FormatJsNode<'a>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<FormatJsNodeOptions<'a>>,
     /// FormatJS Extension: `control` is not part of FormatJS and may break expected behavior. It is
@@ -74,22 +96,29 @@ pub struct FormatJsSingleNode<'a> {
     /// "visible" and what is "hidden". By adding this `control` type, the distinction is always
     /// trivial to process.
     ///
-    /// This value may have other uses in the future but for now is simply reserved for links.
+    /// Links use this for their destination, and headings use it for their id, so that renderers
+    /// can add an anchor to long-form content.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub control: Option<Box<FormatJsNode<'a>>>,
+    pub control: Option<&'a FormatJsNode<'a>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<usize>,
     #[serde(rename = "pluralType", skip_serializing_if = "Option::is_none")]
     pub plural_type: Option<IcuPluralKind>,
+    /// FormatJS Extension: the text used in place of an argument when no value is given for it,
+    /// from `{name|default}`. Arguments have no other fields after their value, so this is always
+    /// the third item of a keyless argument. FormatJS has no equivalent, so it is left out of
+    /// react-intl output and plain ICU strings.
+    #[serde(rename = "default", skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<&'a str>,
 }
 
 impl<'a> FormatJsSingleNode<'a> {
     fn tag(tag_name: &'a str) -> Self {
         Self::default()
             .with_type(FormatJsElementType::Tag)
-            .with_value(tag_name.into())
+            .with_value(tag_name)
     }
 
     fn variable(name: &'a str) -> Self {
@@ -108,21 +137,21 @@ impl<'a> FormatJsSingleNode<'a> {
         self
     }
 
-    fn with_children(mut self, children: FormatJsNode<'a>) -> Self {
+    fn with_children(mut self, children: &'a FormatJsNode<'a>) -> Self {
         debug_assert!(
             matches!(children, FormatJsNode::ListNode(_)),
             "`children` should always be a list of elements"
         );
-        self.children = Some(Box::new(children));
+        self.children = Some(children);
         self
     }
 
-    fn with_control(mut self, control: FormatJsNode<'a>) -> Self {
+    fn with_control(mut self, control: &'a FormatJsNode<'a>) -> Self {
         debug_assert!(
             matches!(control, FormatJsNode::ListNode(_)),
             "`control` should always be a list of elements"
         );
-        self.control = Some(Box::new(control));
+        self.control = Some(control);
         self
     }
 
@@ -136,6 +165,11 @@ impl<'a> FormatJsSingleNode<'a> {
         self
     }
 
+    fn with_default_value(mut self, default_value: Option<&'a str>) -> Self {
+        self.default_value = default_value;
+        self
+    }
+
     fn with_offset(mut self, offset: usize) -> Self {
         self.offset = Some(offset);
         self
@@ -153,24 +187,33 @@ impl<'a> From<FormatJsSingleNode<'a>> for FormatJsNode<'a> {
     }
 }
 
-impl<'a> From<Vec<FormatJsNode<'a>>> for FormatJsNode<'a> {
-    fn from(value: Vec<FormatJsNode<'a>>) -> Self {
-        FormatJsNode::ListNode(value)
+impl<'a> From<&'a str> for FormatJsNode<'a> {
+    fn from(value: &'a str) -> Self {
+        FormatJsNode::Literal(value)
     }
 }
 
 //#region Serialization
 
+/// The compiled arms of a plural or select, as pairs of the selector and its content.
 #[derive(Debug, Eq, PartialEq)]
-pub struct FormatJsNodeOptions<'a>(&'a Vec<IcuPluralArm>);
+pub struct FormatJsNodeOptions<'a>(&'a [(&'a str, FormatJsNode<'a>)]);
+
+impl<'a> FormatJsNodeOptions<'a> {
+    /// Every arm of the plural or select, in the order they were written.
+    pub fn arms(&self) -> &'a [(&'a str, FormatJsNode<'a>)] {
+        self.0
+    }
+}
+
 impl Serialize for FormatJsNodeOptions<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         let mut arms = serializer.serialize_map(Some(self.0.len()))?;
-        for arm in self.0 {
-            arms.serialize_entry(arm.selector(), &FormatJsNode::from(arm.content()))?;
+        for (selector, content) in self.0 {
+            arms.serialize_entry(selector, content)?;
         }
         arms.end()
     }
@@ -179,201 +222,242 @@ impl Serialize for FormatJsNodeOptions<'_> {
 //#endregion
 
 //#region AST to Node conversions
-impl<'a> From<&'a str> for FormatJsNode<'a> {
-    fn from(value: &'a str) -> Self {
-        FormatJsNode::Literal(value)
-    }
+
+struct FormatJsCompiler<'a> {
+    arena: &'a CompileArena,
 }
-impl<'a> From<&'a String> for FormatJsNode<'a> {
-    fn from(value: &'a String) -> Self {
-        FormatJsNode::Literal(value)
+
+impl<'a> FormatJsCompiler<'a> {
+    fn alloc(&self, node: FormatJsNode<'a>) -> &'a FormatJsNode<'a> {
+        self.arena.0.alloc(node)
+    }
+
+    /// Allocate a list node containing the given nodes, for use as `children` or `control`.
+    fn alloc_list<I>(&self, nodes: I) -> &'a FormatJsNode<'a>
+    where
+        I: IntoIterator<Item = FormatJsNode<'a>>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        self.alloc(self.arena.list(nodes))
+    }
+
+    fn inline_list(&self, content: &'a [InlineContent]) -> FormatJsNode<'a> {
+        self.arena
+            .list(content.iter().map(|content| self.inline(content)))
+    }
+
+    fn tag(&self, tag_name: &'a str, content: &'a [InlineContent]) -> FormatJsNode<'a> {
+        let children = self.alloc(self.inline_list(content));
+        FormatJsSingleNode::tag(tag_name)
+            .with_children(children)
+            .into()
     }
-}
 
-impl<'a> From<&'a Document> for FormatJsNode<'a> {
-    fn from(value: &'a Document) -> Self {
-        match value.blocks().get(0) {
+    /// A tag containing only a single literal, like the text of code spans and blocks.
+    fn literal_tag(&self, tag_name: &'a str, content: &'a str) -> FormatJsNode<'a> {
+        FormatJsSingleNode::tag(tag_name)
+            .with_children(self.alloc_list([FormatJsNode::Literal(content)]))
+            .into()
+    }
+
+    fn empty_tag(&self, tag_name: &'a str) -> FormatJsNode<'a> {
+        FormatJsSingleNode::tag(tag_name)
+            .with_children(self.alloc_list([]))
+            .into()
+    }
+
+    fn document(&self, document: &'a Document) -> FormatJsNode<'a> {
+        match document.blocks().first() {
             // For Documents with a single InlineContent segment, the value shouldn't get wrapped
             // with another list node. Otherwise, the output is like `[["content"]]`.
-            Some(BlockNode::InlineContent(content)) if value.blocks().len() == 1 => {
-                FormatJsNode::from(content)
+            Some(BlockNode::InlineContent(content)) if document.blocks().len() == 1 => {
+                self.inline_list(content)
             }
-            _ => FormatJsNode::list(value.blocks().iter().map(FormatJsNode::from).collect()),
+            _ => self
+                .arena
+                .list(document.blocks().iter().map(|block| self.block(block))),
         }
     }
-}
 
-impl<'a> From<&'a InlineContent> for FormatJsNode<'a> {
-    fn from(value: &'a InlineContent) -> Self {
-        match value {
-            InlineContent::Text(text) => FormatJsNode::literal(text),
-            InlineContent::Emphasis(emphasis) => FormatJsNode::from(emphasis),
-            InlineContent::Strong(strong) => FormatJsNode::from(strong),
-            InlineContent::Link(link) => FormatJsNode::from(link),
-            InlineContent::CodeSpan(code_span) => FormatJsNode::from(code_span),
-            InlineContent::HardLineBreak => FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.br())
-                .with_children(FormatJsNode::list(vec![]))
-                .into(),
-            InlineContent::Hook(hook) => FormatJsNode::from(hook),
-            InlineContent::Strikethrough(strikethrough) => FormatJsNode::from(strikethrough),
-            InlineContent::Icu(icu) => FormatJsNode::from(icu),
+    fn block(&self, block: &'a BlockNode) -> FormatJsNode<'a> {
+        match block {
+            BlockNode::Paragraph(paragraph) => self.paragraph(paragraph),
+            BlockNode::Heading(heading) => self.heading(heading),
+            BlockNode::CodeBlock(code_block) => self.code_block(code_block),
+            BlockNode::InlineContent(inline_content) => self.inline_list(inline_content),
+            BlockNode::Error(text) => FormatJsNode::Literal(text),
+            BlockNode::ThematicBreak => self.empty_tag(DEFAULT_TAG_NAMES.hr()),
+        }
+    }
+
+    fn inline(&self, content: &'a InlineContent) -> FormatJsNode<'a> {
+        match content {
+            InlineContent::Text(text) => FormatJsNode::Literal(text),
+            InlineContent::Emphasis(emphasis) => self.emphasis(emphasis),
+            InlineContent::Strong(strong) => self.strong(strong),
+            InlineContent::Link(link) => self.link(link),
+            InlineContent::CodeSpan(code_span) => self.code_span(code_span),
+            InlineContent::HardLineBreak => self.empty_tag(DEFAULT_TAG_NAMES.br()),
+            InlineContent::Hook(hook) => self.hook(hook),
+            InlineContent::Strikethrough(strikethrough) => self.strikethrough(strikethrough),
+            InlineContent::Emoji(emoji) => self.emoji(emoji),
+            InlineContent::Mention(mention) => self.mention(mention),
+            InlineContent::KeyboardKey(key) => {
+                self.literal_tag(DEFAULT_TAG_NAMES.keyboard_key(), key.name())
+            }
+            // References are resolved before compiling, so any that are left couldn't be, and are
+            // kept as the text they were written with.
+            InlineContent::MessageReference(reference) => FormatJsNode::Literal(
+                self.arena.0.alloc_str(&format!("{{@{}}}", reference.key())),
+            ),
+            InlineContent::Icu(icu) => self.icu(icu),
             InlineContent::IcuPound => FormatJsSingleNode::default()
                 .with_type(FormatJsElementType::Pound)
                 .into(),
         }
     }
-}
 
-impl<'a> From<&'a Vec<InlineContent>> for FormatJsNode<'a> {
-    fn from(value: &'a Vec<InlineContent>) -> Self {
-        FormatJsNode::list(value.iter().map(FormatJsNode::from).collect())
+    fn paragraph(&self, paragraph: &'a Paragraph) -> FormatJsNode<'a> {
+        self.tag(DEFAULT_TAG_NAMES.paragraph(), paragraph.content())
     }
-}
 
-macro_rules! impl_from_for_tag_node {
-    ($struct:ident, $tag:expr, $method:ident) => {
-        impl<'a> From<&'a $struct> for FormatJsNode<'a> {
-            fn from(value: &'a $struct) -> Self {
-                FormatJsSingleNode::tag($tag)
-                    .with_children(value.$method().into())
-                    .into()
-            }
-        }
-    };
-}
+    fn emphasis(&self, emphasis: &'a Emphasis) -> FormatJsNode<'a> {
+        self.tag(DEFAULT_TAG_NAMES.emphasis(), emphasis.content())
+    }
+
+    fn strong(&self, strong: &'a Strong) -> FormatJsNode<'a> {
+        self.tag(DEFAULT_TAG_NAMES.strong(), strong.content())
+    }
+
+    fn strikethrough(&self, strikethrough: &'a Strikethrough) -> FormatJsNode<'a> {
+        self.tag(DEFAULT_TAG_NAMES.strike_through(), strikethrough.content())
+    }
 
-impl_from_for_tag_node!(CodeBlock, DEFAULT_TAG_NAMES.code_block(), content);
-impl_from_for_tag_node!(Paragraph, DEFAULT_TAG_NAMES.paragraph(), content);
-impl_from_for_tag_node!(Emphasis, DEFAULT_TAG_NAMES.emphasis(), content);
-impl_from_for_tag_node!(Strong, DEFAULT_TAG_NAMES.strong(), content);
-impl_from_for_tag_node!(Strikethrough, DEFAULT_TAG_NAMES.strike_through(), content);
-
-impl<'a> From<&'a CodeSpan> for FormatJsNode<'a> {
-    fn from(value: &'a CodeSpan) -> Self {
-        FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.code())
-            .with_children(FormatJsNode::ListNode(vec![FormatJsNode::literal(
-                value.content(),
-            )]))
+    fn heading(&self, heading: &'a Heading) -> FormatJsNode<'a> {
+        let children = self.alloc(self.inline_list(heading.content()));
+        FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.heading(heading.level()))
+            .with_children(children)
+            .with_control(self.alloc_list([FormatJsNode::Literal(heading.id())]))
             .into()
     }
-}
 
-impl<'a> From<&'a Heading> for FormatJsNode<'a> {
-    fn from(value: &'a Heading) -> Self {
-        FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.heading(value.level()))
-            .with_children(value.content().into())
+    fn hook(&self, hook: &'a Hook) -> FormatJsNode<'a> {
+        self.tag(hook.name(), hook.content())
+    }
+
+    /// Emoji keep their shortcode as the content, so renderers that don't handle them still show
+    /// the original text, and the bare name as the control for renderers that do.
+    fn emoji(&self, emoji: &'a Emoji) -> FormatJsNode<'a> {
+        FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.emoji())
+            .with_children(self.alloc_list([FormatJsNode::Literal(emoji.shortcode())]))
+            .with_control(self.alloc_list([FormatJsNode::Literal(emoji.name())]))
             .into()
     }
-}
 
-fn compile_link_children(label: &Vec<InlineContent>) -> FormatJsNode {
-    let mut children = Vec::with_capacity(label.len());
-    children.extend(label.iter().map(FormatJsNode::from));
-    FormatJsNode::list(children)
-}
+    /// Mentions contain the variable with the mentioned id, and use their sigil as the control so
+    /// that renderers know what kind of thing is being mentioned.
+    fn mention(&self, mention: &'a Mention) -> FormatJsNode<'a> {
+        FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.mention())
+            .with_children(self.alloc_list([self.variable(mention.variable())]))
+            .with_control(self.alloc_list([FormatJsNode::Literal(mention.sigil())]))
+            .into()
+    }
 
-fn compile_link_destination(destination: &LinkDestination) -> FormatJsNode {
-    let node = match destination {
-        LinkDestination::Text(text) => FormatJsNode::literal(text),
-        LinkDestination::Placeholder(icu) => FormatJsNode::from(icu),
-        LinkDestination::Handler(handler_name) => FormatJsSingleNode::variable(handler_name).into(),
-    };
-    FormatJsNode::list(vec![node])
-}
+    fn code_span(&self, code_span: &'a CodeSpan) -> FormatJsNode<'a> {
+        self.literal_tag(DEFAULT_TAG_NAMES.code(), code_span.content())
+    }
+
+    fn code_block(&self, code_block: &'a CodeBlock) -> FormatJsNode<'a> {
+        self.literal_tag(DEFAULT_TAG_NAMES.code_block(), code_block.content())
+    }
 
-impl<'a> From<&'a Link> for FormatJsNode<'a> {
-    fn from(value: &'a Link) -> Self {
+    fn link(&self, link: &'a Link) -> FormatJsNode<'a> {
+        let destination = match link.destination() {
+            LinkDestination::Text(text) => FormatJsNode::Literal(text),
+            LinkDestination::Placeholder(icu) => self.icu(icu),
+            LinkDestination::Handler(handler_name) => {
+                FormatJsSingleNode::variable(handler_name).into()
+            }
+        };
+        let children = self.alloc(self.inline_list(link.label()));
         FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.link())
-            .with_children(compile_link_children(value.label()))
-            .with_control(compile_link_destination(value.destination()))
+            .with_children(children)
+            .with_control(self.alloc_list([destination]))
             .into()
     }
-}
 
-impl<'a> From<&'a BlockNode> for FormatJsNode<'a> {
-    fn from(value: &'a BlockNode) -> Self {
-        match value {
-            BlockNode::Paragraph(paragraph) => FormatJsNode::from(paragraph),
-            BlockNode::Heading(heading) => FormatJsNode::from(heading),
-            BlockNode::CodeBlock(code_block) => FormatJsNode::from(code_block),
-            BlockNode::InlineContent(inline_content) => FormatJsNode::from(inline_content),
-            BlockNode::ThematicBreak => FormatJsSingleNode::tag(DEFAULT_TAG_NAMES.hr())
-                .with_children(FormatJsNode::list(vec![]))
-                .into(),
+    fn icu(&self, icu: &'a Icu) -> FormatJsNode<'a> {
+        match icu {
+            Icu::IcuVariable(variable) => self.variable(variable),
+            Icu::IcuPlural(plural) => self.plural(plural),
+            Icu::IcuSelect(select) => self.select(select),
+            Icu::IcuDate(date) => self.date(date),
+            Icu::IcuTime(time) => self.time(time),
+            Icu::IcuNumber(number) => self.number(number),
         }
     }
-}
 
-impl<'a> From<&'a Hook> for FormatJsNode<'a> {
-    fn from(value: &'a Hook) -> Self {
-        FormatJsSingleNode::tag(value.name())
-            .with_children(value.content().into())
+    fn variable(&self, variable: &'a IcuVariable) -> FormatJsNode<'a> {
+        FormatJsSingleNode::variable(variable.name())
+            .with_default_value(variable.default_value())
             .into()
     }
-}
 
-impl<'a> From<&'a Icu> for FormatJsNode<'a> {
-    fn from(value: &'a Icu) -> Self {
-        match value {
-            Icu::IcuVariable(variable) => FormatJsNode::from(variable),
-            Icu::IcuPlural(plural) => FormatJsNode::from(plural),
-            Icu::IcuSelect(select) => FormatJsNode::from(select),
-            Icu::IcuDate(date) => FormatJsNode::from(date),
-            Icu::IcuTime(time) => FormatJsNode::from(time),
-            Icu::IcuNumber(number) => FormatJsNode::from(number),
+    fn formatted_variable(
+        &self,
+        ty: FormatJsElementType,
+        name: &'a str,
+        style: Option<&'a str>,
+    ) -> FormatJsNode<'a> {
+        let mut node = FormatJsSingleNode::default().with_type(ty).with_value(name);
+        if let Some(style) = style {
+            node = node.with_style(style);
         }
+        node.into()
     }
-}
 
-macro_rules! impl_from_for_icu_type {
-    ($struct:ident, $ty:expr) => {
-        impl<'a> From<&'a $struct> for FormatJsNode<'a> {
-            fn from(value: &'a $struct) -> Self {
-                let mut node = FormatJsSingleNode::default()
-                    .with_type($ty)
-                    .with_value(value.name());
-                if value.style().is_some() {
-                    node = node.with_style(value.style().as_ref().unwrap().text());
-                }
-                node.into()
-            }
-        }
-    };
-}
+    fn date(&self, date: &'a IcuDate) -> FormatJsNode<'a> {
+        let style = date.style().as_ref().map(|style| style.text());
+        self.formatted_variable(FormatJsElementType::Date, date.name(), style)
+    }
+
+    fn time(&self, time: &'a IcuTime) -> FormatJsNode<'a> {
+        let style = time.style().as_ref().map(|style| style.text());
+        self.formatted_variable(FormatJsElementType::Time, time.name(), style)
+    }
+
+    fn number(&self, number: &'a IcuNumber) -> FormatJsNode<'a> {
+        let style = number.style().as_ref().map(|style| style.text());
+        self.formatted_variable(FormatJsElementType::Number, number.name(), style)
+    }
 
-impl_from_for_icu_type!(IcuDate, FormatJsElementType::Date);
-impl_from_for_icu_type!(IcuTime, FormatJsElementType::Time);
-impl_from_for_icu_type!(IcuNumber, FormatJsElementType::Number);
+    fn options(&self, arms: &'a [IcuPluralArm]) -> FormatJsNodeOptions<'a> {
+        FormatJsNodeOptions(self.arena.0.alloc_slice_fill_iter(
+            arms.iter()
+                .map(|arm| (arm.selector(), self.inline_list(arm.content()))),
+        ))
+    }
 
-impl<'a> From<&'a IcuPlural> for FormatJsNode<'a> {
-    fn from(value: &'a IcuPlural) -> Self {
+    fn plural(&self, plural: &'a IcuPlural) -> FormatJsNode<'a> {
         FormatJsSingleNode::default()
             .with_type(FormatJsElementType::Plural)
-            .with_value(value.name())
-            .with_options(FormatJsNodeOptions(value.arms()))
+            .with_value(plural.name())
+            .with_options(self.options(plural.arms()))
             // TODO: Implement offset in parsing
             .with_offset(0)
-            .with_plural_type(*value.kind())
+            .with_plural_type(*plural.kind())
             .into()
     }
-}
 
-impl<'a> From<&'a IcuSelect> for FormatJsNode<'a> {
-    fn from(value: &'a IcuSelect) -> Self {
+    fn select(&self, select: &'a IcuSelect) -> FormatJsNode<'a> {
         FormatJsSingleNode::default()
             .with_type(FormatJsElementType::Select)
-            .with_value(value.name())
-            .with_options(FormatJsNodeOptions(value.arms()))
+            .with_value(select.name())
+            .with_options(self.options(select.arms()))
             .into()
     }
 }
 
-impl<'a> From<&'a IcuVariable> for FormatJsNode<'a> {
-    fn from(value: &'a IcuVariable) -> Self {
-        FormatJsSingleNode::variable(value.name()).into()
-    }
-}
-
 //#endregion
 
 #[cfg(test)]
@@ -381,7 +465,14 @@ mod tests {
     use crate::icu::tags::DEFAULT_TAG_NAMES;
     use crate::parse_intl_message;
 
-    use super::{compile_to_format_js, FormatJsElementType, FormatJsNode, FormatJsSingleNode};
+    use super::{
+        compile_to_format_js, CompileArena, FormatJsElementType, FormatJsNode, FormatJsSingleNode,
+    };
+
+    /// Expected nodes are built outside of any arena, so their lists are leaked instead.
+    fn leak<'a>(nodes: Vec<FormatJsNode<'a>>) -> &'a FormatJsNode<'a> {
+        Box::leak(Box::new(FormatJsNode::ListNode(nodes.leak())))
+    }
 
     fn assert_formatjs_with_blocks(
         input_str: &str,
@@ -389,7 +480,8 @@ mod tests {
         include_blocks: bool,
     ) {
         let parsed = parse_intl_message(input_str, include_blocks);
-        let compiled = compile_to_format_js(&parsed);
+        let arena = CompileArena::new();
+        let compiled = compile_to_format_js(&parsed, &arena);
         assert_eq!(
             serde_json::to_string(&compiled).unwrap(),
             serde_json::to_string(expected_node).unwrap()
@@ -402,7 +494,7 @@ mod tests {
 
     macro_rules! tag {
         ($name:expr, [$($content:expr),* $(,)*]) => {
-            FormatJsSingleNode::tag($name).with_children(FormatJsNode::from(vec![$($content.into()),*]))
+            FormatJsSingleNode::tag($name).with_children(leak(vec![$($content.into()),*]))
         }
 
     }
@@ -424,7 +516,7 @@ mod tests {
 
     macro_rules! list {
         ($($item:expr),+ $(,)*) => {
-            FormatJsNode::ListNode(vec![$($item.into()),*])
+            FormatJsNode::ListNode(vec![$($item.into()),*].leak())
         }
     }
 
@@ -450,7 +542,8 @@ mod tests {
     #[test]
     fn links() {
         let doc = parse_intl_message("[a *link*](./somewhere.png)", false);
-        let compiled = compile_to_format_js(&doc);
+        let arena = CompileArena::new();
+        let compiled = compile_to_format_js(&doc, &arena);
         // Asserting that the destination is placed as the first child, then all the label content.
         assert_eq!(
             compiled,
@@ -458,7 +551,7 @@ mod tests {
                 DEFAULT_TAG_NAMES.link(),
                 [lit!("a "), tag!(DEFAULT_TAG_NAMES.emphasis(), ["link"])]
             )
-            .with_control(list!(lit!("./somewhere.png"))))
+            .with_control(leak(vec![lit!("./somewhere.png")])))
         )
     }
 
@@ -483,6 +576,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn icu_number_styles() {
+        assert_formatjs(
+            "{ratio, number, percent}",
+            &list!(var!("ratio", Number).with_style("percent")),
+        );
+        assert_formatjs(
+            "{price, number, currency}",
+            &list!(var!("price", Number).with_style("currency")),
+        );
+
+        let parsed = parse_intl_message("{price, number, ::currency/EUR}", false);
+        let arena = CompileArena::new();
+        assert_eq!(
+            keyless_json::to_string(&compile_to_format_js(&parsed, &arena)).unwrap(),
+            r#"[[2,"price","::currency/EUR"]]"#
+        );
+    }
+
     #[test]
     fn paragraph_text() {
         assert_formatjs_with_blocks(
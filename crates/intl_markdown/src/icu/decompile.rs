@@ -0,0 +1,375 @@
+//! Decompiling is the reverse of [crate::compile_to_format_js]: it takes a compiled FormatJS node
+//! tree, as written into keyless JSON message bundles, and writes it back out as ICU-Markdown
+//! source text.
+//!
+//! Compiling is lossy, so the result is not guaranteed to match the original source text exactly.
+//! Markdown elements always use a single canonical syntax (e.g., `**bold**` rather than
+//! `__bold__`), images become regular links, and plural offsets are dropped. Parsing and compiling
+//! the decompiled text again should still produce the same node tree as the input.
+use serde_json::{Map, Value};
+
+use crate::icu::compile::FormatJsElementType;
+use crate::icu::tags::DEFAULT_TAG_NAMES;
+
+/// An error encountered when the given value is not a valid compiled FormatJS node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecompileError {
+    message: String,
+}
+
+impl DecompileError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for DecompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+type DecompileResult<T> = Result<T, DecompileError>;
+
+/// Write the given compiled message as ICU-Markdown source text. `value` can be any entry of a
+/// keyless JSON bundle, either a list of nodes or a plain string for static messages.
+pub fn decompile_format_js(value: &Value) -> DecompileResult<String> {
+    let mut f = String::new();
+    match value {
+        Value::Array(items) if !is_element(items) && items.iter().any(is_block) => {
+            for (index, block) in items.iter().enumerate() {
+                if index > 0 {
+                    f.push_str("\n\n");
+                }
+                write_block(&mut f, block)?;
+            }
+        }
+        _ => write_node(&mut f, value)?,
+    }
+    Ok(f)
+}
+
+/// Returns true if the list of `items` represents a single element, like `[1, "name"]`, rather
+/// than a list of nodes. Elements always start with their type number.
+fn is_element(items: &[Value]) -> bool {
+    items.first().is_some_and(Value::is_number)
+}
+
+/// Block-level content only appears at the top level of a message, either as a block element
+/// like a paragraph or heading, or as a nested list of inline content.
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(items) if is_element(items) => items
+            .get(1)
+            .and_then(Value::as_str)
+            .is_some_and(is_block_tag),
+        Value::Array(_) => true,
+        _ => false,
+    }
+}
+
+fn is_block_tag(tag: &str) -> bool {
+    tag == DEFAULT_TAG_NAMES.paragraph()
+        || tag == DEFAULT_TAG_NAMES.code_block()
+        || tag == DEFAULT_TAG_NAMES.hr()
+        || heading_level(tag).is_some()
+}
+
+fn heading_level(tag: &str) -> Option<u8> {
+    (1..=6).find(|level| DEFAULT_TAG_NAMES.heading(*level) == tag)
+}
+
+fn write_block(f: &mut String, block: &Value) -> DecompileResult<()> {
+    let Value::Array(items) = block else {
+        return write_node(f, block);
+    };
+    if !is_element(items) {
+        return write_nodes(f, block);
+    }
+
+    let tag = element_str(items, 1)?;
+    if tag == DEFAULT_TAG_NAMES.paragraph() {
+        write_nodes(f, element_field(items, 2)?)
+    } else if tag == DEFAULT_TAG_NAMES.code_block() {
+        let content = literal_content(element_field(items, 2)?)?;
+        let fence = "`".repeat(longest_run(content, '`').max(2) + 1);
+        f.push_str(&fence);
+        f.push('\n');
+        f.push_str(content);
+        if !content.ends_with('\n') {
+            f.push('\n');
+        }
+        f.push_str(&fence);
+        Ok(())
+    } else if tag == DEFAULT_TAG_NAMES.hr() {
+        f.push_str("---");
+        Ok(())
+    } else if let Some(level) = heading_level(tag) {
+        f.push_str(&"#".repeat(level as usize));
+        f.push(' ');
+        write_nodes(f, element_field(items, 2)?)
+    } else {
+        write_node(f, block)
+    }
+}
+
+fn write_nodes(f: &mut String, value: &Value) -> DecompileResult<()> {
+    match value {
+        Value::Array(items) if !is_element(items) => {
+            for item in items {
+                write_node(f, item)?;
+            }
+            Ok(())
+        }
+        _ => Err(DecompileError::new("Expected a list of nodes")),
+    }
+}
+
+fn write_node(f: &mut String, value: &Value) -> DecompileResult<()> {
+    match value {
+        Value::String(text) => {
+            write_text(f, text);
+            Ok(())
+        }
+        Value::Array(items) if is_element(items) => write_element(f, items),
+        Value::Array(_) => write_nodes(f, value),
+        _ => Err(DecompileError::new(format!("Unexpected value {value}"))),
+    }
+}
+
+fn write_element(f: &mut String, items: &[Value]) -> DecompileResult<()> {
+    let ty = items[0].as_u64().unwrap_or(u64::MAX);
+    match ty {
+        ty if ty == FormatJsElementType::Argument as u64 => {
+            f.push('{');
+            f.push_str(element_str(items, 1)?);
+            if let Some(default_value) = items.get(2).and_then(Value::as_str) {
+                f.push('|');
+                f.push_str(default_value);
+            }
+            f.push('}');
+        }
+        ty if ty == FormatJsElementType::Number as u64 => write_formatted(f, items, "number")?,
+        ty if ty == FormatJsElementType::Date as u64 => write_formatted(f, items, "date")?,
+        ty if ty == FormatJsElementType::Time as u64 => write_formatted(f, items, "time")?,
+        ty if ty == FormatJsElementType::Select as u64 => {
+            write_selector(f, items, "select")?;
+        }
+        ty if ty == FormatJsElementType::Plural as u64 => {
+            let kind = match items.get(4).and_then(Value::as_str) {
+                Some("ordinal") => "selectordinal",
+                _ => "plural",
+            };
+            write_selector(f, items, kind)?;
+        }
+        ty if ty == FormatJsElementType::Pound as u64 => f.push('#'),
+        ty if ty == FormatJsElementType::Tag as u64 => write_tag(f, items)?,
+        _ => {
+            return Err(DecompileError::new(format!(
+                "Unknown element type {}",
+                items[0]
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Write a number, date, or time element, like `{count, number, percent}`.
+fn write_formatted(f: &mut String, items: &[Value], keyword: &str) -> DecompileResult<()> {
+    f.push('{');
+    f.push_str(element_str(items, 1)?);
+    f.push_str(", ");
+    f.push_str(keyword);
+    if let Some(style) = items.get(2).and_then(Value::as_str) {
+        f.push_str(", ");
+        f.push_str(style);
+    }
+    f.push('}');
+    Ok(())
+}
+
+/// Write a plural or select element with all of its arms, like `{count, plural, one {#}}`.
+fn write_selector(f: &mut String, items: &[Value], keyword: &str) -> DecompileResult<()> {
+    let Some(Value::Object(arms)) = items.get(2) else {
+        return Err(DecompileError::new("Expected the options of a selector"));
+    };
+    f.push('{');
+    f.push_str(element_str(items, 1)?);
+    f.push_str(", ");
+    f.push_str(keyword);
+    f.push(',');
+    write_arms(f, arms)?;
+    f.push('}');
+    Ok(())
+}
+
+fn write_arms(f: &mut String, arms: &Map<String, Value>) -> DecompileResult<()> {
+    // Bundles don't keep a meaningful order for arms, so `other` is always written last, as it
+    // conventionally is in source messages.
+    let (other, rest): (Vec<_>, Vec<_>) =
+        arms.iter().partition(|(selector, _)| *selector == "other");
+    for (selector, content) in rest.into_iter().chain(other) {
+        f.push(' ');
+        f.push_str(selector);
+        f.push_str(" {");
+        write_nodes(f, content)?;
+        f.push('}');
+    }
+    Ok(())
+}
+
+fn write_tag(f: &mut String, items: &[Value]) -> DecompileResult<()> {
+    let tag = element_str(items, 1)?;
+    let children = element_field(items, 2)?;
+    let delimiter = if tag == DEFAULT_TAG_NAMES.strong() {
+        "**"
+    } else if tag == DEFAULT_TAG_NAMES.emphasis() {
+        "*"
+    } else if tag == DEFAULT_TAG_NAMES.strike_through() {
+        "~~"
+    } else if tag == DEFAULT_TAG_NAMES.code() {
+        write_code_span(f, literal_content(children)?);
+        return Ok(());
+    } else if tag == DEFAULT_TAG_NAMES.emoji() {
+        f.push_str(literal_content(children)?);
+        return Ok(());
+    } else if tag == DEFAULT_TAG_NAMES.mention() {
+        f.push('<');
+        f.push_str(literal_content(element_field(items, 3)?)?);
+        write_nodes(f, children)?;
+        f.push('>');
+        return Ok(());
+    } else if tag == DEFAULT_TAG_NAMES.keyboard_key() {
+        f.push_str("[[");
+        f.push_str(literal_content(children)?);
+        f.push_str("]]");
+        return Ok(());
+    } else if tag == DEFAULT_TAG_NAMES.br() {
+        f.push_str("\\\n");
+        return Ok(());
+    } else if tag == DEFAULT_TAG_NAMES.link() {
+        f.push('[');
+        write_nodes(f, children)?;
+        f.push_str("](");
+        write_link_destination(f, element_field(items, 3)?)?;
+        f.push(')');
+        return Ok(());
+    } else if is_block_tag(tag) {
+        return Err(DecompileError::new(format!(
+            "Block element {tag} found inside of inline content"
+        )));
+    } else {
+        // Any other tag name is a hook.
+        f.push_str("$[");
+        write_nodes(f, children)?;
+        f.push_str("](");
+        f.push_str(tag);
+        f.push(')');
+        return Ok(());
+    };
+
+    f.push_str(delimiter);
+    write_nodes(f, children)?;
+    f.push_str(delimiter);
+    Ok(())
+}
+
+fn write_code_span(f: &mut String, content: &str) {
+    let fence = "`".repeat(longest_run(content, '`') + 1);
+    // Content that starts or ends with a backtick needs padding to not merge with the fence, and
+    // the parser strips one space from each side when both are present.
+    let padding = if content.starts_with('`') || content.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    f.push_str(&fence);
+    f.push_str(padding);
+    f.push_str(content);
+    f.push_str(padding);
+    f.push_str(&fence);
+}
+
+fn write_link_destination(f: &mut String, control: &Value) -> DecompileResult<()> {
+    let Value::Array(items) = control else {
+        return Err(DecompileError::new("Expected a link destination"));
+    };
+    match items.first() {
+        Some(Value::String(destination)) => {
+            let needs_brackets = destination.is_empty()
+                || destination
+                    .chars()
+                    .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>'));
+            if needs_brackets {
+                f.push('<');
+                f.push_str(destination);
+                f.push('>');
+            } else {
+                f.push_str(destination);
+            }
+            Ok(())
+        }
+        Some(Value::Array(element)) if is_element(element) => {
+            f.push('{');
+            f.push_str(element_str(element, 1)?);
+            f.push('}');
+            Ok(())
+        }
+        _ => Err(DecompileError::new("Expected a link destination")),
+    }
+}
+
+/// Write literal text, escaping any characters that would otherwise be parsed as Markdown syntax.
+/// ICU syntax characters are kept as-is, since compiled text keeps the apostrophe quoting that
+/// makes them literal.
+fn write_text(f: &mut String, text: &str) {
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '~' | '`' | '[' | ']' | '<' | '>' | '&'
+        ) {
+            f.push('\\');
+        }
+        f.push(c);
+    }
+}
+
+/// Code spans and blocks always contain a single literal node with their content.
+fn literal_content(children: &Value) -> DecompileResult<&str> {
+    match children {
+        Value::Array(items) if items.is_empty() => Ok(""),
+        Value::Array(items) => items
+            .first()
+            .and_then(Value::as_str)
+            .ok_or_else(|| DecompileError::new("Expected literal code content")),
+        _ => Err(DecompileError::new("Expected literal code content")),
+    }
+}
+
+fn longest_run(text: &str, target: char) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        current = if c == target { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    longest
+}
+
+fn element_field(items: &[Value], index: usize) -> DecompileResult<&Value> {
+    items
+        .get(index)
+        .ok_or_else(|| DecompileError::new(format!("Element is missing field {index}")))
+}
+
+fn element_str(items: &[Value], index: usize) -> DecompileResult<&str> {
+    element_field(items, index)?
+        .as_str()
+        .ok_or_else(|| DecompileError::new(format!("Expected field {index} to be a string")))
+}
@@ -2,10 +2,10 @@ use std::fmt::Write;
 
 use crate::ast::util::{escape_body_text, escape_href};
 use crate::ast::{
-    BlockNode, CodeBlock, CodeSpan, Document, Emphasis, Heading, Hook, Icu, IcuDate,
+    BlockNode, CodeBlock, CodeSpan, Document, Emoji, Emphasis, Heading, Hook, Icu, IcuDate,
     IcuDateTimeStyle, IcuNumber, IcuNumberStyle, IcuPlural, IcuPluralArm, IcuPluralKind, IcuSelect,
-    IcuTime, IcuVariable, InlineContent, Link, LinkDestination, LinkKind, Paragraph, Strikethrough,
-    Strong,
+    IcuTime, IcuVariable, InlineContent, KeyboardKey, Link, LinkDestination, LinkKind, Mention,
+    Paragraph, Strikethrough, Strong,
 };
 
 macro_rules! write {
@@ -94,6 +94,7 @@ pub fn format_icu_string(document: &Document) -> FormatResult<String> {
             BlockNode::CodeBlock(code_block) => write!(f, [code_block])?,
             BlockNode::ThematicBreak => write!(f, ["<hr />"])?,
             BlockNode::InlineContent(content) => write!(f, [content])?,
+            BlockNode::Error(text) => write!(f, [escape_body_text(text)])?,
         }
     }
 
@@ -135,6 +136,10 @@ impl FormatIcuString for InlineContent {
             InlineContent::HardLineBreak => write!(f, ["<br />\n"]),
             InlineContent::Hook(hook) => write!(f, [hook]),
             InlineContent::Strikethrough(strikethrough) => write!(f, [strikethrough]),
+            InlineContent::Emoji(emoji) => write!(f, [emoji]),
+            InlineContent::Mention(mention) => write!(f, [mention]),
+            InlineContent::KeyboardKey(key) => write!(f, [key]),
+            InlineContent::MessageReference(reference) => write!(f, ["{@", reference.key(), "}"]),
             InlineContent::Icu(icu) => write!(f, [icu]),
             InlineContent::IcuPound => write!(f, ['#']),
         }
@@ -168,7 +173,7 @@ impl<F: Fn(&str) -> String> FormatIcuString for FormatTextOrPlaceholder<'_, F> {
         match self.node {
             LinkDestination::Text(text) => f.write_str(&(self.text_mutator)(&text)),
             LinkDestination::Placeholder(icu) => write!(f, [icu]),
-            LinkDestination::Handler(handler) => write!(f, [handler]),
+            LinkDestination::Handler(handler) => write!(f, [handler.as_str()]),
         }
     }
 }
@@ -219,6 +224,33 @@ impl FormatIcuString for Strikethrough {
     }
 }
 
+impl FormatIcuString for Emoji {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        write!(f, ["<emoji>", self.shortcode(), "</emoji>"])
+    }
+}
+
+impl FormatIcuString for Mention {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        write!(
+            f,
+            [
+                "<mention>",
+                escape_body_text(self.sigil()),
+                '{',
+                self.variable(),
+                "}</mention>"
+            ]
+        )
+    }
+}
+
+impl FormatIcuString for KeyboardKey {
+    fn fmt(&self, mut f: &mut dyn Write) -> FormatResult<()> {
+        write!(f, ["<kbd>", &escape_body_text(self.name()), "</kbd>"])
+    }
+}
+
 impl FormatIcuString for Icu {
     fn fmt(&self, mut f: &mut dyn Write) -> crate::ast::format::FormatResult<()> {
         f.write_str("{")?;
@@ -236,7 +268,12 @@ impl FormatIcuString for Icu {
 
 impl FormatIcuString for IcuVariable {
     fn fmt(&self, f: &mut dyn Write) -> crate::ast::format::FormatResult<()> {
-        f.write_str(&self.name())
+        f.write_str(self.name())?;
+        if let Some(default_value) = self.default_value() {
+            f.write_str("|")?;
+            f.write_str(default_value)?;
+        }
+        Ok(())
     }
 }
 
@@ -0,0 +1,466 @@
+//! Serialization of message ASTs into Unicode MessageFormat 2.0 (MF2) syntax.
+//!
+//! Markdown elements become MF2 markup, using the same tag names as the FormatJS-compatible
+//! compile without the `$` prefix (e.g., `{#b}bold{/b}`), and ICU placeholders become MF2
+//! expressions with the closest matching function (e.g., `{count, number}` becomes
+//! `{$count :number}`). MF2 has no syntax for default values, so `{name|Someone}` is written as
+//! just `{$name}`.
+//!
+//! MF2 only allows selection at the top level of a message, so plurals and selects are hoisted
+//! out of the content they appear in: every selector becomes an `.input` declaration, and the
+//! message is expanded into one variant for each combination of the selectors' keys. A message
+//! like `You have {count, plural, one {# item} other {# items}}` becomes:
+//!
+//! ```text
+//! .input {$count :number}
+//! .match $count
+//! one {{You have {$count} item}}
+//! * {{You have {$count} items}}
+//! ```
+use std::fmt::Write;
+
+use crate::ast::format::FormatResult;
+use crate::ast::{
+    BlockNode, Document, Heading, Icu, IcuDate, IcuDateTimeStyle, IcuNumber, IcuNumberStyleKind,
+    IcuPluralArm, IcuPluralKind, IcuTime, InlineContent, Link, LinkDestination, LinkKind,
+};
+use crate::icu::tags::DEFAULT_TAG_NAMES;
+
+/// The MF2 key that matches any value, equivalent to `other` in ICU.
+const CATCHALL_KEY: &str = "*";
+
+/// Serialize the given document as an MF2 message string.
+pub fn format_mf2_string(document: &Document) -> FormatResult<String> {
+    let mut flattener = Mf2Flattener::default();
+    let parts = flattener.flatten_document(document);
+    let selectors = flattener.selectors;
+
+    let mut f = String::new();
+    if selectors.is_empty() {
+        let pattern = format_pattern(&parts, &[]);
+        // Simple messages can't start with a `.`, since it would be read as a declaration, and
+        // leading whitespace is easily lost, so those are written as quoted patterns instead.
+        if pattern.starts_with(|c: char| c == '.' || c.is_whitespace()) {
+            std::write!(f, "{{{{{}}}}}", pattern)?;
+        } else {
+            f.push_str(&pattern);
+        }
+        return Ok(f);
+    }
+
+    for selector in &selectors {
+        std::writeln!(f, ".input {{${} {}}}", selector.name, selector.function)?;
+    }
+    f.push_str(".match");
+    for selector in &selectors {
+        std::write!(f, " ${}", selector.name)?;
+    }
+
+    let mut choices = vec![0; selectors.len()];
+    loop {
+        f.push('\n');
+        for (selector, choice) in selectors.iter().zip(&choices) {
+            std::write!(f, "{} ", format_key(&selector.keys[*choice]))?;
+        }
+        let keys = selectors
+            .iter()
+            .zip(&choices)
+            .map(|(selector, choice)| selector.keys[*choice].as_str())
+            .collect::<Vec<_>>();
+        std::write!(f, "{{{{{}}}}}", format_pattern(&parts, &keys))?;
+
+        // Step to the next combination of keys, with the last selector changing fastest.
+        let Some(index) = (0..choices.len())
+            .rev()
+            .find(|index| choices[*index] + 1 < selectors[*index].keys.len())
+        else {
+            break;
+        };
+        choices[index] += 1;
+        choices[index + 1..].fill(0);
+    }
+
+    Ok(f)
+}
+
+/// A variable that the message selects on, with all of the keys used for it across the message.
+/// The catchall key is always last.
+struct Mf2Selector<'a> {
+    name: &'a str,
+    function: &'static str,
+    keys: Vec<String>,
+}
+
+enum Mf2Part<'a> {
+    /// Message text, written exactly as it is rendered in the JSON bundles, including any
+    /// apostrophes, with only the characters that are MF2 syntax escaped.
+    Text(&'a str),
+    /// Syntax that is written to the output as-is, like markup and placeholders.
+    Syntax(String),
+    Select {
+        selector: usize,
+        arms: Vec<(String, Vec<Mf2Part<'a>>)>,
+    },
+}
+
+#[derive(Default)]
+struct Mf2Flattener<'a> {
+    selectors: Vec<Mf2Selector<'a>>,
+    /// Names of the plurals that `#` currently refers to, innermost last.
+    pound_names: Vec<&'a str>,
+}
+
+impl<'a> Mf2Flattener<'a> {
+    fn flatten_document(&mut self, document: &'a Document) -> Vec<Mf2Part<'a>> {
+        let mut parts = vec![];
+        for (index, block) in document.blocks().iter().enumerate() {
+            if index > 0 {
+                parts.push(Mf2Part::Text("\n"));
+            }
+
+            match block {
+                BlockNode::Paragraph(paragraph) => self.push_markup(
+                    &mut parts,
+                    DEFAULT_TAG_NAMES.paragraph(),
+                    paragraph.content(),
+                ),
+                BlockNode::Heading(heading) => self.push_heading(&mut parts, heading),
+                BlockNode::CodeBlock(code_block) => {
+                    parts.push(Mf2Part::Syntax(open_markup(DEFAULT_TAG_NAMES.code_block())));
+                    parts.push(Mf2Part::Text(code_block.content()));
+                    parts.push(Mf2Part::Syntax(close_markup(
+                        DEFAULT_TAG_NAMES.code_block(),
+                    )));
+                }
+                BlockNode::ThematicBreak => {
+                    parts.push(Mf2Part::Syntax(standalone_markup(DEFAULT_TAG_NAMES.hr())))
+                }
+                BlockNode::InlineContent(content) => self.flatten_inline(&mut parts, content),
+                BlockNode::Error(text) => parts.push(Mf2Part::Text(text)),
+            }
+        }
+        parts
+    }
+
+    fn flatten_inline(&mut self, parts: &mut Vec<Mf2Part<'a>>, content: &'a [InlineContent]) {
+        for element in content {
+            match element {
+                InlineContent::Text(text) => parts.push(Mf2Part::Text(text)),
+                InlineContent::Emphasis(emphasis) => {
+                    self.push_markup(parts, DEFAULT_TAG_NAMES.emphasis(), emphasis.content())
+                }
+                InlineContent::Strong(strong) => {
+                    self.push_markup(parts, DEFAULT_TAG_NAMES.strong(), strong.content())
+                }
+                InlineContent::Strikethrough(strikethrough) => self.push_markup(
+                    parts,
+                    DEFAULT_TAG_NAMES.strike_through(),
+                    strikethrough.content(),
+                ),
+                InlineContent::Link(link) => self.push_link(parts, link),
+                InlineContent::CodeSpan(code_span) => {
+                    parts.push(Mf2Part::Syntax(open_markup(DEFAULT_TAG_NAMES.code())));
+                    parts.push(Mf2Part::Text(code_span.content()));
+                    parts.push(Mf2Part::Syntax(close_markup(DEFAULT_TAG_NAMES.code())));
+                }
+                InlineContent::HardLineBreak => {
+                    parts.push(Mf2Part::Syntax(standalone_markup(DEFAULT_TAG_NAMES.br())));
+                    parts.push(Mf2Part::Text("\n"));
+                }
+                InlineContent::Hook(hook) => self.push_markup(parts, hook.name(), hook.content()),
+                InlineContent::Emoji(emoji) => parts.push(Mf2Part::Syntax(format!(
+                    "{{#{} name={} /}}",
+                    markup_name(DEFAULT_TAG_NAMES.emoji()),
+                    format_literal(emoji.name())
+                ))),
+                InlineContent::KeyboardKey(key) => {
+                    parts.push(Mf2Part::Syntax(open_markup(
+                        DEFAULT_TAG_NAMES.keyboard_key(),
+                    )));
+                    parts.push(Mf2Part::Text(key.name()));
+                    parts.push(Mf2Part::Syntax(close_markup(
+                        DEFAULT_TAG_NAMES.keyboard_key(),
+                    )));
+                }
+                // References are resolved before converting, so any that are left are kept as text.
+                InlineContent::MessageReference(reference) => {
+                    parts.push(Mf2Part::Syntax(format!("\\{{@{}\\}}", reference.key())))
+                }
+                InlineContent::Mention(mention) => {
+                    let tag = markup_name(DEFAULT_TAG_NAMES.mention());
+                    parts.push(Mf2Part::Syntax(format!(
+                        "{{#{} sigil={}}}{{${}}}",
+                        tag,
+                        format_literal(mention.sigil()),
+                        mention.variable().name()
+                    )));
+                    parts.push(Mf2Part::Syntax(close_markup(DEFAULT_TAG_NAMES.mention())));
+                }
+                InlineContent::Icu(icu) => self.push_icu(parts, icu),
+                InlineContent::IcuPound => match self.pound_names.last() {
+                    Some(name) => parts.push(Mf2Part::Syntax(format!("{{${}}}", name))),
+                    None => parts.push(Mf2Part::Text("#")),
+                },
+            }
+        }
+    }
+
+    fn push_markup(
+        &mut self,
+        parts: &mut Vec<Mf2Part<'a>>,
+        tag: &str,
+        content: &'a [InlineContent],
+    ) {
+        parts.push(Mf2Part::Syntax(open_markup(tag)));
+        self.flatten_inline(parts, content);
+        parts.push(Mf2Part::Syntax(close_markup(tag)));
+    }
+
+    fn push_heading(&mut self, parts: &mut Vec<Mf2Part<'a>>, heading: &'a Heading) {
+        self.push_markup(
+            parts,
+            DEFAULT_TAG_NAMES.heading(heading.level()),
+            heading.content(),
+        )
+    }
+
+    fn push_link(&mut self, parts: &mut Vec<Mf2Part<'a>>, link: &'a Link) {
+        let destination = match link.destination() {
+            LinkDestination::Text(text) => format_literal(text),
+            LinkDestination::Placeholder(icu) => format!("${}", icu_name(icu)),
+            LinkDestination::Handler(handler) => format!("${}", handler),
+        };
+        match link.kind() {
+            LinkKind::Image => {
+                parts.push(Mf2Part::Syntax(format!("{{#img src={} /}}", destination)));
+            }
+            _ => {
+                let tag = markup_name(DEFAULT_TAG_NAMES.link());
+                parts.push(Mf2Part::Syntax(format!(
+                    "{{#{} href={}}}",
+                    tag, destination
+                )));
+                self.flatten_inline(parts, link.label());
+                parts.push(Mf2Part::Syntax(close_markup(DEFAULT_TAG_NAMES.link())));
+            }
+        }
+    }
+
+    fn push_icu(&mut self, parts: &mut Vec<Mf2Part<'a>>, icu: &'a Icu) {
+        match icu {
+            Icu::IcuVariable(variable) => {
+                parts.push(Mf2Part::Syntax(format!("{{${}}}", variable.name())))
+            }
+            Icu::IcuDate(date) => parts.push(Mf2Part::Syntax(format_date(date))),
+            Icu::IcuTime(time) => parts.push(Mf2Part::Syntax(format_time(time))),
+            Icu::IcuNumber(number) => parts.push(Mf2Part::Syntax(format_number(number))),
+            Icu::IcuPlural(plural) => {
+                let function = match plural.kind() {
+                    IcuPluralKind::Plural => ":number",
+                    IcuPluralKind::SelectOrdinal => ":number select=ordinal",
+                };
+                self.push_select(parts, plural.name(), function, plural.arms(), true);
+            }
+            Icu::IcuSelect(select) => {
+                self.push_select(parts, select.name(), ":string", select.arms(), false)
+            }
+        }
+    }
+
+    fn push_select(
+        &mut self,
+        parts: &mut Vec<Mf2Part<'a>>,
+        name: &'a str,
+        function: &'static str,
+        arms: &'a [IcuPluralArm],
+        is_plural: bool,
+    ) {
+        let selector = self.get_or_add_selector(name, function);
+        if is_plural {
+            self.pound_names.push(name);
+        }
+
+        let mut flattened_arms = Vec::with_capacity(arms.len());
+        for arm in arms {
+            let key = match arm.selector() {
+                "other" => CATCHALL_KEY,
+                // Exact numeric matches in MF2 are just the number itself.
+                selector => selector.strip_prefix('=').unwrap_or(selector),
+            };
+            let keys = &mut self.selectors[selector].keys;
+            if !keys.iter().any(|existing| existing == key) {
+                // Keep the catchall key last.
+                keys.insert(keys.len() - 1, key.into());
+            }
+
+            let mut arm_parts = vec![];
+            self.flatten_inline(&mut arm_parts, arm.content());
+            flattened_arms.push((key.to_string(), arm_parts));
+        }
+
+        if is_plural {
+            self.pound_names.pop();
+        }
+        parts.push(Mf2Part::Select {
+            selector,
+            arms: flattened_arms,
+        });
+    }
+
+    /// Returns the index of the selector for the given variable, adding it if it hasn't been seen
+    /// yet. MF2 only allows each variable to be declared once, so a variable that is selected on
+    /// multiple times keeps the function from its first use.
+    fn get_or_add_selector(&mut self, name: &'a str, function: &'static str) -> usize {
+        if let Some(index) = self
+            .selectors
+            .iter()
+            .position(|selector| selector.name == name)
+        {
+            return index;
+        }
+        self.selectors.push(Mf2Selector {
+            name,
+            function,
+            keys: vec![CATCHALL_KEY.into()],
+        });
+        self.selectors.len() - 1
+    }
+}
+
+/// Write the parts as a single pattern, using the arm matching the given key for each selector, or
+/// its catchall arm when it has no arm for that key.
+fn format_pattern(parts: &[Mf2Part], keys: &[&str]) -> String {
+    let mut pattern = String::new();
+    write_pattern(&mut pattern, parts, keys);
+    pattern
+}
+
+fn write_pattern(f: &mut String, parts: &[Mf2Part], keys: &[&str]) {
+    for part in parts {
+        match part {
+            Mf2Part::Text(text) => f.push_str(&escape_text(text)),
+            Mf2Part::Syntax(syntax) => f.push_str(syntax),
+            Mf2Part::Select { selector, arms } => {
+                let arm = arms
+                    .iter()
+                    .find(|(key, _)| key == keys[*selector])
+                    .or_else(|| arms.iter().find(|(key, _)| key == CATCHALL_KEY));
+                if let Some((_, content)) = arm {
+                    write_pattern(f, content, keys);
+                }
+            }
+        }
+    }
+}
+
+fn format_date(date: &IcuDate) -> String {
+    format!(
+        "{{${} :date{}}}",
+        date.name(),
+        format_date_time_style(date.style())
+    )
+}
+
+fn format_time(time: &IcuTime) -> String {
+    format!(
+        "{{${} :time{}}}",
+        time.name(),
+        format_date_time_style(time.style())
+    )
+}
+
+/// MF2 only defines the named date and time styles. Skeletons have no equivalent, so those are
+/// left to the default style.
+fn format_date_time_style(style: &Option<IcuDateTimeStyle>) -> String {
+    match style.as_ref().map(|style| style.text()) {
+        Some(style @ ("full" | "long" | "medium" | "short")) => format!(" style={}", style),
+        _ => String::new(),
+    }
+}
+
+fn format_number(number: &IcuNumber) -> String {
+    let function = match number.style().as_ref().map(|style| style.kind()) {
+        Some(IcuNumberStyleKind::Integer) => ":integer".into(),
+        Some(IcuNumberStyleKind::Percent) => ":percent".into(),
+        Some(IcuNumberStyleKind::Currency(Some(code))) => {
+            format!(":currency currency={}", format_literal(code))
+        }
+        Some(IcuNumberStyleKind::Currency(None)) => ":currency".into(),
+        Some(IcuNumberStyleKind::Other) | None => ":number".into(),
+    };
+    format!("{{${} {}}}", number.name(), function)
+}
+
+fn icu_name(icu: &Icu) -> &str {
+    match icu {
+        Icu::IcuVariable(variable) => variable.name(),
+        Icu::IcuPlural(plural) => plural.name(),
+        Icu::IcuSelect(select) => select.name(),
+        Icu::IcuDate(date) => date.name(),
+        Icu::IcuTime(time) => time.name(),
+        Icu::IcuNumber(number) => number.name(),
+    }
+}
+
+/// Tag names for built-in elements are prefixed with `$` to distinguish them from hooks, but `$`
+/// isn't valid in an MF2 identifier.
+fn markup_name(tag: &str) -> &str {
+    tag.strip_prefix('$').unwrap_or(tag)
+}
+
+fn open_markup(tag: &str) -> String {
+    format!("{{#{}}}", markup_name(tag))
+}
+
+fn close_markup(tag: &str) -> String {
+    format!("{{/{}}}", markup_name(tag))
+}
+
+fn standalone_markup(tag: &str) -> String {
+    format!("{{#{} /}}", markup_name(tag))
+}
+
+fn format_key(key: &str) -> String {
+    match key {
+        CATCHALL_KEY => key.into(),
+        _ => format_literal(key),
+    }
+}
+
+/// Write the value as an unquoted literal if it is a valid name or number, otherwise as a quoted
+/// literal.
+fn format_literal(value: &str) -> String {
+    if is_unquoted_literal(value) {
+        value.into()
+    } else {
+        format!("|{}|", value.replace('\\', "\\\\").replace('|', "\\|"))
+    }
+}
+
+fn is_unquoted_literal(value: &str) -> bool {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        }
+        Some(first) if first.is_ascii_digit() => is_number_literal(value),
+        _ => false,
+    }
+}
+
+fn is_number_literal(value: &str) -> bool {
+    let is_digits = |digits: &str| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    is_digits(integer) && is_digits(fraction) && (integer == "0" || !integer.starts_with('0'))
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -1,4 +1,8 @@
 pub mod compile;
+pub mod decompile;
 pub mod format;
+pub mod mf2;
+pub mod react_intl;
 pub mod serialize;
+pub mod stringify;
 pub mod tags;
@@ -0,0 +1,214 @@
+//! Serialization of compiled messages as the exact AST produced by
+//! `@formatjs/icu-messageformat-parser`, so bundles can be given directly to react-intl and other
+//! FormatJS-based renderers that expect pre-parsed messages.
+//!
+//! The compiled format is already close to FormatJS' AST, but it is more compact: literals are
+//! plain strings, lists can be nested, and plural options hold their content directly. This
+//! writes every element as an object with the same field names and order as FormatJS, flattens
+//! nested lists, and merges adjacent literals the same way the FormatJS parser does.
+//!
+//! FormatJS has no equivalent of `control`, so tags with a control, like links, write it at the
+//! start of their children, followed by a `{_}` argument when it is plain text. This matches how
+//! [crate::to_icu_string] writes them, so parsing that string with FormatJS gives the same AST.
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+use crate::ast::IcuPluralKind;
+use crate::icu::compile::{
+    FormatJsElementType, FormatJsNode, FormatJsNodeOptions, FormatJsSingleNode,
+};
+use crate::icu::stringify::ends_with_literal;
+
+/// A compiled message that serializes as a FormatJS AST, a list of message format elements.
+pub struct ReactIntlAst<'a>(pub &'a FormatJsNode<'a>);
+
+impl Serialize for ReactIntlAst<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut elements = vec![];
+        flatten(self.0, &mut elements);
+        serialize_elements(&elements, serializer)
+    }
+}
+
+/// A single FormatJS element. Literals are owned so that adjacent ones can be merged together.
+enum Element<'a> {
+    Literal(String),
+    Argument(&'a str),
+    Node(&'a FormatJsSingleNode<'a>),
+}
+
+/// Append every element of `node` to `elements`, flattening nested lists and merging adjacent
+/// literals into one.
+fn flatten<'a>(node: &'a FormatJsNode<'a>, elements: &mut Vec<Element<'a>>) {
+    match node {
+        FormatJsNode::Literal(text) => push_literal(elements, text),
+        FormatJsNode::ListNode(nodes) => {
+            for node in nodes.iter() {
+                flatten(node, elements);
+            }
+        }
+        FormatJsNode::SingleNode(node) => match node.ty {
+            None | Some(FormatJsElementType::Literal) => {
+                push_literal(elements, node.value.unwrap_or_default())
+            }
+            _ => elements.push(Element::Node(node)),
+        },
+    }
+}
+
+fn push_literal(elements: &mut Vec<Element>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match elements.last_mut() {
+        Some(Element::Literal(existing)) => existing.push_str(text),
+        _ => elements.push(Element::Literal(text.to_string())),
+    }
+}
+
+fn serialize_elements<S: Serializer>(
+    elements: &[Element],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+    for element in elements {
+        seq.serialize_element(element)?;
+    }
+    seq.end()
+}
+
+/// The flattened elements of a list of children, serialized as a FormatJS element list.
+struct ElementList<'a>(Vec<Element<'a>>);
+
+impl<'a> ElementList<'a> {
+    fn from_node(node: Option<&'a FormatJsNode<'a>>) -> Self {
+        let mut elements = vec![];
+        if let Some(node) = node {
+            flatten(node, &mut elements);
+        }
+        Self(elements)
+    }
+}
+
+impl Serialize for ElementList<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_elements(&self.0, serializer)
+    }
+}
+
+/// Plural and select options, where each arm's content is wrapped in an object as its `value`.
+struct Options<'a>(&'a FormatJsNodeOptions<'a>);
+
+impl Serialize for Options<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let arms = self.0.arms();
+        let mut map = serializer.serialize_map(Some(arms.len()))?;
+        for (selector, content) in arms {
+            map.serialize_entry(
+                selector,
+                &OptionValue(ElementList::from_node(Some(content))),
+            )?;
+        }
+        map.end()
+    }
+}
+
+struct OptionValue<'a>(ElementList<'a>);
+
+impl Serialize for OptionValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut option = serializer.serialize_struct("PluralOrSelectOption", 1)?;
+        option.serialize_field("value", &self.0)?;
+        option.end()
+    }
+}
+
+impl Serialize for Element<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let node = match self {
+            Element::Literal(text) => return serialize_value_element(serializer, 0, text),
+            Element::Argument(name) => return serialize_value_element(serializer, 1, name),
+            Element::Node(node) => node,
+        };
+        // Nodes without a type are always flattened into literals, so every node has one here.
+        let ty = node.ty.unwrap();
+        let value = node.value.unwrap_or_default();
+        match ty {
+            FormatJsElementType::Literal | FormatJsElementType::Argument => {
+                serialize_value_element(serializer, ty as u8, value)
+            }
+            FormatJsElementType::Number | FormatJsElementType::Date | FormatJsElementType::Time => {
+                let mut element = serializer.serialize_struct("FormattedElement", 3)?;
+                element.serialize_field("type", &(ty as u8))?;
+                element.serialize_field("value", value)?;
+                element.serialize_field("style", &node.style)?;
+                element.end()
+            }
+            FormatJsElementType::Select => {
+                let mut element = serializer.serialize_struct("SelectElement", 3)?;
+                element.serialize_field("type", &(ty as u8))?;
+                element.serialize_field("value", value)?;
+                element.serialize_field("options", &node.options.as_ref().map(Options))?;
+                element.end()
+            }
+            FormatJsElementType::Plural => {
+                let plural_type = match node.plural_type {
+                    Some(IcuPluralKind::SelectOrdinal) => "ordinal",
+                    _ => "cardinal",
+                };
+                let mut element = serializer.serialize_struct("PluralElement", 5)?;
+                element.serialize_field("type", &(ty as u8))?;
+                element.serialize_field("value", value)?;
+                element.serialize_field("options", &node.options.as_ref().map(Options))?;
+                element.serialize_field("offset", &node.offset.unwrap_or_default())?;
+                element.serialize_field("pluralType", plural_type)?;
+                element.end()
+            }
+            FormatJsElementType::Pound => {
+                let mut element = serializer.serialize_struct("PoundElement", 1)?;
+                element.serialize_field("type", &(ty as u8))?;
+                element.end()
+            }
+            FormatJsElementType::Tag => {
+                let mut children = ElementList::from_node(node.control);
+                if node.control.is_some_and(ends_with_literal) {
+                    children.0.push(Element::Argument("_"));
+                }
+                if let Some(content) = node.children {
+                    flatten(content, &mut children.0);
+                }
+                let mut element = serializer.serialize_struct("TagElement", 3)?;
+                element.serialize_field("type", &(ty as u8))?;
+                element.serialize_field("value", value)?;
+                element.serialize_field("children", &children)?;
+                element.end()
+            }
+        }
+    }
+}
+
+fn serialize_value_element<S: Serializer>(
+    serializer: S,
+    ty: u8,
+    value: &str,
+) -> Result<S::Ok, S::Error> {
+    let mut element = serializer.serialize_struct("ValueElement", 2)?;
+    element.serialize_field("type", &ty)?;
+    element.serialize_field("value", value)?;
+    element.end()
+}
@@ -2,9 +2,9 @@ use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct};
 use serde::{Serialize, Serializer};
 
 use crate::ast::{
-    BlockNode, CodeBlock, CodeSpan, Document, Emphasis, Heading, Hook, Icu, IcuDate, IcuNumber,
-    IcuPlural, IcuPluralArm, IcuPluralKind, IcuSelect, IcuTime, IcuVariable, InlineContent, Link,
-    LinkDestination, Paragraph, Strikethrough, Strong,
+    BlockNode, CodeBlock, CodeSpan, Document, Emoji, Emphasis, Heading, Hook, Icu, IcuDate,
+    IcuNumber, IcuPlural, IcuPluralArm, IcuPluralKind, IcuSelect, IcuTime, IcuVariable,
+    InlineContent, KeyboardKey, Link, LinkDestination, Mention, Paragraph, Strikethrough, Strong,
 };
 use crate::icu::tags::DEFAULT_TAG_NAMES;
 
@@ -22,6 +22,9 @@ pub(super) mod fjs_types {
     pub(crate) static STYLE: &str = "style";
     pub(crate) static OFFSET: &str = "offset";
     pub(crate) static PLURAL_TYPE: &str = "pluralType";
+    /// Custom extension to FormatJS' AST for the default value of an argument, like `Someone` in
+    /// `{name|Someone}`.
+    pub(crate) static DEFAULT: &str = "default";
 }
 
 impl Serialize for IcuPluralKind {
@@ -68,6 +71,16 @@ fn serialize_literal<S: Serializer>(serializer: S, value: &str) -> Result<S::Ok,
     literal.end()
 }
 
+struct SerializeLiteral<'a>(&'a str);
+impl Serialize for SerializeLiteral<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_literal(serializer, self.0)
+    }
+}
+
 #[inline(always)]
 fn serialize_tag<S: Serializer, T: Serialize>(
     serializer: S,
@@ -81,7 +94,7 @@ fn serialize_tag<S: Serializer, T: Serialize>(
     tag.end()
 }
 
-struct SerializeHandler<'a>(&'a String);
+struct SerializeHandler<'a>(&'a str);
 impl Serialize for SerializeHandler<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -111,6 +124,9 @@ impl Serialize for Document {
                         root.serialize_element(&element)?
                     }
                 }
+                BlockNode::Error(text) => {
+                    root.serialize_element(&InlineContent::Text(text.clone()))?
+                }
             }
         }
 
@@ -150,19 +166,61 @@ impl Serialize for CodeSpan {
     }
 }
 
-impl Serialize for Heading {
+impl Serialize for KeyboardKey {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         serialize_tag(
             serializer,
-            DEFAULT_TAG_NAMES.heading(self.level()),
-            self.content(),
+            DEFAULT_TAG_NAMES.keyboard_key(),
+            &[SerializeLiteral(self.name())],
         )
     }
 }
 
+impl Serialize for Heading {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut heading = serializer.serialize_struct("Heading", 4)?;
+        heading.serialize_field(fjs_types::TYPE, &FormatJsElementType::Tag)?;
+        heading.serialize_field(fjs_types::VALUE, DEFAULT_TAG_NAMES.heading(self.level()))?;
+        heading.serialize_field(fjs_types::CHILDREN, self.content())?;
+        heading.serialize_field(fjs_types::CONTROL, &[SerializeLiteral(self.id())])?;
+        heading.end()
+    }
+}
+
+impl Serialize for Emoji {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut emoji = serializer.serialize_struct("Emoji", 4)?;
+        emoji.serialize_field(fjs_types::TYPE, &FormatJsElementType::Tag)?;
+        emoji.serialize_field(fjs_types::VALUE, DEFAULT_TAG_NAMES.emoji())?;
+        emoji.serialize_field(fjs_types::CHILDREN, &[SerializeLiteral(self.shortcode())])?;
+        emoji.serialize_field(fjs_types::CONTROL, &[SerializeLiteral(self.name())])?;
+        emoji.end()
+    }
+}
+
+impl Serialize for Mention {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut mention = serializer.serialize_struct("Mention", 4)?;
+        mention.serialize_field(fjs_types::TYPE, &FormatJsElementType::Tag)?;
+        mention.serialize_field(fjs_types::VALUE, DEFAULT_TAG_NAMES.mention())?;
+        mention.serialize_field(fjs_types::CHILDREN, &[self.variable()])?;
+        mention.serialize_field(fjs_types::CONTROL, &[SerializeLiteral(self.sigil())])?;
+        mention.end()
+    }
+}
+
 impl Serialize for LinkDestination {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -175,7 +233,7 @@ impl Serialize for LinkDestination {
             LinkDestination::Text(text) => InlineContent::Text(text.clone()).serialize(serializer),
             LinkDestination::Placeholder(icu) => icu.serialize(serializer),
             LinkDestination::Handler(handler_name) => {
-                SerializeHandler(&handler_name).serialize(serializer)
+                SerializeHandler(handler_name).serialize(serializer)
             }
         }
     }
@@ -209,6 +267,12 @@ impl Serialize for InlineContent {
             InlineContent::HardLineBreak => serialize_tag(serializer, DEFAULT_TAG_NAMES.br(), &()),
             InlineContent::Hook(hook) => hook.serialize(serializer),
             InlineContent::Strikethrough(strikethrough) => strikethrough.serialize(serializer),
+            InlineContent::Emoji(emoji) => emoji.serialize(serializer),
+            InlineContent::Mention(mention) => mention.serialize(serializer),
+            InlineContent::KeyboardKey(key) => key.serialize(serializer),
+            InlineContent::MessageReference(reference) => {
+                serialize_literal(serializer, &format!("{{@{}}}", reference.key()))
+            }
             InlineContent::Icu(icu) => icu.serialize(serializer),
             InlineContent::IcuPound => {
                 let mut pound = serializer.serialize_struct("IcuPound", 1)?;
@@ -249,9 +313,13 @@ impl Serialize for IcuVariable {
     where
         S: Serializer,
     {
-        let mut variable = serializer.serialize_struct("IcuVariable", 2)?;
+        let len = if self.default_value().is_some() { 3 } else { 2 };
+        let mut variable = serializer.serialize_struct("IcuVariable", len)?;
         variable.serialize_field(fjs_types::TYPE, &FormatJsElementType::Argument)?;
         variable.serialize_field(fjs_types::VALUE, self.name())?;
+        if let Some(default_value) = self.default_value() {
+            variable.serialize_field(fjs_types::DEFAULT, default_value)?;
+        }
         variable.end()
     }
 }
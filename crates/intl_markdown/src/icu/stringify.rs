@@ -0,0 +1,134 @@
+//! Stringifying writes a compiled FormatJS node tree as a standard ICU MessageFormat string, so
+//! that compiled messages can be handed to tools from other ecosystems that only understand plain
+//! ICU syntax.
+//!
+//! Markdown elements are written as the same tags they compile to, like `<$b>bold</$b>`. Tags that
+//! have a `control`, like the destination of a link, write it at the start of their content,
+//! followed by a `{_}` delimiter when it is plain text, matching [crate::format_icu_string]. Text
+//! is escaped with ICU apostrophe quoting rather than html entities.
+use std::fmt::Write;
+
+use crate::ast::IcuPluralKind;
+use crate::icu::compile::{FormatJsElementType, FormatJsNode, FormatJsSingleNode};
+
+type StringifyResult = Result<(), std::fmt::Error>;
+
+/// Write a compiled message as a standard ICU MessageFormat string.
+pub fn to_icu_string(node: &FormatJsNode) -> Result<String, std::fmt::Error> {
+    let mut f = String::new();
+    write_node(&mut f, node, false)?;
+    Ok(f)
+}
+
+/// `in_plural` is true while writing the arms of a plural, where `#` has to be escaped to be kept
+/// as literal text.
+fn write_node(f: &mut dyn Write, node: &FormatJsNode, in_plural: bool) -> StringifyResult {
+    match node {
+        FormatJsNode::Literal(text) => write_literal(f, text, in_plural),
+        FormatJsNode::ListNode(nodes) => {
+            for node in nodes.iter() {
+                write_node(f, node, in_plural)?;
+            }
+            Ok(())
+        }
+        FormatJsNode::SingleNode(node) => write_single_node(f, node, in_plural),
+    }
+}
+
+fn write_single_node(
+    f: &mut dyn Write,
+    node: &FormatJsSingleNode,
+    in_plural: bool,
+) -> StringifyResult {
+    let value = node.value.unwrap_or_default();
+    match node.ty {
+        None | Some(FormatJsElementType::Literal) => write_literal(f, value, in_plural),
+        Some(FormatJsElementType::Argument) => std::write!(f, "{{{value}}}"),
+        Some(FormatJsElementType::Number) => write_formatted(f, value, "number", node.style),
+        Some(FormatJsElementType::Date) => write_formatted(f, value, "date", node.style),
+        Some(FormatJsElementType::Time) => write_formatted(f, value, "time", node.style),
+        Some(FormatJsElementType::Pound) => f.write_char('#'),
+        Some(FormatJsElementType::Select) => {
+            std::write!(f, "{{{value}, select,")?;
+            write_options(f, node, in_plural)?;
+            f.write_char('}')
+        }
+        Some(FormatJsElementType::Plural) => {
+            let kind = match node.plural_type {
+                Some(IcuPluralKind::SelectOrdinal) => "selectordinal",
+                _ => "plural",
+            };
+            std::write!(f, "{{{value}, {kind},")?;
+            if let Some(offset) = node.offset.filter(|offset| *offset > 0) {
+                std::write!(f, " offset:{offset}")?;
+            }
+            write_options(f, node, true)?;
+            f.write_char('}')
+        }
+        Some(FormatJsElementType::Tag) => {
+            std::write!(f, "<{value}>")?;
+            if let Some(control) = node.control {
+                write_node(f, control, in_plural)?;
+                if ends_with_literal(control) {
+                    f.write_str("{_}")?;
+                }
+            }
+            if let Some(children) = node.children {
+                write_node(f, children, in_plural)?;
+            }
+            std::write!(f, "</{value}>")
+        }
+    }
+}
+
+fn write_formatted(
+    f: &mut dyn Write,
+    name: &str,
+    kind: &str,
+    style: Option<&str>,
+) -> StringifyResult {
+    match style {
+        Some(style) => std::write!(f, "{{{name}, {kind}, {style}}}"),
+        None => std::write!(f, "{{{name}, {kind}}}"),
+    }
+}
+
+fn write_options(f: &mut dyn Write, node: &FormatJsSingleNode, in_plural: bool) -> StringifyResult {
+    let Some(options) = &node.options else {
+        return Ok(());
+    };
+    for (selector, content) in options.arms() {
+        std::write!(f, " {selector} {{")?;
+        write_node(f, content, in_plural)?;
+        f.write_char('}')?;
+    }
+    Ok(())
+}
+
+/// Returns true if the last element written for `node` is plain text.
+pub(super) fn ends_with_literal(node: &FormatJsNode) -> bool {
+    match node {
+        FormatJsNode::Literal(_) => true,
+        FormatJsNode::ListNode(nodes) => nodes.last().is_some_and(ends_with_literal),
+        FormatJsNode::SingleNode(_) => false,
+    }
+}
+
+/// Write `text` with every character that ICU treats as syntax quoted, so it is kept as literal
+/// text. Apostrophes are doubled, since a single one would start a quoted section.
+fn write_literal(f: &mut dyn Write, text: &str, in_plural: bool) -> StringifyResult {
+    let mut run_start = 0;
+    for (index, c) in text.char_indices() {
+        let is_syntax = matches!(c, '{' | '}' | '<' | '>') || (in_plural && c == '#');
+        if !is_syntax && c != '\'' {
+            continue;
+        }
+        f.write_str(&text[run_start..index])?;
+        match c {
+            '\'' => f.write_str("''")?,
+            _ => std::write!(f, "'{c}'")?,
+        }
+        run_start = index + c.len_utf8();
+    }
+    f.write_str(&text[run_start..])
+}
@@ -6,6 +6,9 @@ pub struct TagNames<'a> {
     link: &'a str,
     code: &'a str,
     code_block: &'a str,
+    emoji: &'a str,
+    mention: &'a str,
+    keyboard_key: &'a str,
     br: &'a str,
     hr: &'a str,
     h1: &'a str,
@@ -38,6 +41,15 @@ impl<'a> TagNames<'a> {
     pub const fn code_block(&self) -> &'a str {
         &self.code_block
     }
+    pub const fn emoji(&self) -> &'a str {
+        self.emoji
+    }
+    pub const fn mention(&self) -> &'a str {
+        self.mention
+    }
+    pub const fn keyboard_key(&self) -> &'a str {
+        self.keyboard_key
+    }
     pub const fn br(&self) -> &'a str {
         &self.br
     }
@@ -66,6 +78,9 @@ pub static DEFAULT_TAG_NAMES: TagNames<'static> = TagNames {
     link: "$link",
     code: "$code",
     code_block: "$codeBlock",
+    emoji: "$emoji",
+    mention: "$mention",
+    keyboard_key: "$kbd",
     br: "$br",
     hr: "$hr",
     h1: "$h1",
@@ -1,9 +1,10 @@
 use unicode_properties::{GeneralCategoryGroup, UnicodeGeneralCategory};
 
 use crate::byte_lookup::{
-    byte_is_significant_punctuation, char_length_from_byte, is_unicode_identifier_continue,
-    is_unicode_identifier_start,
+    byte_ends_keyboard_key, byte_is_emoji_name, byte_is_significant_punctuation, char_length_from_byte,
+    is_unicode_identifier_continue, is_unicode_identifier_start,
 };
+use crate::options::ParserOptions;
 use crate::token::{TextIndex, TextSpan};
 
 use super::{
@@ -84,6 +85,12 @@ pub enum LexContext {
     LinkDestination,
     /// Autolinks only allow email address or URI tokens.
     Autolink,
+    /// The name of an emoji shortcode directly after its opening colon.
+    EmojiName,
+    /// One of the configured mention sigils directly after an opening angle bracket.
+    MentionSigil,
+    /// The name of a keyboard key directly after its opening brackets.
+    KeyboardKeyName,
     /// ICU semantic blocks (e.g., within `{}` segments) ignore Markdown syntax and only lex out
     /// ICU MessageFormat syntax
     Icu,
@@ -93,6 +100,9 @@ pub enum LexContext {
     /// `+! short currency/GBP` could potentially be parsed as a number of keyword and punctuation
     /// tokens, but should be treated as a single string of text in this position).
     IcuStyle,
+    /// The default value of a variable after the `|` in `{var|default}` is also plain text, read
+    /// until the closing brace.
+    IcuDefault,
 }
 
 pub struct Lexer<'source> {
@@ -104,10 +114,11 @@ pub struct Lexer<'source> {
     last_position: usize,
     current_flags: TokenFlags,
     state: LexerState,
+    options: ParserOptions,
 }
 
 impl<'source> Lexer<'source> {
-    pub fn new(text: &'source str, block_bounds: Vec<BlockBound>) -> Self {
+    pub fn new(text: &'source str, block_bounds: Vec<BlockBound>, options: ParserOptions) -> Self {
         Self {
             text,
             block_bounds: block_bounds.into(),
@@ -116,6 +127,7 @@ impl<'source> Lexer<'source> {
             last_position: 0,
             current_flags: TokenFlags::default(),
             state: LexerState::new(),
+            options,
         }
     }
 
@@ -124,6 +136,10 @@ impl<'source> Lexer<'source> {
         &self.block_bounds
     }
 
+    pub fn options(&self) -> &ParserOptions {
+        &self.options
+    }
+
     pub fn state_mut(&mut self) -> &mut LexerState {
         &mut self.state
     }
@@ -163,8 +179,12 @@ impl<'source> Lexer<'source> {
             LexContext::CodeBlock => self.next_code_block_token(),
             LexContext::LinkDestination => self.next_regular_token(false),
             LexContext::Autolink => self.next_autolink_token(),
+            LexContext::EmojiName => self.next_emoji_name_token(),
+            LexContext::MentionSigil => self.next_mention_sigil_token(),
+            LexContext::KeyboardKeyName => self.next_keyboard_key_name_token(),
             LexContext::Icu => self.next_icu_token(),
             LexContext::IcuStyle => self.next_icu_style_token(),
+            LexContext::IcuDefault => self.next_icu_default_token(),
         };
 
         self.current_kind
@@ -172,7 +192,6 @@ impl<'source> Lexer<'source> {
 
     fn next_regular_token(&mut self, merge_whitespace_in_text: bool) -> SyntaxKind {
         match self.current() {
-            b'\0' => self.consume_byte(SyntaxKind::EOF),
             b'\r' | b'\n' => self.consume_line_ending(),
             b'\\' => self.consume_escaped(),
             c if c.is_ascii_whitespace() => self.consume_whitespace(LexContext::Regular),
@@ -196,7 +215,9 @@ impl<'source> Lexer<'source> {
             b'\'' => match self.peek() {
                 // `'{` is an escaped ICU block, meaning it has no semantic
                 // meaning and is treated as plain text.
-                Some(b'{' | b'}') => self.consume_plain_text(merge_whitespace_in_text),
+                Some(b'{' | b'}') if self.options.apostrophes_escape_braces() => {
+                    self.consume_plain_text(merge_whitespace_in_text)
+                }
                 _ => self.consume_byte(SyntaxKind::QUOTE),
             },
             b'"' => self.consume_byte(SyntaxKind::DOUBLE_QUOTE),
@@ -208,20 +229,17 @@ impl<'source> Lexer<'source> {
     fn next_code_block_token(&mut self) -> SyntaxKind {
         match self.current() {
             // Consecutive newlines immediately become blank lines
-            b'\n' if self.state.last_was_newline => {
-                self.advance();
+            b'\r' | b'\n' if self.state.last_was_newline => {
+                self.consume_line_ending();
                 return SyntaxKind::BLANK_LINE;
             }
-            // Any other whitespaces character after a newline becomes leading
-            // whitespace, if the configured `indent_depth` is more than 0. If
-            // it is 0, then there cannot be any skipped leading whitespace.
-            c if self.state.last_was_newline
-                && c.is_ascii_whitespace()
-                && self.state.indent_depth > 0 =>
-                {
-                    self.consume_leading_whitespace()
-                }
-            b'\0' => self.consume_byte(SyntaxKind::EOF),
+            // Spaces and tabs after a newline become leading whitespace, if the
+            // configured `indent_depth` is more than 0. If it is 0, then there
+            // cannot be any skipped leading whitespace. Other whitespace, like
+            // form feeds, doesn't count as indentation and stays in the line.
+            b' ' | b'\t' if self.state.last_was_newline && self.state.indent_depth > 0 => {
+                self.consume_leading_whitespace()
+            }
             _ => self.consume_verbatim_line(),
         }
     }
@@ -297,7 +315,7 @@ impl<'source> Lexer<'source> {
             match self.current() {
                 // Reaching the end of the file or line means this is an
                 // entirely blank line.
-                b'\n' => break,
+                b'\r' | b'\n' => break,
                 // ASCII whitespace contributes 1 to the current depth.
                 b' ' => {
                     self.advance();
@@ -318,6 +336,69 @@ impl<'source> Lexer<'source> {
     }
     //#endregion
 
+    //#region Emoji shortcodes
+
+    /// Consume the name of an emoji shortcode as a single EMOJI_NAME token. If there is no valid
+    /// name at the current position, the token is lexed normally instead.
+    fn next_emoji_name_token(&mut self) -> SyntaxKind {
+        if !byte_is_emoji_name(self.current()) {
+            return self.next_regular_token(true);
+        }
+
+        while !self.is_eof() && !self.is_at_block_bound() && byte_is_emoji_name(self.current()) {
+            self.advance();
+        }
+        SyntaxKind::EMOJI_NAME
+    }
+    //#endregion
+
+    //#region Mentions
+
+    /// Consume the longest configured mention sigil at the current position as a single
+    /// MENTION_SIGIL token. If no sigil matches, the token is lexed normally instead.
+    fn next_mention_sigil_token(&mut self) -> SyntaxKind {
+        let remaining = &self.text[self.position..];
+        let sigil_length = self
+            .options
+            .mention_sigils
+            .iter()
+            .filter(|sigil| !sigil.is_empty() && remaining.starts_with(sigil.as_str()))
+            .map(String::len)
+            .max();
+        let Some(sigil_length) = sigil_length else {
+            return self.next_regular_token(true);
+        };
+
+        let end = self.position + sigil_length;
+        while self.position < end {
+            self.advance();
+        }
+        SyntaxKind::MENTION_SIGIL
+    }
+    //#endregion
+
+    //#region Keyboard keys
+
+    /// Consume the name of a keyboard key as a single KEY_NAME token. Names can't start
+    /// or end with whitespace, and if there is no valid name at the current position, the token is
+    /// lexed normally instead.
+    fn next_keyboard_key_name_token(&mut self) -> SyntaxKind {
+        let checkpoint = self.checkpoint();
+        let start = self.position;
+        while !self.is_eof() && !self.is_at_block_bound() && !byte_ends_keyboard_key(self.current())
+        {
+            self.advance();
+        }
+
+        let name = &self.text[start..self.position];
+        if name.is_empty() || name.trim() != name {
+            self.rewind(checkpoint);
+            return self.next_regular_token(true);
+        }
+        SyntaxKind::KEY_NAME
+    }
+    //#endregion
+
     //#region Autolinks
 
     /// Try to consume a single ABSOLUTE_URI or EMAIL_ADDRESS token. If the
@@ -710,13 +791,16 @@ impl<'source> Lexer<'source> {
     /// known HTML entity, only if it matches the appropriate syntax.
     fn consume_html_entity_reference(&mut self, checkpoint: LexerCheckpoint) -> SyntaxKind {
         let mut has_content = false;
-        while self.current().is_ascii_alphanumeric() {
-            has_content = true;
-            self.advance();
+        loop {
             if self.is_eof() {
                 self.rewind(checkpoint);
                 return SyntaxKind::AMPER;
             }
+            if !self.current().is_ascii_alphanumeric() {
+                break;
+            }
+            has_content = true;
+            self.advance();
         }
 
         if self.current() == b';' && has_content {
@@ -748,7 +832,10 @@ impl<'source> Lexer<'source> {
                 // an effect on the markdown parsing anyway. All that we care about
                 // is the brace characters that enter and exit ICU contexts so that
                 // we can track literal state.
-                if current == b'\'' && matches!(self.peek(), Some(b'{' | b'}')) {
+                if current == b'\''
+                    && self.options.apostrophes_escape_braces()
+                    && matches!(self.peek(), Some(b'{' | b'}'))
+                {
                     // Skip past these chars and continue the loop.
                     self.advance_n_bytes(2);
                     continue;
@@ -814,8 +901,8 @@ impl<'source> Lexer<'source> {
             if self.is_eof() {
                 break;
             }
-            if self.current() == b'\n' {
-                self.advance();
+            if matches!(self.current(), b'\r' | b'\n') {
+                self.consume_line_ending();
                 break;
             }
             self.advance();
@@ -832,6 +919,8 @@ impl<'source> Lexer<'source> {
             b'\r' | b'\n' => self.consume_line_ending(),
             b'{' => self.consume_byte(SyntaxKind::LCURLY),
             b',' => self.consume_byte(SyntaxKind::COMMA),
+            b'|' => self.consume_byte(SyntaxKind::PIPE),
+            b'@' => self.consume_byte(SyntaxKind::AT),
             b':' if matches!(self.peek(), Some(b':')) => {
                 self.advance_n_bytes(2);
                 self.consume_byte(SyntaxKind::ICU_DOUBLE_COLON)
@@ -854,15 +943,21 @@ impl<'source> Lexer<'source> {
 
         let mut open_brace_count = 0;
         loop {
+            if self.is_eof() {
+                break;
+            }
+
             match self.current() {
                 // Apostrophes count as quoting characters in ICU syntax, so anything within them
                 // will be treated as a string until the second apostrophe closes it, even opening
                 // and closing braces.
                 // NOTE: This does _not_ deal with "escaped escapes", but that's fine for now.
                 b'\'' => {
+                    self.advance();
                     while !self.is_eof() && self.current() != b'\'' {
                         self.advance()
                     }
+                    self.advance();
                 }
                 b'}' if open_brace_count == 0 => break,
                 b'}' => {
@@ -880,6 +975,22 @@ impl<'source> Lexer<'source> {
         SyntaxKind::ICU_STYLE_TEXT
     }
 
+    /// Consume the default value of a variable as a single ICU_DEFAULT_TEXT token, up to the
+    /// closing curly brace. Default values are plain text, so they can't contain any braces, and
+    /// apostrophes are kept as they are. If the lexer is already at a closing brace, it is consumed
+    /// as an RCURLY immediately.
+    fn next_icu_default_token(&mut self) -> SyntaxKind {
+        if self.current() == b'}' {
+            return self.consume_byte(SyntaxKind::RCURLY);
+        }
+
+        while !self.is_eof() && !matches!(self.current(), b'{' | b'}' | b'\r' | b'\n') {
+            self.advance();
+        }
+
+        SyntaxKind::ICU_DEFAULT_TEXT
+    }
+
     fn consume_icu_keyword_or_ident(&mut self) -> SyntaxKind {
         self.consume_icu_ident();
         let span = self.current_byte_span();
@@ -932,7 +1043,6 @@ impl<'source> Lexer<'source> {
         }
 
         while !self.is_eof() && is_unicode_identifier_continue(self.current_char()) {
-            println!("next: {}", self.current_char());
             self.advance();
         }
 
@@ -976,22 +1086,28 @@ impl<'source> Lexer<'source> {
         self.current_kind
     }
 
-    /// Returns the first byte of the character at the current position.
+    /// Returns the first byte of the character at the current position, or a
+    /// NUL byte if the lexer has reached the end of the input.
     fn current(&self) -> u8 {
         debug_assert!(
             self.text.is_char_boundary(self.position),
             "current parser position is not a ut8 char boundary"
         );
-        self.text.as_bytes()[self.position]
+        self.text
+            .as_bytes()
+            .get(self.position)
+            .copied()
+            .unwrap_or(b'\0')
     }
 
-    /// Returns the complete char at the current position.
+    /// Returns the complete char at the current position, or a NUL char if the
+    /// lexer has reached the end of the input.
     fn current_char(&self) -> char {
         debug_assert!(
             self.text.is_char_boundary(self.position),
             "current parser position is not a ut8 char boundary"
         );
-        self.text[self.position..].chars().nth(0).unwrap()
+        self.text[self.position..].chars().nth(0).unwrap_or('\0')
     }
 
     /// Returns the flags that are applied for the current token.
@@ -1061,7 +1177,7 @@ impl<'source> Lexer<'source> {
         // is not plain ASCII.
         self.state.last_was_cjk_punctuation = !last_char.is_ascii() && cjk::is_cjk_punctuation_codepoint(last_char);
 
-        self.state.last_was_newline = last_char == '\n';
+        self.state.last_was_newline = matches!(last_char, '\r' | '\n');
         self.state.last_was_whitespace = last_char.is_whitespace();
         self.state.is_after_newline = self.state.last_was_newline
             || (self.state.is_after_newline && self.state.last_was_whitespace)
@@ -1069,6 +1185,9 @@ impl<'source> Lexer<'source> {
 
     /// Advance the lexer by one unicode character.
     fn advance(&mut self) {
+        if self.is_eof() {
+            return;
+        }
         let previous = self.current();
         self.position += char_length_from_byte(previous);
     }
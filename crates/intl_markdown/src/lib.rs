@@ -1,36 +1,112 @@
 extern crate core;
 
-pub use ast::format::format_ast;
-pub use ast::process::process_cst_to_ast;
+pub use ast::format::{
+    format_ast, format_ast_to, format_ast_to_io, format_ast_to_with_options,
+    format_ast_with_options,
+};
+pub use ast::cjk::{is_cjk_letter, is_cjk_punctuation};
+pub use ast::combinations::{count_combinations, expand_combinations, MessageCombination};
+pub use ast::constants::{substitute_constants, ConstantValue};
+pub use ast::emoji::find_emoji_shortcodes;
+pub use ast::equivalence::documents_equivalent;
+pub use ast::fold::{fold_document, Fold};
+pub use ast::keyboard::find_keyboard_keys;
+pub use ast::linkify::find_bare_urls;
+pub use ast::process::{process_cst_to_ast, process_cst_to_ast_with_options};
+pub use ast::references::{
+    document_inline_content, message_references, resolve_message_references,
+};
 pub use ast::*;
-pub use icu::compile::compile_to_format_js;
+pub use diagnostic::ParseDiagnostic;
+pub use icu::compile::{compile_to_format_js, CompileArena, FormatJsNode};
+pub use icu::decompile::{decompile_format_js, DecompileError};
 pub use icu::format::format_icu_string;
+pub use icu::mf2::format_mf2_string;
+pub use icu::react_intl::ReactIntlAst;
+pub use icu::stringify::to_icu_string;
 pub use icu::tags::DEFAULT_TAG_NAMES;
+pub use options::{ApostropheMode, HtmlFormatOptions, ParserOptions};
 pub use parser::ICUMarkdownParser;
 pub use syntax::SyntaxKind;
 pub use token::SyntaxToken;
 pub use tree_builder::cst::Document as CstDocument;
+pub use tree_builder::flat::CstNode;
 
 pub mod ast;
 mod block_parser;
 mod byte_lookup;
 mod delimiter;
+mod diagnostic;
 mod event;
 mod html_entities;
 mod icu;
 mod lexer;
+mod options;
 mod parser;
 mod syntax;
 mod token;
 mod tree_builder;
 
 /// Parse an intl message into a final AST representing the semantics of the message.
+///
+/// Malformed content never causes this to fail. Anything the parser had to recover from is
+/// reported through `Document::diagnostics`, and content that couldn't be parsed at all is kept as
+/// `BlockNode::Error` nodes. See [ICUMarkdownParser::into_cst] for the one case where the entire
+/// message is kept as an error.
 pub fn parse_intl_message(content: &str, include_blocks: bool) -> Document {
-    let mut parser = ICUMarkdownParser::new(content, include_blocks);
+    parse_intl_message_with_options(content, include_blocks, ParserOptions::default())
+}
+
+/// Parse an intl message like [parse_intl_message], using the given `options` to change how some
+/// of the syntax is interpreted.
+pub fn parse_intl_message_with_options(
+    content: &str,
+    include_blocks: bool,
+    options: ParserOptions,
+) -> Document {
+    let mut parser = ICUMarkdownParser::with_options(content, include_blocks, options.clone());
+    parser.parse();
+    parsed_document(parser, options)
+}
+
+/// Build the AST of a message from a parser that has already parsed it. If the parsed events can't
+/// be read back as a tree, no part of that tree is kept, and the whole message becomes a single
+/// `BlockNode::Error` with a diagnostic where the tree stopped making sense.
+pub(crate) fn parsed_document(mut parser: ICUMarkdownParser, options: ParserOptions) -> Document {
     let source = parser.source().clone();
+    let diagnostics = parser.take_diagnostics();
+    let cst = match parser.check_events().and_then(|()| parser.into_cst()) {
+        Ok(cst) => cst,
+        Err(diagnostic) => {
+            let mut document = Document::from_error(&source, diagnostic);
+            document.extend_diagnostics(diagnostics);
+            return document;
+        }
+    };
+    let mut document = process_cst_to_ast_with_options(source, &cst, options);
+    document.extend_diagnostics(diagnostics);
+    document
+}
+
+/// Parse an intl message like [parse_intl_message], returning its concrete syntax tree.
+///
+/// The tree is a flat list of every node and token in the order they are written, rather than the
+/// semantics of the message. This keeps all of the syntax that the AST leaves out, like delimiters
+/// and the exact text of tokens.
+pub fn parse_intl_message_cst(content: &str, include_blocks: bool) -> Vec<CstNode> {
+    parse_intl_message_cst_with_options(content, include_blocks, ParserOptions::default())
+}
+
+/// Parse the concrete syntax tree of an intl message like [parse_intl_message_cst], using the
+/// given `options` like [parse_intl_message_with_options].
+pub fn parse_intl_message_cst_with_options(
+    content: &str,
+    include_blocks: bool,
+    options: ParserOptions,
+) -> Vec<CstNode> {
+    let mut parser = ICUMarkdownParser::with_options(content, include_blocks, options);
     parser.parse();
-    let cst = parser.into_cst();
-    process_cst_to_ast(source, &cst)
+    parser.cst_nodes()
 }
 
 /// Return a new Document with the given content as the only value, treated as a raw string with
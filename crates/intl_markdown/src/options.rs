@@ -0,0 +1,83 @@
+/// How apostrophes in message text are interpreted by the parser.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ApostropheMode {
+    /// Standard ICU behavior, where an apostrophe directly before `{` or `}` escapes the brace,
+    /// turning it into plain text. For example, `'{name}'` is the literal text `'{name}'` rather than
+    /// a placeholder.
+    #[default]
+    Icu,
+    /// Apostrophes are always literal characters and never escape anything, so `'{name}'` is a
+    /// placeholder surrounded by apostrophes. Braces are escaped with a backslash instead, like
+    /// `\{name\}`.
+    Literal,
+}
+
+/// Options that change how message source text is parsed. The defaults match standard
+/// ICU MessageFormat semantics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParserOptions {
+    pub apostrophe_mode: ApostropheMode,
+    /// Turn bare urls in text, like `https://discord.com` or `www.discord.com`, into links the same
+    /// way GFM does, so authors don't need to remember to wrap them as `<https://discord.com>`.
+    pub autolink_bare_urls: bool,
+    /// Parse emoji shortcodes like `:smile:` into their own nodes, so they can be rendered as
+    /// emoji rather than text. No emoji data is included, so any valid name is accepted.
+    pub emoji_shortcodes: bool,
+    /// Sigils that mark a mention placeholder like `<@{userId}>` or `<#{channelId}>`, where the
+    /// sigil is written between the opening angle bracket and a single variable placeholder.
+    /// Mentions are only parsed when at least one sigil is configured, and the longest matching
+    /// sigil is always used, so both `@` and `@&` can be configured together.
+    pub mention_sigils: Vec<String>,
+    /// Parse keyboard keys and other inline UI elements written like `[[Ctrl]]` into their own
+    /// nodes, so they can be rendered as key caps. Key names can contain spaces, like
+    /// `[[Page Up]]`, but not brackets, braces, or line breaks.
+    pub keyboard_keys: bool,
+}
+
+impl ParserOptions {
+    pub fn with_apostrophe_mode(mut self, apostrophe_mode: ApostropheMode) -> Self {
+        self.apostrophe_mode = apostrophe_mode;
+        self
+    }
+
+    pub fn with_autolink_bare_urls(mut self, autolink_bare_urls: bool) -> Self {
+        self.autolink_bare_urls = autolink_bare_urls;
+        self
+    }
+
+    pub fn with_emoji_shortcodes(mut self, emoji_shortcodes: bool) -> Self {
+        self.emoji_shortcodes = emoji_shortcodes;
+        self
+    }
+
+    pub fn with_mention_sigils(mut self, mention_sigils: Vec<String>) -> Self {
+        self.mention_sigils = mention_sigils;
+        self
+    }
+
+    pub fn with_keyboard_keys(mut self, keyboard_keys: bool) -> Self {
+        self.keyboard_keys = keyboard_keys;
+        self
+    }
+
+    /// Returns true if an apostrophe directly before a brace should escape it.
+    pub(crate) fn apostrophes_escape_braces(&self) -> bool {
+        self.apostrophe_mode == ApostropheMode::Icu
+    }
+}
+
+/// Options that change the html output of [`crate::format_ast_with_options`]. The defaults match
+/// the output expected by the CommonMark spec.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HtmlFormatOptions {
+    /// Add an `id` attribute to every heading with its [`crate::Heading::id`], so that long-form
+    /// content can link directly to its sections.
+    pub heading_ids: bool,
+}
+
+impl HtmlFormatOptions {
+    pub fn with_heading_ids(mut self, heading_ids: bool) -> Self {
+        self.heading_ids = heading_ids;
+        self
+    }
+}
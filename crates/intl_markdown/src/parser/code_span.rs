@@ -40,7 +40,7 @@ pub(super) fn parse_code_span(p: &mut ICUMarkdownParser, kind: SyntaxKind) -> Op
         match p.current() {
             // EOF means the codespan wasn't matched. Spans are also bounded as
             // inline elements, so the end of a block terminates it's reach.
-            SyntaxKind::EOF | SyntaxKind::BLOCK_END => break false,
+            SyntaxKind::EOF | SyntaxKind::BLOCK_END | SyntaxKind::INLINE_END => break false,
             // If another delimiter is found, try to match it and complete the
             // codespan, otherwise just continue consuming it.
             SyntaxKind::BACKTICK => {
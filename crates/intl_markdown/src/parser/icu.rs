@@ -51,6 +51,9 @@ fn parse_icu_inner(p: &mut ICUMarkdownParser) -> Option<()> {
         p.skip_whitespace_as_trivia_with_context(LexContext::Icu);
         let completed_kind = parse_complex_icu_placeholder(p)?;
         outer_mark.complete(p, completed_kind)?;
+    } else if p.at(SyntaxKind::PIPE) {
+        parse_icu_default_value(p)?;
+        outer_mark.complete(p, SyntaxKind::ICU_VARIABLE_WITH_DEFAULT)?;
     }
 
     p.skip_whitespace_as_trivia_with_context(LexContext::Icu);
@@ -77,24 +80,37 @@ fn parse_complex_icu_placeholder(p: &mut ICUMarkdownParser) -> Option<SyntaxKind
     }
 }
 
+/// A default value is written after a pipe, like `{name|Someone}`, and is used in place of the
+/// variable when no value is given for it. Like style arguments, everything up to the closing
+/// brace is taken as plain text, and an empty default fails the whole placeholder.
+fn parse_icu_default_value(p: &mut ICUMarkdownParser) -> Option<()> {
+    p.bump_with_context(LexContext::Icu);
+    p.skip_whitespace_as_trivia_with_context(LexContext::Icu);
+    // Relexing happens after skipping whitespace for the same reason as in style arguments, since
+    // the default context doesn't treat whitespace as trivia.
+    p.relex_with_context(LexContext::IcuDefault);
+    p.expect_with_context(SyntaxKind::ICU_DEFAULT_TEXT, LexContext::Icu)?;
+    Some(())
+}
+
 fn parse_icu_date(p: &mut ICUMarkdownParser) -> Option<SyntaxKind> {
     p.expect_with_context(SyntaxKind::ICU_DATE_KW, LexContext::Icu)?;
     p.skip_whitespace_as_trivia_with_context(LexContext::Icu);
-    parse_optional_icu_style_argument(p, SyntaxKind::ICU_DATE);
+    parse_optional_icu_style_argument(p, SyntaxKind::ICU_DATE)?;
     Some(SyntaxKind::ICU_DATE)
 }
 
 fn parse_icu_time(p: &mut ICUMarkdownParser) -> Option<SyntaxKind> {
     p.expect_with_context(SyntaxKind::ICU_TIME_KW, LexContext::Icu)?;
     p.skip_whitespace_as_trivia_with_context(LexContext::Icu);
-    parse_optional_icu_style_argument(p, SyntaxKind::ICU_TIME);
+    parse_optional_icu_style_argument(p, SyntaxKind::ICU_TIME)?;
     Some(SyntaxKind::ICU_TIME)
 }
 
 fn parse_icu_number(p: &mut ICUMarkdownParser) -> Option<SyntaxKind> {
     p.expect_with_context(SyntaxKind::ICU_NUMBER_KW, LexContext::Icu)?;
     p.skip_whitespace_as_trivia_with_context(LexContext::Icu);
-    parse_optional_icu_style_argument(p, SyntaxKind::ICU_NUMBER);
+    parse_optional_icu_style_argument(p, SyntaxKind::ICU_NUMBER)?;
     Some(SyntaxKind::ICU_NUMBER)
 }
 
@@ -113,9 +129,10 @@ fn parse_optional_icu_style_argument(
     p: &mut ICUMarkdownParser,
     parent_kind: SyntaxKind,
 ) -> Option<()> {
-    // If there's no comma, then there's no style are and this can just return immediately.
+    // If there's no comma, then there's no style arg and this can just return immediately. A
+    // comma that _isn't_ followed by a style is invalid, though, and fails the whole placeholder.
     if !p.at(SyntaxKind::COMMA) {
-        return None;
+        return Some(());
     }
 
     // Otherwise, open the style marker and consume that comma.
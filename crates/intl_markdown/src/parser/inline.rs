@@ -35,6 +35,15 @@ pub(super) fn parse_inline(p: &mut ICUMarkdownParser, is_inside_icu: bool) {
             // an ICU unsafe block, so that is checked first.
             SyntaxKind::EXCLAIM => parse_image_open(p),
             // Links
+            // Keyboard keys are only parsed when enabled, and otherwise `[[Ctrl]]` is plain text
+            // or the start of a link.
+            SyntaxKind::LSQUARE if p.options().keyboard_keys => {
+                let checkpoint = p.checkpoint();
+                parse_keyboard_key(p).or_else(|| {
+                    p.rewind(checkpoint);
+                    parse_link_open(p)
+                })
+            }
             SyntaxKind::LSQUARE => parse_link_open(p),
             SyntaxKind::RSQUARE => parse_link_like_close(p),
             // Code spans
@@ -51,7 +60,13 @@ pub(super) fn parse_inline(p: &mut ICUMarkdownParser, is_inside_icu: bool) {
                 let checkpoint = p.checkpoint();
                 parse_autolink(p).or_else(|| {
                     p.rewind(checkpoint);
-                    parse_plain_text(p)
+                    // Mentions like `<@{userId}>` also start with an angle bracket, but can never
+                    // be valid autolinks, so they are only checked afterward.
+                    let checkpoint = p.checkpoint();
+                    parse_mention(p).or_else(|| {
+                        p.rewind(checkpoint);
+                        parse_plain_text(p)
+                    })
                 })
             }
 
@@ -64,9 +79,28 @@ pub(super) fn parse_inline(p: &mut ICUMarkdownParser, is_inside_icu: bool) {
             // These are like STAR and UNDER for emphasis, but with _slightly_
             // different rules, so they need to be handled separately.
             SyntaxKind::TILDE => parse_strikethrough_delimiter_run(p, p.current()),
+            // Emoji shortcodes
+            // Only parsed when enabled, and otherwise the colons are just text.
+            SyntaxKind::COLON if p.options().emoji_shortcodes => {
+                let checkpoint = p.checkpoint();
+                parse_emoji(p).or_else(|| {
+                    p.rewind(checkpoint);
+                    p.bump();
+                    Some(())
+                })
+            }
 
             // ICU
-            SyntaxKind::LCURLY | SyntaxKind::UNSAFE_LCURLY => parse_icu(p),
+            // References to other messages like `{@OTHER_MESSAGE}` can never be valid ICU, so
+            // they are checked first.
+            SyntaxKind::LCURLY => {
+                let checkpoint = p.checkpoint();
+                parse_message_reference(p).or_else(|| {
+                    p.rewind(checkpoint);
+                    parse_icu(p)
+                })
+            }
+            SyntaxKind::UNSAFE_LCURLY => parse_icu(p),
             SyntaxKind::RCURLY if is_inside_icu => break,
 
             // Anything else is effectively plain text, but kept separate in
@@ -99,3 +133,74 @@ fn parse_autolink(p: &mut ICUMarkdownParser) -> Option<()> {
     autolink.complete(p, SyntaxKind::AUTOLINK);
     Some(())
 }
+
+/// Parse an emoji shortcode like `:smile:`. Shortcodes can't directly follow a letter or digit,
+/// so that text like times (`10:30:00`) is never treated as one.
+fn parse_emoji(p: &mut ICUMarkdownParser) -> Option<()> {
+    if p.previous_char().is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+
+    let emoji = p.mark();
+    // Whitespace is not allowed within shortcodes, so no trivia is skipped.
+    p.expect_with_context(SyntaxKind::COLON, LexContext::EmojiName)?;
+    p.expect(SyntaxKind::EMOJI_NAME)?;
+    p.expect(SyntaxKind::COLON)?;
+
+    emoji.complete(p, SyntaxKind::EMOJI);
+    Some(())
+}
+
+/// Parse a mention like `<@{userId}>`, using the sigils configured in the parser options. The
+/// sigil must be followed directly by a single variable placeholder, without any whitespace.
+fn parse_mention(p: &mut ICUMarkdownParser) -> Option<()> {
+    if p.options().mention_sigils.is_empty() {
+        return None;
+    }
+
+    let mention = p.mark();
+    p.expect_with_context(SyntaxKind::LANGLE, LexContext::MentionSigil)?;
+    p.expect_with_context(SyntaxKind::MENTION_SIGIL, LexContext::Icu)?;
+    p.expect_with_context(SyntaxKind::LCURLY, LexContext::Icu)?;
+    if !(p.at(SyntaxKind::ICU_IDENT) || p.current().is_icu_keyword()) {
+        return None;
+    }
+    let variable = p.mark();
+    p.bump_as(SyntaxKind::ICU_IDENT, LexContext::Regular);
+    variable.complete(p, SyntaxKind::ICU_VARIABLE)?;
+    p.expect(SyntaxKind::RCURLY)?;
+    p.expect(SyntaxKind::RANGLE)?;
+
+    mention.complete(p, SyntaxKind::MENTION);
+    Some(())
+}
+
+/// Parse a keyboard key like `[[Ctrl]]`. The key name is a single token, so nothing inside of it
+/// is parsed as Markdown.
+fn parse_keyboard_key(p: &mut ICUMarkdownParser) -> Option<()> {
+    let key = p.mark();
+    p.expect(SyntaxKind::LSQUARE)?;
+    p.expect_with_context(SyntaxKind::LSQUARE, LexContext::KeyboardKeyName)?;
+    p.expect(SyntaxKind::KEY_NAME)?;
+    p.expect(SyntaxKind::RSQUARE)?;
+    p.expect(SyntaxKind::RSQUARE)?;
+
+    key.complete(p, SyntaxKind::KEYBOARD_KEY);
+    Some(())
+}
+
+/// Parse a reference to another message like `{@OTHER_MESSAGE}`, which is replaced by the content
+/// of that message when bundling. Whitespace is not allowed anywhere inside of the reference.
+fn parse_message_reference(p: &mut ICUMarkdownParser) -> Option<()> {
+    let reference = p.mark();
+    p.expect_with_context(SyntaxKind::LCURLY, LexContext::Icu)?;
+    p.expect_with_context(SyntaxKind::AT, LexContext::Icu)?;
+    if !(p.at(SyntaxKind::ICU_IDENT) || p.current().is_icu_keyword()) {
+        return None;
+    }
+    p.bump_as(SyntaxKind::ICU_IDENT, LexContext::Regular);
+    p.expect(SyntaxKind::RCURLY)?;
+
+    reference.complete(p, SyntaxKind::MESSAGE_REFERENCE);
+    Some(())
+}
@@ -227,7 +227,7 @@ fn parse_link_destination(p: &mut ICUMarkdownParser) -> Option<()> {
     let mut token_count = 0;
     loop {
         match p.current() {
-            SyntaxKind::EOF | SyntaxKind::BLOCK_END => break,
+            SyntaxKind::EOF | SyntaxKind::BLOCK_END | SyntaxKind::INLINE_END => break,
             SyntaxKind::WHITESPACE | SyntaxKind::LINE_ENDING => break,
             SyntaxKind::RPAREN if balance == 1 => break,
             SyntaxKind::RPAREN => balance -= 1,
@@ -273,7 +273,11 @@ fn parse_link_title(p: &mut ICUMarkdownParser) -> Option<()> {
 
     let content_start = p.mark();
 
-    while p.current() != end_quote_kind && p.current() != SyntaxKind::BLOCK_END {
+    while !matches!(
+        p.current(),
+        SyntaxKind::EOF | SyntaxKind::BLOCK_END | SyntaxKind::INLINE_END
+    ) && p.current() != end_quote_kind
+    {
         p.bump();
     }
 
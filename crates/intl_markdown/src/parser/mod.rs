@@ -1,7 +1,8 @@
-use crate::token::{SourceText, TriviaList};
+use crate::token::{SourceText, TextIndex, TextSpan, TriviaList};
 use crate::{
     lexer::{LexContext, LexerState},
     token::Trivia,
+    ParseDiagnostic, ParserOptions,
 };
 
 use super::{
@@ -11,6 +12,7 @@ use super::{
     lexer::{Lexer, LexerCheckpoint},
     token::TokenFlags,
     tree_builder::cst::{parser_events_to_cst, Document},
+    tree_builder::flat::{flatten_events, CstNode},
     SyntaxKind, SyntaxToken,
 };
 
@@ -73,6 +75,8 @@ pub struct ICUMarkdownParser<'source> {
     /// container, causing an invalid event buffer order.
     delimiter_stacks: Vec<Vec<AnyDelimiter>>,
     state: ParserState,
+    /// Problems that the parser recovered from while parsing, in the order they were found.
+    diagnostics: Vec<ParseDiagnostic>,
 
     // Configuration
     /// When true, the parser will first analyze the document for Blocks according to the Markdown
@@ -83,14 +87,22 @@ pub struct ICUMarkdownParser<'source> {
 
 impl<'source> ICUMarkdownParser<'source> {
     pub fn new(source: &'source str, include_blocks: bool) -> Self {
+        Self::with_options(source, include_blocks, ParserOptions::default())
+    }
+
+    pub fn with_options(
+        source: &'source str,
+        include_blocks: bool,
+        options: ParserOptions,
+    ) -> Self {
         let block_bounds = if include_blocks {
-            BlockParser::new(source).parse_into_block_bounds()
+            BlockParser::new(source, &options).parse_into_block_bounds()
         } else {
             vec![]
         };
 
         Self {
-            lexer: Lexer::new(source, block_bounds),
+            lexer: Lexer::new(source, block_bounds, options),
             source: SourceText::from(source),
             buffer: Vec::with_capacity(source.len() / 2),
             // Pre-allocating some size here should avoid the need to allocate
@@ -99,6 +111,7 @@ impl<'source> ICUMarkdownParser<'source> {
             trivia_list: TriviaList::new(),
             delimiter_stacks: vec![],
             state: ParserState::default(),
+            diagnostics: vec![],
             include_blocks,
         }
     }
@@ -159,10 +172,18 @@ impl<'source> ICUMarkdownParser<'source> {
                     self.push_event(Event::Finish(kind));
                     self.reset_inline_state();
                 }
-                _ => unreachable!(
-                    "Encountered unexpected kind while parsing at the block level {:?}",
-                    self.current()
-                ),
+                // Content outside of any block means the block bounds and the lexer disagree
+                // about where a block ends. Rather than failing the whole message, the stray
+                // token is kept as trivia so the rest of the document can still be parsed.
+                _ => {
+                    let trivia = self.extract_as_trivia();
+                    self.push_diagnostic(
+                        "Unexpected content outside of any block",
+                        trivia.span_start()..trivia.span_end(),
+                    );
+                    self.trivia_list.push(trivia);
+                    self.lexer.next_token(LexContext::Regular);
+                }
             }
         }
         self.expect_end_of_file();
@@ -185,10 +206,82 @@ impl<'source> ICUMarkdownParser<'source> {
     /// Consume this parser, interpreting its events into a constructed,
     /// lossless syntax tree. The return value is the root Node of that tree,
     /// a Document.
-    pub fn into_cst(self) -> Document {
+    ///
+    /// Returns a diagnostic instead if the events don't match the shape of
+    /// the nodes they describe, like when a required child is missing after
+    /// recovering from an error. No partial tree is returned in that case, so
+    /// callers like `parse_intl_message` keep the entire message as an error.
+    /// Use `check_events` first to also catch events that don't form a
+    /// well-nested tree.
+    pub fn into_cst(self) -> Result<Document, ParseDiagnostic> {
         parser_events_to_cst(self.buffer, self.source, self.trivia_list)
     }
 
+    /// Return the syntax tree described by the parsed events as a flat list of
+    /// nodes and tokens, in the order they are written. Unlike `into_cst`,
+    /// this works even when the events don't form a valid tree.
+    pub fn cst_nodes(&self) -> Vec<CstNode> {
+        flatten_events(&self.buffer, &self.source)
+    }
+
+    /// Returns the diagnostics for all of the problems the parser recovered
+    /// from while parsing.
+    pub fn diagnostics(&self) -> &Vec<ParseDiagnostic> {
+        &self.diagnostics
+    }
+
+    /// Removes and returns the diagnostics that the parser has collected.
+    pub fn take_diagnostics(&mut self) -> Vec<ParseDiagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Verify that the parsed events form a single, well-nested Document
+    /// that can be built into a syntax tree. If they don't, the returned
+    /// diagnostic points to the first event that was out of place.
+    pub fn check_events(&self) -> Result<(), ParseDiagnostic> {
+        let mut open_nodes = vec![];
+        // Tracks where the most recent token ended, since Start and Finish
+        // events don't have a position of their own.
+        let mut position = 0;
+        let mut is_complete = false;
+
+        for event in &self.buffer {
+            if event.kind() == SyntaxKind::TOMBSTONE {
+                continue;
+            }
+            if is_complete {
+                return Err(self.unbalanced_event_diagnostic(position));
+            }
+            match event {
+                Event::Start(kind) => open_nodes.push(*kind),
+                Event::Finish(kind) => {
+                    if open_nodes.pop() != Some(*kind) {
+                        return Err(self.unbalanced_event_diagnostic(position));
+                    }
+                    is_complete = open_nodes.is_empty();
+                }
+                Event::Token(token) => {
+                    if open_nodes.is_empty() {
+                        return Err(self.unbalanced_event_diagnostic(position));
+                    }
+                    position = token.span_end() as usize;
+                }
+            }
+        }
+
+        if !is_complete {
+            return Err(self.unbalanced_event_diagnostic(position));
+        }
+        Ok(())
+    }
+
+    fn unbalanced_event_diagnostic(&self, position: usize) -> ParseDiagnostic {
+        ParseDiagnostic::new(
+            "Message structure could not be determined",
+            position..self.source.len(),
+        )
+    }
+
     // Options API
     //
     // The following methods provide an interface for consumers to read the
@@ -198,6 +291,10 @@ impl<'source> ICUMarkdownParser<'source> {
         self.include_blocks
     }
 
+    pub fn options(&self) -> &ParserOptions {
+        self.lexer.options()
+    }
+
     // Internal API
     //
     // All of the following are the interface for parsing functions to use for
@@ -260,16 +357,41 @@ impl<'source> ICUMarkdownParser<'source> {
         self.lexer.current_block_kind() == kind
     }
 
-    /// Assert that the parser has reached the end of the input, and consume
-    /// that final token to pick up any trailing trivia.
+    /// Check that the parser has reached the end of the input, and consume
+    /// that final token to pick up any trailing trivia. Any content that was
+    /// left unparsed is kept as bare tokens in the Document and reported as a
+    /// diagnostic.
     pub(super) fn expect_end_of_file(&mut self) {
-        // At the end of parsing, the lexer must be at the end of the input.
-        assert!(self.at(SyntaxKind::EOF));
+        if !self.at(SyntaxKind::EOF) {
+            let start = self.lexer.current_byte_span().start;
+            self.push_diagnostic(
+                "Unexpected content after the end of the message",
+                start..self.source.len() as TextIndex,
+            );
+            while !self.at(SyntaxKind::EOF) {
+                match self.current() {
+                    SyntaxKind::BLOCK_START
+                    | SyntaxKind::BLOCK_END
+                    | SyntaxKind::INLINE_START
+                    | SyntaxKind::INLINE_END => {
+                        self.eat_block_bound();
+                    }
+                    _ => self.bump(),
+                }
+            }
+        }
         // Add the EOF token to the input so that trailing trivia on the
         // document are picked up.
         self.bump();
     }
 
+    pub(super) fn push_diagnostic(&mut self, message: &str, span: TextSpan) {
+        self.diagnostics.push(ParseDiagnostic::new(
+            message,
+            span.start as usize..span.end as usize,
+        ));
+    }
+
     /// Advances the lexer by one token, adding the current token to the end of
     /// the event buffer as a Token event.
     #[inline]
@@ -355,6 +477,12 @@ impl<'source> ICUMarkdownParser<'source> {
         self.lexer.relex_with_context(context)
     }
 
+    /// Returns the character in the source text directly before the current token, if any.
+    pub(super) fn previous_char(&self) -> Option<char> {
+        let start = self.lexer.current_byte_span().start as usize;
+        self.source[..start].chars().next_back()
+    }
+
     /// Returns true if the lexer is currently at a token of the given kind.
     #[inline]
     pub(super) fn at(&self, kind: SyntaxKind) -> bool {
@@ -449,8 +577,11 @@ impl<'source> ICUMarkdownParser<'source> {
 
 #[cfg(test)]
 mod test {
-    use crate::event::DebugEventBuffer;
-    use crate::{format_ast, process_cst_to_ast};
+    use crate::event::{DebugEventBuffer, Event};
+    use crate::{
+        format_ast, format_icu_string, parsed_document, process_cst_to_ast, BlockNode,
+        ParserOptions, SyntaxKind,
+    };
 
     use super::ICUMarkdownParser;
 
@@ -473,7 +604,7 @@ mod test {
             )
         );
 
-        let cst = parser.into_cst();
+        let cst = parser.into_cst().unwrap();
         println!("CST:\n----\n{:#?}\n", cst);
 
         let ast = process_cst_to_ast(source, &cst);
@@ -482,4 +613,84 @@ mod test {
         let output = format_ast(&ast);
         println!("Output: {:?}", output.unwrap());
     }
+
+    #[test]
+    fn test_unbalanced_events() {
+        let mut parser = ICUMarkdownParser::new("some *text*", false);
+        parser.parse();
+        assert!(parser.check_events().is_ok());
+
+        parser.push_event(Event::Finish(SyntaxKind::PARAGRAPH));
+        let diagnostic = parser.check_events().unwrap_err();
+        assert_eq!(&(11..11), diagnostic.span());
+    }
+
+    #[test]
+    fn test_missing_required_child() {
+        let mut parser = ICUMarkdownParser::new("some *text*", false);
+        parser.parse();
+
+        // An Emphasis node without any of its delimiter tokens is still well-nested.
+        let document_end = parser.buffer.len() - 1;
+        parser.buffer.splice(
+            document_end..document_end,
+            [
+                Event::Start(SyntaxKind::EMPHASIS),
+                Event::Finish(SyntaxKind::EMPHASIS),
+            ],
+        );
+        assert!(parser.check_events().is_ok());
+        let diagnostic = parser.into_cst().unwrap_err();
+        assert_eq!(&(11..11), diagnostic.span());
+    }
+
+    #[test]
+    fn test_unreadable_tree_keeps_message_as_error() {
+        let mut parser = ICUMarkdownParser::new("some *text*", false);
+        parser.parse();
+        let document_end = parser.buffer.len() - 1;
+        parser.buffer.splice(
+            document_end..document_end,
+            [
+                Event::Start(SyntaxKind::EMPHASIS),
+                Event::Finish(SyntaxKind::EMPHASIS),
+            ],
+        );
+
+        // None of the tree that could be read is kept, only the original content.
+        let document = parsed_document(parser, ParserOptions::default());
+        assert!(matches!(
+            document.blocks().as_slice(),
+            [BlockNode::Error(content)] if content == "some *text*"
+        ));
+        assert_eq!(document.diagnostics().len(), 1);
+        assert_eq!(&(11..11), document.diagnostics()[0].span());
+    }
+
+    #[test]
+    fn test_block_inside_inline_content() {
+        let mut parser = ICUMarkdownParser::new("some *text*", false);
+        let source = parser.source().clone();
+        parser.parse();
+
+        // Wrap the leading text token in a block node, which can't appear inside of inline content.
+        let text_index = parser
+            .buffer
+            .iter()
+            .position(|event| matches!(event, Event::Token(_)))
+            .unwrap();
+        parser
+            .buffer
+            .insert(text_index + 1, Event::Finish(SyntaxKind::THEMATIC_BREAK));
+        parser
+            .buffer
+            .insert(text_index, Event::Start(SyntaxKind::THEMATIC_BREAK));
+        assert!(parser.check_events().is_ok());
+
+        // The rest of the tree is kept, with the block node's source text kept as plain text.
+        let document = process_cst_to_ast(source, &parser.into_cst().unwrap());
+        assert_eq!(format_icu_string(&document).unwrap(), "some <i>text</i>");
+        assert_eq!(document.diagnostics().len(), 1);
+        assert_eq!(&(0..4), document.diagnostics()[0].span());
+    }
 }
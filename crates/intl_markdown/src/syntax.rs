@@ -25,6 +25,9 @@ pub enum SyntaxKind {
     HEX_CHAR_REF,     // Hexadecimal numeric character reference, like `&#X22;`.
     ABSOLUTE_URI,     // An absolute URI, used in autolinks.
     EMAIL_ADDRESS,    // An email address, used in autolinks.
+    EMOJI_NAME,       // The name of an emoji shortcode, like `smile` in `:smile:`.
+    MENTION_SIGIL,    // The sigil of a mention, like `@` in `<@{userId}>`.
+    KEY_NAME,         // The name of a keyboard key, like `Ctrl` in `[[Ctrl]]`.
     VERBATIM_LINE,    // A line that is consumed as a whole with no interpretation.
     // Punctuation
     STAR,          // *
@@ -51,6 +54,8 @@ pub enum SyntaxKind {
     DOLLAR,        // $
     COMMA,         // ,
     EXCLAIM,       // !
+    PIPE,          // |
+    AT,            // @
 
     // Nodes:
     //
@@ -180,6 +185,10 @@ pub enum SyntaxKind {
     HOOK,
     HOOK_NAME,
     CLICK_HANDLER_LINK_DESTINATION,
+    EMOJI,
+    MENTION,
+    KEYBOARD_KEY,
+    MESSAGE_REFERENCE,
 
     // ICU extension nodes
     ICU,        // The overall container node for any ICU content.
@@ -201,15 +210,17 @@ pub enum SyntaxKind {
     ICU_STYLE_TEXT,      // The text token of the ICU_STYLE_ARGUMENT node above.
     ICU_DATE_TIME_STYLE, // Either a keyword like `short` or a skeleton like `::hmsGy`
     ICU_NUMBER_STYLE,    // A number style argument, almost always a skeleton like `::.##`.
+    ICU_DEFAULT_TEXT,    // The fallback text `Unknown` in `{var|Unknown}`.
     // ICU Nodes
-    ICU_DATE,           // {var, date} or {var, date, format}
-    ICU_TIME,           // {var, time} or {var, time, format}
-    ICU_NUMBER,         // {var, number} or {var, number, format}
-    ICU_PLACEHOLDER,    // {var}
-    ICU_PLURAL,         // {var, plural, ...}
-    ICU_SELECT,         // {var, select, ...}
-    ICU_SELECT_ORDINAL, // {var, selectordinal, ...}
-    ICU_VARIABLE,       // `var` in `{var}` or `{var, plural}` and so on.
+    ICU_DATE,                  // {var, date} or {var, date, format}
+    ICU_TIME,                  // {var, time} or {var, time, format}
+    ICU_NUMBER,                // {var, number} or {var, number, format}
+    ICU_PLACEHOLDER,           // {var}
+    ICU_PLURAL,                // {var, plural, ...}
+    ICU_SELECT,                // {var, select, ...}
+    ICU_SELECT_ORDINAL,        // {var, selectordinal, ...}
+    ICU_VARIABLE,              // `var` in `{var}` or `{var, plural}` and so on.
+    ICU_VARIABLE_WITH_DEFAULT, // {var|default}
     // ICU_PLURAL_ARMS,  // The list of arms in a plural or select node.
     ICU_PLURAL_ARM,   // The `one {inner}` in `{var, plural, one {inner}}`
     ICU_PLURAL_VALUE, // The `inner` in `{var, plural, one {inner}}`
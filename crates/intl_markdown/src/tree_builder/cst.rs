@@ -4,7 +4,7 @@ use crate::event::{Event, EventBuffer};
 use crate::syntax::SyntaxKind;
 use crate::token::{SourceText, Token};
 use crate::token::TriviaList;
-use crate::tree_builder::{ReadFromEventBuf, TokenSpan};
+use crate::tree_builder::{ReadFromEventBuf, SyntaxResult, TokenSpan};
 
 //#region Boilerplate
 pub enum NodeOrToken {
@@ -81,11 +81,11 @@ impl TokenSpan for NodeOrToken {
 
 impl ReadFromEventBuf for NodeOrToken {
     #[inline(always)]
-    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Self {
+    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> SyntaxResult<Self> {
         if matches!(buf.peek(), Some(Event::Token(_))) {
-            Self::Token(Token::read_from(buf))
+            Token::read_from(buf).map(Self::Token)
         } else {
-            Self::Node(Node::read_from(buf))
+            Node::read_from(buf).map(Self::Node)
         }
     }
 }
@@ -325,6 +325,40 @@ pub struct HookName {
     pub r_paren: Token,
 }
 
+#[derive(Debug, ReadFromEvents)]
+pub struct Emoji {
+    pub l_colon: Token,
+    pub name: Token,
+    pub r_colon: Token,
+}
+
+#[derive(Debug, ReadFromEvents)]
+pub struct Mention {
+    pub l_angle: Token,
+    pub sigil: Token,
+    pub l_curly: Token,
+    pub variable: IcuVariable,
+    pub r_curly: Token,
+    pub r_angle: Token,
+}
+
+#[derive(Debug, ReadFromEvents)]
+pub struct KeyboardKey {
+    pub l_square_1: Token,
+    pub l_square_2: Token,
+    pub name: Token,
+    pub r_square_1: Token,
+    pub r_square_2: Token,
+}
+
+#[derive(Debug, ReadFromEvents)]
+pub struct MessageReference {
+    pub l_curly: Token,
+    pub at: Token,
+    pub key: Token,
+    pub r_curly: Token,
+}
+
 #[derive(Debug, ReadFromEvents)]
 pub struct Strikethrough {
     pub l_tilde_1: Token,
@@ -346,6 +380,7 @@ pub struct Icu {
 #[derive(ReadFromEvents)]
 pub enum IcuPlaceholder {
     IcuVariable(IcuVariable),
+    IcuVariableWithDefault(IcuVariableWithDefault),
     IcuPlural(IcuPlural),
     IcuSelectOrdinal(IcuSelectOrdinal),
     IcuSelect(IcuSelect),
@@ -359,6 +394,13 @@ pub struct IcuVariable {
     pub ident: Token,
 }
 
+#[derive(Debug, ReadFromEvents)]
+pub struct IcuVariableWithDefault {
+    pub variable: IcuVariable,
+    pub pipe: Token,
+    pub default_text: Token,
+}
+
 #[derive(Debug, ReadFromEvents)]
 pub struct IcuPlural {
     pub variable: IcuVariable,
@@ -451,11 +493,19 @@ pub enum Node {
     Autolink(Autolink),
     CodeSpan(CodeSpan),
     Hook(Hook),
+    Emoji(Emoji),
+    Mention(Mention),
+    KeyboardKey(KeyboardKey),
+    MessageReference(MessageReference),
     Strikethrough(Strikethrough),
     Icu(Icu),
 }
 
-pub fn parser_events_to_cst(buf: Vec<Event>, source: SourceText, trivia: TriviaList) -> Document {
+pub fn parser_events_to_cst(
+    buf: Vec<Event>,
+    source: SourceText,
+    trivia: TriviaList,
+) -> SyntaxResult<Document> {
     let only_important_events = buf
         .into_iter()
         .filter(|event| !matches!(event.kind(), SyntaxKind::TOMBSTONE));
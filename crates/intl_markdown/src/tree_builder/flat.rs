@@ -0,0 +1,86 @@
+use std::ops::Range;
+
+use crate::event::Event;
+use crate::token::SourceText;
+use crate::SyntaxKind;
+
+/// A single node or token of the concrete syntax tree of a message.
+///
+/// The whole tree is represented as a flat list of these in the order they are written, where each
+/// node is followed by all of its descendants, for tools that want to analyze the syntax of a
+/// message without walking the typed tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CstNode {
+    pub kind: SyntaxKind,
+    /// How many nodes this one is nested inside of. The document is the only node at depth 0.
+    pub depth: usize,
+    /// Byte range of the source that this node covers, from the start of its first token to the
+    /// end of its last one, leaving out surrounding trivia. Nodes without any tokens are empty
+    /// ranges where they would be.
+    pub span: Range<usize>,
+    /// The source text of a token, or None for nodes.
+    pub text: Option<String>,
+}
+
+impl CstNode {
+    pub fn is_token(&self) -> bool {
+        self.text.is_some()
+    }
+}
+
+/// Convert the events from the parser into the flat list of [CstNode]s they describe. Unlike
+/// building the typed tree, this never fails. Nodes that are never finished extend to the end of
+/// their last token.
+pub(crate) fn flatten_events(events: &[Event], source: &SourceText) -> Vec<CstNode> {
+    let mut nodes: Vec<CstNode> = vec![];
+    // Indices in `nodes` of every node that has started but not yet finished, and whether it has
+    // any tokens yet.
+    let mut open_nodes: Vec<(usize, bool)> = vec![];
+    // Where the most recent token ended, since Start and Finish events don't have a position.
+    let mut position = 0;
+
+    for event in events {
+        if event.kind() == SyntaxKind::TOMBSTONE {
+            continue;
+        }
+        match event {
+            Event::Start(kind) => {
+                open_nodes.push((nodes.len(), false));
+                nodes.push(CstNode {
+                    kind: *kind,
+                    depth: open_nodes.len() - 1,
+                    span: position..position,
+                    text: None,
+                });
+            }
+            Event::Finish(kind) => {
+                if open_nodes
+                    .last()
+                    .is_some_and(|(index, _)| nodes[*index].kind == *kind)
+                {
+                    open_nodes.pop();
+                }
+            }
+            Event::Token(token) => {
+                let span = token.span_start() as usize..token.span_end() as usize;
+                for (index, has_tokens) in &mut open_nodes {
+                    // Nodes start where their first token does, rather than after the previous one.
+                    if !*has_tokens {
+                        nodes[*index].span.start = span.start;
+                        *has_tokens = true;
+                    }
+                    nodes[*index].span.end = span.end;
+                }
+                position = span.end;
+                nodes.push(CstNode {
+                    kind: token.kind(),
+                    depth: open_nodes.len(),
+                    text: Some(source[span.clone()].to_string()),
+                    span,
+                });
+            }
+        }
+    }
+
+    nodes
+}
@@ -2,9 +2,16 @@ use crate::{
     event::{Event, EventBuffer},
     SyntaxKind,
     token::Token,
+    ParseDiagnostic,
 };
 
 pub mod cst;
+pub mod flat;
+
+/// The result of reading a node from the event buffer. Reading fails when the events don't match
+/// the shape of the node, like when a required child is missing after the parser recovered from
+/// an error, and the diagnostic points to where the tree stopped making sense.
+pub(crate) type SyntaxResult<T> = Result<T, ParseDiagnostic>;
 
 /// General trait allowing callers to access the first and last tokens of any kind of node, even if
 /// the exact token isn't referenced until multiple levels down.
@@ -24,11 +31,15 @@ pub(crate) trait ReadFromEventBuf {
         Self::KIND == kind
     }
 
-    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Self;
+    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> SyntaxResult<Self>
+    where
+        Self: Sized;
 
     /// Like `read_from`, but allows the node to be missing from the
     /// buffer, in which case this method returns None.
-    fn read_optional_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Option<Self>
+    fn read_optional_from<I: Iterator<Item = Event>>(
+        buf: &mut EventBuffer<I>,
+    ) -> SyntaxResult<Option<Self>>
     where
         Self: Sized,
     {
@@ -36,9 +47,9 @@ pub(crate) trait ReadFromEventBuf {
             .peek()
             .is_some_and(|event| Self::matches_kind(event.kind()))
         {
-            Some(Self::read_from(buf))
+            Self::read_from(buf).map(Some)
         } else {
-            None
+            Ok(None)
         }
     }
 }
@@ -48,12 +59,12 @@ impl<'source, T: ReadFromEventBuf> ReadFromEventBuf for Option<T> {
     const IS_TOKEN: bool = T::IS_TOKEN;
 
     #[inline(always)]
-    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Self {
+    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> SyntaxResult<Self> {
         if T::IS_TOKEN {
             if matches!(buf.peek(), Some(Event::Token(_))) {
-                Some(T::read_from(buf))
+                T::read_from(buf).map(Some)
             } else {
-                None
+                Ok(None)
             }
         } else {
             T::read_optional_from(buf)
@@ -65,29 +76,29 @@ impl ReadFromEventBuf for Token {
     const IS_TOKEN: bool = true;
 
     #[inline(always)]
-    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Self {
+    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> SyntaxResult<Self> {
         buf.next_as_token()
     }
 }
 
 impl<'source, T: ReadFromEventBuf> ReadFromEventBuf for Vec<T> {
     #[inline(always)]
-    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Self {
+    fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> SyntaxResult<Self> {
         // Special-casing here ensures that flat lists of tokens can be read
         // iteratively and still stop at the next node boundary (either a Start
         // _or_ a Finish).
         if T::IS_TOKEN {
             let mut children = vec![];
             while matches!(buf.peek(), Some(Event::Token(_))) {
-                children.push(T::read_from(buf));
+                children.push(T::read_from(buf)?);
             }
-            children
+            Ok(children)
         } else {
             let mut children = vec![];
             while !matches!(buf.peek(), None | Some(Event::Finish(_))) {
-                children.push(T::read_from(buf));
+                children.push(T::read_from(buf)?);
             }
-            children
+            Ok(children)
         }
     }
 }
@@ -0,0 +1,101 @@
+use intl_markdown::{
+    count_combinations, expand_combinations, format_icu_string, parse_intl_message,
+};
+
+fn expand(content: &str) -> Vec<(String, String)> {
+    let document = parse_intl_message(content, false);
+    expand_combinations(&document, 100)
+        .unwrap()
+        .into_iter()
+        .map(|combination| {
+            let selections = combination
+                .selections
+                .iter()
+                .map(|(name, selector)| format!("{name}={selector}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            (
+                selections,
+                format_icu_string(&combination.document).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn counts_sibling_and_nested_arms() {
+    let count = |content| count_combinations(&parse_intl_message(content, false));
+    assert_eq!(1, count("no plurals here"));
+    assert_eq!(
+        6,
+        count("{a, plural, one {x} few {y} other {z}} {b, select, m {x} other {y}}")
+    );
+    assert_eq!(
+        4,
+        count("{a, plural, one {{b, select, m {x} f {y} other {z}}} other {w}}")
+    );
+    assert_eq!(
+        4,
+        count("**{a, plural, one {x} other {y}}** [{b, select, m {x} other {y}}](onClick)")
+    );
+}
+
+#[test]
+fn expands_every_combination() {
+    assert_eq!(
+        vec![
+            (
+                "count=one,who=me".to_string(),
+                "{count, number} item for me".to_string()
+            ),
+            (
+                "count=one,who=other".into(),
+                "{count, number} item for you".into()
+            ),
+            (
+                "count=other,who=me".into(),
+                "{count, number} items for me".into()
+            ),
+            (
+                "count=other,who=other".into(),
+                "{count, number} items for you".into()
+            ),
+        ],
+        expand(
+            "{count, plural, one {# item} other {# items}} for {who, select, me {me} other {you}}"
+        )
+    );
+}
+
+#[test]
+fn expands_nested_arms_only_where_they_appear() {
+    assert_eq!(
+        vec![
+            ("count=one,who=me".to_string(), "mine".to_string()),
+            ("count=one,who=other".into(), "yours".into()),
+            ("count=other".into(), "{count, number} things".into()),
+        ],
+        expand("{count, plural, one {{who, select, me {mine} other {yours}}} other {# things}}")
+    );
+}
+
+#[test]
+fn expands_messages_without_plurals_to_themselves() {
+    assert_eq!(
+        vec![("".to_string(), "hello {name}".to_string())],
+        expand("hello {name}")
+    );
+}
+
+#[test]
+fn does_not_expand_past_the_limit() {
+    let document = parse_intl_message(
+        "{a, plural, one {x} other {y}} {b, select, m {x} other {y}} {c, select, m {x} other {y}}",
+        false,
+    );
+    assert_eq!(
+        Some(8),
+        expand_combinations(&document, 8).map(|all| all.len())
+    );
+    assert!(expand_combinations(&document, 7).is_none());
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use intl_markdown::{format_icu_string, parse_intl_message, substitute_constants, ConstantValue};
+
+fn substitute(content: &str) -> String {
+    let constants = HashMap::from([
+        (
+            "PRODUCT_NAME".to_string(),
+            ConstantValue::Text("Discord".into()),
+        ),
+        ("PLATFORM".to_string(), ConstantValue::Text("ios".into())),
+        ("MAX_UPLOAD_MB".to_string(), ConstantValue::Number(25.0)),
+        ("MAX_BOTS".to_string(), ConstantValue::Number(1.0)),
+        ("RATIO".to_string(), ConstantValue::Number(0.5)),
+    ]);
+    let document = parse_intl_message(content, false);
+    format_icu_string(&substitute_constants(document, &constants)).unwrap()
+}
+
+#[test]
+fn plain_placeholders() {
+    assert_eq!(
+        "Welcome to Discord!",
+        substitute("Welcome to {PRODUCT_NAME}!")
+    );
+    assert_eq!("Up to 25 MB", substitute("Up to {MAX_UPLOAD_MB} MB"));
+    assert_eq!("0.5", substitute("{RATIO}"));
+    assert_eq!("Discord", substitute("{PRODUCT_NAME|an app}"));
+    assert_eq!("Hi {name}", substitute("Hi {name}"));
+}
+
+#[test]
+fn selects_choose_matching_arm() {
+    assert_eq!(
+        "Tap here",
+        substitute("{PLATFORM, select, ios {Tap here} other {Click here}}")
+    );
+    assert_eq!(
+        "Click here",
+        substitute("{PLATFORM, select, android {Tap here} other {Click here}}")
+    );
+    assert_eq!(
+        "Open <b>Discord</b>",
+        substitute("{PLATFORM, select, ios {Open **{PRODUCT_NAME}**} other {x}}")
+    );
+}
+
+#[test]
+fn plurals_only_use_exact_arms() {
+    assert_eq!(
+        "Only one bot",
+        substitute("{MAX_BOTS, plural, =1 {Only one bot} other {# bots}}")
+    );
+    // Choosing `one` or `other` depends on the locale.
+    assert_eq!(
+        "{MAX_UPLOAD_MB, plural, one {# MB} other {# MB}}",
+        substitute("{MAX_UPLOAD_MB, plural, one {# MB} other {# MB}}")
+    );
+    // `#` is formatted with the locale's number format.
+    assert_eq!(
+        "{MAX_BOTS, plural, =1 {# bot} other {# bots}}",
+        substitute("{MAX_BOTS, plural, =1 {# bot} other {# bots}}")
+    );
+}
+
+#[test]
+fn formatted_placeholders_are_kept() {
+    assert_eq!(
+        "Up to {MAX_UPLOAD_MB, number} MB",
+        substitute("Up to {MAX_UPLOAD_MB, number} MB")
+    );
+}
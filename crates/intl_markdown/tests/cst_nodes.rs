@@ -0,0 +1,49 @@
+use intl_markdown::{parse_intl_message_cst, CstNode};
+
+fn describe(nodes: &[CstNode]) -> Vec<String> {
+    nodes
+        .iter()
+        .map(|node| {
+            format!(
+                "{}{:?} {:?} {}",
+                "  ".repeat(node.depth),
+                node.kind,
+                node.span,
+                node.text.as_deref().unwrap_or("")
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn nodes_and_tokens_in_source_order() {
+    let nodes = parse_intl_message_cst("Hi **{name}**", false);
+    assert_eq!(
+        describe(&nodes),
+        vec![
+            "DOCUMENT 0..13",
+            "  INLINE_CONTENT 0..13",
+            "    TEXT 0..2 Hi",
+            "    STRONG 3..13",
+            "      STAR 3..4 *",
+            "      STAR 4..5 *",
+            "      INLINE_CONTENT 5..11",
+            "        ICU 5..11",
+            "          LCURLY 5..6 {",
+            "          ICU_VARIABLE 6..10",
+            "            ICU_IDENT 6..10 name",
+            "          RCURLY 10..11 }",
+            "      STAR 11..12 *",
+            "      STAR 12..13 *",
+            "  EOF 13..13",
+        ]
+    );
+}
+
+#[test]
+fn malformed_messages_still_have_nodes() {
+    let nodes = parse_intl_message_cst("Hello {name", false);
+    assert!(nodes.iter().any(|node| node.text.as_deref() == Some("name")));
+}
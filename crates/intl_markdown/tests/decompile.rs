@@ -0,0 +1,54 @@
+use test_case::test_case;
+
+use intl_markdown::{
+    compile_to_format_js, decompile_format_js, parse_intl_message, CompileArena,
+};
+
+fn compile(content: &str) -> String {
+    let document = parse_intl_message(content, content.contains("\n\n"));
+    keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new())).unwrap()
+}
+
+fn decompile(compiled: &str) -> String {
+    decompile_format_js(&serde_json::from_str(compiled).unwrap()).unwrap()
+}
+
+#[test_case("plain text"; "literal")]
+#[test_case("Hello, {username}!"; "argument")]
+#[test_case("Hello, {username|friend}!"; "argument_default")]
+#[test_case("{count, number, percent} at {today, date, medium} {now, time}"; "formatted")]
+#[test_case("{count, plural, =0 {none} one {# item} other {# items}}"; "plural")]
+#[test_case("{count, selectordinal, one {#st} other {#th}}"; "selectordinal")]
+#[test_case("{color, select, blue {**bold** blue} other {plain}}"; "select")]
+#[test_case("***emphasized** italicized* and ~~gone~~"; "style_tags")]
+#[test_case("run `a `` b` now"; "code_span")]
+#[test_case("[a *link*](./somewhere.png) and [dynamic]({target})"; "links")]
+#[test_case("$[click here](onClick)"; "hook")]
+#[test_case("line one\\\nline two\n\nnext paragraph"; "hard_line_break")]
+#[test_case("not \\*emphasis\\* or \\[a link\\] \\&amp;"; "escaped_markdown")]
+#[test_case("keep '{literal}' braces"; "quoted_icu")]
+#[test_case("# Title\n\nSome paragraph\n\n---\n\n```\ncode\n```"; "blocks")]
+fn round_trip(input: &str) {
+    let compiled = compile(input);
+    assert_eq!(compiled, compile(&decompile(&compiled)));
+}
+
+#[test]
+fn canonical_syntax() {
+    assert_eq!(
+        "**bold** and *italic*",
+        decompile(r#"[[8,"$b",["bold"]]," and ",[8,"$i",["italic"]]]"#)
+    );
+    assert_eq!(r"static \*text\*", decompile(r#""static *text*""#));
+    assert_eq!(
+        "{count, plural, one {#}}",
+        decompile(r#"[[6,"count",{"one":[[7]]},0,"cardinal"]]"#)
+    );
+}
+
+#[test]
+fn invalid() {
+    for compiled in [r#"[[99]]"#, r#"[[1]]"#, r#"[[6,"count",[]]]"#, "1"] {
+        assert!(decompile_format_js(&serde_json::from_str(compiled).unwrap()).is_err());
+    }
+}
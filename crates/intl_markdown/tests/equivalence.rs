@@ -0,0 +1,38 @@
+use intl_markdown::{documents_equivalent, parse_intl_message};
+
+fn equivalent(a: &str, b: &str) -> bool {
+    let may_have_blocks = |content: &str| content.contains('\n');
+    documents_equivalent(
+        &parse_intl_message(a, may_have_blocks(a)),
+        &parse_intl_message(b, may_have_blocks(b)),
+    )
+}
+
+#[test]
+fn identical_content() {
+    assert!(equivalent("Hello, {name}!", "Hello, {name}!"));
+    assert!(!equivalent("Hello, {name}!", "Hello, {user}!"));
+    assert!(!equivalent("Hello, **{name}**!", "Hello, *{name}*!"));
+}
+
+#[test]
+fn escapes_are_ignored() {
+    assert!(equivalent("1\\. Not a list", "1. Not a list"));
+    assert!(equivalent("a \\* b", "a * b"));
+    assert!(!equivalent("a \\*b\\*", "a *b*"));
+}
+
+#[test]
+fn surrounding_whitespace_is_ignored() {
+    assert!(equivalent("  Hello\n", "Hello"));
+    assert!(equivalent("Hello\r\nthere\n", "Hello\nthere"));
+    assert!(!equivalent("Hello there", "Hello  there"));
+}
+
+#[test]
+fn block_syntax_variants() {
+    assert!(equivalent("Title\n=====\n\nBody", "# Title\n\nBody"));
+    assert!(!equivalent("# Title\n\nBody", "## Title\n\nBody"));
+    assert!(equivalent("    code\n\ntext", "```\ncode\n```\n\ntext"));
+    assert!(!equivalent("First\n\nSecond", "First Second"));
+}
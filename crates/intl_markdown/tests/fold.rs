@@ -0,0 +1,44 @@
+use intl_markdown::{fold_document, format_icu_string, parse_intl_message, Fold, IcuVariable};
+
+struct Uppercase;
+
+impl Fold for Uppercase {
+    fn fold_text(&mut self, text: String) -> String {
+        text.to_uppercase()
+    }
+}
+
+struct RenameVariables;
+
+impl Fold for RenameVariables {
+    fn fold_icu_variable(&mut self, node: IcuVariable) -> IcuVariable {
+        let name = format!("renamed_{}", node.name());
+        node.with_name(&name)
+    }
+}
+
+#[test]
+fn folds_text_but_not_code() {
+    let document = parse_intl_message(
+        "hello **{user}**, `code` {count, plural, one {# item} other {# items}}",
+        false,
+    );
+    let folded = fold_document(document, &mut Uppercase);
+    assert_eq!(
+        "HELLO <b>{user}</b>, <code>code</code> {count, plural, one {# ITEM} other {# ITEMS}}",
+        format_icu_string(&folded).unwrap()
+    );
+}
+
+#[test]
+fn folds_every_variable() {
+    let document = parse_intl_message(
+        "[{name}]({url}) {count, number} {count, plural, one {{date, date}} other {#}}",
+        false,
+    );
+    let folded = fold_document(document, &mut RenameVariables);
+    assert_eq!(
+        "<link>{renamed_url}{renamed_name}</link> {renamed_count, number} {renamed_count, plural, one {{renamed_date, date}} other {#}}",
+        format_icu_string(&folded).unwrap()
+    );
+}
@@ -1,19 +1,19 @@
 use intl_markdown::{
-    compile_to_format_js, format_ast, format_icu_string, process_cst_to_ast, CstDocument, Document,
-    ICUMarkdownParser,
+    compile_to_format_js, format_ast, format_ast_to_io, format_icu_string, format_mf2_string,
+    process_cst_to_ast, CompileArena, CstDocument, Document, ICUMarkdownParser,
 };
 
 pub fn parse(content: &str, include_blocks: bool) -> CstDocument {
     let mut parser = ICUMarkdownParser::new(content, include_blocks);
     parser.parse();
-    parser.into_cst()
+    parser.into_cst().unwrap()
 }
 
 pub fn parse_to_ast(content: &str, include_blocks: bool) -> Document {
     let mut parser = ICUMarkdownParser::new(content, include_blocks);
     let source = parser.source().clone();
     parser.parse();
-    process_cst_to_ast(source, &parser.into_cst())
+    process_cst_to_ast(source, &parser.into_cst().unwrap())
 }
 
 /// Test that the input is parsed and formatted as HTML as given.
@@ -24,6 +24,11 @@ pub fn run_spec_test(input: &str, expected: &str) {
     let output = format_ast(&ast).unwrap();
 
     assert_eq!(expected, output);
+
+    // Streaming directly into a byte sink should produce exactly the same content.
+    let mut streamed = vec![];
+    format_ast_to_io(&ast, &mut streamed).unwrap();
+    assert_eq!(expected.as_bytes(), streamed);
 }
 
 /// Test that the input is parsed and formatted as an ICU string as given.
@@ -36,12 +41,22 @@ pub fn run_icu_string_test(input: &str, expected: &str, include_blocks: bool) {
     assert_eq!(expected, output);
 }
 
+/// Test that the input is parsed and formatted as an MF2 string as given.
+#[allow(unused)]
+pub fn run_mf2_string_test(input: &str, expected: &str) {
+    let ast = parse_to_ast(input, false);
+    let output = format_mf2_string(&ast).unwrap();
+
+    assert_eq!(expected, output);
+}
+
 /// Test that the input is parsed and formatted as an ICU AST as given.
 #[allow(unused)]
 pub fn run_icu_ast_test(input: &str, expected: &str, include_blocks: bool) {
     // AST-based formatting
     let ast = parse_to_ast(input, include_blocks);
-    let output = keyless_json::to_string(&compile_to_format_js(&ast)).unwrap();
+    let arena = CompileArena::new();
+    let output = keyless_json::to_string(&compile_to_format_js(&ast, &arena)).unwrap();
 
     assert_eq!(expected, output);
 }
@@ -71,6 +86,18 @@ macro_rules! icu_block_string_test {
     };
 }
 
+#[allow(unused)]
+macro_rules! mf2_string_test {
+    ($name:ident, $input:literal, $output:literal) => {
+        #[test]
+        fn $name() {
+            crate::harness::run_mf2_string_test($input, $output);
+        }
+    };
+}
+
 pub(crate) use ast_test;
 pub(crate) use icu_block_string_test;
 pub(crate) use icu_string_test;
+#[allow(unused)]
+pub(crate) use mf2_string_test;
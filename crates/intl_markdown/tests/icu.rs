@@ -48,6 +48,18 @@ mod icu_variable_formats {
         "{count, number, currency/USD}",
         "{count, number, currency/USD}"
     );
+    icu_string_test!(default_value, "{name | Someone}", "{name|Someone}");
+    icu_string_test!(empty_default_value, "{name|}", "{name|}");
+    icu_string_test!(
+        default_value_trailing_whitespace,
+        "{name|Someone   }",
+        "{name|Someone}"
+    );
+    icu_string_test!(
+        empty_default_value_before_placeholder,
+        "{name| } and {other}",
+        "{name| } and {other}"
+    );
 }
 
 mod icu_markdown_blocks {
@@ -147,3 +159,27 @@ mod icu_escapes {
 
     icu_string_test!(icu_escapes, "'{  variable  }", "'{  variable  }");
 }
+
+mod literal_apostrophes {
+    use intl_markdown::{
+        compile_to_format_js, parse_intl_message_with_options, ApostropheMode, CompileArena,
+        ParserOptions,
+    };
+
+    fn compile_literal(content: &str) -> String {
+        let options = ParserOptions::default().with_apostrophe_mode(ApostropheMode::Literal);
+        let document = parse_intl_message_with_options(content, false, options);
+        keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new())).unwrap()
+    }
+
+    #[test]
+    fn quoted_placeholder() {
+        assert_eq!(r#"["'",[1,"name"],"'"]"#, compile_literal("'{name}'"));
+        assert_eq!(r#"["it's ",[1,"count"]]"#, compile_literal("it's {count}"));
+    }
+
+    #[test]
+    fn backslash_escaped_braces() {
+        assert_eq!(r#"["{name}"]"#, compile_literal("\\{name\\}"));
+    }
+}
@@ -5,6 +5,11 @@ mod icu_formatjs_types {
 
     ast_test!(literal, "plaintext", r#"["plaintext"]"#);
     ast_test!(argument, "{username}", r#"[[1,"username"]]"#);
+    ast_test!(
+        argument_default,
+        "{ username | Someone else }",
+        r#"[[1,"username","Someone else"]]"#
+    );
     ast_test!(number, "{count, number}", r#"[[2,"count"]]"#);
     ast_test!(
         number_style,
@@ -0,0 +1,43 @@
+//! Regression tests for malformed content that previously caused the parser to panic or loop
+//! forever. These only assert that the content can be parsed and compiled in both inline and block
+//! modes, not what the resulting document looks like.
+
+use test_case::test_case;
+
+use intl_markdown::{
+    compile_to_format_js, format_icu_string, CompileArena, Document, ParseDiagnostic,
+};
+
+mod harness;
+
+#[test_case("-&"; "trailing_ampersand")]
+#[test_case("ab\r"; "trailing_carriage_return")]
+#[test_case("a\0b"; "nul_byte")]
+#[test_case("a\r\n"; "crlf_line_ending")]
+#[test_case("`\n-"; "unclosed_code_span_in_setext_heading")]
+#[test_case("[a](b \"c"; "unclosed_link_title")]
+#[test_case("    1\r  b"; "carriage_return_after_indented_code_block")]
+#[test_case("\t~\r\r\t_"; "carriage_return_blank_line_in_indented_code_block")]
+#[test_case(" ```\n\x0c"; "form_feed_in_fenced_code_block")]
+#[test_case(" #\n\n>#"; "empty_indented_atx_heading")]
+#[test_case("# {a, plural, one {"; "unclosed_icu_in_atx_heading")]
+#[test_case("{a, number, }"; "empty_number_style")]
+#[test_case("{a, number, 'x'}"; "quoted_number_style")]
+fn does_not_panic(input: &str) {
+    for include_blocks in [false, true] {
+        let ast = harness::parse_to_ast(input, include_blocks);
+        compile_to_format_js(&ast, &CompileArena::new());
+        format_icu_string(&ast).unwrap();
+    }
+}
+
+#[test]
+fn error_document_keeps_content() {
+    let document = Document::from_error(
+        "{a, plural",
+        ParseDiagnostic::new("Message structure could not be determined", 0..10),
+    );
+    assert!(document.has_errors());
+    assert_eq!("{a, plural", format_icu_string(&document).unwrap());
+    compile_to_format_js(&document, &CompileArena::new());
+}
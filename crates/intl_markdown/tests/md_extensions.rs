@@ -1,5 +1,5 @@
 //! Tests for Markdown syntax extensions, specifically hooks (`$[]()`), unsafe variables (`!!{}!!`),
-//! and strikethroughs (a la GFM, `~~deleted~~`).
+//! strikethroughs (a la GFM, `~~deleted~~`), bare url autolinks, and heading ids.
 
 mod harness;
 
@@ -178,3 +178,370 @@ mod strikethrough {
         "flanked punctuation single~!~"
     );
 }
+
+mod bare_urls {
+    use intl_markdown::{
+        compile_to_format_js, find_bare_urls, parse_intl_message_with_options, CompileArena,
+        ParserOptions,
+    };
+
+    fn linkified(input: &str) -> String {
+        let options = ParserOptions::default().with_autolink_bare_urls(true);
+        let document = parse_intl_message_with_options(input, false, options);
+        keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new())).unwrap()
+    }
+
+    fn urls(input: &str) -> Vec<&str> {
+        find_bare_urls(input).map(|range| &input[range]).collect()
+    }
+
+    #[test]
+    fn schemes_and_www() {
+        assert_eq!(
+            linkified("Visit https://discord.com/app or www.discord.com today"),
+            r#"["Visit ",[8,"$link",["https://discord.com/app"],["https://discord.com/app"]]," or ",[8,"$link",["www.discord.com"],["http://www.discord.com"]]," today"]"#
+        );
+    }
+
+    #[test]
+    fn trailing_punctuation() {
+        assert_eq!(
+            urls("See www.discord.com. (Or https://en.wikipedia.org/wiki/Foo_(bar)), ok?"),
+            ["www.discord.com", "https://en.wikipedia.org/wiki/Foo_(bar)"]
+        );
+    }
+
+    #[test]
+    fn invalid_domains() {
+        assert!(
+            urls("http://localhost and www.x_y.z_w and https:// and awww.discord.com").is_empty()
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let document = intl_markdown::parse_intl_message("https://discord.com", false);
+        assert_eq!(
+            keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new()))
+                .unwrap(),
+            r#"["https://discord.com"]"#
+        );
+    }
+
+    #[test]
+    fn not_nested_or_incomplete() {
+        assert_eq!(
+            linkified("[www.discord.com](./foo) https://discord.com/{path}"),
+            r#"[[8,"$link",["www.discord.com"],["./foo"]]," https://discord.com/",[1,"path"]]"#
+        );
+        assert_eq!(
+            linkified("**www.discord.com**"),
+            r#"[[8,"$b",[[8,"$link",["www.discord.com"],["http://www.discord.com"]]]]]"#
+        );
+    }
+}
+
+mod heading_ids {
+    use intl_markdown::{format_ast_with_options, HtmlFormatOptions};
+
+    use crate::harness::{parse_to_ast, run_icu_ast_test};
+
+    fn format_with_ids(input: &str) -> String {
+        let options = HtmlFormatOptions::default().with_heading_ids(true);
+        format_ast_with_options(&parse_to_ast(input, true), options).unwrap()
+    }
+
+    #[test]
+    fn slugified() {
+        assert_eq!(
+            format_with_ids("## Getting *Started*, again!"),
+            r#"<h2 id="getting-started-again">Getting <em>Started</em>, again!</h2>"#
+        );
+        assert_eq!(
+            format_with_ids("# Hello {username} -- `code`"),
+            r#"<h1 id="hello-username-code">Hello {username} -- <code>code</code></h1>"#
+        );
+        assert_eq!(format_with_ids("# !!!"), r#"<h1 id="heading">!!!</h1>"#);
+    }
+
+    #[test]
+    fn deduplicated() {
+        assert_eq!(
+            format_with_ids("# Setup\n\n# Setup 1\n\nSetup\n---\n\n# Setup"),
+            [
+                r#"<h1 id="setup">Setup</h1>"#,
+                r#"<h1 id="setup-1">Setup 1</h1>"#,
+                r#"<h2 id="setup-2">Setup</h2>"#,
+                r#"<h1 id="setup-3">Setup</h1>"#,
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn compiled_as_control() {
+        run_icu_ast_test(
+            "# Title\n\n## Title",
+            r#"[[8,"$h1",["Title"],["title"]],[8,"$h2",["Title"],["title-1"]]]"#,
+            true,
+        );
+    }
+}
+
+mod image_alt_text {
+    use crate::harness::run_spec_test;
+
+    #[test]
+    fn icu_variable() {
+        run_spec_test(
+            "![hi {name}](x.png)",
+            "<p><img src=\"x.png\" alt=\"hi {name}\" /></p>",
+        );
+    }
+
+    #[test]
+    fn icu_plural_keeps_plain_arms() {
+        run_spec_test(
+            "![{count, plural, one {**#** item} other {# items}}](x.png)",
+            "<p><img src=\"x.png\" alt=\"{count, plural, one {# item} other {# items}}\" /></p>",
+        );
+    }
+}
+
+mod emoji_shortcodes {
+    use intl_markdown::{
+        compile_to_format_js, find_emoji_shortcodes, format_ast, parse_intl_message,
+        parse_intl_message_with_options, CompileArena, ParserOptions,
+    };
+
+    fn compiled(input: &str) -> String {
+        let options = ParserOptions::default().with_emoji_shortcodes(true);
+        let document = parse_intl_message_with_options(input, false, options);
+        keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new())).unwrap()
+    }
+
+    fn shortcodes(input: &str) -> Vec<&str> {
+        find_emoji_shortcodes(input)
+            .map(|range| &input[range])
+            .collect()
+    }
+
+    #[test]
+    fn compiled_as_tag() {
+        assert_eq!(
+            compiled("Nice :thumbs_up: :+1:!"),
+            r#"["Nice ",[8,"$emoji",[":thumbs_up:"],["thumbs_up"]]," ",[8,"$emoji",[":+1:"],["+1"]],"!"]"#
+        );
+        assert_eq!(
+            compiled("**:smile:**"),
+            r#"[[8,"$b",[[8,"$emoji",[":smile:"],["smile"]]]]]"#
+        );
+    }
+
+    #[test]
+    fn html() {
+        let options = ParserOptions::default().with_emoji_shortcodes(true);
+        let document = parse_intl_message_with_options("hi :wave:", true, options);
+        assert_eq!(
+            format_ast(&document).unwrap(),
+            "<p>hi <span data-emoji=\"wave\">:wave:</span></p>"
+        );
+    }
+
+    #[test]
+    fn boundaries() {
+        assert_eq!(compiled("at 10:30:45"), r#"["at 10:30:45"]"#);
+        assert_eq!(compiled(": smile: and :"), r#"[": smile: and :"]"#);
+        assert_eq!(shortcodes("at 10:30:45"), Vec::<&str>::new());
+        assert_eq!(shortcodes(":a::b: (:c:)"), [":a:", ":b:", ":c:"]);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let document = parse_intl_message(":smile:", false);
+        assert_eq!(
+            keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new()))
+                .unwrap(),
+            r#"[":smile:"]"#
+        );
+    }
+}
+
+mod mentions {
+    use intl_markdown::{
+        compile_to_format_js, decompile_format_js, format_icu_string, parse_intl_message,
+        parse_intl_message_with_options, CompileArena, ParserOptions,
+    };
+
+    fn options() -> ParserOptions {
+        ParserOptions::default().with_mention_sigils(vec!["@".into(), "@&".into(), "#".into()])
+    }
+
+    fn compiled(input: &str) -> String {
+        let document = parse_intl_message_with_options(input, false, options());
+        keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new())).unwrap()
+    }
+
+    #[test]
+    fn compiled_as_tag() {
+        assert_eq!(
+            compiled("Hi <@{userId}> in <#{channelId}>"),
+            r##"["Hi ",[8,"$mention",[[1,"userId"]],["@"]]," in ",[8,"$mention",[[1,"channelId"]],["#"]]]"##
+        );
+    }
+
+    #[test]
+    fn longest_sigil() {
+        assert_eq!(
+            compiled("<@&{roleId}>"),
+            r#"[[8,"$mention",[[1,"roleId"]],["@&"]]]"#
+        );
+    }
+
+    #[test]
+    fn only_simple_variables() {
+        assert_eq!(
+            compiled("<@{count, number}> <!{userId}> <@ {userId}>"),
+            r#"["<","@",[2,"count"],"> ","<","!",[1,"userId"],"> ","<","@ ",[1,"userId"],">"]"#
+        );
+    }
+
+    #[test]
+    fn icu_string() {
+        let document = parse_intl_message_with_options("<@&{roleId}>", false, options());
+        assert_eq!(
+            format_icu_string(&document).unwrap(),
+            "<mention>@&amp;{roleId}</mention>"
+        );
+    }
+
+    #[test]
+    fn decompiled() {
+        let compiled = compiled("Hi <@{userId}>!");
+        let value: serde_json::Value = serde_json::from_str(&compiled).unwrap();
+        assert_eq!(decompile_format_js(&value).unwrap(), "Hi <@{userId}>!");
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let document = parse_intl_message("<@{userId}>", false);
+        assert_eq!(
+            keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new()))
+                .unwrap(),
+            r#"["<","@",[1,"userId"],">"]"#
+        );
+    }
+}
+
+mod keyboard_keys {
+    use intl_markdown::{
+        compile_to_format_js, find_keyboard_keys, format_ast, parse_intl_message,
+        parse_intl_message_with_options, CompileArena, ParserOptions,
+    };
+
+    fn compiled(input: &str) -> String {
+        let options = ParserOptions::default().with_keyboard_keys(true);
+        let document = parse_intl_message_with_options(input, false, options);
+        keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new())).unwrap()
+    }
+
+    fn keys(input: &str) -> Vec<&str> {
+        find_keyboard_keys(input)
+            .map(|range| &input[range])
+            .collect()
+    }
+
+    #[test]
+    fn compiled_as_tag() {
+        assert_eq!(
+            compiled("Press [[Ctrl]]+[[Page Up]]"),
+            r#"["Press ",[8,"$kbd",["Ctrl"]],"+",[8,"$kbd",["Page Up"]]]"#
+        );
+    }
+
+    #[test]
+    fn html() {
+        let options = ParserOptions::default().with_keyboard_keys(true);
+        let document = parse_intl_message_with_options("Press [[Esc]]", true, options);
+        assert_eq!(
+            format_ast(&document).unwrap(),
+            "<p>Press <kbd>Esc</kbd></p>"
+        );
+    }
+
+    #[test]
+    fn invalid_names() {
+        assert_eq!(compiled("[[ Ctrl]] [[]]"), r#"["[[ Ctrl]] [[]]"]"#);
+        assert_eq!(
+            compiled("[[[Ctrl]]](./keys)"),
+            r#"[[8,"$link",[[8,"$kbd",["Ctrl"]]],["./keys"]]]"#
+        );
+        assert_eq!(keys("[[ Ctrl]] [[]] [[[Alt]] [[{key}]]"), ["Alt"]);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let document = parse_intl_message("[[Ctrl]]", false);
+        assert_eq!(
+            keyless_json::to_string(&compile_to_format_js(&document, &CompileArena::new()))
+                .unwrap(),
+            r#"["[[Ctrl]]"]"#
+        );
+    }
+}
+
+mod message_references {
+    use std::collections::HashMap;
+
+    use intl_markdown::{
+        document_inline_content, format_icu_string, message_references, parse_intl_message,
+        resolve_message_references,
+    };
+
+    fn references(input: &str) -> Vec<String> {
+        message_references(&parse_intl_message(input, false))
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn parsed_as_references() {
+        assert_eq!(
+            references("Welcome to {@PRODUCT_NAME}, **{@PRODUCT_NAME}** by {@COMPANY}"),
+            ["PRODUCT_NAME", "PRODUCT_NAME", "COMPANY"]
+        );
+        assert_eq!(
+            references("{count, plural, one {{@ONE_ITEM}} other {{@MANY_ITEMS}}}"),
+            ["ONE_ITEM", "MANY_ITEMS"]
+        );
+    }
+
+    #[test]
+    fn invalid_references() {
+        assert!(references("{ @PRODUCT_NAME} {@ PRODUCT_NAME} {@PRODUCT NAME} {@}").is_empty());
+        assert!(references("`{@PRODUCT_NAME}`").is_empty());
+    }
+
+    #[test]
+    fn resolved_with_inline_content() {
+        let resolved = HashMap::from([(
+            "PRODUCT_NAME".to_string(),
+            document_inline_content(parse_intl_message("*Discord* {version}", true)).unwrap(),
+        )]);
+        let document = resolve_message_references(
+            parse_intl_message("Welcome to {@PRODUCT_NAME}! {@UNKNOWN}", false),
+            &resolved,
+        );
+        assert_eq!(
+            format_icu_string(&document).unwrap(),
+            "Welcome to <i>Discord</i> {version}! {@UNKNOWN}"
+        );
+    }
+
+    #[test]
+    fn block_content_is_not_inline() {
+        assert!(document_inline_content(parse_intl_message("# Title\n\ntext", true)).is_none());
+        assert!(document_inline_content(parse_intl_message("one\n\ntwo", true)).is_none());
+    }
+}
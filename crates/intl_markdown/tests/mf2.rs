@@ -0,0 +1,111 @@
+mod harness;
+
+mod mf2_patterns {
+    use crate::harness::mf2_string_test;
+    mf2_string_test!(plain_text, "hello world", "hello world");
+    mf2_string_test!(escaped_braces, "a '{b}' c\\\\d", "a '\\{b\\}' c\\\\d");
+    mf2_string_test!(escaped_closing_brace, "x '}' y", "x '\\}' y");
+    mf2_string_test!(doubled_apostrophe, "it''s", "it''s");
+    mf2_string_test!(code_apostrophes, "`'{a}'`", "{#code}'\\{a\\}'{/code}");
+    mf2_string_test!(leading_period, ".hidden", "{{.hidden}}");
+    mf2_string_test!(variable, "hi {username}", "hi {$username}");
+    mf2_string_test!(
+        markdown,
+        "**a** *b* ~~c~~",
+        "{#b}a{/b} {#i}b{/i} {#del}c{/del}"
+    );
+    mf2_string_test!(code_span, "`{a}`", "{#code}\\{a\\}{/code}");
+    mf2_string_test!(hook, "$[click me](onClick)", "{#onClick}click me{/onClick}");
+    mf2_string_test!(
+        static_link,
+        "[a link](https://example.com)",
+        "{#link href=|https://example.com|}a link{/link}"
+    );
+    mf2_string_test!(
+        dynamic_link,
+        "[a link]({url})",
+        "{#link href=$url}a link{/link}"
+    );
+}
+
+mod mf2_functions {
+    use crate::harness::mf2_string_test;
+    mf2_string_test!(number, "{a, number}", "{$a :number}");
+    mf2_string_test!(integer, "{a, number, integer}", "{$a :integer}");
+    mf2_string_test!(percent, "{a, number, percent}", "{$a :percent}");
+    mf2_string_test!(
+        currency,
+        "{a, number, ::currency/USD}",
+        "{$a :currency currency=USD}"
+    );
+    mf2_string_test!(date, "{a, date, short}", "{$a :date style=short}");
+    mf2_string_test!(date_skeleton, "{a, date, ::yyyyMMdd}", "{$a :date}");
+    mf2_string_test!(time, "{a, time}", "{$a :time}");
+}
+
+mod mf2_selectors {
+    use crate::harness::mf2_string_test;
+    mf2_string_test!(
+        plural,
+        "You have {count, plural, =0 {no items} one {# item} other {# items}}",
+        ".input {$count :number}\n.match $count\n0 {{You have no items}}\none {{You have {$count} item}}\n* {{You have {$count} items}}"
+    );
+    mf2_string_test!(
+        select_ordinal,
+        "{n, selectordinal, one {#st} other {#th}}",
+        ".input {$n :number select=ordinal}\n.match $n\none {{{$n}st}}\n* {{{$n}th}}"
+    );
+    mf2_string_test!(
+        select_inside_markup,
+        "**{gender, select, female {her} other {their}}** turn",
+        ".input {$gender :string}\n.match $gender\nfemale {{{#b}her{/b} turn}}\n* {{{#b}their{/b} turn}}"
+    );
+    mf2_string_test!(
+        multiple_selectors,
+        "{a, select, x {X} other {O}}{b, plural, one {1} other {N}}",
+        ".input {$a :string}\n.input {$b :number}\n.match $a $b\nx one {{X1}}\nx * {{XN}}\n* one {{O1}}\n* * {{ON}}"
+    );
+    mf2_string_test!(
+        nested_selectors,
+        "{a, select, x {{b, plural, one {1} other {N}}} other {O}}",
+        ".input {$a :string}\n.input {$b :number}\n.match $a $b\nx one {{1}}\nx * {{N}}\n* one {{O}}\n* * {{O}}"
+    );
+    mf2_string_test!(
+        quoted_key,
+        "{a, select, has_space {yes} other {no}}",
+        ".input {$a :string}\n.match $a\nhas_space {{yes}}\n* {{no}}"
+    );
+}
+
+/// MF2 bundles have to render the same text as the JSON bundles, which keep ICU apostrophes
+/// exactly as they are written.
+#[test]
+fn text_matches_json_bundles() {
+    use intl_markdown::{
+        compile_to_format_js, format_mf2_string, parse_intl_message, CompileArena,
+    };
+
+    for input in [
+        "it''s",
+        "a '{b}' c",
+        "'{b}' it's",
+        "x '}' y",
+        "''{a}",
+        "don't",
+    ] {
+        let document = parse_intl_message(input, false);
+        let arena = CompileArena::new();
+        let json = keyless_json::to_string(&compile_to_format_js(&document, &arena)).unwrap();
+        let mf2 = format_mf2_string(&document).unwrap();
+        let mut rendered = String::new();
+        let mut chars = mf2.chars();
+        while let Some(c) = chars.next() {
+            rendered.push(if c == '\\' { chars.next().unwrap() } else { c });
+        }
+        assert_eq!(
+            json,
+            keyless_json::to_string(&[rendered]).unwrap(),
+            "{input}"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use intl_markdown::{compile_to_format_js, parse_intl_message, CompileArena, ReactIntlAst};
+
+fn compile(content: &str) -> String {
+    let document = parse_intl_message(content, false);
+    let arena = CompileArena::new();
+    serde_json::to_string(&ReactIntlAst(&compile_to_format_js(&document, &arena))).unwrap()
+}
+
+#[test]
+fn literal() {
+    assert_eq!(
+        r#"[{"type":0,"value":"plain text"}]"#,
+        compile("plain text")
+    );
+}
+
+#[test]
+fn arguments() {
+    assert_eq!(
+        r#"[{"type":0,"value":"Hi "},{"type":1,"value":"name"},{"type":0,"value":", "},{"type":2,"value":"count","style":null},{"type":3,"value":"today","style":"short"}]"#,
+        compile("Hi {name}, {count, number}{today, date, short}")
+    );
+}
+
+#[test]
+fn plural() {
+    assert_eq!(
+        r#"[{"type":6,"value":"count","options":{"one":{"value":[{"type":7},{"type":0,"value":" item"}]},"other":{"value":[{"type":7},{"type":0,"value":" items"}]}},"offset":0,"pluralType":"cardinal"}]"#,
+        compile("{count, plural, one {# item} other {# items}}")
+    );
+}
+
+#[test]
+fn tags() {
+    assert_eq!(
+        r#"[{"type":8,"value":"$b","children":[{"type":0,"value":"bold"}]},{"type":0,"value":" "},{"type":8,"value":"$link","children":[{"type":0,"value":"./page"},{"type":1,"value":"_"},{"type":0,"value":"here"}]}]"#,
+        compile("**bold** [here](./page)")
+    );
+}
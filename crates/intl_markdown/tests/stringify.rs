@@ -0,0 +1,21 @@
+use test_case::test_case;
+
+use intl_markdown::{compile_to_format_js, parse_intl_message, to_icu_string, CompileArena};
+
+fn stringify(content: &str) -> String {
+    let document = parse_intl_message(content, false);
+    to_icu_string(&compile_to_format_js(&document, &CompileArena::new())).unwrap()
+}
+
+#[test_case("plain text", "plain text"; "literal")]
+#[test_case("Hello, {username}!", "Hello, {username}!"; "argument")]
+#[test_case("{count, number, percent} at {today, date}", "{count, number, percent} at {today, date}"; "formatted")]
+#[test_case("{count, plural, one {# item} other {# items}}", "{count, plural, one {# item} other {# items}}"; "plural")]
+#[test_case("{count, selectordinal, one {#st} other {#th}}", "{count, selectordinal, one {#st} other {#th}}"; "selectordinal")]
+#[test_case("{color, select, blue {**bold** #1} other {plain}}", "{color, select, blue {<$b>bold</$b> #1} other {plain}}"; "select")]
+#[test_case("**bold** and *italic*", "<$b>bold</$b> and <$i>italic</$i>"; "style_tags")]
+#[test_case("[a *link*](./somewhere) and [dynamic]({target})", "<$link>./somewhere{_}a <$i>link</$i></$link> and <$link>{target}dynamic</$link>"; "links")]
+#[test_case("keep '{literal}' braces, don't <break>", "keep '''{'literal'}''' braces, don''t '<'break'>'"; "escaped_syntax")]
+fn to_icu(input: &str, expected: &str) {
+    assert_eq!(expected, stringify(input));
+}
@@ -1,4 +1,4 @@
-use intl_markdown::{compile_to_format_js, parse_intl_message};
+use intl_markdown::{compile_to_format_js, parse_intl_message, CompileArena};
 
 #[test]
 #[ignore]
@@ -6,7 +6,8 @@ fn test_ast() {
     let ast = parse_intl_message("{color, select, orange {the # fluffy}}", false);
     println!("{:#?}", ast);
 
-    let compiled = compile_to_format_js(&ast);
+    let arena = CompileArena::new();
+    let compiled = compile_to_format_js(&ast, &arena);
     println!("{:#?}", compiled);
 
     let serialized = serde_json::to_string(&compiled);
@@ -67,24 +67,18 @@ fn derive_read_for_enum(name: &Ident, _: Ident, data: DataEnum) -> TokenStream {
 
     let expanded = quote_spanned! { proc_macro2::Span::call_site() =>
         impl ReadFromEventBuf for #name {
-            fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Self {
-                let start = buf.peek();
-                let Some(Event::Start(start_kind)) = start else {
-                    unreachable!("Encountered an event other than Start when reading a node");
+            fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> crate::tree_builder::SyntaxResult<Self> {
+                // Anything other than the Start of one of the variants means the node is missing
+                // or out of place, like after the parser recovered from an error.
+                let start_kind = match buf.peek() {
+                    Some(Event::Start(start_kind)) => *start_kind,
+                    _ => return Err(buf.unexpected_event_diagnostic()),
                 };
 
-                let node = match start_kind {
-                    SyntaxKind::TOMBSTONE => unreachable!(
-                        "Tried to parse a real event, but encountered a tombstone (an abandoned event)"
-                    ),
-                    #(SyntaxKind::#syntax_names => #name::#variant_idents(#variant_idents::read_from(buf)),)*
-                    kind => unreachable!(
-                        "Expected parsed buffer to have a valid node kind, but got {:?}",
-                        kind
-                    ),
-                };
-
-                node
+                match start_kind {
+                    #(SyntaxKind::#syntax_names => #variant_idents::read_from(buf).map(#name::#variant_idents),)*
+                    _ => Err(buf.unexpected_event_diagnostic()),
+                }
             }
 
             fn matches_kind(kind: SyntaxKind) -> bool {
@@ -116,7 +110,7 @@ fn derive_read_for_struct(name: &Ident, syntax_kind: Ident, data: DataStruct) ->
             _ => panic!("ReadFromEvents only supports Path types"),
         };
         let reader = quote_spanned! { kind.span() =>
-            let #field_name = #kind::read_from(buf);
+            let #field_name = #kind::read_from(buf)?;
         };
 
         let assigner = quote_spanned! { field_name.span() => #field_name };
@@ -151,14 +145,14 @@ fn derive_read_for_struct(name: &Ident, syntax_kind: Ident, data: DataStruct) ->
         impl crate::tree_builder::ReadFromEventBuf for #name {
             const KIND: SyntaxKind = SyntaxKind::#syntax_kind;
 
-            fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> Self {
-                buf.next_as_start();
+            fn read_from<I: Iterator<Item = Event>>(buf: &mut EventBuffer<I>) -> crate::tree_builder::SyntaxResult<Self> {
+                buf.next_as_start()?;
                 #(#readers)*
-                buf.next_as_finish(Self::KIND);
+                buf.next_as_finish(Self::KIND)?;
 
-                Self {
+                Ok(Self {
                     #(#assigners),*
-                }
+                })
             }
         }
 
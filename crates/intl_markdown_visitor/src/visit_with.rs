@@ -1,7 +1,8 @@
 use intl_markdown::{
-    BlockNode, CodeBlock, CodeSpan, Document, Emphasis, Heading, Hook, Icu, IcuDate,
+    BlockNode, CodeBlock, CodeSpan, Document, Emoji, Emphasis, Heading, Hook, Icu, IcuDate,
     IcuDateTimeStyle, IcuNumber, IcuNumberStyle, IcuPlural, IcuPluralArm, IcuSelect, IcuTime,
-    IcuVariable, InlineContent, Link, LinkDestination, Paragraph, Strikethrough, Strong,
+    IcuVariable, InlineContent, KeyboardKey, Link, LinkDestination, Mention, MessageReference,
+    Paragraph, Strikethrough, Strong,
 };
 
 use crate::visitor::Visit;
@@ -30,6 +31,7 @@ impl<V: ?Sized + Visit> VisitWith<V> for BlockNode {
             BlockNode::CodeBlock(code_block) => code_block.visit_with(visitor),
             BlockNode::ThematicBreak => visitor.visit_thematic_break(),
             BlockNode::InlineContent(inline_content) => visit_list(&inline_content, visitor),
+            BlockNode::Error(text) => visitor.visit_text(text),
         }
     }
 }
@@ -60,6 +62,15 @@ impl<V: ?Sized + Visit> VisitWith<V> for Document {
         visit_list(&self.blocks(), visitor);
     }
 }
+impl<V: ?Sized + Visit> VisitWith<V> for Emoji {
+    fn visit_with(&self, visitor: &mut V) {
+        visitor.visit_emoji(self);
+    }
+
+    fn visit_children_with(&self, _visitor: &mut V) {
+        // No children
+    }
+}
 impl<V: ?Sized + Visit> VisitWith<V> for Emphasis {
     fn visit_with(&self, visitor: &mut V) {
         visitor.visit_emphasis(self);
@@ -210,11 +221,24 @@ impl<V: ?Sized + Visit> VisitWith<V> for InlineContent {
             InlineContent::HardLineBreak => visitor.visit_hard_line_break(),
             InlineContent::Hook(hook) => hook.visit_with(visitor),
             InlineContent::Strikethrough(strikethrough) => strikethrough.visit_with(visitor),
+            InlineContent::Emoji(emoji) => emoji.visit_with(visitor),
+            InlineContent::Mention(mention) => mention.visit_with(visitor),
+            InlineContent::KeyboardKey(key) => key.visit_with(visitor),
+            InlineContent::MessageReference(reference) => reference.visit_with(visitor),
             InlineContent::Icu(icu) => icu.visit_with(visitor),
             InlineContent::IcuPound => visitor.visit_icu_pound(),
         }
     }
 }
+impl<V: ?Sized + Visit> VisitWith<V> for KeyboardKey {
+    fn visit_with(&self, visitor: &mut V) {
+        visitor.visit_keyboard_key(self);
+    }
+
+    fn visit_children_with(&self, _visitor: &mut V) {
+        // No children
+    }
+}
 impl<V: ?Sized + Visit> VisitWith<V> for Link {
     fn visit_with(&self, visitor: &mut V) {
         visitor.visit_link(self);
@@ -226,6 +250,24 @@ impl<V: ?Sized + Visit> VisitWith<V> for Link {
         visitor.visit_link_destination(self.destination());
     }
 }
+impl<V: ?Sized + Visit> VisitWith<V> for Mention {
+    fn visit_with(&self, visitor: &mut V) {
+        visitor.visit_mention(self);
+    }
+
+    fn visit_children_with(&self, visitor: &mut V) {
+        self.variable().visit_with(visitor);
+    }
+}
+impl<V: ?Sized + Visit> VisitWith<V> for MessageReference {
+    fn visit_with(&self, visitor: &mut V) {
+        visitor.visit_message_reference(self);
+    }
+
+    fn visit_children_with(&self, _visitor: &mut V) {
+        // No children
+    }
+}
 impl<V: ?Sized + Visit> VisitWith<V> for Paragraph {
     fn visit_with(&self, visitor: &mut V) {
         visitor.visit_paragraph(self);
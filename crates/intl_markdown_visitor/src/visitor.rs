@@ -1,7 +1,8 @@
 use intl_markdown::{
-    BlockNode, CodeBlock, CodeSpan, Document, Emphasis, Heading, Hook, Icu, IcuDate,
+    BlockNode, CodeBlock, CodeSpan, Document, Emoji, Emphasis, Heading, Hook, Icu, IcuDate,
     IcuDateTimeStyle, IcuNumber, IcuNumberStyle, IcuPlural, IcuPluralArm, IcuSelect, IcuTime,
-    IcuVariable, InlineContent, Link, LinkDestination, Paragraph, Strikethrough, Strong,
+    IcuVariable, InlineContent, KeyboardKey, Link, LinkDestination, Mention, MessageReference,
+    Paragraph, Strikethrough, Strong,
 };
 
 use crate::visit_with::VisitWith;
@@ -19,6 +20,9 @@ pub trait Visit {
     fn visit_document(&mut self, node: &Document) {
         node.visit_children_with(self);
     }
+    fn visit_emoji(&mut self, node: &Emoji) {
+        node.visit_children_with(self);
+    }
     fn visit_emphasis(&mut self, node: &Emphasis) {
         node.visit_children_with(self);
     }
@@ -61,12 +65,21 @@ pub trait Visit {
     fn visit_inline_content(&mut self, node: &InlineContent) {
         node.visit_children_with(self);
     }
+    fn visit_keyboard_key(&mut self, node: &KeyboardKey) {
+        node.visit_children_with(self);
+    }
     fn visit_link(&mut self, node: &Link) {
         node.visit_children_with(self);
     }
     fn visit_link_destination(&mut self, node: &LinkDestination) {
         node.visit_children_with(self);
     }
+    fn visit_mention(&mut self, node: &Mention) {
+        node.visit_children_with(self);
+    }
+    fn visit_message_reference(&mut self, node: &MessageReference) {
+        node.visit_children_with(self);
+    }
     fn visit_paragraph(&mut self, node: &Paragraph) {
         node.visit_children_with(self);
     }
@@ -1,8 +1,48 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
-export declare class IntlMessagesDatabase {
+/**
+ * A token for cancelling async tasks, like `validateMessagesAsync`. The same token can be given
+ * to any number of tasks, and cancelling it stops all of them.
+ */
+export declare class IntlCancellationToken {
   constructor()
-  findAllMessagesFiles(directories: Array<string>, defaultDefinitionLocale: string): Array<IntlMessagesFileDescriptor>
+  /**
+   * Stop every task using this token as soon as possible. Tasks that are stopped reject with a
+   * `Cancelled` error. Cancelling can't be undone, so a new token is needed for later tasks.
+   */
+  cancel(): void
+  get isCancelled(): boolean
+}
+
+export declare class IntlMessagesDatabase {
+  constructor(options?: IntlMessagesDatabaseOptions | undefined | null)
+  /**
+   * Open a database that another thread shared with `share`, like in a worker thread. Both
+   * instances use the same underlying database, so changes made through either one are seen by
+   * the other.
+   */
+  static fromSharedToken(token: number): IntlMessagesDatabase
+  /**
+   * Share this database with the other threads in the process, returning a token that
+   * `IntlMessagesDatabase.fromSharedToken` opens it with. The token can be sent to workers with
+   * `postMessage` or `workerData`. Sharing the same database again returns the same token.
+   *
+   * The token doesn't keep the database alive, so it can only be opened until every instance
+   * using the database has been garbage collected. Changes wait for every call already in
+   * progress on any thread to finish, so a change made while a worker is validating blocks until
+   * the validation is done.
+   */
+  share(): number
+  /**
+   * Stop sharing this database, so its token can no longer be opened. Instances that were
+   * already opened from the token keep working. Returns `false` if the database wasn't shared.
+   */
+  unshare(): boolean
+  /**
+   * Scan the given `directories` for every messages file within them. `options` controls which
+   * files and directories are visited, like excluding build output directories with globs.
+   */
+  findAllMessagesFiles(directories: Array<string>, defaultDefinitionLocale: string, options?: IntlFileDiscoveryOptions | undefined | null): Array<IntlMessagesFileDescriptor>
   filterAllMessagesFiles(files: Array<string>, defaultDefinitionLocale: string): Array<IntlMessagesFileDescriptor>
   processAllMessagesFiles(directories: Array<IntlMessagesFileDescriptor>): IntlMultiProcessingResult
   processDefinitionsFile(filePath: string, locale?: string | undefined | null): string
@@ -10,7 +50,62 @@ export declare class IntlMessagesDatabase {
   processAllTranslationFiles(localeMap: Record<string, string>): IntlMultiProcessingResult
   processTranslationFile(filePath: string, locale: string): string
   processTranslationFileContent(filePath: string, locale: string, content: string): string
+  /**
+   * Recover translations for `locale` from a compiled `.compiled.messages.*` bundle, marking
+   * them as machine-recovered. Only messages that are already defined can be recovered, since
+   * bundles only contain hashed keys.
+   */
+  processCompiledBundleFile(filePath: string, locale: string): string
+  processCompiledBundleFileContent(filePath: string, locale: string, content: string): string
+  /**
+   * Register a package in a monorepo as a project, so that queries, exports, and validation
+   * can be limited to the messages it defines. Registering a project with the same name again
+   * replaces it.
+   */
+  registerProject(project: IntlProject): void
+  /** Return every registered project, sorted by name. */
+  getProjects(): Array<IntlProject>
+  /** Return the project that `filePath` belongs to, or null if it isn't inside of any of them. */
+  getProjectForFile(filePath: string): IntlProject | null
+  /**
+   * Set the names of the hooks and link handlers that the app provides, which validation
+   * checks every hook and link handler against. Names of either kind aren't checked when they
+   * aren't given.
+   */
+  setHandlerNames(hookNames?: Array<string> | undefined | null, linkHandlerNames?: Array<string> | undefined | null): void
+  /**
+   * Set the extensions of definitions files in addition to the built-in `.messages.js`
+   * variants, replacing the `definitionsExtensions` the database was created with, for
+   * databases that are created before their configuration is known.
+   */
+  setDefinitionsExtensions(extensions: Array<string>): void
+  /**
+   * Return the extensions of definitions files in addition to the built-in `.messages.js`
+   * variants, to pass along to `isMessageDefinitionsFile` and build plugins.
+   */
+  getDefinitionsExtensions(): Array<string>
+  /**
+   * Change how messages are parsed, applying every option that's given on top of the current
+   * ones. Messages that were already processed are parsed again with the new options the next
+   * time they're needed, and cached validation results are dropped.
+   */
+  setParserOptions(options: IntlParserOptions): void
+  /**
+   * Return the locale that definitions are written in, unless their source file sets its own
+   * `sourceLocale` in its meta.
+   */
+  getSourceLocale(): string
   getKnownLocales(): Array<string>
+  /**
+   * Return the known locale that `tag` refers to, matching any spelling of it, like `pt_br`
+   * for `pt-BR`, or null if the database has no values in that locale.
+   */
+  resolveLocale(tag: string): string | null
+  /**
+   * Report every translation file whose locale tag is not well-formed, or that spells its
+   * locale differently from other files, like `en_GB` and `en-GB`.
+   */
+  validateLocaleTags(): Array<IntlLocaleTagDiagnostic>
   getSourceFile(filePath: string): IntlSourceFile
   getAllSourceFilePaths(): Array<string>
   /**
@@ -19,19 +114,392 @@ export declare class IntlMessagesDatabase {
    */
   getSourceFileKeyMap(filePath: string): Record<string, string>
   getMessage(key: string): IntlMessage
-  generateTypes(sourceFilePath: string, outputFilePath: string): void
+  /**
+   * Return the versions of the definition of the message `key` that have been processed, oldest
+   * first, and which version each of its translations was written for.
+   */
+  getMessageHistory(key: string): IntlMessageHistory
+  /**
+   * Return every variable used in the source message of `key`, with the ways it is written in
+   * that message, for editors to autocomplete while writing translations.
+   */
+  getVariableCompletions(key: string): Array<IntlVariableCompletion>
+  /**
+   * Return the content hash of every defined message, keyed by the message key. A hash only
+   * changes when the source value or meta of its message changes.
+   */
+  getContentHashes(): Record<string, string>
+  /**
+   * Return the short alias of every defined message, mapped to its message key, for looking up
+   * messages in bundles compiled with `IntlBundleKeyMode.Alias`.
+   */
+  getMessageKeyAliases(): Record<string, string>
+  /**
+   * Return every defined message that has no translation in `locale`, sorted by key, leaving
+   * out messages that are marked as `translate: false`.
+   */
+  getUntranslatedMessages(locale: string): Array<IntlUntranslatedMessage>
+  /**
+   * Return the translation of the message `key` in `locale` as a standard ICU MessageFormat
+   * string, with markdown written as the tags it compiles to.
+   */
+  getMessageIcu(key: string, locale: string): string
+  findMessages(query?: IntlMessageQuery | undefined | null): IntlMessageQueryResult
+  generateTypes(sourceFilePath: string, outputFilePath: string, options?: IntlTypesGeneratorOptions | undefined | null): void
   precompile(filePath: string, locale: string, outputPath: string, options?: IntlMessageBundlerOptions | undefined | null): void
+  /**
+   * Precompile the given definitions file like `precompile`, on another thread so that the
+   * event loop isn't blocked. `onProgress` is called with the number of messages bundled so
+   * far. Nothing is written if the task is cancelled.
+   */
+  precompileAsync(filePath: string, locale: string, outputPath: string, options?: IntlMessageBundlerOptions | undefined | null, cancellation?: IntlCancellationToken | undefined | null, onProgress?: ((progress: IntlServiceProgress) => void) | undefined | null): Promise<void>
   precompileToBuffer(filePath: string, locale: string, options?: IntlMessageBundlerOptions | undefined | null): Buffer
-  validateMessages(): Array<IntlDiagnostic>
-  exportTranslations(fileExtension?: string | undefined | null): Array<string>
+  /**
+   * Compare two compiled bundles, like the bundles of a definitions file from two releases,
+   * reporting every message that was added, removed, or changed along with how its size
+   * changed. Each change is attributed to its message and definitions file in the database.
+   */
+  diffBundles(before: Buffer, after: Buffer): IntlBundleDiff
+  /**
+   * Precompile the given definitions file like `precompileToBuffer` and compare the result to
+   * `previous`, a bundle of the same file and locale compiled earlier with the same options.
+   */
+  diffPrecompiledBundle(filePath: string, locale: string, previous: Buffer, options?: IntlMessageBundlerOptions | undefined | null): IntlBundleDiff
+  /**
+   * Precompile the given definitions file like `precompileToBuffer`, also returning every file
+   * that the bundle depends on along with a hash of the content compiled from it, for use in
+   * cache keys. This includes the translations file for `locale` even when it doesn't exist yet.
+   */
+  precompileWithDependencies(filePath: string, locale: string, options?: IntlMessageBundlerOptions | undefined | null): IntlPrecompiledBundle
+  /**
+   * Return everything needed to build each definitions file in the database, sorted by path:
+   * its hashed message keys, the translations file for each locale, the path of the compiled
+   * artifact for each locale, and a hash of each locale's content. `artifactExtension` sets the
+   * extension of artifact names, defaulting to `json`.
+   */
+  getBuildManifest(artifactExtension?: string | undefined | null): Array<IntlBuildManifestEntry>
+  validateMessages(options?: IntlValidatorOptions | undefined | null): Array<IntlDiagnostic>
+  /**
+   * Return the words in the translation of the message `key` in `locale` that should be spell
+   * checked, leaving out placeholders, code, urls, and other text that isn't spelled like words.
+   */
+  getMessageWords(key: string, locale: string): Array<IntlMessageWord>
+  /**
+   * Return the concrete syntax tree of the translation of the message `key` in `locale`, as a
+   * flat list of every node and token in the order they are written, where each node is
+   * followed by everything inside of it. Unlike the parsed AST, this keeps all of the syntax of
+   * the message, like delimiters and the exact text of each token.
+   */
+  getMessageCst(key: string, locale: string): Array<IntlCstNode>
+  /**
+   * Call `visit` with each node and token of the syntax tree from `getMessageCst`, in the order
+   * they are written. Returning `false` from `visit` skips everything inside of that node.
+   */
+  walkMessageCst(key: string, locale: string, visit: (node: IntlCstNode) => boolean | void): void
+  /**
+   * Report every occurrence of the words in `misspellings`, which a spell checker found to be
+   * misspelled in `locale`, as a `NoMisspelledWords` diagnostic. When `project` is given, only
+   * the messages defined in that project are checked.
+   */
+  validateMisspelledWords(locale: string, misspellings: Array<IntlMisspelling>, project?: string | undefined | null): Array<IntlDiagnostic>
+  /**
+   * Remove every cached diagnostic, so that the next validation checks every message again.
+   * Validation results are cached for each translation that hasn't changed, and the cache is
+   * already cleared whenever different validator options are used.
+   */
+  clearValidationCache(): void
+  /**
+   * Validate every message like `validateMessages`, returning the diagnostics as a SARIF 2.1
+   * log for code scanning tools to show inline in pull requests. Files inside of `root`, usually
+   * the root of the repository, are written relative to it.
+   */
+  validateMessagesAsSarif(options?: IntlValidatorOptions | undefined | null, root?: string | undefined | null): string
+  /**
+   * Validate every message like `validateMessages`, on another thread so that the event loop
+   * isn't blocked. `onProgress` is called with the number of messages validated so far.
+   */
+  validateMessagesAsync(options?: IntlValidatorOptions | undefined | null, cancellation?: IntlCancellationToken | undefined | null, onProgress?: ((progress: IntlServiceProgress) => void) | undefined | null): Promise<Array<IntlDiagnostic>>
+  /**
+   * Add a validator named `name` that runs on every translation in `validateMessagesAsync`,
+   * replacing any validator that was already registered with the same name. `validate` is called
+   * with each translation and returns the problems it finds, which are reported as diagnostics
+   * with the validator's name.
+   *
+   * Validation waits for `validate` to run on the main thread, so registered validators don't
+   * run in the other validation methods, which block the main thread. `validate` must return
+   * its diagnostics synchronously, and must not change the database, which waits for validation
+   * to finish. Changing the registered validators clears the validation cache.
+   */
+  registerValidator(name: string, validate: (input: IntlCustomValidatorInput) => Array<IntlCustomDiagnostic> | undefined | null): void
+  /** Remove the validator that was registered with `name`. Returns `false` if there wasn't one. */
+  unregisterValidator(name: string): boolean
+  /**
+   * Validate every message and summarize the results for CI, with counts of diagnostics by
+   * severity and by rule. The summary fails when any diagnostic is at least as severe as
+   * `failOn`, which defaults to `Error`. Diagnostics recorded in the `baseline` option aren't
+   * counted.
+   */
+  summarizeValidation(options?: IntlValidatorOptions | undefined | null, failOn?: IntlDiagnosticSeverity | undefined | null): IntlValidationSummary
+  /**
+   * Record every diagnostic that validating the database currently reports in a baseline file
+   * at `baselinePath`, replacing any existing baseline. Validating with the `baseline` option
+   * afterward only reports new diagnostics, so that new rules can be turned on before every
+   * existing problem is fixed. Returns the number of entries written to the baseline.
+   */
+  writeValidationBaseline(baselinePath: string, options?: IntlValidatorOptions | undefined | null): number
+  /**
+   * Write every translation in the database to its translation file, skipping files whose
+   * content wouldn't change. With `dryRun`, no files are written, and the result only reports
+   * which files would change.
+   *
+   * For vendors that can't handle nested plurals and selects, messages with more than
+   * `expandCombinationsAbove` combinations of arms are written as one entry per combination,
+   * like `KEY[count=one,gender=female]`. These can't be imported back as translations.
+   *
+   * When `project` is given, only the translations of messages defined in that project are
+   * exported.
+   *
+   * `placeholderRenames` renames the placeholder variables of individual messages in the
+   * written translations, keyed by message key and then by their current names, like to match
+   * renames applied while bundling with `messagePlaceholderRenames`.
+   *
+   * Exports that redact secrets, filter by review state, expand combinations, or rename
+   * placeholders can't replace the real translation files, so they must be written into a
+   * separate `outputDirectory`, where each file keeps its path relative to the current working
+   * directory.
+   */
+  exportTranslations(fileExtension?: string | undefined | null, secretRedaction?: IntlSecretRedaction | undefined | null, reviewStates?: Array<IntlReviewState> | undefined | null, dryRun?: boolean | undefined | null, expandCombinationsAbove?: number | undefined | null, project?: string | undefined | null, placeholderRenames?: Record<string, Record<string, string>> | undefined | null, outputDirectory?: string | undefined | null): IntlExportResult
+  /**
+   * Set the review state of the translation of `key` in `locale`, like when a reviewer approves
+   * it. The new state is written to the translation files the next time they are exported.
+   */
+  setTranslationReviewState(key: string, locale: string, reviewState: IntlReviewState): void
+  /**
+   * Remove stray zero-width characters from the translations in `locale`, or every locale if not
+   * given, and normalize them to NFC. Neither changes how a translation is displayed. Returns
+   * every translation that was fixed, which are written to the translation files the next time
+   * they are exported.
+   */
+  fixTranslationUnicode(locale?: string | undefined | null): Array<IntlFixedTranslation>
+  /**
+   * Apply `edits` to the source content of the message `key` by rewriting its definition in its
+   * source file, then process the file again. The inserted text is escaped as needed for the
+   * string it's written into, and nothing else in the file changes. If anything fails, the file
+   * and the database are both left as they were. Returns the diagnostics for the rewritten
+   * message.
+   */
+  rewriteMessage(key: string, edits: Array<IntlMessageEdit>, options?: IntlValidatorOptions | undefined | null): Array<IntlDiagnostic>
+  /**
+   * Plan the replacement of every match of the regular expression `pattern` with `replacement`
+   * in the values of messages, both definitions and translations. `replacement` can use the
+   * capture groups of the pattern like `$1`. Nothing is changed until the preview is given to
+   * `applyReplacements`, so it can be reviewed first.
+   */
+  previewReplacements(pattern: string, replacement: string, options?: IntlReplacementOptions | undefined | null): IntlReplacementPreview
+  /**
+   * Write every file changed by a preview from `previewReplacements` and process them again,
+   * all at once. Throws if any of the files changed since the preview was made. If anything
+   * fails, every file and the database are left as they were.
+   */
+  applyReplacements(preview: IntlReplacementPreview): void
+  /**
+   * Begin a transaction, so that every change to the database until it's committed can be
+   * undone at once with `rollbackTransaction`. Transactions can be nested. Each one copies
+   * every message in the database, so wrap whole batches of changes rather than single ones.
+   */
+  beginTransaction(): void
+  /**
+   * Keep every change made since the innermost transaction began and end it. Throws a
+   * `NoTransaction` error if no transaction is in progress.
+   */
+  commitTransaction(): void
+  /**
+   * Undo every change made since the innermost transaction began and end it. Files that were
+   * written during the transaction aren't changed back. Throws a `NoTransaction` error if no
+   * transaction is in progress.
+   */
+  rollbackTransaction(): void
+  /** How many transactions are in progress, counting each nested one. */
+  getTransactionDepth(): number
   getSourceFileMessageValues(filePath: string): Record<string, IntlMessageValue | undefined>
+  /**
+   * Enable or disable recording how long each message takes to parse and compile, and how long
+   * each rule takes while validating. Messages are parsed as soon as they are processed while
+   * recording is enabled.
+   */
+  setPerformanceRecording(enabled: boolean): void
+  /**
+   * Return the slowest messages to parse or compile since performance recording was enabled,
+   * along with the total time spent on each source file and on each rule during the most recent
+   * validation. `limit` defaults to 20 messages.
+   */
+  getPerformanceReport(limit?: number | undefined | null): IntlPerformanceReport
 }
 
 export declare function hashMessageKey(key: string): string
 
+export declare const enum IntlApostropheMode {
+  Icu = 0,
+  Literal = 1
+}
+
+export declare const enum IntlBundleChangeKind {
+  Added = 0,
+  Removed = 1,
+  Changed = 2
+}
+
+export declare const enum IntlBundleKeyMode {
+  /** Key each message by the hash of its key. */
+  Hashed = 0,
+  /**
+   * Key each message by a short, sequential alias, which `@discord/intl` resolves back to the
+   * hashed key using the aliases from `getMessageKeyAliases`.
+   */
+  Alias = 1,
+  /** Key each message by its original key, for debugging development bundles. */
+  Original = 2,
+  /**
+   * Key each message by the hash of its key, with a `$keys` entry mapping each hash back to
+   * its original key.
+   */
+  HashedWithKeys = 3
+}
+
+/**
+ * How a locale separates CJK text from the placeholders written next to it, which are usually
+ * filled with Latin text or numbers.
+ */
+export declare const enum IntlCjkPlaceholderSpacing {
+  /** A space is written between CJK text and a placeholder, like `{name} さん`. */
+  Spaced = 0,
+  /** Placeholders are written directly against CJK text, like `{name}さん`. */
+  Unspaced = 1
+}
+
 export declare const enum IntlCompiledMessageFormat {
   Json = 0,
-  KeylessJson = 1
+  KeylessJson = 1,
+  Mf2 = 2,
+  ReactIntl = 3
+}
+
+export interface IntlBuildManifestEntry {
+  filePath: string
+  sourceLocale: string
+  /** Map of hashed message keys to the original key. */
+  messageKeys: Record<string, string>
+  /** The translations file providing each locale. Empty when the file sets `translate: false`. */
+  translations: Record<string, string>
+  /** The path of the compiled artifact for each locale, including the source locale. */
+  artifacts: Record<string, string>
+  /**
+   * Hash of the content of every message in the file for each locale in `artifacts`, including
+   * the locale's translations, which only changes when that artifact would.
+   */
+  contentHashes: Record<string, string>
+}
+
+export interface IntlBundleDependency {
+  filePath: string
+  /**
+   * Hash of the content that the bundle was compiled from, or null for a translations file that
+   * doesn't exist yet.
+   */
+  contentHash?: string
+}
+
+/** The differences between two versions of a compiled bundle. */
+export interface IntlBundleDiff {
+  /** Every entry that was added, removed, or changed, with the largest changes in size first. */
+  changes: Array<IntlBundleEntryChange>
+  /** How many entries are exactly the same in both bundles. */
+  unchanged: number
+  /** Total size of the old bundle in bytes. */
+  beforeBytes: number
+  /** Total size of the new bundle in bytes. */
+  afterBytes: number
+  /**
+   * How many bytes the new bundle is larger than the old one, which is negative when it got
+   * smaller.
+   */
+  byteDelta: number
+}
+
+/** An entry of a compiled bundle that is different between two versions of it. */
+export interface IntlBundleEntryChange {
+  /** The key of the entry as it's written in the bundle, which is usually a hashed key. */
+  bundleKey: string
+  /** The message that the entry belongs to, when it's defined in the database. */
+  key?: string
+  /** The definitions file of the message. */
+  file?: string
+  kind: IntlBundleChangeKind
+  /** Size of the entry in the old bundle, including its key, or 0 when it was added. */
+  beforeBytes: number
+  /** Size of the entry in the new bundle, including its key, or 0 when it was removed. */
+  afterBytes: number
+  /** How many bytes the entry added to the bundle, which is negative when it got smaller. */
+  byteDelta: number
+}
+
+export interface IntlComplexityOptions {
+  /** Maximum number of characters in a message. Defaults to 1000. */
+  maxLength?: number
+  /** Maximum number of plurals and selects nested inside of each other. Defaults to 2. */
+  maxNestingDepth?: number
+  /** Maximum number of arms in a single plural. Defaults to 8. */
+  maxPluralArms?: number
+  /** Maximum number of distinct variables in a message. Defaults to 10. */
+  maxVariables?: number
+  /**
+   * Maximum number of distinct sentences a message can produce, counting every combination of
+   * plural and select arms. Defaults to 24.
+   */
+  maxCombinations?: number
+}
+
+/** A node or token of the concrete syntax tree of a message. */
+export interface IntlCstNode {
+  /** The kind of syntax, like `STRONG` for a node or `ICU_IDENT` for a token. */
+  kind: string
+  /** How many nodes this one is nested inside of. The document is the only node at depth 0. */
+  depth: number
+  /**
+   * Where the node starts in the message, as an index into the JS string like `slice` takes.
+   * Whitespace around the node isn't included.
+   */
+  start: number
+  end: number
+  /** The text of a token. Nodes don't have text of their own, only the tokens inside of them. */
+  text?: string
+}
+
+/** A problem found by a validator registered with `registerValidator`. */
+export interface IntlCustomDiagnostic {
+  /**
+   * Position in `raw` where the problem is, as an index into the JS string like `indexOf`
+   * returns. The diagnostic applies to the whole translation when not given.
+   */
+  offset?: number
+  /** Defaults to `Warning`. */
+  severity?: IntlDiagnosticSeverity
+  description: string
+  help?: string
+}
+
+/** The translation that a validator registered with `registerValidator` is checking. */
+export interface IntlCustomValidatorInput {
+  key: string
+  locale: string
+  /** The content of the translation as it was written. */
+  raw: string
+  /** The parsed translation, in the same shape as `IntlMessageValue.parsed`. */
+  parsed: object
+  /** The content of the source message that the translation was translated from. */
+  sourceRaw: string
+  /** True when the translation is the source message itself. */
+  isSource: boolean
 }
 
 export interface IntlDiagnostic {
@@ -44,6 +512,143 @@ export interface IntlDiagnostic {
   severity: string
   description: string
   help?: string
+  /** Position within the message value that the diagnostic applies to, if any. */
+  span?: IntlDiagnosticSpan
+}
+
+export interface IntlDiagnosticSpan {
+  byteOffset: number
+  /** Offset in UTF-16 code units, matching JavaScript string indices. */
+  utf16Offset: number
+  /** Offset in user-perceived characters, for highlighting. */
+  graphemeOffset: number
+  line: number
+  utf16Column: number
+  graphemeColumn: number
+}
+
+export declare const enum IntlDiagnosticSeverity {
+  Info = 0,
+  Warning = 1,
+  Error = 2
+}
+
+/**
+ * The `code` of every error thrown by the database. Errors may also have `filePath`, `key`,
+ * `locale`, and `position` properties when they are known.
+ */
+export declare const enum IntlErrorCode {
+  /** A file that was given to the database does not exist. */
+  FileNotFound = 'FileNotFound',
+  /** A file exists but could not be read or written. */
+  IoError = 'IoError',
+  /** A messages file is not syntactically valid. `position` is set when the location is known. */
+  ParseError = 'ParseError',
+  /**
+   * A messages file parsed, but its content can't be used, like a definition without a value
+   * or a file with no messages at all.
+   */
+  InvalidSource = 'InvalidSource',
+  /** A message was defined more than once, or a translation was set more than once. */
+  DuplicateKey = 'DuplicateKey',
+  /** A message has no translation in the requested locale. */
+  MissingTranslation = 'MissingTranslation',
+  /** No values in the database use the requested locale. */
+  UnknownLocale = 'UnknownLocale',
+  /** No message with the requested key is defined in the database. */
+  UnknownMessage = 'UnknownMessage',
+  /** The requested file has not been processed into the database. */
+  UnknownSourceFile = 'UnknownSourceFile',
+  /** No project with the requested name has been registered in the database. */
+  UnknownProject = 'UnknownProject',
+  /**
+   * A message references another message like `{@OTHER_MESSAGE}` that isn't defined, that has
+   * block content, or that leads back to itself. `key` is the message with the reference.
+   */
+  InvalidMessageReference = 'InvalidMessageReference',
+  /**
+   * An argument was given that can't be used, like an invalid query pattern or a token that no
+   * database is shared with.
+   */
+  InvalidArgument = 'InvalidArgument',
+  /** The operation was cancelled with its cancellation token before it completed. */
+  Cancelled = 'Cancelled',
+  /** A transaction was committed or rolled back while none was in progress. */
+  NoTransaction = 'NoTransaction',
+  /** Anything else, which usually indicates a bug in the database. */
+  Internal = 'Internal'
+}
+
+export interface IntlExportResult {
+  /** Every translation file covered by the export, whether or not it changed. */
+  files: Array<string>
+  /** The files whose content changed and were written, or would have been written in a dry run. */
+  changedFiles: Array<string>
+}
+
+export interface IntlFileDiscoveryOptions {
+  /**
+   * Globs for files and directories to skip, using the same syntax as `.gitignore`, like
+   * `dist` or `packages/*/build`. Globs without a `/` match an entry with that name anywhere.
+   */
+  exclude?: Array<string>
+  /** Skip files that are ignored by git. Defaults to true. */
+  respectGitignore?: boolean
+  /** Follow symbolic links while scanning. Defaults to false. */
+  followSymlinks?: boolean
+  /** How many directories deep to scan within each directory. Defaults to no limit. */
+  maxDepth?: number
+}
+
+/** A translation that was changed by an autofix, like `fixTranslationUnicode`. */
+export interface IntlFixedTranslation {
+  key: string
+  locale: string
+}
+
+/** The changes that replacements make to a single file. */
+export interface IntlFileReplacement {
+  filePath: string
+  /** The locale that the values in the file are written in. */
+  locale: string
+  isDefinitions: boolean
+  /** Hash of the content of the file when the replacements were planned. */
+  contentHash: string
+  newContent: string
+  /** A unified diff of the changes to the file. */
+  diff: string
+  messages: Array<IntlReplacedMessage>
+}
+
+export interface IntlFileTiming {
+  file: string
+  phase: IntlPerformancePhase
+  messageCount: number
+  /** Total time spent on this phase for every message in the file, in milliseconds. */
+  total: number
+  /** Time spent on the slowest message in the file, in milliseconds. */
+  slowest: number
+}
+
+export interface IntlFragmentOptions {
+  /**
+   * Words that mark a message as a fragment when it starts with them. Defaults to common
+   * lowercase conjunctions like `and` and `or`.
+   */
+  leadingWords?: Array<string>
+  /** Text that marks a message as a fragment when it ends with it. Defaults to `:`. */
+  trailingSuffixes?: Array<string>
+}
+
+export interface IntlLocaleTagDiagnostic {
+  filePath: string
+  /** The locale tag as it was given for the file. */
+  localeTag: string
+  /** The locale that the file's translations were stored under. */
+  locale: string
+  /** Either `invalid` or `duplicate`. */
+  problem: string
+  description: string
 }
 
 export interface IntlMessage {
@@ -57,11 +662,97 @@ export interface IntlMessage {
   sourceLocale?: string
   /** Meta information about how to handle and process this message. */
   meta: IntlMessageMeta
+  /**
+   * Hash of the source value and meta of this message, which only changes when the definition
+   * does. Not present when the message has no definition.
+   */
+  contentHash?: string
 }
 
 export interface IntlMessageBundlerOptions {
   format?: IntlCompiledMessageFormat
   bundleSecrets?: boolean
+  /** How to handle secret messages in the bundle. Takes precedence over `bundleSecrets`. */
+  secretRedaction?: IntlSecretRedaction
+  /** Include the plural categories used by the bundled messages under the `$plurals` key. */
+  pluralRules?: boolean
+  /**
+   * How apostrophes are interpreted when compiling messages. This and the other parser options
+   * below default to the database's `parserOptions`, and messages are only parsed again for
+   * the bundle when they differ.
+   */
+  apostropheMode?: IntlApostropheMode
+  /**
+   * Turn bare urls like `https://discord.com` and `www.discord.com` into links when compiling
+   * messages. Defaults to false.
+   */
+  autolinkBareUrls?: boolean
+  /**
+   * Compile emoji shortcodes like `:smile:` into `$emoji` tags, with the name of the emoji as
+   * the tag's control value. Defaults to false.
+   */
+  emojiShortcodes?: boolean
+  /**
+   * Sigils that mark mention placeholders like `<@{userId}>`, compiled into `$mention` tags
+   * with the sigil as the tag's control value. Mentions are not parsed when this is not set.
+   */
+  mentionSigils?: Array<string>
+  /** Compile keyboard keys like `[[Ctrl]]` into `$kbd` tags. Defaults to false. */
+  keyboardKeys?: boolean
+  /** Transforms to apply to every message before it is compiled, in the order they are given. */
+  transforms?: Array<IntlMessageTransform>
+  /**
+   * New names for placeholder variables, keyed by their names in the source messages. Renaming
+   * is applied after every other transform.
+   */
+  placeholderRenames?: Record<string, string>
+  /**
+   * New names for the placeholder variables of individual messages, keyed by message key and
+   * then by their current names, like when a variable is renamed in code before translations
+   * are updated. Applied after `placeholderRenames`.
+   */
+  messagePlaceholderRenames?: Record<string, Record<string, string>>
+  /**
+   * Values for variables that are constant for the whole build, like a product name or an
+   * upload limit, keyed by variable name. Placeholders for them are replaced with their values
+   * wherever the result is the same in every locale, before any other transform.
+   */
+  constants?: Record<string, string | number>
+  /** How messages are keyed in the bundle. Defaults to their hashed keys. */
+  keyMode?: IntlBundleKeyMode
+  /**
+   * Put every entry of the bundle on its own line and indent compiled values, for reading
+   * debug artifacts. Defaults to false.
+   */
+  pretty?: boolean
+  /**
+   * Include the `file:line:column` that each bundled value was read from under the `$spans`
+   * key. Defaults to false.
+   */
+  includeSpans?: boolean
+  /**
+   * Write whole numbers in compiled values without a fractional part, the same way
+   * `JSON.stringify` does. Defaults to false.
+   */
+  stableFloats?: boolean
+}
+
+/**
+ * A change to the content of a message for `rewriteMessage`, replacing `start..end` with `text`.
+ * Offsets are indices into the raw content of the message, like `indexOf` returns.
+ */
+export interface IntlMessageEdit {
+  start: number
+  end: number
+  text: string
+}
+
+export interface IntlMessageHistory {
+  key: string
+  /** Versions of the definition, oldest first. */
+  revisions: Array<IntlMessageRevision>
+  /** Every translation other than the source, sorted by locale. */
+  translations: Array<IntlTranslationVersion>
 }
 
 export interface IntlMessageMeta {
@@ -70,6 +761,99 @@ export interface IntlMessageMeta {
   translationsPath: string
 }
 
+export interface IntlMessageQuery {
+  /** Text to search for in message keys and values. Matches any message when not given. */
+  pattern?: string
+  /** Treat `pattern` as a regular expression rather than a plain substring. */
+  isRegex?: boolean
+  /** Match `pattern` against message keys. Defaults to true. */
+  searchKeys?: boolean
+  /** Match `pattern` against the raw values of every translation. Defaults to true. */
+  searchValues?: boolean
+  /** Only include messages that have a value in this locale. */
+  withLocale?: string
+  /** Only include messages that have no value in this locale. */
+  withoutLocale?: string
+  secret?: boolean
+  translate?: boolean
+  /** Only include messages defined in this definitions file. */
+  definitionsFile?: string
+  /** Only include messages that use a variable with this name. */
+  variable?: string
+  /** Only include messages defined in the registered project with this name. */
+  project?: string
+  offset?: number
+  limit?: number
+}
+
+export interface IntlMessageQueryResult {
+  /** Messages on the requested page of results, ordered by key. */
+  messages: Array<IntlMessage>
+  /** Total number of messages matching the query, across all pages. */
+  total: number
+}
+
+/** A version of the definition of a message. */
+export interface IntlMessageRevision {
+  raw: string
+  contentHash: string
+  /** When the version was first processed, in milliseconds since the Unix epoch. */
+  recordedAt: number
+}
+
+export interface IntlMessageTiming {
+  key: string
+  locale: string
+  file?: string
+  phase: IntlPerformancePhase
+  /** Time spent on this phase of the message, in milliseconds. */
+  duration: number
+}
+
+/** Built-in transforms that can be applied to messages when bundling. */
+export declare const enum IntlMessageTransform {
+  /** Replace straight quotes and apostrophes with typographic ones. */
+  SmartQuotes = 0,
+  /** Wrap placeholder values in Unicode directional isolates. */
+  BidiIsolation = 1,
+  /** Insert zero-width spaces where lines can break within CJK text. */
+  CjkWordBreaks = 2,
+  /**
+   * Remove stray zero-width characters and normalize text to NFC. Has to come before
+   * `CjkWordBreaks`, which it would otherwise undo.
+   */
+  NormalizeUnicode = 3
+}
+
+export interface IntlMessagesDatabaseOptions {
+  /**
+   * The locale that definitions are written in, unless their source file sets its own
+   * `sourceLocale` in its meta. Defaults to `en-US`.
+   */
+  sourceLocale?: string
+  /**
+   * Extensions of files that contain message definitions in addition to the built-in
+   * `.messages.js` variants, like `.messages.mjs`. Matching files are found when scanning for
+   * messages files and are processed as JS definitions.
+   */
+  definitionsExtensions?: Array<string>
+  /**
+   * Names that hooks are allowed to use, like `tooltipHook` in `$[text](tooltipHook)`. Hook
+   * names are not checked when not given.
+   */
+  hookNames?: Array<string>
+  /**
+   * Names that link handlers are allowed to use, like `onClick` in `[text](onClick)`. Link
+   * handlers are not checked when not given.
+   */
+  linkHandlerNames?: Array<string>
+  /**
+   * How every message in the database is parsed, which validation, type generation, and
+   * bundling all use. Defaults to standard ICU MessageFormat semantics.
+   */
+  parserOptions?: IntlParserOptions
+}
+
 export interface IntlMessagesFileDescriptor {
   filePath: string
   locale: string
@@ -80,10 +864,36 @@ export interface IntlMessageValue {
   parsed: object
   variables: object
   filePosition: object
+  /** True when the value was recovered from a compiled bundle rather than written by a person. */
+  machineRecovered: boolean
+  /** Where the value is in the review workflow, one of `new`, `machine`, `reviewed`, or `final`. */
+  reviewState: string
+  /** Comments written around the value in its translation file. */
+  comments?: IntlValueComments
+  /**
+   * Content hash of the definition when this translation was set, used to tell when the source
+   * has changed since it was translated.
+   */
+  sourceHash?: string
+}
+
+/** A word from the visible text of a message, to be checked by a spell checker. */
+export interface IntlMessageWord {
+  word: string
+  /** Position of the word in the message, unless it was written with escapes. */
+  span?: IntlDiagnosticSpan
+}
+
+/** A word that a spell checker found to be misspelled. */
+export interface IntlMisspelling {
+  word: string
+  /** Replacements suggested by the spell checker. */
+  suggestions?: Array<string>
 }
 
 export interface IntlMultiProcessingFailure {
   file: string
+  code: IntlErrorCode
   error: string
 }
 
@@ -92,15 +902,353 @@ export interface IntlMultiProcessingResult {
   failed: Array<IntlMultiProcessingFailure>
 }
 
+/**
+ * Options that change how message content is parsed. Any that aren't given keep their current
+ * value, which is the default unless the database was given other options.
+ */
+export interface IntlParserOptions {
+  /** How apostrophes are interpreted. Defaults to ICU escaping. */
+  apostropheMode?: IntlApostropheMode
+  /**
+   * Turn bare urls like `https://discord.com` and `www.discord.com` into links. Defaults to
+   * false.
+   */
+  autolinkBareUrls?: boolean
+  /**
+   * Parse emoji shortcodes like `:smile:`, which are compiled into `$emoji` tags with the name
+   * of the emoji as the tag's control value. Defaults to false.
+   */
+  emojiShortcodes?: boolean
+  /**
+   * Sigils that mark mention placeholders like `<@{userId}>`, which are compiled into
+   * `$mention` tags with the sigil as the tag's control value. Mentions are not parsed when
+   * this is not set.
+   */
+  mentionSigils?: Array<string>
+  /**
+   * Parse keyboard keys like `[[Ctrl]]`, which are compiled into `$kbd` tags. Defaults to
+   * false.
+   */
+  keyboardKeys?: boolean
+}
+
+export declare const enum IntlPerformancePhase {
+  Parse = 0,
+  Compile = 1
+}
+
+export interface IntlPerformanceReport {
+  /** The slowest individual messages, slowest first. */
+  messages: Array<IntlMessageTiming>
+  /** Totals for every source file with recorded timings, slowest first. */
+  files: Array<IntlFileTiming>
+  /** Totals for every validation rule from the most recent validation, slowest first. */
+  rules: Array<IntlRuleTiming>
+}
+
+export declare const enum IntlReviewState {
+  New = 0,
+  Machine = 1,
+  Reviewed = 2,
+  Final = 3
+}
+
+export interface IntlPrecompiledBundle {
+  buffer: Buffer
+  /** Every file that the bundle depends on, sorted by path. */
+  dependencies: Array<IntlBundleDependency>
+}
+
+/**
+ * A package in a monorepo that shares the database with other packages. Every file under `root`
+ * belongs to the project.
+ */
+export interface IntlProject {
+  name: string
+  root: string
+  /**
+   * The locale that definitions in the project are written in when they aren't processed with
+   * an explicit locale. Defaults to the source locale of the database.
+   */
+  sourceLocale?: string
+  /**
+   * The directory where translations for the project are kept, relative to `root`. Used by
+   * definitions files in the project that don't set their own `translationsPath`.
+   */
+  translationsPath?: string
+}
+
+/** A value that the pattern matched but that can't be rewritten in its file. */
+export interface IntlSkippedReplacement {
+  key: string
+  locale: string
+  reason: string
+}
+
 export interface IntlSourceFile {
   type: string
   file: string
   messageKeys: Array<number>
   meta: IntlMessageMeta
   locale?: number
+  /**
+   * The locale tag as it was given for the file, before it was normalized. Only present for
+   * translations files.
+   */
+  localeTag?: string
+  /** Entries that could not be extracted as messages. Only present for definitions files. */
+  errors?: Array<IntlSourceFileError>
+}
+
+export interface IntlSourceFileError {
+  position: IntlSourcePosition
+  message: string
+}
+
+export interface IntlSourcePosition {
+  line: number
+  col: number
 }
 
-export declare function isMessageDefinitionsFile(key: string): boolean
+/**
+ * How far an async task has gotten through its work, like the number of messages validated so
+ * far out of every message in the database.
+ */
+export interface IntlReplacedMessage {
+  key: string
+  locale: string
+  before: string
+  after: string
+  /** How many matches of the pattern were replaced in the value. */
+  matches: number
+}
+
+/** Limits on which values `previewReplacements` changes. */
+export interface IntlReplacementOptions {
+  /**
+   * Languages, like `en`, or full locales, like `en-GB`, to replace values in. Replaces values
+   * in every locale when not given.
+   */
+  locales?: Array<string>
+  /**
+   * Globs of the message keys to replace values of, like `SETTINGS_*`. Replaces values of
+   * every message when not given.
+   */
+  keys?: Array<string>
+}
+
+/** Every change planned by `previewReplacements`, to be given to `applyReplacements`. */
+export interface IntlReplacementPreview {
+  files: Array<IntlFileReplacement>
+  skipped: Array<IntlSkippedReplacement>
+}
+
+export interface IntlRuleTiming {
+  rule: string
+  /** Number of message values that the rule checked. */
+  valueCount: number
+  /** Total time spent running the rule on every value, in milliseconds. */
+  total: number
+  /** Time spent on the slowest value, in milliseconds. */
+  slowest: number
+}
+
+export interface IntlServiceProgress {
+  completed: number
+  total: number
+}
+
+export declare const enum IntlSecretRedaction {
+  Keep = 0,
+  Mask = 1,
+  Omit = 2
+}
+
+export interface IntlSeverityCounts {
+  errors: number
+  warnings: number
+  infos: number
+}
+
+/**
+ * A house style rule, like reporting "Use 'sign in' instead of 'log in'" wherever `\blog in\b`
+ * matches.
+ */
+export interface IntlStyleRule {
+  /**
+   * Regular expression matched against the raw content of each message, using the syntax of
+   * the Rust `regex` crate.
+   */
+  pattern: string
+  /** Description of the diagnostic reported for each match. */
+  message: string
+  help?: string
+  /**
+   * Languages, like `en`, or full locales, like `en-GB`, to apply the rule to. Applies to every
+   * locale when not given.
+   */
+  locales?: Array<string>
+  /**
+   * Globs of the message keys to apply the rule to, like `SETTINGS_*`. Applies to every message
+   * when not given.
+   */
+  keys?: Array<string>
+  /** Defaults to `Warning`. */
+  severity?: IntlDiagnosticSeverity
+}
+
+/** Which version of the definition of a message a translation was written for. */
+export interface IntlTranslationVersion {
+  locale: string
+  /** Content hash of the definition when the translation was set, if it's known. */
+  sourceHash?: string
+  /** True if the definition has changed since the translation was set. */
+  isOutdated: boolean
+}
+
+export interface IntlTypesGeneratorOptions {
+  /** The kind of type definitions file to generate. Defaults to TypeScript. */
+  format?: IntlTypesOutputFormat
+  /** Also declare a `messageLocales` map of each message key to the locales that define it. */
+  localeMap?: boolean
+  /**
+   * Also declare a `messageKeys` map of each hashed message key to its original key, so that
+   * devtools can show readable keys for the ones in compiled bundles.
+   */
+  keyMap?: boolean
+  /**
+   * The same constants given to the bundler. Variables that are always replaced with their
+   * values are left out of the generated types.
+   */
+  constants?: Record<string, string | number>
+  /**
+   * The same `messagePlaceholderRenames` given to the bundler, so that translations that still
+   * use the old names of renamed placeholders don't add them to the generated types.
+   */
+  messagePlaceholderRenames?: Record<string, Record<string, string>>
+  /**
+   * Generate types for every definitions file in a directory at once, with one declaration
+   * file for each. When given, the source path is the directory of definitions files, and the
+   * output path is the directory to write the declarations and their barrel index files to.
+   */
+  split?: IntlTypesSplitMode
+  /**
+   * Keys of the messages that changed since the types were last generated. When given and the
+   * output and its source map already exist, only the entries of these messages and the
+   * messages that reference them are generated again, and the rest are kept from the existing
+   * output. Every message should be regenerated when the known locales change. Not used
+   * with `split`, which always generates every file in full.
+   */
+  changedKeys?: Array<string>
+}
+
+export declare const enum IntlTypesOutputFormat {
+  TypeScript = 0,
+  Flow = 1,
+  JsDoc = 2
+}
+
+export declare const enum IntlTypesSplitMode {
+  /** A single index at the root of the output re-exports every declaration file. */
+  File = 0,
+  /**
+   * Every directory of the output has an index that re-exports the declaration files in it,
+   * along with the index of each of its subdirectories.
+   */
+  Directory = 1
+}
+
+export interface IntlUntranslatedMessage {
+  key: string
+  /** Raw content of the message in its source locale. */
+  source: string
+  /** Location of the message's definition, when it was processed from a file. */
+  file?: string
+  line?: number
+  col?: number
+}
+
+export interface IntlValidationSummary {
+  /** Whether there are no diagnostics at or above the `failOn` severity. */
+  passed: boolean
+  /** The status that a CI process should exit with: 0 when the summary passed, otherwise 1. */
+  exitCode: number
+  counts: IntlSeverityCounts
+  /** Counts for each rule that reported any diagnostics, keyed by the rule name. */
+  rules: Record<string, IntlSeverityCounts>
+}
+
+/**
+ * Comments written around a value in a `.jsona` translation file, each exactly as written
+ * including the comment markers.
+ */
+export interface IntlValueComments {
+  /** Comments on the lines above the entry. */
+  leading?: string
+  /** Comments between the key and the value, like `"key": /* note *\/ "value"`. */
+  inline?: string
+  /**
+   * Comments after the value on the same line. For the last entry in a file, this also includes
+   * any comments on the lines after it.
+   */
+  trailing?: string
+}
+
+export interface IntlValidatorOptions {
+  /**
+   * Thresholds for warning about messages that are too large or complex. Any threshold that is
+   * not given uses its default.
+   */
+  complexity?: IntlComplexityOptions
+  /**
+   * Patterns for recognizing messages that are fragments of a larger sentence. Any pattern that
+   * is not given uses its default.
+   */
+  fragments?: IntlFragmentOptions
+  /**
+   * Suffixes that mark a word as plural for each language or locale, like `{ en: ['s'] }`, used
+   * to find plural options that use the wrong form of a word, like `one {# items}`. Languages
+   * that are not given keep their defaults, and an empty list disables the check for one.
+   */
+  pluralSuffixes?: Record<string, Array<string>>
+  /**
+   * The convention for spacing between placeholders and CJK text for each language or locale,
+   * like `{ ko: IntlCjkPlaceholderSpacing.Spaced }`. `ja` and `zh` default to `Unspaced`, and
+   * other locales are not checked unless given.
+   */
+  cjkPlaceholderSpacing?: Record<string, IntlCjkPlaceholderSpacing>
+  /** House style rules, reported as `NoStyleViolations` wherever their patterns match. */
+  styleRules?: Array<IntlStyleRule>
+  /** Only validate the messages defined in the registered project with this name. */
+  project?: string
+  /**
+   * Path to a baseline file written by `writeValidationBaseline`. Only diagnostics that aren't
+   * recorded in the baseline are returned.
+   */
+  baseline?: string
+}
+
+export interface IntlVariableCompletion {
+  name: string
+  /** Every kind of value the variable is used as, like `number`, `select`, or `hook`. */
+  kinds: Array<string>
+  isBuiltin: boolean
+  /**
+   * Each distinct way the variable is written in the source message, ready to be inserted into
+   * a translation. Empty for builtin variables, which are written as markdown syntax instead.
+   */
+  examples: Array<string>
+}
+
+/**
+ * Returns true if `key` is a definitions file, either with one of the built-in `.messages.js`
+ * extensions or with any of `definitionsExtensions`, which should be the same extensions the
+ * database was created with. Imports can leave out the last part of an extension, like
+ * `Feature.messages` or `Feature.strings` for `.strings.js`.
+ */
+export declare function isMessageDefinitionsFile(key: string, definitionsExtensions?: Array<string> | undefined | null): boolean
 
 export declare function isMessageTranslationsFile(key: string): boolean
 
+export declare function normalizeLocaleTag(tag: string): string | null
+
@@ -76,14 +76,28 @@ const {
   hashMessageKey,
   isMessageDefinitionsFile,
   isMessageTranslationsFile,
+  IntlCancellationToken,
   IntlMessagesDatabase,
+  IntlBundleKeyMode,
   IntlCompiledMessageFormat,
+  IntlErrorCode,
+  IntlPerformancePhase,
+  IntlSecretRedaction,
+  IntlTypesOutputFormat,
+  IntlTypesSplitMode,
 } = nativeBinding;
 
 module.exports = {
   hashMessageKey,
   isMessageDefinitionsFile,
   isMessageTranslationsFile,
+  IntlCancellationToken,
   IntlMessagesDatabase,
+  IntlBundleKeyMode,
   IntlCompiledMessageFormat,
+  IntlErrorCode,
+  IntlPerformancePhase,
+  IntlSecretRedaction,
+  IntlTypesOutputFormat,
+  IntlTypesSplitMode,
 };
@@ -0,0 +1,380 @@
+//! An interactive shell for looking through a messages database while debugging, like checking
+//! which translations a message has or why it fails validation, without writing a script for it.
+//!
+//! ```shell
+//! cargo run -p intl_message_database --features repl --bin intl-repl -- ./src
+//! ```
+//!
+//! Like the other interfaces to the database, this only calls into
+//! [intl_message_database::public] and formats the results.
+use std::io::{BufRead, IsTerminal, Write};
+
+use intl_database_core::{
+    FilePosition, KeySymbol, Message, MessageQuery, MessageQueryPattern, MessagesDatabase,
+};
+use intl_message_database::public;
+use intl_message_database::sources::MessagesFileDiscoveryOptions;
+use intl_validator::{
+    validate_message, validate_message_references, DiagnosticSeverity, ValueValidatorConfig,
+};
+
+const HELP: &str = "\
+Commands:
+  show KEY            Show the definition and meta of a message
+  translations KEY    Show the value of a message in every locale
+  grep PATTERN        Find messages whose key or any value contains PATTERN
+  grep /REGEX/        Find messages whose key or any value matches REGEX
+  validate KEY        Run every validator on a message
+  help                Show this list of commands
+  exit                Leave the shell";
+
+const USAGE: &str = "\
+Usage: intl-repl [--source-locale LOCALE] [--exclude GLOB]... [--sarif] [--baseline FILE]
+                 [--update-baseline] [--summary] [--fail-on SEVERITY] DIRECTORY...
+
+Loads every messages file in the given directories, then reads commands from stdin.
+
+With --sarif, validates every message instead and writes the diagnostics to stdout as a SARIF
+log, with paths relative to the current directory, for code scanning tools in CI. When a
+--baseline file is given, only diagnostics that aren't recorded in it are written.
+
+With --update-baseline, records every current diagnostic in the --baseline file and exits.
+
+With --summary, writes the number of diagnostics of each severity and rule to stdout as JSON,
+then exits with status 1 if any diagnostic is at least as severe as --fail-on, which is one of
+info, warning, or error and defaults to error.";
+
+/// Colors for terminal output, which are all empty when colors are disabled.
+struct Style {
+    bold: &'static str,
+    dim: &'static str,
+    red: &'static str,
+    yellow: &'static str,
+    cyan: &'static str,
+    reset: &'static str,
+}
+
+impl Style {
+    fn new(enabled: bool) -> Self {
+        if enabled {
+            Self {
+                bold: "\x1b[1m",
+                dim: "\x1b[2m",
+                red: "\x1b[31m",
+                yellow: "\x1b[33m",
+                cyan: "\x1b[36m",
+                reset: "\x1b[0m",
+            }
+        } else {
+            Self {
+                bold: "",
+                dim: "",
+                red: "",
+                yellow: "",
+                cyan: "",
+                reset: "",
+            }
+        }
+    }
+
+    fn severity(&self, severity: DiagnosticSeverity) -> &'static str {
+        match severity {
+            DiagnosticSeverity::Error => self.red,
+            DiagnosticSeverity::Warning => self.yellow,
+            DiagnosticSeverity::Info => self.cyan,
+        }
+    }
+}
+
+struct Repl {
+    database: MessagesDatabase,
+    style: Style,
+}
+
+impl Repl {
+    /// Run a single command line, returning false once the shell should exit.
+    fn run_command(&self, line: &str, out: &mut impl Write) -> std::io::Result<bool> {
+        let (command, argument) = match line.trim().split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line.trim(), ""),
+        };
+        let result = match (command, argument) {
+            ("", _) => Ok(()),
+            ("exit" | "quit", _) => return Ok(false),
+            ("help", _) => writeln!(out, "{HELP}").map_err(Into::into),
+            ("show", key) if !key.is_empty() => self.show(key, out),
+            ("translations", key) if !key.is_empty() => self.translations(key, out),
+            ("grep", pattern) if !pattern.is_empty() => self.grep(pattern, out),
+            ("validate", key) if !key.is_empty() => self.validate(key, out),
+            _ => Err(anyhow::anyhow!(
+                "Unknown command `{}`. Type `help` to see every command",
+                line.trim()
+            )),
+        };
+        if let Err(error) = result {
+            let style = &self.style;
+            writeln!(out, "{}error{}: {error}", style.red, style.reset)?;
+        }
+        Ok(true)
+    }
+
+    fn show(&self, key: &str, out: &mut impl Write) -> anyhow::Result<()> {
+        let message = public::get_message(&self.database, key)?;
+        let style = &self.style;
+        let meta = message.meta();
+        writeln!(out, "{}{}{}", style.bold, message.key(), style.reset)?;
+        writeln!(out, "  hashed key:    {}", message.hashed_key())?;
+        if let Some(locale) = message.source_locale() {
+            writeln!(out, "  source locale: {locale}")?;
+        }
+        writeln!(out, "  secret:        {}", meta.secret)?;
+        writeln!(out, "  translate:     {}", meta.translate)?;
+        writeln!(out, "  strictness:    {}", meta.strictness)?;
+        if let Some(description) = &meta.description {
+            writeln!(out, "  description:   {description}")?;
+        }
+        match message.get_source_translation() {
+            Some(source) => {
+                if let Some(position) = source.file_position {
+                    writeln!(out, "  defined at:    {}", self.format_position(position))?;
+                }
+                writeln!(out, "{}", source.raw.as_str())?;
+            }
+            None => writeln!(
+                out,
+                "{}This message has no definition, only translations{}",
+                style.yellow, style.reset
+            )?,
+        }
+        Ok(())
+    }
+
+    fn translations(&self, key: &str, out: &mut impl Write) -> anyhow::Result<()> {
+        let message = public::get_message(&self.database, key)?;
+        let style = &self.style;
+        let mut translations: Vec<_> = message.translations().iter().collect();
+        translations.sort_by_key(|(locale, _)| locale.as_str());
+        for (locale, value) in translations {
+            let is_source = *message.source_locale() == Some(*locale);
+            write!(out, "{}{locale}{}", style.bold, style.reset)?;
+            if is_source {
+                write!(out, " (source)")?;
+            }
+            if let Some(position) = value.file_position {
+                write!(
+                    out,
+                    " {}{}{}",
+                    style.dim,
+                    self.format_position(position),
+                    style.reset
+                )?;
+            }
+            writeln!(out)?;
+            writeln!(out, "  {}", value.raw.as_str())?;
+        }
+        Ok(())
+    }
+
+    fn grep(&self, pattern: &str, out: &mut impl Write) -> anyhow::Result<()> {
+        let pattern = unquote(pattern);
+        let pattern = match pattern
+            .strip_prefix('/')
+            .and_then(|pattern| pattern.strip_suffix('/'))
+        {
+            Some(regex) => MessageQueryPattern::regex(regex)?,
+            None => MessageQueryPattern::substring(pattern),
+        };
+        let query = MessageQuery::new().with_pattern(Some(pattern.clone()));
+        let result = public::find_messages(&self.database, &query)?;
+        let style = &self.style;
+        for message in &result.messages {
+            writeln!(out, "{}{}{}", style.bold, message.key(), style.reset)?;
+            for (locale, value) in matching_values(message, &pattern) {
+                writeln!(out, "  {}{locale}{}: {}", style.dim, style.reset, value)?;
+            }
+        }
+        writeln!(out, "{} matching messages", result.total)?;
+        Ok(())
+    }
+
+    fn validate(&self, key: &str, out: &mut impl Write) -> anyhow::Result<()> {
+        let message = public::get_message(&self.database, key)?;
+        let mut diagnostics = validate_message(message);
+        diagnostics.extend(validate_message_references(&self.database, message));
+        let style = &self.style;
+        if diagnostics.is_empty() {
+            writeln!(out, "No problems found")?;
+            return Ok(());
+        }
+        for diagnostic in &diagnostics {
+            writeln!(
+                out,
+                "{}{}{} {}{}{} [{}] {}",
+                style.severity(diagnostic.severity),
+                diagnostic.severity.as_str(),
+                style.reset,
+                style.bold,
+                diagnostic.name.as_str(),
+                style.reset,
+                diagnostic.locale,
+                diagnostic.description
+            )?;
+            writeln!(
+                out,
+                "  {}{}{}",
+                style.dim,
+                self.format_position(diagnostic.file_position),
+                style.reset
+            )?;
+            if let Some(help) = &diagnostic.help {
+                writeln!(out, "  {help}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn format_position(&self, position: FilePosition) -> String {
+        format!("{}:{}:{}", position.file, position.line, position.col + 1)
+    }
+}
+
+/// Return the locale and the first line of every value of `message` that matches `pattern`.
+fn matching_values<'a>(
+    message: &'a Message,
+    pattern: &MessageQueryPattern,
+) -> Vec<(KeySymbol, &'a str)> {
+    let mut values: Vec<_> = message
+        .translations()
+        .iter()
+        .filter(|(_, value)| pattern.is_match(&value.raw))
+        .map(|(locale, value)| (*locale, value.raw.lines().next().unwrap_or_default()))
+        .collect();
+    values.sort_by_key(|(locale, _)| locale.as_str());
+    values
+}
+
+/// Remove one pair of matching quotes around `text`, so that patterns can be written with
+/// surrounding whitespace, like `grep " to "`.
+fn unquote(text: &str) -> &str {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| text.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(text)
+}
+
+fn load_database(
+    directories: &[String],
+    source_locale: Option<&str>,
+    exclude: Vec<String>,
+) -> anyhow::Result<MessagesDatabase> {
+    let mut database = public::create_database(source_locale, vec![]);
+    let default_locale = public::get_source_locale(&database).to_string();
+    let options = MessagesFileDiscoveryOptions::default().with_exclude(exclude);
+    let files = public::find_all_messages_files_with_options(
+        &database,
+        directories.iter(),
+        &default_locale,
+        &options,
+    )?;
+    let result = public::process_all_messages_files(&mut database, files.into_iter())?;
+    for (file, error) in &result.failed {
+        eprintln!("Failed to process {file}: {error}");
+    }
+    eprintln!(
+        "Loaded {} messages from {} files",
+        database.messages.len(),
+        result.processed.len()
+    );
+    Ok(database)
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut directories = vec![];
+    let mut source_locale = None;
+    let mut exclude = vec![];
+    let mut sarif = false;
+    let mut baseline = None;
+    let mut update_baseline = false;
+    let mut summary = false;
+    let mut fail_on = DiagnosticSeverity::Error;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--source-locale" => source_locale = args.next(),
+            "--exclude" => exclude.extend(args.next()),
+            "--sarif" => sarif = true,
+            "--baseline" => baseline = args.next(),
+            "--update-baseline" => update_baseline = true,
+            "--summary" => summary = true,
+            "--fail-on" => {
+                let severity = args.next().unwrap_or_default();
+                let Some(severity) = DiagnosticSeverity::parse(&severity) else {
+                    eprintln!("--fail-on must be one of info, warning, or error");
+                    std::process::exit(2);
+                };
+                fail_on = severity;
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}\n\n{HELP}");
+                return Ok(());
+            }
+            _ => directories.push(arg),
+        }
+    }
+    if directories.is_empty() {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    }
+
+    let database = load_database(&directories, source_locale.as_deref(), exclude)?;
+    if update_baseline {
+        let Some(baseline) = &baseline else {
+            eprintln!("--update-baseline needs a --baseline file to write");
+            std::process::exit(2);
+        };
+        let config = ValueValidatorConfig::default();
+        let entries = public::write_validation_baseline(&database, &config, None, baseline)?;
+        eprintln!("Wrote {entries} entries to {baseline}");
+        return Ok(());
+    }
+    if summary {
+        let config = ValueValidatorConfig::default();
+        let summary =
+            public::summarize_validation(&database, &config, None, baseline.as_deref(), fail_on)?;
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        std::process::exit(summary.exit_code());
+    }
+    if sarif {
+        let mut diagnostics = public::validate_messages(&database)?;
+        if let Some(baseline) = &baseline {
+            diagnostics = public::filter_baselined_diagnostics(&database, diagnostics, baseline)?;
+        }
+        let root = std::env::current_dir()?;
+        let log = public::format_diagnostics_as_sarif(&diagnostics, root.to_str())?;
+        println!("{log}");
+        return Ok(());
+    }
+
+    let repl = Repl {
+        database,
+        style: Style::new(
+            std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        ),
+    };
+    let is_interactive = std::io::stdin().is_terminal();
+    let mut stdout = std::io::stdout().lock();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        if is_interactive {
+            write!(stdout, "intl> ")?;
+            stdout.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        if !repl.run_command(&line, &mut stdout)? {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,106 @@
+//! Custom validators written in JS, registered with `registerValidator`. Validation runs on other
+//! threads, so each translation is sent to the JS callback on the main thread, and the validating
+//! thread waits for its result. That means JS validators can only run while the main thread is
+//! free, which is only the case for `validateMessagesAsync`.
+use std::sync::mpsc;
+
+use intl_validator::{CustomDiagnostic, CustomValidator, CustomValidatorInput, DiagnosticSeverity};
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{JsError, JsUnknown, NapiValue};
+
+use crate::napi::types::{byte_offset_from_utf16, IntlCustomDiagnostic, IntlCustomValidatorInput};
+
+/// A validator function given by JS. Registered validators don't keep the process alive.
+pub type CustomValidatorCallback = ThreadsafeFunction<
+    IntlCustomValidatorInput,
+    Option<Vec<IntlCustomDiagnostic>>,
+    IntlCustomValidatorInput,
+    false,
+    true,
+>;
+
+pub struct JsCustomValidator {
+    name: String,
+    callback: CustomValidatorCallback,
+}
+
+impl JsCustomValidator {
+    pub fn new(name: String, callback: CustomValidatorCallback) -> Self {
+        Self { name, callback }
+    }
+
+    fn failure(&self, reason: String) -> Vec<CustomDiagnostic> {
+        vec![CustomDiagnostic {
+            span: None,
+            severity: DiagnosticSeverity::Error,
+            description: format!("Custom validator '{}' failed: {reason}", self.name),
+            help: Some("The validator threw an error or returned something other than an array of diagnostics.".into()),
+        }]
+    }
+}
+
+/// Returns the message of an error from calling a JS validator. Values thrown by the validator are
+/// only kept as a reference, so they're converted to a string like `Error: message`.
+fn error_message(env: &Env, error: Error) -> String {
+    if !error.reason.is_empty() {
+        return error.reason;
+    }
+    // SAFETY: This is only called on the main thread, with the environment the error came from.
+    let value = unsafe {
+        let value = JsError::from(error).into_value(env.raw());
+        JsUnknown::from_raw_unchecked(env.raw(), value)
+    };
+    value
+        .coerce_to_string()
+        .and_then(|value| value.into_utf8()?.into_owned())
+        .unwrap_or_else(|error| error.reason)
+}
+
+impl CustomValidator for JsCustomValidator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn validate(&self, input: &CustomValidatorInput) -> Vec<CustomDiagnostic> {
+        let message = IntlCustomValidatorInput {
+            key: input.key.to_string(),
+            locale: input.locale.to_string(),
+            raw: input.translation.raw.to_string(),
+            parsed: serde_json::to_value(input.translation.parsed()).unwrap_or_default(),
+            source_raw: input.source.raw.to_string(),
+            is_source: input.is_source,
+        };
+        let (sender, receiver) = mpsc::channel();
+        let status = self.callback.call_with_return_value(
+            message,
+            ThreadsafeFunctionCallMode::Blocking,
+            move |result, env| {
+                let _ = sender.send(result.map_err(|error| error_message(&env, error)));
+                Ok(())
+            },
+        );
+        // The callback is closing when the environment shuts down, so there's nothing to report.
+        if status != Status::Ok {
+            return vec![];
+        }
+        match receiver.recv() {
+            Ok(Ok(diagnostics)) => diagnostics
+                .unwrap_or_default()
+                .into_iter()
+                .map(|diagnostic| CustomDiagnostic {
+                    span: diagnostic
+                        .offset
+                        .map(|offset| byte_offset_from_utf16(&input.translation.raw, offset)),
+                    severity: diagnostic
+                        .severity
+                        .map_or(DiagnosticSeverity::Warning, Into::into),
+                    description: diagnostic.description,
+                    help: diagnostic.help,
+                })
+                .collect(),
+            Ok(Err(reason)) => self.failure(reason),
+            Err(_) => vec![],
+        }
+    }
+}
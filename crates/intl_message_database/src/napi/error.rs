@@ -0,0 +1,307 @@
+//! Errors thrown to JS callers. Every failure from the public API is classified into an
+//! [IntlErrorCode] and thrown as a regular JS `Error` with that `code` set, along with any details
+//! that are known about where it happened, so that tooling can branch on the kind of failure
+//! rather than matching on message strings.
+use intl_database_core::{DatabaseError, MessageSourceError, RawPosition};
+use intl_database_exporter::BundleDiffError;
+use intl_database_service::ServiceCancelled;
+use napi::{Env, JsObject, Status};
+use napi_derive::napi;
+
+use crate::napi::registry::UnknownSharedDatabase;
+use crate::napi::types::IntlSourcePosition;
+use crate::sources::rewrite::RewriteError;
+
+/// The `code` of every error thrown by the database. Errors may also have `filePath`, `key`,
+/// `locale`, and `position` properties when they are known.
+#[napi(string_enum)]
+#[derive(Debug)]
+pub enum IntlErrorCode {
+    /// A file that was given to the database does not exist.
+    FileNotFound,
+    /// A file exists but could not be read or written.
+    IoError,
+    /// A messages file is not syntactically valid. `position` is set when the location is known.
+    ParseError,
+    /// A messages file parsed, but its content can't be used, like a definition without a value
+    /// or a file with no messages at all.
+    InvalidSource,
+    /// A message was defined more than once, or a translation was set more than once.
+    DuplicateKey,
+    /// A message has no translation in the requested locale.
+    MissingTranslation,
+    /// No values in the database use the requested locale.
+    UnknownLocale,
+    /// No message with the requested key is defined in the database.
+    UnknownMessage,
+    /// The requested file has not been processed into the database.
+    UnknownSourceFile,
+    /// No project with the requested name has been registered in the database.
+    UnknownProject,
+    /// A message references another message like `{@OTHER_MESSAGE}` that isn't defined, that has
+    /// block content, or that leads back to itself. `key` is the message with the reference.
+    InvalidMessageReference,
+    /// An argument was given that can't be used, like an invalid query pattern or a token that no
+    /// database is shared with.
+    InvalidArgument,
+    /// The operation was cancelled with its cancellation token before it completed.
+    Cancelled,
+    /// A transaction was committed or rolled back while none was in progress.
+    NoTransaction,
+    /// Anything else, which usually indicates a bug in the database.
+    Internal,
+}
+
+/// A failure from the public API, along with everything that is known about where it happened.
+#[derive(Debug)]
+pub struct IntlError {
+    pub code: IntlErrorCode,
+    pub message: String,
+    pub file_path: Option<String>,
+    pub key: Option<String>,
+    pub locale: Option<String>,
+    pub position: Option<RawPosition>,
+}
+
+impl IntlError {
+    fn new(code: IntlErrorCode, message: String) -> Self {
+        Self {
+            code,
+            message,
+            file_path: None,
+            key: None,
+            locale: None,
+            position: None,
+        }
+    }
+
+    fn with_file_path(mut self, file_path: impl ToString) -> Self {
+        self.file_path = Some(file_path.to_string());
+        self
+    }
+
+    fn with_key(mut self, key: impl ToString) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    fn with_locale(mut self, locale: impl ToString) -> Self {
+        self.locale = Some(locale.to_string());
+        self
+    }
+
+    /// Create the JS `Error` for this failure and throw it in `env`. The returned error only
+    /// signals to napi that an exception is already pending, so it should be returned as-is.
+    pub fn throw(self, env: &Env) -> napi::Error {
+        match self.to_js_error(env).and_then(|error| env.throw(error)) {
+            Ok(()) => napi::Error::from_status(Status::PendingException),
+            Err(error) => error,
+        }
+    }
+
+    /// Create the JS `Error` for this failure as a regular error value, for rejecting promises
+    /// where there's no call to throw it from, like when an async task fails.
+    pub fn into_rejection(self, env: &Env) -> napi::Error {
+        match self.to_js_error(env) {
+            Ok(error) => error.into_unknown().into(),
+            Err(error) => error,
+        }
+    }
+
+    fn to_js_error(&self, env: &Env) -> napi::Result<JsObject> {
+        let mut error = env.create_error(napi::Error::from_reason(self.message.clone()))?;
+        error.set_named_property("code", self.code)?;
+        if let Some(file_path) = &self.file_path {
+            error.set_named_property("filePath", file_path.as_str())?;
+        }
+        if let Some(key) = &self.key {
+            error.set_named_property("key", key.as_str())?;
+        }
+        if let Some(locale) = &self.locale {
+            error.set_named_property("locale", locale.as_str())?;
+        }
+        if let Some(position) = self.position {
+            error.set_named_property(
+                "position",
+                IntlSourcePosition {
+                    line: position.line,
+                    col: position.col,
+                },
+            )?;
+        }
+        Ok(error)
+    }
+}
+
+impl From<&MessageSourceError> for IntlError {
+    fn from(value: &MessageSourceError) -> Self {
+        let code = match value {
+            MessageSourceError::ParseError(..) | MessageSourceError::SyntaxError(..) => {
+                IntlErrorCode::ParseError
+            }
+            MessageSourceError::DefinitionRestrictionViolated(_)
+            | MessageSourceError::TranslationRestrictionViolated(_)
+            | MessageSourceError::InvalidSourceFileMeta
+            | MessageSourceError::InvalidMessageMeta(_)
+            | MessageSourceError::NoMessageValue(_)
+            | MessageSourceError::UnknownFragment(..)
+            | MessageSourceError::NoMessagesFound => IntlErrorCode::InvalidSource,
+        };
+        let mut error = IntlError::new(code, value.to_string());
+        match value {
+            MessageSourceError::SyntaxError(_, _, position) => error.position = Some(*position),
+            MessageSourceError::NoMessageValue(key)
+            | MessageSourceError::InvalidMessageMeta(key)
+            | MessageSourceError::UnknownFragment(key, _) => error.key = Some(key.to_string()),
+            _ => {}
+        }
+        error
+    }
+}
+
+impl From<&DatabaseError> for IntlError {
+    fn from(value: &DatabaseError) -> Self {
+        let message = value.to_string();
+        match value {
+            DatabaseError::SourceError(error) => error.into(),
+            DatabaseError::ReadError(file_path, error) => {
+                let code = match error.kind() {
+                    std::io::ErrorKind::NotFound => IntlErrorCode::FileNotFound,
+                    _ => IntlErrorCode::IoError,
+                };
+                IntlError::new(code, message).with_file_path(file_path)
+            }
+            DatabaseError::NoExtractableValues(file_path)
+            | DatabaseError::NoSourceImplementation(file_path)
+            | DatabaseError::MismatchedSourceFileKind {
+                file_name: file_path,
+                ..
+            } => IntlError::new(IntlErrorCode::InvalidSource, message).with_file_path(file_path),
+            DatabaseError::AlreadyDefined(key) => {
+                IntlError::new(IntlErrorCode::DuplicateKey, message).with_key(key)
+            }
+            DatabaseError::TranslationAlreadySet(key, locale) => {
+                IntlError::new(IntlErrorCode::DuplicateKey, message)
+                    .with_key(key)
+                    .with_locale(locale)
+            }
+            DatabaseError::MissingTranslation(key, locale) => {
+                IntlError::new(IntlErrorCode::MissingTranslation, message)
+                    .with_key(key)
+                    .with_locale(locale)
+            }
+            DatabaseError::UnknownLocale(locale) => {
+                IntlError::new(IntlErrorCode::UnknownLocale, message).with_locale(locale)
+            }
+            DatabaseError::UnknownMessage(key) => {
+                IntlError::new(IntlErrorCode::UnknownMessage, message).with_key(key)
+            }
+            DatabaseError::UnknownProject(_) => {
+                IntlError::new(IntlErrorCode::UnknownProject, message)
+            }
+            DatabaseError::UnknownSourceFile(file_path) => {
+                IntlError::new(IntlErrorCode::UnknownSourceFile, message).with_file_path(file_path)
+            }
+            DatabaseError::UnknownMessageReference(key, _)
+            | DatabaseError::BlockMessageReference(key, _) => {
+                IntlError::new(IntlErrorCode::InvalidMessageReference, message).with_key(key)
+            }
+            DatabaseError::MessageReferenceCycle(cycle) => {
+                let error = IntlError::new(IntlErrorCode::InvalidMessageReference, message);
+                match cycle.first() {
+                    Some(key) => error.with_key(key),
+                    None => error,
+                }
+            }
+            DatabaseError::InvalidQueryPattern(_) => {
+                IntlError::new(IntlErrorCode::InvalidArgument, message)
+            }
+            DatabaseError::NoTransaction => IntlError::new(IntlErrorCode::NoTransaction, message),
+            DatabaseError::SymbolStorePoisonedError
+            | DatabaseError::SymbolNotFound(_)
+            | DatabaseError::ValueNotInterned(_) => {
+                IntlError::new(IntlErrorCode::Internal, message)
+            }
+        }
+    }
+}
+
+impl From<&RewriteError> for IntlError {
+    fn from(value: &RewriteError) -> Self {
+        let message = value.to_string();
+        match value {
+            RewriteError::InvalidEditRange(..)
+            | RewriteError::OverlappingEdits(..)
+            | RewriteError::InvalidPattern(_) => {
+                IntlError::new(IntlErrorCode::InvalidArgument, message)
+            }
+            RewriteError::UnsupportedRawText(key) => {
+                IntlError::new(IntlErrorCode::InvalidArgument, message).with_key(key)
+            }
+            RewriteError::UnknownPosition(key)
+            | RewriteError::ExpandedFragments(key)
+            | RewriteError::UnsupportedDefinition(key)
+            | RewriteError::OutdatedDefinition(key)
+            | RewriteError::UnexpectedValue(key) => {
+                IntlError::new(IntlErrorCode::InvalidSource, message).with_key(key)
+            }
+            RewriteError::ChangedFile(file_path) => {
+                IntlError::new(IntlErrorCode::InvalidSource, message).with_file_path(file_path)
+            }
+            RewriteError::RestoreFailed { .. } => IntlError::new(IntlErrorCode::IoError, message),
+        }
+    }
+}
+
+impl From<anyhow::Error> for IntlError {
+    fn from(value: anyhow::Error) -> Self {
+        if let Some(error) = value.downcast_ref::<DatabaseError>() {
+            return error.into();
+        }
+        if let Some(error) = value.downcast_ref::<MessageSourceError>() {
+            return error.into();
+        }
+        if let Some(error) = value.downcast_ref::<RewriteError>() {
+            return error.into();
+        }
+        if value.is::<ServiceCancelled>() {
+            return IntlError::new(IntlErrorCode::Cancelled, value.to_string());
+        }
+        if value.is::<UnknownSharedDatabase>() || value.is::<BundleDiffError>() {
+            return IntlError::new(IntlErrorCode::InvalidArgument, value.to_string());
+        }
+        // File discovery only returns these for exclude globs that can't be parsed.
+        if value.is::<ignore::Error>() {
+            return IntlError::new(IntlErrorCode::InvalidArgument, format!("{value:#}"));
+        }
+        if let Some(error) = value.downcast_ref::<std::io::Error>() {
+            let code = match error.kind() {
+                std::io::ErrorKind::NotFound => IntlErrorCode::FileNotFound,
+                _ => IntlErrorCode::IoError,
+            };
+            return IntlError::new(code, format!("{value:#}"));
+        }
+        IntlError::new(IntlErrorCode::Internal, format!("{value:#}"))
+    }
+}
+
+/// Convert the error of a public API result into an [IntlError] thrown in JS.
+pub(crate) trait OrThrow<T> {
+    fn or_throw(self, env: &Env) -> napi::Result<T>;
+    /// Like `or_throw`, attributing errors that don't already name a file to `file_path`.
+    fn or_throw_in_file(self, env: &Env, file_path: &str) -> napi::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> OrThrow<T> for Result<T, E> {
+    fn or_throw(self, env: &Env) -> napi::Result<T> {
+        self.map_err(|error| IntlError::from(error.into()).throw(env))
+    }
+
+    fn or_throw_in_file(self, env: &Env, file_path: &str) -> napi::Result<T> {
+        self.map_err(|error| {
+            let mut error = IntlError::from(error.into());
+            error.file_path.get_or_insert_with(|| file_path.to_string());
+            error.throw(env)
+        })
+    }
+}
@@ -8,41 +8,151 @@ use napi::bindgen_prelude::*;
 use napi::JsUnknown;
 use napi_derive::napi;
 use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+use crate::napi::custom_validator::{CustomValidatorCallback, JsCustomValidator};
+use crate::napi::error::OrThrow;
+use crate::napi::registry::SharedDatabase;
+use crate::napi::tasks::{
+    IntlCancellationToken, PrecompileTask, ProgressCallback, TaskState, ValidateMessagesTask,
+};
 use crate::napi::types::{
-    IntlDiagnostic, IntlMessageBundlerOptions, IntlMessagesFileDescriptor,
-    IntlMultiProcessingResult,
+    cst_nodes, message_edits, message_placeholder_renames, IntlBuildManifestEntry, IntlBundleDiff,
+    IntlCstNode, IntlDiagnostic, IntlDiagnosticSeverity, IntlExportResult,
+    IntlFileDiscoveryOptions, IntlFixedTranslation, IntlLocaleTagDiagnostic,
+    IntlMessageBundlerOptions, IntlMessageEdit, IntlMessageHistory, IntlMessageQuery,
+    IntlMessageWord, IntlMessagesDatabaseOptions, IntlMessagesFileDescriptor, IntlMisspelling,
+    IntlMultiProcessingResult, IntlParserOptions, IntlPerformanceReport, IntlPrecompiledBundle,
+    IntlProject, IntlReplacementOptions, IntlReplacementPreview, IntlReviewState,
+    IntlSecretRedaction, IntlTypesGeneratorOptions, IntlUntranslatedMessage, IntlValidationSummary,
+    IntlValidatorOptions, IntlVariableCompletion,
 };
 use crate::public;
 use crate::sources::MessagesFileDescriptor;
-use intl_database_core::MessagesDatabase;
+use intl_database_core::{MessageQuery, MessagesDatabase};
+use intl_database_exporter::SecretRedaction;
+use intl_database_service::ServiceContext;
+use intl_validator::{CustomValidators, DiagnosticSeverity, ValueValidatorConfig};
 
+mod custom_validator;
+mod error;
+mod registry;
+mod tasks;
 mod types;
 
 #[napi]
 pub struct IntlMessagesDatabase {
-    database: MessagesDatabase,
+    /// Shared with tasks that run on other threads, like `validateMessagesAsync`, and with other
+    /// instances opened from a shared token. Methods that change the database wait for any running
+    /// tasks to finish first.
+    database: SharedDatabase,
+    /// Validators registered with `registerValidator`, which only run in `validateMessagesAsync`.
+    custom_validators: RwLock<CustomValidators>,
+}
+
+impl IntlMessagesDatabase {
+    fn database(&self) -> RwLockReadGuard<'_, MessagesDatabase> {
+        self.database.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn database_mut(&self) -> RwLockWriteGuard<'_, MessagesDatabase> {
+        self.database
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn custom_validators(&self) -> CustomValidators {
+        self.custom_validators
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn task_state(
+        &self,
+        cancellation: Option<ClassInstance<IntlCancellationToken>>,
+        on_progress: Option<ProgressCallback>,
+    ) -> TaskState {
+        TaskState::new(
+            self.database.clone(),
+            cancellation.map(|cancellation| cancellation.token()),
+            on_progress,
+        )
+    }
 }
 
 #[napi]
 impl IntlMessagesDatabase {
     #[napi(constructor)]
-    pub fn new() -> Self {
+    pub fn new(options: Option<IntlMessagesDatabaseOptions>) -> Self {
+        let options = options.unwrap_or_default();
+        let mut database = public::create_database(
+            options.source_locale.as_deref(),
+            options.definitions_extensions.unwrap_or_default(),
+        );
+        public::set_handler_names(
+            &mut database,
+            options.hook_names,
+            options.link_handler_names,
+        );
+        if let Some(parser_options) = options.parser_options {
+            public::set_parser_options(&mut database, parser_options.apply_to(Default::default()));
+        }
         IntlMessagesDatabase {
-            database: MessagesDatabase::new(),
+            database: Arc::new(RwLock::new(database)),
+            custom_validators: Default::default(),
         }
     }
 
+    /// Open a database that another thread shared with `share`, like in a worker thread. Both
+    /// instances use the same underlying database, so changes made through either one are seen by
+    /// the other.
+    #[napi(factory)]
+    pub fn from_shared_token(env: Env, token: u32) -> Result<Self> {
+        let database = registry::open(token).or_throw(&env)?;
+        Ok(IntlMessagesDatabase {
+            database,
+            custom_validators: Default::default(),
+        })
+    }
+
+    /// Share this database with the other threads in the process, returning a token that
+    /// `IntlMessagesDatabase.fromSharedToken` opens it with. The token can be sent to workers with
+    /// `postMessage` or `workerData`. Sharing the same database again returns the same token.
+    ///
+    /// The token doesn't keep the database alive, so it can only be opened until every instance
+    /// using the database has been garbage collected. Changes wait for every call already in
+    /// progress on any thread to finish, so a change made while a worker is validating blocks until
+    /// the validation is done.
+    #[napi]
+    pub fn share(&self) -> u32 {
+        registry::share(&self.database)
+    }
+
+    /// Stop sharing this database, so its token can no longer be opened. Instances that were
+    /// already opened from the token keep working. Returns `false` if the database wasn't shared.
+    #[napi]
+    pub fn unshare(&self) -> bool {
+        registry::unshare(&self.database)
+    }
+
+    /// Scan the given `directories` for every messages file within them. `options` controls which
+    /// files and directories are visited, like excluding build output directories with globs.
     #[napi]
     pub fn find_all_messages_files(
         &mut self,
+        env: Env,
         directories: Vec<String>,
         default_definition_locale: String,
-    ) -> anyhow::Result<Vec<IntlMessagesFileDescriptor>> {
-        let sources = public::find_all_messages_files(
+        options: Option<IntlFileDiscoveryOptions>,
+    ) -> Result<Vec<IntlMessagesFileDescriptor>> {
+        let sources = public::find_all_messages_files_with_options(
+            &self.database(),
             directories.iter().map(String::as_str),
             &default_definition_locale,
-        );
+            &options.unwrap_or_default().into(),
+        )
+        .or_throw(&env)?;
         Ok(sources
             .into_iter()
             .map(IntlMessagesFileDescriptor::from)
@@ -54,8 +164,9 @@ impl IntlMessagesDatabase {
         &mut self,
         files: Vec<String>,
         default_definition_locale: String,
-    ) -> anyhow::Result<Vec<IntlMessagesFileDescriptor>> {
+    ) -> Result<Vec<IntlMessagesFileDescriptor>> {
         let sources = public::filter_all_messages_files(
+            &self.database(),
             files.iter().map(String::as_str),
             &default_definition_locale,
         );
@@ -68,181 +179,924 @@ impl IntlMessagesDatabase {
     #[napi]
     pub fn process_all_messages_files(
         &mut self,
+        env: Env,
         directories: Vec<IntlMessagesFileDescriptor>,
-    ) -> anyhow::Result<IntlMultiProcessingResult> {
+    ) -> Result<IntlMultiProcessingResult> {
         let sources = public::process_all_messages_files(
-            &mut self.database,
+            &mut self.database_mut(),
             directories.iter().map(MessagesFileDescriptor::from),
-        )?;
+        )
+        .or_throw(&env)?;
         Ok(sources.into())
     }
 
     #[napi]
     pub fn process_definitions_file(
         &mut self,
+        env: Env,
         file_path: String,
         locale: Option<String>,
-    ) -> anyhow::Result<String> {
+    ) -> Result<String> {
         let source_file = public::process_definitions_file(
-            &mut self.database,
+            &mut self.database_mut(),
             &file_path,
             locale.as_ref().map(String::as_str),
-        )?;
+        )
+        .or_throw_in_file(&env, &file_path)?;
         Ok(source_file.to_string())
     }
 
     #[napi]
     pub fn process_definitions_file_content(
         &mut self,
+        env: Env,
         file_path: String,
         content: String,
         locale: Option<String>,
-    ) -> anyhow::Result<String> {
+    ) -> Result<String> {
         let source_file = public::process_definitions_file_content(
-            &mut self.database,
+            &mut self.database_mut(),
             &file_path,
             &content,
             locale.as_ref().map(String::as_str),
-        )?;
+        )
+        .or_throw_in_file(&env, &file_path)?;
         Ok(source_file.to_string())
     }
 
     #[napi]
     pub fn process_all_translation_files(
         &mut self,
+        env: Env,
         locale_map: HashMap<String, String>,
-    ) -> anyhow::Result<IntlMultiProcessingResult> {
-        let result = public::process_all_translation_files(&mut self.database, locale_map)?;
+    ) -> Result<IntlMultiProcessingResult> {
+        let result = public::process_all_translation_files(&mut self.database_mut(), locale_map)
+            .or_throw(&env)?;
         Ok(result.into())
     }
 
     #[napi]
     pub fn process_translation_file(
         &mut self,
+        env: Env,
         file_path: String,
         locale: String,
-    ) -> anyhow::Result<String> {
+    ) -> Result<String> {
         let source_file =
-            public::process_translation_file(&mut self.database, &file_path, &locale)?;
+            public::process_translation_file(&mut self.database_mut(), &file_path, &locale)
+                .or_throw_in_file(&env, &file_path)?;
         Ok(source_file.to_string())
     }
 
     #[napi]
     pub fn process_translation_file_content(
         &mut self,
+        env: Env,
         file_path: String,
         locale: String,
         content: String,
-    ) -> anyhow::Result<String> {
+    ) -> Result<String> {
         let source_file = public::process_translation_file_content(
-            &mut self.database,
+            &mut self.database_mut(),
             &file_path,
             &locale,
             &content,
-        )?;
+        )
+        .or_throw_in_file(&env, &file_path)?;
+        Ok(source_file.to_string())
+    }
+
+    /// Recover translations for `locale` from a compiled `.compiled.messages.*` bundle, marking
+    /// them as machine-recovered. Only messages that are already defined can be recovered, since
+    /// bundles only contain hashed keys.
+    #[napi]
+    pub fn process_compiled_bundle_file(
+        &mut self,
+        env: Env,
+        file_path: String,
+        locale: String,
+    ) -> Result<String> {
+        let source_file =
+            public::process_compiled_bundle_file(&mut self.database_mut(), &file_path, &locale)
+                .or_throw_in_file(&env, &file_path)?;
         Ok(source_file.to_string())
     }
 
+    #[napi]
+    pub fn process_compiled_bundle_file_content(
+        &mut self,
+        env: Env,
+        file_path: String,
+        locale: String,
+        content: String,
+    ) -> Result<String> {
+        let source_file = public::process_compiled_bundle_file_content(
+            &mut self.database_mut(),
+            &file_path,
+            &locale,
+            &content,
+        )
+        .or_throw_in_file(&env, &file_path)?;
+        Ok(source_file.to_string())
+    }
+
+    /// Register a package in a monorepo as a project, so that queries, exports, and validation
+    /// can be limited to the messages it defines. Registering a project with the same name again
+    /// replaces it.
+    #[napi]
+    pub fn register_project(&mut self, project: IntlProject) {
+        public::register_project(
+            &mut self.database_mut(),
+            &project.name,
+            &project.root,
+            project.source_locale.as_deref(),
+            project.translations_path.as_deref(),
+        );
+    }
+
+    /// Return every registered project, sorted by name.
+    #[napi]
+    pub fn get_projects(&self) -> Vec<IntlProject> {
+        let database = self.database();
+        let projects = public::get_projects(&database);
+        projects.into_iter().map(IntlProject::from).collect()
+    }
+
+    /// Return the project that `filePath` belongs to, or null if it isn't inside of any of them.
+    #[napi]
+    pub fn get_project_for_file(&self, file_path: String) -> Option<IntlProject> {
+        public::get_project_for_file(&self.database(), &file_path).map(IntlProject::from)
+    }
+
+    /// Set the names of the hooks and link handlers that the app provides, which validation
+    /// checks every hook and link handler against. Names of either kind aren't checked when they
+    /// aren't given.
+    #[napi]
+    pub fn set_handler_names(
+        &self,
+        hook_names: Option<Vec<String>>,
+        link_handler_names: Option<Vec<String>>,
+    ) {
+        public::set_handler_names(&mut self.database_mut(), hook_names, link_handler_names);
+    }
+
+    /// Set the extensions of definitions files in addition to the built-in `.messages.js`
+    /// variants, replacing the `definitionsExtensions` the database was created with, for
+    /// databases that are created before their configuration is known.
+    #[napi]
+    pub fn set_definitions_extensions(&self, extensions: Vec<String>) {
+        public::set_definitions_extensions(&mut self.database_mut(), extensions);
+    }
+
+    /// Return the extensions of definitions files in addition to the built-in `.messages.js`
+    /// variants, to pass along to `isMessageDefinitionsFile` and build plugins.
+    #[napi]
+    pub fn get_definitions_extensions(&self) -> Vec<String> {
+        self.database().definitions_extensions.clone()
+    }
+
+    /// Change how messages are parsed, applying every option that's given on top of the current
+    /// ones. Messages that were already processed are parsed again with the new options the next
+    /// time they're needed, and cached validation results are dropped.
+    #[napi]
+    pub fn set_parser_options(&self, options: IntlParserOptions) {
+        let mut database = self.database_mut();
+        let parser_options = options.apply_to(database.parser_options().clone());
+        public::set_parser_options(&mut database, parser_options);
+    }
+
+    /// Return the locale that definitions are written in, unless their source file sets its own
+    /// `sourceLocale` in its meta.
+    #[napi]
+    pub fn get_source_locale(&self) -> String {
+        public::get_source_locale(&self.database()).to_string()
+    }
+
     #[napi]
     pub fn get_known_locales(&self) -> Vec<String> {
-        let locales = public::get_known_locales(&self.database);
+        let locales = public::get_known_locales(&self.database());
         Vec::from_iter(locales.into_iter().map(|locale| locale.to_string()))
     }
 
+    /// Return the known locale that `tag` refers to, matching any spelling of it, like `pt_br`
+    /// for `pt-BR`, or null if the database has no values in that locale.
+    #[napi]
+    pub fn resolve_locale(&self, tag: String) -> Option<String> {
+        public::resolve_locale(&self.database(), &tag).map(|locale| locale.to_string())
+    }
+
+    /// Report every translation file whose locale tag is not well-formed, or that spells its
+    /// locale differently from other files, like `en_GB` and `en-GB`.
+    #[napi]
+    pub fn validate_locale_tags(&self) -> Vec<IntlLocaleTagDiagnostic> {
+        public::validate_locale_tags(&self.database())
+            .into_iter()
+            .map(IntlLocaleTagDiagnostic::from)
+            .collect()
+    }
+
     #[napi(ts_return_type = "IntlSourceFile")]
-    pub fn get_source_file(&self, env: Env, file_path: String) -> anyhow::Result<JsUnknown> {
-        let source = public::get_source_file(&self.database, &file_path)?;
-        Ok(env.to_js_value(source)?)
+    pub fn get_source_file(&self, env: Env, file_path: String) -> Result<JsUnknown> {
+        let database = self.database();
+        let source = public::get_source_file(&database, &file_path).or_throw(&env)?;
+        env.to_js_value(source)
     }
 
     #[napi]
-    pub fn get_all_source_file_paths(&self) -> anyhow::Result<Vec<String>> {
-        let paths = public::get_all_source_file_paths(&self.database)?;
+    pub fn get_all_source_file_paths(&self, env: Env) -> Result<Vec<String>> {
+        let paths = public::get_all_source_file_paths(&self.database()).or_throw(&env)?;
         Ok(paths.into_iter().map(|path| path.to_string()).collect())
     }
 
     #[napi(ts_return_type = "Record<string, string>")]
     /// Return a map of all message keys contained in the given source file, where the key of the
     /// map is the hashed name and the value is the original.
-    pub fn get_source_file_key_map(
+    pub fn get_source_file_key_map(&self, env: Env, file_path: String) -> Result<JsUnknown> {
+        let hashes =
+            public::get_source_file_key_map(&self.database(), &file_path).or_throw(&env)?;
+        env.to_js_value(&hashes)
+    }
+
+    #[napi(ts_return_type = "IntlMessage")]
+    pub fn get_message(&self, env: Env, key: String) -> Result<JsUnknown> {
+        let database = self.database();
+        let definition = public::get_message(&database, &key).or_throw(&env)?;
+        env.to_js_value(definition)
+    }
+
+    /// Return the versions of the definition of the message `key` that have been processed, oldest
+    /// first, and which version each of its translations was written for.
+    #[napi]
+    pub fn get_message_history(&self, env: Env, key: String) -> Result<IntlMessageHistory> {
+        let database = self.database();
+        let history = public::get_message_history(&database, &key).or_throw(&env)?;
+        Ok(history.into())
+    }
+
+    /// Return every variable used in the source message of `key`, with the ways it is written in
+    /// that message, for editors to autocomplete while writing translations.
+    #[napi]
+    pub fn get_variable_completions(
         &self,
         env: Env,
-        file_path: String,
-    ) -> anyhow::Result<JsUnknown> {
-        let hashes = public::get_source_file_key_map(&self.database, &file_path)?;
-        Ok(env.to_js_value(&hashes)?)
+        key: String,
+    ) -> Result<Vec<IntlVariableCompletion>> {
+        let completions =
+            public::get_variable_completions(&self.database(), &key).or_throw(&env)?;
+        Ok(completions
+            .into_iter()
+            .map(IntlVariableCompletion::from)
+            .collect())
     }
 
-    #[napi(ts_return_type = "IntlMessage")]
-    pub fn get_message(&self, env: Env, key: String) -> anyhow::Result<JsUnknown> {
-        let definition = public::get_message(&self.database, &key)?;
-        Ok(env.to_js_value(definition)?)
+    /// Return the content hash of every defined message, keyed by the message key. A hash only
+    /// changes when the source value or meta of its message changes.
+    #[napi(ts_return_type = "Record<string, string>")]
+    pub fn get_content_hashes(&self, env: Env) -> Result<JsUnknown> {
+        env.to_js_value(&public::get_content_hashes(&self.database()))
+    }
+
+    /// Return the short alias of every defined message, mapped to its message key, for looking up
+    /// messages in bundles compiled with `IntlBundleKeyMode.Alias`.
+    #[napi(ts_return_type = "Record<string, string>")]
+    pub fn get_message_key_aliases(&self, env: Env) -> Result<JsUnknown> {
+        env.to_js_value(&public::get_message_key_aliases(&self.database()))
+    }
+
+    /// Return every defined message that has no translation in `locale`, sorted by key, leaving
+    /// out messages that are marked as `translate: false`.
+    #[napi]
+    pub fn get_untranslated_messages(
+        &self,
+        env: Env,
+        locale: String,
+    ) -> Result<Vec<IntlUntranslatedMessage>> {
+        let database = self.database();
+        let messages = public::get_untranslated_messages(&database, &locale).or_throw(&env)?;
+        Ok(messages
+            .into_iter()
+            .map(IntlUntranslatedMessage::from)
+            .collect())
+    }
+
+    /// Return the translation of the message `key` in `locale` as a standard ICU MessageFormat
+    /// string, with markdown written as the tags it compiles to.
+    #[napi]
+    pub fn get_message_icu(&self, env: Env, key: String, locale: String) -> Result<String> {
+        public::get_message_icu(&self.database(), &key, &locale).or_throw(&env)
+    }
+
+    #[napi(ts_return_type = "IntlMessageQueryResult")]
+    pub fn find_messages(&self, env: Env, query: Option<IntlMessageQuery>) -> Result<JsUnknown> {
+        let query: MessageQuery = query.unwrap_or_default().try_into().or_throw(&env)?;
+        let database = self.database();
+        let result = public::find_messages(&database, &query).or_throw(&env)?;
+        env.to_js_value(&result)
     }
 
     #[napi]
     pub fn generate_types(
         &self,
+        env: Env,
         source_file_path: String,
         output_file_path: String,
-    ) -> anyhow::Result<()> {
-        public::generate_types(&self.database, &source_file_path, &output_file_path)
+        options: Option<IntlTypesGeneratorOptions>,
+    ) -> Result<()> {
+        let mut options = options.unwrap_or_default();
+        let split = options.split.take();
+        let changed_keys = options.changed_keys.take();
+        match split {
+            Some(mode) => public::generate_split_types(
+                &self.database(),
+                &source_file_path,
+                &output_file_path,
+                mode.into(),
+                options.into(),
+            )
+            .map(|_| ())
+            .or_throw(&env),
+            None => public::generate_types(
+                &self.database(),
+                &source_file_path,
+                &output_file_path,
+                options.into(),
+                changed_keys.as_deref(),
+            )
+            .or_throw(&env),
+        }
     }
 
     #[napi]
     pub fn precompile(
         &self,
+        env: Env,
         file_path: String,
         locale: String,
         output_path: String,
         options: Option<IntlMessageBundlerOptions>,
-    ) -> anyhow::Result<()> {
-        public::precompile(
-            &self.database,
-            &file_path,
-            &locale,
-            &output_path,
-            options.unwrap_or_default().into(),
-        )
+    ) -> Result<()> {
+        let database = self.database();
+        let options = options
+            .unwrap_or_default()
+            .into_bundler_options(database.parser_options());
+        public::precompile(&database, &file_path, &locale, &output_path, options).or_throw(&env)
+    }
+
+    /// Precompile the given definitions file like `precompile`, on another thread so that the
+    /// event loop isn't blocked. `onProgress` is called with the number of messages bundled so
+    /// far. Nothing is written if the task is cancelled.
+    #[napi(
+        ts_args_type = "filePath: string, locale: string, outputPath: string, options?: IntlMessageBundlerOptions | undefined | null, cancellation?: IntlCancellationToken | undefined | null, onProgress?: ((progress: IntlServiceProgress) => void) | undefined | null"
+    )]
+    pub fn precompile_async(
+        &self,
+        file_path: String,
+        locale: String,
+        output_path: String,
+        options: Option<IntlMessageBundlerOptions>,
+        cancellation: Option<ClassInstance<IntlCancellationToken>>,
+        on_progress: Option<ProgressCallback>,
+    ) -> AsyncTask<PrecompileTask> {
+        AsyncTask::new(PrecompileTask {
+            state: self.task_state(cancellation, on_progress),
+            file_path,
+            locale,
+            output_path,
+            options: Some(
+                options
+                    .unwrap_or_default()
+                    .into_bundler_options(self.database().parser_options()),
+            ),
+        })
     }
 
     #[napi]
     pub fn precompile_to_buffer(
         &self,
+        env: Env,
         file_path: String,
         locale: String,
         options: Option<IntlMessageBundlerOptions>,
-    ) -> anyhow::Result<Buffer> {
-        let result = public::precompile_to_buffer(
-            &self.database,
-            &file_path,
-            &locale,
-            options.unwrap_or_default().into(),
-        )?;
+    ) -> Result<Buffer> {
+        let database = self.database();
+        let options = options
+            .unwrap_or_default()
+            .into_bundler_options(database.parser_options());
+        let result =
+            public::precompile_to_buffer(&database, &file_path, &locale, options).or_throw(&env)?;
         Ok(result.into())
     }
 
+    /// Compare two compiled bundles, like the bundles of a definitions file from two releases,
+    /// reporting every message that was added, removed, or changed along with how its size
+    /// changed. Each change is attributed to its message and definitions file in the database.
+    #[napi]
+    pub fn diff_bundles(&self, env: Env, before: Buffer, after: Buffer) -> Result<IntlBundleDiff> {
+        let diff = public::diff_bundles(&self.database(), &before, &after).or_throw(&env)?;
+        Ok(diff.into())
+    }
+
+    /// Precompile the given definitions file like `precompileToBuffer` and compare the result to
+    /// `previous`, a bundle of the same file and locale compiled earlier with the same options.
     #[napi]
-    pub fn validate_messages(&self) -> anyhow::Result<Vec<IntlDiagnostic>> {
-        let result = public::validate_messages(&self.database)?;
+    pub fn diff_precompiled_bundle(
+        &self,
+        env: Env,
+        file_path: String,
+        locale: String,
+        previous: Buffer,
+        options: Option<IntlMessageBundlerOptions>,
+    ) -> Result<IntlBundleDiff> {
+        let database = self.database();
+        let options = options
+            .unwrap_or_default()
+            .into_bundler_options(database.parser_options());
+        let diff =
+            public::diff_precompiled_bundle(&database, &file_path, &locale, &previous, options)
+                .or_throw(&env)?;
+        Ok(diff.into())
+    }
+
+    /// Precompile the given definitions file like `precompileToBuffer`, also returning every file
+    /// that the bundle depends on along with a hash of the content compiled from it, for use in
+    /// cache keys. This includes the translations file for `locale` even when it doesn't exist yet.
+    #[napi]
+    pub fn precompile_with_dependencies(
+        &self,
+        env: Env,
+        file_path: String,
+        locale: String,
+        options: Option<IntlMessageBundlerOptions>,
+    ) -> Result<IntlPrecompiledBundle> {
+        let database = self.database();
+        let options = options
+            .unwrap_or_default()
+            .into_bundler_options(database.parser_options());
+        let result = public::precompile_with_dependencies(&database, &file_path, &locale, options)
+            .or_throw(&env)?;
+        Ok(result.into())
+    }
+
+    /// Return everything needed to build each definitions file in the database, sorted by path:
+    /// its hashed message keys, the translations file for each locale, the path of the compiled
+    /// artifact for each locale, and a hash of each locale's content. `artifactExtension` sets the
+    /// extension of artifact names, defaulting to `json`.
+    #[napi]
+    pub fn get_build_manifest(
+        &self,
+        artifact_extension: Option<String>,
+    ) -> Vec<IntlBuildManifestEntry> {
+        public::get_build_manifest(&self.database(), artifact_extension.as_deref())
+            .into_iter()
+            .map(IntlBuildManifestEntry::from)
+            .collect()
+    }
+
+    #[napi]
+    pub fn validate_messages(
+        &self,
+        env: Env,
+        options: Option<IntlValidatorOptions>,
+    ) -> Result<Vec<IntlDiagnostic>> {
+        let mut options = options.unwrap_or_default();
+        let project = options.project.take();
+        let baseline = options.baseline.take();
+        let database = self.database();
+        let mut result = public::validate_messages_with_context(
+            &database,
+            &options.try_into().or_throw(&env)?,
+            project.as_deref(),
+            &ServiceContext::default(),
+        )
+        .or_throw(&env)?;
+        if let Some(baseline) = baseline {
+            result = public::filter_baselined_diagnostics(&database, result, &baseline)
+                .or_throw(&env)?;
+        }
+        Ok(result.into_iter().map(IntlDiagnostic::from).collect())
+    }
+
+    /// Return the words in the translation of the message `key` in `locale` that should be spell
+    /// checked, leaving out placeholders, code, urls, and other text that isn't spelled like words.
+    #[napi]
+    pub fn get_message_words(
+        &self,
+        env: Env,
+        key: String,
+        locale: String,
+    ) -> Result<Vec<IntlMessageWord>> {
+        let words = public::get_message_words(&self.database(), &key, &locale).or_throw(&env)?;
+        Ok(words.into_iter().map(IntlMessageWord::from).collect())
+    }
+
+    /// Return the concrete syntax tree of the translation of the message `key` in `locale`, as a
+    /// flat list of every node and token in the order they are written, where each node is
+    /// followed by everything inside of it. Unlike the parsed AST, this keeps all of the syntax of
+    /// the message, like delimiters and the exact text of each token.
+    #[napi]
+    pub fn get_message_cst(
+        &self,
+        env: Env,
+        key: String,
+        locale: String,
+    ) -> Result<Vec<IntlCstNode>> {
+        let database = self.database();
+        let cst = public::get_message_cst(&database, &key, &locale).or_throw(&env)?;
+        Ok(cst_nodes(cst))
+    }
+
+    /// Call `visit` with each node and token of the syntax tree from `getMessageCst`, in the order
+    /// they are written. Returning `false` from `visit` skips everything inside of that node.
+    #[napi(
+        ts_args_type = "key: string, locale: string, visit: (node: IntlCstNode) => boolean | void"
+    )]
+    pub fn walk_message_cst(
+        &self,
+        env: Env,
+        key: String,
+        locale: String,
+        visit: Function<IntlCstNode, Option<bool>>,
+    ) -> Result<()> {
+        // The database isn't kept locked while calling `visit`, so that it can still be changed.
+        let nodes = self.get_message_cst(env, key, locale)?;
+        let mut skipped_depth = None;
+        for node in nodes {
+            if skipped_depth.is_some_and(|depth| node.depth > depth) {
+                continue;
+            }
+            let depth = node.depth;
+            skipped_depth = match visit.call(node)? {
+                Some(false) => Some(depth),
+                _ => None,
+            };
+        }
+        Ok(())
+    }
+
+    /// Report every occurrence of the words in `misspellings`, which a spell checker found to be
+    /// misspelled in `locale`, as a `NoMisspelledWords` diagnostic. When `project` is given, only
+    /// the messages defined in that project are checked.
+    #[napi]
+    pub fn validate_misspelled_words(
+        &self,
+        env: Env,
+        locale: String,
+        misspellings: Vec<IntlMisspelling>,
+        project: Option<String>,
+    ) -> Result<Vec<IntlDiagnostic>> {
+        let misspellings = misspellings.into_iter().map(|misspelling| {
+            (
+                misspelling.word,
+                misspelling.suggestions.unwrap_or_default(),
+            )
+        });
+        let result = public::validate_misspelled_words(
+            &self.database(),
+            &locale,
+            misspellings,
+            project.as_deref(),
+        )
+        .or_throw(&env)?;
         Ok(result.into_iter().map(IntlDiagnostic::from).collect())
     }
 
+    /// Remove every cached diagnostic, so that the next validation checks every message again.
+    /// Validation results are cached for each translation that hasn't changed, and the cache is
+    /// already cleared whenever different validator options are used.
+    #[napi]
+    pub fn clear_validation_cache(&self) {
+        public::clear_validation_cache(&self.database());
+    }
+
+    /// Validate every message like `validateMessages`, returning the diagnostics as a SARIF 2.1
+    /// log for code scanning tools to show inline in pull requests. Files inside of `root`, usually
+    /// the root of the repository, are written relative to it.
+    #[napi]
+    pub fn validate_messages_as_sarif(
+        &self,
+        env: Env,
+        options: Option<IntlValidatorOptions>,
+        root: Option<String>,
+    ) -> Result<String> {
+        let mut options = options.unwrap_or_default();
+        let project = options.project.take();
+        let baseline = options.baseline.take();
+        let database = self.database();
+        let mut diagnostics = public::validate_messages_with_context(
+            &database,
+            &options.try_into().or_throw(&env)?,
+            project.as_deref(),
+            &ServiceContext::default(),
+        )
+        .or_throw(&env)?;
+        if let Some(baseline) = baseline {
+            diagnostics = public::filter_baselined_diagnostics(&database, diagnostics, &baseline)
+                .or_throw(&env)?;
+        }
+        public::format_diagnostics_as_sarif(&diagnostics, root.as_deref()).or_throw(&env)
+    }
+
+    /// Validate every message like `validateMessages`, on another thread so that the event loop
+    /// isn't blocked. `onProgress` is called with the number of messages validated so far.
+    #[napi(
+        ts_args_type = "options?: IntlValidatorOptions | undefined | null, cancellation?: IntlCancellationToken | undefined | null, onProgress?: ((progress: IntlServiceProgress) => void) | undefined | null"
+    )]
+    pub fn validate_messages_async(
+        &self,
+        env: Env,
+        options: Option<IntlValidatorOptions>,
+        cancellation: Option<ClassInstance<IntlCancellationToken>>,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<AsyncTask<ValidateMessagesTask>> {
+        let mut options = options.unwrap_or_default();
+        let project = options.project.take();
+        let baseline = options.baseline.take();
+        Ok(AsyncTask::new(ValidateMessagesTask {
+            state: self.task_state(cancellation, on_progress),
+            project,
+            baseline,
+            config: ValueValidatorConfig::try_from(options)
+                .or_throw(&env)?
+                .with_custom_validators(self.custom_validators()),
+        }))
+    }
+
+    /// Add a validator named `name` that runs on every translation in `validateMessagesAsync`,
+    /// replacing any validator that was already registered with the same name. `validate` is called
+    /// with each translation and returns the problems it finds, which are reported as diagnostics
+    /// with the validator's name.
+    ///
+    /// Validation waits for `validate` to run on the main thread, so registered validators don't
+    /// run in the other validation methods, which block the main thread. `validate` must return
+    /// its diagnostics synchronously, and must not change the database, which waits for validation
+    /// to finish. Changing the registered validators clears the validation cache.
+    #[napi(
+        ts_args_type = "name: string, validate: (input: IntlCustomValidatorInput) => Array<IntlCustomDiagnostic> | undefined | null"
+    )]
+    pub fn register_validator(&self, name: String, validate: CustomValidatorCallback) {
+        let mut validators = self
+            .custom_validators
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        validators.remove(&name);
+        let validator = Arc::new(JsCustomValidator::new(name, validate));
+        *validators = std::mem::take(&mut *validators).with_validator(validator);
+    }
+
+    /// Remove the validator that was registered with `name`. Returns `false` if there wasn't one.
+    #[napi]
+    pub fn unregister_validator(&self, name: String) -> bool {
+        self.custom_validators
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&name)
+    }
+
+    /// Validate every message and summarize the results for CI, with counts of diagnostics by
+    /// severity and by rule. The summary fails when any diagnostic is at least as severe as
+    /// `failOn`, which defaults to `Error`. Diagnostics recorded in the `baseline` option aren't
+    /// counted.
+    #[napi]
+    pub fn summarize_validation(
+        &self,
+        env: Env,
+        options: Option<IntlValidatorOptions>,
+        fail_on: Option<IntlDiagnosticSeverity>,
+    ) -> Result<IntlValidationSummary> {
+        let mut options = options.unwrap_or_default();
+        let project = options.project.take();
+        let baseline = options.baseline.take();
+        let summary = public::summarize_validation(
+            &self.database(),
+            &options.try_into().or_throw(&env)?,
+            project.as_deref(),
+            baseline.as_deref(),
+            fail_on.map_or(DiagnosticSeverity::Error, Into::into),
+        )
+        .or_throw(&env)?;
+        Ok(summary.into())
+    }
+
+    /// Record every diagnostic that validating the database currently reports in a baseline file
+    /// at `baselinePath`, replacing any existing baseline. Validating with the `baseline` option
+    /// afterward only reports new diagnostics, so that new rules can be turned on before every
+    /// existing problem is fixed. Returns the number of entries written to the baseline.
+    #[napi]
+    pub fn write_validation_baseline(
+        &self,
+        env: Env,
+        baseline_path: String,
+        options: Option<IntlValidatorOptions>,
+    ) -> Result<u32> {
+        let mut options = options.unwrap_or_default();
+        let project = options.project.take();
+        let entries = public::write_validation_baseline(
+            &self.database(),
+            &options.try_into().or_throw(&env)?,
+            project.as_deref(),
+            &baseline_path,
+        )
+        .or_throw(&env)?;
+        Ok(entries as u32)
+    }
+
+    /// Write every translation in the database to its translation file, skipping files whose
+    /// content wouldn't change. With `dryRun`, no files are written, and the result only reports
+    /// which files would change.
+    ///
+    /// For vendors that can't handle nested plurals and selects, messages with more than
+    /// `expandCombinationsAbove` combinations of arms are written as one entry per combination,
+    /// like `KEY[count=one,gender=female]`. These can't be imported back as translations.
+    ///
+    /// When `project` is given, only the translations of messages defined in that project are
+    /// exported.
+    ///
+    /// `placeholderRenames` renames the placeholder variables of individual messages in the
+    /// written translations, keyed by message key and then by their current names, like to match
+    /// renames applied while bundling with `messagePlaceholderRenames`.
+    ///
+    /// Exports that redact secrets, filter by review state, expand combinations, or rename
+    /// placeholders can't replace the real translation files, so they must be written into a
+    /// separate `outputDirectory`, where each file keeps its path relative to the current working
+    /// directory.
     #[napi]
     pub fn export_translations(
         &self,
+        env: Env,
         file_extension: Option<String>,
-    ) -> anyhow::Result<Vec<String>> {
-        public::export_translations(&self.database, file_extension)
+        secret_redaction: Option<IntlSecretRedaction>,
+        review_states: Option<Vec<IntlReviewState>>,
+        dry_run: Option<bool>,
+        expand_combinations_above: Option<u32>,
+        project: Option<String>,
+        placeholder_renames: Option<HashMap<String, HashMap<String, String>>>,
+        output_directory: Option<String>,
+    ) -> Result<IntlExportResult> {
+        let placeholder_renames = placeholder_renames.map(message_placeholder_renames);
+        let result = public::export_translations(
+            &self.database(),
+            file_extension,
+            secret_redaction.map_or(SecretRedaction::Keep, Into::into),
+            review_states.map(|states| states.into_iter().map(Into::into).collect()),
+            dry_run.unwrap_or(false),
+            expand_combinations_above.map(|threshold| threshold as usize),
+            project.as_deref(),
+            placeholder_renames.as_ref(),
+            output_directory.as_deref(),
+        )
+        .or_throw(&env)?;
+        Ok(result.into())
     }
 
-    #[napi(ts_return_type = "Record<string, IntlMessageValue | undefined>")]
-    pub fn get_source_file_message_values(
+    /// Set the review state of the translation of `key` in `locale`, like when a reviewer approves
+    /// it. The new state is written to the translation files the next time they are exported.
+    #[napi]
+    pub fn set_translation_review_state(
+        &mut self,
+        env: Env,
+        key: String,
+        locale: String,
+        review_state: IntlReviewState,
+    ) -> Result<()> {
+        public::set_translation_review_state(
+            &mut self.database_mut(),
+            &key,
+            &locale,
+            review_state.into(),
+        )
+        .or_throw(&env)
+    }
+
+    /// Remove stray zero-width characters from the translations in `locale`, or every locale if not
+    /// given, and normalize them to NFC. Neither changes how a translation is displayed. Returns
+    /// every translation that was fixed, which are written to the translation files the next time
+    /// they are exported.
+    #[napi]
+    pub fn fix_translation_unicode(
+        &mut self,
+        env: Env,
+        locale: Option<String>,
+    ) -> Result<Vec<IntlFixedTranslation>> {
+        let fixed = public::fix_translation_unicode(&mut self.database_mut(), locale.as_deref())
+            .or_throw(&env)?;
+        Ok(fixed
+            .into_iter()
+            .map(|(key, locale)| IntlFixedTranslation {
+                key: key.to_string(),
+                locale: locale.to_string(),
+            })
+            .collect())
+    }
+
+    /// Apply `edits` to the source content of the message `key` by rewriting its definition in its
+    /// source file, then process the file again. The inserted text is escaped as needed for the
+    /// string it's written into, and nothing else in the file changes. If anything fails, the file
+    /// and the database are both left as they were. Returns the diagnostics for the rewritten
+    /// message.
+    #[napi]
+    pub fn rewrite_message(
+        &mut self,
+        env: Env,
+        key: String,
+        edits: Vec<IntlMessageEdit>,
+        options: Option<IntlValidatorOptions>,
+    ) -> Result<Vec<IntlDiagnostic>> {
+        let config = options.unwrap_or_default().try_into().or_throw(&env)?;
+        let mut database = self.database_mut();
+        let message = public::get_message(&database, &key).or_throw(&env)?;
+        let raw = message
+            .get_source_translation()
+            .map_or("", |value| value.raw.as_str());
+        let edits = message_edits(raw, edits);
+        let result = public::rewrite_message(&mut database, &key, edits, &config).or_throw(&env)?;
+        Ok(result.into_iter().map(IntlDiagnostic::from).collect())
+    }
+
+    /// Plan the replacement of every match of the regular expression `pattern` with `replacement`
+    /// in the values of messages, both definitions and translations. `replacement` can use the
+    /// capture groups of the pattern like `$1`. Nothing is changed until the preview is given to
+    /// `applyReplacements`, so it can be reviewed first.
+    #[napi]
+    pub fn preview_replacements(
         &self,
         env: Env,
-        file_path: String,
-    ) -> anyhow::Result<JsUnknown> {
-        let result = public::get_source_file_message_values(&self.database, &file_path)?;
-        Ok(env.to_js_value(&result)?)
+        pattern: String,
+        replacement: String,
+        options: Option<IntlReplacementOptions>,
+    ) -> Result<IntlReplacementPreview> {
+        let options = options.unwrap_or_default();
+        let preview = public::preview_message_replacements(
+            &self.database(),
+            &pattern,
+            &replacement,
+            options.locales.unwrap_or_default(),
+            options.keys.unwrap_or_default(),
+            &ServiceContext::default(),
+        )
+        .or_throw(&env)?;
+        Ok(preview.into())
+    }
+
+    /// Write every file changed by a preview from `previewReplacements` and process them again,
+    /// all at once. Throws if any of the files changed since the preview was made. If anything
+    /// fails, every file and the database are left as they were.
+    #[napi]
+    pub fn apply_replacements(&mut self, env: Env, preview: IntlReplacementPreview) -> Result<()> {
+        let files: Vec<_> = preview.files.into_iter().map(Into::into).collect();
+        public::apply_message_replacements(&mut self.database_mut(), &files).or_throw(&env)
+    }
+
+    /// Begin a transaction, so that every change to the database until it's committed can be
+    /// undone at once with `rollbackTransaction`. Transactions can be nested. Each one copies
+    /// every message in the database, so wrap whole batches of changes rather than single ones.
+    #[napi]
+    pub fn begin_transaction(&mut self) {
+        public::begin_transaction(&mut self.database_mut());
+    }
+
+    /// Keep every change made since the innermost transaction began and end it. Throws a
+    /// `NoTransaction` error if no transaction is in progress.
+    #[napi]
+    pub fn commit_transaction(&mut self, env: Env) -> Result<()> {
+        public::commit_transaction(&mut self.database_mut()).or_throw(&env)
+    }
+
+    /// Undo every change made since the innermost transaction began and end it. Files that were
+    /// written during the transaction aren't changed back. Throws a `NoTransaction` error if no
+    /// transaction is in progress.
+    #[napi]
+    pub fn rollback_transaction(&mut self, env: Env) -> Result<()> {
+        public::rollback_transaction(&mut self.database_mut()).or_throw(&env)
+    }
+
+    /// How many transactions are in progress, counting each nested one.
+    #[napi]
+    pub fn get_transaction_depth(&self) -> u32 {
+        public::get_transaction_depth(&self.database()) as u32
+    }
+
+    #[napi(ts_return_type = "Record<string, IntlMessageValue | undefined>")]
+    pub fn get_source_file_message_values(&self, env: Env, file_path: String) -> Result<JsUnknown> {
+        let database = self.database();
+        let result =
+            public::get_source_file_message_values(&database, &file_path).or_throw(&env)?;
+        env.to_js_value(&result)
+    }
+
+    /// Enable or disable recording how long each message takes to parse and compile, and how long
+    /// each rule takes while validating. Messages are parsed as soon as they are processed while
+    /// recording is enabled.
+    #[napi]
+    pub fn set_performance_recording(&mut self, enabled: bool) {
+        public::set_performance_recording(&mut self.database_mut(), enabled);
+    }
+
+    /// Return the slowest messages to parse or compile since performance recording was enabled,
+    /// along with the total time spent on each source file and on each rule during the most recent
+    /// validation. `limit` defaults to 20 messages.
+    #[napi]
+    pub fn get_performance_report(&self, limit: Option<u32>) -> IntlPerformanceReport {
+        public::get_performance_report(&self.database(), limit.unwrap_or(20) as usize).into()
     }
 }
 
@@ -251,12 +1105,26 @@ pub fn hash_message_key(key: String) -> String {
     public::hash_message_key(&key)
 }
 
+/// Returns true if `key` is a definitions file.
+///
+/// Definitions files have one of the built-in `.messages.js` extensions or any of
+/// `definitionsExtensions`, which should be the same extensions the database was created with.
+/// Imports can leave out the last part of an extension, like `Feature.messages` or
+/// `Feature.strings` for `.strings.js`.
 #[napi]
-pub fn is_message_definitions_file(key: String) -> bool {
-    public::is_message_definitions_file(&key)
+pub fn is_message_definitions_file(
+    key: String,
+    definitions_extensions: Option<Vec<String>>,
+) -> bool {
+    public::is_message_definitions_file(&key, &definitions_extensions.unwrap_or_default())
 }
 
 #[napi]
 pub fn is_message_translations_file(key: String) -> bool {
     public::is_message_translations_file(&key)
 }
+
+#[napi]
+pub fn normalize_locale_tag(tag: String) -> Option<String> {
+    public::normalize_locale_tag(&tag)
+}
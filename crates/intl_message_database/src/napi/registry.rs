@@ -0,0 +1,121 @@
+//! A process-wide registry of databases that are shared between Node worker threads. JS objects
+//! can't be passed between workers, but every worker in a process loads the same native module, so
+//! a database registered here can be opened by its token from any of them.
+//!
+//! The registry only holds weak references, so it never keeps a database alive by itself. Once
+//! every instance using a database has been garbage collected, the database is dropped and its
+//! token can no longer be opened, even if it was never unshared.
+//!
+//! Every instance opened from a token uses the same underlying database behind a single
+//! [RwLock], and each method holds the lock for the duration of the call. Methods that only read,
+//! like validating or compiling, take a shared lock, so workers can run them in parallel. Methods
+//! that change the database take an exclusive lock, so they wait for every call already in
+//! progress on any thread to finish, and calls made while they wait are blocked until they are
+//! done. A change made on the main thread while a worker is validating blocks the main thread's
+//! event loop until the validation finishes, so long reads are best left to async tasks or made
+//! while no changes are expected.
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError, RwLock, Weak};
+
+use intl_database_core::MessagesDatabase;
+use rustc_hash::FxHashMap;
+
+pub type SharedDatabase = Arc<RwLock<MessagesDatabase>>;
+
+static SHARED_DATABASES: LazyLock<Mutex<FxHashMap<u32, Weak<RwLock<MessagesDatabase>>>>> =
+    LazyLock::new(Default::default);
+static NEXT_TOKEN: AtomicU32 = AtomicU32::new(1);
+
+/// The error returned when opening a token that no database is currently shared with.
+#[derive(Debug)]
+pub struct UnknownSharedDatabase(pub u32);
+
+impl std::fmt::Display for UnknownSharedDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No database is shared with the token {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownSharedDatabase {}
+
+/// Lock the registry, first removing the databases that have been dropped since.
+fn shared_databases() -> MutexGuard<'static, FxHashMap<u32, Weak<RwLock<MessagesDatabase>>>> {
+    let mut shared = SHARED_DATABASES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    shared.retain(|_, database| database.strong_count() > 0);
+    shared
+}
+
+/// Register `database` to be opened from other threads, returning its token. Sharing a database
+/// that is already registered returns its existing token. The registry doesn't keep the database
+/// alive, so the token only works for as long as some instance still uses it.
+pub fn share(database: &SharedDatabase) -> u32 {
+    let mut shared = shared_databases();
+    if let Some((token, _)) = shared
+        .iter()
+        .find(|(_, existing)| std::ptr::eq(existing.as_ptr(), Arc::as_ptr(database)))
+    {
+        return *token;
+    }
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    shared.insert(token, Arc::downgrade(database));
+    token
+}
+
+/// Return the database shared with `token`, if it was shared and hasn't been dropped since.
+pub fn open(token: u32) -> Result<SharedDatabase, UnknownSharedDatabase> {
+    shared_databases()
+        .get(&token)
+        .and_then(Weak::upgrade)
+        .ok_or(UnknownSharedDatabase(token))
+}
+
+/// Remove `database` from the registry, returning whether it was shared. Instances that were
+/// already opened from its token keep working.
+pub fn unshare(database: &SharedDatabase) -> bool {
+    let mut shared = shared_databases();
+    let count = shared.len();
+    shared.retain(|_, existing| !std::ptr::eq(existing.as_ptr(), Arc::as_ptr(database)));
+    shared.len() != count
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_database() -> SharedDatabase {
+        Arc::new(RwLock::new(MessagesDatabase::new()))
+    }
+
+    #[test]
+    fn test_share_and_open() {
+        let database = new_database();
+        let token = share(&database);
+        assert_eq!(share(&database), token);
+        assert!(Arc::ptr_eq(&open(token).unwrap(), &database));
+
+        let other = new_database();
+        assert_ne!(share(&other), token);
+
+        assert!(unshare(&database));
+        assert!(!unshare(&database));
+        assert!(open(token).is_err());
+        assert!(unshare(&other));
+    }
+
+    #[test]
+    fn test_registry_does_not_keep_databases_alive() {
+        let database = new_database();
+        let token = share(&database);
+        let opened = open(token).unwrap();
+        let weak = Arc::downgrade(&database);
+
+        drop(database);
+        assert!(open(token).is_ok());
+        drop(opened);
+        assert!(weak.upgrade().is_none());
+        assert!(matches!(open(token), Err(UnknownSharedDatabase(t)) if t == token));
+        assert!(shared_databases().get(&token).is_none());
+    }
+}
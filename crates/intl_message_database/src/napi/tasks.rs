@@ -0,0 +1,182 @@
+//! Long-running operations that run on the libuv thread pool rather than blocking the event loop.
+//! Each task returns a promise, reports its progress through an optional callback, and can be
+//! stopped early with an [IntlCancellationToken], which rejects the promise with a `Cancelled`
+//! error.
+//!
+//! Tasks hold a shared reference to the database while they run, so methods that change the
+//! database wait for them to finish, while methods that only read from it can still be called.
+use std::sync::{Arc, PoisonError, RwLock};
+
+use intl_database_core::MessagesDatabase;
+use intl_database_exporter::IntlMessageBundlerOptions;
+use intl_database_service::{CancellationToken, ServiceContext};
+use intl_validator::{MessageDiagnostic, ValueValidatorConfig};
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+
+use crate::napi::error::IntlError;
+use crate::napi::types::{IntlDiagnostic, IntlServiceProgress};
+use crate::public;
+
+/// A callback given by JS that receives progress updates from a task.
+pub type ProgressCallback =
+    ThreadsafeFunction<IntlServiceProgress, Unknown, IntlServiceProgress, false>;
+
+/// A token for cancelling async tasks, like `validateMessagesAsync`. The same token can be given
+/// to any number of tasks, and cancelling it stops all of them.
+#[napi]
+#[derive(Default)]
+pub struct IntlCancellationToken {
+    token: CancellationToken,
+}
+
+#[napi]
+impl IntlCancellationToken {
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop every task using this token as soon as possible. Tasks that are stopped reject with a
+    /// `Cancelled` error. Cancelling can't be undone, so a new token is needed for later tasks.
+    #[napi]
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    #[napi(getter)]
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+impl IntlCancellationToken {
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+/// The state shared by every task: the database it reads from, and how it reports back to JS.
+pub struct TaskState {
+    database: Arc<RwLock<MessagesDatabase>>,
+    cancellation: CancellationToken,
+    on_progress: Option<ProgressCallback>,
+    /// The failure from `compute`, kept so that `reject` can create the full JS error for it.
+    error: Option<IntlError>,
+}
+
+impl TaskState {
+    pub fn new(
+        database: Arc<RwLock<MessagesDatabase>>,
+        cancellation: Option<CancellationToken>,
+        on_progress: Option<ProgressCallback>,
+    ) -> Self {
+        Self {
+            database,
+            cancellation: cancellation.unwrap_or_default(),
+            on_progress,
+            error: None,
+        }
+    }
+
+    /// Run `operation` with the database and a context for reporting progress, keeping any error
+    /// it returns for `reject`.
+    fn run<T>(
+        &mut self,
+        operation: impl FnOnce(&MessagesDatabase, &ServiceContext) -> anyhow::Result<T>,
+    ) -> Result<T> {
+        let database = self.database.read().unwrap_or_else(PoisonError::into_inner);
+        let mut context = ServiceContext::new().with_cancellation(self.cancellation.clone());
+        if let Some(on_progress) = &self.on_progress {
+            context = context.with_progress(|progress| {
+                on_progress.call(progress.into(), ThreadsafeFunctionCallMode::NonBlocking);
+            });
+        }
+        operation(&database, &context).map_err(|error| {
+            let error = IntlError::from(error);
+            let reason = error.message.clone();
+            self.error = Some(error);
+            Error::from_reason(reason)
+        })
+    }
+
+    fn reject(&mut self, env: &Env, error: Error) -> Error {
+        match self.error.take() {
+            Some(error) => error.into_rejection(env),
+            None => error,
+        }
+    }
+}
+
+pub struct ValidateMessagesTask {
+    pub state: TaskState,
+    pub config: ValueValidatorConfig,
+    pub project: Option<String>,
+    pub baseline: Option<String>,
+}
+
+impl Task for ValidateMessagesTask {
+    type Output = Vec<MessageDiagnostic>;
+    type JsValue = Vec<IntlDiagnostic>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let (config, project) = (&self.config, self.project.as_deref());
+        let baseline = self.baseline.as_deref();
+        self.state.run(|database, context| {
+            let diagnostics =
+                public::validate_messages_with_context(database, config, project, context)?;
+            match baseline {
+                Some(baseline) => {
+                    public::filter_baselined_diagnostics(database, diagnostics, baseline)
+                }
+                None => Ok(diagnostics),
+            }
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into_iter().map(IntlDiagnostic::from).collect())
+    }
+
+    fn reject(&mut self, env: Env, error: Error) -> Result<Self::JsValue> {
+        Err(self.state.reject(&env, error))
+    }
+}
+
+pub struct PrecompileTask {
+    pub state: TaskState,
+    pub file_path: String,
+    pub locale: String,
+    pub output_path: String,
+    /// Taken when the task runs, since the bundler takes ownership of its options.
+    pub options: Option<IntlMessageBundlerOptions>,
+}
+
+impl Task for PrecompileTask {
+    type Output = ();
+    type JsValue = ();
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let options = self.options.take().unwrap_or_default();
+        let (file_path, locale, output_path) = (&self.file_path, &self.locale, &self.output_path);
+        self.state.run(|database, context| {
+            public::precompile_with_context(
+                database,
+                file_path,
+                locale,
+                output_path,
+                options,
+                context,
+            )
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, _output: Self::Output) -> Result<Self::JsValue> {
+        Ok(())
+    }
+
+    fn reject(&mut self, env: Env, error: Error) -> Result<Self::JsValue> {
+        Err(self.state.reject(&env, error))
+    }
+}
@@ -1,34 +1,489 @@
-use crate::public::MultiProcessingResult;
-use crate::sources::MessagesFileDescriptor;
-use intl_database_core::key_symbol;
-use intl_database_exporter::CompiledMessageFormat;
-use intl_validator::MessageDiagnostic;
+use crate::napi::error::{IntlError, IntlErrorCode};
+use crate::public::{
+    BuildManifestEntry, LocaleTagDiagnostic, LocaleTagProblem, MessageCst, MessageHistory,
+    MultiProcessingResult, PrecompiledBundle, TranslationVersion, UntranslatedMessage,
+    VariableCompletion,
+};
+use crate::sources::replace::{FileReplacement, ReplacedMessage, ReplacementPreview};
+use crate::sources::rewrite::MessageEdit;
+use crate::sources::{MessagesFileDescriptor, MessagesFileDiscoveryOptions};
+use intl_database_core::{
+    key_symbol, DatabaseError, DatabaseResult, FileTiming, MessageQuery, MessageQueryPattern,
+    MessageRevision, MessageTiming, PerformancePhase, PerformanceReport, Project, ReviewState,
+    RuleTiming,
+};
+use intl_database_exporter::{
+    BidiIsolation, BundleChangeKind, BundleDiff, BundleEntryChange, BundleKeyMode, CjkWordBreaks,
+    CompiledMessageFormat, ExportedTranslationFiles, MessagePlaceholderRenames, NormalizeUnicode,
+    RenamePlaceholders, SecretRedaction, SmartQuotes, SubstituteConstants, TransformPipeline,
+};
+use intl_database_service::ServiceProgress;
+use intl_database_types_generator::{TypesOutputFormat, TypesSplitMode};
+use intl_markdown::{ApostropheMode, ConstantValue, ParserOptions};
+use intl_validator::{
+    CjkPlaceholderSpacing, DiagnosticSeverity, DiagnosticSpan, MessageDiagnostic, MessageWord,
+    NoConcatenatedFragmentsOptions, NoExcessiveComplexityOptions, NoInconsistentCjkSpacingOptions,
+    NoMismatchedPluralFormsOptions, NoStyleViolationsOptions, SeverityCounts, StyleRule,
+    ValidationSummary, ValueValidatorConfig,
+};
+use keyless_json::SerializeOptions;
+use napi::bindgen_prelude::{Buffer, Either};
 use napi::{JsNumber, JsObject};
 use napi_derive::napi;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlMessagesDatabaseOptions {
+    /// The locale that definitions are written in, unless their source file sets its own
+    /// `sourceLocale` in its meta. Defaults to `en-US`.
+    #[napi(js_name = "sourceLocale")]
+    pub source_locale: Option<String>,
+    /// Extensions of files that contain message definitions in addition to the built-in
+    /// `.messages.js` variants, like `.messages.mjs`. Matching files are found when scanning for
+    /// messages files and are processed as JS definitions.
+    #[napi(js_name = "definitionsExtensions")]
+    pub definitions_extensions: Option<Vec<String>>,
+    /// Names that hooks are allowed to use, like `tooltipHook` in `$[text](tooltipHook)`. Hook
+    /// names are not checked when not given.
+    #[napi(js_name = "hookNames")]
+    pub hook_names: Option<Vec<String>>,
+    /// Names that link handlers are allowed to use, like `onClick` in `[text](onClick)`. Link
+    /// handlers are not checked when not given.
+    #[napi(js_name = "linkHandlerNames")]
+    pub link_handler_names: Option<Vec<String>>,
+    /// How every message in the database is parsed, which validation, type generation, and
+    /// bundling all use. Defaults to standard ICU MessageFormat semantics.
+    #[napi(js_name = "parserOptions")]
+    pub parser_options: Option<IntlParserOptions>,
+}
+
+/// Options that change how message content is parsed. Any that aren't given keep their current
+/// value, which is the default unless the database was given other options.
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlParserOptions {
+    /// How apostrophes are interpreted. Defaults to ICU escaping.
+    #[napi(js_name = "apostropheMode")]
+    pub apostrophe_mode: Option<IntlApostropheMode>,
+    /// Turn bare urls like `https://discord.com` and `www.discord.com` into links. Defaults to
+    /// false.
+    #[napi(js_name = "autolinkBareUrls")]
+    pub autolink_bare_urls: Option<bool>,
+    /// Parse emoji shortcodes like `:smile:`, which are compiled into `$emoji` tags with the name
+    /// of the emoji as the tag's control value. Defaults to false.
+    #[napi(js_name = "emojiShortcodes")]
+    pub emoji_shortcodes: Option<bool>,
+    /// Sigils that mark mention placeholders like `<@{userId}>`, which are compiled into
+    /// `$mention` tags with the sigil as the tag's control value. Mentions are not parsed when
+    /// this is not set.
+    #[napi(js_name = "mentionSigils")]
+    pub mention_sigils: Option<Vec<String>>,
+    /// Parse keyboard keys like `[[Ctrl]]`, which are compiled into `$kbd` tags. Defaults to
+    /// false.
+    #[napi(js_name = "keyboardKeys")]
+    pub keyboard_keys: Option<bool>,
+}
+
+impl IntlParserOptions {
+    /// Apply every option that was given to `parser_options`, keeping the rest as they are.
+    pub fn apply_to(self, mut parser_options: ParserOptions) -> ParserOptions {
+        if let Some(apostrophe_mode) = self.apostrophe_mode {
+            parser_options = parser_options.with_apostrophe_mode(apostrophe_mode.into());
+        }
+        if let Some(autolink_bare_urls) = self.autolink_bare_urls {
+            parser_options = parser_options.with_autolink_bare_urls(autolink_bare_urls);
+        }
+        if let Some(emoji_shortcodes) = self.emoji_shortcodes {
+            parser_options = parser_options.with_emoji_shortcodes(emoji_shortcodes);
+        }
+        if let Some(mention_sigils) = self.mention_sigils {
+            parser_options = parser_options.with_mention_sigils(mention_sigils);
+        }
+        if let Some(keyboard_keys) = self.keyboard_keys {
+            parser_options = parser_options.with_keyboard_keys(keyboard_keys);
+        }
+        parser_options
+    }
+}
+
 #[napi(object)]
 #[derive(Default)]
 pub struct IntlMessageBundlerOptions {
     pub format: Option<IntlCompiledMessageFormat>,
     #[napi(js_name = "bundleSecrets")]
     pub bundle_secrets: Option<bool>,
+    /// How to handle secret messages in the bundle. Takes precedence over `bundleSecrets`.
+    #[napi(js_name = "secretRedaction")]
+    pub secret_redaction: Option<IntlSecretRedaction>,
+    /// Include the plural categories used by the bundled messages under the `$plurals` key.
+    #[napi(js_name = "pluralRules")]
+    pub plural_rules: Option<bool>,
+    /// How apostrophes are interpreted when compiling messages. This and the other parser options
+    /// below default to the database's `parserOptions`, and messages are only parsed again for
+    /// the bundle when they differ.
+    #[napi(js_name = "apostropheMode")]
+    pub apostrophe_mode: Option<IntlApostropheMode>,
+    /// Turn bare urls like `https://discord.com` and `www.discord.com` into links when compiling
+    /// messages. Defaults to false.
+    #[napi(js_name = "autolinkBareUrls")]
+    pub autolink_bare_urls: Option<bool>,
+    /// Compile emoji shortcodes like `:smile:` into `$emoji` tags, with the name of the emoji as
+    /// the tag's control value. Defaults to false.
+    #[napi(js_name = "emojiShortcodes")]
+    pub emoji_shortcodes: Option<bool>,
+    /// Sigils that mark mention placeholders like `<@{userId}>`, compiled into `$mention` tags
+    /// with the sigil as the tag's control value. Mentions are not parsed when this is not set.
+    #[napi(js_name = "mentionSigils")]
+    pub mention_sigils: Option<Vec<String>>,
+    /// Compile keyboard keys like `[[Ctrl]]` into `$kbd` tags. Defaults to false.
+    #[napi(js_name = "keyboardKeys")]
+    pub keyboard_keys: Option<bool>,
+    /// Transforms to apply to every message before it is compiled, in the order they are given.
+    pub transforms: Option<Vec<IntlMessageTransform>>,
+    /// New names for placeholder variables, keyed by their names in the source messages. Renaming
+    /// is applied after every other transform.
+    #[napi(js_name = "placeholderRenames")]
+    pub placeholder_renames: Option<HashMap<String, String>>,
+    /// New names for the placeholder variables of individual messages, keyed by message key and
+    /// then by their current names, like when a variable is renamed in code before translations
+    /// are updated. Applied after `placeholderRenames`.
+    #[napi(js_name = "messagePlaceholderRenames")]
+    pub message_placeholder_renames: Option<HashMap<String, HashMap<String, String>>>,
+    /// Values for variables that are constant for the whole build, like a product name or an
+    /// upload limit, keyed by variable name. Placeholders for them are replaced with their values
+    /// wherever the result is the same in every locale, before any other transform.
+    pub constants: Option<HashMap<String, Either<String, f64>>>,
+    /// How messages are keyed in the bundle. Defaults to their hashed keys.
+    #[napi(js_name = "keyMode")]
+    pub key_mode: Option<IntlBundleKeyMode>,
+    /// Put every entry of the bundle on its own line and indent compiled values, for reading
+    /// debug artifacts. Defaults to false.
+    pub pretty: Option<bool>,
+    /// Include the `file:line:column` that each bundled value was read from under the `$spans`
+    /// key. Defaults to false.
+    #[napi(js_name = "includeSpans")]
+    pub include_spans: Option<bool>,
+    /// Write whole numbers in compiled values without a fractional part, the same way
+    /// `JSON.stringify` does. Defaults to false.
+    #[napi(js_name = "stableFloats")]
+    pub stable_floats: Option<bool>,
+}
+
+/// Convert constants given as strings or numbers into the values that are substituted into
+/// messages.
+pub fn constant_values(
+    constants: HashMap<String, Either<String, f64>>,
+) -> HashMap<String, ConstantValue> {
+    constants
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Either::A(text) => ConstantValue::Text(text),
+                Either::B(number) => ConstantValue::Number(number),
+            };
+            (name, value)
+        })
+        .collect()
 }
 
-impl Into<intl_database_exporter::IntlMessageBundlerOptions> for IntlMessageBundlerOptions {
-    fn into(self) -> intl_database_exporter::IntlMessageBundlerOptions {
+/// Convert renames keyed by message key into the ones used by the bundler and exporter.
+pub fn message_placeholder_renames(
+    renames: HashMap<String, HashMap<String, String>>,
+) -> MessagePlaceholderRenames {
+    renames.into_iter().fold(
+        MessagePlaceholderRenames::new(),
+        |result, (key, renames)| result.with_message_renames(key_symbol(&key), renames),
+    )
+}
+
+impl IntlMessageBundlerOptions {
+    /// Convert these options for the bundler, applying the parser options given here on top of
+    /// `parser_options`, the ones that the database parses every message with.
+    pub fn into_bundler_options(
+        self,
+        parser_options: &ParserOptions,
+    ) -> intl_database_exporter::IntlMessageBundlerOptions {
         let mut options = intl_database_exporter::IntlMessageBundlerOptions::default();
         if let Some(bundle_secrets) = self.bundle_secrets {
             options = options.with_bundle_secrets(bundle_secrets);
         }
+        if let Some(secret_redaction) = self.secret_redaction {
+            options = options.with_secret_redaction(secret_redaction.into());
+        }
+        if let Some(plural_rules) = self.plural_rules {
+            options = options.with_plural_rules(plural_rules);
+        }
         if let Some(format) = self.format {
             options = options.with_format(format.into());
         }
+        if let Some(key_mode) = self.key_mode {
+            options = options.with_key_mode(key_mode.into());
+        }
+        if let Some(include_spans) = self.include_spans {
+            options = options.with_spans(include_spans);
+        }
+        options = options.with_serialize_options(
+            SerializeOptions::new()
+                .with_pretty(self.pretty.unwrap_or_default())
+                .with_stable_floats(self.stable_floats.unwrap_or_default()),
+        );
+        let mut transforms = TransformPipeline::new();
+        if let Some(constants) = self.constants {
+            transforms =
+                transforms.with_transform(SubstituteConstants::new(constant_values(constants)));
+        }
+        for transform in self.transforms.unwrap_or_default() {
+            transforms = match transform {
+                IntlMessageTransform::SmartQuotes => transforms.with_transform(SmartQuotes),
+                IntlMessageTransform::BidiIsolation => transforms.with_transform(BidiIsolation),
+                IntlMessageTransform::CjkWordBreaks => {
+                    transforms.with_transform(CjkWordBreaks::new())
+                }
+                IntlMessageTransform::NormalizeUnicode => {
+                    transforms.with_transform(NormalizeUnicode)
+                }
+            };
+        }
+        if let Some(placeholder_renames) = self.placeholder_renames {
+            transforms = transforms.with_transform(RenamePlaceholders::new(placeholder_renames));
+        }
+        options = options.with_transforms(transforms);
+        if let Some(renames) = self.message_placeholder_renames {
+            options = options.with_placeholder_renames(message_placeholder_renames(renames));
+        }
+        let bundle_parser_options = IntlParserOptions {
+            apostrophe_mode: self.apostrophe_mode,
+            autolink_bare_urls: self.autolink_bare_urls,
+            emoji_shortcodes: self.emoji_shortcodes,
+            mention_sigils: self.mention_sigils,
+            keyboard_keys: self.keyboard_keys,
+        };
+        options.with_parser_options(bundle_parser_options.apply_to(parser_options.clone()))
+    }
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlTypesGeneratorOptions {
+    /// The kind of type definitions file to generate. Defaults to TypeScript.
+    pub format: Option<IntlTypesOutputFormat>,
+    /// Also declare a `messageLocales` map of each message key to the locales that define it.
+    #[napi(js_name = "localeMap")]
+    pub locale_map: Option<bool>,
+    /// Also declare a `messageKeys` map of each hashed message key to its original key, so that
+    /// devtools can show readable keys for the ones in compiled bundles.
+    #[napi(js_name = "keyMap")]
+    pub key_map: Option<bool>,
+    /// The same constants given to the bundler. Variables that are always replaced with their
+    /// values are left out of the generated types.
+    pub constants: Option<HashMap<String, Either<String, f64>>>,
+    /// The same `messagePlaceholderRenames` given to the bundler, so that translations that still
+    /// use the old names of renamed placeholders don't add them to the generated types.
+    #[napi(js_name = "messagePlaceholderRenames")]
+    pub message_placeholder_renames: Option<HashMap<String, HashMap<String, String>>>,
+    /// Generate types for every definitions file in a directory at once, with one declaration
+    /// file for each. When given, the source path is the directory of definitions files, and the
+    /// output path is the directory to write the declarations and their barrel index files to.
+    pub split: Option<IntlTypesSplitMode>,
+    /// Keys of the messages that changed since the types were last generated. When given and the
+    /// output and its source map already exist, only the entries of these messages and the
+    /// messages that reference them are generated again, and the rest are kept from the existing
+    /// output. Every message should be regenerated when the known locales change. Not used
+    /// with `split`, which always generates every file in full.
+    #[napi(js_name = "changedKeys")]
+    pub changed_keys: Option<Vec<String>>,
+}
+
+impl From<IntlTypesGeneratorOptions> for intl_database_types_generator::IntlTypesGeneratorOptions {
+    fn from(value: IntlTypesGeneratorOptions) -> Self {
+        let mut options = intl_database_types_generator::IntlTypesGeneratorOptions::default();
+        if let Some(format) = value.format {
+            options = options.with_format(format.into());
+        }
+        if let Some(locale_map) = value.locale_map {
+            options = options.with_locale_map(locale_map);
+        }
+        if let Some(key_map) = value.key_map {
+            options = options.with_key_map(key_map);
+        }
+        if let Some(constants) = value.constants {
+            options = options.with_constants(constant_values(constants));
+        }
+        if let Some(renames) = value.message_placeholder_renames {
+            options = options.with_placeholder_renames(message_placeholder_renames(renames));
+        }
+        options
+    }
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlValidatorOptions {
+    /// Thresholds for warning about messages that are too large or complex. Any threshold that is
+    /// not given uses its default.
+    pub complexity: Option<IntlComplexityOptions>,
+    /// Patterns for recognizing messages that are fragments of a larger sentence. Any pattern that
+    /// is not given uses its default.
+    pub fragments: Option<IntlFragmentOptions>,
+    /// Suffixes that mark a word as plural for each language or locale, like `{ en: ['s'] }`, used
+    /// to find plural options that use the wrong form of a word, like `one {# items}`. Languages
+    /// that are not given keep their defaults, and an empty list disables the check for one.
+    #[napi(js_name = "pluralSuffixes")]
+    pub plural_suffixes: Option<HashMap<String, Vec<String>>>,
+    /// The convention for spacing between placeholders and CJK text for each language or locale,
+    /// like `{ ko: IntlCjkPlaceholderSpacing.Spaced }`. `ja` and `zh` default to `Unspaced`, and
+    /// other locales are not checked unless given.
+    #[napi(js_name = "cjkPlaceholderSpacing")]
+    pub cjk_placeholder_spacing: Option<HashMap<String, IntlCjkPlaceholderSpacing>>,
+    /// House style rules, reported as `NoStyleViolations` wherever their patterns match.
+    #[napi(js_name = "styleRules")]
+    pub style_rules: Option<Vec<IntlStyleRule>>,
+    /// Only validate the messages defined in the registered project with this name.
+    pub project: Option<String>,
+    /// Path to a baseline file written by `writeValidationBaseline`. Only diagnostics that aren't
+    /// recorded in the baseline are returned.
+    pub baseline: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlComplexityOptions {
+    /// Maximum number of characters in a message. Defaults to 1000.
+    #[napi(js_name = "maxLength")]
+    pub max_length: Option<u32>,
+    /// Maximum number of plurals and selects nested inside of each other. Defaults to 2.
+    #[napi(js_name = "maxNestingDepth")]
+    pub max_nesting_depth: Option<u32>,
+    /// Maximum number of arms in a single plural. Defaults to 8.
+    #[napi(js_name = "maxPluralArms")]
+    pub max_plural_arms: Option<u32>,
+    /// Maximum number of distinct variables in a message. Defaults to 10.
+    #[napi(js_name = "maxVariables")]
+    pub max_variables: Option<u32>,
+    /// Maximum number of distinct sentences a message can produce, counting every combination of
+    /// plural and select arms. Defaults to 24.
+    #[napi(js_name = "maxCombinations")]
+    pub max_combinations: Option<u32>,
+}
+
+impl From<IntlComplexityOptions> for NoExcessiveComplexityOptions {
+    fn from(value: IntlComplexityOptions) -> Self {
+        let mut options = NoExcessiveComplexityOptions::default();
+        if let Some(max_length) = value.max_length {
+            options = options.with_max_length(max_length as usize);
+        }
+        if let Some(max_nesting_depth) = value.max_nesting_depth {
+            options = options.with_max_nesting_depth(max_nesting_depth as usize);
+        }
+        if let Some(max_plural_arms) = value.max_plural_arms {
+            options = options.with_max_plural_arms(max_plural_arms as usize);
+        }
+        if let Some(max_variables) = value.max_variables {
+            options = options.with_max_variables(max_variables as usize);
+        }
+        if let Some(max_combinations) = value.max_combinations {
+            options = options.with_max_combinations(max_combinations as usize);
+        }
+        options
+    }
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlFragmentOptions {
+    /// Words that mark a message as a fragment when it starts with them. Defaults to common
+    /// lowercase conjunctions like `and` and `or`.
+    #[napi(js_name = "leadingWords")]
+    pub leading_words: Option<Vec<String>>,
+    /// Text that marks a message as a fragment when it ends with it. Defaults to `:`.
+    #[napi(js_name = "trailingSuffixes")]
+    pub trailing_suffixes: Option<Vec<String>>,
+}
+
+impl From<IntlFragmentOptions> for NoConcatenatedFragmentsOptions {
+    fn from(value: IntlFragmentOptions) -> Self {
+        let mut options = NoConcatenatedFragmentsOptions::default();
+        if let Some(leading_words) = value.leading_words {
+            options = options.with_leading_words(leading_words);
+        }
+        if let Some(trailing_suffixes) = value.trailing_suffixes {
+            options = options.with_trailing_suffixes(trailing_suffixes);
+        }
         options
     }
 }
 
+/// A house style rule, like reporting "Use 'sign in' instead of 'log in'" wherever `\blog in\b`
+/// matches.
+#[napi(object)]
+pub struct IntlStyleRule {
+    /// Regular expression matched against the raw content of each message, using the syntax of
+    /// the Rust `regex` crate.
+    pub pattern: String,
+    /// Description of the diagnostic reported for each match.
+    pub message: String,
+    pub help: Option<String>,
+    /// Languages, like `en`, or full locales, like `en-GB`, to apply the rule to. Applies to every
+    /// locale when not given.
+    pub locales: Option<Vec<String>>,
+    /// Globs of the message keys to apply the rule to, like `SETTINGS_*`. Applies to every message
+    /// when not given.
+    pub keys: Option<Vec<String>>,
+    /// Defaults to `Warning`.
+    pub severity: Option<IntlDiagnosticSeverity>,
+}
+
+impl TryFrom<IntlStyleRule> for StyleRule {
+    type Error = anyhow::Error;
+
+    fn try_from(value: IntlStyleRule) -> Result<Self, Self::Error> {
+        Ok(StyleRule::new(&value.pattern, value.message)?
+            .with_help(value.help)
+            .with_locales(value.locales.unwrap_or_default())
+            .with_keys(value.keys.unwrap_or_default())
+            .with_severity(
+                value
+                    .severity
+                    .map_or(DiagnosticSeverity::Warning, Into::into),
+            ))
+    }
+}
+
+/// Style rules are compiled while converting the options, which fails if any of their patterns are
+/// invalid.
+impl TryFrom<IntlValidatorOptions> for ValueValidatorConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(value: IntlValidatorOptions) -> Result<Self, Self::Error> {
+        let style_rules = value
+            .style_rules
+            .unwrap_or_default()
+            .into_iter()
+            .map(StyleRule::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(ValueValidatorConfig::default()
+            .with_complexity(value.complexity.unwrap_or_default().into())
+            .with_fragments(value.fragments.unwrap_or_default().into())
+            .with_plural_forms(
+                NoMismatchedPluralFormsOptions::default()
+                    .with_plural_suffixes(value.plural_suffixes.unwrap_or_default()),
+            )
+            .with_cjk_spacing(
+                NoInconsistentCjkSpacingOptions::default().with_placeholder_spacing(
+                    value
+                        .cjk_placeholder_spacing
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(locale, spacing)| (locale, spacing.into()))
+                        .collect(),
+                ),
+            )
+            .with_style_rules(NoStyleViolationsOptions::default().with_rules(style_rules)))
+    }
+}
+
 #[napi(object)]
 pub struct IntlDiagnostic {
     pub name: String,
@@ -40,6 +495,38 @@ pub struct IntlDiagnostic {
     pub severity: String,
     pub description: String,
     pub help: Option<String>,
+    /// Position within the message value that the diagnostic applies to, if any.
+    pub span: Option<IntlDiagnosticSpan>,
+}
+
+#[napi(object)]
+pub struct IntlDiagnosticSpan {
+    #[napi(js_name = "byteOffset")]
+    pub byte_offset: u32,
+    /// Offset in UTF-16 code units, matching JavaScript string indices.
+    #[napi(js_name = "utf16Offset")]
+    pub utf16_offset: u32,
+    /// Offset in user-perceived characters, for highlighting.
+    #[napi(js_name = "graphemeOffset")]
+    pub grapheme_offset: u32,
+    pub line: u32,
+    #[napi(js_name = "utf16Column")]
+    pub utf16_column: u32,
+    #[napi(js_name = "graphemeColumn")]
+    pub grapheme_column: u32,
+}
+
+impl From<DiagnosticSpan> for IntlDiagnosticSpan {
+    fn from(value: DiagnosticSpan) -> Self {
+        Self {
+            byte_offset: value.byte_offset as u32,
+            utf16_offset: value.utf16_offset as u32,
+            grapheme_offset: value.grapheme_offset as u32,
+            line: value.line as u32,
+            utf16_column: value.utf16_column as u32,
+            grapheme_column: value.grapheme_column as u32,
+        }
+    }
 }
 
 impl From<MessageDiagnostic> for IntlDiagnostic {
@@ -54,6 +541,68 @@ impl From<MessageDiagnostic> for IntlDiagnostic {
             severity: value.severity.to_string(),
             description: value.description,
             help: value.help,
+            span: value.span.map(IntlDiagnosticSpan::from),
+        }
+    }
+}
+
+#[napi]
+pub enum IntlDiagnosticSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl From<IntlDiagnosticSeverity> for DiagnosticSeverity {
+    fn from(value: IntlDiagnosticSeverity) -> Self {
+        match value {
+            IntlDiagnosticSeverity::Info => DiagnosticSeverity::Info,
+            IntlDiagnosticSeverity::Warning => DiagnosticSeverity::Warning,
+            IntlDiagnosticSeverity::Error => DiagnosticSeverity::Error,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlSeverityCounts {
+    pub errors: u32,
+    pub warnings: u32,
+    pub infos: u32,
+}
+
+impl From<SeverityCounts> for IntlSeverityCounts {
+    fn from(value: SeverityCounts) -> Self {
+        Self {
+            errors: value.errors as u32,
+            warnings: value.warnings as u32,
+            infos: value.infos as u32,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlValidationSummary {
+    /// Whether there are no diagnostics at or above the `failOn` severity.
+    pub passed: bool,
+    /// The status that a CI process should exit with: 0 when the summary passed, otherwise 1.
+    #[napi(js_name = "exitCode")]
+    pub exit_code: i32,
+    pub counts: IntlSeverityCounts,
+    /// Counts for each rule that reported any diagnostics, keyed by the rule name.
+    pub rules: HashMap<String, IntlSeverityCounts>,
+}
+
+impl From<ValidationSummary> for IntlValidationSummary {
+    fn from(value: ValidationSummary) -> Self {
+        Self {
+            passed: value.passed,
+            exit_code: value.exit_code(),
+            counts: value.counts.into(),
+            rules: value
+                .rules
+                .into_iter()
+                .map(|(rule, counts)| (rule.to_string(), counts.into()))
+                .collect(),
         }
     }
 }
@@ -68,6 +617,26 @@ pub struct IntlSourceFile {
     pub message_keys: Vec<JsNumber>,
     pub meta: IntlMessageMeta,
     pub locale: Option<JsNumber>,
+    /// The locale tag as it was given for the file, before it was normalized. Only present for
+    /// translations files.
+    #[napi(js_name = "localeTag")]
+    pub locale_tag: Option<String>,
+    /// Entries that could not be extracted as messages. Only present for definitions files.
+    pub errors: Option<Vec<IntlSourceFileError>>,
+}
+
+// This is an unused struct purely for generating functional TS types.
+#[napi(object)]
+pub struct IntlSourceFileError {
+    pub position: IntlSourcePosition,
+    pub message: String,
+}
+
+// This is an unused struct purely for generating functional TS types.
+#[napi(object)]
+pub struct IntlSourcePosition {
+    pub line: u32,
+    pub col: u32,
 }
 
 // This is an unused struct purely for generating functional TS types.
@@ -94,6 +663,10 @@ pub struct IntlMessage {
     pub source_locale: Option<String>,
     /// Meta information about how to handle and process this message.
     pub meta: IntlMessageMeta,
+    /// Hash of the source value and meta of this message, which only changes when the definition
+    /// does. Not present when the message has no definition.
+    #[napi(js_name = "contentHash")]
+    pub content_hash: Option<String>,
 }
 
 // This is an unused struct purely for generating functional TS types.
@@ -104,12 +677,202 @@ pub struct IntlMessageValue {
     pub variables: JsObject,
     #[napi(js_name = "filePosition")]
     pub file_position: JsObject,
+    /// True when the value was recovered from a compiled bundle rather than written by a person.
+    #[napi(js_name = "machineRecovered")]
+    pub machine_recovered: bool,
+    /// Where the value is in the review workflow, one of `new`, `machine`, `reviewed`, or `final`.
+    #[napi(js_name = "reviewState")]
+    pub review_state: String,
+    /// Comments written around the value in its translation file.
+    pub comments: Option<IntlValueComments>,
+}
+
+/// Comments written around a value in a `.jsona` translation file, each exactly as written
+/// including the comment markers.
+#[napi(object)]
+pub struct IntlValueComments {
+    /// Comments on the lines above the entry.
+    pub leading: Option<String>,
+    /// Comments between the key and the value, like `"key": /* note */ "value"`.
+    pub inline: Option<String>,
+    /// Comments after the value on the same line. For the last entry in a file, this also
+    /// includes any comments on the lines after it.
+    pub trailing: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlMessageQuery {
+    /// Text to search for in message keys and values. Matches any message when not given.
+    pub pattern: Option<String>,
+    /// Treat `pattern` as a regular expression rather than a plain substring.
+    #[napi(js_name = "isRegex")]
+    pub is_regex: Option<bool>,
+    /// Match `pattern` against message keys. Defaults to true.
+    #[napi(js_name = "searchKeys")]
+    pub search_keys: Option<bool>,
+    /// Match `pattern` against the raw values of every translation. Defaults to true.
+    #[napi(js_name = "searchValues")]
+    pub search_values: Option<bool>,
+    /// Only include messages that have a value in this locale.
+    #[napi(js_name = "withLocale")]
+    pub with_locale: Option<String>,
+    /// Only include messages that have no value in this locale.
+    #[napi(js_name = "withoutLocale")]
+    pub without_locale: Option<String>,
+    pub secret: Option<bool>,
+    pub translate: Option<bool>,
+    /// Only include messages defined in this definitions file.
+    #[napi(js_name = "definitionsFile")]
+    pub definitions_file: Option<String>,
+    /// Only include messages that use a variable with this name.
+    pub variable: Option<String>,
+    /// Only include messages defined in the registered project with this name.
+    pub project: Option<String>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+impl TryFrom<IntlMessageQuery> for MessageQuery {
+    type Error = DatabaseError;
+
+    fn try_from(value: IntlMessageQuery) -> DatabaseResult<Self> {
+        let pattern = match value.pattern {
+            Some(pattern) if value.is_regex.unwrap_or(false) => {
+                Some(MessageQueryPattern::regex(&pattern)?)
+            }
+            Some(pattern) => Some(MessageQueryPattern::substring(&pattern)),
+            None => None,
+        };
+        let to_symbol = |value: Option<String>| value.map(|value| key_symbol(&value));
+
+        Ok(MessageQuery::new()
+            .with_pattern(pattern)
+            .with_search_keys(value.search_keys.unwrap_or(true))
+            .with_search_values(value.search_values.unwrap_or(true))
+            .with_locale(to_symbol(value.with_locale))
+            .without_locale(to_symbol(value.without_locale))
+            .with_secret(value.secret)
+            .with_translate(value.translate)
+            .with_definitions_file(to_symbol(value.definitions_file))
+            .with_variable(to_symbol(value.variable))
+            .with_project(to_symbol(value.project))
+            .with_page(
+                value.offset.unwrap_or(0) as usize,
+                value.limit.map(|limit| limit as usize),
+            ))
+    }
+}
+
+// This is an unused struct purely for generating functional TS types.
+#[napi(object)]
+pub struct IntlMessageQueryResult {
+    /// Messages on the requested page of results, ordered by key.
+    pub messages: Vec<IntlMessage>,
+    /// Total number of messages matching the query, across all pages.
+    pub total: u32,
+}
+
+#[napi]
+pub enum IntlApostropheMode {
+    Icu,
+    Literal,
+}
+
+impl From<IntlApostropheMode> for ApostropheMode {
+    fn from(value: IntlApostropheMode) -> Self {
+        match value {
+            IntlApostropheMode::Icu => ApostropheMode::Icu,
+            IntlApostropheMode::Literal => ApostropheMode::Literal,
+        }
+    }
+}
+
+/// How a locale separates CJK text from the placeholders written next to it, which are usually
+/// filled with Latin text or numbers.
+#[napi]
+pub enum IntlCjkPlaceholderSpacing {
+    /// A space is written between CJK text and a placeholder, like `{name} さん`.
+    Spaced,
+    /// Placeholders are written directly against CJK text, like `{name}さん`.
+    Unspaced,
+}
+
+impl From<IntlCjkPlaceholderSpacing> for CjkPlaceholderSpacing {
+    fn from(value: IntlCjkPlaceholderSpacing) -> Self {
+        match value {
+            IntlCjkPlaceholderSpacing::Spaced => CjkPlaceholderSpacing::Spaced,
+            IntlCjkPlaceholderSpacing::Unspaced => CjkPlaceholderSpacing::Unspaced,
+        }
+    }
+}
+
+#[napi]
+pub enum IntlTypesOutputFormat {
+    TypeScript,
+    Flow,
+    JsDoc,
+}
+
+impl From<IntlTypesOutputFormat> for TypesOutputFormat {
+    fn from(value: IntlTypesOutputFormat) -> Self {
+        match value {
+            IntlTypesOutputFormat::TypeScript => TypesOutputFormat::TypeScript,
+            IntlTypesOutputFormat::Flow => TypesOutputFormat::Flow,
+            IntlTypesOutputFormat::JsDoc => TypesOutputFormat::JsDoc,
+        }
+    }
+}
+
+#[napi]
+pub enum IntlTypesSplitMode {
+    /// A single index at the root of the output re-exports every declaration file.
+    File,
+    /// Every directory of the output has an index that re-exports the declaration files in it,
+    /// along with the index of each of its subdirectories.
+    Directory,
+}
+
+impl From<IntlTypesSplitMode> for TypesSplitMode {
+    fn from(value: IntlTypesSplitMode) -> Self {
+        match value {
+            IntlTypesSplitMode::File => TypesSplitMode::File,
+            IntlTypesSplitMode::Directory => TypesSplitMode::Directory,
+        }
+    }
+}
+
+#[napi]
+pub enum IntlBundleKeyMode {
+    /// Key each message by the hash of its key.
+    Hashed,
+    /// Key each message by a short, sequential alias, which `@discord/intl` resolves back to the
+    /// hashed key using the aliases from `getMessageKeyAliases`.
+    Alias,
+    /// Key each message by its original key, for debugging development bundles.
+    Original,
+    /// Key each message by the hash of its key, with a `$keys` entry mapping each hash back to
+    /// its original key.
+    HashedWithKeys,
+}
+
+impl From<IntlBundleKeyMode> for BundleKeyMode {
+    fn from(value: IntlBundleKeyMode) -> Self {
+        match value {
+            IntlBundleKeyMode::Hashed => BundleKeyMode::Hashed,
+            IntlBundleKeyMode::Alias => BundleKeyMode::Alias,
+            IntlBundleKeyMode::Original => BundleKeyMode::Original,
+            IntlBundleKeyMode::HashedWithKeys => BundleKeyMode::HashedWithKeys,
+        }
+    }
 }
 
 #[napi]
 pub enum IntlCompiledMessageFormat {
     Json,
     KeylessJson,
+    Mf2,
+    ReactIntl,
 }
 
 impl From<IntlCompiledMessageFormat> for CompiledMessageFormat {
@@ -117,10 +880,94 @@ impl From<IntlCompiledMessageFormat> for CompiledMessageFormat {
         match value {
             IntlCompiledMessageFormat::Json => CompiledMessageFormat::Json,
             IntlCompiledMessageFormat::KeylessJson => CompiledMessageFormat::KeylessJson,
+            IntlCompiledMessageFormat::Mf2 => CompiledMessageFormat::Mf2,
+            IntlCompiledMessageFormat::ReactIntl => CompiledMessageFormat::ReactIntl,
+        }
+    }
+}
+
+/// Built-in transforms that can be applied to messages when bundling.
+#[napi]
+pub enum IntlMessageTransform {
+    /// Replace straight quotes and apostrophes with typographic ones.
+    SmartQuotes,
+    /// Wrap placeholder values in Unicode directional isolates.
+    BidiIsolation,
+    /// Insert zero-width spaces where lines can break within CJK text.
+    CjkWordBreaks,
+    /// Remove stray zero-width characters and normalize text to NFC. Has to come before
+    /// `CjkWordBreaks`, which it would otherwise undo.
+    NormalizeUnicode,
+}
+
+#[napi]
+pub enum IntlSecretRedaction {
+    Keep,
+    Mask,
+    Omit,
+}
+
+impl From<IntlSecretRedaction> for SecretRedaction {
+    fn from(value: IntlSecretRedaction) -> Self {
+        match value {
+            IntlSecretRedaction::Keep => SecretRedaction::Keep,
+            IntlSecretRedaction::Mask => SecretRedaction::Mask,
+            IntlSecretRedaction::Omit => SecretRedaction::Omit,
+        }
+    }
+}
+
+#[napi]
+pub enum IntlReviewState {
+    New,
+    Machine,
+    Reviewed,
+    Final,
+}
+
+impl From<IntlReviewState> for ReviewState {
+    fn from(value: IntlReviewState) -> Self {
+        match value {
+            IntlReviewState::New => ReviewState::New,
+            IntlReviewState::Machine => ReviewState::Machine,
+            IntlReviewState::Reviewed => ReviewState::Reviewed,
+            IntlReviewState::Final => ReviewState::Final,
         }
     }
 }
 
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlFileDiscoveryOptions {
+    /// Globs for files and directories to skip, using the same syntax as `.gitignore`, like
+    /// `dist` or `packages/*/build`. Globs without a `/` match an entry with that name anywhere.
+    pub exclude: Option<Vec<String>>,
+    /// Skip files that are ignored by git. Defaults to true.
+    #[napi(js_name = "respectGitignore")]
+    pub respect_gitignore: Option<bool>,
+    /// Follow symbolic links while scanning. Defaults to false.
+    #[napi(js_name = "followSymlinks")]
+    pub follow_symlinks: Option<bool>,
+    /// How many directories deep to scan within each directory. Defaults to no limit.
+    #[napi(js_name = "maxDepth")]
+    pub max_depth: Option<u32>,
+}
+
+impl From<IntlFileDiscoveryOptions> for MessagesFileDiscoveryOptions {
+    fn from(value: IntlFileDiscoveryOptions) -> Self {
+        let mut options = MessagesFileDiscoveryOptions::default()
+            .with_exclude(value.exclude.unwrap_or_default())
+            .with_max_depth(value.max_depth.map(|max_depth| max_depth as usize));
+        if let Some(respect_gitignore) = value.respect_gitignore {
+            options = options.with_respect_gitignore(respect_gitignore);
+        }
+        if let Some(follow_symlinks) = value.follow_symlinks {
+            options = options.with_follow_symlinks(follow_symlinks);
+        }
+        options
+    }
+}
+
 #[napi(object)]
 pub struct IntlMessagesFileDescriptor {
     #[napi(js_name = "filePath")]
@@ -146,12 +993,630 @@ impl From<MessagesFileDescriptor> for IntlMessagesFileDescriptor {
     }
 }
 
+/// A package in a monorepo that shares the database with other packages. Every file under `root`
+/// belongs to the project.
+#[napi(object)]
+pub struct IntlProject {
+    pub name: String,
+    pub root: String,
+    /// The locale that definitions in the project are written in when they aren't processed with
+    /// an explicit locale. Defaults to the source locale of the database.
+    #[napi(js_name = "sourceLocale")]
+    pub source_locale: Option<String>,
+    /// The directory where translations for the project are kept, relative to `root`. Used by
+    /// definitions files in the project that don't set their own `translationsPath`.
+    #[napi(js_name = "translationsPath")]
+    pub translations_path: Option<String>,
+}
+
+impl From<&Project> for IntlProject {
+    fn from(value: &Project) -> Self {
+        IntlProject {
+            name: value.name.to_string(),
+            root: value.root.to_string_lossy().to_string(),
+            source_locale: value.source_locale.map(|locale| locale.to_string()),
+            translations_path: value
+                .translations_path
+                .as_ref()
+                .map(|path| path.to_string_lossy().to_string()),
+        }
+    }
+}
+
 #[napi(object)]
 pub struct IntlMultiProcessingFailure {
     pub file: String,
+    pub code: IntlErrorCode,
     pub error: String,
 }
 
+#[napi(object)]
+pub struct IntlExportResult {
+    /// Every translation file covered by the export, whether or not it changed.
+    pub files: Vec<String>,
+    /// The files whose content changed and were written, or would have been written in a dry run.
+    #[napi(js_name = "changedFiles")]
+    pub changed_files: Vec<String>,
+}
+
+impl From<ExportedTranslationFiles> for IntlExportResult {
+    fn from(value: ExportedTranslationFiles) -> Self {
+        IntlExportResult {
+            files: value.files,
+            changed_files: value.changed_files,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlPrecompiledBundle {
+    pub buffer: Buffer,
+    /// Every file that the bundle depends on, sorted by path.
+    pub dependencies: Vec<IntlBundleDependency>,
+}
+
+#[napi(object)]
+pub struct IntlBundleDependency {
+    #[napi(js_name = "filePath")]
+    pub file_path: String,
+    /// Hash of the content that the bundle was compiled from, or null for a translations file that
+    /// doesn't exist yet.
+    #[napi(js_name = "contentHash")]
+    pub content_hash: Option<String>,
+}
+
+impl From<PrecompiledBundle> for IntlPrecompiledBundle {
+    fn from(value: PrecompiledBundle) -> Self {
+        IntlPrecompiledBundle {
+            buffer: value.buffer.into(),
+            dependencies: value
+                .dependencies
+                .into_iter()
+                .map(|dependency| IntlBundleDependency {
+                    file_path: dependency.file_path.to_string(),
+                    content_hash: dependency.content_hash,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlBuildManifestEntry {
+    #[napi(js_name = "filePath")]
+    pub file_path: String,
+    #[napi(js_name = "sourceLocale")]
+    pub source_locale: String,
+    /// Map of hashed message keys to the original key.
+    #[napi(js_name = "messageKeys")]
+    pub message_keys: HashMap<String, String>,
+    /// The translations file providing each locale. Empty when the file sets `translate: false`.
+    pub translations: HashMap<String, String>,
+    /// The path of the compiled artifact for each locale, including the source locale.
+    pub artifacts: HashMap<String, String>,
+    /// Hash of the content of every message in the file for each locale in `artifacts`, including
+    /// the locale's translations, which only changes when that artifact would.
+    #[napi(js_name = "contentHashes")]
+    pub content_hashes: HashMap<String, String>,
+}
+
+impl From<BuildManifestEntry> for IntlBuildManifestEntry {
+    fn from(value: BuildManifestEntry) -> Self {
+        IntlBuildManifestEntry {
+            file_path: value.file_path.to_string(),
+            source_locale: value.source_locale.to_string(),
+            message_keys: value
+                .message_keys
+                .into_iter()
+                .map(|(hashed, key)| (hashed, key.to_string()))
+                .collect(),
+            translations: value
+                .translations
+                .into_iter()
+                .map(|(locale, file)| (locale.to_string(), file.to_string()))
+                .collect(),
+            artifacts: value
+                .artifacts
+                .into_iter()
+                .map(|(locale, artifact)| (locale.to_string(), artifact))
+                .collect(),
+            content_hashes: value
+                .content_hashes
+                .into_iter()
+                .map(|(locale, hash)| (locale.to_string(), hash))
+                .collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlLocaleTagDiagnostic {
+    #[napi(js_name = "filePath")]
+    pub file_path: String,
+    /// The locale tag as it was given for the file.
+    #[napi(js_name = "localeTag")]
+    pub locale_tag: String,
+    /// The locale that the file's translations were stored under.
+    pub locale: String,
+    /// Either `invalid` or `duplicate`.
+    pub problem: String,
+    pub description: String,
+}
+
+impl From<LocaleTagDiagnostic> for IntlLocaleTagDiagnostic {
+    fn from(value: LocaleTagDiagnostic) -> Self {
+        IntlLocaleTagDiagnostic {
+            file_path: value.file_path.to_string(),
+            locale_tag: value.locale_tag,
+            locale: value.locale.to_string(),
+            problem: match value.problem {
+                LocaleTagProblem::Invalid => "invalid",
+                LocaleTagProblem::Duplicate => "duplicate",
+            }
+            .into(),
+            description: value.description,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlVariableCompletion {
+    pub name: String,
+    /// Every kind of value the variable is used as, like `number`, `select`, or `hook`.
+    pub kinds: Vec<String>,
+    #[napi(js_name = "isBuiltin")]
+    pub is_builtin: bool,
+    /// Each distinct way the variable is written in the source message, ready to be inserted into
+    /// a translation. Empty for builtin variables, which are written as markdown syntax instead.
+    pub examples: Vec<String>,
+}
+
+impl From<VariableCompletion> for IntlVariableCompletion {
+    fn from(value: VariableCompletion) -> Self {
+        IntlVariableCompletion {
+            name: value.name.to_string(),
+            kinds: value.kinds.into_iter().map(String::from).collect(),
+            is_builtin: value.is_builtin,
+            examples: value.examples,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlUntranslatedMessage {
+    pub key: String,
+    /// Raw content of the message in its source locale.
+    pub source: String,
+    /// Location of the message's definition, when it was processed from a file.
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub col: Option<u32>,
+}
+
+impl From<UntranslatedMessage<'_>> for IntlUntranslatedMessage {
+    fn from(value: UntranslatedMessage) -> Self {
+        IntlUntranslatedMessage {
+            key: value.key.to_string(),
+            source: value.source.to_string(),
+            file: value
+                .file_position
+                .map(|position| position.file.to_string()),
+            line: value.file_position.map(|position| position.line),
+            col: value.file_position.map(|position| position.col),
+        }
+    }
+}
+
+#[napi]
+pub enum IntlBundleChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl From<BundleChangeKind> for IntlBundleChangeKind {
+    fn from(value: BundleChangeKind) -> Self {
+        match value {
+            BundleChangeKind::Added => Self::Added,
+            BundleChangeKind::Removed => Self::Removed,
+            BundleChangeKind::Changed => Self::Changed,
+        }
+    }
+}
+
+/// An entry of a compiled bundle that is different between two versions of it.
+#[napi(object)]
+pub struct IntlBundleEntryChange {
+    /// The key of the entry as it's written in the bundle, which is usually a hashed key.
+    #[napi(js_name = "bundleKey")]
+    pub bundle_key: String,
+    /// The message that the entry belongs to, when it's defined in the database.
+    pub key: Option<String>,
+    /// The definitions file of the message.
+    pub file: Option<String>,
+    pub kind: IntlBundleChangeKind,
+    /// Size of the entry in the old bundle, including its key, or 0 when it was added.
+    #[napi(js_name = "beforeBytes")]
+    pub before_bytes: u32,
+    /// Size of the entry in the new bundle, including its key, or 0 when it was removed.
+    #[napi(js_name = "afterBytes")]
+    pub after_bytes: u32,
+    /// How many bytes the entry added to the bundle, which is negative when it got smaller.
+    #[napi(js_name = "byteDelta")]
+    pub byte_delta: i64,
+}
+
+impl From<BundleEntryChange> for IntlBundleEntryChange {
+    fn from(value: BundleEntryChange) -> Self {
+        Self {
+            byte_delta: value.byte_delta(),
+            bundle_key: value.bundle_key,
+            key: value.key.map(|key| key.to_string()),
+            file: value.file.map(|file| file.to_string()),
+            kind: value.kind.into(),
+            before_bytes: value.before_bytes as u32,
+            after_bytes: value.after_bytes as u32,
+        }
+    }
+}
+
+/// The differences between two versions of a compiled bundle.
+#[napi(object)]
+pub struct IntlBundleDiff {
+    /// Every entry that was added, removed, or changed, with the largest changes in size first.
+    pub changes: Vec<IntlBundleEntryChange>,
+    /// How many entries are exactly the same in both bundles.
+    pub unchanged: u32,
+    /// Total size of the old bundle in bytes.
+    #[napi(js_name = "beforeBytes")]
+    pub before_bytes: u32,
+    /// Total size of the new bundle in bytes.
+    #[napi(js_name = "afterBytes")]
+    pub after_bytes: u32,
+    /// How many bytes the new bundle is larger than the old one, which is negative when it got
+    /// smaller.
+    #[napi(js_name = "byteDelta")]
+    pub byte_delta: i64,
+}
+
+impl From<BundleDiff> for IntlBundleDiff {
+    fn from(value: BundleDiff) -> Self {
+        Self {
+            byte_delta: value.byte_delta(),
+            changes: value.changes.into_iter().map(Into::into).collect(),
+            unchanged: value.unchanged as u32,
+            before_bytes: value.before_bytes as u32,
+            after_bytes: value.after_bytes as u32,
+        }
+    }
+}
+
+/// A version of the definition of a message.
+#[napi(object)]
+pub struct IntlMessageRevision {
+    pub raw: String,
+    #[napi(js_name = "contentHash")]
+    pub content_hash: String,
+    /// When the version was first processed, in milliseconds since the Unix epoch.
+    #[napi(js_name = "recordedAt")]
+    pub recorded_at: f64,
+}
+
+impl From<&MessageRevision> for IntlMessageRevision {
+    fn from(value: &MessageRevision) -> Self {
+        Self {
+            raw: value.raw.clone(),
+            content_hash: value.content_hash.clone(),
+            recorded_at: value.recorded_at as f64,
+        }
+    }
+}
+
+/// Which version of the definition of a message a translation was written for.
+#[napi(object)]
+pub struct IntlTranslationVersion {
+    pub locale: String,
+    /// Content hash of the definition when the translation was set, if it's known.
+    #[napi(js_name = "sourceHash")]
+    pub source_hash: Option<String>,
+    /// True if the definition has changed since the translation was set.
+    #[napi(js_name = "isOutdated")]
+    pub is_outdated: bool,
+}
+
+impl From<TranslationVersion<'_>> for IntlTranslationVersion {
+    fn from(value: TranslationVersion) -> Self {
+        Self {
+            locale: value.locale.to_string(),
+            source_hash: value.source_hash.map(String::from),
+            is_outdated: value.is_outdated,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlMessageHistory {
+    pub key: String,
+    /// Versions of the definition, oldest first.
+    pub revisions: Vec<IntlMessageRevision>,
+    /// Every translation other than the source, sorted by locale.
+    pub translations: Vec<IntlTranslationVersion>,
+}
+
+impl From<MessageHistory<'_>> for IntlMessageHistory {
+    fn from(value: MessageHistory) -> Self {
+        Self {
+            key: value.key.to_string(),
+            revisions: value.revisions.iter().map(Into::into).collect(),
+            translations: value.translations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A word from the visible text of a message, to be checked by a spell checker.
+#[napi(object)]
+pub struct IntlMessageWord {
+    pub word: String,
+    /// Position of the word in the message, unless it was written with escapes.
+    pub span: Option<IntlDiagnosticSpan>,
+}
+
+impl From<MessageWord> for IntlMessageWord {
+    fn from(value: MessageWord) -> Self {
+        Self {
+            word: value.word,
+            span: value.span.map(IntlDiagnosticSpan::from),
+        }
+    }
+}
+
+/// A word that a spell checker found to be misspelled.
+#[napi(object)]
+pub struct IntlMisspelling {
+    pub word: String,
+    /// Replacements suggested by the spell checker.
+    pub suggestions: Option<Vec<String>>,
+}
+
+/// A node or token of the concrete syntax tree of a message.
+#[napi(object)]
+pub struct IntlCstNode {
+    /// The kind of syntax, like `STRONG` for a node or `ICU_IDENT` for a token.
+    pub kind: String,
+    /// How many nodes this one is nested inside of. The document is the only node at depth 0.
+    pub depth: u32,
+    /// Where the node starts in the message, as an index into the JS string like `slice` takes.
+    /// Whitespace around the node isn't included.
+    pub start: u32,
+    pub end: u32,
+    /// The text of a token. Nodes don't have text of their own, only the tokens inside of them.
+    pub text: Option<String>,
+}
+
+/// Convert the syntax tree of a message into nodes with positions in the JS string.
+pub fn cst_nodes(cst: MessageCst) -> Vec<IntlCstNode> {
+    // The UTF-16 offset at each byte offset of the message, including its end.
+    let mut utf16_offsets = Vec::with_capacity(cst.raw.len() + 1);
+    let mut utf16_offset = 0;
+    for c in cst.raw.chars() {
+        utf16_offsets.extend(std::iter::repeat_n(utf16_offset, c.len_utf8()));
+        utf16_offset += c.len_utf16() as u32;
+    }
+    utf16_offsets.push(utf16_offset);
+
+    cst.nodes
+        .into_iter()
+        .map(|node| IntlCstNode {
+            kind: format!("{:?}", node.kind),
+            depth: node.depth as u32,
+            start: utf16_offsets[node.span.start],
+            end: utf16_offsets[node.span.end],
+            text: node.text,
+        })
+        .collect()
+}
+
+/// The translation that a validator registered with `registerValidator` is checking.
+#[napi(object)]
+pub struct IntlCustomValidatorInput {
+    pub key: String,
+    pub locale: String,
+    /// The content of the translation as it was written.
+    pub raw: String,
+    /// The parsed translation, in the same shape as `IntlMessageValue.parsed`.
+    #[napi(ts_type = "object")]
+    pub parsed: serde_json::Value,
+    /// The content of the source message that the translation was translated from.
+    #[napi(js_name = "sourceRaw")]
+    pub source_raw: String,
+    /// True when the translation is the source message itself.
+    #[napi(js_name = "isSource")]
+    pub is_source: bool,
+}
+
+/// A problem found by a validator registered with `registerValidator`.
+#[napi(object)]
+pub struct IntlCustomDiagnostic {
+    /// Position in `raw` where the problem is, as an index into the JS string like `indexOf`
+    /// returns. The diagnostic applies to the whole translation when not given.
+    pub offset: Option<u32>,
+    /// Defaults to `Warning`.
+    pub severity: Option<IntlDiagnosticSeverity>,
+    pub description: String,
+    pub help: Option<String>,
+}
+
+/// A translation that was changed by an autofix, like `fixTranslationUnicode`.
+#[napi(object)]
+pub struct IntlFixedTranslation {
+    pub key: String,
+    pub locale: String,
+}
+
+/// A change to the content of a message for `rewriteMessage`, replacing `start..end` with `text`.
+/// Offsets are indices into the raw content of the message, like `indexOf` returns.
+#[napi(object)]
+pub struct IntlMessageEdit {
+    pub start: u32,
+    pub end: u32,
+    pub text: String,
+}
+
+/// Returns the byte offset in `text` of the UTF-16 `offset`, which is how JS indexes strings.
+pub(crate) fn byte_offset_from_utf16(text: &str, offset: u32) -> usize {
+    let mut utf16_offset = 0;
+    for (byte_offset, c) in text.char_indices() {
+        if utf16_offset >= offset as usize {
+            return byte_offset;
+        }
+        utf16_offset += c.len_utf16();
+    }
+    text.len()
+}
+
+/// Convert the UTF-16 offsets of `edits` to byte offsets in `raw`, the content they apply to.
+/// Offsets past the end of `raw` stay past the end, so they are rejected rather than clamped.
+pub fn message_edits(raw: &str, edits: Vec<IntlMessageEdit>) -> Vec<MessageEdit> {
+    let utf16_len = raw.encode_utf16().count();
+    let byte_offset = |offset: u32| match (offset as usize).checked_sub(utf16_len) {
+        Some(past_end) if past_end > 0 => raw.len() + past_end,
+        _ => byte_offset_from_utf16(raw, offset),
+    };
+    edits
+        .into_iter()
+        .map(|edit| MessageEdit {
+            start: byte_offset(edit.start),
+            end: byte_offset(edit.end),
+            text: edit.text,
+        })
+        .collect()
+}
+
+/// Limits on which values `previewReplacements` changes.
+#[napi(object)]
+#[derive(Default)]
+pub struct IntlReplacementOptions {
+    /// Languages, like `en`, or full locales, like `en-GB`, to replace values in. Replaces values
+    /// in every locale when not given.
+    pub locales: Option<Vec<String>>,
+    /// Globs of the message keys to replace values of, like `SETTINGS_*`. Replaces values of
+    /// every message when not given.
+    pub keys: Option<Vec<String>>,
+}
+
+#[napi(object)]
+pub struct IntlReplacedMessage {
+    pub key: String,
+    pub locale: String,
+    pub before: String,
+    pub after: String,
+    /// How many matches of the pattern were replaced in the value.
+    pub matches: u32,
+}
+
+impl From<ReplacedMessage> for IntlReplacedMessage {
+    fn from(value: ReplacedMessage) -> Self {
+        Self {
+            key: value.key.to_string(),
+            locale: value.locale.to_string(),
+            before: value.before,
+            after: value.after,
+            matches: value.matches as u32,
+        }
+    }
+}
+
+impl From<IntlReplacedMessage> for ReplacedMessage {
+    fn from(value: IntlReplacedMessage) -> Self {
+        Self {
+            key: key_symbol(&value.key),
+            locale: key_symbol(&value.locale),
+            before: value.before,
+            after: value.after,
+            matches: value.matches as usize,
+        }
+    }
+}
+
+/// The changes that replacements make to a single file.
+#[napi(object)]
+pub struct IntlFileReplacement {
+    #[napi(js_name = "filePath")]
+    pub file_path: String,
+    /// The locale that the values in the file are written in.
+    pub locale: String,
+    #[napi(js_name = "isDefinitions")]
+    pub is_definitions: bool,
+    /// Hash of the content of the file when the replacements were planned.
+    #[napi(js_name = "contentHash")]
+    pub content_hash: String,
+    #[napi(js_name = "newContent")]
+    pub new_content: String,
+    /// A unified diff of the changes to the file.
+    pub diff: String,
+    pub messages: Vec<IntlReplacedMessage>,
+}
+
+impl From<FileReplacement> for IntlFileReplacement {
+    fn from(value: FileReplacement) -> Self {
+        Self {
+            file_path: value.file.to_string(),
+            locale: value.locale.to_string(),
+            is_definitions: value.is_definitions,
+            content_hash: value.content_hash,
+            new_content: value.new_content,
+            diff: value.diff,
+            messages: value.messages.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<IntlFileReplacement> for FileReplacement {
+    fn from(value: IntlFileReplacement) -> Self {
+        Self {
+            file: key_symbol(&value.file_path),
+            locale: key_symbol(&value.locale),
+            is_definitions: value.is_definitions,
+            content_hash: value.content_hash,
+            new_content: value.new_content,
+            diff: value.diff,
+            messages: value.messages.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A value that the pattern matched but that can't be rewritten in its file.
+#[napi(object)]
+pub struct IntlSkippedReplacement {
+    pub key: String,
+    pub locale: String,
+    pub reason: String,
+}
+
+/// Every change planned by `previewReplacements`, to be given to `applyReplacements`.
+#[napi(object)]
+pub struct IntlReplacementPreview {
+    pub files: Vec<IntlFileReplacement>,
+    pub skipped: Vec<IntlSkippedReplacement>,
+}
+
+impl From<ReplacementPreview> for IntlReplacementPreview {
+    fn from(value: ReplacementPreview) -> Self {
+        Self {
+            files: value.files.into_iter().map(Into::into).collect(),
+            skipped: value
+                .skipped
+                .into_iter()
+                .map(|skipped| IntlSkippedReplacement {
+                    key: skipped.key.to_string(),
+                    locale: skipped.locale.to_string(),
+                    reason: skipped.reason,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[napi(object)]
 pub struct IntlMultiProcessingResult {
     pub processed: Vec<String>,
@@ -171,9 +1636,131 @@ impl From<MultiProcessingResult> for IntlMultiProcessingResult {
                 .into_iter()
                 .map(|(key, error)| IntlMultiProcessingFailure {
                     file: key.to_string(),
+                    code: IntlError::from(&error).code,
                     error: error.to_string(),
                 })
                 .collect(),
         }
     }
 }
+
+#[napi]
+pub enum IntlPerformancePhase {
+    Parse,
+    Compile,
+}
+
+impl From<PerformancePhase> for IntlPerformancePhase {
+    fn from(value: PerformancePhase) -> Self {
+        match value {
+            PerformancePhase::Parse => IntlPerformancePhase::Parse,
+            PerformancePhase::Compile => IntlPerformancePhase::Compile,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlMessageTiming {
+    pub key: String,
+    pub locale: String,
+    pub file: Option<String>,
+    pub phase: IntlPerformancePhase,
+    /// Time spent on this phase of the message, in milliseconds.
+    pub duration: f64,
+}
+
+impl From<MessageTiming> for IntlMessageTiming {
+    fn from(value: MessageTiming) -> Self {
+        Self {
+            key: value.key.to_string(),
+            locale: value.locale.to_string(),
+            file: value.file.map(|file| file.to_string()),
+            phase: value.phase.into(),
+            duration: value.duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlFileTiming {
+    pub file: String,
+    pub phase: IntlPerformancePhase,
+    #[napi(js_name = "messageCount")]
+    pub message_count: u32,
+    /// Total time spent on this phase for every message in the file, in milliseconds.
+    pub total: f64,
+    /// Time spent on the slowest message in the file, in milliseconds.
+    pub slowest: f64,
+}
+
+impl From<FileTiming> for IntlFileTiming {
+    fn from(value: FileTiming) -> Self {
+        Self {
+            file: value.file.to_string(),
+            phase: value.phase.into(),
+            message_count: value.message_count as u32,
+            total: value.total.as_secs_f64() * 1000.0,
+            slowest: value.slowest.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlRuleTiming {
+    pub rule: String,
+    /// Number of message values that the rule checked.
+    #[napi(js_name = "valueCount")]
+    pub value_count: u32,
+    /// Total time spent running the rule on every value, in milliseconds.
+    pub total: f64,
+    /// Time spent on the slowest value, in milliseconds.
+    pub slowest: f64,
+}
+
+impl From<RuleTiming> for IntlRuleTiming {
+    fn from(value: RuleTiming) -> Self {
+        Self {
+            rule: value.rule.to_string(),
+            value_count: value.value_count as u32,
+            total: value.total.as_secs_f64() * 1000.0,
+            slowest: value.slowest.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// How far an async task has gotten through its work, like the number of messages validated so
+/// far out of every message in the database.
+#[napi(object)]
+pub struct IntlServiceProgress {
+    pub completed: u32,
+    pub total: u32,
+}
+
+impl From<ServiceProgress> for IntlServiceProgress {
+    fn from(value: ServiceProgress) -> Self {
+        Self {
+            completed: value.completed as u32,
+            total: value.total as u32,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct IntlPerformanceReport {
+    /// The slowest individual messages, slowest first.
+    pub messages: Vec<IntlMessageTiming>,
+    /// Totals for every source file with recorded timings, slowest first.
+    pub files: Vec<IntlFileTiming>,
+    /// Totals for every validation rule from the most recent validation, slowest first.
+    pub rules: Vec<IntlRuleTiming>,
+}
+
+impl From<PerformanceReport> for IntlPerformanceReport {
+    fn from(value: PerformanceReport) -> Self {
+        Self {
+            messages: value.messages.into_iter().map(Into::into).collect(),
+            files: value.files.into_iter().map(Into::into).collect(),
+            rules: value.rules.into_iter().map(Into::into).collect(),
+        }
+    }
+}
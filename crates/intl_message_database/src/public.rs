@@ -5,42 +5,124 @@
 //! casting to and from the caller types and then call one of these functions. Any implementation
 //! of multiple calls should become a new function here rather than in the wrapper, unless it is
 //! language-specific to the host (like constructing a host object for object-oriented languages).
-use crate::sources::{get_locale_from_file_name, MessagesFileDescriptor};
+use crate::sources::replace::{
+    FileReplacement, ReplaceInMessages, ReplacedMessage, ReplacementPreview,
+};
+use crate::sources::rewrite::{apply_file_edits, rewrite_value, MessageEdit, RewriteError};
+use crate::sources::{
+    get_locale_from_file_name, normalize_locale_key, MessagesFileDescriptor,
+    MessagesFileDiscoveryOptions,
+};
 use crate::threading::run_in_thread_pool;
 use intl_database_core::{
-    get_key_symbol, key_symbol, DatabaseError, DatabaseResult, KeySymbol, Message, MessageValue,
-    MessagesDatabase, RawMessageDefinition, RawMessageTranslation, SourceFile, DEFAULT_LOCALE,
+    get_key_symbol, key_symbol, DatabaseError, DatabaseResult, DefinitionFile, FilePosition,
+    KeySymbol, KeySymbolSet, Message, MessageQuery, MessageQueryResult, MessageRevision,
+    MessageValue, MessageVariableType, MessagesDatabase, PerformanceReport, Project,
+    RawMessageDefinition, RawMessageTranslation, ReviewState, SourceFile, SourceFileInsertionData,
+    TranslationFile,
+};
+use intl_database_exporter::{
+    diff_compiled_bundles, BundleDiff, ExportTranslations, ExportedTranslationFiles,
+    IntlMessageBundler, IntlMessageBundlerOptions, MachineTranslationProvider, MessageKeyAliases,
+    MessagePlaceholderRenames, PrefillMachineTranslations, SecretRedaction,
+};
+use intl_database_service::{IntlDatabaseService, ServiceContext};
+use intl_database_types_generator::{
+    plan_split_types, IntlTypesGenerator, IntlTypesGeneratorOptions, TypesSplitMode,
 };
-use intl_database_exporter::{ExportTranslations, IntlMessageBundler, IntlMessageBundlerOptions};
-use intl_database_service::IntlDatabaseService;
-use intl_database_types_generator::IntlTypesGenerator;
-use intl_validator::{validate_message, MessageDiagnostic};
+use intl_markdown::{
+    compile_to_format_js, parse_intl_message_cst_with_options, to_icu_string, CompileArena,
+    CstNode, ParserOptions,
+};
+use intl_message_utils::{is_message_definitions_file_with_extensions, message_may_have_blocks};
+use intl_validator::{
+    collect_message_words, to_sarif_log, validate_message_references,
+    validate_message_references_with_timings, validate_message_spelling,
+    validate_message_with_cache, validate_message_with_config, DiagnosticBaseline,
+    DiagnosticSeverity, MessageDiagnostic, MessageWord, MisspelledWords, NoUnknownHookNamesOptions,
+    RuleTimings, SpellChecker, ValidationCache, ValidationSummary, ValueValidatorConfig,
+};
+use regex::Regex;
 use rustc_hash::FxHashMap;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Look up the symbol for a locale tag by its normalized form, so that callers can use any spelling
+/// of a locale, like `en_gb` for `en-GB`.
+fn get_locale_symbol_or_error(locale: &str) -> DatabaseResult<KeySymbol> {
+    let normalized = normalize_locale_tag(locale);
+    get_key_symbol(normalized.as_deref().unwrap_or(locale))
+        .ok_or_else(|| DatabaseError::UnknownLocale(locale.to_string()))
+}
 
-fn get_key_symbol_or_error(value: &str) -> DatabaseResult<KeySymbol> {
-    get_key_symbol(value).ok_or(DatabaseError::ValueNotInterned(value.to_string()))
+/// Look up the symbol for the path of a source file in the database.
+fn get_source_file_symbol_or_error(file_path: &str) -> DatabaseResult<KeySymbol> {
+    get_key_symbol(file_path).ok_or_else(|| DatabaseError::UnknownSourceFile(key_symbol(file_path)))
 }
 
 /// Scan the file system within the given `source_directories` for all messages files contained
-/// within them. Each returned entry will have the file path and the locale it should represent,
-/// defaulting to `default_definition_locale` for definitions.
+/// within them, including definitions files with any of the database's configured extensions.
+/// Each returned entry will have the file path and the locale it should represent, defaulting to
+/// `default_definition_locale` for definitions.
 ///
 /// For large repositories, this can be quite slow, as all folders are scanned, including
-/// `node_modules` and others.
+/// `node_modules` and others. Use [find_all_messages_files_with_options] to exclude them.
 pub fn find_all_messages_files<A: AsRef<str>>(
+    database: &MessagesDatabase,
     source_directories: impl Iterator<Item = A>,
     default_definition_locale: &str,
 ) -> Vec<MessagesFileDescriptor> {
-    crate::sources::find_all_messages_files(source_directories, default_definition_locale).collect()
+    find_all_messages_files_with_options(
+        database,
+        source_directories,
+        default_definition_locale,
+        &MessagesFileDiscoveryOptions::default(),
+    )
+    .expect("Default discovery options have no exclude globs that could be invalid")
+}
+
+/// Scan the file system like [find_all_messages_files], using `options` to control which files
+/// and directories are visited, like skipping build output directories with exclude globs.
+pub fn find_all_messages_files_with_options<A: AsRef<str>>(
+    database: &MessagesDatabase,
+    source_directories: impl Iterator<Item = A>,
+    default_definition_locale: &str,
+    options: &MessagesFileDiscoveryOptions,
+) -> anyhow::Result<Vec<MessagesFileDescriptor>> {
+    let files = crate::sources::find_all_messages_files(
+        source_directories,
+        default_definition_locale,
+        &database.definitions_extensions,
+        options,
+    )?;
+    Ok(files
+        .map(|descriptor| with_project_locale(database, descriptor))
+        .collect())
+}
+
+/// Use the source locale of the project containing a definitions file in place of the default
+/// definition locale given for discovery, since the project's is more specific.
+fn with_project_locale(
+    database: &MessagesDatabase,
+    mut descriptor: MessagesFileDescriptor,
+) -> MessagesFileDescriptor {
+    if is_message_translations_file(&descriptor.file_path.to_string_lossy()) {
+        return descriptor;
+    }
+    let project = database.get_project_for_file(&descriptor.file_path);
+    if let Some(source_locale) = project.and_then(|project| project.source_locale) {
+        descriptor.locale = source_locale;
+    }
+    descriptor
 }
 
 /// Given a list of sources files, filter out all files except for those that can be treated as
 /// messages files, either definitions or translations. Each returned entry will have the file path
 /// and the locale it should represent, defaulting to `default_definition_locale` for definitions.
 pub fn filter_all_messages_files<A: AsRef<str>>(
+    database: &MessagesDatabase,
     files: impl Iterator<Item = A>,
     default_definition_locale: &str,
 ) -> Vec<MessagesFileDescriptor> {
@@ -48,14 +130,15 @@ pub fn filter_all_messages_files<A: AsRef<str>>(
     let mut result = vec![];
     for file in files {
         let file = file.as_ref();
-        if !is_message_definitions_file(file) && !is_message_translations_file(file) {
+        if !database.is_definitions_file(file) && !is_message_translations_file(file) {
             continue;
         }
         let locale = get_locale_from_file_name(file, definition_locale_key);
-        result.push(MessagesFileDescriptor {
+        let descriptor = MessagesFileDescriptor {
             file_path: PathBuf::from(file),
             locale,
-        });
+        };
+        result.push(with_project_locale(database, descriptor));
     }
     result
 }
@@ -81,64 +164,117 @@ impl From<Vec<(KeySymbol, DatabaseResult<KeySymbol>)>> for MultiProcessingResult
     }
 }
 
+/// The messages extracted from a single file while processing many files at once, before they
+/// are inserted into the database.
+enum ExtractedMessages {
+    Definitions(SourceFileInsertionData<std::vec::IntoIter<RawMessageDefinition>>),
+    Translations(Vec<RawMessageTranslation>),
+}
+
+/// What each thread in the pool needs from the database to extract the messages from a file.
+#[derive(Clone)]
+struct ExtractionContext {
+    /// The extra extensions of definitions files configured for the database.
+    definitions_extensions: Arc<[String]>,
+    /// Whether extracted values are parsed in the thread that extracts them. When the database is
+    /// recording performance, values are left to be parsed and timed as they are inserted instead.
+    parse_eagerly: bool,
+    parser_options: Option<Arc<ParserOptions>>,
+}
+
+impl ExtractionContext {
+    fn new(database: &MessagesDatabase) -> Self {
+        Self {
+            definitions_extensions: database.definitions_extensions.as_slice().into(),
+            parse_eagerly: !database.is_recording_performance(),
+            parser_options: database.shared_parser_options(),
+        }
+    }
+
+    /// Parse `value` with the database's options, so that the work is spread across the thread
+    /// pool rather than all happening on the main thread as each value is inserted.
+    fn parse(&self, value: &mut MessageValue) {
+        if self.parse_eagerly {
+            value.parse_with_options(self.parser_options.clone());
+        }
+    }
+
+    fn parse_translations(&self, translations: &mut [RawMessageTranslation]) {
+        for translation in translations {
+            self.parse(&mut translation.value);
+        }
+    }
+}
+
+/// Extract all of the messages from the content of a single messages file, as either definitions
+/// or translations depending on the type of file, and parse their values using `context`.
+fn extract_messages_file(
+    file_key: KeySymbol,
+    content: &str,
+    context: &ExtractionContext,
+) -> DatabaseResult<ExtractedMessages> {
+    if is_message_definitions_file_with_extensions(&file_key, &context.definitions_extensions) {
+        let data = crate::sources::extract_definitions_from_file(
+            file_key,
+            content,
+            &context.definitions_extensions,
+        )?;
+        let mut definitions = data.definitions.collect::<Vec<_>>();
+        for definition in &mut definitions {
+            context.parse(&mut definition.value);
+        }
+        Ok(ExtractedMessages::Definitions(SourceFileInsertionData {
+            meta: data.meta,
+            definitions: definitions.into_iter(),
+            errors: data.errors,
+        }))
+    } else {
+        let translations =
+            crate::sources::extract_translations_from_file(file_key, content.into())?;
+        let mut translations = translations.collect::<Vec<_>>();
+        context.parse_translations(&mut translations);
+        Ok(ExtractedMessages::Translations(translations))
+    }
+}
+
 /// Given a list of directories, scan their entire contents to find all messages files (both
 /// definitions _and_ translations), then process their content into the database.
 ///
 /// Returns a list of processing results containing the file key and information about whether it
 /// was processed successfully.
+///
+/// Every value is parsed in the thread that extracts it, so most of the parsing happens in
+/// parallel rather than lazily on the main thread once the values are in the database.
 pub fn process_all_messages_files(
     database: &mut MessagesDatabase,
     files: impl Iterator<Item = MessagesFileDescriptor> + ExactSizeIterator,
 ) -> anyhow::Result<MultiProcessingResult> {
+    let context = ExtractionContext::new(database);
+    let files = files.map(|descriptor| (descriptor, context.clone()));
     let results = run_in_thread_pool(
         files,
-        |descriptor| {
+        |(descriptor, context)| {
             let MessagesFileDescriptor { file_path, locale } = descriptor;
-            let content = std::fs::read_to_string(&file_path).expect(&format!(
-                "Failed to read messages file at {}",
-                file_path.display()
-            ));
-            let file_path = key_symbol(&file_path.to_string_lossy());
-
-            let (definitions, translations) = if is_message_definitions_file(&file_path) {
-                match crate::sources::extract_definitions_from_file(file_path, &content) {
-                    Ok((meta, definitions)) => (
-                        Some((meta, definitions.collect::<Vec<RawMessageDefinition>>())),
-                        None,
-                    ),
-                    _ => (None, None),
-                }
-            } else {
-                let translations = crate::sources::extract_translations_from_file(
-                    key_symbol(&file_path),
-                    &content,
-                )
-                .map(|translations| translations.collect::<Vec<RawMessageTranslation>>());
-                (None, Some(translations))
-            };
-            (locale, file_path, definitions, translations)
+            let file_key = key_symbol(&file_path.to_string_lossy());
+            let extracted = std::fs::read_to_string(&file_path)
+                .map_err(|error| DatabaseError::ReadError(file_key.to_string(), error))
+                .and_then(|content| extract_messages_file(file_key, &content, &context));
+            (locale, file_key, extracted)
         },
-        |(locale, file_path, definitions, translations)| {
-            let result = if let Some((source_meta, definitions)) = definitions {
-                crate::sources::insert_definitions(
-                    database,
-                    file_path,
-                    locale,
-                    source_meta,
-                    definitions.into_iter(),
-                )
-            } else if let Some(translations) = translations {
-                translations.and_then(|translations| {
+        |(locale, file_path, extracted)| {
+            let result = extracted.and_then(|extracted| match extracted {
+                ExtractedMessages::Definitions(data) => {
+                    crate::sources::insert_definitions(database, file_path, locale, data)
+                }
+                ExtractedMessages::Translations(translations) => {
                     crate::sources::insert_translations(
                         database,
                         file_path,
                         locale,
                         translations.into_iter(),
                     )
-                })
-            } else {
-                Err(DatabaseError::NoExtractableValues(file_path.to_string()))
-            };
+                }
+            });
             (file_path, result)
         },
     )?;
@@ -150,40 +286,56 @@ pub fn process_definitions_file(
     file_path: &str,
     locale: Option<&str>,
 ) -> anyhow::Result<KeySymbol> {
-    let content = std::fs::read_to_string(&file_path)?;
+    let content = std::fs::read_to_string(file_path)
+        .map_err(|error| DatabaseError::ReadError(file_path.to_string(), error))?;
     process_definitions_file_content(database, file_path, &content, locale)
 }
 
+/// Process the given `content` of a definitions file into the database.
+///
+/// When no `locale` is given, the definitions are written in the source locale of the project
+/// containing the file, or in the database's source locale when it isn't in a project that sets
+/// one.
 pub fn process_definitions_file_content(
     database: &mut MessagesDatabase,
     file_path: &str,
     content: &str,
     locale: Option<&str>,
 ) -> anyhow::Result<KeySymbol> {
-    let source_file = crate::sources::process_definitions_file(
-        database,
-        &file_path,
-        &content,
-        locale.as_ref().map_or(DEFAULT_LOCALE, |locale| &locale),
-    )?;
+    let locale = locale.map_or_else(
+        || database.get_source_locale_for_file(file_path),
+        key_symbol,
+    );
+    let source_file =
+        crate::sources::process_definitions_file(database, file_path, content, &locale)?;
     Ok(source_file)
 }
 
+/// Process every translation file in `locale_map`, keyed by the locale of the file. Like
+/// [process_all_messages_files], every value is parsed in the thread that extracts it.
 pub fn process_all_translation_files(
     database: &mut MessagesDatabase,
     locale_map: HashMap<String, String>,
 ) -> anyhow::Result<MultiProcessingResult> {
+    let context = ExtractionContext::new(database);
+    let files = locale_map
+        .into_iter()
+        .map(|(locale, file_path)| (locale, file_path, context.clone()));
     let results = run_in_thread_pool(
-        locale_map.into_iter(),
-        |(locale, file_path)| {
-            let content = std::fs::read_to_string(&file_path)
-                .expect(&format!("Failed to read translation file at {}", file_path));
-            (
-                key_symbol(&locale),
-                key_symbol(&file_path),
-                crate::sources::extract_translations_from_file(key_symbol(&file_path), &content)
-                    .map(|translations| translations.collect::<Vec<RawMessageTranslation>>()),
-            )
+        files,
+        |(locale, file_path, context)| {
+            let file_key = key_symbol(&file_path);
+            let translations = std::fs::read_to_string(&file_path)
+                .map_err(|error| DatabaseError::ReadError(file_path, error))
+                .and_then(|content| {
+                    crate::sources::extract_translations_from_file(file_key, content.into())
+                })
+                .map(|translations| {
+                    let mut translations = translations.collect::<Vec<RawMessageTranslation>>();
+                    context.parse_translations(&mut translations);
+                    translations
+                });
+            (key_symbol(&locale), file_key, translations)
         },
         |(locale, file_path, translations)| {
             (
@@ -207,7 +359,8 @@ pub fn process_translation_file(
     file_path: &str,
     locale: &str,
 ) -> anyhow::Result<KeySymbol> {
-    let content = std::fs::read_to_string(&file_path)?;
+    let content = std::fs::read_to_string(file_path)
+        .map_err(|error| DatabaseError::ReadError(file_path.to_string(), error))?;
     process_translation_file_content(database, file_path, &locale, &content)
 }
 
@@ -222,19 +375,210 @@ pub fn process_translation_file_content(
     Ok(source_file)
 }
 
+pub fn process_compiled_bundle_file(
+    database: &mut MessagesDatabase,
+    file_path: &str,
+    locale: &str,
+) -> anyhow::Result<KeySymbol> {
+    let content = std::fs::read_to_string(file_path)
+        .map_err(|error| DatabaseError::ReadError(file_path.to_string(), error))?;
+    process_compiled_bundle_file_content(database, file_path, locale, &content)
+}
+
+pub fn process_compiled_bundle_file_content(
+    database: &mut MessagesDatabase,
+    file_path: &str,
+    locale: &str,
+    content: &str,
+) -> anyhow::Result<KeySymbol> {
+    let source_file =
+        crate::sources::process_compiled_bundle_file(database, file_path, locale, content)?;
+    Ok(source_file)
+}
+
+/// Create a new, empty database whose definitions are written in `source_locale`.
+///
+/// When no locale is given, [intl_database_core::DEFAULT_LOCALE] is used. Files ending with any of
+/// the `definitions_extensions`, like `.messages.mjs`, are treated as definitions files in addition
+/// to the built-in `.messages.js` variants.
+pub fn create_database(
+    source_locale: Option<&str>,
+    definitions_extensions: Vec<String>,
+) -> MessagesDatabase {
+    let database = MessagesDatabase::new().with_definitions_extensions(definitions_extensions);
+    match source_locale {
+        Some(locale) => database.with_source_locale(normalize_locale_key(key_symbol(locale))),
+        None => database,
+    }
+}
+
+/// Set the names of the hooks and link handlers that the app provides.
+///
+/// Validation checks every hook and link handler in the database against them. Names of either kind
+/// aren't checked when they aren't given.
+pub fn set_handler_names(
+    database: &mut MessagesDatabase,
+    hook_names: Option<Vec<String>>,
+    link_handler_names: Option<Vec<String>>,
+) {
+    database.hook_names = hook_names;
+    database.link_handler_names = link_handler_names;
+}
+
+/// Set the extensions of definitions files in addition to the built-in `.messages.js` variants,
+/// replacing the ones the database was created with. Files that were already processed are kept.
+pub fn set_definitions_extensions(database: &mut MessagesDatabase, extensions: Vec<String>) {
+    database.definitions_extensions = extensions;
+}
+
+/// Set the options that every message in the database is parsed with. Messages that were already
+/// processed keep their content and are parsed again the next time it's needed.
+pub fn set_parser_options(database: &mut MessagesDatabase, parser_options: ParserOptions) {
+    database.set_parser_options(parser_options);
+}
+
+/// Register a package in a monorepo as a project in the database.
+///
+/// Queries, exports, and validation can then be limited to the messages it defines. Every file
+/// under `root` belongs to the project, and definitions in it use `source_locale` when they aren't
+/// given a locale. Registering a project with the same name again replaces it.
+pub fn register_project(
+    database: &mut MessagesDatabase,
+    name: &str,
+    root: &str,
+    source_locale: Option<&str>,
+    translations_path: Option<&str>,
+) {
+    let project = Project::new(key_symbol(name), root)
+        .with_source_locale(source_locale.map(|locale| normalize_locale_key(key_symbol(locale))))
+        .with_translations_path(translations_path.map(PathBuf::from));
+    database.register_project(project);
+}
+
+/// Return every project registered in the database, sorted by name.
+pub fn get_projects(database: &MessagesDatabase) -> Vec<&Project> {
+    let mut projects: Vec<&Project> = database.projects.values().collect();
+    projects.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()));
+    projects
+}
+
+/// Return the project that `file_path` belongs to, if it is inside of any registered project.
+pub fn get_project_for_file<'a>(
+    database: &'a MessagesDatabase,
+    file_path: &str,
+) -> Option<&'a Project> {
+    database.get_project_for_file(file_path)
+}
+
+/// Return the locale that definitions in the database are written in, unless their source file
+/// sets its own `sourceLocale`.
+pub fn get_source_locale(database: &MessagesDatabase) -> KeySymbol {
+    database.source_locale
+}
+
 pub fn get_known_locales(database: &MessagesDatabase) -> Vec<KeySymbol> {
     let locales = &database.known_locales;
 
     Vec::from_iter(locales.clone())
 }
 
+/// Return the known locale that `tag` refers to, matching any spelling of it, like `pt_br` or
+/// `PT-BR` for `pt-BR`. Returns `None` if the database has no values in that locale.
+pub fn resolve_locale(database: &MessagesDatabase, tag: &str) -> Option<KeySymbol> {
+    let locale = get_locale_symbol_or_error(tag).ok()?;
+    database.known_locales.contains(&locale).then_some(locale)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocaleTagProblem {
+    /// The tag is not a well-formed BCP 47 locale tag, so it could not be normalized.
+    Invalid,
+    /// Other files use a different spelling of the same locale, like `en_GB` and `en-GB`, so
+    /// their translations are merged into one locale.
+    Duplicate,
+}
+
+/// A translation file whose locale tag can't be cleanly mapped to a single locale.
+pub struct LocaleTagDiagnostic {
+    pub file_path: KeySymbol,
+    /// The locale tag as it was given for the file.
+    pub locale_tag: String,
+    /// The locale that the file's translations were stored under.
+    pub locale: KeySymbol,
+    pub problem: LocaleTagProblem,
+    pub description: String,
+}
+
+/// Check the locale tags of every translation file in the database, reporting tags that aren't
+/// well-formed and locales that are spelled in more than one way across files. Results are sorted
+/// by file path.
+pub fn validate_locale_tags(database: &MessagesDatabase) -> Vec<LocaleTagDiagnostic> {
+    let mut files: Vec<_> = database
+        .sources
+        .values()
+        .filter_map(|source| match source {
+            SourceFile::Translation(file) => Some(file),
+            SourceFile::Definition(_) => None,
+        })
+        .collect();
+    files.sort_by(|a, b| a.file().cmp(b.file()));
+
+    let mut spellings: FxHashMap<KeySymbol, Vec<&str>> = FxHashMap::default();
+    for file in &files {
+        let tags = spellings.entry(*file.locale()).or_default();
+        if !tags.contains(&file.locale_tag().as_str()) {
+            tags.push(file.locale_tag());
+        }
+    }
+
+    let mut diagnostics = vec![];
+    for file in files {
+        let locale_tag = file.locale_tag();
+        let (problem, description) = if normalize_locale_tag(locale_tag).is_none() {
+            (
+                LocaleTagProblem::Invalid,
+                format!("\"{locale_tag}\" is not a valid locale tag"),
+            )
+        } else {
+            let others: Vec<&str> = spellings[file.locale()]
+                .iter()
+                .filter(|tag| *tag != locale_tag)
+                .copied()
+                .collect();
+            if others.is_empty() {
+                continue;
+            }
+            (
+                LocaleTagProblem::Duplicate,
+                format!(
+                    "\"{locale_tag}\" is the same locale as {}, which are all stored as \"{}\"",
+                    others
+                        .iter()
+                        .map(|tag| format!("\"{tag}\""))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    file.locale()
+                ),
+            )
+        };
+        diagnostics.push(LocaleTagDiagnostic {
+            file_path: key_symbol(file.file()),
+            locale_tag: locale_tag.clone(),
+            locale: *file.locale(),
+            problem,
+            description,
+        });
+    }
+    diagnostics
+}
+
 pub fn get_source_file<'a>(
     database: &'a MessagesDatabase,
     file_path: &str,
 ) -> anyhow::Result<&'a SourceFile> {
-    let file_symbol = get_key_symbol_or_error(file_path)?;
+    let file_symbol = get_source_file_symbol_or_error(file_path)?;
     let Some(source) = database.sources.get(&file_symbol) else {
-        return Err(DatabaseError::SymbolNotFound(file_symbol).into());
+        return Err(DatabaseError::UnknownSourceFile(file_symbol).into());
     };
 
     Ok(source)
@@ -250,9 +594,9 @@ pub fn get_source_file_key_map(
     database: &MessagesDatabase,
     file_path: &str,
 ) -> anyhow::Result<FxHashMap<String, KeySymbol>> {
-    let file_symbol = get_key_symbol_or_error(file_path)?;
+    let file_symbol = get_source_file_symbol_or_error(file_path)?;
     let Some(source) = database.sources.get(&file_symbol) else {
-        return Err(DatabaseError::SymbolNotFound(file_symbol).into());
+        return Err(DatabaseError::UnknownSourceFile(file_symbol).into());
     };
 
     let mut hashes = FxHashMap::default();
@@ -270,19 +614,275 @@ pub fn get_source_file_key_map(
 pub fn get_message<'a>(database: &'a MessagesDatabase, key: &str) -> anyhow::Result<&'a Message> {
     let definition = database
         .get_message(&key)
-        .ok_or_else(|| DatabaseError::ValueNotInterned(key.to_string()))?;
+        .ok_or_else(|| DatabaseError::UnknownMessage(key.to_string()))?;
 
     Ok(definition)
 }
 
+/// Which version of the definition of a message a translation was written for.
+pub struct TranslationVersion<'a> {
+    pub locale: KeySymbol,
+    /// Content hash of the definition when the translation was set, if it's known.
+    pub source_hash: Option<&'a str>,
+    /// True if the definition has changed since the translation was set.
+    pub is_outdated: bool,
+}
+
+/// The versions of the definition of a message that the database has seen, and the version that
+/// each of its translations was written for.
+pub struct MessageHistory<'a> {
+    pub key: KeySymbol,
+    /// Versions of the definition, oldest first.
+    pub revisions: &'a [MessageRevision],
+    /// Every translation other than the source, sorted by locale.
+    pub translations: Vec<TranslationVersion<'a>>,
+}
+
+/// Return the previous versions of the definition of the message `key`.
+///
+/// These show what the message said at an earlier time, and which version each translation was
+/// written for. Only the versions that were processed since the database was created are known.
+pub fn get_message_history<'a>(
+    database: &'a MessagesDatabase,
+    key: &str,
+) -> anyhow::Result<MessageHistory<'a>> {
+    let message = get_message(database, key)?;
+    let mut translations: Vec<_> = message
+        .translations()
+        .iter()
+        .filter(|(locale, _)| *message.source_locale() != Some(**locale))
+        .map(|(locale, value)| TranslationVersion {
+            locale: *locale,
+            source_hash: value.source_hash.as_deref(),
+            is_outdated: message.is_translation_outdated(*locale),
+        })
+        .collect();
+    translations.sort_by_key(|translation| translation.locale.as_str());
+    Ok(MessageHistory {
+        key: message.key(),
+        revisions: message.history(),
+        translations,
+    })
+}
+
+/// A variable used in the source of a message, described for autocompleting it while writing
+/// translations.
+pub struct VariableCompletion {
+    pub name: KeySymbol,
+    /// Every kind of value the variable is used as, like `number` or `select`.
+    pub kinds: Vec<&'static str>,
+    pub is_builtin: bool,
+    /// Each distinct way the variable is written in the source message, ready to be inserted into
+    /// a translation. Empty for builtin variables, which are written as markdown syntax instead.
+    pub examples: Vec<String>,
+}
+
+/// Return every variable used in the source message of `key`, for editors to autocomplete while
+/// writing translations. Variables that callers provide come first, then builtins, each sorted by
+/// name.
+pub fn get_variable_completions(
+    database: &MessagesDatabase,
+    key: &str,
+) -> anyhow::Result<Vec<VariableCompletion>> {
+    let message = get_message(database, key)?;
+    let Some(variables) = message.source_variables() else {
+        return Ok(vec![]);
+    };
+
+    let mut completions = Vec::with_capacity(variables.count());
+    for (name, instances) in variables.iter() {
+        let is_builtin = instances.iter().any(|instance| instance.is_builtin);
+        let is_plural = instances
+            .iter()
+            .any(|instance| instance.kind == MessageVariableType::Plural);
+        let mut kinds = vec![];
+        let mut examples = vec![];
+        for instance in instances {
+            // Plurals also record their own name as a plain variable, and each `#` in their arms
+            // as a number, neither of which is a separate way of writing the variable.
+            if is_plural
+                && matches!(
+                    instance.kind,
+                    MessageVariableType::Any | MessageVariableType::Number(None)
+                )
+            {
+                continue;
+            }
+            let kind = instance.kind.kind_name();
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+            let example = instance.kind.example_usage(name);
+            if !is_builtin && !examples.contains(&example) {
+                examples.push(example);
+            }
+        }
+        completions.push(VariableCompletion {
+            name: *name,
+            kinds,
+            is_builtin,
+            examples,
+        });
+    }
+    completions
+        .sort_by(|a, b| (a.is_builtin, a.name.as_str()).cmp(&(b.is_builtin, b.name.as_str())));
+    Ok(completions)
+}
+
+/// Return the content hash of every defined message, keyed by the message key.
+///
+/// Hashes only change when the source value or meta of a message changes, so build tools can
+/// compare them between runs to find exactly which messages changed.
+pub fn get_content_hashes(database: &MessagesDatabase) -> FxHashMap<KeySymbol, &str> {
+    database
+        .messages
+        .values()
+        .filter_map(|message| Some((message.key(), message.content_hash()?)))
+        .collect()
+}
+
+/// Return the manifest mapping each alias used by [BundleKeyMode::Alias] bundles to its message key.
+///
+/// Aliases are assigned sequentially over the sorted keys of every defined message, so the
+/// manifest has to come from the same database that the bundles were compiled from.
+///
+/// [BundleKeyMode::Alias]: intl_database_exporter::BundleKeyMode::Alias
+pub fn get_message_key_aliases(database: &MessagesDatabase) -> BTreeMap<String, KeySymbol> {
+    MessageKeyAliases::from_database(database)
+        .manifest()
+        .into_iter()
+        .map(|(alias, key)| (alias.to_string(), key))
+        .collect()
+}
+
+/// A defined message that has no translation in a locale, with the location of its definition.
+pub struct UntranslatedMessage<'a> {
+    pub key: KeySymbol,
+    pub source: &'a str,
+    pub file_position: Option<FilePosition>,
+}
+
+/// Return every defined message that has no translation in `locale`, sorted by key.
+///
+/// Messages with `translate: false` are never sent for translation, so they are left out. A locale
+/// that no file has been processed for yet is missing every message.
+pub fn get_untranslated_messages<'a>(
+    database: &'a MessagesDatabase,
+    locale: &str,
+) -> anyhow::Result<Vec<UntranslatedMessage<'a>>> {
+    let locale = key_symbol(normalize_locale_tag(locale).as_deref().unwrap_or(locale));
+    let query = MessageQuery::new()
+        .without_locale(Some(locale))
+        .with_translate(Some(true));
+    let result = database.find_messages(&query)?;
+    Ok(result
+        .messages
+        .into_iter()
+        .filter_map(|message| {
+            let source = message.get_source_translation()?;
+            Some(UntranslatedMessage {
+                key: message.key(),
+                source: source.raw.as_str(),
+                file_position: source.file_position,
+            })
+        })
+        .collect())
+}
+
+/// Return the translation of the message `key` in `locale` as a standard ICU MessageFormat string,
+/// as it would be compiled into a bundle, for tools that don't understand ICU-Markdown.
+pub fn get_message_icu(
+    database: &MessagesDatabase,
+    key: &str,
+    locale: &str,
+) -> anyhow::Result<String> {
+    let message = get_message(database, key)?;
+    let locale_key = get_locale_symbol_or_error(locale)?;
+    let value = message
+        .translations()
+        .get(&locale_key)
+        .ok_or(DatabaseError::MissingTranslation(message.key(), locale_key))?;
+    let arena = CompileArena::new();
+    let compiled = compile_to_format_js(value.parsed(), &arena);
+    Ok(to_icu_string(&compiled)?)
+}
+
+/// Return the words in the translation of the message `key` in `locale` that should be spell
+/// checked, leaving out placeholders, code, urls, and other text that isn't spelled like words.
+pub fn get_message_words(
+    database: &MessagesDatabase,
+    key: &str,
+    locale: &str,
+) -> anyhow::Result<Vec<MessageWord>> {
+    let message = get_message(database, key)?;
+    let locale_key = get_locale_symbol_or_error(locale)?;
+    let value = message
+        .translations()
+        .get(&locale_key)
+        .ok_or(DatabaseError::MissingTranslation(message.key(), locale_key))?;
+    Ok(collect_message_words(value))
+}
+
+/// The concrete syntax tree of a translation, along with the content it was parsed from.
+pub struct MessageCst<'a> {
+    pub raw: &'a str,
+    /// Every node and token of the tree, in the order they are written.
+    pub nodes: Vec<CstNode>,
+}
+
+/// Return the concrete syntax tree of the translation of the message `key` in `locale`.
+///
+/// This is for tools that need all of the syntax that the AST leaves out. Messages are parsed the
+/// same way as when they are processed, so the tree matches the AST that the database uses.
+pub fn get_message_cst<'a>(
+    database: &'a MessagesDatabase,
+    key: &str,
+    locale: &str,
+) -> anyhow::Result<MessageCst<'a>> {
+    let message = get_message(database, key)?;
+    let locale_key = get_locale_symbol_or_error(locale)?;
+    let value = message
+        .translations()
+        .get(&locale_key)
+        .ok_or(DatabaseError::MissingTranslation(message.key(), locale_key))?;
+    Ok(MessageCst {
+        raw: &value.raw,
+        nodes: parse_intl_message_cst_with_options(
+            &value.raw,
+            message_may_have_blocks(&value.raw),
+            value.parser_options().clone(),
+        ),
+    })
+}
+
+/// Find all messages matching the given query, returning the requested page of results along with
+/// the total number of matches.
+pub fn find_messages<'a>(
+    database: &'a MessagesDatabase,
+    query: &MessageQuery,
+) -> anyhow::Result<MessageQueryResult<'a>> {
+    Ok(database.find_messages(query)?)
+}
+
 pub fn generate_types(
     database: &MessagesDatabase,
     source_file_path: &str,
     output_file_path: &str,
+    options: IntlTypesGeneratorOptions,
+    changed_keys: Option<&[String]>,
 ) -> anyhow::Result<()> {
-    let source_file_key = get_key_symbol_or_error(source_file_path)?;
-    let mut generator =
-        IntlTypesGenerator::new(&database, source_file_key, output_file_path.to_string());
+    let source_file_key = get_source_file_symbol_or_error(source_file_path)?;
+    let mut generator = IntlTypesGenerator::new(
+        database,
+        source_file_key,
+        output_file_path.to_string(),
+        options,
+    );
+    if let Some((output, source_map, changed_keys)) =
+        read_previous_types_output(output_file_path, changed_keys)
+    {
+        generator = generator.with_previous_output(output, source_map, changed_keys);
+    }
     generator.run()?;
     std::fs::write(&output_file_path, generator.take_buffer())?;
     let map_file_path = String::from(output_file_path) + ".map";
@@ -292,6 +892,73 @@ pub fn generate_types(
     Ok(())
 }
 
+/// Read the types file at `output_file_path` and its source map from a previous run, so that only
+/// the entries of `changed_keys` have to be generated again. Returns None when no keys are given
+/// or either file doesn't exist, meaning the types are generated in full.
+fn read_previous_types_output(
+    output_file_path: &str,
+    changed_keys: Option<&[String]>,
+) -> Option<(String, String, KeySymbolSet)> {
+    let changed_keys = changed_keys?;
+    let output = std::fs::read_to_string(output_file_path).ok()?;
+    let source_map = std::fs::read_to_string(String::from(output_file_path) + ".map").ok()?;
+    let changed_keys = changed_keys.iter().map(|key| key_symbol(key)).collect();
+    Some((output, source_map, changed_keys))
+}
+
+/// Generate types for every definitions file under `source_directory` at once.
+///
+/// One declaration file is written for each into `output_directory` at the same relative path,
+/// along with barrel index files laid out according to `mode`. Source maps refer to the definitions
+/// files by their relative paths. Returns the paths of every file that was written.
+pub fn generate_split_types(
+    database: &MessagesDatabase,
+    source_directory: &str,
+    output_directory: &str,
+    mode: TypesSplitMode,
+    options: IntlTypesGeneratorOptions,
+) -> anyhow::Result<Vec<String>> {
+    let source_directory = std::path::absolute(source_directory)?;
+    let output_directory = std::path::absolute(output_directory)?;
+    let plan = plan_split_types(
+        database,
+        &source_directory,
+        &output_directory,
+        mode,
+        options.format(),
+    );
+
+    let mut written = vec![];
+    for file in &plan.files {
+        let output_file_path = file.output_path.to_string_lossy().into_owned();
+        let mut generator = IntlTypesGenerator::new(
+            database,
+            file.source_file_key,
+            output_file_path.clone(),
+            options.clone(),
+        )
+        .with_relative_sources(true);
+        generator.run()?;
+        if let Some(parent) = file.output_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&file.output_path, generator.take_buffer())?;
+        std::fs::write(
+            output_file_path.clone() + ".map",
+            generator.into_sourcemap()?,
+        )?;
+        written.push(output_file_path);
+    }
+    for (path, content) in plan.indexes {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)?;
+        written.push(path.to_string_lossy().into_owned());
+    }
+    Ok(written)
+}
+
 pub fn precompile(
     database: &MessagesDatabase,
     file_path: &str,
@@ -299,7 +966,27 @@ pub fn precompile(
     output_path: &str,
     options: IntlMessageBundlerOptions,
 ) -> anyhow::Result<()> {
-    let buffer = precompile_to_buffer(database, file_path, locale, options)?;
+    precompile_with_context(
+        database,
+        file_path,
+        locale,
+        output_path,
+        options,
+        &ServiceContext::default(),
+    )
+}
+
+/// Precompile the given definitions file like [precompile], reporting progress to `context` after
+/// each message and stopping early once it is cancelled. Nothing is written when cancelled.
+pub fn precompile_with_context(
+    database: &MessagesDatabase,
+    file_path: &str,
+    locale: &str,
+    output_path: &str,
+    options: IntlMessageBundlerOptions,
+    context: &ServiceContext,
+) -> anyhow::Result<()> {
+    let buffer = bundle_to_buffer(database, file_path, locale, options, context)?;
     std::fs::write(output_path, buffer)?;
     Ok(())
 }
@@ -310,58 +997,1223 @@ pub fn precompile_to_buffer(
     locale: &str,
     options: IntlMessageBundlerOptions,
 ) -> anyhow::Result<Vec<u8>> {
-    let locale_key = get_key_symbol_or_error(&locale)?;
-    let source_key = get_key_symbol_or_error(file_path)?;
+    bundle_to_buffer(
+        database,
+        file_path,
+        locale,
+        options,
+        &ServiceContext::default(),
+    )
+}
+
+fn bundle_to_buffer(
+    database: &MessagesDatabase,
+    file_path: &str,
+    locale: &str,
+    options: IntlMessageBundlerOptions,
+    context: &ServiceContext,
+) -> anyhow::Result<Vec<u8>> {
+    let locale_key = get_locale_symbol_or_error(locale)?;
+    let source_key = get_source_file_symbol_or_error(file_path)?;
     let keys_count = database
         .get_source_file(source_key)
         .map_or(0, |source| source.message_keys().len());
     let mut result: Vec<u8> = Vec::with_capacity(keys_count * 80);
-    IntlMessageBundler::new(&database, &mut result, source_key, locale_key, options).run()?;
+    IntlMessageBundler::new(database, &mut result, source_key, locale_key, options)
+        .run_with_context(context)?;
     Ok(result.into())
 }
 
+/// A file that a precompiled bundle depends on.
+pub struct BundleDependency {
+    pub file_path: KeySymbol,
+    /// Hash of the content that the bundle was compiled from, or `None` for a translations file
+    /// that doesn't exist yet. For the definitions file, this covers the key, value, and meta of
+    /// every message it defines, and for others, the key and value of every message that was
+    /// bundled from them.
+    pub content_hash: Option<String>,
+}
+
+pub struct PrecompiledBundle {
+    pub buffer: Vec<u8>,
+    /// Every file that the bundle depends on, sorted by path.
+    pub dependencies: Vec<BundleDependency>,
+}
+
+/// Precompile the given definitions file like [precompile_to_buffer], also returning every file
+/// that the bundle depends on, so that build tools can tell when it needs to be compiled again.
+///
+/// The dependencies are the definitions file itself, every file that a bundled value was read
+/// from, and the translations file that the locale is expected to be read from, even when it
+/// doesn't exist yet, since creating it changes the bundle. Their hashes are computed from the
+/// content in the database that was compiled, not from the files on disk.
+pub fn precompile_with_dependencies(
+    database: &MessagesDatabase,
+    file_path: &str,
+    locale: &str,
+    options: IntlMessageBundlerOptions,
+) -> anyhow::Result<PrecompiledBundle> {
+    let locale_key = get_locale_symbol_or_error(locale)?;
+    let source_key = get_source_file_symbol_or_error(file_path)?;
+    let keys_count = database
+        .get_source_file(source_key)
+        .map_or(0, |source| source.message_keys().len());
+    let mut buffer: Vec<u8> = Vec::with_capacity(keys_count * 80);
+    let mut bundler =
+        IntlMessageBundler::new(database, &mut buffer, source_key, locale_key, options);
+    bundler.run()?;
+
+    let mut hashes: BTreeMap<KeySymbol, Option<String>> = bundler
+        .dependencies()
+        .into_iter()
+        .map(|(file_path, values)| {
+            let mut hasher = xxhash_rust::xxh64::Xxh64::new(0);
+            for (key, value) in values {
+                hasher.update(key.as_bytes());
+                hasher.update(&[0]);
+                hasher.update(value.raw.as_bytes());
+                hasher.update(&[0]);
+            }
+            (file_path, Some(format!("{:016x}", hasher.digest())))
+        })
+        .collect();
+    if let Some(SourceFile::Definition(definition)) = database.get_source_file(source_key) {
+        let mut messages: Vec<&Message> = definition
+            .message_keys()
+            .iter()
+            .filter_map(|key| database.messages.get(key))
+            .collect();
+        messages.sort_unstable_by(|a, b| a.key().as_str().cmp(b.key().as_str()));
+        let mut hasher = xxhash_rust::xxh64::Xxh64::new(0);
+        for message in messages {
+            hasher.update(message.key().as_bytes());
+            hasher.update(&[0]);
+            hasher.update(message.content_hash().unwrap_or_default().as_bytes());
+            hasher.update(&[0]);
+        }
+        hashes.insert(source_key, Some(format!("{:016x}", hasher.digest())));
+
+        if let Some(translations_file) =
+            expected_translations_file(database, source_key, definition, locale_key)
+        {
+            // An existing file that nothing was bundled from still has a hash, so that it's only
+            // `None` while the file doesn't exist.
+            let exists = database.get_source_file(translations_file).is_some();
+            hashes.entry(translations_file).or_insert_with(|| {
+                exists.then(|| format!("{:016x}", xxhash_rust::xxh64::Xxh64::new(0).digest()))
+            });
+        }
+    }
+
+    let dependencies = hashes
+        .into_iter()
+        .map(|(file_path, content_hash)| BundleDependency {
+            file_path,
+            content_hash,
+        })
+        .collect();
+    Ok(PrecompiledBundle {
+        buffer,
+        dependencies,
+    })
+}
+
+/// Returns the translations file that the messages of `definition` are read from for `locale`:
+/// the one already in the database from its translations directory, or the path it would have
+/// there, like `messages/fr.messages.json`. Returns `None` for the file's source locale and when
+/// the file sets `translate: false`.
+fn expected_translations_file(
+    database: &MessagesDatabase,
+    file_path: KeySymbol,
+    definition: &DefinitionFile,
+    locale: KeySymbol,
+) -> Option<KeySymbol> {
+    let meta = definition.meta();
+    let source_locale = meta.source_locale.as_deref().map_or_else(
+        || database.get_source_locale_for_file(file_path.as_str()),
+        key_symbol,
+    );
+    if !meta.translate || locale == source_locale {
+        return None;
+    }
+    let directory = normalize_path(
+        &Path::new(file_path.as_str())
+            .parent()?
+            .join(&meta.translations_path),
+    );
+    let existing = database.sources.values().find_map(|source| match source {
+        SourceFile::Translation(translation)
+            if *translation.locale() == locale
+                && Path::new(translation.file())
+                    .parent()
+                    .is_some_and(|parent| normalize_path(parent) == directory) =>
+        {
+            Some(key_symbol(translation.file()))
+        }
+        _ => None,
+    });
+    existing.or_else(|| {
+        let path = directory.join(format!("{locale}.messages.json"));
+        Some(key_symbol(&path.to_string_lossy()))
+    })
+}
+
+/// Compare two compiled bundles and find the message behind every entry that changed.
+///
+/// This is for bundles like those of a definitions file from two releases. Each changed entry is
+/// attributed to its message and definitions file, so that the growth of the bundle can be
+/// attributed to specific features. Messages are found by the keys they have in the database now.
+pub fn diff_bundles(
+    database: &MessagesDatabase,
+    before: &[u8],
+    after: &[u8],
+) -> anyhow::Result<BundleDiff> {
+    let mut diff = diff_compiled_bundles(before, after)?;
+    diff.resolve_keys(database);
+    Ok(diff)
+}
+
+/// Precompile the given definitions file and compare the result to `previous`, like [diff_bundles].
+///
+/// The file is compiled like [precompile_to_buffer]. `previous` must be a bundle of the same file
+/// and locale that was compiled earlier with the same `options`.
+pub fn diff_precompiled_bundle(
+    database: &MessagesDatabase,
+    file_path: &str,
+    locale: &str,
+    previous: &[u8],
+    options: IntlMessageBundlerOptions,
+) -> anyhow::Result<BundleDiff> {
+    let current = precompile_to_buffer(database, file_path, locale, options)?;
+    diff_bundles(database, previous, &current)
+}
+
+/// Everything a bundler needs to know about one definitions file to build it, gathered by
+/// [get_build_manifest].
+pub struct BuildManifestEntry {
+    pub file_path: KeySymbol,
+    pub source_locale: KeySymbol,
+    /// Map of hashed message keys to the original key, like [get_source_file_key_map].
+    pub message_keys: FxHashMap<String, KeySymbol>,
+    /// The translations file providing each locale, from the file's translations directory. Empty
+    /// when the file sets `translate: false`.
+    pub translations: BTreeMap<KeySymbol, KeySymbol>,
+    /// The path of the compiled artifact for each locale, including the source locale.
+    pub artifacts: BTreeMap<KeySymbol, String>,
+    /// Hash of the content of every message in the file for each locale in `artifacts`, covering
+    /// the source values and meta along with the locale's translations, which only changes when
+    /// the compiled artifact for that locale would.
+    pub content_hashes: BTreeMap<KeySymbol, String>,
+}
+
+/// Returns the path of the compiled artifact for the messages file at `file_path`, named the same
+/// way the bundler plugins name them: the file's path with its `.messages` extension and anything
+/// after it replaced by `.compiled.messages.{artifact_extension}`, like
+/// `src/Feature.compiled.messages.json` for `src/Feature.messages.js`. Files with other extensions
+/// have everything after the first `.` of their name replaced instead.
+fn compiled_artifact_path(file_path: &str, artifact_extension: &str) -> String {
+    let name_start = file_path.rfind(['/', '\\']).map_or(0, |index| index + 1);
+    let name = &file_path[name_start..];
+    let stem = name
+        .find(".messages")
+        .or_else(|| name.find('.'))
+        .map_or(name, |index| &name[..index]);
+    format!(
+        "{}{stem}.compiled.messages.{artifact_extension}",
+        &file_path[..name_start]
+    )
+}
+
+/// Return a [BuildManifestEntry] for every definitions file in the database, sorted by path, so
+/// that bundler plugins can learn everything they need for a build from a single call.
+///
+/// Artifacts are named like [compiled_artifact_path] names them, from the definitions file for the
+/// source locale and from the translations file for every other locale. `artifact_extension`
+/// defaults to `json`.
+pub fn get_build_manifest(
+    database: &MessagesDatabase,
+    artifact_extension: Option<&str>,
+) -> Vec<BuildManifestEntry> {
+    let artifact_extension = artifact_extension.unwrap_or("json");
+
+    let mut translations_by_directory: FxHashMap<PathBuf, Vec<&TranslationFile>> =
+        FxHashMap::default();
+    for source in database.sources.values() {
+        if let SourceFile::Translation(translation) = source {
+            let Some(directory) = Path::new(translation.file()).parent() else {
+                continue;
+            };
+            translations_by_directory
+                .entry(normalize_path(directory))
+                .or_default()
+                .push(translation);
+        }
+    }
+
+    let mut entries: Vec<BuildManifestEntry> = database
+        .sources
+        .iter()
+        .filter_map(|(file_path, source)| match source {
+            SourceFile::Definition(definition) => Some((*file_path, definition)),
+            _ => None,
+        })
+        .map(|(file_path, definition)| {
+            let meta = definition.meta();
+            let source_locale = meta.source_locale.as_deref().map_or_else(
+                || database.get_source_locale_for_file(file_path.as_str()),
+                key_symbol,
+            );
+
+            let mut message_keys = FxHashMap::default();
+            let mut messages = Vec::with_capacity(definition.message_keys().len());
+            for key in definition.message_keys() {
+                let Some(message) = database.messages.get(key) else {
+                    continue;
+                };
+                message_keys.insert(message.hashed_key().clone(), message.key());
+                messages.push(message);
+            }
+            messages.sort_unstable_by(|a, b| a.key().as_str().cmp(b.key().as_str()));
+
+            let mut translations = BTreeMap::new();
+            if meta.translate {
+                let directory = Path::new(file_path.as_str())
+                    .parent()
+                    .map(|parent| normalize_path(&parent.join(&meta.translations_path)));
+                let files = directory
+                    .and_then(|directory| translations_by_directory.get(&directory))
+                    .map_or(&[][..], Vec::as_slice);
+                for translation in files {
+                    translations.insert(*translation.locale(), key_symbol(translation.file()));
+                }
+            }
+
+            let artifacts = std::iter::once((source_locale, file_path))
+                .chain(translations.iter().map(|(locale, file)| (*locale, *file)))
+                .map(|(locale, file)| (locale, compiled_artifact_path(&file, artifact_extension)))
+                .collect();
+            let content_hashes = std::iter::once(source_locale)
+                .chain(translations.keys().copied())
+                .map(|locale| {
+                    let mut hasher = xxhash_rust::xxh64::Xxh64::new(0);
+                    for message in &messages {
+                        hasher.update(message.key().as_bytes());
+                        hasher.update(&[0]);
+                        hasher.update(message.content_hash().unwrap_or_default().as_bytes());
+                        hasher.update(&[0]);
+                        if locale != source_locale {
+                            // Missing translations are hashed differently from empty ones.
+                            match message.translations().get(&locale) {
+                                Some(value) => {
+                                    hasher.update(&[1]);
+                                    hasher.update(value.raw.as_bytes());
+                                }
+                                None => hasher.update(&[2]),
+                            }
+                            hasher.update(&[0]);
+                        }
+                    }
+                    (locale, format!("{:016x}", hasher.digest()))
+                })
+                .collect();
+
+            BuildManifestEntry {
+                file_path,
+                source_locale,
+                message_keys,
+                translations,
+                artifacts,
+                content_hashes,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.file_path.as_str().cmp(b.file_path.as_str()));
+    entries
+}
+
+/// Resolve `.` and `..` in `path` without touching the file system, so that paths written
+/// differently can be compared.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !result.pop() {
+                    result.push(component);
+                }
+            }
+            component => result.push(component),
+        }
+    }
+    result
+}
+
 pub fn validate_messages(database: &MessagesDatabase) -> anyhow::Result<Vec<MessageDiagnostic>> {
+    validate_messages_with_config(database, &ValueValidatorConfig::default())
+}
+
+/// Returns `config` with the hook and link handler names configured for the database, which apply
+/// to every validation of it. See [set_handler_names].
+fn with_database_handler_names(
+    database: &MessagesDatabase,
+    config: &ValueValidatorConfig,
+) -> ValueValidatorConfig {
+    config.clone().with_hook_names(
+        NoUnknownHookNamesOptions::default()
+            .with_hook_names(database.hook_names.clone())
+            .with_link_handler_names(database.link_handler_names.clone()),
+    )
+}
+
+/// Validate all messages in the database, using the given project `config` for validators that
+/// support customizing their behavior.
+pub fn validate_messages_with_config(
+    database: &MessagesDatabase,
+    config: &ValueValidatorConfig,
+) -> anyhow::Result<Vec<MessageDiagnostic>> {
+    validate_messages_with_context(database, config, None, &ServiceContext::default())
+}
+
+/// Validate all messages like [validate_messages_with_config], reporting progress to `context`.
+///
+/// Progress is reported after each message, and validation stops early once the context is
+/// cancelled. When `project` is given, only the messages defined in that project are validated.
+///
+/// Diagnostics are cached in the database for each translation, so translations that haven't
+/// changed since the last validation with the same `config` aren't validated again.
+///
+/// While performance recording is enabled, the time that each rule took is recorded for
+/// [get_performance_report], replacing the rule timings from any previous validation. Cached
+/// translations aren't validated, so only the rules that ran on changed translations are timed.
+pub fn validate_messages_with_context(
+    database: &MessagesDatabase,
+    config: &ValueValidatorConfig,
+    project: Option<&str>,
+    context: &ServiceContext,
+) -> anyhow::Result<Vec<MessageDiagnostic>> {
+    let project = project
+        .map(|project| database.get_project(key_symbol(project)))
+        .transpose()?;
+    let messages: Vec<&Message> = database
+        .messages
+        .values()
+        .filter(|message| {
+            project.is_none_or(|project| database.is_message_in_project(message, project))
+        })
+        .collect();
+    let config = &with_database_handler_names(database, config);
+    let total = messages.len();
     let mut results = vec![];
-    for message in database.messages.values() {
-        let diagnostics = validate_message(&message);
-        if diagnostics.is_empty() {
-            continue;
+    let mut timings = match database.performance {
+        Some(_) => RuleTimings::recording(),
+        None => RuleTimings::disabled(),
+    };
+    ValidationCache::with_database(database, config, |cache| {
+        for (index, message) in messages.into_iter().enumerate() {
+            context.check_cancelled()?;
+            context.report_progress(index, total);
+            let mut diagnostics =
+                validate_message_with_cache(message, config, &mut timings, Some(cache));
+            // References depend on other messages, so they can't be cached with this one.
+            diagnostics.extend(validate_message_references_with_timings(
+                database,
+                message,
+                &mut timings,
+            ));
+            if diagnostics.is_empty() {
+                continue;
+            }
+
+            results.extend(diagnostics);
         }
+        anyhow::Ok(())
+    })?;
 
-        results.extend(diagnostics);
+    if let Some(recorder) = &database.performance {
+        recorder.record_rules(timings.into_timings());
     }
-
+    context.report_progress(total, total);
     Ok(results)
 }
 
+/// Check the spelling of every translation in the database with `checker`.
+///
+/// A `NoMisspelledWords` diagnostic is returned for each misspelled word. When `project` is given,
+/// only the messages defined in that project are checked.
+pub fn validate_spelling(
+    database: &MessagesDatabase,
+    checker: &dyn SpellChecker,
+    project: Option<&str>,
+) -> anyhow::Result<Vec<MessageDiagnostic>> {
+    let project = project
+        .map(|project| database.get_project(key_symbol(project)))
+        .transpose()?;
+    Ok(database
+        .messages
+        .values()
+        .filter(|message| {
+            project.is_none_or(|project| database.is_message_in_project(message, project))
+        })
+        .flat_map(|message| validate_message_spelling(message, checker))
+        .collect())
+}
+
+/// Report the words in `misspellings` that an external spell checker found in `locale`.
+///
+/// Each word is reported like [validate_spelling], along with its suggested replacements. Usually
+/// the words to check come from [get_message_words].
+pub fn validate_misspelled_words(
+    database: &MessagesDatabase,
+    locale: &str,
+    misspellings: impl IntoIterator<Item = (String, Vec<String>)>,
+    project: Option<&str>,
+) -> anyhow::Result<Vec<MessageDiagnostic>> {
+    let locale = get_locale_symbol_or_error(locale)?;
+    validate_spelling(
+        database,
+        &MisspelledWords::new(locale, misspellings),
+        project,
+    )
+}
+
+/// Remove every cached diagnostic from the database.
+///
+/// The next validation checks every message again. The cache is already cleared whenever the
+/// validator configuration changes, so this is only needed when something else that validators
+/// depend on changes.
+pub fn clear_validation_cache(database: &MessagesDatabase) {
+    ValidationCache::clear_database(database);
+}
+
+/// Record every current diagnostic from validating the database in a baseline file.
+///
+/// The baseline is written to `baseline_path`, replacing any baseline that was there before, so
+/// that validating against it afterward only reports new diagnostics. When `project` is given, only
+/// the messages defined in that project are recorded. Returns the number of entries in the
+/// baseline.
+pub fn write_validation_baseline(
+    database: &MessagesDatabase,
+    config: &ValueValidatorConfig,
+    project: Option<&str>,
+    baseline_path: &str,
+) -> anyhow::Result<usize> {
+    let diagnostics =
+        validate_messages_with_context(database, config, project, &ServiceContext::default())?;
+    let baseline = DiagnosticBaseline::from_diagnostics(database, &diagnostics);
+    let content = serde_json::to_string_pretty(&baseline)? + "\n";
+    std::fs::write(baseline_path, content)?;
+    Ok(baseline.entries.len())
+}
+
+/// Return only the diagnostics from `diagnostics` that aren't recorded in the baseline file at
+/// `baseline_path`, as written by [write_validation_baseline].
+pub fn filter_baselined_diagnostics(
+    database: &MessagesDatabase,
+    diagnostics: Vec<MessageDiagnostic>,
+    baseline_path: &str,
+) -> anyhow::Result<Vec<MessageDiagnostic>> {
+    let content = std::fs::read_to_string(baseline_path)
+        .map_err(|error| DatabaseError::ReadError(baseline_path.to_string(), error))?;
+    let baseline: DiagnosticBaseline = serde_json::from_str(&content)?;
+    Ok(baseline.filter_new(database, diagnostics))
+}
+
+/// Validate all messages and summarize the results for CI.
+///
+/// The summary counts diagnostics by severity and by rule, and fails if any diagnostic is at least
+/// as severe as `fail_on`. When `baseline_path` is given, diagnostics recorded in that baseline
+/// aren't counted.
+pub fn summarize_validation(
+    database: &MessagesDatabase,
+    config: &ValueValidatorConfig,
+    project: Option<&str>,
+    baseline_path: Option<&str>,
+    fail_on: DiagnosticSeverity,
+) -> anyhow::Result<ValidationSummary> {
+    let mut diagnostics =
+        validate_messages_with_context(database, config, project, &ServiceContext::default())?;
+    if let Some(baseline_path) = baseline_path {
+        diagnostics = filter_baselined_diagnostics(database, diagnostics, baseline_path)?;
+    }
+    Ok(ValidationSummary::new(&diagnostics, fail_on))
+}
+
+/// Format `diagnostics` as a SARIF 2.1 log.
+///
+/// Code scanning tools in CI use the log to show diagnostics inline in pull requests. Files inside
+/// of `root` are written relative to it, which is usually the root of the repository.
+pub fn format_diagnostics_as_sarif(
+    diagnostics: &[MessageDiagnostic],
+    root: Option<&str>,
+) -> anyhow::Result<String> {
+    let log = to_sarif_log(diagnostics, root.map(Path::new));
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
 pub fn export_translations(
     database: &MessagesDatabase,
     file_extension: Option<String>,
-) -> anyhow::Result<Vec<String>> {
-    let files = ExportTranslations::new(&database, file_extension).run()?;
-    Ok(files)
+    secret_redaction: SecretRedaction,
+    review_states: Option<Vec<ReviewState>>,
+    dry_run: bool,
+    expand_combinations_above: Option<usize>,
+    project: Option<&str>,
+    placeholder_renames: Option<&MessagePlaceholderRenames>,
+    output_directory: Option<&str>,
+) -> anyhow::Result<ExportedTranslationFiles> {
+    let mut exporter = ExportTranslations::new(database, file_extension)
+        .with_secret_redaction(secret_redaction)
+        .with_dry_run(dry_run);
+    if let Some(project) = project {
+        exporter = exporter.with_project(database.get_project(key_symbol(project))?);
+    }
+    if let Some(review_states) = review_states {
+        exporter = exporter.with_review_states(review_states);
+    }
+    if let Some(threshold) = expand_combinations_above {
+        exporter = exporter.with_expanded_combinations(threshold);
+    }
+    if let Some(placeholder_renames) = placeholder_renames {
+        exporter = exporter.with_placeholder_renames(placeholder_renames);
+    }
+    if let Some(output_directory) = output_directory {
+        exporter = exporter.with_output_directory(output_directory, std::env::current_dir()?);
+    }
+    exporter.run()
+}
+
+pub fn set_translation_review_state(
+    database: &mut MessagesDatabase,
+    key: &str,
+    locale: &str,
+    review_state: ReviewState,
+) -> anyhow::Result<()> {
+    let key = get_key_symbol(key).ok_or_else(|| DatabaseError::UnknownMessage(key.to_string()))?;
+    let locale = get_locale_symbol_or_error(locale)?;
+    database.set_translation_review_state(key, locale, review_state)?;
+    Ok(())
+}
+
+/// Remove stray zero-width characters from translations and normalize them to NFC.
+///
+/// Only translations in `locale` are fixed, or every locale if it isn't given. This fixes what the
+/// `NoProblematicUnicode` rule reports without changing how any translation is displayed. Returns
+/// the key and locale of every fixed translation, sorted by key. The fixed values are only held in
+/// the database until they are exported.
+pub fn fix_translation_unicode(
+    database: &mut MessagesDatabase,
+    locale: Option<&str>,
+) -> anyhow::Result<Vec<(KeySymbol, KeySymbol)>> {
+    let locale = locale.map(get_locale_symbol_or_error).transpose()?;
+    let mut fixed = database.fix_translation_unicode(locale);
+    fixed.sort_by(|a, b| (a.0.as_str(), a.1.as_str()).cmp(&(b.0.as_str(), b.1.as_str())));
+    Ok(fixed)
+}
+
+/// Apply `edits` to the source value of the message `key` by rewriting its definitions file.
+///
+/// The file is processed again afterward so the database matches it. Edits are byte ranges of the
+/// raw content of the message, and the inserted text is escaped as needed for the string it's
+/// written into. Nothing else in the file changes.
+///
+/// The file is only written if the new content processes into the expected message. If anything
+/// fails, the file and the database are both left as they were. Returns the diagnostics for the
+/// rewritten message, validated with `config`.
+pub fn rewrite_message(
+    database: &mut MessagesDatabase,
+    key: &str,
+    edits: Vec<MessageEdit>,
+    config: &ValueValidatorConfig,
+) -> anyhow::Result<Vec<MessageDiagnostic>> {
+    let message = get_message(database, key)?;
+    let key = message.key();
+    let (Some(locale), Some(value)) = (*message.source_locale(), message.get_source_translation())
+    else {
+        return Err(DatabaseError::UnknownMessage(key.to_string()).into());
+    };
+    let file = value
+        .file_position
+        .ok_or(RewriteError::UnknownPosition(key))?
+        .file;
+    let content = std::fs::read_to_string(file.as_str())
+        .map_err(|error| DatabaseError::ReadError(file.to_string(), error))?;
+    let matches = edits.len();
+    let rewrite = rewrite_value(&content, key, value, edits)?;
+    let replacement = FileReplacement {
+        file,
+        locale,
+        is_definitions: true,
+        content_hash: hash_message_key(&content),
+        new_content: apply_file_edits(&content, &rewrite.file_edits),
+        diff: String::new(),
+        messages: vec![ReplacedMessage {
+            key,
+            locale,
+            before: value.raw.to_string(),
+            after: rewrite.raw,
+            matches,
+        }],
+    };
+    commit_file_replacements(database, &[replacement], &[content])?;
+
+    let message = &database.messages[&key];
+    let config = with_database_handler_names(database, config);
+    let mut diagnostics = validate_message_with_config(message, &config);
+    diagnostics.extend(validate_message_references(database, message));
+    Ok(diagnostics)
+}
+
+/// Plan replacing every match of the regular expression `pattern` in the values of messages.
+///
+/// Matches are replaced with `replacement`, limited to the given `locales` and key globs in `keys`
+/// when they aren't empty. Nothing is changed until the preview is given to
+/// [apply_message_replacements].
+pub fn preview_message_replacements(
+    database: &MessagesDatabase,
+    pattern: &str,
+    replacement: &str,
+    locales: Vec<String>,
+    keys: Vec<String>,
+    context: &ServiceContext,
+) -> anyhow::Result<ReplacementPreview> {
+    let pattern = Regex::new(pattern).map_err(RewriteError::from)?;
+    ReplaceInMessages::new(database, pattern, replacement.to_string())
+        .with_locales(locales)
+        .with_keys(keys)
+        .run_with_context(context)
+}
+
+/// Write every file from [preview_message_replacements] and process them into the database.
+///
+/// Every file must still have the content it had when the preview was made. If anything fails,
+/// every file and the database are left as they were.
+pub fn apply_message_replacements(
+    database: &mut MessagesDatabase,
+    files: &[FileReplacement],
+) -> anyhow::Result<()> {
+    let mut originals = Vec::with_capacity(files.len());
+    for file in files {
+        let content = std::fs::read_to_string(file.file.as_str())
+            .map_err(|error| DatabaseError::ReadError(file.file.to_string(), error))?;
+        if hash_message_key(&content) != file.content_hash {
+            return Err(RewriteError::ChangedFile(file.file).into());
+        }
+        originals.push(content);
+    }
+    commit_file_replacements(database, files, &originals)
+}
+
+/// Process `content` into the database as the content of the file that `replacement` changes.
+fn process_replaced_file_content(
+    database: &mut MessagesDatabase,
+    replacement: &FileReplacement,
+    content: &str,
+) -> anyhow::Result<()> {
+    let file_path = replacement.file.as_str();
+    if replacement.is_definitions {
+        process_definitions_file_content(database, file_path, content, Some(&replacement.locale))?;
+        return Ok(());
+    }
+    // Translations are processed with the locale tag exactly as the file was given it before.
+    let locale_tag = match database.get_source_file(replacement.file) {
+        Some(SourceFile::Translation(file)) => file.locale_tag().clone(),
+        _ => replacement.locale.to_string(),
+    };
+    process_translation_file_content(database, file_path, &locale_tag, content)?;
+    Ok(())
+}
+
+/// Process the new content of every file in `replacements` into the database, making sure each
+/// message gets the value that was planned, then write them all. Everything happens in one
+/// transaction covering those files, so the database is left as it was if anything fails.
+/// `originals` is the current content of each file, which is written back to the files that were
+/// already written if writing another one fails.
+fn commit_file_replacements(
+    database: &mut MessagesDatabase,
+    replacements: &[FileReplacement],
+    originals: &[String],
+) -> anyhow::Result<()> {
+    commit_file_replacements_with(database, replacements, originals, |path, content| {
+        std::fs::write(path, content)
+    })
+}
+
+/// [commit_file_replacements], writing each file with `write`.
+fn commit_file_replacements_with(
+    database: &mut MessagesDatabase,
+    replacements: &[FileReplacement],
+    originals: &[String],
+    mut write: impl FnMut(&str, &str) -> std::io::Result<()>,
+) -> anyhow::Result<()> {
+    let files: Vec<KeySymbol> = replacements
+        .iter()
+        .map(|replacement| replacement.file)
+        .collect();
+    database.in_file_transaction(&files, |database| {
+        for replacement in replacements {
+            process_replaced_file_content(database, replacement, &replacement.new_content)?;
+            let unexpected = replacement.messages.iter().find(|message| {
+                database
+                    .messages
+                    .get(&message.key)
+                    .and_then(|stored| stored.translations().get(&message.locale))
+                    .is_none_or(|value| *value.raw != *message.after)
+            });
+            if let Some(message) = unexpected {
+                return Err(RewriteError::UnexpectedValue(message.key).into());
+            }
+        }
+        for (index, replacement) in replacements.iter().enumerate() {
+            let file_path = replacement.file.as_str();
+            if let Err(error) = write(file_path, &replacement.new_content) {
+                // Every file is restored even if some of them fail, and the error that caused the
+                // rollback is kept either way.
+                let unrestored: Vec<KeySymbol> = replacements
+                    .iter()
+                    .zip(originals)
+                    .take(index)
+                    .filter(|(written, original)| write(written.file.as_str(), original).is_err())
+                    .map(|(written, _)| written.file)
+                    .collect();
+                if unrestored.is_empty() {
+                    return Err(error.into());
+                }
+                return Err(RewriteError::RestoreFailed { error, unrestored }.into());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Fill in every missing translation in the given `locales` using `provider`.
+///
+/// Each one is marked as machine translated so it can be reviewed later. Returns the number of
+/// translations that were filled in. The new values are only held in the database until they are
+/// exported.
+pub fn prefill_machine_translations(
+    database: &mut MessagesDatabase,
+    provider: &dyn MachineTranslationProvider,
+    locales: &[&str],
+) -> anyhow::Result<usize> {
+    let locales = locales
+        .iter()
+        .map(|locale| normalize_locale_key(key_symbol(locale)))
+        .collect();
+    PrefillMachineTranslations::new(database, provider, locales).run()
 }
 
 pub fn get_source_file_message_values<'a>(
     database: &'a MessagesDatabase,
     file_path: &str,
 ) -> anyhow::Result<FxHashMap<&'a KeySymbol, Option<&'a MessageValue>>> {
-    let source_key = get_key_symbol_or_error(file_path)?;
+    let source_key = get_source_file_symbol_or_error(file_path)?;
     let key_value_pairs = database.get_source_file_message_values(source_key)?;
     Ok(FxHashMap::from_iter(key_value_pairs))
 }
 
+/// Begin a transaction, so that every change to the database until it's committed can be undone
+/// at once by rolling it back. Transactions can be nested.
+pub fn begin_transaction(database: &mut MessagesDatabase) {
+    database.begin_transaction();
+}
+
+/// Keep every change made since the innermost transaction began and end it.
+pub fn commit_transaction(database: &mut MessagesDatabase) -> anyhow::Result<()> {
+    database.commit_transaction()?;
+    Ok(())
+}
+
+/// Undo every change made since the innermost transaction began and end it. Files that were
+/// written by operations during the transaction aren't changed back.
+pub fn rollback_transaction(database: &mut MessagesDatabase) -> anyhow::Result<()> {
+    database.rollback_transaction()?;
+    Ok(())
+}
+
+/// How many transactions are in progress, counting each nested one.
+pub fn get_transaction_depth(database: &MessagesDatabase) -> usize {
+    database.transaction_depth()
+}
+
+/// Enable or disable recording how long messages take to parse, compile, and validate.
+///
+/// Validation is timed for each rule. While enabled, messages are parsed as soon as they're
+/// processed rather than when they're first used.
+pub fn set_performance_recording(database: &mut MessagesDatabase, enabled: bool) {
+    database.set_performance_recording(enabled);
+}
+
+/// Return the `limit` slowest messages to parse or compile since performance recording was enabled.
+///
+/// The report also includes the total time spent on each source file and on each rule during the
+/// most recent validation. Returns an empty report if recording is not enabled.
+pub fn get_performance_report(database: &MessagesDatabase, limit: usize) -> PerformanceReport {
+    database
+        .performance
+        .as_ref()
+        .map(|recorder| recorder.report(limit))
+        .unwrap_or_default()
+}
+
 #[inline(always)]
 pub fn hash_message_key(key: &str) -> String {
     intl_message_utils::hash_message_key(key)
 }
 
+/// Returns true if `key` is a definitions file, including files that end with any of
+/// `definitions_extensions`, like the ones configured for a database.
 #[inline(always)]
-pub fn is_message_definitions_file(key: &str) -> bool {
-    intl_message_utils::is_message_definitions_file(key)
+pub fn is_message_definitions_file(key: &str, definitions_extensions: &[String]) -> bool {
+    is_message_definitions_file_with_extensions(key, definitions_extensions)
 }
 
 #[inline(always)]
 pub fn is_message_translations_file(key: &str) -> bool {
     intl_message_utils::is_message_translations_file(key)
 }
+
+#[inline(always)]
+pub fn normalize_locale_tag(tag: &str) -> Option<String> {
+    intl_message_utils::normalize_locale_tag(tag)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DEFINITIONS_CONTENT: &str = "import {defineMessages} from '@discord/intl';\n\
+        export default defineMessages({\n  GREETING: 'Hello, Discord',\n});\n";
+    const TRANSLATIONS_CONTENT: &str = "{\"GREETING\": \"Bonjour, Discord\"}";
+
+    /// Process a definitions file and a French translations file in a directory that's unique to
+    /// the calling test, and plan replacing `Discord` in both of them. Returns the paths of the
+    /// files along with the preview.
+    fn replacement_preview(
+        test_name: &str,
+    ) -> (MessagesDatabase, ReplacementPreview, String, String) {
+        let directory = std::env::temp_dir()
+            .join("intl_message_database")
+            .join(test_name);
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("messages")).unwrap();
+        let definitions = directory.join("test.messages.js");
+        let translations = directory.join("messages/fr.messages.json");
+        std::fs::write(&definitions, DEFINITIONS_CONTENT).unwrap();
+        std::fs::write(&translations, TRANSLATIONS_CONTENT).unwrap();
+        let definitions = definitions.to_str().unwrap().to_string();
+        let translations = translations.to_str().unwrap().to_string();
+
+        let mut database = MessagesDatabase::new();
+        process_definitions_file(&mut database, &definitions, None).unwrap();
+        process_translation_file(&mut database, &translations, "fr").unwrap();
+        let preview = preview_message_replacements(
+            &database,
+            "Discord",
+            "Acme",
+            vec![],
+            vec![],
+            &ServiceContext::default(),
+        )
+        .unwrap();
+        assert_eq!(preview.files.len(), 2);
+        (database, preview, definitions, translations)
+    }
+
+    /// Commit the replacements in `preview`, failing to write any file for which `fails` is true.
+    fn commit_failing(
+        database: &mut MessagesDatabase,
+        preview: &ReplacementPreview,
+        fails: impl Fn(&str, usize) -> bool,
+    ) -> anyhow::Error {
+        let originals: Vec<String> = preview
+            .files
+            .iter()
+            .map(|file| std::fs::read_to_string(file.file.as_str()).unwrap())
+            .collect();
+        let mut writes = 0;
+        commit_file_replacements_with(database, &preview.files, &originals, |path, content| {
+            writes += 1;
+            match fails(path, writes) {
+                true => Err(std::io::Error::other("disk full")),
+                false => std::fs::write(path, content),
+            }
+        })
+        .unwrap_err()
+    }
+
+    fn assert_unchanged(database: &MessagesDatabase) {
+        let message = get_message(database, "GREETING").unwrap();
+        let values = message
+            .translations()
+            .iter()
+            .map(|(locale, value)| (locale.as_str(), value.raw.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            values,
+            BTreeMap::from([
+                ("en-US", "Hello, Discord".to_string()),
+                ("fr", "Bonjour, Discord".to_string())
+            ])
+        );
+        assert_eq!(database.transaction_depth(), 0);
+    }
+
+    #[test]
+    fn test_compiled_artifact_path() {
+        for (file_path, expected) in [
+            (
+                "src/Feature.messages.js",
+                "src/Feature.compiled.messages.json",
+            ),
+            (
+                "src/messages/fr.messages.json",
+                "src/messages/fr.compiled.messages.json",
+            ),
+            (
+                "C:\\src\\en_GB.messages.jsona",
+                "C:\\src\\en_GB.compiled.messages.json",
+            ),
+            ("Feature.strings.js", "Feature.compiled.messages.json"),
+        ] {
+            assert_eq!(compiled_artifact_path(file_path, "json"), expected);
+        }
+    }
+
+    #[test]
+    fn test_build_manifest() {
+        let (mut database, _, definitions, translations) = replacement_preview("build_manifest");
+        let manifest = |database: &MessagesDatabase| {
+            let mut entries = get_build_manifest(database, Some("jsona"));
+            assert_eq!(entries.len(), 1);
+            entries.remove(0)
+        };
+        let entry = manifest(&database);
+        let (en, fr) = (key_symbol("en-US"), key_symbol("fr"));
+        assert_eq!(
+            entry.translations,
+            BTreeMap::from([(fr, key_symbol(&translations))])
+        );
+        assert_eq!(
+            entry.artifacts,
+            BTreeMap::from([
+                (
+                    en,
+                    definitions.replace(".messages.js", ".compiled.messages.jsona")
+                ),
+                (
+                    fr,
+                    translations.replace(".messages.json", ".compiled.messages.jsona")
+                ),
+            ])
+        );
+
+        // Changing a translation only changes the hash of its own locale.
+        process_translation_file_content(
+            &mut database,
+            &translations,
+            "fr",
+            "{\"GREETING\": \"Salut, Discord\"}",
+        )
+        .unwrap();
+        let changed = manifest(&database);
+        assert_eq!(changed.content_hashes[&en], entry.content_hashes[&en]);
+        assert_ne!(changed.content_hashes[&fr], entry.content_hashes[&fr]);
+
+        // Changing a definition changes the hash of every locale.
+        process_definitions_file_content(
+            &mut database,
+            &definitions,
+            &DEFINITIONS_CONTENT.replace("Hello", "Hi"),
+            None,
+        )
+        .unwrap();
+        let redefined = manifest(&database);
+        assert_ne!(redefined.content_hashes[&en], changed.content_hashes[&en]);
+        assert_ne!(redefined.content_hashes[&fr], changed.content_hashes[&fr]);
+    }
+
+    #[test]
+    fn test_precompile_with_dependencies() {
+        let (mut database, _, definitions, translations) =
+            replacement_preview("precompile_with_dependencies");
+        let dependencies = |database: &MessagesDatabase, locale: &str| {
+            precompile_with_dependencies(database, &definitions, locale, Default::default())
+                .unwrap()
+                .dependencies
+                .into_iter()
+                .map(|dependency| (dependency.file_path.to_string(), dependency.content_hash))
+                .collect::<Vec<_>>()
+        };
+
+        let source = dependencies(&database, "en-US");
+        assert_eq!(source.len(), 1);
+        assert_eq!(source[0].0, definitions);
+        let french = dependencies(&database, "fr");
+        assert_eq!(
+            french.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+            vec![&translations, &definitions]
+        );
+        assert!(french.iter().all(|(_, hash)| hash.is_some()));
+
+        // Hashes come from what was compiled, so changing the file on disk alone changes nothing.
+        std::fs::write(&translations, "{\"GREETING\": \"Salut, Discord\"}").unwrap();
+        assert_eq!(dependencies(&database, "fr"), french);
+        process_translation_file(&mut database, &translations, "fr").unwrap();
+        let changed = dependencies(&database, "fr");
+        assert_eq!(changed[1], french[1]);
+        assert_ne!(changed[0], french[0]);
+
+        // The translations file for a locale is listed even when it doesn't exist yet, and files
+        // for that locale in other directories aren't.
+        let directory = Path::new(&definitions).parent().unwrap();
+        let other = directory.join("other/es.messages.json");
+        process_translation_file_content(&mut database, other.to_str().unwrap(), "es", "{}")
+            .unwrap();
+        let expected = directory.join("messages/es.messages.json");
+        assert_eq!(
+            dependencies(&database, "es"),
+            vec![
+                (expected.to_str().unwrap().to_string(), None),
+                (definitions.clone(), french[1].1.clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replacements_roll_back_when_a_write_fails() {
+        let (mut database, preview, definitions, translations) =
+            replacement_preview("replacements_roll_back");
+        // Writing the first file succeeds, so it has to be restored when the second one fails.
+        let failing_file = preview.files[1].file.as_str();
+        let error = commit_failing(&mut database, &preview, |path, _| path == failing_file);
+        assert_eq!(error.to_string(), "disk full");
+
+        assert_eq!(
+            std::fs::read_to_string(definitions).unwrap(),
+            DEFINITIONS_CONTENT
+        );
+        assert_eq!(
+            std::fs::read_to_string(translations).unwrap(),
+            TRANSLATIONS_CONTENT
+        );
+        assert_unchanged(&database);
+    }
+
+    #[test]
+    fn test_replacements_keep_the_error_when_restoring_fails() {
+        let (mut database, preview, _, _) = replacement_preview("replacements_restore_fails");
+        // Only the first write succeeds, so the first file can't be restored either.
+        let error = commit_failing(&mut database, &preview, |_, writes| writes > 1);
+        let first_file = preview.files[0].file;
+        match error.downcast_ref::<RewriteError>() {
+            Some(RewriteError::RestoreFailed { error, unrestored }) => {
+                assert_eq!(error.to_string(), "disk full");
+                assert_eq!(unrestored, &vec![first_file]);
+            }
+            _ => panic!("Expected a RestoreFailed error, got {error}"),
+        }
+        assert!(error.to_string().starts_with("disk full. Restoring"));
+        assert_unchanged(&database);
+    }
+
+    #[test]
+    fn test_processing_every_file_parses_values_eagerly() {
+        let directory = std::env::temp_dir()
+            .join("intl_message_database")
+            .join("test_processing_every_file_parses_values_eagerly");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let definitions = directory.join("test.messages.js");
+        let translations = directory.join("fr.messages.json");
+        std::fs::write(&definitions, DEFINITIONS_CONTENT).unwrap();
+        std::fs::write(&translations, TRANSLATIONS_CONTENT).unwrap();
+        let parser_options = ParserOptions::default().with_keyboard_keys(true);
+        let assert_parsed = |database: &MessagesDatabase, locales: &[&str]| {
+            let message = get_message(database, "GREETING").unwrap();
+            for locale in locales {
+                let value = message.translations().get(&key_symbol(locale)).unwrap();
+                assert!(value.is_parsed(), "{locale} was not parsed");
+                assert_eq!(value.parser_options(), &parser_options);
+            }
+        };
+
+        let mut database = MessagesDatabase::new().with_parser_options(parser_options.clone());
+        let files = [(&definitions, "en-US"), (&translations, "fr")].map(|(file_path, locale)| {
+            MessagesFileDescriptor {
+                file_path: file_path.clone(),
+                locale: key_symbol(locale),
+            }
+        });
+        process_all_messages_files(&mut database, files.into_iter()).unwrap();
+        assert_parsed(&database, &["en-US", "fr"]);
+
+        let mut database = MessagesDatabase::new().with_parser_options(parser_options.clone());
+        let locale_map =
+            HashMap::from([("fr".to_string(), translations.to_str().unwrap().to_string())]);
+        process_all_translation_files(&mut database, locale_map).unwrap();
+        assert_parsed(&database, &["fr"]);
+    }
+
+    #[test]
+    fn test_split_types_match_generating_each_file() {
+        let directory = std::env::temp_dir()
+            .join("intl_message_database")
+            .join("test_split_types_match_generating_each_file");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("src/settings")).unwrap();
+        let definitions = [
+            directory.join("src/Chat.messages.js"),
+            directory.join("src/settings/Settings.messages.js"),
+        ];
+        let mut database = MessagesDatabase::new();
+        for (file, key) in definitions.iter().zip(["CHAT_TITLE", "SETTINGS_TITLE"]) {
+            let content = DEFINITIONS_CONTENT.replace("GREETING", key);
+            std::fs::write(file, content).unwrap();
+            process_definitions_file(&mut database, file.to_str().unwrap(), None).unwrap();
+        }
+
+        let types_directory = directory.join("types");
+        let written = generate_split_types(
+            &database,
+            directory.join("src").to_str().unwrap(),
+            types_directory.to_str().unwrap(),
+            TypesSplitMode::Directory,
+            IntlTypesGeneratorOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(written.len(), 4);
+
+        let split_files = [
+            ("Chat.messages.d.ts", "../src/Chat.messages.js"),
+            (
+                "settings/Settings.messages.d.ts",
+                "../../src/settings/Settings.messages.js",
+            ),
+        ];
+        for (file, (split_path, source)) in definitions.iter().zip(split_files) {
+            let full_path = directory.join("full.d.ts");
+            generate_types(
+                &database,
+                file.to_str().unwrap(),
+                full_path.to_str().unwrap(),
+                IntlTypesGeneratorOptions::default(),
+                None,
+            )
+            .unwrap();
+            let split_path = types_directory.join(split_path);
+            assert_eq!(
+                std::fs::read_to_string(&split_path).unwrap(),
+                std::fs::read_to_string(&full_path).unwrap()
+            );
+            // The split source maps refer to the definitions by their relative paths instead.
+            let source_map = std::fs::read_to_string(split_path.with_extension("ts.map")).unwrap();
+            assert!(
+                source_map.contains(&format!("\"sources\":[\"{source}\"]")),
+                "{source_map}"
+            );
+        }
+        assert!(types_directory.join("index.d.ts").exists());
+        assert!(types_directory.join("settings/index.d.ts").exists());
+    }
+}
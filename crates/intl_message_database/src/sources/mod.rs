@@ -1,16 +1,27 @@
+pub mod replace;
+pub mod rewrite;
+
+use ignore::overrides::OverrideBuilder;
 use ignore::WalkBuilder;
 use intl_database_core::{
     key_symbol, DatabaseError, DatabaseResult, DefinitionFile, FilePosition, KeySymbol,
-    KeySymbolSet, MessageDefinitionSource, MessageTranslationSource, MessagesDatabase, RawMessage,
-    RawMessageDefinition, RawMessageTranslation, SourceFile, SourceFileMeta, TranslationFile,
+    KeySymbolSet, MessageDefinitionSource, MessageSourceResult, MessageTranslationSource,
+    MessagesDatabase, RawMessage, RawMessageDefinition, RawMessageTranslation, SourceFile,
+    SourceFileInsertionData, SourceFileMeta, TranslationFile,
 };
 use intl_database_js_source::JsMessageSource;
-use intl_database_json_source::JsonMessageSource;
-use intl_message_utils::{is_any_messages_file, is_message_translations_file};
+use intl_database_json_source::{
+    CompiledBundleSource, FormatJsMessageSource, JsonMessageSource,
+};
+use intl_message_utils::{
+    is_any_messages_file, is_formatjs_definitions_file, is_message_definitions_file_with_extensions,
+    is_message_translations_file, normalize_locale_tag,
+};
 use rustc_hash::FxHashSet;
 use serde::Serialize;
 use std::iter::FusedIterator;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 struct SourceFileKeyTrackingIterator<T: RawMessage, I: Iterator<Item = T>> {
     iterator: I,
@@ -53,9 +64,56 @@ impl<T: RawMessage, I: Iterator<Item = T>> FusedIterator
 {
 }
 
-fn get_definition_source_from_file_name(file_name: &str) -> Option<impl MessageDefinitionSource> {
-    if file_name.ends_with(".js") {
-        Some(JsMessageSource)
+/// All of the sources that are able to provide message definitions, dispatched by file name.
+enum DefinitionSource {
+    Js(JsMessageSource),
+    FormatJs(FormatJsMessageSource),
+}
+
+impl MessageDefinitionSource for DefinitionSource {
+    fn extract_definitions(
+        self,
+        file_name: KeySymbol,
+        content: &str,
+    ) -> MessageSourceResult<
+        SourceFileInsertionData<impl Iterator<Item = RawMessageDefinition> + '_>,
+    > {
+        let data: SourceFileInsertionData<Box<dyn Iterator<Item = RawMessageDefinition> + '_>> =
+            match self {
+                DefinitionSource::Js(source) => {
+                    let data = source.extract_definitions(file_name, content)?;
+                    SourceFileInsertionData {
+                        meta: data.meta,
+                        definitions: Box::new(data.definitions),
+                        errors: data.errors,
+                    }
+                }
+                DefinitionSource::FormatJs(source) => {
+                    let data = source.extract_definitions(file_name, content)?;
+                    SourceFileInsertionData {
+                        meta: data.meta,
+                        definitions: Box::new(data.definitions),
+                        errors: data.errors,
+                    }
+                }
+            };
+        Ok(data)
+    }
+}
+
+/// Returns the source for reading definitions from `file_name`. Files ending with any of the
+/// `definitions_extensions` configured for the database are read as JS, like plain `.js` files.
+fn get_definition_source_from_file_name(
+    file_name: &str,
+    definitions_extensions: &[String],
+) -> Option<DefinitionSource> {
+    let has_definitions_extension = definitions_extensions
+        .iter()
+        .any(|extension| file_name.ends_with(extension.as_str()));
+    if is_formatjs_definitions_file(file_name) {
+        Some(DefinitionSource::FormatJs(FormatJsMessageSource))
+    } else if file_name.ends_with(".js") || has_definitions_extension {
+        Some(DefinitionSource::Js(JsMessageSource))
     } else {
         None
     }
@@ -79,28 +137,90 @@ pub(crate) fn get_locale_from_file_name(
                 source.get_locale_from_file_name(file_name)
             })
     } else {
-        get_definition_source_from_file_name(file_name)
-            .map_or(default_definition_locale, |source| {
-                source.get_default_locale(file_name)
-            })
+        // Definitions files can set their own locale through `sourceLocale` in their meta, but
+        // that is only known once the file is processed, where it overrides this default.
+        default_definition_locale
     }
 }
 
+/// Returns the canonical form of `locale`, so that files named with different spellings of the
+/// same locale, like `en_GB` and `en-gb`, are all stored as `en-GB`. Tags that aren't well-formed
+/// are kept as they are, and are reported by [crate::public::validate_locale_tags] instead.
+pub(crate) fn normalize_locale_key(locale: KeySymbol) -> KeySymbol {
+    normalize_locale_tag(&locale).map_or(locale, |normalized| key_symbol(&normalized))
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct MessagesFileDescriptor {
     pub file_path: PathBuf,
     pub locale: KeySymbol,
 }
 
+/// Options for how [find_all_messages_files] walks the file system.
+#[derive(Clone, Debug)]
+pub struct MessagesFileDiscoveryOptions {
+    /// Globs for files and directories to skip entirely, using the same syntax as `.gitignore`.
+    /// Globs without a `/`, like `dist`, match an entry with that name at any depth.
+    pub exclude: Vec<String>,
+    /// Skip files that are ignored by `.gitignore`, `.git/info/exclude`, and the global git ignore
+    /// file. Defaults to true.
+    pub respect_gitignore: bool,
+    /// Follow symbolic links into the files and directories they point to. Defaults to false.
+    pub follow_symlinks: bool,
+    /// How many directories deep to descend into each of the scanned directories, where 0 only
+    /// looks at the directories themselves. No limit is applied by default.
+    pub max_depth: Option<usize>,
+}
+
+impl Default for MessagesFileDiscoveryOptions {
+    fn default() -> Self {
+        Self {
+            exclude: vec![],
+            respect_gitignore: true,
+            follow_symlinks: false,
+            max_depth: None,
+        }
+    }
+}
+
+impl MessagesFileDiscoveryOptions {
+    pub fn with_exclude(mut self, exclude: Vec<String>) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    pub fn with_respect_gitignore(mut self, respect_gitignore: bool) -> Self {
+        self.respect_gitignore = respect_gitignore;
+        self
+    }
+
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
 /// Discover all files that are presumed to contain message definitions or translations by scanning
 /// the file system through the given `directories`. Each returned entry will have both the path
 /// for the file and the locale that it should represent. For definitions files,
 /// `default_definition_locale` will be used unless the source is able to provide more information
 /// about what locale it represents.
+///
+/// Files ending with any of the `definitions_extensions` are found along with the built-in kinds
+/// of messages files.
+///
+/// Returns an error if any of the exclude globs in `options` are invalid.
 pub fn find_all_messages_files<A: AsRef<str>>(
     mut directories: impl Iterator<Item = A>,
     default_definition_locale: &str,
-) -> impl Iterator<Item = MessagesFileDescriptor> {
+    definitions_extensions: &[String],
+    options: &MessagesFileDiscoveryOptions,
+) -> Result<impl Iterator<Item = MessagesFileDescriptor>, ignore::Error> {
     let first_directory = directories
         .next()
         .expect("find_all_messages_files requires at least one directory to scan");
@@ -109,9 +229,26 @@ pub fn find_all_messages_files<A: AsRef<str>>(
     for directory in directories {
         builder.add(directory.as_ref());
     }
+
+    // Overrides treat plain globs as the only files to include, so excludes are written as
+    // negated globs instead. The root is left empty so that the globs apply the same way within
+    // every scanned directory.
+    let mut overrides = OverrideBuilder::new("");
+    for glob in &options.exclude {
+        overrides.add(&format!("!{glob}"))?;
+    }
+    builder
+        .overrides(overrides.build()?)
+        .git_ignore(options.respect_gitignore)
+        .git_global(options.respect_gitignore)
+        .git_exclude(options.respect_gitignore)
+        .follow_links(options.follow_symlinks)
+        .max_depth(options.max_depth);
+
     let walker = builder.build();
+    let definitions_extensions = definitions_extensions.to_vec();
     let mut found_files = FxHashSet::default();
-    walker.into_iter().filter_map(move |item| {
+    Ok(walker.into_iter().filter_map(move |item| {
         let Ok(item) = item else {
             return None;
         };
@@ -125,14 +262,14 @@ pub fn find_all_messages_files<A: AsRef<str>>(
             return None;
         };
         let basename = &basename.to_string_lossy();
-        if item.file_type().is_some_and(|file_type| file_type.is_dir())
-            || !is_any_messages_file(basename)
-        {
+        let is_messages_file = is_any_messages_file(basename)
+            || is_message_definitions_file_with_extensions(basename, &definitions_extensions);
+        if item.file_type().is_some_and(|file_type| file_type.is_dir()) || !is_messages_file {
             return None;
         }
         let locale = get_locale_from_file_name(&basename, default_definition_locale);
         Some(MessagesFileDescriptor { file_path, locale })
-    })
+    }))
 }
 
 pub fn process_definitions_file(
@@ -143,18 +280,16 @@ pub fn process_definitions_file(
 ) -> DatabaseResult<KeySymbol> {
     let file_key = key_symbol(file_name);
     let locale_key = key_symbol(locale);
-    let (file_meta, definitions) = extract_definitions_from_file(file_key, content)?;
-    insert_definitions(db, file_key, locale_key, file_meta, definitions)
+    let data = extract_definitions_from_file(file_key, content, &db.definitions_extensions)?;
+    insert_definitions(db, file_key, locale_key, data)
 }
 
-pub fn extract_definitions_from_file(
+pub fn extract_definitions_from_file<'a>(
     file_key: KeySymbol,
-    content: &str,
-) -> DatabaseResult<(
-    SourceFileMeta,
-    impl Iterator<Item = RawMessageDefinition> + '_,
-)> {
-    let source = get_definition_source_from_file_name(&file_key)
+    content: &'a str,
+    definitions_extensions: &[String],
+) -> DatabaseResult<SourceFileInsertionData<impl Iterator<Item = RawMessageDefinition> + 'a>> {
+    let source = get_definition_source_from_file_name(&file_key, definitions_extensions)
         .ok_or(DatabaseError::NoSourceImplementation(file_key.to_string()))?;
 
     source
@@ -166,14 +301,34 @@ pub fn insert_definitions(
     db: &mut MessagesDatabase,
     file_key: KeySymbol,
     locale_key: KeySymbol,
-    source_file_meta: SourceFileMeta,
-    definitions: impl Iterator<Item = RawMessageDefinition>,
+    data: SourceFileInsertionData<impl Iterator<Item = RawMessageDefinition>>,
 ) -> DatabaseResult<KeySymbol> {
+    let SourceFileInsertionData {
+        mut meta,
+        definitions,
+        errors,
+    } = data;
+    let locale_key = normalize_locale_key(
+        meta.source_locale
+            .as_deref()
+            .map_or(locale_key, key_symbol),
+    );
+    meta.source_locale = meta.source_locale.map(|_| locale_key.to_string());
+    // Files that don't set their own translations path keep their translations with the rest of
+    // their project's.
+    if meta.translations_path == Path::new(SourceFileMeta::DEFAULT_TRANSLATIONS_PATH) {
+        if let Some(directory) = db
+            .get_project_for_file(file_key.as_str())
+            .and_then(|project| project.translations_directory())
+        {
+            meta.translations_path = directory;
+        }
+    }
     let source_file = db.get_or_create_source_file(
         file_key,
         SourceFile::Definition(DefinitionFile::new(
             file_key.to_string(),
-            source_file_meta,
+            meta,
             KeySymbolSet::default(),
         )),
     );
@@ -190,6 +345,7 @@ pub fn insert_definitions(
     }
 
     db.set_source_file_keys(file_key, iterator.inserted_keys)?;
+    db.set_definition_file_errors(file_key, errors)?;
     for key in iterator.removed_keys {
         db.remove_definition(key);
     }
@@ -205,14 +361,14 @@ pub fn process_translations_file(
 ) -> DatabaseResult<KeySymbol> {
     let file_key = key_symbol(file_name);
     let locale_key = key_symbol(&locale);
-    let translations = extract_translations_from_file(file_key, content)?;
+    let translations = extract_translations_from_file(file_key, content.into())?;
     insert_translations(db, file_key, locale_key, translations)
 }
 
 pub fn extract_translations_from_file(
     file_key: KeySymbol,
-    content: &str,
-) -> DatabaseResult<impl Iterator<Item = RawMessageTranslation> + '_> {
+    content: Arc<str>,
+) -> DatabaseResult<impl Iterator<Item = RawMessageTranslation>> {
     let source = get_translation_source_from_file_name(&file_key)
         .ok_or(DatabaseError::NoSourceImplementation(file_key.to_string()))?;
     source
@@ -220,19 +376,44 @@ pub fn extract_translations_from_file(
         .map_err(DatabaseError::SourceError)
 }
 
+/// Recover translations for `locale` from a compiled message bundle.
+///
+/// Bundles only contain hashed message keys, so only messages that are already defined in the
+/// database can be resolved, and entries for any other keys are skipped.
+pub fn process_compiled_bundle_file(
+    db: &mut MessagesDatabase,
+    file_name: &str,
+    locale: &str,
+    content: &str,
+) -> DatabaseResult<KeySymbol> {
+    let file_key = key_symbol(file_name);
+    let locale_key = key_symbol(locale);
+    let translations = CompiledBundleSource
+        .extract_translations(file_key, content.into())
+        .map_err(DatabaseError::SourceError)?;
+    let translations: Vec<RawMessageTranslation> = translations
+        .filter_map(|mut translation| {
+            translation.name = *db.hash_lookup.get(translation.name.as_str())?;
+            Some(translation)
+        })
+        .collect();
+    insert_translations(db, file_key, locale_key, translations.into_iter())
+}
+
 pub fn insert_translations(
     db: &mut MessagesDatabase,
     file_key: KeySymbol,
     locale_key: KeySymbol,
     translations: impl Iterator<Item = RawMessageTranslation>,
 ) -> DatabaseResult<KeySymbol> {
+    let locale_tag = locale_key.to_string();
+    let locale_key = normalize_locale_key(locale_key);
     let source_file = db.get_or_create_source_file(
         file_key,
-        SourceFile::Translation(TranslationFile::new(
-            file_key.to_string(),
-            locale_key,
-            KeySymbolSet::default(),
-        )),
+        SourceFile::Translation(
+            TranslationFile::new(file_key.to_string(), locale_key, KeySymbolSet::default())
+                .with_locale_tag(locale_tag),
+        ),
     );
 
     let mut iterator =
@@ -0,0 +1,449 @@
+//! Searching for a pattern across the values of every message and replacing each match, for
+//! content migrations like renaming a product everywhere it's mentioned.
+//!
+//! Replacements are planned as a preview of every file they would change, which can be reviewed
+//! before it is applied.
+use std::collections::BTreeMap;
+
+use intl_database_core::{KeySymbol, MessageValue, MessagesDatabase, SourceFile};
+use intl_database_service::{IntlDatabaseService, ServiceContext};
+use intl_message_utils::{hash_message_key, matches_glob};
+use regex::Regex;
+
+use crate::sources::rewrite::{
+    apply_file_edits, format_file_diff, rewrite_value, FileEdit, MessageEdit,
+};
+
+/// A message value that a replacement changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplacedMessage {
+    pub key: KeySymbol,
+    pub locale: KeySymbol,
+    pub before: String,
+    pub after: String,
+    /// How many matches of the pattern were replaced in the value.
+    pub matches: usize,
+}
+
+/// The new content of a single file that replacements change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileReplacement {
+    pub file: KeySymbol,
+    /// The locale that the values in the file are written in.
+    pub locale: KeySymbol,
+    pub is_definitions: bool,
+    /// Hash of the content of the file when the replacements were planned, to make sure it
+    /// hasn't changed before they are applied.
+    pub content_hash: String,
+    pub new_content: String,
+    /// A unified diff of the changes to the file.
+    pub diff: String,
+    pub messages: Vec<ReplacedMessage>,
+}
+
+/// A value that the pattern matched but that can't be rewritten in its file, like a definition
+/// built from a concatenation of strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedReplacement {
+    pub key: KeySymbol,
+    pub locale: KeySymbol,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplacementPreview {
+    pub files: Vec<FileReplacement>,
+    pub skipped: Vec<SkippedReplacement>,
+}
+
+/// A service for planning the replacement of every match of a regular expression in the values of
+/// messages, both definitions and translations.
+///
+/// Replacements can use the capture groups of the pattern like `$1` or `$name`, using the syntax
+/// of the `regex` crate.
+///
+/// Nothing is changed by this service. The result is a [ReplacementPreview] with the new content
+/// and a diff of every file that would change, ordered by path, which can then be applied all at
+/// once. Values that can't be rewritten in their files are reported as skipped instead.
+///
+/// [ReplaceInMessages::with_locales] and [ReplaceInMessages::with_keys] limit the replacement to
+/// some of the values, like only the `en` translations of messages matching `SETTINGS_*`.
+pub struct ReplaceInMessages<'a> {
+    database: &'a MessagesDatabase,
+    pattern: Regex,
+    replacement: String,
+    locales: Vec<String>,
+    keys: Vec<String>,
+}
+
+impl<'a> ReplaceInMessages<'a> {
+    pub fn new(database: &'a MessagesDatabase, pattern: Regex, replacement: String) -> Self {
+        Self {
+            database,
+            pattern,
+            replacement,
+            locales: vec![],
+            keys: vec![],
+        }
+    }
+
+    /// Only replace values in these languages, like `en`, or full locales, like `en-GB`.
+    pub fn with_locales(mut self, locales: Vec<String>) -> Self {
+        self.locales = locales;
+        self
+    }
+
+    /// Only replace values of messages with keys matching any of these globs, like `SETTINGS_*`.
+    pub fn with_keys(mut self, keys: Vec<String>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    fn applies_to(&self, key: &str, locale: &str) -> bool {
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        let matches_locale = self.locales.is_empty()
+            || self
+                .locales
+                .iter()
+                .any(|filter| filter == locale || filter == language);
+        let matches_key =
+            self.keys.is_empty() || self.keys.iter().any(|glob| matches_glob(glob, key));
+        matches_locale && matches_key
+    }
+
+    /// Return the edits that replace every match of the pattern in `raw`.
+    fn find_edits(&self, raw: &str) -> Vec<MessageEdit> {
+        self.pattern
+            .captures_iter(raw)
+            .map(|captures| {
+                let found = captures
+                    .get(0)
+                    .expect("Captures always include the whole match");
+                let mut text = String::new();
+                captures.expand(&self.replacement, &mut text);
+                MessageEdit {
+                    start: found.start(),
+                    end: found.end(),
+                    text,
+                }
+            })
+            .filter(|edit| edit.text != raw[edit.start..edit.end])
+            .collect()
+    }
+
+    /// Returns the file that `value` is written in if it's one that can be rewritten, which is the
+    /// definitions file for source values and a translations file in the same locale otherwise.
+    fn value_file(
+        &self,
+        value: &MessageValue,
+        locale: KeySymbol,
+        is_source: bool,
+    ) -> Option<KeySymbol> {
+        let file = value.file_position?.file;
+        match self.database.sources.get(&file)? {
+            SourceFile::Definition(_) if is_source => Some(file),
+            SourceFile::Translation(translation)
+                if !is_source && *translation.locale() == locale =>
+            {
+                Some(file)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A value that is planned to be replaced, before its file has been read.
+struct PendingReplacement<'a> {
+    key: KeySymbol,
+    locale: KeySymbol,
+    value: &'a MessageValue,
+    edits: Vec<MessageEdit>,
+}
+
+impl IntlDatabaseService for ReplaceInMessages<'_> {
+    type Result = anyhow::Result<ReplacementPreview>;
+
+    fn run(&mut self) -> Self::Result {
+        self.run_with_context(&ServiceContext::default())
+    }
+
+    fn run_with_context(&mut self, context: &ServiceContext) -> Self::Result {
+        let mut messages: Vec<_> = self.database.messages.values().collect();
+        messages.sort_by_key(|message| message.key().as_str());
+        let total = messages.len();
+
+        let mut preview = ReplacementPreview::default();
+        // Files are ordered by their paths, so that previews are always in the same order.
+        let mut pending: BTreeMap<&str, (KeySymbol, Vec<PendingReplacement>)> = BTreeMap::new();
+        for (index, message) in messages.into_iter().enumerate() {
+            context.check_cancelled()?;
+            context.report_progress(index, total);
+            let mut translations: Vec<_> = message.translations().iter().collect();
+            translations.sort_by_key(|(locale, _)| locale.as_str());
+            for (locale, value) in translations {
+                if !self.applies_to(&message.key(), locale) {
+                    continue;
+                }
+                let edits = self.find_edits(&value.raw);
+                if edits.is_empty() {
+                    continue;
+                }
+                let is_source = *message.source_locale() == Some(*locale);
+                match self.value_file(value, *locale, is_source) {
+                    Some(file) => pending
+                        .entry(file.as_str())
+                        .or_insert_with(|| (file, vec![]))
+                        .1
+                        .push(PendingReplacement {
+                            key: message.key(),
+                            locale: *locale,
+                            value,
+                            edits,
+                        }),
+                    None => preview.skipped.push(SkippedReplacement {
+                        key: message.key(),
+                        locale: *locale,
+                        reason: format!(
+                            "The {locale} value of {} isn't written in a messages file yet",
+                            message.key()
+                        ),
+                    }),
+                }
+            }
+        }
+
+        for (file_path, (file, replacements)) in pending {
+            context.check_cancelled()?;
+            let content = std::fs::read_to_string(file_path)?;
+            let mut file_edits: Vec<FileEdit> = vec![];
+            let mut replaced = vec![];
+            for replacement in replacements {
+                let matches = replacement.edits.len();
+                match rewrite_value(
+                    &content,
+                    replacement.key,
+                    replacement.value,
+                    replacement.edits,
+                ) {
+                    Ok(rewrite) => {
+                        file_edits.extend(rewrite.file_edits);
+                        replaced.push(ReplacedMessage {
+                            key: replacement.key,
+                            locale: replacement.locale,
+                            before: replacement.value.raw.to_string(),
+                            after: rewrite.raw,
+                            matches,
+                        });
+                    }
+                    Err(error) => preview.skipped.push(SkippedReplacement {
+                        key: replacement.key,
+                        locale: replacement.locale,
+                        reason: error.to_string(),
+                    }),
+                }
+            }
+            let Some(first) = replaced.first() else {
+                continue;
+            };
+            file_edits.sort_by_key(|edit| edit.range.start);
+            preview.files.push(FileReplacement {
+                file,
+                locale: first.locale,
+                is_definitions: matches!(
+                    self.database.sources.get(&file),
+                    Some(SourceFile::Definition(_))
+                ),
+                content_hash: hash_message_key(&content),
+                new_content: apply_file_edits(&content, &file_edits),
+                diff: format_file_diff(file_path, &content, &file_edits),
+                messages: replaced,
+            });
+        }
+
+        context.report_progress(total, total);
+        Ok(preview)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use intl_database_core::key_symbol;
+
+    use super::*;
+    use crate::public;
+    use crate::sources::rewrite::RewriteError;
+
+    /// Write a definitions file with `definitions` and a French translations file with
+    /// `translations` into a directory that's unique to the calling test, and process them both.
+    fn database(
+        test_name: &str,
+        definitions: &str,
+        translations: &str,
+    ) -> (MessagesDatabase, PathBuf, PathBuf) {
+        let directory = std::env::temp_dir()
+            .join("intl_message_database")
+            .join(test_name);
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("messages")).unwrap();
+        let definitions_path = directory.join("test.messages.js");
+        let translations_path = directory.join("messages/fr.messages.json");
+        std::fs::write(
+            &definitions_path,
+            format!(
+                "import {{defineMessages}} from '@discord/intl';\n\
+                 export default defineMessages({{\n{definitions}}});\n"
+            ),
+        )
+        .unwrap();
+        std::fs::write(&translations_path, translations).unwrap();
+
+        let mut database = MessagesDatabase::new();
+        public::process_definitions_file(&mut database, definitions_path.to_str().unwrap(), None)
+            .unwrap();
+        public::process_translation_file(&mut database, translations_path.to_str().unwrap(), "fr")
+            .unwrap();
+        (database, definitions_path, translations_path)
+    }
+
+    fn preview(
+        database: &MessagesDatabase,
+        pattern: &str,
+        replacement: &str,
+    ) -> ReplacementPreview {
+        ReplaceInMessages::new(database, Regex::new(pattern).unwrap(), replacement.into())
+            .run()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_preview() {
+        let (database, definitions, translations) = database(
+            "replace_preview",
+            "  GREETING: 'Welcome to Discord!',\n  OTHER: 'Nothing to see',\n",
+            "{\n  \"GREETING\": \"Bienvenue sur Discord ! Discord\"\n}\n",
+        );
+        let definitions_content = std::fs::read_to_string(&definitions).unwrap();
+        let preview = preview(&database, "Discord", "Acme");
+        assert!(preview.skipped.is_empty());
+
+        let definitions = definitions.to_str().unwrap();
+        let translations = translations.to_str().unwrap();
+        assert_eq!(
+            preview
+                .files
+                .iter()
+                .map(|file| file.file.as_str())
+                .collect::<Vec<_>>(),
+            {
+                let mut paths = vec![definitions, translations];
+                paths.sort();
+                paths
+            }
+        );
+
+        let source = preview
+            .files
+            .iter()
+            .find(|file| file.file.as_str() == definitions)
+            .unwrap();
+        assert!(source.is_definitions);
+        assert_eq!(source.locale, key_symbol("en-US"));
+        assert_eq!(source.content_hash, hash_message_key(&definitions_content));
+        assert_eq!(
+            source.new_content,
+            definitions_content.replace("Welcome to Discord!", "Welcome to Acme!")
+        );
+        assert_eq!(
+            source.diff,
+            format!(
+                "--- {definitions}\n+++ {definitions}\n@@ -3,1 +3,1 @@\n\
+                 -  GREETING: 'Welcome to Discord!',\n+  GREETING: 'Welcome to Acme!',\n"
+            )
+        );
+        assert_eq!(
+            source.messages,
+            vec![ReplacedMessage {
+                key: key_symbol("GREETING"),
+                locale: key_symbol("en-US"),
+                before: "Welcome to Discord!".into(),
+                after: "Welcome to Acme!".into(),
+                matches: 1,
+            }]
+        );
+
+        let translation = preview
+            .files
+            .iter()
+            .find(|file| file.file.as_str() == translations)
+            .unwrap();
+        assert!(!translation.is_definitions);
+        assert_eq!(translation.locale, key_symbol("fr"));
+        assert_eq!(
+            translation.new_content,
+            "{\n  \"GREETING\": \"Bienvenue sur Acme ! Acme\"\n}\n"
+        );
+        assert_eq!(translation.messages[0].matches, 2);
+
+        // Previews never change the files.
+        assert_eq!(
+            std::fs::read_to_string(definitions).unwrap(),
+            definitions_content
+        );
+    }
+
+    #[test]
+    fn test_capture_groups() {
+        let (database, _, _) = database(
+            "replace_capture_groups",
+            "  BASIC: 'Nitro Basic for {price}',\n  CLASSIC: 'Nitro Classic',\n",
+            "{}\n",
+        );
+        let afters = |pattern, replacement| {
+            preview(&database, pattern, replacement)
+                .files
+                .into_iter()
+                .flat_map(|file| file.messages)
+                .map(|message| message.after)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            afters(r"Nitro (\w+)", "$1 Premium"),
+            vec!["Basic Premium for {price}", "Classic Premium"]
+        );
+        assert_eq!(
+            afters(r"Nitro (?P<tier>Basic)", "${tier} tier"),
+            vec!["Basic tier for {price}"]
+        );
+        // Matches that are replaced with the same text aren't changes.
+        assert!(afters("Nitro", "Nitro").is_empty());
+    }
+
+    #[test]
+    fn test_filters_and_skipped_values() {
+        let (database, _, _) = database(
+            "replace_filters",
+            "  SETTINGS_TITLE: 'Old settings',\n  SETTINGS_JOINED: 'Old ' + 'joined',\n  OTHER: 'Old other',\n",
+            "{\n  \"SETTINGS_TITLE\": \"Old paramètres\"\n}\n",
+        );
+        let preview = ReplaceInMessages::new(&database, Regex::new("Old").unwrap(), "New".into())
+            .with_keys(vec!["SETTINGS_*".into()])
+            .with_locales(vec!["en".into()])
+            .run()
+            .unwrap();
+        let replaced = preview
+            .files
+            .iter()
+            .flat_map(|file| &file.messages)
+            .map(|message| (message.key.as_str(), message.locale.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(replaced, vec![("SETTINGS_TITLE", "en-US")]);
+        assert_eq!(preview.skipped.len(), 1);
+        assert_eq!(preview.skipped[0].key, key_symbol("SETTINGS_JOINED"));
+        assert_eq!(
+            preview.skipped[0].reason,
+            RewriteError::UnsupportedDefinition(key_symbol("SETTINGS_JOINED")).to_string()
+        );
+    }
+}
@@ -0,0 +1,587 @@
+//! Rewriting the value of a message in the content of the file it's written in, either a
+//! definitions file or a translations file.
+//!
+//! Edits are made against the content of the message as the database knows it, then mapped back to
+//! the string literal in the file through the escapes that were decoded while reading it, so that
+//! only the edited text changes and everything else in the file, including other escapes in the
+//! same string, is kept exactly as it was written.
+use std::fmt::Write;
+use std::ops::Range;
+
+use intl_database_core::{KeySymbol, MessageValue, SourceOffset, SourceOffsetList};
+use thiserror::Error;
+
+/// A single change to the content of a message, replacing `start..end` with `text`. Offsets are
+/// bytes in the raw content of the message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Error)]
+pub enum RewriteError {
+    #[error(
+        "Edit {0}..{1} is outside of the message or doesn't start and end on a character boundary"
+    )]
+    InvalidEditRange(usize, usize),
+    #[error("Edits {0}..{1} and {2}..{3} overlap")]
+    OverlappingEdits(usize, usize, usize, usize),
+    #[error("{0} has no source position, so it can't be rewritten")]
+    UnknownPosition(KeySymbol),
+    #[error(
+        "{0} contains fragments that were expanded from the file meta, so it can't be rewritten"
+    )]
+    ExpandedFragments(KeySymbol),
+    #[error("{0} isn't written as a single string literal, so it can't be rewritten")]
+    UnsupportedDefinition(KeySymbol),
+    #[error("The source of {0} has changed since it was processed. Process the file again before rewriting it")]
+    OutdatedDefinition(KeySymbol),
+    #[error(
+        "{0} is written with String.raw, which can't contain backticks, backslashes, or `${{`"
+    )]
+    UnsupportedRawText(KeySymbol),
+    #[error("Replacement pattern is not a valid regular expression: {0}")]
+    InvalidPattern(#[from] regex::Error),
+    #[error("{0} didn't get the expected value from the new content of its file, so nothing was changed")]
+    UnexpectedValue(KeySymbol),
+    #[error("{0} has changed since the replacements were planned")]
+    ChangedFile(KeySymbol),
+    #[error("{error}. Restoring the original content of {} also failed, so they may still have their new content", .unrestored.iter().map(KeySymbol::as_str).collect::<Vec<_>>().join(", "))]
+    RestoreFailed {
+        error: std::io::Error,
+        unrestored: Vec<KeySymbol>,
+    },
+}
+
+/// The location of a string literal in the content of a file.
+struct StringLiteral {
+    /// Range of the content between the quotes.
+    content: Range<usize>,
+    quote: char,
+    /// True for `String.raw` templates, which don't have escapes.
+    is_raw: bool,
+}
+
+/// Returns the byte offset of the 1-based `line` and 0-based character `col` in `content`.
+fn offset_of_position(content: &str, line: u32, col: u32) -> Option<usize> {
+    let line_start = match line {
+        0 => return None,
+        1 => 0,
+        _ => {
+            content
+                .match_indices('\n')
+                .nth(line as usize - 2)
+                .map(|(index, _)| index)?
+                + 1
+        }
+    };
+    let line_content = &content[line_start..];
+    let column = line_content
+        .char_indices()
+        .nth(col as usize)
+        .map_or(line_content.len(), |(index, _)| index);
+    Some(line_start + column)
+}
+
+/// Find the string literal that starts at `offset`, which is either a quoted string, a template,
+/// or a `String.raw` template.
+fn find_string_literal(content: &str, offset: usize) -> Option<StringLiteral> {
+    let mut start = offset;
+    let is_raw = content[start..].starts_with("String.raw");
+    if is_raw {
+        start += "String.raw".len();
+        start += content[start..].len() - content[start..].trim_start().len();
+    }
+    let quote = content[start..].chars().next()?;
+    if !matches!(quote, '"' | '\'' | '`') || (is_raw && quote != '`') {
+        return None;
+    }
+
+    let content_start = start + 1;
+    let mut chars = content[content_start..].char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            // Even in raw templates, a backslash keeps the next character from closing it.
+            '\\' => {
+                chars.next();
+            }
+            '$' if quote == '`' && content[content_start + index..].starts_with("${") => {
+                return None;
+            }
+            c if c == quote => {
+                let content_end = content_start + index;
+                // Strings joined with `+` are only part of the value.
+                let rest = content[content_end + 1..].trim_start();
+                return (!rest.starts_with('+')).then_some(StringLiteral {
+                    content: content_start..content_end,
+                    quote,
+                    is_raw,
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the value of `digits` if they are all hexadecimal.
+fn parse_hex(digits: &str) -> Option<u32> {
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok()
+}
+
+/// Decode the escape sequence at the start of `escape`, which is everything after a backslash.
+/// Returns the character it decodes to, or None for line continuations that decode to nothing,
+/// along with the length of the sequence after the backslash. Returns None for invalid escapes.
+fn decode_escape(escape: &str) -> Option<(Option<char>, usize)> {
+    let first = escape.chars().next()?;
+    let decoded = match first {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        'v' => '\u{b}',
+        '0' => '\0',
+        'x' => return Some((char::from_u32(parse_hex(escape.get(1..3)?)?), 3)),
+        'u' if escape[1..].starts_with('{') => {
+            let close = escape.find('}')?;
+            return Some((char::from_u32(parse_hex(&escape[2..close])?), close + 1));
+        }
+        'u' => {
+            let code = parse_hex(escape.get(1..5)?)?;
+            // Characters outside of the BMP are written as a pair of escaped surrogates.
+            let low = escape
+                .get(5..11)
+                .and_then(|pair| pair.strip_prefix("\\u"))
+                .and_then(parse_hex)
+                .filter(|low| (0xDC00..0xE000).contains(low));
+            return match low {
+                Some(low) if (0xD800..0xDC00).contains(&code) => {
+                    let code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                    Some((char::from_u32(code), 11))
+                }
+                _ => Some((Some(char::from_u32(code)?), 5)),
+            };
+        }
+        '\r' if escape[1..].starts_with('\n') => return Some((None, 2)),
+        '\r' | '\n' | '\u{2028}' | '\u{2029}' => return Some((None, first.len_utf8())),
+        other => other,
+    };
+    Some((Some(decoded), first.len_utf8()))
+}
+
+/// Decode the content of a string literal, returning the decoded value and the escapes that were
+/// decoded in it. Returns None if the content has an invalid escape.
+fn decode_literal(content: &str) -> Option<(String, SourceOffsetList)> {
+    let mut decoded = String::with_capacity(content.len());
+    let mut offsets = SourceOffsetList::default();
+    let mut position = 0;
+    while let Some(index) = content[position..].find('\\') {
+        decoded.push_str(&content[position..position + index]);
+        let escape_start = position + index;
+        let (character, length) = decode_escape(&content[escape_start + 1..])?;
+        offsets.push(SourceOffset {
+            decoded_offset: decoded.len(),
+            decoded_len: character.map_or(0, char::len_utf8),
+            source_len: length + 1,
+        });
+        decoded.extend(character);
+        position = escape_start + 1 + length;
+    }
+    decoded.push_str(&content[position..]);
+    Some((decoded, offsets))
+}
+
+/// Escape `text` to be written inside of a string literal quoted with `quote`. Line breaks and
+/// other control characters are always escaped, except for newlines and tabs in templates.
+fn escape_text(text: &str, quote: char) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '$' if quote == '`' && chars.peek() == Some(&'{') => escaped.push_str("\\$"),
+            '\n' | '\t' if quote == '`' => escaped.push(c),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Sort `edits` and check that they can all be applied to `raw` together.
+fn check_edits(raw: &str, edits: &mut [MessageEdit]) -> Result<(), RewriteError> {
+    edits.sort_by_key(|edit| (edit.start, edit.end));
+    for edit in edits.iter() {
+        if edit.start > edit.end
+            || edit.end > raw.len()
+            || !raw.is_char_boundary(edit.start)
+            || !raw.is_char_boundary(edit.end)
+        {
+            return Err(RewriteError::InvalidEditRange(edit.start, edit.end));
+        }
+    }
+    for pair in edits.windows(2) {
+        if pair[1].start < pair[0].end {
+            return Err(RewriteError::OverlappingEdits(
+                pair[0].start,
+                pair[0].end,
+                pair[1].start,
+                pair[1].end,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A replacement of `range` in the content of a file with `text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// The changes to the content of a file that rewrite the value of a single message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueRewrite {
+    /// Edits to the string literal of the value, in order.
+    pub file_edits: Vec<FileEdit>,
+    /// The raw content of the value once the edits are applied.
+    pub raw: String,
+}
+
+/// Plan the changes to `content`, the content of the file that `value` of the message `key` is
+/// written in, that apply `edits` to the value. Definitions and translations are both supported.
+///
+/// The string literal at the position of the value must still decode to the raw content of the
+/// value, which makes sure that the file hasn't changed since it was processed. Values that are
+/// written as more than one string, like concatenations, can't be rewritten.
+pub fn rewrite_value(
+    content: &str,
+    key: KeySymbol,
+    value: &MessageValue,
+    mut edits: Vec<MessageEdit>,
+) -> Result<ValueRewrite, RewriteError> {
+    let raw: &str = &value.raw;
+    check_edits(raw, &mut edits)?;
+    if value.fragment_expansions.is_some() {
+        return Err(RewriteError::ExpandedFragments(key));
+    }
+    let position = value
+        .file_position
+        .ok_or(RewriteError::UnknownPosition(key))?;
+    let literal = offset_of_position(content, position.line, position.col)
+        .and_then(|offset| find_string_literal(content, offset))
+        .ok_or(RewriteError::UnsupportedDefinition(key))?;
+
+    let literal_content = &content[literal.content.clone()];
+    let offsets = match literal.is_raw {
+        true => (literal_content == raw).then(SourceOffsetList::default),
+        false => decode_literal(literal_content)
+            .filter(|(decoded, _)| decoded == raw)
+            .map(|(_, offsets)| offsets),
+    };
+    let offsets = offsets.ok_or(RewriteError::OutdatedDefinition(key))?;
+
+    let mut file_edits = Vec::with_capacity(edits.len());
+    let mut new_raw = raw.to_string();
+    // Applying the edits from last to first keeps the offsets of the earlier ones valid.
+    for edit in edits.iter().rev() {
+        let text = match literal.is_raw {
+            true if edit.text.contains(['`', '\\']) || edit.text.contains("${") => {
+                return Err(RewriteError::UnsupportedRawText(key));
+            }
+            true => edit.text.clone(),
+            false => escape_text(&edit.text, literal.quote),
+        };
+        let start = literal.content.start + offsets.to_source_offset(edit.start);
+        let end = literal.content.start + offsets.to_source_offset(edit.end);
+        file_edits.push(FileEdit {
+            range: start..end,
+            text,
+        });
+        new_raw.replace_range(edit.start..edit.end, &edit.text);
+    }
+    file_edits.reverse();
+    Ok(ValueRewrite {
+        file_edits,
+        raw: new_raw,
+    })
+}
+
+/// Apply `edits` to `content`. The edits must be in order and must not overlap.
+pub fn apply_file_edits(content: &str, edits: &[FileEdit]) -> String {
+    let mut new_content = content.to_string();
+    for edit in edits.iter().rev() {
+        new_content.replace_range(edit.range.clone(), &edit.text);
+    }
+    new_content
+}
+
+/// Format `edits` to `content`, the content of the file at `path`, as a unified diff without any
+/// context lines. Edits that touch the same lines are shown together in one hunk.
+pub fn format_file_diff(path: &str, content: &str, edits: &[FileEdit]) -> String {
+    let new_content = apply_file_edits(content, edits);
+    let old_lines: Vec<&str> = content.split('\n').collect();
+    let new_lines: Vec<&str> = new_content.split('\n').collect();
+    let line_starts = |text: &str| {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(index, _)| index + 1));
+        starts
+    };
+    let (old_starts, new_starts) = (line_starts(content), line_starts(&new_content));
+    let line_of =
+        |starts: &[usize], offset: usize| starts.partition_point(|start| *start <= offset) - 1;
+
+    // The first and last lines of each hunk in the old and new content.
+    let mut hunks: Vec<(Range<usize>, Range<usize>)> = vec![];
+    let mut new_offset = 0;
+    let mut old_offset = 0;
+    for edit in edits {
+        let new_start = new_offset + (edit.range.start - old_offset);
+        let new_end = new_start + edit.text.len();
+        (old_offset, new_offset) = (edit.range.end, new_end);
+
+        let old = line_of(&old_starts, edit.range.start)..line_of(&old_starts, edit.range.end);
+        let new = line_of(&new_starts, new_start)..line_of(&new_starts, new_end);
+        match hunks.last_mut() {
+            Some((last_old, last_new)) if last_old.end >= old.start => {
+                last_old.end = old.end;
+                last_new.end = new.end;
+            }
+            _ => hunks.push((old, new)),
+        }
+    }
+
+    let mut diff = format!("--- {path}\n+++ {path}\n");
+    for (old, new) in hunks {
+        let _ = writeln!(
+            diff,
+            "@@ -{},{} +{},{} @@",
+            old.start + 1,
+            old.len() + 1,
+            new.start + 1,
+            new.len() + 1
+        );
+        for line in &old_lines[old.start..=old.end] {
+            let _ = writeln!(diff, "-{line}");
+        }
+        for line in &new_lines[new.start..=new.end] {
+            let _ = writeln!(diff, "+{line}");
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, FilePosition};
+
+    use super::*;
+
+    fn edit(start: usize, end: usize, text: &str) -> MessageEdit {
+        MessageEdit {
+            start,
+            end,
+            text: text.into(),
+        }
+    }
+
+    /// Rewrite the value `raw` of a message whose literal starts at `line` and `col` of `content`,
+    /// returning the new content of the file.
+    fn rewrite(
+        content: &str,
+        raw: &str,
+        (line, col): (u32, u32),
+        edits: Vec<MessageEdit>,
+    ) -> Result<String, RewriteError> {
+        let value = MessageValue::from_raw(raw).with_file_position(FilePosition {
+            file: key_symbol("test.messages.js"),
+            line,
+            col,
+        });
+        let rewrite = rewrite_value(content, key_symbol("MESSAGE"), &value, edits.clone())?;
+        let mut expected_raw = raw.to_string();
+        for edit in edits.iter().rev() {
+            expected_raw.replace_range(edit.start..edit.end, &edit.text);
+        }
+        assert_eq!(rewrite.raw, expected_raw);
+        Ok(apply_file_edits(content, &rewrite.file_edits))
+    }
+
+    #[test]
+    fn test_offset_of_position() {
+        let content = "first\néé x\nlast";
+        assert_eq!(offset_of_position(content, 1, 0), Some(0));
+        // Columns count characters, not bytes.
+        assert_eq!(offset_of_position(content, 2, 3), Some(11));
+        assert_eq!(&content[11..], "x\nlast");
+        assert_eq!(offset_of_position(content, 3, 2), Some(15));
+        // Columns past the end of a line stop at the end of the content.
+        assert_eq!(offset_of_position(content, 3, 10), Some(content.len()));
+        assert_eq!(offset_of_position(content, 0, 0), None);
+        assert_eq!(offset_of_position(content, 4, 0), None);
+    }
+
+    #[test]
+    fn test_rewrites_around_non_ascii_columns() {
+        let content = "{\n  'ÉTÉ': 'été', 'x': 'été'\n}";
+        let new_content = rewrite(content, "été", (2, 21), vec![edit(0, 2, "e")]).unwrap();
+        assert_eq!(new_content, "{\n  'ÉTÉ': 'été', 'x': 'eté'\n}");
+    }
+
+    #[test]
+    fn test_keeps_escapes_outside_of_edits() {
+        let content = r#"  MESSAGE: "line\nnext \u{1F600} \uD83D\uDE00 \x41 end","#;
+        let raw = "line\nnext 😀 😀 A end";
+        let next = raw.find("next").unwrap();
+        let end = raw.find("end").unwrap();
+        let new_content = rewrite(
+            content,
+            raw,
+            (1, 11),
+            vec![edit(end, end + 3, "stop"), edit(next, next + 4, "then")],
+        )
+        .unwrap();
+        assert_eq!(
+            new_content,
+            r#"  MESSAGE: "line\nthen \u{1F600} \uD83D\uDE00 \x41 stop","#
+        );
+
+        // Edits that cover an escape replace the whole sequence.
+        let new_content = rewrite(content, raw, (1, 11), vec![edit(4, 5, " ")]).unwrap();
+        assert_eq!(
+            new_content,
+            r#"  MESSAGE: "line next \u{1F600} \uD83D\uDE00 \x41 end","#
+        );
+    }
+
+    #[test]
+    fn test_keeps_line_continuations() {
+        let content = "  MESSAGE: 'first \\\n second',";
+        let new_content = rewrite(
+            content,
+            "first  second",
+            (1, 11),
+            vec![edit(7, 13, "third")],
+        )
+        .unwrap();
+        assert_eq!(new_content, "  MESSAGE: 'first \\\n third',");
+    }
+
+    #[test]
+    fn test_escapes_new_text() {
+        let content = r#"  MESSAGE: "Hello","#;
+        let new_content = rewrite(content, "Hello", (1, 11), vec![edit(5, 5, "\n\"\\")]).unwrap();
+        assert_eq!(new_content, r#"  MESSAGE: "Hello\n\"\\","#);
+
+        // Templates can hold line breaks, but not the start of a substitution.
+        let content = "  MESSAGE: `Hello`,";
+        let new_content = rewrite(content, "Hello", (1, 11), vec![edit(5, 5, "\n${`")]).unwrap();
+        assert_eq!(new_content, "  MESSAGE: `Hello\n\\${\\``,");
+    }
+
+    #[test]
+    fn test_string_raw() {
+        let content = r"  MESSAGE: String.raw`C:\path {name}`,";
+        let raw = r"C:\path {name}";
+        let new_content = rewrite(content, raw, (1, 11), vec![edit(3, 7, "other")]).unwrap();
+        assert_eq!(new_content, r"  MESSAGE: String.raw`C:\other {name}`,");
+
+        for text in ["`", "\\", "${"] {
+            assert!(matches!(
+                rewrite(content, raw, (1, 11), vec![edit(0, 0, text)]),
+                Err(RewriteError::UnsupportedRawText(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_definitions() {
+        for content in [
+            "  MESSAGE: 'Hello' + ' world',",
+            "  MESSAGE: `Hello ${name}`,",
+            "  MESSAGE: getMessage(),",
+            "  MESSAGE: String.raw'Hello',",
+        ] {
+            assert!(
+                matches!(
+                    rewrite(content, "Hello", (1, 11), vec![edit(0, 0, "Oh, ")]),
+                    Err(RewriteError::UnsupportedDefinition(_))
+                ),
+                "{content}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_outdated_definitions() {
+        let content = "  MESSAGE: 'Hello, world',";
+        assert!(matches!(
+            rewrite(content, "Hello", (1, 11), vec![edit(0, 0, "Oh, ")]),
+            Err(RewriteError::OutdatedDefinition(_))
+        ));
+        // A position that no longer points at the literal can't be rewritten either.
+        assert!(matches!(
+            rewrite(content, "Hello, world", (1, 10), vec![edit(0, 0, "Oh, ")]),
+            Err(RewriteError::UnsupportedDefinition(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_edits() {
+        let content = "  MESSAGE: 'héllo',";
+        assert!(matches!(
+            rewrite(content, "héllo", (1, 11), vec![edit(0, 2, "")]),
+            Err(RewriteError::InvalidEditRange(0, 2))
+        ));
+        assert!(matches!(
+            rewrite(content, "héllo", (1, 11), vec![edit(0, 10, "")]),
+            Err(RewriteError::InvalidEditRange(0, 10))
+        ));
+        assert!(matches!(
+            rewrite(
+                content,
+                "héllo",
+                (1, 11),
+                vec![edit(3, 5, ""), edit(0, 4, "")]
+            ),
+            Err(RewriteError::OverlappingEdits(0, 4, 3, 5))
+        ));
+    }
+
+    #[test]
+    fn test_format_file_diff() {
+        let content = "{\n  A: 'one',\n  B: 'two',\n}";
+        let edits = [
+            FileEdit {
+                range: 8..11,
+                text: "uno".into(),
+            },
+            FileEdit {
+                range: 20..23,
+                text: "dos\nmore".into(),
+            },
+        ];
+        assert_eq!(
+            format_file_diff("test.messages.js", content, &edits),
+            "--- test.messages.js\n+++ test.messages.js\n\
+             @@ -2,1 +2,1 @@\n-  A: 'one',\n+  A: 'uno',\n\
+             @@ -3,1 +3,2 @@\n-  B: 'two',\n+  B: 'dos\n+more',\n"
+        );
+    }
+}
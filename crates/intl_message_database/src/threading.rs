@@ -51,7 +51,8 @@ pub(crate) fn run_in_thread_pool<
     mut processor: F,
 ) -> anyhow::Result<Vec<R>> {
     let num_jobs = data.len();
-    let pool = ThreadPool::new(get_reasonable_thread_count());
+    // Machines with a single core would otherwise get no threads at all.
+    let pool = ThreadPool::new(get_reasonable_thread_count().max(1));
     let (tx, rx) = channel();
     for datum in data {
         let tx = tx.clone();
@@ -63,6 +64,10 @@ pub(crate) fn run_in_thread_pool<
         });
     }
 
+    // Only the workers should be holding senders now. If one of them fails without sending a
+    // result, the channel closes once the rest are finished instead of waiting on it forever.
+    drop(tx);
+
     let mut results = Vec::with_capacity(num_jobs);
     for result in rx.iter().take(num_jobs) {
         results.push(processor(result));
@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml should be a valid cbindgen config");
+
+    // Build scripts must not write into the source tree. The checked-in copy under `include/` is
+    // kept in sync by a test instead.
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Failed to generate C bindings for intl_message_database_ffi")
+        .write_to_file(out_dir.join("intl_message_database.h"));
+}
@@ -0,0 +1,163 @@
+#ifndef INTL_MESSAGE_DATABASE_H
+#define INTL_MESSAGE_DATABASE_H
+
+/* This file is generated by cbindgen from `intl_message_database_ffi`. Do not edit it manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum IntlCompiledMessageFormat {
+  INTL_COMPILED_MESSAGE_FORMAT_JSON = 0,
+  INTL_COMPILED_MESSAGE_FORMAT_KEYLESS_JSON = 1,
+  INTL_COMPILED_MESSAGE_FORMAT_MF2 = 2,
+  INTL_COMPILED_MESSAGE_FORMAT_REACT_INTL = 3,
+} IntlCompiledMessageFormat;
+
+/**
+ * Result of every fallible function in this library. When the status is anything other than
+ * `Ok`, a description of the failure is available from `intl_last_error_message`.
+ */
+typedef enum IntlStatus {
+  INTL_STATUS_OK = 0,
+  /**
+   * The operation itself failed, like a file that couldn't be read or parsed.
+   */
+  INTL_STATUS_ERROR = 1,
+  /**
+   * An argument was null or not valid UTF-8.
+   */
+  INTL_STATUS_INVALID_ARGUMENT = 2,
+  /**
+   * The library panicked. The database may be left partially updated.
+   */
+  INTL_STATUS_PANIC = 3,
+} IntlStatus;
+
+/**
+ * An opaque handle to a messages database.
+ */
+typedef struct IntlMessagesDatabase IntlMessagesDatabase;
+
+/**
+ * A byte buffer allocated by the library and owned by the caller. Every buffer returned through
+ * an `out` parameter must be released with `intl_buffer_free`.
+ */
+typedef struct IntlBuffer {
+  uint8_t *data;
+  size_t len;
+  /**
+   * Allocated capacity of `data`, needed to release it. Callers must not modify this.
+   */
+  size_t capacity;
+} IntlBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a new, empty database. The returned pointer must be released with
+ * [intl_database_free].
+ */
+struct IntlMessagesDatabase *intl_database_new(void);
+
+/**
+ * Release a database created by [intl_database_new]. Passing null is a no-op.
+ *
+ * # Safety
+ * `database` must either be null or a pointer returned by [intl_database_new] that has not
+ * already been freed.
+ */
+void intl_database_free(struct IntlMessagesDatabase *database);
+
+/**
+ * Scan `directories` for every messages file they contain and process all of them into the
+ * database. Definitions files use `default_definition_locale` as their locale.
+ *
+ * Files that fail to process don't stop the others from being processed, but cause the call to
+ * return `Error`, with every failure listed in the error message. The number of failed files is
+ * written to `failed_count` when it is not null.
+ *
+ * # Safety
+ * `directories` must point to `directories_len` NUL-terminated strings. All other pointers must
+ * follow the rules of their individual types.
+ */
+enum IntlStatus intl_database_process_all_messages_files(struct IntlMessagesDatabase *database,
+                                                         const char *const *directories,
+                                                         size_t directories_len,
+                                                         const char *default_definition_locale,
+                                                         size_t *failed_count);
+
+/**
+ * Process a single definitions file into the database. `locale` may be null to use the default
+ * locale for definitions.
+ *
+ * # Safety
+ * All pointers must follow the rules of their individual types.
+ */
+enum IntlStatus intl_database_process_definitions_file(struct IntlMessagesDatabase *database,
+                                                       const char *file_path,
+                                                       const char *locale);
+
+/**
+ * Process a single translations file for the given `locale` into the database.
+ *
+ * # Safety
+ * All pointers must follow the rules of their individual types.
+ */
+enum IntlStatus intl_database_process_translation_file(struct IntlMessagesDatabase *database,
+                                                       const char *file_path,
+                                                       const char *locale);
+
+/**
+ * Validate every message in the database, writing the diagnostics to `out` as a JSON array.
+ * Each entry has the same shape as the diagnostics returned by the Node bindings.
+ *
+ * # Safety
+ * All pointers must follow the rules of their individual types.
+ */
+enum IntlStatus intl_database_validate(struct IntlMessagesDatabase *database,
+                                       struct IntlBuffer *out);
+
+/**
+ * Compile the messages from the source file at `file_path` for the given `locale` into a bundle,
+ * writing the result to `out`. Secret messages are obfuscated unless `bundle_secrets` is true.
+ *
+ * `format` must be one of the `IntlCompiledMessageFormat` values. It's taken as an integer
+ * rather than the enum itself, since C allows an enum to hold any value and an unknown one can't
+ * be represented safely in Rust.
+ *
+ * # Safety
+ * All pointers must follow the rules of their individual types.
+ */
+enum IntlStatus intl_database_precompile_to_buffer(struct IntlMessagesDatabase *database,
+                                                   const char *file_path,
+                                                   const char *locale,
+                                                   uint32_t format,
+                                                   bool bundle_secrets,
+                                                   struct IntlBuffer *out);
+
+/**
+ * Release a buffer that was returned by this library. Passing a buffer with a null `data`
+ * pointer is a no-op.
+ *
+ * # Safety
+ * `buffer` must have been returned by this library, unmodified, and not already freed.
+ */
+void intl_buffer_free(struct IntlBuffer buffer);
+
+/**
+ * Returns a description of the last failure that happened on the calling thread, or null if the
+ * last call succeeded. The returned string is owned by the library and is only valid until the
+ * next call into the library on the same thread.
+ */
+const char *intl_last_error_message(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* INTL_MESSAGE_DATABASE_H */
@@ -0,0 +1,53 @@
+use std::mem::ManuallyDrop;
+
+use crate::error::{FfiError, FfiResult};
+
+/// A byte buffer allocated by the library and owned by the caller. Every buffer returned through
+/// an `out` parameter must be released with `intl_buffer_free`.
+#[repr(C)]
+pub struct IntlBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+    /// Allocated capacity of `data`, needed to release it. Callers must not modify this.
+    pub capacity: usize,
+}
+
+impl IntlBuffer {
+    fn from_vec(data: Vec<u8>) -> Self {
+        let mut data = ManuallyDrop::new(data);
+        Self {
+            data: data.as_mut_ptr(),
+            len: data.len(),
+            capacity: data.capacity(),
+        }
+    }
+}
+
+/// Move `data` into the caller-provided `out` buffer.
+///
+/// # Safety
+/// `out` must either be null or point to writable memory for an [IntlBuffer].
+pub(crate) unsafe fn write_buffer(out: *mut IntlBuffer, data: Vec<u8>) -> FfiResult<()> {
+    if out.is_null() {
+        return Err(FfiError::InvalidArgument("out", "must not be null"));
+    }
+    out.write(IntlBuffer::from_vec(data));
+    Ok(())
+}
+
+/// Release a buffer that was returned by this library. Passing a buffer with a null `data`
+/// pointer is a no-op.
+///
+/// # Safety
+/// `buffer` must have been returned by this library, unmodified, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn intl_buffer_free(buffer: IntlBuffer) {
+    if buffer.data.is_null() {
+        return;
+    }
+    drop(Vec::from_raw_parts(
+        buffer.data,
+        buffer.len,
+        buffer.capacity,
+    ));
+}
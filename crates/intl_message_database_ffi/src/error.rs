@@ -0,0 +1,82 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use thiserror::Error;
+
+/// Result of every fallible function in this library. When the status is anything other than
+/// `Ok`, a description of the failure is available from `intl_last_error_message`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntlStatus {
+    Ok = 0,
+    /// The operation itself failed, like a file that couldn't be read or parsed.
+    Error = 1,
+    /// An argument was null or not valid UTF-8.
+    InvalidArgument = 2,
+    /// The library panicked. The database may be left partially updated.
+    Panic = 3,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum FfiError {
+    #[error("Argument `{0}` {1}")]
+    InvalidArgument(&'static str, &'static str),
+    #[error(transparent)]
+    Operation(#[from] anyhow::Error),
+}
+
+pub(crate) type FfiResult<T> = Result<T, FfiError>;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Error messages can't contain interior NUL bytes, but they could come from file content.
+    let message = CString::new(message.replace('\0', "\\0")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+// Panics can only be caught and reported as a status when they unwind. Aborting would take down
+// the whole host process instead.
+#[cfg(panic = "abort")]
+compile_error!("intl_message_database_ffi must be built with `panic = \"unwind\"`");
+
+/// Run `operation`, converting any error or panic into a status code and recording its message
+/// for `intl_last_error_message`.
+pub(crate) fn run_ffi(operation: impl FnOnce() -> FfiResult<()>) -> IntlStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(operation)) {
+        Ok(Ok(())) => IntlStatus::Ok,
+        Ok(Err(error)) => {
+            let status = match error {
+                FfiError::InvalidArgument(..) => IntlStatus::InvalidArgument,
+                FfiError::Operation(_) => IntlStatus::Error,
+            };
+            set_last_error(format!("{error:#}"));
+            status
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic".into());
+            set_last_error(format!("intl_message_database panicked: {message}"));
+            IntlStatus::Panic
+        }
+    }
+}
+
+/// Returns a description of the last failure that happened on the calling thread, or null if the
+/// last call succeeded. The returned string is owned by the library and is only valid until the
+/// next call into the library on the same thread.
+#[no_mangle]
+pub extern "C" fn intl_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
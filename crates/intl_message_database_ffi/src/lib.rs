@@ -0,0 +1,442 @@
+//! C-compatible bindings for the intl message database, for embedding the message tooling in
+//! native build steps without Node. Like the Node wrapper, these functions only convert to and
+//! from C types and then call into [intl_message_database::public].
+//!
+//! See the README for the ownership rules of values passed across the boundary.
+use std::ffi::{c_char, CStr};
+
+use intl_database_core::MessagesDatabase;
+use intl_database_exporter::{CompiledMessageFormat, IntlMessageBundlerOptions};
+use intl_message_database::public;
+use intl_validator::MessageDiagnostic;
+
+use crate::buffer::write_buffer;
+pub use crate::buffer::{intl_buffer_free, IntlBuffer};
+pub use crate::error::{intl_last_error_message, IntlStatus};
+use crate::error::{run_ffi, FfiError, FfiResult};
+
+mod buffer;
+mod error;
+
+/// An opaque handle to a messages database.
+pub struct IntlMessagesDatabase {
+    database: MessagesDatabase,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub enum IntlCompiledMessageFormat {
+    Json = 0,
+    KeylessJson = 1,
+    Mf2 = 2,
+    ReactIntl = 3,
+}
+
+impl From<IntlCompiledMessageFormat> for CompiledMessageFormat {
+    fn from(value: IntlCompiledMessageFormat) -> Self {
+        match value {
+            IntlCompiledMessageFormat::Json => CompiledMessageFormat::Json,
+            IntlCompiledMessageFormat::KeylessJson => CompiledMessageFormat::KeylessJson,
+            IntlCompiledMessageFormat::Mf2 => CompiledMessageFormat::Mf2,
+            IntlCompiledMessageFormat::ReactIntl => CompiledMessageFormat::ReactIntl,
+        }
+    }
+}
+
+/// # Safety
+/// `value` must either be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(name: &'static str, value: *const c_char) -> FfiResult<&'a str> {
+    read_optional_str(name, value)?.ok_or(FfiError::InvalidArgument(name, "must not be null"))
+}
+
+/// # Safety
+/// `value` must either be null or point to a NUL-terminated string.
+unsafe fn read_optional_str<'a>(
+    name: &'static str,
+    value: *const c_char,
+) -> FfiResult<Option<&'a str>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map(Some)
+        .map_err(|_| FfiError::InvalidArgument(name, "must be valid UTF-8"))
+}
+
+fn read_format(format: u32) -> FfiResult<IntlCompiledMessageFormat> {
+    match format {
+        0 => Ok(IntlCompiledMessageFormat::Json),
+        1 => Ok(IntlCompiledMessageFormat::KeylessJson),
+        2 => Ok(IntlCompiledMessageFormat::Mf2),
+        3 => Ok(IntlCompiledMessageFormat::ReactIntl),
+        _ => Err(FfiError::InvalidArgument(
+            "format",
+            "must be an IntlCompiledMessageFormat value",
+        )),
+    }
+}
+
+/// # Safety
+/// `database` must either be null or a pointer returned by [intl_database_new] that has not been
+/// freed.
+unsafe fn read_database<'a>(
+    database: *mut IntlMessagesDatabase,
+) -> FfiResult<&'a mut MessagesDatabase> {
+    database
+        .as_mut()
+        .map(|handle| &mut handle.database)
+        .ok_or(FfiError::InvalidArgument("database", "must not be null"))
+}
+
+fn serialize_diagnostic(diagnostic: &MessageDiagnostic) -> serde_json::Value {
+    serde_json::json!({
+        "name": diagnostic.name.as_str(),
+        "key": diagnostic.key.to_string(),
+        "file": diagnostic.file_position.file.to_string(),
+        "line": diagnostic.file_position.line,
+        "col": diagnostic.file_position.col,
+        "locale": diagnostic.locale.to_string(),
+        "severity": diagnostic.severity,
+        "description": diagnostic.description,
+        "help": diagnostic.help,
+    })
+}
+
+/// Create a new, empty database. The returned pointer must be released with
+/// [intl_database_free].
+#[no_mangle]
+pub extern "C" fn intl_database_new() -> *mut IntlMessagesDatabase {
+    Box::into_raw(Box::new(IntlMessagesDatabase {
+        database: MessagesDatabase::new(),
+    }))
+}
+
+/// Release a database created by [intl_database_new]. Passing null is a no-op.
+///
+/// # Safety
+/// `database` must either be null or a pointer returned by [intl_database_new] that has not
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn intl_database_free(database: *mut IntlMessagesDatabase) {
+    if !database.is_null() {
+        drop(Box::from_raw(database));
+    }
+}
+
+/// Scan `directories` for every messages file they contain and process all of them into the
+/// database. Definitions files use `default_definition_locale` as their locale.
+///
+/// Files that fail to process don't stop the others from being processed, but cause the call to
+/// return `Error`, with every failure listed in the error message. The number of failed files is
+/// written to `failed_count` when it is not null.
+///
+/// # Safety
+/// `directories` must point to `directories_len` NUL-terminated strings. All other pointers must
+/// follow the rules of their individual types.
+#[no_mangle]
+pub unsafe extern "C" fn intl_database_process_all_messages_files(
+    database: *mut IntlMessagesDatabase,
+    directories: *const *const c_char,
+    directories_len: usize,
+    default_definition_locale: *const c_char,
+    failed_count: *mut usize,
+) -> IntlStatus {
+    run_ffi(|| {
+        let database = read_database(database)?;
+        let default_definition_locale =
+            read_str("default_definition_locale", default_definition_locale)?;
+        if directories.is_null() && directories_len > 0 {
+            return Err(FfiError::InvalidArgument("directories", "must not be null"));
+        }
+        let directories = match directories_len {
+            0 => vec![],
+            _ => std::slice::from_raw_parts(directories, directories_len)
+                .iter()
+                .map(|directory| read_str("directories", *directory))
+                .collect::<FfiResult<Vec<&str>>>()?,
+        };
+
+        let files = public::find_all_messages_files(
+            database,
+            directories.iter(),
+            default_definition_locale,
+        );
+        let result = public::process_all_messages_files(database, files.into_iter())?;
+        if let Some(failed_count) = failed_count.as_mut() {
+            *failed_count = result.failed.len();
+        }
+        if result.failed.is_empty() {
+            return Ok(());
+        }
+
+        let failures = result
+            .failed
+            .iter()
+            .map(|(file, error)| format!("{file}: {error}"))
+            .collect::<Vec<String>>()
+            .join("\n");
+        Err(anyhow::anyhow!(
+            "Failed to process {} files:\n{failures}",
+            result.failed.len()
+        )
+        .into())
+    })
+}
+
+/// Process a single definitions file into the database. `locale` may be null to use the default
+/// locale for definitions.
+///
+/// # Safety
+/// All pointers must follow the rules of their individual types.
+#[no_mangle]
+pub unsafe extern "C" fn intl_database_process_definitions_file(
+    database: *mut IntlMessagesDatabase,
+    file_path: *const c_char,
+    locale: *const c_char,
+) -> IntlStatus {
+    run_ffi(|| {
+        let database = read_database(database)?;
+        let file_path = read_str("file_path", file_path)?;
+        let locale = read_optional_str("locale", locale)?;
+        public::process_definitions_file(database, file_path, locale)?;
+        Ok(())
+    })
+}
+
+/// Process a single translations file for the given `locale` into the database.
+///
+/// # Safety
+/// All pointers must follow the rules of their individual types.
+#[no_mangle]
+pub unsafe extern "C" fn intl_database_process_translation_file(
+    database: *mut IntlMessagesDatabase,
+    file_path: *const c_char,
+    locale: *const c_char,
+) -> IntlStatus {
+    run_ffi(|| {
+        let database = read_database(database)?;
+        let file_path = read_str("file_path", file_path)?;
+        let locale = read_str("locale", locale)?;
+        public::process_translation_file(database, file_path, locale)?;
+        Ok(())
+    })
+}
+
+/// Validate every message in the database, writing the diagnostics to `out` as a JSON array.
+/// Each entry has the same shape as the diagnostics returned by the Node bindings.
+///
+/// # Safety
+/// All pointers must follow the rules of their individual types.
+#[no_mangle]
+pub unsafe extern "C" fn intl_database_validate(
+    database: *mut IntlMessagesDatabase,
+    out: *mut IntlBuffer,
+) -> IntlStatus {
+    run_ffi(|| {
+        let database = read_database(database)?;
+        let diagnostics = public::validate_messages(database)?;
+        let serialized: Vec<serde_json::Value> =
+            diagnostics.iter().map(serialize_diagnostic).collect();
+        let content = serde_json::to_vec(&serialized).map_err(anyhow::Error::from)?;
+        write_buffer(out, content)
+    })
+}
+
+/// Compile the messages from the source file at `file_path` for the given `locale` into a bundle,
+/// writing the result to `out`. Secret messages are obfuscated unless `bundle_secrets` is true.
+///
+/// `format` must be one of the `IntlCompiledMessageFormat` values. It's taken as an integer
+/// rather than the enum itself, since C allows an enum to hold any value and an unknown one can't
+/// be represented safely in Rust.
+///
+/// # Safety
+/// All pointers must follow the rules of their individual types.
+#[no_mangle]
+pub unsafe extern "C" fn intl_database_precompile_to_buffer(
+    database: *mut IntlMessagesDatabase,
+    file_path: *const c_char,
+    locale: *const c_char,
+    format: u32,
+    bundle_secrets: bool,
+    out: *mut IntlBuffer,
+) -> IntlStatus {
+    run_ffi(|| {
+        let database = read_database(database)?;
+        let file_path = read_str("file_path", file_path)?;
+        let locale = read_str("locale", locale)?;
+        let format = read_format(format)?;
+        let options = IntlMessageBundlerOptions::default()
+            .with_format(format.into())
+            .with_bundle_secrets(bundle_secrets);
+        let content = public::precompile_to_buffer(database, file_path, locale, options)?;
+        write_buffer(out, content)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::{CStr, CString};
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Write a definitions file into a directory that's unique to the calling test.
+    fn write_definitions(test_name: &str, content: &str) -> CString {
+        let directory = std::env::temp_dir()
+            .join("intl_message_database_ffi")
+            .join(test_name);
+        std::fs::create_dir_all(&directory).unwrap();
+        let path: PathBuf = directory.join("test.messages.js");
+        let content = format!("import {{defineMessages}} from '@discord/intl';\n{content}");
+        std::fs::write(&path, content).unwrap();
+        CString::new(path.to_str().unwrap()).unwrap()
+    }
+
+    fn last_error() -> String {
+        let message = intl_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    unsafe fn take_buffer(buffer: IntlBuffer) -> String {
+        let content = std::slice::from_raw_parts(buffer.data, buffer.len).to_vec();
+        intl_buffer_free(buffer);
+        String::from_utf8(content).unwrap()
+    }
+
+    fn empty_buffer() -> IntlBuffer {
+        IntlBuffer {
+            data: std::ptr::null_mut(),
+            len: 0,
+            capacity: 0,
+        }
+    }
+
+    #[test]
+    fn test_header_is_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/intl_message_database.h"));
+        let checked_in = include_str!("../include/intl_message_database.h");
+        assert!(
+            generated == checked_in,
+            "include/intl_message_database.h is out of date. Copy it from {}",
+            concat!(env!("OUT_DIR"), "/intl_message_database.h")
+        );
+    }
+
+    #[test]
+    fn test_precompile() {
+        let file_path = write_definitions(
+            "test_precompile",
+            "export default defineMessages({ GREETING: 'Hello {name}' });",
+        );
+        let locale = CString::new("en-US").unwrap();
+        unsafe {
+            let database = intl_database_new();
+            let status = intl_database_process_definitions_file(
+                database,
+                file_path.as_ptr(),
+                std::ptr::null(),
+            );
+            assert_eq!(status, IntlStatus::Ok, "{}", last_error());
+
+            let mut out = empty_buffer();
+            let status = intl_database_precompile_to_buffer(
+                database,
+                file_path.as_ptr(),
+                locale.as_ptr(),
+                IntlCompiledMessageFormat::KeylessJson as u32,
+                false,
+                &mut out,
+            );
+            assert_eq!(status, IntlStatus::Ok, "{}", last_error());
+            assert!(intl_last_error_message().is_null());
+            assert!(take_buffer(out).contains(r#"["Hello ",[1,"name"]]"#));
+            intl_database_free(database);
+        }
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        let file_path = write_definitions(
+            "test_invalid_format",
+            "export default defineMessages({ GREETING: 'Hello' });",
+        );
+        let locale = CString::new("en-US").unwrap();
+        unsafe {
+            let database = intl_database_new();
+            intl_database_process_definitions_file(database, file_path.as_ptr(), std::ptr::null());
+
+            let mut out = empty_buffer();
+            let status = intl_database_precompile_to_buffer(
+                database,
+                file_path.as_ptr(),
+                locale.as_ptr(),
+                42,
+                false,
+                &mut out,
+            );
+            assert_eq!(status, IntlStatus::InvalidArgument);
+            assert!(last_error().contains("`format`"));
+            assert!(out.data.is_null());
+            intl_database_free(database);
+        }
+    }
+
+    #[test]
+    fn test_null_arguments() {
+        unsafe {
+            let status = intl_database_process_translation_file(
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+            assert_eq!(status, IntlStatus::InvalidArgument);
+            assert_eq!(last_error(), "Argument `database` must not be null");
+
+            let database = intl_database_new();
+            let status = intl_database_process_translation_file(
+                database,
+                std::ptr::null(),
+                std::ptr::null(),
+            );
+            assert_eq!(status, IntlStatus::InvalidArgument);
+            assert_eq!(last_error(), "Argument `file_path` must not be null");
+            intl_database_free(database);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let file_path = write_definitions(
+            "test_validate",
+            "export default defineMessages({ PADDED: ' Hello ' });",
+        );
+        unsafe {
+            let database = intl_database_new();
+            intl_database_process_definitions_file(database, file_path.as_ptr(), std::ptr::null());
+
+            let mut out = empty_buffer();
+            let status = intl_database_validate(database, &mut out);
+            assert_eq!(status, IntlStatus::Ok, "{}", last_error());
+            let diagnostics: serde_json::Value = serde_json::from_str(&take_buffer(out)).unwrap();
+            let diagnostics = diagnostics.as_array().unwrap();
+            assert!(diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic["key"] == "PADDED"));
+            intl_database_free(database);
+        }
+    }
+
+    #[test]
+    fn test_panic_is_reported() {
+        let status = run_ffi(|| panic!("something went wrong"));
+        assert_eq!(status, IntlStatus::Panic);
+        assert_eq!(
+            last_error(),
+            "intl_message_database panicked: something went wrong"
+        );
+    }
+}
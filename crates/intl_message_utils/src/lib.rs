@@ -1,6 +1,12 @@
 use memchr::memmem;
 use once_cell::sync::Lazy;
 
+pub use unicode::{
+    is_bidi_control, is_nfc_normalized, is_stray_zero_width, is_zero_width, normalize_unicode,
+};
+
+mod unicode;
+
 /// Name of the JS runtime package that should be used for all generated code or parsing for imports
 /// that read from the package.
 pub static RUNTIME_PACKAGE_NAME: &str = "@discord/intl";
@@ -50,6 +56,33 @@ pub fn is_message_definitions_file(file_name: &str) -> bool {
         || file_name.ends_with(".messages.jsx")
         || file_name.ends_with(".messages.ts")
         || file_name.ends_with(".messages.js")
+        || is_formatjs_definitions_file(file_name)
+}
+
+/// Returns true if the given `file_name` is considered a message definitions file, including
+/// files that end with any of the given `extensions`.
+///
+/// Files match either by the built-in rules of [is_message_definitions_file] or by ending with one
+/// of `extensions`, like `.messages.mjs` or a project-specific suffix like `.strings.js`.
+///
+/// Like `.messages` for the built-in extensions, imports can leave out the last part of an
+/// extension, so `Feature.strings` is a definitions file when `.strings.js` is configured.
+pub fn is_message_definitions_file_with_extensions(file_name: &str, extensions: &[String]) -> bool {
+    is_message_definitions_file(file_name)
+        || extensions.iter().any(|extension| {
+            let import_extension = extension
+                .rfind('.')
+                .filter(|index| *index > 0)
+                .map(|index| &extension[..index]);
+            file_name.ends_with(extension.as_str())
+                || import_extension.is_some_and(|import| file_name.ends_with(import))
+        })
+}
+
+/// Returns true if the given `file_name` is the output of `formatjs extract`, containing message
+/// definitions from an existing react-intl codebase, like `Legacy.messages.formatjs.json`.
+pub fn is_formatjs_definitions_file(file_name: &str) -> bool {
+    file_name.ends_with(".messages.formatjs.json")
 }
 
 pub fn is_message_translations_file(file_name: &str) -> bool {
@@ -57,6 +90,13 @@ pub fn is_message_translations_file(file_name: &str) -> bool {
 }
 
 pub fn is_any_messages_file(file_name: &str) -> bool {
+    // FormatJS files are the only messages files with an extra extension part.
+    if is_formatjs_definitions_file(file_name) {
+        return !file_name
+            .trim_end_matches(".messages.formatjs.json")
+            .contains('.');
+    }
+
     // Split into <prefix> <second_extension> <last_extension>. A file is a messages file
     // if `last_extension` or `second_extension` is `messages`, meaning anything like `.messages.js`
     // or `.messages.py` or anything else counts, as well as implicit final extensions, like
@@ -73,6 +113,76 @@ pub fn is_any_messages_file(file_name: &str) -> bool {
     is_messages_extesnsion && !stem.is_some_and(|stem| stem.contains('.'))
 }
 
+/// Returns the canonical form of the BCP 47 locale `tag`, or `None` if it is not a well-formed
+/// tag.
+///
+/// Subtags can be separated by either `-` or `_`, and are re-cased to the conventional form,
+/// so `en_us`, `EN-us` and `en-US` all become `en-US`, and `zh_hant_tw` becomes `zh-Hant-TW`.
+///
+/// This only canonicalizes the syntax of the tag. Subtags are not checked against the language
+/// subtag registry, and deprecated or aliased subtags are kept as they are written.
+pub fn normalize_locale_tag(tag: &str) -> Option<String> {
+    let mut subtags = tag.split(['-', '_']);
+    let language = subtags.next()?;
+    if !matches!(language.len(), 2..=3 | 5..=8)
+        || !language.bytes().all(|b| b.is_ascii_alphabetic())
+    {
+        return None;
+    }
+
+    let mut normalized = language.to_ascii_lowercase();
+    // Subtags have to appear in order, so each kind is only allowed until a later one is seen:
+    // extended languages, then the script, region, and variants, and finally any extensions.
+    let mut extlang_count = 0;
+    let mut has_script = false;
+    let mut has_region = false;
+    let mut has_variant = false;
+    let mut in_extension = false;
+    for subtag in subtags {
+        if subtag.is_empty()
+            || subtag.len() > 8
+            || !subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+        {
+            return None;
+        }
+        let is_alpha = subtag.bytes().all(|b| b.is_ascii_alphabetic());
+        let is_digit = subtag.bytes().all(|b| b.is_ascii_digit());
+        normalized.push('-');
+
+        if in_extension || subtag.len() == 1 {
+            in_extension = true;
+            normalized.push_str(&subtag.to_ascii_lowercase());
+        } else if is_alpha
+            && subtag.len() == 3
+            && extlang_count < 3
+            && !(has_script || has_region || has_variant)
+        {
+            extlang_count += 1;
+            normalized.push_str(&subtag.to_ascii_lowercase());
+        } else if is_alpha && subtag.len() == 4 && !(has_script || has_region || has_variant) {
+            has_script = true;
+            normalized.push_str(&subtag[..1].to_ascii_uppercase());
+            normalized.push_str(&subtag[1..].to_ascii_lowercase());
+        } else if ((is_alpha && subtag.len() == 2) || (is_digit && subtag.len() == 3))
+            && !(has_region || has_variant)
+        {
+            has_region = true;
+            normalized.push_str(&subtag.to_ascii_uppercase());
+        } else if subtag.len() >= 5 || (subtag.len() == 4 && subtag.as_bytes()[0].is_ascii_digit())
+        {
+            has_variant = true;
+            normalized.push_str(&subtag.to_ascii_lowercase());
+        } else {
+            return None;
+        }
+    }
+    // A singleton has to be followed by at least one subtag, like the `ca` in `th-u-ca-buddhist`.
+    if in_extension && normalized.as_bytes()[normalized.len() - 2] == b'-' {
+        return None;
+    }
+    Some(normalized)
+}
+
 static DOUBLE_NEWLINE_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"\n\n"));
 
 /// Returns true if the given `message` contains block-like content and should
@@ -81,3 +191,100 @@ static DOUBLE_NEWLINE_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder
 pub fn message_may_have_blocks(message: &str) -> bool {
     DOUBLE_NEWLINE_FINDER.find(message.as_bytes()).is_some()
 }
+
+/// Returns true if `text` matches `glob` in full, where `*` matches any number of characters and
+/// `?` matches exactly one.
+pub fn matches_glob(glob: &str, text: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    // Positions to go back to after a `*` fails to match, trying one more character each time.
+    let mut backtrack: Option<(usize, usize)> = None;
+    let (mut g, mut t) = (0, 0);
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g, t));
+                g += 1;
+            }
+            Some('?') => (g, t) = (g + 1, t + 1),
+            Some(c) if *c == text[t] => (g, t) = (g + 1, t + 1),
+            _ => match backtrack {
+                Some((star, start)) => {
+                    backtrack = Some((star, start + 1));
+                    (g, t) = (star + 1, start + 1);
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_definitions_extensions() {
+        let extensions = vec![".messages.mjs".to_string(), ".strings.js".to_string()];
+        for file_name in [
+            "Feature.messages.js",
+            "Feature.messages",
+            "Feature.messages.mjs",
+            "Feature.strings.js",
+            "./Feature.strings",
+        ] {
+            assert!(
+                is_message_definitions_file_with_extensions(file_name, &extensions),
+                "{file_name}"
+            );
+        }
+        for file_name in ["Feature.js", "Feature.strings.ts", "strings.json"] {
+            assert!(
+                !is_message_definitions_file_with_extensions(file_name, &extensions),
+                "{file_name}"
+            );
+        }
+        assert!(!is_message_definitions_file("Feature.messages.mjs"));
+    }
+
+    #[test]
+    fn test_normalize_locale_tag() {
+        for (tag, expected) in [
+            ("en", "en"),
+            ("en_us", "en-US"),
+            ("EN-us", "en-US"),
+            ("en_GB", "en-GB"),
+            ("zh_hant_tw", "zh-Hant-TW"),
+            ("SR-LATN-rs", "sr-Latn-RS"),
+            ("es-419", "es-419"),
+            ("zh-YUE-hk", "zh-yue-HK"),
+            ("de-CH-1996", "de-CH-1996"),
+            ("sl-Rozaj-BISKE", "sl-rozaj-biske"),
+            ("th-TH-u-CA-Buddhist", "th-TH-u-ca-buddhist"),
+            ("en-x-Private", "en-x-private"),
+        ] {
+            assert_eq!(normalize_locale_tag(tag).as_deref(), Some(expected), "{tag}");
+        }
+    }
+
+    #[test]
+    fn test_normalize_invalid_locale_tag() {
+        for tag in [
+            "",
+            "e",
+            "english1",
+            "en--US",
+            "en-US-",
+            "en-US-GB",
+            "en-Latn-Latn",
+            "en-US-Latn",
+            "en-abcdefghi",
+            "en-ÜS",
+            "th-u",
+            "en-a-b",
+        ] {
+            assert_eq!(normalize_locale_tag(tag), None, "{tag}");
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Classification of characters that are invisible or change how the text around them is
+//! displayed, which are easy to add to messages by accident, like when copying text from another
+//! application, and hard for reviewers to notice.
+use std::borrow::Cow;
+
+use unicode_normalization::UnicodeNormalization;
+
+const ZERO_WIDTH_NON_JOINER: char = '\u{200C}';
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// Returns true for characters that take up no space and are never visible themselves: the zero
+/// width space, the zero width joiner and non-joiner, the word joiner, and the byte order mark.
+pub fn is_zero_width(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}' | ZERO_WIDTH_NON_JOINER | ZERO_WIDTH_JOINER | '\u{2060}' | '\u{FEFF}'
+    )
+}
+
+/// Returns true for the bidi embedding, override, and isolate controls, which change the order
+/// that text is displayed in without being visible themselves.
+///
+/// These are what "Trojan Source" attacks use to make text read differently than it is stored.
+/// Directional marks like U+200F are not included, since right-to-left text commonly needs them
+/// and they can't reorder other text.
+pub fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Returns true if `c` is a zero-width character that has no effect between `previous` and
+/// `next`.
+///
+/// Joiners change how the characters on either side of them are shaped, like the non-joiner in
+/// Persian words or the joiner in emoji sequences, so they are only stray when they aren't between
+/// two such characters. Every other zero-width character is always stray.
+pub fn is_stray_zero_width(previous: Option<char>, c: char, next: Option<char>) -> bool {
+    if !is_zero_width(c) {
+        return false;
+    }
+    if !matches!(c, ZERO_WIDTH_NON_JOINER | ZERO_WIDTH_JOINER) {
+        return true;
+    }
+    let can_join = |c: Option<char>| {
+        c.is_some_and(|c| !c.is_ascii() && !c.is_whitespace() && !is_zero_width(c))
+    };
+    !(can_join(previous) && can_join(next))
+}
+
+/// Returns true if `text` is already in Unicode Normalization Form C, where characters like `é`
+/// are written as a single code point rather than a letter followed by a combining accent.
+pub fn is_nfc_normalized(text: &str) -> bool {
+    unicode_normalization::is_nfc(text)
+}
+
+/// Remove stray zero-width characters from `text` and normalize it to NFC, neither of which
+/// changes how the text is displayed.
+///
+/// Bidi controls are kept, since removing them can. Returns `text` as it is when there is nothing
+/// to fix.
+pub fn normalize_unicode(text: &str) -> Cow<'_, str> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_stray = |index: usize| {
+        let previous = index.checked_sub(1).map(|previous| chars[previous]);
+        is_stray_zero_width(previous, chars[index], chars.get(index + 1).copied())
+    };
+    let has_stray = (0..chars.len()).any(is_stray);
+    if !has_stray && is_nfc_normalized(text) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result: String = (0..chars.len())
+        .filter(|index| !is_stray(*index))
+        .map(|index| chars[index])
+        .collect();
+    if !is_nfc_normalized(&result) {
+        result = result.nfc().collect();
+    }
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stray_zero_width() {
+        assert!(is_stray_zero_width(Some('a'), '\u{200B}', Some('b')));
+        assert!(is_stray_zero_width(None, '\u{FEFF}', Some('a')));
+        // Joiners are only needed between characters that they can join.
+        assert!(!is_stray_zero_width(Some('👩'), '\u{200D}', Some('💻')));
+        assert!(!is_stray_zero_width(Some('ی'), '\u{200C}', Some('خ')));
+        assert!(is_stray_zero_width(Some('a'), '\u{200D}', Some('b')));
+        assert!(is_stray_zero_width(Some(' '), '\u{200C}', Some('خ')));
+        assert!(is_stray_zero_width(Some('ی'), '\u{200C}', None));
+        assert!(!is_stray_zero_width(Some('a'), 'b', Some('c')));
+    }
+
+    #[test]
+    fn test_bidi_controls() {
+        assert!(is_bidi_control('\u{202E}'));
+        assert!(is_bidi_control('\u{2067}'));
+        assert!(!is_bidi_control('\u{200F}'));
+        assert!(!is_bidi_control('a'));
+    }
+
+    #[test]
+    fn test_normalize_unicode() {
+        assert!(matches!(normalize_unicode("Café"), Cow::Borrowed("Café")));
+        assert!(matches!(
+            normalize_unicode("👩\u{200D}💻"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(normalize_unicode("Cafe\u{0301}"), "Caf\u{00E9}");
+        assert_eq!(normalize_unicode("\u{FEFF}Hi\u{200B} there"), "Hi there");
+        // Removing a stray character can leave an accent next to the letter it belongs to.
+        assert_eq!(normalize_unicode("e\u{200B}\u{0301}"), "\u{00E9}");
+        assert_eq!(normalize_unicode("a\u{202E}b"), "a\u{202E}b");
+    }
+}
@@ -0,0 +1,241 @@
+//! A baseline records the diagnostics that a project already has, so that new validators can be
+//! turned on without first fixing every existing problem across all messages. Validating against a
+//! baseline only reports diagnostics that aren't recorded in it, and the existing ones can be fixed
+//! gradually.
+//!
+//! Diagnostics are recorded by message key, locale, and rule, along with a hash of the value they
+//! were found in. Once a value changes, its diagnostics are no longer covered by the baseline, so
+//! editing a message means fixing its problems rather than carrying them forward. Each entry also
+//! counts how many times the rule was reported for that value, so adding another instance of the
+//! same problem is still reported.
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh64::Xxh64;
+
+use intl_database_core::{KeySymbol, MessagesDatabase};
+
+use crate::MessageDiagnostic;
+
+/// Version of the baseline file format, written into every baseline so that older files can be
+/// detected if the format ever changes.
+const BASELINE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub key: String,
+    pub locale: String,
+    pub rule: String,
+    /// Hash of the value that the diagnostics were found in, along with its source value.
+    pub hash: String,
+    /// Number of diagnostics of this rule that were found in the value.
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiagnosticBaseline {
+    pub version: u32,
+    /// Entries sorted by key, locale, and rule, so that baseline files have stable diffs.
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl Default for DiagnosticBaseline {
+    fn default() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            entries: vec![],
+        }
+    }
+}
+
+type EntryId = (KeySymbol, KeySymbol, &'static str);
+
+impl DiagnosticBaseline {
+    /// Create a baseline that records every diagnostic in `diagnostics`, which were found by
+    /// validating the messages in `database`.
+    pub fn from_diagnostics(
+        database: &MessagesDatabase,
+        diagnostics: &[MessageDiagnostic],
+    ) -> Self {
+        let mut counts: HashMap<EntryId, usize> = HashMap::new();
+        for diagnostic in diagnostics {
+            *counts.entry(entry_id(diagnostic)).or_default() += 1;
+        }
+        let mut entries: Vec<BaselineEntry> = counts
+            .into_iter()
+            .filter_map(|((key, locale, rule), count)| {
+                Some(BaselineEntry {
+                    key: key.to_string(),
+                    locale: locale.to_string(),
+                    rule: rule.to_string(),
+                    hash: value_hash(database, key, locale)?,
+                    count,
+                })
+            })
+            .collect();
+        entries.sort();
+        Self {
+            version: BASELINE_VERSION,
+            entries,
+        }
+    }
+
+    /// Return only the diagnostics that aren't recorded in this baseline, in their original order.
+    /// Diagnostics in values that changed since the baseline was created are always returned.
+    pub fn filter_new(
+        &self,
+        database: &MessagesDatabase,
+        diagnostics: Vec<MessageDiagnostic>,
+    ) -> Vec<MessageDiagnostic> {
+        let mut remaining: HashMap<(&str, &str, &str), (&str, usize)> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    (
+                        entry.key.as_str(),
+                        entry.locale.as_str(),
+                        entry.rule.as_str(),
+                    ),
+                    (entry.hash.as_str(), entry.count),
+                )
+            })
+            .collect();
+        diagnostics
+            .into_iter()
+            .filter(|diagnostic| {
+                let (key, locale, rule) = entry_id(diagnostic);
+                let Some((hash, count)) = remaining.get_mut(&(key.as_str(), locale.as_str(), rule))
+                else {
+                    return true;
+                };
+                if *count == 0 || value_hash(database, key, locale).as_deref() != Some(*hash) {
+                    return true;
+                }
+                *count -= 1;
+                false
+            })
+            .collect()
+    }
+}
+
+fn entry_id(diagnostic: &MessageDiagnostic) -> EntryId {
+    (diagnostic.key, diagnostic.locale, diagnostic.name.as_str())
+}
+
+/// Hash the value of message `key` in `locale` together with its source value, since diagnostics
+/// in translations usually come from comparing the two.
+fn value_hash(database: &MessagesDatabase, key: KeySymbol, locale: KeySymbol) -> Option<String> {
+    let message = database.messages.get(&key)?;
+    let value = message.translations().get(&locale)?;
+    let mut hasher = Xxh64::new(0);
+    for content in [
+        message
+            .get_source_translation()
+            .map_or("", |source| source.raw.as_str()),
+        value.raw.as_str(),
+    ] {
+        hasher.update(content.as_bytes());
+        hasher.update(&[0]);
+    }
+    Some(format!("{:016x}", hasher.digest()))
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, FilePosition, MessageMeta, MessageValue, DEFAULT_LOCALE};
+
+    use super::*;
+    use crate::diagnostic::DiagnosticName;
+    use crate::DiagnosticSeverity;
+
+    fn database(translation: &str) -> MessagesDatabase {
+        let mut database = MessagesDatabase::new();
+        database
+            .insert_definition(
+                "GREETING",
+                MessageValue::from_raw("Hello, {name}!"),
+                key_symbol(DEFAULT_LOCALE),
+                MessageMeta::default(),
+                false,
+            )
+            .unwrap();
+        database
+            .insert_translation(
+                key_symbol("GREETING"),
+                key_symbol("fr"),
+                MessageValue::from_raw(translation),
+                false,
+            )
+            .unwrap();
+        database
+    }
+
+    fn diagnostic(name: DiagnosticName) -> MessageDiagnostic {
+        MessageDiagnostic {
+            key: key_symbol("GREETING"),
+            file_position: FilePosition {
+                file: key_symbol("fr.messages.json"),
+                line: 1,
+                col: 0,
+            },
+            locale: key_symbol("fr"),
+            name,
+            span: None,
+            severity: DiagnosticSeverity::Warning,
+            description: String::new(),
+            help: None,
+        }
+    }
+
+    fn names(diagnostics: &[MessageDiagnostic]) -> Vec<DiagnosticName> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_recorded_diagnostics_are_filtered() {
+        let database = database("Bonjour , {name} !");
+        let whitespace = || diagnostic(DiagnosticName::NoTrimmableWhitespace);
+        let baseline = DiagnosticBaseline::from_diagnostics(
+            &database,
+            &[
+                whitespace(),
+                whitespace(),
+                diagnostic(DiagnosticName::NoMismatchedPunctuation),
+            ],
+        );
+        assert_eq!(baseline.entries.len(), 2);
+        assert_eq!(baseline.entries[0].rule, "NoMismatchedPunctuation");
+        assert_eq!(baseline.entries[1].count, 2);
+
+        let new = baseline.filter_new(
+            &database,
+            vec![
+                whitespace(),
+                diagnostic(DiagnosticName::NoUntranslatedValues),
+                whitespace(),
+                whitespace(),
+            ],
+        );
+        // Another instance of a recorded rule is still new.
+        assert_eq!(
+            names(&new),
+            vec![
+                DiagnosticName::NoUntranslatedValues,
+                DiagnosticName::NoTrimmableWhitespace
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_values_are_not_covered() {
+        let whitespace = || diagnostic(DiagnosticName::NoTrimmableWhitespace);
+        let baseline =
+            DiagnosticBaseline::from_diagnostics(&database("Bonjour , {name} !"), &[whitespace()]);
+        let new = baseline.filter_new(&database("Bonjour ,  {name} !"), vec![whitespace()]);
+        assert_eq!(names(&new), vec![DiagnosticName::NoTrimmableWhitespace]);
+    }
+}
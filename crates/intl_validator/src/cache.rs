@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use xxhash_rust::xxh64::Xxh64;
+
+use intl_database_core::{KeySymbol, Message, MessagesDatabase};
+
+use crate::diagnostic::ValueDiagnostic;
+use crate::ValueValidatorConfig;
+
+/// Diagnostics from earlier validations of each translation, keyed by message and locale.
+///
+/// Most messages don't change between validations, so only the translations whose content changed
+/// have to be validated again. Each entry is only reused while the hash of everything that the
+/// validators read from the translation, its source, and its message still matches.
+///
+/// Diagnostics are kept without their positions in the file, which are added again from the
+/// current value on every validation, so moving a message within its file doesn't invalidate it.
+#[derive(Debug, Default)]
+pub struct ValidationCache {
+    /// The configuration that every cached diagnostic was found with.
+    config: Option<ValueValidatorConfig>,
+    entries: HashMap<(KeySymbol, KeySymbol), CacheEntry>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    hash: u64,
+    diagnostics: Vec<ValueDiagnostic>,
+}
+
+impl ValidationCache {
+    /// Run `operation` with the cache stored in `database` for validating with `config`, creating
+    /// it the first time. If the cache was filled using a different configuration, it's cleared
+    /// first, since any of its diagnostics might be different now.
+    pub fn with_database<T>(
+        database: &MessagesDatabase,
+        config: &ValueValidatorConfig,
+        operation: impl FnOnce(&mut ValidationCache) -> T,
+    ) -> T {
+        // A poisoned lock only means that another validation panicked. Its entries were either
+        // fully written or not written at all, so the cache can still be used as-is.
+        let mut slot = database
+            .validation_cache
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let cache = slot
+            .get_or_insert_with(|| Box::new(ValidationCache::default()))
+            .downcast_mut::<ValidationCache>()
+            .expect("The database validation cache can only hold a ValidationCache");
+        if cache.config.as_ref() != Some(config) {
+            cache.clear();
+            cache.config = Some(config.clone());
+        }
+        operation(cache)
+    }
+
+    /// Remove every cached diagnostic from the cache stored in `database`, so that the next
+    /// validation checks every message again.
+    pub fn clear_database(database: &MessagesDatabase) {
+        *database
+            .validation_cache
+            .lock()
+            .unwrap_or_else(|error| error.into_inner()) = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.config = None;
+        self.entries.clear();
+    }
+
+    /// Return the cached diagnostics for the translation of `message` in `locale`, or run
+    /// `validate` and cache its result if the translation changed since it was last validated.
+    pub(crate) fn get_or_validate(
+        &mut self,
+        message: &Message,
+        locale: KeySymbol,
+        validate: impl FnOnce() -> Vec<ValueDiagnostic>,
+    ) -> Vec<ValueDiagnostic> {
+        let hash = translation_hash(message, locale);
+        if let Some(entry) = self.entries.get(&(message.key(), locale)) {
+            if entry.hash == hash {
+                return entry.diagnostics.clone();
+            }
+        }
+
+        let diagnostics = validate();
+        self.entries.insert(
+            (message.key(), locale),
+            CacheEntry {
+                hash,
+                diagnostics: diagnostics.clone(),
+            },
+        );
+        diagnostics
+    }
+}
+
+/// Hash everything that validators read when checking the translation of `message` in `locale`:
+/// its content, review state and the version of the source it was written for, the source content
+/// and locale, the content hash of the message, the markdown strictness, and the declared variable
+/// types.
+fn translation_hash(message: &Message, locale: KeySymbol) -> u64 {
+    let translation = message.translations().get(&locale);
+    let source = message.get_source_translation();
+    let mut hasher = Xxh64::new(0);
+    for field in [
+        translation.map_or("", |value| value.raw.as_str()),
+        translation.map_or("", |value| value.review_state.as_str()),
+        translation
+            .and_then(|value| value.source_hash.as_deref())
+            .unwrap_or_default(),
+        source.map_or("", |value| value.raw.as_str()),
+        message.source_locale().as_deref().unwrap_or_default(),
+        message.content_hash().unwrap_or_default(),
+        message.meta().strictness.as_str(),
+    ] {
+        hasher.update(field.as_bytes());
+        hasher.update(&[0]);
+    }
+    for (name, kind) in &message.meta().variables {
+        for field in [name.as_str(), kind.kind_name()] {
+            hasher.update(field.as_bytes());
+            hasher.update(&[0]);
+        }
+    }
+    hasher.digest()
+}
@@ -1,24 +1,123 @@
 use intl_database_core::MessageValue;
 
+use crate::custom::CustomValidators;
 use crate::diagnostic::ValueDiagnostic;
+use crate::timings::RuleTimings;
 use crate::validators;
 use crate::validators::validator::Validator;
+use crate::validators::{
+    NoConcatenatedFragmentsOptions, NoExcessiveComplexityOptions, NoInconsistentCjkSpacingOptions,
+    NoInvalidVariableNamesOptions, NoMismatchedPluralFormsOptions, NoStyleViolationsOptions,
+    NoUnknownHookNamesOptions,
+};
+
+/// Configuration for the validators that support customizing their behavior. Validators without
+/// any configuration always run with their default behavior.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValueValidatorConfig {
+    pub variable_names: NoInvalidVariableNamesOptions,
+    pub hook_names: NoUnknownHookNamesOptions,
+    pub complexity: NoExcessiveComplexityOptions,
+    pub fragments: NoConcatenatedFragmentsOptions,
+    pub plural_forms: NoMismatchedPluralFormsOptions,
+    pub cjk_spacing: NoInconsistentCjkSpacingOptions,
+    pub style_rules: NoStyleViolationsOptions,
+    pub custom_validators: CustomValidators,
+}
+
+impl ValueValidatorConfig {
+    pub fn with_variable_names(mut self, variable_names: NoInvalidVariableNamesOptions) -> Self {
+        self.variable_names = variable_names;
+        self
+    }
+
+    pub fn with_hook_names(mut self, hook_names: NoUnknownHookNamesOptions) -> Self {
+        self.hook_names = hook_names;
+        self
+    }
+
+    pub fn with_complexity(mut self, complexity: NoExcessiveComplexityOptions) -> Self {
+        self.complexity = complexity;
+        self
+    }
+
+    pub fn with_fragments(mut self, fragments: NoConcatenatedFragmentsOptions) -> Self {
+        self.fragments = fragments;
+        self
+    }
+
+    pub fn with_plural_forms(mut self, plural_forms: NoMismatchedPluralFormsOptions) -> Self {
+        self.plural_forms = plural_forms;
+        self
+    }
+
+    pub fn with_cjk_spacing(mut self, cjk_spacing: NoInconsistentCjkSpacingOptions) -> Self {
+        self.cjk_spacing = cjk_spacing;
+        self
+    }
+
+    pub fn with_style_rules(mut self, style_rules: NoStyleViolationsOptions) -> Self {
+        self.style_rules = style_rules;
+        self
+    }
+
+    pub fn with_custom_validators(mut self, custom_validators: CustomValidators) -> Self {
+        self.custom_validators = custom_validators;
+        self
+    }
+}
 
 pub fn validate_message_value(message: &MessageValue) -> Vec<ValueDiagnostic> {
+    validate_message_value_with_config(message, &ValueValidatorConfig::default())
+}
+
+pub fn validate_message_value_with_config(
+    message: &MessageValue,
+    config: &ValueValidatorConfig,
+) -> Vec<ValueDiagnostic> {
+    validate_message_value_with_timings(message, config, &mut RuleTimings::disabled())
+}
+
+/// Validate `message` like [validate_message_value_with_config], recording how long each
+/// validator took in `timings`.
+pub(crate) fn validate_message_value_with_timings(
+    message: &MessageValue,
+    config: &ValueValidatorConfig,
+    timings: &mut RuleTimings,
+) -> Vec<ValueDiagnostic> {
     let mut diagnostics: Vec<ValueDiagnostic> = vec![];
     let mut validators: Vec<Box<dyn Validator>> = vec![
+        Box::new(validators::NoSyntaxErrors::new()),
         Box::new(validators::NoUnicodeVariableNames::new()),
+        Box::new(validators::NoInvalidVariableNames::with_options(
+            config.variable_names.clone(),
+        )),
         Box::new(validators::NoRepeatedPluralNames::new()),
         Box::new(validators::NoRepeatedPluralOptions::new()),
+        Box::new(validators::NoStrayPoundSigns::new()),
         Box::new(validators::NoTrimmableWhitespace::new()),
+        Box::new(validators::NoProblematicUnicode::new()),
+        Box::new(validators::NoSuspiciousApostrophes::new()),
+        Box::new(validators::NoUnknownHookNames::with_options(
+            config.hook_names.clone(),
+        )),
+        Box::new(validators::NoExcessiveComplexity::with_options(
+            config.complexity.clone(),
+        )),
+        Box::new(validators::NoUnreviewedMachineTranslations::new()),
+        Box::new(validators::NoConcatenatedFragments::with_options(
+            config.fragments.clone(),
+        )),
     ];
     for validator in validators.iter_mut() {
-        if let Some(result) = validator.validate_raw(message) {
-            diagnostics.extend(result);
-        }
-        if let Some(result) = validator.validate_ast(message) {
-            diagnostics.extend(result);
-        }
+        timings.time(validator.name(), || {
+            if let Some(result) = validator.validate_raw(message) {
+                diagnostics.extend(result);
+            }
+            if let Some(result) = validator.validate_ast(message) {
+                diagnostics.extend(result);
+            }
+        });
     }
 
     diagnostics
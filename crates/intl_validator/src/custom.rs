@@ -0,0 +1,113 @@
+//! Custom validators let consumers add their own checks at runtime, like product-specific rules
+//! about wording or placeholders, without changing this crate. They run alongside the built-in
+//! validators on every translation, and their diagnostics are reported under their own names.
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use intl_database_core::{key_symbol, KeySymbol, MessageValue};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// The translation that a [CustomValidator] is checking, along with the source message it was
+/// translated from, for validators that compare the two.
+pub struct CustomValidatorInput<'a> {
+    pub key: KeySymbol,
+    pub locale: KeySymbol,
+    pub translation: &'a MessageValue,
+    /// The source message. This is the same as `translation` when checking the source itself.
+    pub source: &'a MessageValue,
+    pub is_source: bool,
+}
+
+/// A problem found by a [CustomValidator], which is reported with the validator's name.
+#[derive(Clone, Debug)]
+pub struct CustomDiagnostic {
+    /// Byte offset within the raw content of the translation where the diagnostic applies.
+    pub span: Option<usize>,
+    pub severity: DiagnosticSeverity,
+    pub description: String,
+    pub help: Option<String>,
+}
+
+/// A validator provided by a consumer of the crate rather than built in.
+pub trait CustomValidator: Send + Sync {
+    /// The name that diagnostics from this validator are reported with, like `NoBrandNameChanges`.
+    fn name(&self) -> &str;
+
+    fn validate(&self, input: &CustomValidatorInput) -> Vec<CustomDiagnostic>;
+}
+
+/// The set of custom validators to run, in the order they were added.
+///
+/// Two sets are only equal when they contain the same validator instances, so that cached
+/// diagnostics are cleared whenever the validators change.
+#[derive(Clone, Default)]
+pub struct CustomValidators {
+    validators: Vec<Arc<dyn CustomValidator>>,
+}
+
+impl CustomValidators {
+    pub fn with_validator(mut self, validator: Arc<dyn CustomValidator>) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Remove every validator with the given `name`, returning true if there were any.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.validators.len();
+        self.validators.retain(|validator| validator.name() != name);
+        self.validators.len() != count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn CustomValidator>> {
+        self.validators.iter()
+    }
+}
+
+impl Debug for CustomValidators {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.validators.iter().map(|validator| validator.name()))
+            .finish()
+    }
+}
+
+impl PartialEq for CustomValidators {
+    fn eq(&self, other: &Self) -> bool {
+        self.validators.len() == other.validators.len()
+            && self
+                .validators
+                .iter()
+                .zip(&other.validators)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+/// The name that diagnostics from `validator` are reported with.
+pub(crate) fn custom_diagnostic_name(validator: &dyn CustomValidator) -> DiagnosticName {
+    DiagnosticName::Custom(key_symbol(validator.name()))
+}
+
+/// Run `validator` on `input`, returning its diagnostics under the name of the validator.
+pub(crate) fn validate_custom(
+    validator: &dyn CustomValidator,
+    input: &CustomValidatorInput,
+) -> Vec<ValueDiagnostic> {
+    let name = custom_diagnostic_name(validator);
+    validator
+        .validate(input)
+        .into_iter()
+        .map(|diagnostic| ValueDiagnostic {
+            name,
+            span: diagnostic.span,
+            severity: diagnostic.severity,
+            description: diagnostic.description,
+            help: diagnostic.help,
+        })
+        .collect()
+}
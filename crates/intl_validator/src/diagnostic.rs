@@ -1,27 +1,85 @@
-use intl_database_core::{FilePosition, KeySymbol};
+use intl_database_core::{FilePosition, KeySymbol, MessageValue};
 
+use crate::span::{DiagnosticSpan, LineIndex};
 use crate::DiagnosticSeverity;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum DiagnosticName {
+    NoConcatenatedFragments,
+    NoCrossedTags,
+    NoDisallowedMarkdown,
+    NoExcessiveComplexity,
     NoExtraTranslationVariables,
+    NoHardcodedNumbers,
+    NoInconsistentCjkSpacing,
+    NoInvalidMessageReferences,
+    NoInvalidVariableNames,
+    NoMismatchedEmoji,
+    NoMismatchedKeyboardKeys,
+    NoMismatchedLinkDestinations,
+    NoMismatchedMarkdown,
+    NoMismatchedPluralForms,
+    NoMismatchedPunctuation,
+    NoMismatchedUrls,
+    NoMismatchedVariableTypes,
+    NoMisspelledWords,
     NoMissingSourceVariables,
+    NoOutdatedTranslations,
+    NoProblematicUnicode,
+    NoRelocatedPlaceholders,
     NoRepeatedPluralNames,
     NoRepeatedPluralOptions,
+    NoStrayPoundSigns,
+    NoStyleViolations,
+    NoSuspiciousApostrophes,
+    NoSyntaxErrors,
     NoTrimmableWhitespace,
     NoUnicodeVariableNames,
+    NoUnknownHookNames,
+    NoUnreviewedMachineTranslations,
+    NoUntranslatedValues,
+    /// A diagnostic from a [crate::CustomValidator], with the name that the validator reports.
+    Custom(KeySymbol),
 }
 
 impl DiagnosticName {
     pub fn as_str(&self) -> &'static str {
         match self {
+            DiagnosticName::NoConcatenatedFragments => "NoConcatenatedFragments",
+            DiagnosticName::NoCrossedTags => "NoCrossedTags",
+            DiagnosticName::NoDisallowedMarkdown => "NoDisallowedMarkdown",
+            DiagnosticName::NoExcessiveComplexity => "NoExcessiveComplexity",
             DiagnosticName::NoExtraTranslationVariables => "NoExtraTranslationVariables",
+            DiagnosticName::NoHardcodedNumbers => "NoHardcodedNumbers",
+            DiagnosticName::NoInconsistentCjkSpacing => "NoInconsistentCjkSpacing",
+            DiagnosticName::NoInvalidMessageReferences => "NoInvalidMessageReferences",
+            DiagnosticName::NoInvalidVariableNames => "NoInvalidVariableNames",
+            DiagnosticName::NoMismatchedEmoji => "NoMismatchedEmoji",
+            DiagnosticName::NoMismatchedKeyboardKeys => "NoMismatchedKeyboardKeys",
+            DiagnosticName::NoMismatchedLinkDestinations => "NoMismatchedLinkDestinations",
+            DiagnosticName::NoMismatchedMarkdown => "NoMismatchedMarkdown",
+            DiagnosticName::NoMismatchedPluralForms => "NoMismatchedPluralForms",
+            DiagnosticName::NoMismatchedPunctuation => "NoMismatchedPunctuation",
+            DiagnosticName::NoMismatchedUrls => "NoMismatchedUrls",
+            DiagnosticName::NoMismatchedVariableTypes => "NoMismatchedVariableTypes",
+            DiagnosticName::NoMisspelledWords => "NoMisspelledWords",
             DiagnosticName::NoMissingSourceVariables => "NoMissingSourceVariables",
+            DiagnosticName::NoOutdatedTranslations => "NoOutdatedTranslations",
+            DiagnosticName::NoProblematicUnicode => "NoProblematicUnicode",
+            DiagnosticName::NoRelocatedPlaceholders => "NoRelocatedPlaceholders",
             DiagnosticName::NoRepeatedPluralNames => "NoRepeatedPluralNames",
             DiagnosticName::NoRepeatedPluralOptions => "NoRepeatedPluralOptions",
+            DiagnosticName::NoStrayPoundSigns => "NoStrayPoundSigns",
+            DiagnosticName::NoStyleViolations => "NoStyleViolations",
+            DiagnosticName::NoSuspiciousApostrophes => "NoSuspiciousApostrophes",
+            DiagnosticName::NoSyntaxErrors => "NoSyntaxErrors",
             DiagnosticName::NoTrimmableWhitespace => "NoTrimmableWhitespace",
             DiagnosticName::NoUnicodeVariableNames => "NoUnicodeVariableNames",
+            DiagnosticName::NoUnknownHookNames => "NoUnknownHookNames",
+            DiagnosticName::NoUnreviewedMachineTranslations => "NoUnreviewedMachineTranslations",
+            DiagnosticName::NoUntranslatedValues => "NoUntranslatedValues",
+            DiagnosticName::Custom(name) => name.as_str(),
         }
     }
 }
@@ -37,6 +95,9 @@ pub struct MessageDiagnostic {
     pub file_position: FilePosition,
     pub locale: KeySymbol,
     pub name: DiagnosticName,
+    /// Position within the message value where the diagnostic applies, if it applies to a
+    /// specific position rather than the message as a whole.
+    pub span: Option<DiagnosticSpan>,
     pub severity: DiagnosticSeverity,
     pub description: String,
     pub help: Option<String>,
@@ -45,12 +106,59 @@ pub struct MessageDiagnostic {
 #[derive(Debug, Clone)]
 pub struct ValueDiagnostic {
     pub name: DiagnosticName,
+    /// Byte offset within the message value where the diagnostic applies.
     pub span: Option<usize>,
     pub severity: DiagnosticSeverity,
     pub description: String,
     pub help: Option<String>,
 }
 
+/// Returns the position in the source file of the character at `span` within `value`. Spans in
+/// content that was expanded from a fragment point at the value of that fragment, and spans in
+/// values joined from several strings point at the literal they are in. Otherwise, only values
+/// whose source recorded the escapes it decoded can be mapped exactly. All others just point at
+/// the start of the value.
+fn get_span_file_position(
+    value: &MessageValue,
+    value_position: FilePosition,
+    span: &DiagnosticSpan,
+) -> FilePosition {
+    if let Some(expansion) = value
+        .fragment_expansions
+        .as_ref()
+        .and_then(|expansions| expansions.expansion_at(span.byte_offset))
+    {
+        return FilePosition {
+            line: expansion.position.line,
+            col: expansion.position.col,
+            ..value_position
+        };
+    }
+    // Values joined from several strings are only mapped to the literal that the span is in.
+    if let Some(part) = value
+        .string_parts
+        .as_ref()
+        .and_then(|parts| parts.part_at(span.byte_offset))
+    {
+        return FilePosition {
+            line: part.position.line,
+            col: part.position.col,
+            ..value_position
+        };
+    }
+    let Some(source_offsets) = &value.source_offsets else {
+        return value_position;
+    };
+    // The value position is the opening quote of the string, and offsets are relative to the
+    // content that follows it. Strings with escapes can't contain literal newlines, so the whole
+    // value is always on the same line.
+    let column = source_offsets.to_source_column(&value.raw, span.byte_offset) as u32;
+    FilePosition {
+        col: value_position.col + 1 + column,
+        ..value_position
+    }
+}
+
 pub struct MessageDiagnosticsBuilder {
     pub diagnostics: Vec<MessageDiagnostic>,
     pub key: KeySymbol,
@@ -64,28 +172,34 @@ impl MessageDiagnosticsBuilder {
         }
     }
 
-    pub fn add(&mut self, diagnostic: MessageDiagnostic) {
-        self.diagnostics.push(diagnostic);
-    }
-
     pub fn extend_from_value_diagnostics(
         &mut self,
         value_diagnostics: Vec<ValueDiagnostic>,
-        file_position: FilePosition,
+        value: &MessageValue,
         locale: KeySymbol,
     ) {
-        let converted_diagnostics =
-            value_diagnostics
-                .into_iter()
-                .map(|diagnostic| MessageDiagnostic {
-                    key: self.key,
-                    file_position,
-                    locale,
-                    name: diagnostic.name,
-                    severity: diagnostic.severity,
-                    description: diagnostic.description,
-                    help: diagnostic.help,
-                });
+        if value_diagnostics.is_empty() {
+            return;
+        }
+
+        // SAFETY: Only values that have been processed from a file are validated.
+        let file_position = value.file_position.unwrap();
+        let line_index = LineIndex::new(&value.raw);
+        let converted_diagnostics = value_diagnostics.into_iter().map(|diagnostic| {
+            let span = diagnostic.span.map(|offset| line_index.span_at(offset));
+            MessageDiagnostic {
+                key: self.key,
+                file_position: span.map_or(file_position, |span| {
+                    get_span_file_position(value, file_position, &span)
+                }),
+                locale,
+                name: diagnostic.name,
+                span,
+                severity: diagnostic.severity,
+                description: diagnostic.description,
+                help: diagnostic.help,
+            }
+        });
 
         self.diagnostics.extend(converted_diagnostics);
     }
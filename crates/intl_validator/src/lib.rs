@@ -1,13 +1,39 @@
-use intl_database_core::Message;
+use intl_database_core::{KeySymbol, Message, MessageValue, MessagesDatabase};
 
-pub use crate::content::validate_message_value;
+pub use crate::baseline::{BaselineEntry, DiagnosticBaseline};
+pub use crate::cache::ValidationCache;
+use crate::content::validate_message_value_with_timings;
+pub use crate::content::{
+    validate_message_value, validate_message_value_with_config, ValueValidatorConfig,
+};
+pub use crate::custom::{
+    CustomDiagnostic, CustomValidator, CustomValidatorInput, CustomValidators,
+};
 pub use crate::diagnostic::MessageDiagnostic;
-use crate::diagnostic::{DiagnosticName, MessageDiagnosticsBuilder};
+use crate::diagnostic::{DiagnosticName, MessageDiagnosticsBuilder, ValueDiagnostic};
+pub use crate::sarif::{to_sarif_log, SarifLog};
 pub use crate::severity::DiagnosticSeverity;
+pub use crate::span::{DiagnosticSpan, LineIndex};
+pub use crate::spelling::{collect_message_words, MessageWord, MisspelledWords, SpellChecker};
+pub use crate::summary::{SeverityCounts, ValidationSummary};
+pub use crate::timings::RuleTimings;
+pub use crate::validators::{
+    CjkPlaceholderSpacing, NoConcatenatedFragmentsOptions, NoExcessiveComplexityOptions,
+    NoInconsistentCjkSpacingOptions, NoInvalidVariableNamesOptions, NoMismatchedPluralFormsOptions,
+    NoStyleViolationsOptions, NoUnknownHookNamesOptions, StyleRule, VariableNameCase,
+};
 
+mod baseline;
+mod cache;
 mod content;
+mod custom;
 mod diagnostic;
+mod sarif;
 mod severity;
+mod span;
+mod spelling;
+mod summary;
+mod timings;
 mod validators;
 
 /// Validate the content of a message across all of its translations, returning
@@ -18,70 +44,230 @@ mod validators;
 /// diagnostics presented from general errors, like invalid syntax or
 /// unsupported syntax.
 pub fn validate_message(message: &Message) -> Vec<MessageDiagnostic> {
-    let Some(source) = message.get_source_translation() else {
-        return vec![];
-    };
+    validate_message_with_config(message, &ValueValidatorConfig::default())
+}
 
-    // SAFETY: If the message has a source translation, it must have a source locale.
-    let source_locale = message.source_locale().unwrap();
-    let mut diagnostics = MessageDiagnosticsBuilder::new(message.key());
+/// Validate the references that a message makes to other messages, like `{@PRODUCT_NAME}`.
+///
+/// Every translation of the message is checked. Unlike other validators, this needs the whole
+/// database to find the referenced messages.
+pub fn validate_message_references(
+    database: &MessagesDatabase,
+    message: &Message,
+) -> Vec<MessageDiagnostic> {
+    validate_message_references_with_timings(database, message, &mut RuleTimings::disabled())
+}
 
-    let source_variables = &source.variables;
-    let source_has_variables = source_variables
-        .as_ref()
-        .is_some_and(|variables| variables.count() > 0);
+/// Validate the references that a message makes like [validate_message_references], recording
+/// how long it took for each translation in `timings`.
+pub fn validate_message_references_with_timings(
+    database: &MessagesDatabase,
+    message: &Message,
+    timings: &mut RuleTimings,
+) -> Vec<MessageDiagnostic> {
+    let mut diagnostics = MessageDiagnosticsBuilder::new(message.key());
+    for (locale, translation) in message.translations() {
+        diagnostics.extend_from_value_diagnostics(
+            timings.time(DiagnosticName::NoInvalidMessageReferences, || {
+                validators::validate_message_references(
+                    database,
+                    message.key(),
+                    translation,
+                    *locale,
+                )
+            }),
+            translation,
+            *locale,
+        );
+    }
+    diagnostics.diagnostics
+}
 
+/// Check the spelling of every translation of a message with `checker`, which is kept separate
+/// from the other validators since spell checkers are provided by the caller.
+pub fn validate_message_spelling(
+    message: &Message,
+    checker: &dyn SpellChecker,
+) -> Vec<MessageDiagnostic> {
+    let mut diagnostics = MessageDiagnosticsBuilder::new(message.key());
     for (locale, translation) in message.translations() {
         diagnostics.extend_from_value_diagnostics(
-            validate_message_value(translation),
-            translation.file_position.unwrap(),
+            spelling::validate_spelling(checker, translation, *locale),
+            translation,
             *locale,
         );
-        if *locale == source_locale {
-            continue;
-        }
+    }
+    diagnostics.diagnostics
+}
 
-        let _translation_variables = match &translation.variables {
-            // If the translation contains variables but the source does not,
-            // it's likely unintended (the only time this should reasonably
-            // happen is when translations are out-of-date, which should be
-            // fixed automatically once the translations are imported again).
-            Some(translation_variables)
-                if !source_has_variables && translation_variables.count() > 0 =>
-            {
-                diagnostics.add(MessageDiagnostic {
-                        key: message.key(),
-                        file_position: translation.file_position.unwrap(),
-                        locale: locale.clone(),
-                        name: DiagnosticName::NoExtraTranslationVariables,
-                        severity: DiagnosticSeverity::Warning,
-                        description: "Translation includes variables, but the source message does not"
-                            .into(),
-                        help: Some("This is okay, but likely unintentional. Check that the source message is defined as expected.".into())
-                    });
-                continue;
-            }
+/// Validate the content of a message across all of its translations, like
+/// [validate_message], using the given `config` for validators that support
+/// customizing their behavior.
+pub fn validate_message_with_config(
+    message: &Message,
+    config: &ValueValidatorConfig,
+) -> Vec<MessageDiagnostic> {
+    validate_message_with_timings(message, config, &mut RuleTimings::disabled())
+}
 
-            Some(translated_variables) => translated_variables,
-            // If the translation has no variables, but the source does, this
-            // also likely not intentional, but still won't break things.
-            _ => {
-                if source_has_variables {
-                    diagnostics.add(MessageDiagnostic {
-                        key: message.key(),
-                        file_position: translation.file_position.unwrap(),
-                        locale: locale.clone(),
-                        name: DiagnosticName::NoMissingSourceVariables,
-                        severity: DiagnosticSeverity::Warning,
-                        description: "Source message includes variables, but this translation has none.".into(),
-                        help: Some("This is okay, but likely unintentional. Check that the source message is defined as expected.".into())
-                    });
-                }
+/// Validate the content of a message like [validate_message_with_config], recording how long
+/// each rule took on each of its translations in `timings`.
+pub fn validate_message_with_timings(
+    message: &Message,
+    config: &ValueValidatorConfig,
+    timings: &mut RuleTimings,
+) -> Vec<MessageDiagnostic> {
+    validate_message_with_cache(message, config, timings, None)
+}
 
-                continue;
-            }
+/// Validate the content of a message like [validate_message_with_timings], using `cache`.
+///
+/// Diagnostics in `cache` are reused for any translation that hasn't changed since it was last
+/// validated. The cache must only be used with a single `config`, which
+/// [ValidationCache::with_database] makes sure of.
+pub fn validate_message_with_cache(
+    message: &Message,
+    config: &ValueValidatorConfig,
+    timings: &mut RuleTimings,
+    mut cache: Option<&mut ValidationCache>,
+) -> Vec<MessageDiagnostic> {
+    let Some(source) = message.get_source_translation() else {
+        return vec![];
+    };
+
+    let mut diagnostics = MessageDiagnosticsBuilder::new(message.key());
+    for (locale, translation) in message.translations() {
+        let mut validate =
+            || validate_translation(message, source, *locale, translation, config, timings);
+        let value_diagnostics = match cache.as_deref_mut() {
+            Some(cache) => cache.get_or_validate(message, *locale, validate),
+            None => validate(),
         };
+        diagnostics.extend_from_value_diagnostics(value_diagnostics, translation, *locale);
     }
 
     diagnostics.diagnostics
 }
+
+/// Run every validator on the translation of `message` in `locale`, which may be the source.
+fn validate_translation(
+    message: &Message,
+    source: &MessageValue,
+    locale: KeySymbol,
+    translation: &MessageValue,
+    config: &ValueValidatorConfig,
+    timings: &mut RuleTimings,
+) -> Vec<ValueDiagnostic> {
+    let mut diagnostics = validate_message_value_with_timings(translation, config, timings);
+    diagnostics.extend(timings.time(DiagnosticName::NoMismatchedPluralForms, || {
+        validators::validate_plural_forms(translation, &locale, &config.plural_forms)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoInconsistentCjkSpacing, || {
+        validators::validate_cjk_spacing(translation, &locale, &config.cjk_spacing)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoStyleViolations, || {
+        validators::validate_style_rules(message.key(), translation, &locale, &config.style_rules)
+    }));
+    let is_source = *message.source_locale() == Some(locale);
+    for validator in config.custom_validators.iter() {
+        let input = CustomValidatorInput {
+            key: message.key(),
+            locale,
+            translation,
+            source,
+            is_source,
+        };
+        let name = custom::custom_diagnostic_name(validator.as_ref());
+        diagnostics
+            .extend(timings.time(name, || custom::validate_custom(validator.as_ref(), &input)));
+    }
+    diagnostics.extend(timings.time(DiagnosticName::NoDisallowedMarkdown, || {
+        validators::validate_markdown_strictness(translation, message.meta().strictness)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoMismatchedVariableTypes, || {
+        validators::validate_declared_variable_types(translation, &message.meta().variables)
+    }));
+    if is_source {
+        diagnostics.extend(timings.time(DiagnosticName::NoHardcodedNumbers, || {
+            validators::validate_hardcoded_counts(translation)
+        }));
+        diagnostics.extend(timings.time(DiagnosticName::NoMismatchedVariableTypes, || {
+            validators::validate_unused_variable_types(translation, &message.meta().variables)
+        }));
+        return diagnostics;
+    }
+
+    diagnostics.extend(timings.time(DiagnosticName::NoCrossedTags, || {
+        validators::validate_tag_nesting(source, translation)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoMismatchedMarkdown, || {
+        validators::validate_markdown_structure(source, translation)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoMismatchedUrls, || {
+        validators::validate_bare_urls(source, translation)
+    }));
+    diagnostics.extend(
+        timings.time(DiagnosticName::NoMismatchedLinkDestinations, || {
+            validators::validate_link_destinations(source, translation)
+        }),
+    );
+    diagnostics.extend(timings.time(DiagnosticName::NoRelocatedPlaceholders, || {
+        validators::validate_placeholder_containers(source, translation)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoMismatchedEmoji, || {
+        validators::validate_emoji_shortcodes(source, translation)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoMismatchedKeyboardKeys, || {
+        validators::validate_keyboard_keys(source, translation)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoMismatchedPunctuation, || {
+        validators::validate_edge_punctuation(source, translation, &locale)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoHardcodedNumbers, || {
+        validators::validate_number_separators(translation)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoUntranslatedValues, || {
+        validators::validate_untranslated_value(source, translation)
+    }));
+    diagnostics.extend(timings.time(DiagnosticName::NoOutdatedTranslations, || {
+        validators::validate_outdated_translation(message, locale)
+    }));
+
+    let source_has_variables = source
+        .variables()
+        .is_some_and(|variables| variables.count() > 0);
+    match translation.variables() {
+        // If the translation contains variables but the source does not,
+        // it's likely unintended (the only time this should reasonably
+        // happen is when translations are out-of-date, which should be
+        // fixed automatically once the translations are imported again).
+        Some(translation_variables)
+            if !source_has_variables && translation_variables.count() > 0 =>
+        {
+            diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoExtraTranslationVariables,
+                span: None,
+                severity: DiagnosticSeverity::Warning,
+                description: "Translation includes variables, but the source message does not"
+                    .into(),
+                help: Some("This is okay, but likely unintentional. Check that the source message is defined as expected.".into())
+            });
+        }
+        Some(_) => {}
+        // If the translation has no variables, but the source does, this
+        // also likely not intentional, but still won't break things.
+        None => {
+            if source_has_variables {
+                diagnostics.push(ValueDiagnostic {
+                    name: DiagnosticName::NoMissingSourceVariables,
+                    span: None,
+                    severity: DiagnosticSeverity::Warning,
+                    description: "Source message includes variables, but this translation has none.".into(),
+                    help: Some("This is okay, but likely unintentional. Check that the source message is defined as expected.".into())
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
@@ -0,0 +1,292 @@
+//! Conversion of diagnostics into [SARIF 2.1](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html),
+//! the format that code scanning tools in CI consume to show problems inline in pull requests.
+//!
+//! Each [DiagnosticName] becomes a rule of the `discord-intl` tool, and each diagnostic becomes a
+//! result pointing at the definitions or translations file where the problem was found.
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::diagnostic::DiagnosticName;
+use crate::{DiagnosticSeverity, MessageDiagnostic};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_NAME: &str = "discord-intl";
+/// Base id for artifact locations that are made relative to the `root` given to [to_sarif_log].
+const SOURCE_ROOT_BASE_ID: &str = "%SRCROOT%";
+
+#[derive(Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: &'static str,
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: &'static str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+    properties: SarifResultProperties,
+}
+
+#[derive(Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: u32,
+    start_column: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResultProperties {
+    message_key: String,
+    locale: String,
+}
+
+/// Convert `diagnostics` into a SARIF log with a single run.
+///
+/// When `root` is given, files inside of it are written relative to it, which is what most code
+/// scanning tools expect to be able to match results to the files in a pull request. Other files
+/// are written as absolute paths.
+pub fn to_sarif_log(diagnostics: &[MessageDiagnostic], root: Option<&Path>) -> SarifLog {
+    let mut rules: Vec<DiagnosticName> = vec![];
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let rule_index = match rules.iter().position(|rule| *rule == diagnostic.name) {
+                Some(index) => index,
+                None => {
+                    rules.push(diagnostic.name);
+                    rules.len() - 1
+                }
+            };
+            let text = match &diagnostic.help {
+                Some(help) => format!("{}\n{help}", diagnostic.description),
+                None => diagnostic.description.clone(),
+            };
+            SarifResult {
+                rule_id: diagnostic.name.as_str(),
+                rule_index,
+                level: severity_level(diagnostic.severity),
+                message: SarifMessage { text },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: artifact_location(&diagnostic.file_position.file, root),
+                        region: SarifRegion {
+                            start_line: diagnostic.file_position.line,
+                            // SARIF columns are 1-based, while file positions are 0-based.
+                            start_column: diagnostic.file_position.col + 1,
+                        },
+                    },
+                }],
+                properties: SarifResultProperties {
+                    message_key: diagnostic.key.to_string(),
+                    locale: diagnostic.locale.to_string(),
+                },
+            }
+        })
+        .collect();
+
+    SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: TOOL_NAME,
+                    information_uri: "https://github.com/discord/discord-intl",
+                    rules: rules
+                        .into_iter()
+                        .map(|name| SarifRule {
+                            id: name.as_str(),
+                            name: name.as_str(),
+                        })
+                        .collect(),
+                },
+            },
+            results,
+        }],
+    }
+}
+
+/// SARIF only has `error`, `warning`, and `note` levels, so info diagnostics become notes.
+fn severity_level(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info => "note",
+    }
+}
+
+fn artifact_location(file: &str, root: Option<&Path>) -> SarifArtifactLocation {
+    match root.and_then(|root| Path::new(file).strip_prefix(root).ok()) {
+        Some(relative) => SarifArtifactLocation {
+            uri: path_to_uri(relative),
+            uri_base_id: Some(SOURCE_ROOT_BASE_ID),
+        },
+        None => SarifArtifactLocation {
+            uri: path_to_uri(Path::new(file)),
+            uri_base_id: None,
+        },
+    }
+}
+
+/// URIs always use forward slashes, even for Windows paths, and absolute paths need a scheme.
+fn path_to_uri(path: &Path) -> String {
+    let uri = path.to_string_lossy().replace('\\', "/");
+    match path.is_absolute() {
+        true if uri.starts_with('/') => format!("file://{uri}"),
+        true => format!("file:///{uri}"),
+        false => uri,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, FilePosition};
+
+    use super::*;
+
+    fn diagnostic(
+        name: DiagnosticName,
+        file: &str,
+        severity: DiagnosticSeverity,
+    ) -> MessageDiagnostic {
+        MessageDiagnostic {
+            key: key_symbol("GREETING"),
+            file_position: FilePosition {
+                file: key_symbol(file),
+                line: 3,
+                col: 4,
+            },
+            locale: key_symbol("fr"),
+            name,
+            span: None,
+            severity,
+            description: "Something is wrong".into(),
+            help: Some("Fix it".into()),
+        }
+    }
+
+    #[test]
+    fn test_rules_and_results() {
+        let log = to_sarif_log(
+            &[
+                diagnostic(
+                    DiagnosticName::NoTrimmableWhitespace,
+                    "/repo/fr.messages.json",
+                    DiagnosticSeverity::Info,
+                ),
+                diagnostic(
+                    DiagnosticName::NoSyntaxErrors,
+                    "/repo/de.messages.json",
+                    DiagnosticSeverity::Error,
+                ),
+                diagnostic(
+                    DiagnosticName::NoTrimmableWhitespace,
+                    "/repo/de.messages.json",
+                    DiagnosticSeverity::Warning,
+                ),
+            ],
+            None,
+        );
+        let run = &log.runs[0];
+        // Each rule is listed once, in the order it was first reported.
+        let rules: Vec<&str> = run.tool.driver.rules.iter().map(|rule| rule.id).collect();
+        assert_eq!(rules, vec!["NoTrimmableWhitespace", "NoSyntaxErrors"]);
+        let results: Vec<(&str, usize, &str)> = run
+            .results
+            .iter()
+            .map(|result| (result.rule_id, result.rule_index, result.level))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("NoTrimmableWhitespace", 0, "note"),
+                ("NoSyntaxErrors", 1, "error"),
+                ("NoTrimmableWhitespace", 0, "warning"),
+            ]
+        );
+
+        let result = &run.results[0];
+        assert_eq!(result.message.text, "Something is wrong\nFix it");
+        let location = &result.locations[0].physical_location;
+        assert_eq!(location.region.start_line, 3);
+        assert_eq!(location.region.start_column, 5);
+        assert_eq!(
+            location.artifact_location.uri,
+            "file:///repo/fr.messages.json"
+        );
+        assert_eq!(location.artifact_location.uri_base_id, None);
+    }
+
+    #[test]
+    fn test_paths_relative_to_root() {
+        let root = Some(Path::new("/repo"));
+        let inside = artifact_location("/repo/messages/fr.messages.json", root);
+        assert_eq!(inside.uri, "messages/fr.messages.json");
+        assert_eq!(inside.uri_base_id, Some(SOURCE_ROOT_BASE_ID));
+
+        let outside = artifact_location("/elsewhere/fr.messages.json", root);
+        assert_eq!(outside.uri, "file:///elsewhere/fr.messages.json");
+        assert_eq!(outside.uri_base_id, None);
+    }
+}
@@ -2,7 +2,8 @@ use std::fmt::Formatter;
 
 use serde::{Serialize, Serializer};
 
-#[derive(Debug, Clone, Copy)]
+/// Severities are ordered from least to most severe, so that thresholds can be compared directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DiagnosticSeverity {
     Info,
     Warning,
@@ -17,6 +18,15 @@ impl DiagnosticSeverity {
             Self::Error => "error",
         }
     }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "info" => Some(Self::Info),
+            "warning" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
 }
 
 impl Serialize for DiagnosticSeverity {
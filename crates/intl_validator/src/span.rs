@@ -0,0 +1,108 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A position within a message value, in every encoding that consumers of diagnostics need.
+///
+/// Rust works with byte offsets, but JavaScript strings and most editor protocols use UTF-16 code
+/// units, and highlighting for users is best done by grapheme clusters so that a single visible
+/// character is never split apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiagnosticSpan {
+    /// Offset from the start of the value in bytes.
+    pub byte_offset: usize,
+    /// Offset from the start of the value in UTF-16 code units.
+    pub utf16_offset: usize,
+    /// Offset from the start of the value in extended grapheme clusters.
+    pub grapheme_offset: usize,
+    /// Zero-based line within the value.
+    pub line: usize,
+    /// Offset from the start of `line` in UTF-16 code units.
+    pub utf16_column: usize,
+    /// Offset from the start of `line` in extended grapheme clusters.
+    pub grapheme_column: usize,
+}
+
+/// Precomputed line starts for a single message value.
+///
+/// These are used to convert byte offsets from validators into [DiagnosticSpan]s. Messages are
+/// usually a single line, so building this once per value keeps the conversion cheap even when a
+/// value produces many diagnostics.
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    /// Convert the given byte offset into a [DiagnosticSpan]. Offsets past the end of the text
+    /// are clamped to the end, and offsets inside of a multi-byte character are moved back to the
+    /// start of that character.
+    pub fn span_at(&self, byte_offset: usize) -> DiagnosticSpan {
+        let mut byte_offset = byte_offset.min(self.text.len());
+        while !self.text.is_char_boundary(byte_offset) {
+            byte_offset -= 1;
+        }
+
+        // `line_starts` always contains 0, so there is always at least one line before the offset.
+        let line = self
+            .line_starts
+            .partition_point(|start| *start <= byte_offset)
+            - 1;
+        let before = &self.text[..byte_offset];
+        let line_before = &self.text[self.line_starts[line]..byte_offset];
+
+        DiagnosticSpan {
+            byte_offset,
+            utf16_offset: before.encode_utf16().count(),
+            grapheme_offset: before.graphemes(true).count(),
+            line,
+            utf16_column: line_before.encode_utf16().count(),
+            grapheme_column: line_before.graphemes(true).count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_offsets_are_equal() {
+        let span = LineIndex::new("hello {name}").span_at(7);
+        assert_eq!(span.utf16_offset, 7);
+        assert_eq!(span.grapheme_offset, 7);
+        assert_eq!(span.utf16_column, 7);
+    }
+
+    #[test]
+    fn multi_byte_offsets() {
+        // "é" is 2 bytes, 1 code unit. The emoji is 4 bytes and 2 code units, and the flag is two
+        // regional indicators that form a single grapheme.
+        let text = "é😀🇯🇵*";
+        let span = LineIndex::new(text).span_at(text.find('*').unwrap());
+        assert_eq!(span.byte_offset, 14);
+        assert_eq!(span.utf16_offset, 7);
+        assert_eq!(span.grapheme_offset, 3);
+    }
+
+    #[test]
+    fn lines() {
+        let text = "first\nsécond *";
+        let span = LineIndex::new(text).span_at(text.find('*').unwrap());
+        assert_eq!(span.line, 1);
+        assert_eq!(span.utf16_column, 7);
+        assert_eq!(span.grapheme_column, 7);
+    }
+
+    #[test]
+    fn clamps_to_char_boundary() {
+        let span = LineIndex::new("é").span_at(1);
+        assert_eq!(span.byte_offset, 0);
+        assert_eq!(LineIndex::new("abc").span_at(10).byte_offset, 3);
+    }
+}
@@ -0,0 +1,191 @@
+//! Spell checking is left to external spell checkers, like hunspell, since dictionaries are large
+//! and differ for every locale. This provides the words of a message that should be checked, and
+//! the [SpellChecker] trait that reports the misspelled ones back as standard diagnostics.
+//!
+//! Only the visible text of a message is checked. Placeholders, code, urls, link destinations,
+//! emoji, mentions, and keyboard keys are all left out, since they are never spelled like words.
+use std::collections::HashMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use intl_database_core::{KeySymbol, MessageValue};
+use intl_markdown::{
+    find_bare_urls, find_emoji_shortcodes, find_keyboard_keys, CodeBlock, CodeSpan, Emoji,
+    KeyboardKey, LinkDestination, Mention,
+};
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::span::{DiagnosticSpan, LineIndex};
+use crate::DiagnosticSeverity;
+
+/// A single word from the visible text of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageWord {
+    pub word: String,
+    /// Position of the word in the raw content of the message. Text that was unescaped while
+    /// parsing won't match the raw content exactly, and its words are left without a span.
+    pub span: Option<DiagnosticSpan>,
+}
+
+/// Checks the spelling of individual words, as an adapter for an external spell checker.
+pub trait SpellChecker: Sync {
+    /// Returns None if `word` is spelled correctly in `locale`, or if the checker doesn't know the
+    /// locale. Otherwise, returns the suggested replacements for the word, which may be empty.
+    fn check_word(&self, locale: KeySymbol, word: &str) -> Option<Vec<String>>;
+}
+
+/// A [SpellChecker] for words that were already checked elsewhere, like by a spell checker
+/// running in JavaScript, so that its results can be reported as diagnostics.
+#[derive(Debug)]
+pub struct MisspelledWords {
+    locale: KeySymbol,
+    suggestions: HashMap<String, Vec<String>>,
+}
+
+impl MisspelledWords {
+    /// Every word in `misspellings` is reported as misspelled in `locale` with its suggestions.
+    /// All other words and locales are considered to be spelled correctly.
+    pub fn new(
+        locale: KeySymbol,
+        misspellings: impl IntoIterator<Item = (String, Vec<String>)>,
+    ) -> Self {
+        Self {
+            locale,
+            suggestions: misspellings.into_iter().collect(),
+        }
+    }
+}
+
+impl SpellChecker for MisspelledWords {
+    fn check_word(&self, locale: KeySymbol, word: &str) -> Option<Vec<String>> {
+        if locale != self.locale {
+            return None;
+        }
+        self.suggestions.get(word).cloned()
+    }
+}
+
+/// Collects the words of the visible text of a message, in source order.
+struct WordCollector<'a> {
+    raw: &'a str,
+    line_index: LineIndex<'a>,
+    /// The position in `raw` to continue searching for text from, since text is visited in the
+    /// same order it is written.
+    cursor: usize,
+    words: Vec<MessageWord>,
+}
+
+impl Visit for WordCollector<'_> {
+    fn visit_code_block(&mut self, _node: &CodeBlock) {}
+
+    fn visit_code_span(&mut self, _node: &CodeSpan) {}
+
+    fn visit_emoji(&mut self, _node: &Emoji) {}
+
+    fn visit_keyboard_key(&mut self, _node: &KeyboardKey) {}
+
+    fn visit_link_destination(&mut self, _node: &LinkDestination) {}
+
+    fn visit_mention(&mut self, _node: &Mention) {}
+
+    fn visit_text(&mut self, node: &String) {
+        let start = self.raw[self.cursor..]
+            .find(node.as_str())
+            .map(|offset| self.cursor + offset);
+        if let Some(start) = start {
+            self.cursor = start + node.len();
+        }
+
+        // Emoji and keyboard keys are still plain text until a message is compiled.
+        let skipped: Vec<_> = find_bare_urls(node)
+            .chain(find_emoji_shortcodes(node))
+            .chain(find_keyboard_keys(node))
+            .collect();
+        for (offset, word) in node.split_word_bound_indices() {
+            let is_word =
+                word.chars().any(char::is_alphabetic) && !word.chars().any(|c| c.is_numeric());
+            let is_skipped = skipped.iter().any(|range| range.contains(&offset));
+            if is_word && !is_skipped {
+                self.words.push(MessageWord {
+                    word: word.to_string(),
+                    span: start.map(|start| self.line_index.span_at(start + offset)),
+                });
+            }
+        }
+    }
+}
+
+/// Return every word in the visible text of `message` that should be spell checked, in the order
+/// they are written. Words containing digits, like `3D`, are left out.
+pub fn collect_message_words(message: &MessageValue) -> Vec<MessageWord> {
+    let mut collector = WordCollector {
+        raw: message.raw.as_str(),
+        line_index: LineIndex::new(&message.raw),
+        cursor: 0,
+        words: vec![],
+    };
+    visit_with_mut(message.parsed(), &mut collector);
+    collector.words
+}
+
+/// Report every word in `translation` that `checker` considers misspelled in `locale`.
+pub(crate) fn validate_spelling(
+    checker: &dyn SpellChecker,
+    translation: &MessageValue,
+    locale: KeySymbol,
+) -> Vec<ValueDiagnostic> {
+    collect_message_words(translation)
+        .into_iter()
+        .filter_map(|word| {
+            let suggestions = checker.check_word(locale, &word.word)?;
+            let suggestions: Vec<String> = suggestions
+                .iter()
+                .map(|suggestion| format!("'{suggestion}'"))
+                .collect();
+            let help = match suggestions.is_empty() {
+                true => "Check the spelling of the word, or add it to the dictionary if it is a name or a term that is spelled correctly.".into(),
+                false => format!("Did you mean {}?", suggestions.join(", ")),
+            };
+            Some(ValueDiagnostic {
+                name: DiagnosticName::NoMisspelledWords,
+                span: word.span.map(|span| span.byte_offset),
+                severity: DiagnosticSeverity::Warning,
+                description: format!("'{}' may be misspelled", word.word),
+                help: Some(help),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use intl_database_core::key_symbol;
+
+    use super::*;
+
+    fn words(content: &str) -> Vec<String> {
+        collect_message_words(&MessageValue::from_raw(content))
+            .into_iter()
+            .map(|word| word.word)
+            .collect()
+    }
+
+    #[test]
+    fn skips_non_words() {
+        assert_eq!(
+            words("Hi {name}, run `npm` at https://example.com in 3D :smile: [[Ctrl]]"),
+            vec!["Hi", "run", "at", "in"]
+        );
+    }
+
+    #[test]
+    fn reports_misspellings_at_their_offset() {
+        let checker = MisspelledWords::new(key_symbol("en-US"), [("teh".into(), vec![])]);
+        let message = MessageValue::from_raw("Open **teh** door");
+        let diagnostics = validate_spelling(&checker, &message, key_symbol("en-US"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Some(7));
+        assert!(validate_spelling(&checker, &message, key_symbol("fr")).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+//! A summary of validation results for CI, which usually only needs to know whether the build
+//! should fail and how many problems of each kind there are, rather than every diagnostic.
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{DiagnosticSeverity, MessageDiagnostic};
+
+/// Number of diagnostics of each severity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SeverityCounts {
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+}
+
+impl SeverityCounts {
+    fn add(&mut self, severity: DiagnosticSeverity) {
+        match severity {
+            DiagnosticSeverity::Error => self.errors += 1,
+            DiagnosticSeverity::Warning => self.warnings += 1,
+            DiagnosticSeverity::Info => self.infos += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.errors + self.warnings + self.infos
+    }
+
+    /// Return the number of diagnostics that are at least as severe as `severity`.
+    pub fn at_least(&self, severity: DiagnosticSeverity) -> usize {
+        match severity {
+            DiagnosticSeverity::Error => self.errors,
+            DiagnosticSeverity::Warning => self.errors + self.warnings,
+            DiagnosticSeverity::Info => self.total(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationSummary {
+    /// Whether there are no diagnostics at or above the `fail_on` severity.
+    pub passed: bool,
+    pub fail_on: DiagnosticSeverity,
+    pub counts: SeverityCounts,
+    /// Counts for each rule that reported any diagnostics, keyed by its name, for tracking how
+    /// individual problems trend over time.
+    pub rules: BTreeMap<&'static str, SeverityCounts>,
+}
+
+impl ValidationSummary {
+    /// Summarize `diagnostics`, failing if any of them are at least as severe as `fail_on`.
+    pub fn new(diagnostics: &[MessageDiagnostic], fail_on: DiagnosticSeverity) -> Self {
+        let mut counts = SeverityCounts::default();
+        let mut rules: BTreeMap<&'static str, SeverityCounts> = BTreeMap::new();
+        for diagnostic in diagnostics {
+            counts.add(diagnostic.severity);
+            rules
+                .entry(diagnostic.name.as_str())
+                .or_default()
+                .add(diagnostic.severity);
+        }
+        Self {
+            passed: counts.at_least(fail_on) == 0,
+            fail_on,
+            counts,
+            rules,
+        }
+    }
+
+    /// The status that a process should exit with for this summary: 0 when it passed, otherwise 1.
+    pub fn exit_code(&self) -> i32 {
+        if self.passed {
+            0
+        } else {
+            1
+        }
+    }
+}
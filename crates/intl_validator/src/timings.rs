@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use intl_database_core::RuleTiming;
+
+use crate::diagnostic::DiagnosticName;
+
+/// Collects how long each validation rule takes while validating messages, for finding the rules
+/// that are slow on large catalogs. Disabled timings just run each rule without measuring it.
+#[derive(Debug)]
+pub struct RuleTimings {
+    rules: Option<HashMap<DiagnosticName, RuleTiming>>,
+}
+
+impl RuleTimings {
+    /// Create timings that record every rule that runs.
+    pub fn recording() -> Self {
+        Self {
+            rules: Some(HashMap::new()),
+        }
+    }
+
+    /// Create timings that don't record anything.
+    pub fn disabled() -> Self {
+        Self { rules: None }
+    }
+
+    /// Run `validate` as a single check of `rule` on a value, recording how long it took.
+    pub fn time<T>(&mut self, rule: DiagnosticName, validate: impl FnOnce() -> T) -> T {
+        let Some(rules) = &mut self.rules else {
+            return validate();
+        };
+        let start = Instant::now();
+        let result = validate();
+        let duration = start.elapsed();
+
+        let timing = rules.entry(rule).or_insert_with(|| RuleTiming {
+            rule: rule.as_str(),
+            value_count: 0,
+            total: Duration::ZERO,
+            slowest: Duration::ZERO,
+        });
+        timing.value_count += 1;
+        timing.total += duration;
+        timing.slowest = timing.slowest.max(duration);
+        result
+    }
+
+    /// Return the totals for every rule that was recorded, in no particular order.
+    pub fn into_timings(self) -> Vec<RuleTiming> {
+        self.rules
+            .map(|rules| rules.into_values().collect())
+            .unwrap_or_default()
+    }
+}
@@ -1,11 +1,69 @@
+pub use no_concatenated_fragments::{NoConcatenatedFragments, NoConcatenatedFragmentsOptions};
+pub use no_crossed_tags::validate_tag_nesting;
+pub use no_disallowed_markdown::validate_markdown_strictness;
+pub use no_excessive_complexity::{NoExcessiveComplexity, NoExcessiveComplexityOptions};
+pub use no_hardcoded_numbers::{validate_hardcoded_counts, validate_number_separators};
+pub use no_inconsistent_cjk_spacing::{
+    validate_cjk_spacing, CjkPlaceholderSpacing, NoInconsistentCjkSpacingOptions,
+};
+pub use no_invalid_message_references::validate_message_references;
+pub use no_invalid_variable_names::{
+    NoInvalidVariableNames, NoInvalidVariableNamesOptions, VariableNameCase,
+};
+pub use no_mismatched_emoji::validate_emoji_shortcodes;
+pub use no_mismatched_keyboard_keys::validate_keyboard_keys;
+pub use no_mismatched_link_destinations::validate_link_destinations;
+pub use no_mismatched_markdown::validate_markdown_structure;
+pub use no_mismatched_plural_forms::{validate_plural_forms, NoMismatchedPluralFormsOptions};
+pub use no_mismatched_punctuation::validate_edge_punctuation;
+pub use no_mismatched_urls::validate_bare_urls;
+pub use no_mismatched_variable_types::{
+    validate_declared_variable_types, validate_unused_variable_types,
+};
+pub use no_outdated_translations::validate_outdated_translation;
+pub use no_problematic_unicode::NoProblematicUnicode;
+pub use no_relocated_placeholders::validate_placeholder_containers;
 pub use no_repeated_plural_names::NoRepeatedPluralNames;
 pub use no_repeated_plural_options::NoRepeatedPluralOptions;
+pub use no_stray_pound_signs::NoStrayPoundSigns;
+pub use no_style_violations::{validate_style_rules, NoStyleViolationsOptions, StyleRule};
+pub use no_suspicious_apostrophes::NoSuspiciousApostrophes;
+pub use no_syntax_errors::NoSyntaxErrors;
 pub use no_trimmable_whitespace::NoTrimmableWhitespace;
 pub use no_unicode_variable_names::NoUnicodeVariableNames;
+pub use no_unknown_hook_names::{NoUnknownHookNames, NoUnknownHookNamesOptions};
+pub use no_unreviewed_machine_translations::NoUnreviewedMachineTranslations;
+pub use no_untranslated_values::validate_untranslated_value;
 
+mod no_concatenated_fragments;
+mod no_crossed_tags;
+mod no_disallowed_markdown;
+mod no_excessive_complexity;
+mod no_hardcoded_numbers;
+mod no_inconsistent_cjk_spacing;
+mod no_invalid_message_references;
+mod no_invalid_variable_names;
+mod no_mismatched_emoji;
+mod no_mismatched_keyboard_keys;
+mod no_mismatched_link_destinations;
+mod no_mismatched_markdown;
+mod no_mismatched_plural_forms;
+mod no_mismatched_punctuation;
+mod no_mismatched_urls;
+mod no_mismatched_variable_types;
+mod no_outdated_translations;
+mod no_problematic_unicode;
+mod no_relocated_placeholders;
 mod no_repeated_plural_names;
 mod no_repeated_plural_options;
+mod no_stray_pound_signs;
+mod no_style_violations;
+mod no_suspicious_apostrophes;
+mod no_syntax_errors;
 mod no_trimmable_whitespace;
 mod no_unicode_variable_names;
+mod no_unknown_hook_names;
+mod no_unreviewed_machine_translations;
+mod no_untranslated_values;
 
 pub mod validator;
@@ -0,0 +1,141 @@
+use intl_database_core::MessageValue;
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoConcatenatedFragmentsOptions {
+    /// Words that mark a message as a fragment when it starts with them, like `and` in
+    /// `and 3 others`. Words are matched exactly, so only lowercase words are flagged by default.
+    pub leading_words: Vec<String>,
+    /// Text that marks a message as a fragment when it ends with it, like the `:` in `Sent by:`.
+    pub trailing_suffixes: Vec<String>,
+}
+
+impl Default for NoConcatenatedFragmentsOptions {
+    fn default() -> Self {
+        Self {
+            leading_words: ["and", "or", "but", "nor", "so", "yet", "then", "because"]
+                .map(String::from)
+                .to_vec(),
+            trailing_suffixes: vec![":".into()],
+        }
+    }
+}
+
+impl NoConcatenatedFragmentsOptions {
+    pub fn with_leading_words(mut self, leading_words: Vec<String>) -> Self {
+        self.leading_words = leading_words;
+        self
+    }
+
+    pub fn with_trailing_suffixes(mut self, trailing_suffixes: Vec<String>) -> Self {
+        self.trailing_suffixes = trailing_suffixes;
+        self
+    }
+}
+
+/// Flag messages that look like fragments of a sentence, like `Sent by:` or `and 3 others`. These
+/// are usually joined with other messages or values at runtime, which breaks in languages that
+/// order the parts of a sentence differently, and leaves translators without the context of the
+/// full sentence. Fragments should be combined into a single message that uses placeholders for
+/// the values instead.
+pub struct NoConcatenatedFragments {
+    options: NoConcatenatedFragmentsOptions,
+}
+
+impl NoConcatenatedFragments {
+    pub fn with_options(options: NoConcatenatedFragmentsOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Validator for NoConcatenatedFragments {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoConcatenatedFragments
+    }
+
+    fn validate_raw(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        let content = message.raw.as_str();
+        let start = content.len() - content.trim_start().len();
+        let trimmed = content.trim();
+        let mut diagnostics = vec![];
+
+        let first_word = trimmed
+            .split(|c: char| !c.is_alphanumeric())
+            .next()
+            .unwrap_or_default();
+        if self
+            .options
+            .leading_words
+            .iter()
+            .any(|word| word == first_word)
+        {
+            diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoConcatenatedFragments,
+                span: Some(start),
+                severity: DiagnosticSeverity::Warning,
+                description: format!("Message starts with \"{first_word}\", which suggests it continues another message"),
+                help: Some("Combine the parts of the sentence into a single message, using placeholders for any values that are inserted between them.".into()),
+            });
+        }
+
+        let suffix = self
+            .options
+            .trailing_suffixes
+            .iter()
+            .find(|suffix| trimmed.len() > suffix.len() && trimmed.ends_with(suffix.as_str()));
+        if let Some(suffix) = suffix {
+            diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoConcatenatedFragments,
+                span: Some(start + trimmed.len() - suffix.len()),
+                severity: DiagnosticSeverity::Warning,
+                description: format!("Message ends with \"{suffix}\", which suggests another value or message is added after it"),
+                help: Some("Include the value that follows in the message as a placeholder, like `Sent by: {username}`, so that translators can place it correctly.".into()),
+            });
+        }
+        Some(diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(options: NoConcatenatedFragmentsOptions, raw: &str) -> Vec<Option<usize>> {
+        NoConcatenatedFragments::with_options(options)
+            .validate_raw(&MessageValue::from_raw(raw))
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_complete_sentences() {
+        let options = NoConcatenatedFragmentsOptions::default();
+        assert_eq!(spans(options.clone(), "Sent by: {username}"), vec![]);
+        assert_eq!(spans(options.clone(), "And then there were none"), vec![]);
+        assert_eq!(spans(options.clone(), "Android"), vec![]);
+        assert_eq!(spans(options, ":"), vec![]);
+    }
+
+    #[test]
+    fn test_fragments() {
+        let options = NoConcatenatedFragmentsOptions::default();
+        assert_eq!(spans(options.clone(), " and 3 others"), vec![Some(1)]);
+        assert_eq!(spans(options.clone(), "Sent by: "), vec![Some(7)]);
+        assert_eq!(spans(options, "or sent by:"), vec![Some(0), Some(10)]);
+    }
+
+    #[test]
+    fn test_configured_words() {
+        let options = NoConcatenatedFragmentsOptions::default()
+            .with_leading_words(vec!["et".into()])
+            .with_trailing_suffixes(vec![" de".into()]);
+        assert_eq!(spans(options.clone(), "et 3 autres"), vec![Some(0)]);
+        assert_eq!(spans(options.clone(), "Envoyé de"), vec![Some(7)]);
+        assert_eq!(spans(options, "and 3 others:"), vec![]);
+    }
+}
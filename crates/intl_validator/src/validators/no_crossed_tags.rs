@@ -0,0 +1,183 @@
+use std::ops::Range;
+
+use intl_database_core::MessageValue;
+use intl_markdown::{Emphasis, Hook, Link, Strikethrough, Strong, DEFAULT_TAG_NAMES};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// A hook or link within a message, along with the list of rich text tags that contain it, from
+/// outermost to innermost.
+#[derive(Debug, PartialEq, Eq)]
+struct TagPlacement {
+    name: String,
+    ancestors: Vec<String>,
+    /// Byte offset where the tag starts in the message.
+    span: Option<usize>,
+}
+
+/// Collects the placement of every hook and link in a message, in source order.
+struct TagPlacementCollector {
+    stack: Vec<String>,
+    placements: Vec<TagPlacement>,
+}
+
+impl TagPlacementCollector {
+    fn collect(message: &MessageValue) -> Vec<TagPlacement> {
+        let mut collector = Self {
+            stack: vec![],
+            placements: vec![],
+        };
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.placements
+    }
+
+    /// Record where a hook or link is placed, relative to the tags that are currently open.
+    fn place(&mut self, name: &str, span: Option<Range<usize>>) {
+        self.placements.push(TagPlacement {
+            name: name.into(),
+            ancestors: self.stack.clone(),
+            span: span.map(|span| span.start),
+        });
+    }
+
+    fn enter(&mut self, name: &str) {
+        self.stack.push(name.into());
+    }
+
+    fn exit(&mut self) {
+        self.stack.pop();
+    }
+}
+
+impl Visit for TagPlacementCollector {
+    fn visit_emphasis(&mut self, node: &Emphasis) {
+        self.enter(DEFAULT_TAG_NAMES.emphasis());
+        node.visit_children_with(self);
+        self.exit();
+    }
+
+    fn visit_hook(&mut self, node: &Hook) {
+        self.place(node.name(), node.span());
+        self.enter(node.name());
+        node.visit_children_with(self);
+        self.exit();
+    }
+
+    fn visit_link(&mut self, node: &Link) {
+        self.place(DEFAULT_TAG_NAMES.link(), node.span());
+        self.enter(DEFAULT_TAG_NAMES.link());
+        node.visit_children_with(self);
+        self.exit();
+    }
+
+    fn visit_strikethrough(&mut self, node: &Strikethrough) {
+        self.enter(DEFAULT_TAG_NAMES.strike_through());
+        node.visit_children_with(self);
+        self.exit();
+    }
+
+    fn visit_strong(&mut self, node: &Strong) {
+        self.enter(DEFAULT_TAG_NAMES.strong());
+        node.visit_children_with(self);
+        self.exit();
+    }
+}
+
+/// Translations are free to reorder hooks and links within a sentence, but the nesting of those
+/// tags relative to the surrounding formatting should stay the same as the source. When a
+/// translator moves a closing delimiter across a link or hook (e.g., `**$[text**](hook)`), the
+/// formatting no longer parses as a balanced pair and the message renders with literal syntax at
+/// runtime.
+///
+/// This compares every hook and link in the translation against the first matching, unused tag
+/// in the source, and reports any whose containing tags differ.
+pub fn validate_tag_nesting(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let mut source_placements: Vec<Option<TagPlacement>> = TagPlacementCollector::collect(source)
+        .into_iter()
+        .map(Some)
+        .collect();
+    let mut diagnostics = vec![];
+
+    for placement in TagPlacementCollector::collect(translation) {
+        let matching_index = source_placements
+            .iter()
+            .position(|source| source.as_ref().is_some_and(|s| s.name == placement.name));
+        // Tags that don't exist in the source at all are reported by other validations.
+        let Some(matching_index) = matching_index else {
+            continue;
+        };
+        let source_placement = source_placements[matching_index]
+            .take()
+            .expect("`position` only matches source placements that haven't been taken");
+        if source_placement.ancestors == placement.ancestors {
+            continue;
+        }
+
+        let format_path = |ancestors: &Vec<String>| match ancestors.is_empty() {
+            true => String::from("at the top level"),
+            false => format!("inside {}", ancestors.join(" > ")),
+        };
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoCrossedTags,
+            span: placement.span,
+            severity: DiagnosticSeverity::Error,
+            description: format!(
+                "'{}' is nested differently than in the source message",
+                placement.name
+            ),
+            help: Some(format!(
+                "The source message has '{}' {}, but this translation has it {}. Check that the formatting around it opens and closes on the same side of the tag.",
+                placement.name,
+                format_path(&source_placement.ancestors),
+                format_path(&placement.ancestors),
+            )),
+        });
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::MessageValue;
+
+    use super::validate_tag_nesting;
+
+    fn spans(source: &str, translation: &str) -> Vec<Option<usize>> {
+        validate_tag_nesting(
+            &MessageValue::from_raw(source),
+            &MessageValue::from_raw(translation),
+        )
+        .iter()
+        .map(|diagnostic| diagnostic.span)
+        .collect()
+    }
+
+    #[test]
+    fn test_reordered_tags() {
+        assert_eq!(
+            spans(
+                "**$[Open](hook)** or [docs](./docs)",
+                "[docs](./docs) or **$[Open](hook)**"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_crossed_tags() {
+        assert_eq!(
+            spans("**$[Open](hook)** now", "**Now** $[open](hook)"),
+            vec![Some(8)]
+        );
+        assert_eq!(
+            spans("[docs](./docs)", "Read *[docs](./docs)*"),
+            vec![Some(6)]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use intl_database_core::{MarkdownStrictness, MessageValue};
+use intl_markdown::{
+    CodeBlock, CodeSpan, Emphasis, Heading, Hook, KeyboardKey, Link, Paragraph, Strikethrough,
+    Strong,
+};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Collects the kinds of markdown syntax in a message that its strictness doesn't allow, in the
+/// order they first appear.
+struct DisallowedSyntaxCollector {
+    strictness: MarkdownStrictness,
+    paragraphs: usize,
+    kinds: Vec<&'static str>,
+}
+
+impl DisallowedSyntaxCollector {
+    fn collect(message: &MessageValue, strictness: MarkdownStrictness) -> Vec<&'static str> {
+        let mut collector = Self {
+            strictness,
+            paragraphs: 0,
+            kinds: vec![],
+        };
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.kinds
+    }
+
+    fn add_block(&mut self, kind: &'static str) {
+        if !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        }
+    }
+
+    fn add_inline(&mut self, kind: &'static str) {
+        if self.strictness == MarkdownStrictness::Plain {
+            self.add_block(kind);
+        }
+    }
+}
+
+impl Visit for DisallowedSyntaxCollector {
+    fn visit_code_block(&mut self, _node: &CodeBlock) {
+        self.add_block("code block");
+    }
+
+    fn visit_heading(&mut self, node: &Heading) {
+        self.add_block("heading");
+        node.visit_children_with(self);
+    }
+
+    fn visit_paragraph(&mut self, node: &Paragraph) {
+        self.paragraphs += 1;
+        if self.paragraphs > 1 {
+            self.add_block("multiple paragraphs");
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_thematic_break(&mut self) {
+        self.add_block("thematic break");
+    }
+
+    fn visit_code_span(&mut self, _node: &CodeSpan) {
+        self.add_inline("code span");
+    }
+
+    fn visit_emphasis(&mut self, node: &Emphasis) {
+        self.add_inline("emphasis");
+        node.visit_children_with(self);
+    }
+
+    fn visit_hook(&mut self, node: &Hook) {
+        self.add_inline("hook");
+        node.visit_children_with(self);
+    }
+
+    fn visit_keyboard_key(&mut self, _node: &KeyboardKey) {
+        self.add_inline("keyboard key");
+    }
+
+    fn visit_link(&mut self, node: &Link) {
+        self.add_inline("link");
+        node.visit_children_with(self);
+    }
+
+    fn visit_strikethrough(&mut self, node: &Strikethrough) {
+        self.add_inline("strikethrough");
+        node.visit_children_with(self);
+    }
+
+    fn visit_strong(&mut self, node: &Strong) {
+        self.add_inline("strong");
+        node.visit_children_with(self);
+    }
+}
+
+/// Some surfaces can only render plain text or a single line of inline content, like push
+/// notifications or native menus, and markup in messages shown there either appears as literal
+/// characters or is silently dropped. Messages declare what they can use with a `strictness` in
+/// their meta, and this reports every kind of syntax in any value of the message that goes beyond
+/// it.
+pub fn validate_markdown_strictness(
+    value: &MessageValue,
+    strictness: MarkdownStrictness,
+) -> Vec<ValueDiagnostic> {
+    if strictness == MarkdownStrictness::FullBlocks {
+        return vec![];
+    }
+
+    let help = match strictness {
+        MarkdownStrictness::Plain => "This message is rendered as plain text, where markdown shows up as literal characters. Remove the formatting, or change the strictness of the message if everywhere it's rendered supports markdown.",
+        _ => "This message is rendered inline, where block content can't be displayed. Keep the message to a single paragraph, or change the strictness of the message if everywhere it's rendered supports blocks.",
+    };
+    DisallowedSyntaxCollector::collect(value, strictness)
+        .into_iter()
+        .map(|kind| ValueDiagnostic {
+            name: DiagnosticName::NoDisallowedMarkdown,
+            span: None,
+            severity: DiagnosticSeverity::Error,
+            description: format!(
+                "Message uses {kind}, which isn't allowed for messages with {strictness} strictness"
+            ),
+            help: Some(help.into()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kinds(raw: &str, strictness: MarkdownStrictness) -> Vec<&'static str> {
+        DisallowedSyntaxCollector::collect(&MessageValue::from_raw(raw), strictness)
+    }
+
+    #[test]
+    fn test_full_blocks_allow_everything() {
+        let value = MessageValue::from_raw("# Title\n\nSome **text**\n\n---");
+        assert!(validate_markdown_strictness(&value, MarkdownStrictness::FullBlocks).is_empty());
+    }
+
+    #[test]
+    fn test_inline_only() {
+        let strictness = MarkdownStrictness::InlineOnly;
+        assert_eq!(
+            kinds("Some **text** with [a link](onClick)", strictness),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            kinds("# Title\n\nOne\n\nTwo\n\n---", strictness),
+            vec!["heading", "multiple paragraphs", "thematic break"]
+        );
+    }
+
+    #[test]
+    fn test_plain() {
+        let strictness = MarkdownStrictness::Plain;
+        assert_eq!(
+            kinds(
+                "Hello {name}, you have {count, number} messages",
+                strictness
+            ),
+            Vec::<&str>::new()
+        );
+        // Each kind is only reported once, even when it's used more than once.
+        assert_eq!(
+            kinds("**Some** *text* `code` **more**", strictness),
+            vec!["strong", "emphasis", "code span"]
+        );
+        let value = MessageValue::from_raw("$[Open](hook) [docs](onClick)");
+        assert_eq!(validate_markdown_strictness(&value, strictness).len(), 2);
+    }
+}
@@ -0,0 +1,206 @@
+use intl_database_core::MessageValue;
+use intl_markdown::{count_combinations, IcuPlural, IcuSelect};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoExcessiveComplexityOptions {
+    /// Maximum number of characters in the raw content of a message.
+    pub max_length: usize,
+    /// Maximum number of plural and select expressions nested inside of each other. A message
+    /// with a single plural has a depth of 1.
+    pub max_nesting_depth: usize,
+    /// Maximum number of arms in any single plural expression.
+    pub max_plural_arms: usize,
+    /// Maximum number of distinct variables used in a message.
+    pub max_variables: usize,
+    /// Maximum number of distinct sentences the message can produce, counting every combination
+    /// of plural and select arms, like 6 for a plural of 3 arms next to a select of 2 arms.
+    pub max_combinations: usize,
+}
+
+impl Default for NoExcessiveComplexityOptions {
+    fn default() -> Self {
+        Self {
+            max_length: 1000,
+            max_nesting_depth: 2,
+            max_plural_arms: 8,
+            max_variables: 10,
+            max_combinations: 24,
+        }
+    }
+}
+
+impl NoExcessiveComplexityOptions {
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    pub fn with_max_nesting_depth(mut self, max_nesting_depth: usize) -> Self {
+        self.max_nesting_depth = max_nesting_depth;
+        self
+    }
+
+    pub fn with_max_plural_arms(mut self, max_plural_arms: usize) -> Self {
+        self.max_plural_arms = max_plural_arms;
+        self
+    }
+
+    pub fn with_max_variables(mut self, max_variables: usize) -> Self {
+        self.max_variables = max_variables;
+        self
+    }
+
+    pub fn with_max_combinations(mut self, max_combinations: usize) -> Self {
+        self.max_combinations = max_combinations;
+        self
+    }
+}
+
+/// Warn about messages that are very large or deeply nested. These are painful for translators to
+/// work with, since every nested arm multiplies the number of sentences that need to be written,
+/// and they bloat bundles. Every measured metric is included in the diagnostic, so authors can
+/// see which parts of the message to split up.
+pub struct NoExcessiveComplexity {
+    options: NoExcessiveComplexityOptions,
+    depth: usize,
+    max_depth: usize,
+    max_plural_arms: usize,
+}
+
+impl NoExcessiveComplexity {
+    pub fn with_options(options: NoExcessiveComplexityOptions) -> Self {
+        Self {
+            options,
+            depth: 0,
+            max_depth: 0,
+            max_plural_arms: 0,
+        }
+    }
+
+    fn visit_nested(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        visit(self);
+        self.depth -= 1;
+    }
+}
+
+impl Validator for NoExcessiveComplexity {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoExcessiveComplexity
+    }
+
+    fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        visit_with_mut(message.parsed(), self);
+        let length = message.raw.chars().count();
+        let variables = message.variables().map_or(0, |variables| variables.count());
+        let combinations = count_combinations(message.parsed());
+        let metrics = format!(
+            "The message has {length} characters, a nesting depth of {}, {} arms in its largest plural, {variables} variables, and {combinations} combinations of plural and select arms.",
+            self.max_depth, self.max_plural_arms,
+        );
+
+        let exceeded = [
+            ("characters", length, self.options.max_length),
+            (
+                "levels of nested plurals and selects",
+                self.max_depth,
+                self.options.max_nesting_depth,
+            ),
+            (
+                "arms in a single plural",
+                self.max_plural_arms,
+                self.options.max_plural_arms,
+            ),
+            ("variables", variables, self.options.max_variables),
+            (
+                "combinations of plural and select arms",
+                combinations,
+                self.options.max_combinations,
+            ),
+        ];
+        let diagnostics = exceeded
+            .into_iter()
+            .filter(|(_, value, limit)| value > limit)
+            .map(|(metric, value, limit)| ValueDiagnostic {
+                name: DiagnosticName::NoExcessiveComplexity,
+                span: None,
+                severity: DiagnosticSeverity::Warning,
+                description: format!("Message has {value} {metric}, more than the limit of {limit}"),
+                help: Some(format!(
+                    "{metrics} Large and deeply nested messages are hard to translate and increase bundle sizes. Consider splitting this into multiple messages."
+                )),
+            })
+            .collect();
+        Some(diagnostics)
+    }
+}
+
+impl Visit for NoExcessiveComplexity {
+    fn visit_icu_plural(&mut self, node: &IcuPlural) {
+        self.max_plural_arms = self.max_plural_arms.max(node.arms().len());
+        self.visit_nested(|this| node.visit_children_with(this));
+    }
+
+    fn visit_icu_select(&mut self, node: &IcuSelect) {
+        self.visit_nested(|this| node.visit_children_with(this));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn descriptions(options: NoExcessiveComplexityOptions, raw: &str) -> Vec<String> {
+        NoExcessiveComplexity::with_options(options)
+            .validate_ast(&MessageValue::from_raw(raw))
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| diagnostic.description)
+            .collect()
+    }
+
+    const NESTED: &str =
+        "{a, plural, one {{b, select, x {{c, plural, other {#}}} other {y}}} other {z}}";
+
+    #[test]
+    fn test_within_limits() {
+        let options = NoExcessiveComplexityOptions::default();
+        assert_eq!(
+            descriptions(options.clone(), "Hello {name}"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            descriptions(options, "{count, plural, one {# item} other {# items}}"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_exceeded_limits() {
+        assert_eq!(
+            descriptions(NoExcessiveComplexityOptions::default(), NESTED),
+            vec!["Message has 3 levels of nested plurals and selects, more than the limit of 2"]
+        );
+        let options = NoExcessiveComplexityOptions::default()
+            .with_max_length(10)
+            .with_max_plural_arms(1)
+            .with_max_variables(1)
+            .with_max_combinations(2)
+            .with_max_nesting_depth(3);
+        assert_eq!(
+            descriptions(options, NESTED),
+            vec![
+                "Message has 78 characters, more than the limit of 10",
+                "Message has 2 arms in a single plural, more than the limit of 1",
+                "Message has 3 variables, more than the limit of 1",
+                "Message has 3 combinations of plural and select arms, more than the limit of 2",
+            ]
+        );
+    }
+}
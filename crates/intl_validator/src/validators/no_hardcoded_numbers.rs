@@ -0,0 +1,230 @@
+use std::ops::Range;
+
+use intl_database_core::MessageValue;
+use intl_markdown::{CodeSpan, IcuPlural};
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Characters that are used to separate groups of thousands in numbers, like the `,` in `1,000`.
+const GROUP_SEPARATORS: [char; 5] = [',', '.', '\'', '\u{a0}', '\u{202f}'];
+
+/// A number written directly in the text of a message.
+struct NumberToken {
+    range: Range<usize>,
+    grouped: bool,
+}
+
+/// Collects the plain text of a message along with where each piece starts in the raw content.
+/// Plural arms are skipped, since writing a number directly in one, like `=1 {1 item}`, is the
+/// intended way to use them, and code spans are skipped since their content is always literal.
+struct NumberTextCollector<'a> {
+    raw: &'a str,
+    /// The position in `raw` to continue searching for text from, since text is visited in the
+    /// same order it is written.
+    cursor: usize,
+    texts: Vec<(String, Option<usize>)>,
+}
+
+impl<'a> NumberTextCollector<'a> {
+    fn collect(message: &'a MessageValue) -> Vec<(String, Option<usize>)> {
+        let mut collector = Self {
+            raw: message.raw.as_str(),
+            cursor: 0,
+            texts: vec![],
+        };
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.texts
+    }
+}
+
+impl Visit for NumberTextCollector<'_> {
+    fn visit_code_span(&mut self, _node: &CodeSpan) {}
+
+    fn visit_icu_plural(&mut self, _node: &IcuPlural) {}
+
+    fn visit_text(&mut self, node: &String) {
+        // Text that was unescaped while parsing won't match the raw content exactly, and is left
+        // without a span.
+        let start = self.raw[self.cursor..]
+            .find(node.as_str())
+            .map(|offset| self.cursor + offset);
+        if let Some(start) = start {
+            self.cursor = start + node.len();
+        }
+        self.texts.push((node.clone(), start));
+    }
+}
+
+/// Return every standalone number in `text`. Numbers that are part of a larger token, like the
+/// `3` in `3D`, or decimals and times like `1.5` and `10:30`, are not included.
+fn find_numbers(text: &str) -> Vec<NumberToken> {
+    let is_joined = |c: char| c.is_alphanumeric() || matches!(c, '.' | ',' | ':' | '_');
+    let mut numbers = vec![];
+    let mut chars = text.char_indices().peekable();
+    let mut previous: Option<char> = None;
+    while let Some((start, c)) = chars.next() {
+        if !c.is_ascii_digit() || previous.is_some_and(is_joined) {
+            previous = Some(c);
+            continue;
+        }
+
+        let mut end = start + 1;
+        let mut grouped = false;
+        let rest = |end: usize| &text[end..];
+        loop {
+            let digits = rest(end).bytes().take_while(u8::is_ascii_digit).count();
+            end += digits;
+            let Some(separator) = rest(end).chars().next() else {
+                break;
+            };
+            let after = &rest(end)[separator.len_utf8()..];
+            let group = after.bytes().take_while(u8::is_ascii_digit).count();
+            if !GROUP_SEPARATORS.contains(&separator) || group != 3 {
+                break;
+            }
+            grouped = true;
+            end += separator.len_utf8() + 3;
+        }
+
+        let next = rest(end).chars().next();
+        let continues_number = next.is_some_and(|next| {
+            is_joined(next)
+                && rest(end)[next.len_utf8()..].starts_with(|c: char| c.is_alphanumeric())
+        });
+        if !next.is_some_and(char::is_alphanumeric) && !continues_number {
+            numbers.push(NumberToken {
+                range: start..end,
+                grouped,
+            });
+        }
+
+        // Skip the rest of the number so that its digits aren't treated as new numbers.
+        while chars.peek().is_some_and(|(index, _)| *index < end) {
+            previous = chars.next().map(|(_, c)| c);
+        }
+    }
+    numbers
+}
+
+/// Returns true if `text` starts with a single space followed by a word that looks like an
+/// English plural, like the ` items` in `5 items`.
+fn is_followed_by_plural_word(text: &str) -> bool {
+    let Some(rest) = text.strip_prefix(' ') else {
+        return false;
+    };
+    let word_length = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    word_length >= 3 && rest[..word_length].ends_with('s')
+}
+
+/// Counts like `5 items` need to be written as plurals, like `{count, plural, one {# item} other
+/// {# items}}`, so that every locale can choose the right word for the number. Source messages
+/// that write a number directly in front of a plural-looking word are usually counts that were
+/// written before the number was made dynamic, or ones that will read incorrectly in languages
+/// with more plural forms. This only checks source messages, since the heuristic is based on
+/// English plurals.
+pub fn validate_hardcoded_counts(source: &MessageValue) -> Vec<ValueDiagnostic> {
+    let mut diagnostics = vec![];
+    for (text, start) in NumberTextCollector::collect(source) {
+        for number in find_numbers(&text) {
+            if number.grouped || !is_followed_by_plural_word(&text[number.range.end..]) {
+                continue;
+            }
+            diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoHardcodedNumbers,
+                span: start.map(|start| start + number.range.start),
+                severity: DiagnosticSeverity::Info,
+                description: format!("\"{}\" looks like a count written directly in the message", &text[number.range]),
+                help: Some("Use a plural placeholder like `{count, plural, one {# item} other {# items}}` so that each locale can use the correct plural form for the number.".into()),
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Separators between groups of thousands are different in every locale, like `1,000` in English,
+/// `1.000` in German and `1 000` in French. Translations that write grouped numbers directly will
+/// only be formatted correctly for one of them, so this reports every grouped number in a
+/// translation, which should be passed in as a `{value, number}` placeholder instead.
+pub fn validate_number_separators(translation: &MessageValue) -> Vec<ValueDiagnostic> {
+    let mut diagnostics = vec![];
+    for (text, start) in NumberTextCollector::collect(translation) {
+        for number in find_numbers(&text) {
+            if !number.grouped {
+                continue;
+            }
+            diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoHardcodedNumbers,
+                span: start.map(|start| start + number.range.start),
+                severity: DiagnosticSeverity::Info,
+                description: format!("\"{}\" is written with hard-coded thousands separators", &text[number.range]),
+                help: Some("Use a number placeholder like `{value, number}` so that the number is formatted for each locale automatically.".into()),
+            });
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn count_spans(raw: &str) -> Vec<Option<usize>> {
+        validate_hardcoded_counts(&MessageValue::from_raw(raw))
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    fn separator_spans(raw: &str) -> Vec<Option<usize>> {
+        validate_number_separators(&MessageValue::from_raw(raw))
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_find_numbers() {
+        let numbers = |text| {
+            find_numbers(text)
+                .into_iter()
+                .map(|number| (&text[number.range], number.grouped))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            numbers("5 items, 1,000 users"),
+            vec![("5", false), ("1,000", true)]
+        );
+        assert_eq!(numbers("3D at 10:30, v1.5 or 1.5x"), vec![]);
+        // Decimals written with a comma are skipped, and only non-breaking spaces group digits.
+        assert_eq!(
+            numbers("12,34 and 1 000 or 1\u{a0}000"),
+            vec![("1", false), ("000", false), ("1\u{a0}000", true)]
+        );
+    }
+
+    #[test]
+    fn test_hardcoded_counts() {
+        assert_eq!(count_spans("You have 5 items"), vec![Some(9)]);
+        assert_eq!(count_spans("**Only** 2 seats left, 3 is"), vec![Some(9)]);
+        assert_eq!(count_spans("Top 10 list"), vec![]);
+        assert_eq!(count_spans("1,000 users"), vec![]);
+        assert_eq!(
+            count_spans("{count, plural, =1 {1 items} other {# items}} and `2 bytes`"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_number_separators() {
+        assert_eq!(separator_spans("Plus de 1.000 utilisateurs"), vec![Some(8)]);
+        assert_eq!(separator_spans("1,000,000 and 1 000"), vec![Some(0)]);
+        assert_eq!(
+            separator_spans("{value, number} utilisateurs, 1000"),
+            vec![]
+        );
+    }
+}
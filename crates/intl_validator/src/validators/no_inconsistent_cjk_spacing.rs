@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use intl_database_core::MessageValue;
+use intl_markdown::{
+    is_cjk_letter, CodeSpan, Emoji, Icu, IcuPluralArm, IcuVariable, KeyboardKey, LinkDestination,
+    Mention, Paragraph,
+};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// How a locale separates CJK text from the placeholders written next to it, which are usually
+/// filled with Latin text or numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CjkPlaceholderSpacing {
+    /// A space is written between CJK text and a placeholder, like `{name} さん`.
+    Spaced,
+    /// Placeholders are written directly against CJK text, like `{name}さん`.
+    Unspaced,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoInconsistentCjkSpacingOptions {
+    /// The spacing convention for each language, like `Unspaced` for `ja`. Keys are either a
+    /// language, like `zh`, or a full locale, like `zh-TW`, which takes precedence over its
+    /// language. Locales without a convention are not checked.
+    pub placeholder_spacing: HashMap<String, CjkPlaceholderSpacing>,
+}
+
+impl Default for NoInconsistentCjkSpacingOptions {
+    fn default() -> Self {
+        Self {
+            placeholder_spacing: HashMap::from([
+                ("ja".into(), CjkPlaceholderSpacing::Unspaced),
+                ("zh".into(), CjkPlaceholderSpacing::Unspaced),
+            ]),
+        }
+    }
+}
+
+impl NoInconsistentCjkSpacingOptions {
+    /// Set the spacing convention for each of the given languages or locales, keeping the
+    /// defaults for any that aren't given.
+    pub fn with_placeholder_spacing(
+        mut self,
+        placeholder_spacing: HashMap<String, CjkPlaceholderSpacing>,
+    ) -> Self {
+        self.placeholder_spacing.extend(placeholder_spacing);
+        self
+    }
+
+    fn spacing_for_locale(&self, locale: &str) -> Option<CjkPlaceholderSpacing> {
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        self.placeholder_spacing
+            .get(locale)
+            .or_else(|| self.placeholder_spacing.get(language))
+            .copied()
+    }
+}
+
+/// A piece of the rendered message, in the order it is shown.
+enum Segment {
+    Text(String),
+    /// A value that is filled in when the message is formatted, like `{name}` or `{count, number}`,
+    /// along with the start of its name in the raw content when it's known.
+    Placeholder(String, Option<usize>),
+    /// Anything that separates the text around it, like an emoji or a plural option boundary.
+    Break,
+}
+
+/// Flattens a message into the text and placeholders that are shown next to each other. Markdown
+/// like emphasis and links doesn't separate the text around it, so only their content is kept.
+#[derive(Default)]
+struct SegmentCollector {
+    segments: Vec<Segment>,
+}
+
+impl SegmentCollector {
+    fn collect(message: &MessageValue) -> Vec<Segment> {
+        let mut collector = Self::default();
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.segments
+    }
+
+    fn add_placeholder(&mut self, variable: &IcuVariable) {
+        let span = variable.name_span().map(|span| span.start);
+        self.segments
+            .push(Segment::Placeholder(variable.name().to_string(), span));
+    }
+}
+
+impl Visit for SegmentCollector {
+    fn visit_code_span(&mut self, _node: &CodeSpan) {
+        self.segments.push(Segment::Break);
+    }
+
+    fn visit_emoji(&mut self, _node: &Emoji) {
+        self.segments.push(Segment::Break);
+    }
+
+    fn visit_icu(&mut self, node: &Icu) {
+        match node {
+            Icu::IcuVariable(variable) => self.add_placeholder(variable),
+            Icu::IcuNumber(number) => self.add_placeholder(number.variable()),
+            Icu::IcuDate(date) => self.add_placeholder(date.variable()),
+            Icu::IcuTime(time) => self.add_placeholder(time.variable()),
+            // Each option of a plural or select is shown on its own, so the text around them is
+            // never next to the text inside of them.
+            Icu::IcuPlural(plural) => {
+                self.segments.push(Segment::Break);
+                for arm in plural.arms() {
+                    arm.visit_with(self);
+                }
+            }
+            Icu::IcuSelect(select) => {
+                self.segments.push(Segment::Break);
+                for arm in select.arms() {
+                    arm.visit_with(self);
+                }
+            }
+        }
+    }
+
+    fn visit_icu_plural_arm(&mut self, node: &IcuPluralArm) {
+        node.visit_children_with(self);
+        self.segments.push(Segment::Break);
+    }
+
+    fn visit_icu_pound(&mut self) {
+        self.segments.push(Segment::Placeholder("#".into(), None));
+    }
+
+    fn visit_keyboard_key(&mut self, _node: &KeyboardKey) {
+        self.segments.push(Segment::Break);
+    }
+
+    fn visit_link_destination(&mut self, _node: &LinkDestination) {}
+
+    fn visit_mention(&mut self, _node: &Mention) {
+        self.segments.push(Segment::Break);
+    }
+
+    fn visit_paragraph(&mut self, node: &Paragraph) {
+        self.segments.push(Segment::Break);
+        node.visit_children_with(self);
+    }
+
+    fn visit_text(&mut self, node: &String) {
+        match self.segments.last_mut() {
+            Some(Segment::Text(text)) => text.push_str(node),
+            _ => self.segments.push(Segment::Text(node.clone())),
+        }
+    }
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\u{00A0}')
+}
+
+/// Returns whether there is a space between `text` and a placeholder on the given side of it,
+/// when the text next to that space is CJK. Text that isn't CJK on that side isn't checked.
+fn cjk_spacing(text: &str, placeholder_after_text: bool) -> Option<bool> {
+    let (edge, trimmed) = if placeholder_after_text {
+        (text.chars().next_back()?, text.trim_end_matches(is_space))
+    } else {
+        (text.chars().next()?, text.trim_start_matches(is_space))
+    };
+    let letter = if placeholder_after_text {
+        trimmed.chars().next_back()?
+    } else {
+        trimmed.chars().next()?
+    };
+    is_cjk_letter(letter).then_some(is_space(edge))
+}
+
+fn spacing_diagnostic(
+    placeholder: &str,
+    span: Option<usize>,
+    expected: CjkPlaceholderSpacing,
+    locale: &str,
+) -> ValueDiagnostic {
+    let (description, help) = match expected {
+        CjkPlaceholderSpacing::Spaced => (
+            format!("The placeholder '{placeholder}' is written directly against the text next to it"),
+            format!("Translations for {locale} put a space between placeholders and the CJK text around them."),
+        ),
+        CjkPlaceholderSpacing::Unspaced => (
+            format!("The placeholder '{placeholder}' is separated from the text next to it by a space"),
+            format!("Translations for {locale} write placeholders directly against the CJK text around them, without a space."),
+        ),
+    };
+    ValueDiagnostic {
+        name: DiagnosticName::NoInconsistentCjkSpacing,
+        span,
+        severity: DiagnosticSeverity::Warning,
+        description,
+        help: Some(help),
+    }
+}
+
+/// Placeholders in CJK translations are usually filled with Latin text or numbers, and locales
+/// have their own conventions for whether a space separates them from the CJK text around them.
+/// Translations often get this wrong by copying the spacing of the source message. For locales
+/// with a configured convention, this flags every placeholder whose spacing next to CJK text
+/// doesn't follow it.
+pub fn validate_cjk_spacing(
+    translation: &MessageValue,
+    locale: &str,
+    options: &NoInconsistentCjkSpacingOptions,
+) -> Vec<ValueDiagnostic> {
+    let Some(expected) = options.spacing_for_locale(locale) else {
+        return vec![];
+    };
+
+    let segments = SegmentCollector::collect(translation);
+    let mut diagnostics = vec![];
+    for (index, segment) in segments.iter().enumerate() {
+        let Segment::Placeholder(name, span) = segment else {
+            continue;
+        };
+        let before = index
+            .checked_sub(1)
+            .and_then(|previous| match &segments[previous] {
+                Segment::Text(text) => cjk_spacing(text, true),
+                _ => None,
+            });
+        let after = segments.get(index + 1).and_then(|next| match next {
+            Segment::Text(text) => cjk_spacing(text, false),
+            _ => None,
+        });
+        let is_spaced = expected == CjkPlaceholderSpacing::Spaced;
+        if [before, after]
+            .into_iter()
+            .flatten()
+            .any(|has_space| has_space != is_spaced)
+        {
+            diagnostics.push(spacing_diagnostic(name, *span, expected, locale));
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(
+        raw: &str,
+        locale: &str,
+        options: &NoInconsistentCjkSpacingOptions,
+    ) -> Vec<Option<usize>> {
+        validate_cjk_spacing(&MessageValue::from_raw(raw), locale, options)
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_unspaced_locales() {
+        let options = NoInconsistentCjkSpacingOptions::default();
+        assert_eq!(spans("{name}さん、こんにちは", "ja", &options), vec![]);
+        assert_eq!(
+            spans("{name} さん、こんにちは", "ja", &options),
+            vec![Some(1)]
+        );
+        assert_eq!(
+            spans("共 {count, number} 个", "zh-CN", &options),
+            vec![Some(5)]
+        );
+        // Text that isn't CJK can be spaced either way.
+        assert_eq!(spans("Discord {name}さん", "ja", &options), vec![]);
+        assert_eq!(
+            spans("{count, plural, other {# 件}}", "ja", &options),
+            vec![None]
+        );
+    }
+
+    #[test]
+    fn test_spaced_locales() {
+        let options = NoInconsistentCjkSpacingOptions::default().with_placeholder_spacing(
+            HashMap::from([("zh-TW".into(), CjkPlaceholderSpacing::Spaced)]),
+        );
+        assert_eq!(spans("共 {count, number} 個", "zh-TW", &options), vec![]);
+        assert_eq!(
+            spans("共{count, number}個", "zh-TW", &options),
+            vec![Some(4)]
+        );
+        // The language's convention still applies to its other locales.
+        assert_eq!(spans("共{count, number}个", "zh-CN", &options), vec![]);
+    }
+
+    #[test]
+    fn test_unchecked_locales() {
+        let options = NoInconsistentCjkSpacingOptions::default();
+        assert_eq!(spans("{name} 님", "ko", &options), vec![]);
+        assert_eq!(spans("{name} さん", "en", &options), vec![]);
+    }
+}
@@ -0,0 +1,121 @@
+use intl_database_core::{DatabaseError, KeySymbol, MessageValue, MessagesDatabase};
+use intl_markdown::message_references;
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// References to other messages like `{@PRODUCT_NAME}` are replaced by the content of that message
+/// when bundling, which fails if the message isn't defined, has block content, or references lead
+/// back to a message that is already being included. This reports those problems for a value of
+/// the message `key` before they break a build.
+pub fn validate_message_references(
+    database: &MessagesDatabase,
+    key: KeySymbol,
+    value: &MessageValue,
+    locale: KeySymbol,
+) -> Vec<ValueDiagnostic> {
+    if message_references(value.parsed()).is_empty() {
+        return vec![];
+    }
+    let Err(error) = database.resolve_message_references(key, value.parsed(), locale) else {
+        return vec![];
+    };
+
+    // The message that contains the problematic reference, and the message it references.
+    let (referencing_key, referenced_key, help) = match &error {
+        DatabaseError::UnknownMessageReference(referencing, referenced) => (
+            *referencing,
+            *referenced,
+            "Check the spelling of the referenced key, or define a message with that key.",
+        ),
+        DatabaseError::BlockMessageReference(referencing, referenced) => (
+            *referencing,
+            *referenced,
+            "Only messages with a single paragraph of content can be referenced from other messages.",
+        ),
+        // Every reference in a cycle is part of the problem, so the one that starts it is used,
+        // which is the one written in this value when the cycle leads back to it.
+        DatabaseError::MessageReferenceCycle(cycle) => (
+            cycle[0],
+            cycle[1],
+            "A message can't include itself, either directly or through the messages it references. Remove one of the references to break the cycle.",
+        ),
+        _ => return vec![],
+    };
+    // The reference can only be pointed to when it's written in this value, rather than in one of
+    // the messages it references.
+    let span = (referencing_key == key)
+        .then(|| value.raw.find(&format!("{{@{referenced_key}}}")))
+        .flatten();
+    vec![ValueDiagnostic {
+        name: DiagnosticName::NoInvalidMessageReferences,
+        span,
+        severity: DiagnosticSeverity::Error,
+        description: error.to_string(),
+        help: Some(help.into()),
+    }]
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, MessageMeta};
+
+    use super::*;
+
+    fn database() -> MessagesDatabase {
+        let mut database = MessagesDatabase::new();
+        let locale = key_symbol("en-US");
+        for (key, value) in [
+            ("PRODUCT_NAME", "Discord"),
+            ("DOCS", "# Docs\n\nRead the docs"),
+            ("LOOP_A", "{@LOOP_B}"),
+            ("LOOP_B", "{@LOOP_A}"),
+        ] {
+            database
+                .insert_definition(
+                    key,
+                    MessageValue::from_raw(value),
+                    locale,
+                    MessageMeta::default(),
+                    true,
+                )
+                .unwrap();
+        }
+        database
+    }
+
+    fn spans(database: &MessagesDatabase, key: &str, raw: &str) -> Vec<Option<usize>> {
+        validate_message_references(
+            database,
+            key_symbol(key),
+            &MessageValue::from_raw(raw),
+            key_symbol("en-US"),
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.span)
+        .collect()
+    }
+
+    #[test]
+    fn test_valid_references() {
+        let database = database();
+        assert_eq!(
+            spans(&database, "GREETING", "Welcome to {@PRODUCT_NAME}"),
+            vec![]
+        );
+        assert_eq!(spans(&database, "GREETING", "Welcome"), vec![]);
+    }
+
+    #[test]
+    fn test_invalid_references() {
+        let database = database();
+        assert_eq!(spans(&database, "GREETING", "Hi {@PRODUCT}"), vec![Some(3)]);
+        assert_eq!(spans(&database, "GREETING", "See {@DOCS}"), vec![Some(4)]);
+        assert_eq!(
+            spans(&database, "LOOP_A", "Back to {@LOOP_B}"),
+            vec![Some(8)]
+        );
+        // The cycle is between other messages, so there's nothing in this value to point to.
+        assert_eq!(spans(&database, "GREETING", "{@LOOP_A}"), vec![None]);
+    }
+}
@@ -0,0 +1,198 @@
+use intl_database_core::MessageValue;
+use intl_markdown::IcuVariable;
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+/// The casing convention that variable names are expected to follow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariableNameCase {
+    /// `userName`, `count`. Only ASCII alphanumerics, starting with a lowercase letter.
+    CamelCase,
+    /// `user_name`, `count`. Only lowercase ASCII alphanumerics and underscores.
+    SnakeCase,
+}
+
+impl VariableNameCase {
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            VariableNameCase::CamelCase => {
+                name.starts_with(|c: char| c.is_ascii_lowercase())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+            VariableNameCase::SnakeCase => name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            VariableNameCase::CamelCase => "camelCase",
+            VariableNameCase::SnakeCase => "snake_case",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoInvalidVariableNamesOptions {
+    /// When set, every variable name must follow the given casing convention.
+    pub case: Option<VariableNameCase>,
+    /// Maximum number of characters allowed in a single variable name.
+    pub max_length: Option<usize>,
+    /// When true, variable names may not start with a digit, like `{0}` or `{1st}`.
+    pub disallow_leading_digits: bool,
+}
+
+impl NoInvalidVariableNamesOptions {
+    pub fn with_case(mut self, case: Option<VariableNameCase>) -> Self {
+        self.case = case;
+        self
+    }
+
+    pub fn with_max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    pub fn with_disallow_leading_digits(mut self, disallow_leading_digits: bool) -> Self {
+        self.disallow_leading_digits = disallow_leading_digits;
+        self
+    }
+}
+
+impl Default for NoInvalidVariableNamesOptions {
+    /// By default, nothing is enforced, since existing messages are not guaranteed to follow any
+    /// convention. Each check has to be opted into.
+    fn default() -> Self {
+        Self {
+            case: None,
+            max_length: None,
+            disallow_leading_digits: false,
+        }
+    }
+}
+
+/// Enforce a consistent naming style for ICU variables, including casing, length, and the
+/// starting character of the name.
+pub struct NoInvalidVariableNames {
+    options: NoInvalidVariableNamesOptions,
+    diagnostics: Vec<ValueDiagnostic>,
+}
+
+impl NoInvalidVariableNames {
+    pub fn with_options(options: NoInvalidVariableNamesOptions) -> Self {
+        Self {
+            options,
+            diagnostics: vec![],
+        }
+    }
+
+    fn report(&mut self, span: Option<usize>, description: String, help: String) {
+        self.diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoInvalidVariableNames,
+            span,
+            severity: DiagnosticSeverity::Warning,
+            description,
+            help: Some(help),
+        });
+    }
+}
+
+impl Validator for NoInvalidVariableNames {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoInvalidVariableNames
+    }
+
+    fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        visit_with_mut(message.parsed(), self);
+        Some(self.diagnostics.clone())
+    }
+}
+
+impl Visit for NoInvalidVariableNames {
+    fn visit_icu_variable(&mut self, node: &IcuVariable) {
+        let name = node.name();
+        let span = node.name_span().map(|span| span.start);
+
+        if self.options.disallow_leading_digits && name.starts_with(|c: char| c.is_ascii_digit()) {
+            self.report(
+                span,
+                "Variable names should not start with a digit".into(),
+                format!("Positional names like \"{name}\" give translators no context about the value. Rename it to describe what it contains."),
+            );
+        }
+
+        if let Some(max_length) = self.options.max_length {
+            let length = name.chars().count();
+            if length > max_length {
+                self.report(
+                    span,
+                    format!("Variable names should be at most {max_length} characters long"),
+                    format!("\"{name}\" is {length} characters long. Use a shorter name."),
+                );
+            }
+        }
+
+        if let Some(case) = self.options.case {
+            if !case.matches(name) {
+                let case_name = case.as_str();
+                self.report(
+                    span,
+                    format!("Variable names should be written in {case_name}"),
+                    format!("Rename \"{name}\" to use {case_name} to match the other messages in this project."),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::MessageValue;
+
+    use super::{NoInvalidVariableNames, NoInvalidVariableNamesOptions, VariableNameCase};
+    use crate::validators::validator::Validator;
+
+    fn spans(options: NoInvalidVariableNamesOptions, raw: &str) -> Vec<Option<usize>> {
+        NoInvalidVariableNames::with_options(options)
+            .validate_ast(&MessageValue::from_raw(raw))
+            .unwrap()
+            .iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_defaults_report_nothing() {
+        let raw = "{0} and {a_very_long_variable_name_that_goes_on_and_on_for_quite_a_while_yes}";
+        assert_eq!(spans(NoInvalidVariableNamesOptions::default(), raw), vec![]);
+    }
+
+    #[test]
+    fn test_leading_digits() {
+        let options = NoInvalidVariableNamesOptions::default().with_disallow_leading_digits(true);
+        assert_eq!(spans(options.clone(), "Hi {name}"), vec![]);
+        assert_eq!(spans(options, "Item: {0}"), vec![Some(7)]);
+    }
+
+    #[test]
+    fn test_max_length() {
+        let options = NoInvalidVariableNamesOptions::default().with_max_length(Some(4));
+        assert_eq!(spans(options.clone(), "{name}"), vec![]);
+        assert_eq!(spans(options, "{name} {names}"), vec![Some(8)]);
+    }
+
+    #[test]
+    fn test_case() {
+        let options =
+            NoInvalidVariableNamesOptions::default().with_case(Some(VariableNameCase::CamelCase));
+        assert_eq!(spans(options.clone(), "{userName}"), vec![]);
+        assert_eq!(
+            spans(options, "user_name: {count, plural, other {{user_name}}}"),
+            vec![Some(35)]
+        );
+    }
+}
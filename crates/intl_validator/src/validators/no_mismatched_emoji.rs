@@ -0,0 +1,130 @@
+use intl_database_core::MessageValue;
+use intl_markdown::{find_emoji_shortcodes, Emoji};
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Collects the name of every emoji shortcode in a message, in source order. Shortcodes are only
+/// parsed into their own nodes when the extension is enabled, so plain text is scanned for them as
+/// well.
+#[derive(Default)]
+struct EmojiCollector {
+    names: Vec<String>,
+}
+
+impl EmojiCollector {
+    fn collect(message: &MessageValue) -> Vec<String> {
+        let mut collector = Self::default();
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.names
+    }
+}
+
+impl Visit for EmojiCollector {
+    fn visit_emoji(&mut self, node: &Emoji) {
+        self.names.push(node.name().to_string());
+    }
+
+    fn visit_text(&mut self, node: &String) {
+        self.names.extend(
+            find_emoji_shortcodes(node).map(|range| node[range.start + 1..range.end - 1].into()),
+        );
+    }
+}
+
+/// Emoji shortcodes like `:smile:` are rendered as emoji by name, so a translated or misspelled
+/// name like `:sourire:` renders as plain text instead. This reports every shortcode from the
+/// source message that is missing from the translation, along with any shortcode that the
+/// translation adds.
+pub fn validate_emoji_shortcodes(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let mut translation_names = EmojiCollector::collect(translation);
+
+    let mut diagnostics = vec![];
+    for name in EmojiCollector::collect(source) {
+        if let Some(index) = translation_names
+            .iter()
+            .position(|candidate| *candidate == name)
+        {
+            translation_names.swap_remove(index);
+            continue;
+        }
+
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedEmoji,
+            span: None,
+            severity: DiagnosticSeverity::Warning,
+            description: format!(
+                "Translation is missing the emoji :{name}: from the source message"
+            ),
+            help: Some("Emoji shortcodes are rendered by name and must not be translated. Check that the shortcode is written exactly as it is in the source message.".into()),
+        });
+    }
+
+    for name in translation_names {
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedEmoji,
+            span: None,
+            severity: DiagnosticSeverity::Warning,
+            description: format!("Translation uses the emoji :{name}:, which is not in the source message"),
+            help: Some("Emoji shortcodes are rendered by name and must not be translated. If this is a translated shortcode, replace it with the one from the source message.".into()),
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use intl_markdown::ParserOptions;
+
+    use super::*;
+
+    fn descriptions(source: &str, translation: &str, parse_emoji: bool) -> Vec<String> {
+        let value = |raw: &str| {
+            let mut value = MessageValue::from_raw(raw);
+            let options = ParserOptions::default().with_emoji_shortcodes(parse_emoji);
+            value.set_parser_options(Some(Arc::new(options)));
+            value
+        };
+        validate_emoji_shortcodes(&value(source), &value(translation))
+            .into_iter()
+            .map(|diagnostic| diagnostic.description)
+            .collect()
+    }
+
+    #[test]
+    fn test_matching_emoji() {
+        for parse_emoji in [true, false] {
+            assert_eq!(
+                descriptions("Nice :wave: :smile:", ":smile: Salut :wave:", parse_emoji),
+                Vec::<String>::new()
+            );
+            assert_eq!(
+                descriptions("At 10:30", "À 10:30", parse_emoji),
+                Vec::<String>::new()
+            );
+        }
+    }
+
+    #[test]
+    fn test_mismatched_emoji() {
+        for parse_emoji in [true, false] {
+            assert_eq!(
+                descriptions(
+                    "Nice :smile: :smile:",
+                    "Super :sourire: :smile:",
+                    parse_emoji
+                ),
+                vec![
+                    "Translation is missing the emoji :smile: from the source message",
+                    "Translation uses the emoji :sourire:, which is not in the source message",
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use intl_database_core::MessageValue;
+use intl_markdown::{find_keyboard_keys, KeyboardKey};
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Collects the name of every keyboard key in a message, in source order. Keys are only parsed
+/// into their own nodes when the extension is enabled, so plain text is scanned for them as well.
+#[derive(Default)]
+struct KeyboardKeyCollector {
+    names: Vec<String>,
+}
+
+impl KeyboardKeyCollector {
+    fn collect(message: &MessageValue) -> Vec<String> {
+        let mut collector = Self::default();
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.names
+    }
+}
+
+impl Visit for KeyboardKeyCollector {
+    fn visit_keyboard_key(&mut self, node: &KeyboardKey) {
+        self.names.push(node.name().to_string());
+    }
+
+    fn visit_text(&mut self, node: &String) {
+        self.names
+            .extend(find_keyboard_keys(node).map(|range| node[range].to_string()));
+    }
+}
+
+/// Keyboard keys like `[[Ctrl]]` name the key that is printed on the keyboard or shown in the
+/// app, so they need to stay the same in every translation. This reports every key from the source
+/// message that is missing from the translation, along with any key that only the translation
+/// uses, which is usually a translated version of one of the source keys.
+pub fn validate_keyboard_keys(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let mut translation_names = KeyboardKeyCollector::collect(translation);
+
+    let mut diagnostics = vec![];
+    for name in KeyboardKeyCollector::collect(source) {
+        if let Some(index) = translation_names
+            .iter()
+            .position(|candidate| *candidate == name)
+        {
+            translation_names.swap_remove(index);
+            continue;
+        }
+
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedKeyboardKeys,
+            span: None,
+            severity: DiagnosticSeverity::Warning,
+            description: format!("Translation is missing the key [[{name}]] from the source message"),
+            help: Some("Key names refer to the keys and interface elements themselves and must not be translated. Check that the key is written exactly as it is in the source message.".into()),
+        });
+    }
+
+    for name in translation_names {
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedKeyboardKeys,
+            span: None,
+            severity: DiagnosticSeverity::Warning,
+            description: format!("Translation uses the key [[{name}]], which is not in the source message"),
+            help: Some("Key names refer to the keys and interface elements themselves and must not be translated. If this is a translated key name, replace it with the one from the source message.".into()),
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use intl_markdown::ParserOptions;
+
+    use super::*;
+
+    fn descriptions(source: &str, translation: &str, parse_keys: bool) -> Vec<String> {
+        let value = |raw: &str| {
+            let mut value = MessageValue::from_raw(raw);
+            let options = ParserOptions::default().with_keyboard_keys(parse_keys);
+            value.set_parser_options(Some(Arc::new(options)));
+            value
+        };
+        validate_keyboard_keys(&value(source), &value(translation))
+            .into_iter()
+            .map(|diagnostic| diagnostic.description)
+            .collect()
+    }
+
+    #[test]
+    fn test_matching_keys() {
+        for parse_keys in [true, false] {
+            assert_eq!(
+                descriptions(
+                    "Press [[Ctrl]] + [[Page Up]]",
+                    "Appuyez sur [[Ctrl]] + [[Page Up]]",
+                    parse_keys
+                ),
+                Vec::<String>::new()
+            );
+        }
+    }
+
+    #[test]
+    fn test_mismatched_keys() {
+        for parse_keys in [true, false] {
+            assert_eq!(
+                descriptions("Press [[Enter]]", "Appuyez sur [[Entrée]]", parse_keys),
+                vec![
+                    "Translation is missing the key [[Enter]] from the source message",
+                    "Translation uses the key [[Entrée]], which is not in the source message",
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use intl_database_core::MessageValue;
+use intl_markdown::{Link, LinkDestination};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// A link destination written in a message, along with where it starts in the raw content.
+struct LinkDestinationEntry {
+    url: String,
+    span: Option<usize>,
+}
+
+/// Collects the destination of every link in a message whose destination is written as plain
+/// text, in source order. Destinations given by placeholders or handlers are checked along with
+/// the rest of the message's variables instead.
+struct LinkDestinationCollector<'a> {
+    raw: &'a str,
+    /// The position in `raw` to continue searching for destinations from, since links are
+    /// visited in the same order they are written.
+    cursor: usize,
+    destinations: Vec<LinkDestinationEntry>,
+}
+
+impl<'a> LinkDestinationCollector<'a> {
+    fn collect(message: &'a MessageValue) -> Vec<LinkDestinationEntry> {
+        let mut collector = Self {
+            raw: message.raw.as_str(),
+            cursor: 0,
+            destinations: vec![],
+        };
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.destinations
+    }
+
+    /// Find where `url` is written in the raw content. Destinations that were unescaped while
+    /// parsing won't match the raw content exactly, and are left without a span.
+    fn find_span(&mut self, url: &str) -> Option<usize> {
+        let start = self.cursor + self.raw.get(self.cursor..)?.find(url)?;
+        self.cursor = start + url.len();
+        Some(start)
+    }
+}
+
+impl Visit for LinkDestinationCollector<'_> {
+    fn visit_link(&mut self, node: &Link) {
+        if let LinkDestination::Text(url) = node.destination() {
+            // The url can also be written in the text before the link, which isn't the
+            // destination.
+            if let Some(link) = node.span() {
+                self.cursor = self.cursor.max(link.start);
+            }
+            let span = self.find_span(url);
+            self.destinations.push(LinkDestinationEntry {
+                url: url.clone(),
+                span,
+            });
+        }
+        node.visit_children_with(self);
+    }
+}
+
+/// Links in translations need to point to the same places as the links in the source message,
+/// since the pages they lead to are usually already localized on their own. This reports every
+/// link destination that was changed in the translation, along with any that were dropped from or
+/// added to it. Destinations are compared in order, so a changed url is reported against the
+/// first unmatched destination in the source message.
+pub fn validate_link_destinations(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let mut added = LinkDestinationCollector::collect(translation);
+
+    let mut removed = vec![];
+    for entry in LinkDestinationCollector::collect(source) {
+        match added
+            .iter()
+            .position(|candidate| candidate.url == entry.url)
+        {
+            Some(index) => {
+                added.remove(index);
+            }
+            None => removed.push(entry.url),
+        }
+    }
+
+    let mut diagnostics = vec![];
+    let mut added = added.into_iter();
+    for url in removed {
+        match added.next() {
+            Some(entry) => diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoMismatchedLinkDestinations,
+                span: entry.span,
+                severity: DiagnosticSeverity::Warning,
+                description: format!("Translation links to \"{}\", but the source message links to \"{url}\"", entry.url),
+                help: Some("Link destinations need to be kept exactly as they are written in the source message. Check that the url was not translated or changed.".into()),
+            }),
+            None => diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoMismatchedLinkDestinations,
+                span: None,
+                severity: DiagnosticSeverity::Warning,
+                description: format!("Translation is missing the link to \"{url}\" from the source message"),
+                help: Some("Check that the link was not removed or turned into plain text while translating.".into()),
+            }),
+        }
+    }
+
+    for entry in added {
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedLinkDestinations,
+            span: entry.span,
+            severity: DiagnosticSeverity::Warning,
+            description: format!(
+                "Translation links to \"{}\", which is not in the source message",
+                entry.url
+            ),
+            help: Some(
+                "Translations should only link to the destinations used in the source message."
+                    .into(),
+            ),
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(source: &str, translation: &str) -> Vec<Option<usize>> {
+        validate_link_destinations(
+            &MessageValue::from_raw(source),
+            &MessageValue::from_raw(translation),
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.span)
+        .collect()
+    }
+
+    #[test]
+    fn test_matching_destinations() {
+        assert_eq!(
+            spans(
+                "[Docs](https://discord.com/docs) and [help](onClick)",
+                "[aide](onHelp) et [docs](https://discord.com/docs)"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_changed_destinations() {
+        // The span points to the destination, even when the same url is in the text before it.
+        assert_eq!(
+            spans(
+                "[Docs](https://discord.com/docs)",
+                "https://discord.com/fr [Docs](https://discord.com/fr)"
+            ),
+            vec![Some(30)]
+        );
+    }
+
+    #[test]
+    fn test_missing_and_added_destinations() {
+        assert_eq!(
+            spans("[Docs](https://discord.com/docs)", "Docs"),
+            vec![None]
+        );
+        assert_eq!(
+            spans("Docs", "[Docs](https://discord.com/docs)"),
+            vec![Some(7)]
+        );
+    }
+}
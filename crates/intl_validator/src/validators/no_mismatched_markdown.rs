@@ -0,0 +1,138 @@
+use intl_database_core::MessageValue;
+use intl_markdown::{CodeSpan, Emphasis, Link, Strong};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Characters that, when left over as plain text, indicate that some markdown syntax failed to
+/// find its matching delimiter.
+const DELIMITER_CHARS: [char; 5] = ['*', '_', '`', '[', ']'];
+
+/// Counts of the structural markdown elements in a message.
+#[derive(Default)]
+struct MarkdownStructure {
+    emphasis: usize,
+    strong: usize,
+    code_spans: usize,
+    links: usize,
+    /// Text content that contains a character which could have been a markdown delimiter.
+    delimiter_texts: Vec<String>,
+}
+
+impl MarkdownStructure {
+    fn collect(message: &MessageValue) -> Self {
+        let mut structure = Self::default();
+        visit_with_mut(message.parsed(), &mut structure);
+        structure
+    }
+
+    fn counts(&self) -> [(&'static str, usize); 4] {
+        [
+            ("emphasis", self.emphasis),
+            ("strong", self.strong),
+            ("code span", self.code_spans),
+            ("link", self.links),
+        ]
+    }
+}
+
+impl Visit for MarkdownStructure {
+    fn visit_code_span(&mut self, _node: &CodeSpan) {
+        self.code_spans += 1;
+    }
+
+    fn visit_emphasis(&mut self, node: &Emphasis) {
+        self.emphasis += 1;
+        node.visit_children_with(self);
+    }
+
+    fn visit_link(&mut self, node: &Link) {
+        self.links += 1;
+        node.visit_children_with(self);
+    }
+
+    fn visit_strong(&mut self, node: &Strong) {
+        self.strong += 1;
+        node.visit_children_with(self);
+    }
+
+    fn visit_text(&mut self, node: &String) {
+        if node.contains(DELIMITER_CHARS) {
+            self.delimiter_texts.push(node.clone());
+        }
+    }
+}
+
+/// Find the position in the raw translation of the first delimiter that was left as plain text,
+/// which is most likely the one that lost its pair.
+fn find_stray_delimiter(raw: &str, delimiter_texts: &[String]) -> Option<usize> {
+    delimiter_texts.iter().find_map(|text| {
+        let text_start = raw.find(text.as_str())?;
+        let delimiter_offset = text.find(DELIMITER_CHARS)?;
+        Some(text_start + delimiter_offset)
+    })
+}
+
+/// Translations frequently drop one half of a markdown delimiter pair, like a single `*` from
+/// `**bold**`, which then renders as literal characters instead of formatting. This compares the
+/// number of each structural markdown element between the source and translation, and reports
+/// any kind whose count differs.
+pub fn validate_markdown_structure(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let source_structure = MarkdownStructure::collect(source);
+    let translation_structure = MarkdownStructure::collect(translation);
+    let span = find_stray_delimiter(&translation.raw, &translation_structure.delimiter_texts);
+
+    source_structure
+        .counts()
+        .into_iter()
+        .zip(translation_structure.counts())
+        .filter(|((_, source_count), (_, translation_count))| source_count != translation_count)
+        .map(|((kind, source_count), (_, translation_count))| ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedMarkdown,
+            span,
+            severity: DiagnosticSeverity::Warning,
+            description: format!("Translation has a different number of {kind} elements than the source message"),
+            help: Some(format!("The source message has {source_count} {kind} element(s), but this translation has {translation_count}. Check that every markdown delimiter in the translation has a matching pair.")),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(source: &str, translation: &str) -> Vec<Option<usize>> {
+        validate_markdown_structure(
+            &MessageValue::from_raw(source),
+            &MessageValue::from_raw(translation),
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.span)
+        .collect()
+    }
+
+    #[test]
+    fn test_matching_structure() {
+        assert_eq!(
+            spans(
+                "**Bold** *and* `code` [link](onClick)",
+                "[lien](onClick) `code` **Gras** *et*"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_lost_delimiters() {
+        assert_eq!(
+            spans("Some **bold** text", "Du texte **gras"),
+            vec![Some(9)]
+        );
+        // Each kind of element with a different count is reported.
+        assert_eq!(spans("*a* `b`", "a b"), vec![None, None]);
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use intl_database_core::MessageValue;
+use intl_markdown::{IcuPlural, IcuPluralArm, IcuPluralKind, InlineContent};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoMismatchedPluralFormsOptions {
+    /// Suffixes that mark a word as plural for each language, like `s` in `items` for `en`. Keys
+    /// are either a language, like `en`, or a full locale, like `en-GB`, which takes precedence
+    /// over its language. Locales without any suffixes are not checked.
+    pub plural_suffixes: HashMap<String, Vec<String>>,
+}
+
+impl Default for NoMismatchedPluralFormsOptions {
+    fn default() -> Self {
+        let plural_suffixes = [
+            ("en", &["s"][..]),
+            ("es", &["s"]),
+            ("fr", &["s", "x"]),
+            ("pt", &["s"]),
+        ];
+        Self {
+            plural_suffixes: plural_suffixes
+                .into_iter()
+                .map(|(language, suffixes)| {
+                    let suffixes = suffixes.iter().map(|suffix| suffix.to_string()).collect();
+                    (language.to_string(), suffixes)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl NoMismatchedPluralFormsOptions {
+    /// Set the plural suffixes for each of the given languages or locales, keeping the defaults
+    /// for any that aren't given. An empty list disables the check for that language.
+    pub fn with_plural_suffixes(mut self, plural_suffixes: HashMap<String, Vec<String>>) -> Self {
+        self.plural_suffixes.extend(plural_suffixes);
+        self
+    }
+
+    fn suffixes_for_locale(&self, locale: &str) -> &[String] {
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        self.plural_suffixes
+            .get(locale)
+            .or_else(|| self.plural_suffixes.get(language))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Return the word that directly follows the `#` in a plural arm, like `items` in `# items`.
+fn word_after_pound(arm: &IcuPluralArm) -> Option<String> {
+    let mut content = arm.content().iter();
+    content.find(|element| matches!(element, InlineContent::IcuPound))?;
+    let InlineContent::Text(text) = content.next()? else {
+        return None;
+    };
+    let word: String = text
+        .strip_prefix(' ')?
+        .chars()
+        .take_while(|c| c.is_alphabetic())
+        .collect();
+    (!word.is_empty()).then_some(word)
+}
+
+struct PluralFormsVisitor<'a> {
+    suffixes: &'a [String],
+    diagnostics: Vec<ValueDiagnostic>,
+}
+
+impl PluralFormsVisitor<'_> {
+    fn is_plural_word(&self, word: &str) -> bool {
+        let lowercase = word.to_lowercase();
+        self.suffixes
+            .iter()
+            .any(|suffix| lowercase.len() > suffix.len() && lowercase.ends_with(suffix.as_str()))
+    }
+
+    fn add(&mut self, plural: &IcuPlural, selector: &str, word: &str, expected: &str) {
+        let plural_name = plural.name();
+        self.diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedPluralForms,
+            // Plural arms don't keep their position, so the plural's name is reported instead.
+            span: plural.variable().name_span().map(|span| span.start),
+            severity: DiagnosticSeverity::Warning,
+            description: format!("The '{selector}' option of '{plural_name}' uses \"{word}\", which looks like the wrong form for the option"),
+            help: Some(format!("The '{selector}' option is usually written with the {expected} form of the word. Check that the options of the plural weren't swapped or copied from each other.")),
+        });
+    }
+}
+
+impl Visit for PluralFormsVisitor<'_> {
+    fn visit_icu_plural(&mut self, node: &IcuPlural) {
+        node.visit_children_with(self);
+        if !matches!(node.kind(), IcuPluralKind::Plural) {
+            return;
+        }
+
+        let find_word = |selector: &str| {
+            node.arms()
+                .iter()
+                .find(|arm| arm.selector() == selector)
+                .and_then(word_after_pound)
+        };
+        let one = find_word("one");
+        let other = find_word("other");
+
+        // `one {# bus} other {# buses}` is fine even though `bus` ends like a plural, so a word
+        // is only treated as plural when the `other` option doesn't extend it any further.
+        if let Some(one) = &one {
+            let is_extended = other
+                .as_ref()
+                .is_some_and(|other| other.len() > one.len() && other.starts_with(one.as_str()));
+            if self.is_plural_word(one) && !is_extended {
+                self.add(node, "one", one, "singular");
+            }
+        }
+        // Likewise, `other` is only flagged when it repeats the `one` word (or a shorter form of
+        // it), since irregular plurals like `child` and `children` can't be matched by suffix.
+        if let (Some(one), Some(other)) = (&one, &other) {
+            if !self.is_plural_word(other) && one.starts_with(other.as_str()) {
+                self.add(node, "other", other, "plural");
+            }
+        }
+    }
+}
+
+/// Plurals like `{count, plural, one {# item} other {# items}}` are easy to get backwards or to
+/// copy from one option to the other, and nothing in the syntax catches it. Using the suffixes
+/// that mark words as plural in the translation's locale, this flags `one` options that use a
+/// plural-looking word and `other` options that repeat the singular word. Since it is only a
+/// heuristic, locales without any configured suffixes are skipped entirely.
+pub fn validate_plural_forms(
+    translation: &MessageValue,
+    locale: &str,
+    options: &NoMismatchedPluralFormsOptions,
+) -> Vec<ValueDiagnostic> {
+    let suffixes = options.suffixes_for_locale(locale);
+    if suffixes.is_empty() {
+        return vec![];
+    }
+
+    let mut visitor = PluralFormsVisitor {
+        suffixes,
+        diagnostics: vec![],
+    };
+    visit_with_mut(translation.parsed(), &mut visitor);
+    visitor.diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(
+        raw: &str,
+        locale: &str,
+        options: &NoMismatchedPluralFormsOptions,
+    ) -> Vec<Option<usize>> {
+        validate_plural_forms(&MessageValue::from_raw(raw), locale, options)
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_matching_forms() {
+        let options = NoMismatchedPluralFormsOptions::default();
+        for raw in [
+            "{count, plural, one {# item} other {# items}}",
+            "{count, plural, one {# bus} other {# buses}}",
+            "{count, plural, one {# child} other {# children}}",
+            "{count, plural, other {# items}}",
+        ] {
+            assert_eq!(spans(raw, "en-US", &options), vec![], "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_swapped_forms() {
+        let options = NoMismatchedPluralFormsOptions::default();
+        let raw = "You have {count, plural, one {# items} other {# item}}";
+        assert_eq!(spans(raw, "en-US", &options), vec![Some(10), Some(10)]);
+        let raw = "{count, plural, one {# chevaux} other {# chevaux}}";
+        assert_eq!(spans(raw, "fr", &options), vec![Some(1)]);
+    }
+
+    #[test]
+    fn test_ordinals_and_selects_are_skipped() {
+        let options = NoMismatchedPluralFormsOptions::default();
+        let raw = "{count, selectordinal, one {# items} other {# item}}";
+        assert_eq!(spans(raw, "en-US", &options), vec![]);
+        let raw = "{who, select, one {items} other {item}}";
+        assert_eq!(spans(raw, "en-US", &options), vec![]);
+    }
+
+    #[test]
+    fn test_configured_suffixes() {
+        let raw = "{count, plural, one {# items} other {# items}}";
+        let options = NoMismatchedPluralFormsOptions::default();
+        assert_eq!(spans(raw, "de", &options), vec![]);
+
+        let options = options.with_plural_suffixes(HashMap::from([
+            ("en-GB".to_string(), vec![]),
+            ("de".to_string(), vec!["s".to_string()]),
+        ]));
+        assert_eq!(spans(raw, "de", &options), vec![Some(1)]);
+        assert_eq!(spans(raw, "en-GB", &options), vec![]);
+        assert_eq!(spans(raw, "en-US", &options), vec![Some(1)]);
+    }
+}
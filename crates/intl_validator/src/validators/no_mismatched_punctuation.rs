@@ -0,0 +1,237 @@
+use intl_database_core::MessageValue;
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PunctuationClass {
+    Period,
+    Ellipsis,
+    Colon,
+    Question,
+    Exclamation,
+}
+
+impl PunctuationClass {
+    fn description(self) -> &'static str {
+        match self {
+            PunctuationClass::Period => "a period",
+            PunctuationClass::Ellipsis => "an ellipsis",
+            PunctuationClass::Colon => "a colon",
+            PunctuationClass::Question => "a question mark",
+            PunctuationClass::Exclamation => "an exclamation mark",
+        }
+    }
+}
+
+/// Every way that each class of punctuation is written across scripts, so that `...` and `…` or
+/// `.` and `。` count as the same punctuation. Longer forms come first so that an ellipsis isn't
+/// mistaken for a period.
+const PUNCTUATION_FORMS: &[(&str, PunctuationClass)] = &[
+    ("...", PunctuationClass::Ellipsis),
+    ("。。。", PunctuationClass::Ellipsis),
+    ("…", PunctuationClass::Ellipsis),
+    ("⋯", PunctuationClass::Ellipsis),
+    (".", PunctuationClass::Period),
+    ("。", PunctuationClass::Period),
+    ("｡", PunctuationClass::Period),
+    ("।", PunctuationClass::Period),
+    ("۔", PunctuationClass::Period),
+    ("։", PunctuationClass::Period),
+    ("።", PunctuationClass::Period),
+    ("។", PunctuationClass::Period),
+    ("။", PunctuationClass::Period),
+    (":", PunctuationClass::Colon),
+    ("：", PunctuationClass::Colon),
+    ("?", PunctuationClass::Question),
+    ("？", PunctuationClass::Question),
+    ("؟", PunctuationClass::Question),
+    ("!", PunctuationClass::Exclamation),
+    ("！", PunctuationClass::Exclamation),
+];
+
+/// Greek writes its question mark like a semicolon, either as the ASCII character or as the
+/// dedicated Greek question mark that normalizes to it.
+const GREEK_PUNCTUATION_FORMS: &[(&str, PunctuationClass)] = &[
+    (";", PunctuationClass::Question),
+    ("\u{037E}", PunctuationClass::Question),
+];
+
+/// Characters that wrap text without being part of its punctuation, like markdown emphasis and
+/// quotes, which are skipped to find the punctuation at the edge of the text inside of them.
+/// Spanish opening marks are also skipped, since only the closing mark has a source equivalent.
+const LEADING_WRAPPERS: &[char] = &[
+    '*', '_', '~', '"', '\'', '“', '‘', '«', '「', '『', '(', '（', '¿', '¡',
+];
+const TRAILING_WRAPPERS: &[char] = &[
+    '*', '_', '~', '"', '\'', '”', '’', '»', '」', '』', ')', '）',
+];
+
+/// Thai and Lao end sentences with a space rather than a period, so a missing period isn't a
+/// mismatch there.
+fn omits_periods(language: &str) -> bool {
+    matches!(language, "th" | "lo")
+}
+
+fn punctuation_forms(language: &str) -> Vec<(&'static str, PunctuationClass)> {
+    let mut forms = PUNCTUATION_FORMS.to_vec();
+    if language == "el" {
+        forms.extend_from_slice(GREEK_PUNCTUATION_FORMS);
+    }
+    forms
+}
+
+/// Return the byte offset and class of the punctuation that `content` starts with, if any.
+fn leading_punctuation(
+    content: &str,
+    forms: &[(&str, PunctuationClass)],
+) -> Option<(usize, PunctuationClass)> {
+    let rest =
+        content.trim_start_matches(|c: char| c.is_whitespace() || LEADING_WRAPPERS.contains(&c));
+    let offset = content.len() - rest.len();
+    forms
+        .iter()
+        .find(|(form, _)| rest.starts_with(form))
+        .map(|(_, class)| (offset, *class))
+}
+
+/// Return the byte offset and class of the punctuation that `content` ends with, if any.
+fn trailing_punctuation(
+    content: &str,
+    forms: &[(&str, PunctuationClass)],
+) -> Option<(usize, PunctuationClass)> {
+    let rest =
+        content.trim_end_matches(|c: char| c.is_whitespace() || TRAILING_WRAPPERS.contains(&c));
+    forms
+        .iter()
+        .find(|(form, _)| rest.ends_with(form))
+        .map(|(form, class)| (rest.len() - form.len(), *class))
+}
+
+fn punctuation_diagnostic(
+    edge: &str,
+    source: Option<PunctuationClass>,
+    translation: Option<PunctuationClass>,
+    span: usize,
+) -> ValueDiagnostic {
+    let description = match (source, translation) {
+        (Some(source), Some(translation)) => format!(
+            "Translation {edge}s with {}, but the source message {edge}s with {}",
+            translation.description(),
+            source.description()
+        ),
+        (Some(source), None) => format!(
+            "Translation is missing {} that the source message {edge}s with",
+            source.description()
+        ),
+        (None, Some(translation)) => format!(
+            "Translation {edge}s with {}, but the source message does not",
+            translation.description()
+        ),
+        (None, None) => unreachable!("punctuation diagnostics are only created for mismatches"),
+    };
+    ValueDiagnostic {
+        name: DiagnosticName::NoMismatchedPunctuation,
+        span: Some(span),
+        severity: DiagnosticSeverity::Warning,
+        description,
+        help: Some("Punctuation at the start or end of a message often matters to how it's shown, like a colon before a value or an ellipsis on an action that opens a dialog. Use the same punctuation as the source message, written the way this locale writes it.".into()),
+    }
+}
+
+/// Translations often drop the punctuation that a source message starts or ends with, or add some
+/// that it doesn't have. This compares the class of that punctuation between the source and the
+/// translation, treating the different ways that scripts write each class as the same, and
+/// reports each edge where they differ.
+pub fn validate_edge_punctuation(
+    source: &MessageValue,
+    translation: &MessageValue,
+    locale: &str,
+) -> Vec<ValueDiagnostic> {
+    let language = locale.split(['-', '_']).next().unwrap_or(locale);
+    let source_forms = PUNCTUATION_FORMS;
+    let translation_forms = punctuation_forms(language);
+    let source_content = source.raw.as_str();
+    let translation_content = translation.raw.as_str();
+
+    let mut diagnostics = vec![];
+    let source_leading = leading_punctuation(source_content, source_forms);
+    let translation_leading = leading_punctuation(translation_content, &translation_forms);
+    let source_class = source_leading.map(|(_, class)| class);
+    let translation_class = translation_leading.map(|(_, class)| class);
+    if source_class != translation_class {
+        let span = translation_leading.map_or_else(
+            || translation_content.len() - translation_content.trim_start().len(),
+            |(offset, _)| offset,
+        );
+        diagnostics.push(punctuation_diagnostic(
+            "start",
+            source_class,
+            translation_class,
+            span,
+        ));
+    }
+
+    let source_trailing = trailing_punctuation(source_content, source_forms);
+    let translation_trailing = trailing_punctuation(translation_content, &translation_forms);
+    let source_class = source_trailing.map(|(_, class)| class);
+    let translation_class = translation_trailing.map(|(_, class)| class);
+    let is_omitted_period = source_class == Some(PunctuationClass::Period)
+        && translation_class.is_none()
+        && omits_periods(language);
+    if source_class != translation_class && !is_omitted_period {
+        let span = translation_trailing.map_or_else(
+            || translation_content.trim_end().len(),
+            |(offset, _)| offset,
+        );
+        diagnostics.push(punctuation_diagnostic(
+            "end",
+            source_class,
+            translation_class,
+            span,
+        ));
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(source: &str, translation: &str, locale: &str) -> Vec<Option<usize>> {
+        validate_edge_punctuation(
+            &MessageValue::from_raw(source),
+            &MessageValue::from_raw(translation),
+            locale,
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.span)
+        .collect()
+    }
+
+    #[test]
+    fn test_matching_punctuation() {
+        assert_eq!(spans("Name:", "Nom :", "fr"), vec![]);
+        assert_eq!(spans("Loading...", "読み込み中…", "ja"), vec![]);
+        assert_eq!(spans("Done.", "完了。", "ja"), vec![]);
+        assert_eq!(spans("**Really?**", "¿**De verdad?**", "es"), vec![]);
+        assert_eq!(spans("Ready?", "Έτοιμο;", "el"), vec![]);
+    }
+
+    #[test]
+    fn test_mismatched_punctuation() {
+        // Missing, added, and different punctuation at the end.
+        assert_eq!(spans("Hello.", "Bonjour", "fr"), vec![Some(7)]);
+        assert_eq!(spans("Hello", "¡Hola!", "es"), vec![Some(6)]);
+        assert_eq!(spans("Name:", "Nom.", "fr"), vec![Some(3)]);
+        assert_eq!(spans("...and more", "et plus", "fr"), vec![Some(0)]);
+        // Only Greek writes a question mark like a semicolon.
+        assert_eq!(spans("Ready?", "Prêt;", "fr"), vec![Some(6)]);
+    }
+
+    #[test]
+    fn test_omitted_periods() {
+        assert_eq!(spans("Done.", "เสร็จ", "th"), vec![]);
+        assert_eq!(spans("Done!", "เสร็จ", "th"), vec![Some(15)]);
+    }
+}
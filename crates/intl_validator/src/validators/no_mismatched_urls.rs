@@ -0,0 +1,100 @@
+use intl_database_core::MessageValue;
+use intl_markdown::find_bare_urls;
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Collects every bare url written in the text of a message, in source order.
+#[derive(Default)]
+struct BareUrlCollector {
+    urls: Vec<String>,
+}
+
+impl BareUrlCollector {
+    fn collect(message: &MessageValue) -> Vec<String> {
+        let mut collector = Self::default();
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.urls
+    }
+}
+
+impl Visit for BareUrlCollector {
+    fn visit_text(&mut self, node: &String) {
+        self.urls
+            .extend(find_bare_urls(node).map(|range| node[range].to_string()));
+    }
+}
+
+/// Bare urls like `https://discord.com/safety` can be turned into links automatically when
+/// messages are compiled, so they have to be kept exactly as written for the link to keep working.
+/// Translators sometimes translate part of the path or drop the url entirely, so this reports
+/// every url from the source message that doesn't appear in the translation.
+pub fn validate_bare_urls(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let mut translation_urls = BareUrlCollector::collect(translation);
+
+    let mut diagnostics = vec![];
+    for url in BareUrlCollector::collect(source) {
+        if let Some(index) = translation_urls
+            .iter()
+            .position(|candidate| *candidate == url)
+        {
+            translation_urls.swap_remove(index);
+            continue;
+        }
+
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedUrls,
+            span: None,
+            severity: DiagnosticSeverity::Warning,
+            description: format!("Translation is missing the url \"{url}\" from the source message"),
+            help: Some("Urls are linked automatically and need to be kept exactly as they are written in the source message. Check that the url was not translated or changed.".into()),
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn descriptions(source: &str, translation: &str) -> Vec<String> {
+        validate_bare_urls(
+            &MessageValue::from_raw(source),
+            &MessageValue::from_raw(translation),
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.description)
+        .collect()
+    }
+
+    #[test]
+    fn test_matching_urls() {
+        assert_eq!(
+            descriptions(
+                "See https://discord.com/safety or www.discord.com",
+                "Voir www.discord.com ou https://discord.com/safety"
+            ),
+            Vec::<String>::new()
+        );
+        // Urls that only the translation has aren't reported.
+        assert_eq!(
+            descriptions("Hello", "Bonjour https://discord.com"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_missing_urls() {
+        assert_eq!(
+            descriptions(
+                "See https://discord.com/safety",
+                "Voir https://discord.com/securite"
+            ),
+            vec!["Translation is missing the url \"https://discord.com/safety\" from the source message"]
+        );
+    }
+}
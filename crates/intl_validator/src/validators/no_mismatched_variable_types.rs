@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use intl_database_core::{key_symbol, MessageValue, MessageVariableType};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Returns true if a variable used as `inferred` in a message can be given a value of the
+/// `declared` type. Variables whose type couldn't be inferred accept anything, and plurals are
+/// formatted from numbers, so the two are interchangeable.
+fn is_compatible(declared: &MessageVariableType, inferred: &MessageVariableType) -> bool {
+    match (declared, inferred) {
+        (_, MessageVariableType::Any) | (MessageVariableType::Any, _) => true,
+        (MessageVariableType::Number(_), MessageVariableType::Plural)
+        | (MessageVariableType::Plural, MessageVariableType::Number(_)) => true,
+        (declared, inferred) => declared.kind_name() == inferred.kind_name(),
+    }
+}
+
+/// Types declared in a message's meta replace the types inferred from the message when
+/// generating types, so a declaration that disagrees with how the variable is actually used means
+/// callers will be asked for a value that the message can't format, like a `Date` for
+/// `{count, plural, ...}`.
+///
+/// This reports every declared variable that is used as an incompatible kind of variable in the
+/// given message value.
+pub fn validate_declared_variable_types(
+    value: &MessageValue,
+    declared: &BTreeMap<String, MessageVariableType>,
+) -> Vec<ValueDiagnostic> {
+    let Some(variables) = value.variables() else {
+        return vec![];
+    };
+
+    let mut diagnostics = vec![];
+    for (name, declared_kind) in declared {
+        let Some(instances) = variables.get(&key_symbol(name)) else {
+            continue;
+        };
+        let Some(instance) = instances
+            .iter()
+            .find(|instance| !is_compatible(declared_kind, &instance.kind))
+        else {
+            continue;
+        };
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedVariableTypes,
+            span: instance.span,
+            severity: DiagnosticSeverity::Warning,
+            description: format!(
+                "'{name}' is declared as a {} variable, but is used as a {} variable",
+                declared_kind.kind_name(),
+                instance.kind.kind_name(),
+            ),
+            help: Some(format!(
+                "Either update the declared type in the message's meta, or use the variable like {}.",
+                declared_kind.example_usage(name)
+            )),
+        });
+    }
+    diagnostics
+}
+
+/// Declaring the type of a variable that the source message doesn't use has no effect, and
+/// usually means the variable was renamed or removed without updating its declaration.
+pub fn validate_unused_variable_types(
+    source: &MessageValue,
+    declared: &BTreeMap<String, MessageVariableType>,
+) -> Vec<ValueDiagnostic> {
+    declared
+        .keys()
+        .filter(|name| {
+            !source
+                .variables()
+                .is_some_and(|variables| variables.contains_key(&key_symbol(name)))
+        })
+        .map(|name| ValueDiagnostic {
+            name: DiagnosticName::NoMismatchedVariableTypes,
+            span: None,
+            severity: DiagnosticSeverity::Warning,
+            description: format!(
+                "'{name}' has a declared type, but is not used in the source message"
+            ),
+            help: Some(
+                "Remove the declaration, or check that the variable was not renamed.".into(),
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn declared(types: &[(&str, MessageVariableType)]) -> BTreeMap<String, MessageVariableType> {
+        types
+            .iter()
+            .map(|(name, kind)| (name.to_string(), kind.clone()))
+            .collect()
+    }
+
+    fn spans(raw: &str, types: &[(&str, MessageVariableType)]) -> Vec<Option<usize>> {
+        validate_declared_variable_types(&MessageValue::from_raw(raw), &declared(types))
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_compatible_declarations() {
+        let types = [
+            ("count", MessageVariableType::Number(None)),
+            ("when", MessageVariableType::Date),
+        ];
+        let raw = "{count, plural, other {# items}} on {when, date}";
+        assert_eq!(spans(raw, &types), vec![]);
+        // Variables that don't infer a type accept any declaration.
+        assert_eq!(spans("{count} on {when}", &types), vec![]);
+    }
+
+    #[test]
+    fn test_incompatible_declarations() {
+        let types = [("when", MessageVariableType::Date)];
+        assert_eq!(
+            spans("Sent {when} at {when, number}", &types),
+            vec![Some(16)]
+        );
+    }
+
+    #[test]
+    fn test_unused_declarations() {
+        let types = declared(&[
+            ("count", MessageVariableType::Number(None)),
+            ("total", MessageVariableType::Number(None)),
+        ]);
+        let source = MessageValue::from_raw("{count, number} items");
+        let diagnostics = validate_unused_variable_types(&source, &types);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].description.starts_with("'total'"));
+    }
+}
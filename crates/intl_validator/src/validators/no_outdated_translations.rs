@@ -0,0 +1,73 @@
+use intl_database_core::{KeySymbol, Message};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// When the source of a message changes, its translations still say what the old version said
+/// until they are translated again. This reports translations that were written for an older
+/// version of the definition, including what the source said at the time when it's still known.
+pub fn validate_outdated_translation(message: &Message, locale: KeySymbol) -> Vec<ValueDiagnostic> {
+    if !message.is_translation_outdated(locale) {
+        return vec![];
+    }
+
+    let help = match message.translation_revision(locale) {
+        Some(revision) => format!(
+            "The source message said \"{}\" when this was translated. Send it for translation again to get it up to date.",
+            revision.raw
+        ),
+        None => "Send the message for translation again to get it up to date.".into(),
+    };
+    vec![ValueDiagnostic {
+        name: DiagnosticName::NoOutdatedTranslations,
+        span: None,
+        severity: DiagnosticSeverity::Warning,
+        description: "Translation was written for an older version of the source message".into(),
+        help: Some(help),
+    }]
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, MessageMeta, MessageValue};
+
+    use super::*;
+
+    #[test]
+    fn test_outdated_translations() {
+        let source_locale = key_symbol("en-US");
+        let locale = key_symbol("fr");
+        let mut message = Message::from_definition(
+            key_symbol("GREETING"),
+            MessageValue::from_raw("Hello"),
+            source_locale,
+            MessageMeta::default(),
+        );
+        message.set_translation(locale, MessageValue::from_raw("Bonjour"));
+        assert!(validate_outdated_translation(&message, locale).is_empty());
+        assert!(validate_outdated_translation(&message, source_locale).is_empty());
+
+        message.set_definition(
+            MessageValue::from_raw("Hello there"),
+            source_locale,
+            MessageMeta::default(),
+        );
+        let diagnostics = validate_outdated_translation(&message, locale);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0]
+            .help
+            .as_ref()
+            .unwrap()
+            .starts_with("The source message said \"Hello\""));
+
+        // Translations for versions that are no longer in the history are still outdated.
+        let mut value = MessageValue::from_raw("Salut");
+        value.source_hash = Some("0000000000000000".into());
+        message.set_translation(locale, value);
+        let diagnostics = validate_outdated_translation(&message, locale);
+        assert_eq!(
+            diagnostics[0].help.as_deref(),
+            Some("Send the message for translation again to get it up to date.")
+        );
+    }
+}
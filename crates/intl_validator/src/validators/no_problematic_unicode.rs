@@ -0,0 +1,199 @@
+use intl_database_core::MessageValue;
+use intl_message_utils::{is_bidi_control, is_nfc_normalized, is_stray_zero_width};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+/// Return the ASCII character that `c` is easily mistaken for, if any. This covers the Cyrillic
+/// and Greek letters that look identical to Latin ones in most fonts, and the fullwidth forms of
+/// ASCII characters that IMEs for CJK languages often produce.
+fn confusable_ascii(c: char) -> Option<char> {
+    let ascii = match c {
+        // Fullwidth forms are offset from ASCII by a fixed amount.
+        '\u{FF01}'..='\u{FF5E}' => return char::from_u32(c as u32 - 0xFEE0),
+        'а' => 'a',
+        'с' => 'c',
+        'е' => 'e',
+        'һ' => 'h',
+        'і' | 'ι' | 'ı' => 'i',
+        'ј' => 'j',
+        'о' | 'ο' => 'o',
+        'р' | 'ρ' => 'p',
+        'ѕ' => 's',
+        'ν' => 'v',
+        'х' => 'x',
+        'у' => 'y',
+        'А' | 'Α' => 'A',
+        'В' | 'Β' => 'B',
+        'С' => 'C',
+        'Е' | 'Ε' => 'E',
+        'Н' | 'Η' => 'H',
+        'І' | 'Ι' => 'I',
+        'Ј' => 'J',
+        'К' | 'Κ' => 'K',
+        'М' | 'Μ' => 'M',
+        'Ν' => 'N',
+        'О' | 'Ο' => 'O',
+        'Р' | 'Ρ' => 'P',
+        'Ѕ' => 'S',
+        'Т' | 'Τ' => 'T',
+        'Х' | 'Χ' => 'X',
+        'Υ' | 'Ү' => 'Y',
+        'Ζ' => 'Z',
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+/// Invisible characters make a message look the same to reviewers while being different, which
+/// can break rendering, searching, and comparing messages, or with bidi controls, make a message
+/// display differently than it reads in the source. This reports:
+///
+/// - Bidi embedding, override, and isolate controls, as errors.
+/// - Zero-width characters that have no effect where they are, which are safe to remove.
+/// - Messages that aren't normalized to NFC, which are safe to normalize.
+/// - Variable names using letters that look like ASCII but aren't, like a Cyrillic `а`.
+pub struct NoProblematicUnicode {
+    diagnostics: Vec<ValueDiagnostic>,
+}
+
+impl NoProblematicUnicode {
+    pub fn new() -> Self {
+        Self {
+            diagnostics: vec![],
+        }
+    }
+
+    fn check_characters(&mut self, raw: &str) {
+        let chars: Vec<(usize, char)> = raw.char_indices().collect();
+        for (index, (offset, c)) in chars.iter().copied().enumerate() {
+            if is_bidi_control(c) {
+                self.diagnostics.push(ValueDiagnostic {
+                    name: DiagnosticName::NoProblematicUnicode,
+                    span: Some(offset),
+                    severity: DiagnosticSeverity::Error,
+                    description: format!("Message contains the invisible bidi control character U+{:04X}", c as u32),
+                    help: Some("Bidi controls change the order that text is displayed in, so the message can read differently than it is written. Remove it, and use the bidi-isolation transform when bundling to isolate placeholders instead.".into()),
+                });
+                continue;
+            }
+            let previous = index.checked_sub(1).map(|previous| chars[previous].1);
+            let next = chars.get(index + 1).map(|(_, next)| *next);
+            if is_stray_zero_width(previous, c, next) {
+                self.diagnostics.push(ValueDiagnostic {
+                    name: DiagnosticName::NoProblematicUnicode,
+                    span: Some(offset),
+                    severity: DiagnosticSeverity::Warning,
+                    description: format!(
+                        "Message contains the invisible zero-width character U+{:04X}",
+                        c as u32
+                    ),
+                    help: Some("It has no effect where it is, so it can be removed safely. Fixing the Unicode of translations removes it automatically.".into()),
+                });
+            }
+        }
+    }
+
+    fn check_variable_names(&mut self, message: &MessageValue) {
+        let Some(variables) = message.variables() else {
+            return;
+        };
+        for (name, instances) in variables.iter() {
+            let confusables: Vec<(char, char)> = name
+                .chars()
+                .filter_map(|c| Some((c, confusable_ascii(c)?)))
+                .collect();
+            let Some((first, first_ascii)) = confusables.first().copied() else {
+                continue;
+            };
+            let ascii_name: String = name
+                .chars()
+                .map(|c| confusable_ascii(c).unwrap_or(c))
+                .collect();
+            self.diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoProblematicUnicode,
+                span: instances.iter().find_map(|instance| instance.span),
+                severity: DiagnosticSeverity::Warning,
+                description: format!(
+                    "Variable '{name}' uses U+{:04X}, which looks like '{first_ascii}' but is a different character",
+                    first as u32
+                ),
+                help: Some(format!(
+                    "Lookalike characters make the variable a different one than it appears to be. Did you mean '{ascii_name}'?"
+                )),
+            });
+        }
+    }
+}
+
+impl Validator for NoProblematicUnicode {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoProblematicUnicode
+    }
+
+    fn validate_raw(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        self.check_characters(&message.raw);
+        if !is_nfc_normalized(&message.raw) {
+            self.diagnostics.push(ValueDiagnostic {
+                name: DiagnosticName::NoProblematicUnicode,
+                span: None,
+                severity: DiagnosticSeverity::Warning,
+                description: "Message is not normalized to Unicode NFC, so it may not match the same text typed another way".into(),
+                help: Some("Normalize the message to NFC. Fixing the Unicode of translations does this automatically, and the normalize-unicode transform does it when bundling.".into()),
+            });
+        }
+        Some(std::mem::take(&mut self.diagnostics))
+    }
+
+    fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        self.check_variable_names(message);
+        Some(std::mem::take(&mut self.diagnostics))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(raw: &str) -> Vec<Option<usize>> {
+        let value = MessageValue::from_raw(raw);
+        let mut validator = NoProblematicUnicode::new();
+        let mut diagnostics = validator.validate_raw(&value).unwrap();
+        diagnostics.extend(validator.validate_ast(&value).unwrap());
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_messages() {
+        assert_eq!(spans("Hello {name}"), vec![]);
+        // Joiners between the characters they shape are needed.
+        assert_eq!(spans("👩\u{200D}💻 می\u{200C}خواهم"), vec![]);
+    }
+
+    #[test]
+    fn test_invisible_characters() {
+        assert_eq!(spans("Hi\u{200B} {name}"), vec![Some(2)]);
+        assert_eq!(spans("Hi \u{200D}there"), vec![Some(3)]);
+        assert_eq!(spans("abc \u{202E}fed"), vec![Some(4)]);
+    }
+
+    #[test]
+    fn test_unnormalized_messages() {
+        assert_eq!(spans("Cafe\u{0301}"), vec![None]);
+        assert_eq!(spans("Caf\u{00E9}"), vec![]);
+    }
+
+    #[test]
+    fn test_confusable_variable_names() {
+        // The Cyrillic `а` is reported where the variable is used, not in the text before it.
+        assert_eq!(spans("nаme: {nаme}"), vec![Some(8)]);
+        assert_eq!(spans("{cοunt, number}"), vec![Some(1)]);
+        assert_eq!(spans("{nａme}"), vec![Some(1)]);
+        // Letters that don't look like ASCII are fine.
+        assert_eq!(spans("{userимя}"), vec![]);
+    }
+}
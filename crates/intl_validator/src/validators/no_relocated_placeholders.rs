@@ -0,0 +1,194 @@
+use intl_database_core::MessageValue;
+use intl_markdown::{Hook, Icu, Link, LinkDestination};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// A variable within a message, along with the innermost hook or link whose content contains it.
+#[derive(Debug)]
+struct PlaceholderPlacement {
+    name: String,
+    /// Start of the variable's name in the raw content, when it's known.
+    span: Option<usize>,
+    container: Option<String>,
+}
+
+/// Collects the placement of every variable in a message, in source order. Variables used as
+/// link destinations aren't part of the link's content, and are skipped.
+struct PlaceholderPlacementCollector {
+    stack: Vec<String>,
+    placements: Vec<PlaceholderPlacement>,
+}
+
+impl PlaceholderPlacementCollector {
+    fn collect(message: &MessageValue) -> Vec<PlaceholderPlacement> {
+        let mut collector = Self {
+            stack: vec![],
+            placements: vec![],
+        };
+        visit_with_mut(message.parsed(), &mut collector);
+        collector.placements
+    }
+}
+
+impl Visit for PlaceholderPlacementCollector {
+    fn visit_hook(&mut self, node: &Hook) {
+        self.stack.push(format!("hook '{}'", node.name()));
+        node.visit_children_with(self);
+        self.stack.pop();
+    }
+
+    fn visit_icu(&mut self, node: &Icu) {
+        let variable = match node {
+            Icu::IcuVariable(variable) => variable,
+            Icu::IcuPlural(plural) => plural.variable(),
+            Icu::IcuSelect(select) => select.variable(),
+            Icu::IcuDate(date) => date.variable(),
+            Icu::IcuTime(time) => time.variable(),
+            Icu::IcuNumber(number) => number.variable(),
+        };
+        self.placements.push(PlaceholderPlacement {
+            name: variable.name().into(),
+            span: variable.name_span().map(|span| span.start),
+            container: self.stack.last().cloned(),
+        });
+        node.visit_children_with(self);
+    }
+
+    fn visit_link(&mut self, node: &Link) {
+        let destination = match node.destination() {
+            LinkDestination::Text(url) => format!("link to \"{url}\""),
+            LinkDestination::Placeholder(_) => String::from("link with a placeholder destination"),
+            LinkDestination::Handler(name) => format!("link '{name}'"),
+        };
+        self.stack.push(destination);
+        node.visit_children_with(self);
+        self.stack.pop();
+    }
+
+    fn visit_link_destination(&mut self, _node: &LinkDestination) {}
+}
+
+/// Hooks and links render their content as a single clickable or styled element, so a variable
+/// written inside of one in the source message needs to stay inside of that same element in
+/// translations. When a translator moves `{filename}` out of `$[{filename}](link)`, the message
+/// still contains every variable, but clicking it no longer does what the source intended.
+///
+/// This compares the innermost hook or link containing each variable in the translation against
+/// the first unused occurrence of the same variable in the source, and reports any variable that
+/// was moved into, out of, or between hooks and links.
+pub fn validate_placeholder_containers(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let mut source_placements: Vec<Option<PlaceholderPlacement>> =
+        PlaceholderPlacementCollector::collect(source)
+            .into_iter()
+            .map(Some)
+            .collect();
+    let mut diagnostics = vec![];
+
+    for placement in PlaceholderPlacementCollector::collect(translation) {
+        // Prefer an occurrence in the same container, so that a variable used several times
+        // is only reported when it can't be matched up with the source at all.
+        let matching_index = source_placements
+            .iter()
+            .position(|source| {
+                source
+                    .as_ref()
+                    .is_some_and(|s| s.name == placement.name && s.container == placement.container)
+            })
+            .or_else(|| {
+                source_placements
+                    .iter()
+                    .position(|source| source.as_ref().is_some_and(|s| s.name == placement.name))
+            });
+        // Variables that don't exist in the source at all are reported by other validations.
+        let Some(matching_index) = matching_index else {
+            continue;
+        };
+        // SAFETY: `position` only matches entries that are `Some`.
+        let source_placement = source_placements[matching_index].take().unwrap();
+        if source_placement.container == placement.container {
+            continue;
+        }
+
+        let format_container = |container: &Option<String>| match container {
+            Some(container) => format!("inside the {container}"),
+            None => String::from("outside of any hook or link"),
+        };
+        diagnostics.push(ValueDiagnostic {
+            name: DiagnosticName::NoRelocatedPlaceholders,
+            span: placement.span,
+            severity: DiagnosticSeverity::Warning,
+            description: format!(
+                "'{{{}}}' is {} in the translation, but {} in the source message",
+                placement.name,
+                format_container(&placement.container),
+                format_container(&source_placement.container),
+            ),
+            help: Some("Keep variables inside the same hooks and links as the source message, so that the rendered element still covers the same content.".into()),
+        });
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(source: &str, translation: &str) -> Vec<Option<usize>> {
+        validate_placeholder_containers(
+            &MessageValue::from_raw(source),
+            &MessageValue::from_raw(translation),
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.span)
+        .collect()
+    }
+
+    #[test]
+    fn test_placeholders_in_the_same_containers() {
+        assert_eq!(
+            spans("Open $[{filename}](link) now", "Ouvrir $[{filename}](link)"),
+            vec![]
+        );
+        // Order doesn't matter, only which hook or link each variable is in.
+        assert_eq!(
+            spans("{a} and [{b}](onClick)", "[{b}](onClick) et {a}"),
+            vec![]
+        );
+        // Variables that aren't in the source are left to other validators.
+        assert_eq!(spans("Hello", "Bonjour {name}"), vec![]);
+    }
+
+    #[test]
+    fn test_relocated_placeholders() {
+        assert_eq!(
+            spans("Open $[{filename}](link)", "Ouvrir {filename} $[ici](link)"),
+            vec![Some(8)]
+        );
+        assert_eq!(
+            spans(
+                "[{count, plural, other {# files}}](onClick)",
+                "$[{count, plural, other {# fichiers}}](hook)"
+            ),
+            vec![Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_repeated_placeholders() {
+        // Repeated variables are matched to an occurrence in the same container first.
+        assert_eq!(
+            spans("{name}: [{name}](onClick)", "[{name}](onClick) {name}"),
+            vec![]
+        );
+        // Only the occurrence that left the link is reported.
+        assert_eq!(
+            spans("{name}: [{name}](onClick)", "{name}: {name}"),
+            vec![Some(9)]
+        );
+    }
+}
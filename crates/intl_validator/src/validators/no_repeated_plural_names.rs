@@ -23,8 +23,12 @@ impl NoRepeatedPluralNames {
 }
 
 impl Validator for NoRepeatedPluralNames {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoRepeatedPluralNames
+    }
+
     fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
-        visit_with_mut(&message.parsed, self);
+        visit_with_mut(message.parsed(), self);
         Some(self.diagnostics.clone())
     }
 }
@@ -68,7 +72,7 @@ impl Visit for NoRepeatedPluralNames {
             return;
         };
 
-        if plural_name.eq(node.name()) {
+        if plural_name.as_str() == node.name() {
             let diagnostic = ValueDiagnostic {
                 name: DiagnosticName::NoRepeatedPluralNames,
                 span: None,
@@ -20,8 +20,12 @@ impl NoRepeatedPluralOptions {
 }
 
 impl Validator for NoRepeatedPluralOptions {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoRepeatedPluralOptions
+    }
+
     fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
-        visit_with_mut(&message.parsed, self);
+        visit_with_mut(message.parsed(), self);
         Some(self.diagnostics.clone())
     }
 }
@@ -29,7 +33,7 @@ impl Validator for NoRepeatedPluralOptions {
 impl Visit for NoRepeatedPluralOptions {
     fn visit_icu_plural(&mut self, node: &IcuPlural) {
         let plural_name = node.name();
-        let arm_names = node.arms().iter().map(|arm| arm.selector().as_str());
+        let arm_names = node.arms().iter().map(|arm| arm.selector());
         let mut seen = HashSet::new();
         // Allotting enough capacity to handle basically every possible case. More than 4
         // repetitions is egregious and there will almost never be more than 1, but this just
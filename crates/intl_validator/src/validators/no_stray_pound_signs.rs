@@ -0,0 +1,145 @@
+use intl_database_core::{MessageValue, MessageVariableType};
+use intl_markdown::{CodeSpan, IcuPlural};
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+/// Flags `#` characters written outside of any plural, like `You have # new messages`. Only plural
+/// arms replace `#` with the number being counted, so anywhere else it is rendered literally. This
+/// usually happens when content is copied out of a plural arm, so it's only reported when the
+/// message has a number or plural variable that the `#` was likely meant to be.
+///
+/// Only a `#` that stands on its own is reported, so channel names like `#general` are left alone.
+/// Code spans are skipped, since their content is always literal.
+pub struct NoStrayPoundSigns {
+    /// The position in the raw content to continue searching for text from, since text is
+    /// visited in the same order it is written.
+    cursor: usize,
+    /// Start of every stray `#` in the raw content, or `None` if the text it's in couldn't be
+    /// found in the raw content.
+    pounds: Vec<Option<usize>>,
+    raw: String,
+}
+
+impl NoStrayPoundSigns {
+    pub fn new() -> Self {
+        Self {
+            cursor: 0,
+            pounds: vec![],
+            raw: String::new(),
+        }
+    }
+}
+
+/// Returns the name of a variable in the message that holds a number, preferring plurals, since
+/// those are the ones a `#` would have referred to.
+fn find_number_variable(message: &MessageValue) -> Option<String> {
+    let variables = message.variables()?;
+    let mut names: Vec<(bool, String)> = variables
+        .iter()
+        .filter_map(|(name, instances)| {
+            let is_plural = instances
+                .iter()
+                .any(|instance| instance.kind == MessageVariableType::Plural);
+            let is_number = instances
+                .iter()
+                .any(|instance| matches!(instance.kind, MessageVariableType::Number(_)));
+            (is_plural || is_number).then(|| (!is_plural, name.to_string()))
+        })
+        .collect();
+    names.sort();
+    names.into_iter().next().map(|(_, name)| name)
+}
+
+impl Validator for NoStrayPoundSigns {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoStrayPoundSigns
+    }
+
+    fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        let variable = find_number_variable(message)?;
+        self.raw = message.raw.as_str().into();
+        visit_with_mut(message.parsed(), self);
+
+        let diagnostics = self
+            .pounds
+            .iter()
+            .map(|span| ValueDiagnostic {
+                name: DiagnosticName::NoStrayPoundSigns,
+                span: *span,
+                severity: DiagnosticSeverity::Warning,
+                description: "`#` is only replaced with a number inside of a plural".into(),
+                help: Some(format!("This `#` will be rendered literally. Use `{{{variable}, number}}` to insert the value of `{variable}` instead.")),
+            })
+            .collect();
+        Some(diagnostics)
+    }
+}
+
+impl Visit for NoStrayPoundSigns {
+    fn visit_code_span(&mut self, _node: &CodeSpan) {}
+
+    fn visit_icu_plural(&mut self, _node: &IcuPlural) {}
+
+    fn visit_text(&mut self, node: &String) {
+        let start = self.raw[self.cursor..]
+            .find(node.as_str())
+            .map(|offset| self.cursor + offset);
+        if let Some(start) = start {
+            self.cursor = start + node.len();
+        }
+
+        let is_separator = |c: Option<char>| c.is_none_or(char::is_whitespace);
+        for (index, _) in node.match_indices('#') {
+            let before = node[..index].chars().next_back();
+            let after = node[index + 1..].chars().next();
+            if is_separator(before) && is_separator(after) {
+                self.pounds.push(start.map(|start| start + index));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(raw: &str) -> Vec<Option<usize>> {
+        NoStrayPoundSigns::new()
+            .validate_ast(&MessageValue::from_raw(raw))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_pounds_inside_plurals() {
+        assert_eq!(
+            spans("{count, plural, one {# item} other {# items}}"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_stray_pounds() {
+        assert_eq!(
+            spans("You have # new messages {count, number}"),
+            vec![Some(9)]
+        );
+        assert_eq!(
+            spans("# of {count, plural, one {# item} other {# items}}, # total"),
+            vec![Some(0), Some(52)]
+        );
+    }
+
+    #[test]
+    fn test_pounds_that_are_not_numbers() {
+        // Without a number to refer to, a `#` is just text.
+        assert_eq!(spans("You have # new messages"), vec![]);
+        assert_eq!(spans("Join #general with {count, number} others"), vec![]);
+        assert_eq!(spans("Type `#` then {count, number}"), vec![]);
+    }
+}
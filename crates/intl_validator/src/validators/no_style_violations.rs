@@ -0,0 +1,176 @@
+use regex::Regex;
+
+use intl_database_core::{KeySymbol, MessageValue};
+use intl_message_utils::matches_glob;
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// A house style rule, reported wherever its pattern matches the raw content of a message, like
+/// `\blog in\b` with the message "Use 'sign in' instead of 'log in'".
+#[derive(Clone, Debug)]
+pub struct StyleRule {
+    pattern: Regex,
+    message: String,
+    help: Option<String>,
+    /// Languages, like `en`, or full locales, like `en-GB`, that the rule applies to. The rule
+    /// applies to every locale when empty.
+    locales: Vec<String>,
+    /// Globs of the message keys that the rule applies to, like `SETTINGS_*`, where `*` matches
+    /// any number of characters and `?` matches exactly one. The rule applies to every message
+    /// when empty.
+    keys: Vec<String>,
+    severity: DiagnosticSeverity,
+}
+
+impl PartialEq for StyleRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str()
+            && self.message == other.message
+            && self.help == other.help
+            && self.locales == other.locales
+            && self.keys == other.keys
+            && self.severity == other.severity
+    }
+}
+
+impl StyleRule {
+    /// Create a rule reporting `message` wherever `pattern` matches, which is a regular expression
+    /// using the syntax of the `regex` crate.
+    pub fn new(pattern: &str, message: impl Into<String>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: Regex::new(pattern)?,
+            message: message.into(),
+            help: None,
+            locales: vec![],
+            keys: vec![],
+            severity: DiagnosticSeverity::Warning,
+        })
+    }
+
+    pub fn with_help(mut self, help: Option<String>) -> Self {
+        self.help = help;
+        self
+    }
+
+    pub fn with_locales(mut self, locales: Vec<String>) -> Self {
+        self.locales = locales;
+        self
+    }
+
+    pub fn with_keys(mut self, keys: Vec<String>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    pub fn with_severity(mut self, severity: DiagnosticSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    fn applies_to(&self, key: &str, locale: &str) -> bool {
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        let matches_locale = self.locales.is_empty()
+            || self
+                .locales
+                .iter()
+                .any(|rule_locale| rule_locale == locale || rule_locale == language);
+        let matches_key =
+            self.keys.is_empty() || self.keys.iter().any(|glob| matches_glob(glob, key));
+        matches_locale && matches_key
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoStyleViolationsOptions {
+    pub rules: Vec<StyleRule>,
+}
+
+impl NoStyleViolationsOptions {
+    pub fn with_rules(mut self, rules: Vec<StyleRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+}
+
+/// Report every match of the project's style rules in the translation of the message `key` in
+/// `locale`, so teams can enforce their own conventions for wording, like always writing
+/// "sign in" rather than "log in", without writing a validator for each one.
+pub fn validate_style_rules(
+    key: KeySymbol,
+    translation: &MessageValue,
+    locale: &str,
+    options: &NoStyleViolationsOptions,
+) -> Vec<ValueDiagnostic> {
+    options
+        .rules
+        .iter()
+        .filter(|rule| rule.applies_to(&key, locale))
+        .flat_map(|rule| {
+            rule.pattern
+                .find_iter(&translation.raw)
+                .map(|found| ValueDiagnostic {
+                    name: DiagnosticName::NoStyleViolations,
+                    span: Some(found.start()),
+                    severity: rule.severity,
+                    description: rule.message.clone(),
+                    help: rule.help.clone(),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::key_symbol;
+
+    use super::*;
+
+    fn spans(key: &str, raw: &str, locale: &str, rules: Vec<StyleRule>) -> Vec<Option<usize>> {
+        let options = NoStyleViolationsOptions::default().with_rules(rules);
+        validate_style_rules(
+            key_symbol(key),
+            &MessageValue::from_raw(raw),
+            locale,
+            &options,
+        )
+        .into_iter()
+        .map(|diagnostic| diagnostic.span)
+        .collect()
+    }
+
+    fn log_in() -> StyleRule {
+        StyleRule::new(r"(?i)\blog in\b", "Use 'sign in' instead of 'log in'").unwrap()
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(
+            spans("LOGIN", "Sign in to continue", "en-US", vec![log_in()]),
+            vec![]
+        );
+        assert_eq!(
+            spans("LOGIN", "Log in, or log in later", "en-US", vec![log_in()]),
+            vec![Some(0), Some(11)]
+        );
+    }
+
+    #[test]
+    fn test_rule_scope() {
+        let rule = log_in()
+            .with_locales(vec!["en".into()])
+            .with_keys(vec!["AUTH_*".into()]);
+        let raw = "Log in";
+        assert_eq!(
+            spans("AUTH_LOGIN", raw, "en-GB", vec![rule.clone()]),
+            vec![Some(0)]
+        );
+        assert_eq!(spans("AUTH_LOGIN", raw, "fr", vec![rule.clone()]), vec![]);
+        assert_eq!(spans("SETTINGS_LOGIN", raw, "en-GB", vec![rule]), vec![]);
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(StyleRule::new("(unclosed", "message").is_err());
+    }
+}
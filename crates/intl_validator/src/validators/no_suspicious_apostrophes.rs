@@ -0,0 +1,166 @@
+use intl_database_core::MessageValue;
+use intl_markdown::ApostropheMode;
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+/// Flags apostrophes that change how a message renders in ways authors rarely intend. ICU treats
+/// an apostrophe directly before a brace as an escape, so `'{name}'` is rendered as literal text
+/// rather than a placeholder, and the apostrophes themselves are kept in the output. Doubled
+/// apostrophes like `don''t` are also kept as-is rather than being collapsed into a single one.
+///
+/// Code spans are skipped, since their content is always literal. When messages are parsed with
+/// [ApostropheMode::Literal], apostrophes never escape braces, so only doubled apostrophes are
+/// reported.
+pub struct NoSuspiciousApostrophes;
+impl NoSuspiciousApostrophes {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Validator for NoSuspiciousApostrophes {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoSuspiciousApostrophes
+    }
+
+    fn validate_raw(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        let mut diagnostics = vec![];
+        let content = message.raw.as_str();
+        let bytes = content.as_bytes();
+        let escapes_braces = message.parser_options().apostrophe_mode == ApostropheMode::Icu;
+        let mut index = 0;
+        while index < bytes.len() {
+            match bytes[index] {
+                b'`' => index = skip_code_span(bytes, index),
+                b'\'' => match (bytes.get(index + 1), bytes.get(index + 2)) {
+                    // `''{` is a literal apostrophe followed by an escaped brace, which is
+                    // reported on the next iteration.
+                    (Some(b'\''), Some(b'{' | b'}')) if escapes_braces => index += 1,
+                    (Some(b'\''), _) => {
+                        diagnostics.push(doubled_apostrophe(index));
+                        index += 2;
+                    }
+                    (Some(b'{'), _) if escapes_braces => {
+                        diagnostics.push(escaped_placeholder(content, index));
+                        index += 2;
+                    }
+                    (Some(b'}'), _) if escapes_braces => {
+                        diagnostics.push(escaped_closing_brace(index));
+                        index += 2;
+                    }
+                    _ => index += 1,
+                },
+                _ => index += 1,
+            }
+        }
+        Some(diagnostics)
+    }
+}
+
+fn doubled_apostrophe(index: usize) -> ValueDiagnostic {
+    ValueDiagnostic {
+        name: DiagnosticName::NoSuspiciousApostrophes,
+        span: Some(index),
+        severity: DiagnosticSeverity::Warning,
+        description: "Doubled apostrophes are rendered as two apostrophes".into(),
+        help: Some(
+            "The runtime will output `''` exactly as written. Use a single `'` for an apostrophe."
+                .into(),
+        ),
+    }
+}
+
+fn escaped_placeholder(content: &str, index: usize) -> ValueDiagnostic {
+    // Show the text that will be output literally, up to the end of the would-be placeholder.
+    let rest = &content[index..];
+    let line_end = rest.find('\n').unwrap_or(rest.len());
+    let literal = match rest[..line_end].find('}') {
+        Some(close) if rest[close + 1..].starts_with('\'') => &rest[..close + 2],
+        Some(close) => &rest[..close + 1],
+        None => "'{",
+    };
+    ValueDiagnostic {
+        name: DiagnosticName::NoSuspiciousApostrophes,
+        span: Some(index),
+        severity: DiagnosticSeverity::Warning,
+        description: "An apostrophe before `{` turns the placeholder into plain text".into(),
+        help: Some(format!(
+            "The runtime will output `{literal}` exactly as written instead of inserting a value. Remove the apostrophe if this was meant to be a placeholder."
+        )),
+    }
+}
+
+fn escaped_closing_brace(index: usize) -> ValueDiagnostic {
+    ValueDiagnostic {
+        name: DiagnosticName::NoSuspiciousApostrophes,
+        span: Some(index),
+        severity: DiagnosticSeverity::Warning,
+        description: "An apostrophe before `}` turns the brace into plain text".into(),
+        help: Some(
+            "The runtime will output `'}` exactly as written, and the brace will not close the placeholder or plural arm it appears in. Remove the apostrophe if the brace was meant to close it."
+                .into(),
+        ),
+    }
+}
+
+/// Returns the index just past the code span starting at `start`, or just past the opening
+/// backticks if the span is never closed. Code spans are closed by a run of the same number of
+/// backticks that opened them.
+fn skip_code_span(bytes: &[u8], start: usize) -> usize {
+    let run_length = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|byte| **byte == b'`')
+            .count()
+    };
+    let opening = run_length(start);
+    let mut index = start + opening;
+    while index < bytes.len() {
+        if bytes[index] == b'`' {
+            let closing = run_length(index);
+            if closing == opening {
+                return index + closing;
+            }
+            index += closing;
+        } else {
+            index += 1;
+        }
+    }
+    start + opening
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use intl_markdown::ParserOptions;
+
+    use super::*;
+
+    fn spans(value: &MessageValue) -> Vec<Option<usize>> {
+        NoSuspiciousApostrophes::new()
+            .validate_raw(value)
+            .unwrap()
+            .iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let value = MessageValue::from_raw("Hi '{name}', it''s `'{code}'`");
+        assert_eq!(spans(&value), vec![Some(3), Some(15)]);
+        assert_eq!(spans(&MessageValue::from_raw("It's {name}'s")), vec![]);
+    }
+
+    #[test]
+    fn test_literal_apostrophes() {
+        let mut value = MessageValue::from_raw("Hi '{name}', it''s");
+        value.set_parser_options(Some(Arc::new(
+            ParserOptions::default().with_apostrophe_mode(ApostropheMode::Literal),
+        )));
+        assert_eq!(spans(&value), vec![Some(15)]);
+    }
+}
@@ -0,0 +1,77 @@
+use intl_database_core::MessageValue;
+use intl_markdown::Document;
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+/// Reports every problem the parser had to recover from. Recovered content is kept in the message
+/// as literal text, so it will render, but almost certainly not the way it was meant to.
+pub struct NoSyntaxErrors;
+impl NoSyntaxErrors {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Validator for NoSyntaxErrors {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoSyntaxErrors
+    }
+
+    fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        Some(syntax_diagnostics(message.parsed()))
+    }
+}
+
+fn syntax_diagnostics(document: &Document) -> Vec<ValueDiagnostic> {
+    document
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| ValueDiagnostic {
+            name: DiagnosticName::NoSyntaxErrors,
+            span: Some(diagnostic.span().start),
+            severity: DiagnosticSeverity::Error,
+            description: diagnostic.message().into(),
+            help: Some("Check this part of the message for unclosed or misplaced syntax".into()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use intl_markdown::ParseDiagnostic;
+
+    use super::*;
+
+    fn spans(raw: &str) -> Vec<Option<usize>> {
+        NoSyntaxErrors::new()
+            .validate_ast(&MessageValue::from_raw(raw))
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_syntax() {
+        assert_eq!(spans("Hello {name}, **welcome**"), vec![]);
+        // Unclosed syntax that the parser can read as text isn't an error.
+        assert_eq!(spans("Hello {name"), vec![]);
+    }
+
+    #[test]
+    fn test_recovered_syntax() {
+        let document = Document::from_error(
+            "Hello {name}",
+            ParseDiagnostic::new("Unexpected content after the end of the message", 6..12),
+        );
+        let diagnostics = syntax_diagnostics(&document);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Some(6));
+        assert_eq!(
+            diagnostics[0].description,
+            "Unexpected content after the end of the message"
+        );
+    }
+}
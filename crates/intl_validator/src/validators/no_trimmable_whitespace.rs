@@ -12,9 +12,13 @@ impl NoTrimmableWhitespace {
 }
 
 impl Validator for NoTrimmableWhitespace {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoTrimmableWhitespace
+    }
+
     fn validate_raw(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
         let mut diagnostics = vec![];
-        let content = &message.raw;
+        let content = message.raw.as_str();
         if content.trim_start() != content {
             diagnostics.push(ValueDiagnostic {
                 name: DiagnosticName::NoTrimmableWhitespace,
@@ -19,8 +19,12 @@ impl NoUnicodeVariableNames {
 }
 
 impl Validator for NoUnicodeVariableNames {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoUnicodeVariableNames
+    }
+
     fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
-        visit_with_mut(&message.parsed, self);
+        visit_with_mut(message.parsed(), self);
         Some(self.diagnostics.clone())
     }
 }
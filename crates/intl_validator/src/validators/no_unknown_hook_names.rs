@@ -0,0 +1,188 @@
+use intl_database_core::MessageValue;
+use intl_markdown::{Hook, Link, LinkDestination};
+use intl_markdown_visitor::{visit_with_mut, Visit, VisitWith};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoUnknownHookNamesOptions {
+    /// Names that hooks are allowed to use, like `tooltipHook` in `$[text](tooltipHook)`. Hook
+    /// names are not checked when this is not set.
+    pub hook_names: Option<Vec<String>>,
+    /// Names that link handlers are allowed to use, like `onClick` in `[text](onClick)`. Link
+    /// handlers are not checked when this is not set.
+    pub link_handler_names: Option<Vec<String>>,
+}
+
+impl NoUnknownHookNamesOptions {
+    pub fn with_hook_names(mut self, hook_names: Option<Vec<String>>) -> Self {
+        self.hook_names = hook_names;
+        self
+    }
+
+    pub fn with_link_handler_names(mut self, link_handler_names: Option<Vec<String>>) -> Self {
+        self.link_handler_names = link_handler_names;
+        self
+    }
+}
+
+/// Ensure that hooks and link handlers only use names from the configured set of handlers that
+/// the app provides. Messages can name any handler they want, but one that isn't provided at
+/// runtime will fail to render, so this catches typos before they reach users.
+pub struct NoUnknownHookNames {
+    options: NoUnknownHookNamesOptions,
+    diagnostics: Vec<ValueDiagnostic>,
+}
+
+impl NoUnknownHookNames {
+    pub fn with_options(options: NoUnknownHookNamesOptions) -> Self {
+        Self {
+            options,
+            diagnostics: vec![],
+        }
+    }
+}
+
+impl Validator for NoUnknownHookNames {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoUnknownHookNames
+    }
+
+    fn validate_ast(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        if self.options.hook_names.is_none() && self.options.link_handler_names.is_none() {
+            return None;
+        }
+        visit_with_mut(message.parsed(), self);
+        Some(self.diagnostics.clone())
+    }
+}
+
+impl Visit for NoUnknownHookNames {
+    fn visit_hook(&mut self, node: &Hook) {
+        if let Some(allowed) = &self.options.hook_names {
+            let span = node.name_span().map(|span| span.start);
+            self.diagnostics
+                .extend(unknown_name(node.name(), span, "hook", allowed));
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_link(&mut self, node: &Link) {
+        if let (LinkDestination::Handler(name), Some(allowed)) =
+            (node.destination(), &self.options.link_handler_names)
+        {
+            let span = node.handler_span().map(|span| span.start);
+            self.diagnostics
+                .extend(unknown_name(name, span, "link handler", allowed));
+        }
+        node.visit_children_with(self);
+    }
+}
+
+/// Returns a diagnostic for `name` if it is not one of the `allowed` names.
+fn unknown_name(
+    name: &str,
+    span: Option<usize>,
+    kind: &str,
+    allowed: &[String],
+) -> Option<ValueDiagnostic> {
+    if allowed.iter().any(|allowed_name| allowed_name == name) {
+        return None;
+    }
+
+    let help = match closest_name(name, allowed) {
+        Some(suggestion) => format!("Did you mean \"{suggestion}\"?"),
+        None => format!(
+            "Use one of the {kind} names configured for this project, or add \"{name}\" to the configuration if it is a new {kind}."
+        ),
+    };
+    Some(ValueDiagnostic {
+        name: DiagnosticName::NoUnknownHookNames,
+        span,
+        severity: DiagnosticSeverity::Error,
+        description: format!("\"{name}\" is not a known {kind} name"),
+        help: Some(help),
+    })
+}
+
+/// Returns the allowed name that is closest to `name`, if any is close enough to likely be what
+/// was intended.
+fn closest_name<'a>(name: &str, allowed: &'a [String]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    allowed
+        .iter()
+        .map(|allowed_name| (edit_distance(name, allowed_name), allowed_name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, allowed_name)| allowed_name.as_str())
+}
+
+/// Levenshtein distance between the two strings, counting each inserted, removed, or replaced
+/// character as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let replace_cost = if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = (previous[j] + replace_cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(names: &[&str]) -> Option<Vec<String>> {
+        Some(names.iter().map(|name| name.to_string()).collect())
+    }
+
+    fn spans(options: NoUnknownHookNamesOptions, raw: &str) -> Vec<Option<usize>> {
+        NoUnknownHookNames::with_options(options)
+            .validate_ast(&MessageValue::from_raw(raw))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|diagnostic| diagnostic.span)
+            .collect()
+    }
+
+    #[test]
+    fn test_unchecked_by_default() {
+        let raw = "$[text](tooltipHook) [text](onClick)";
+        assert_eq!(spans(NoUnknownHookNamesOptions::default(), raw), vec![]);
+    }
+
+    #[test]
+    fn test_known_names() {
+        let options = NoUnknownHookNamesOptions::default()
+            .with_hook_names(names(&["tooltipHook"]))
+            .with_link_handler_names(names(&["onClick"]));
+        let raw = "$[text](tooltipHook) [text](onClick)";
+        assert_eq!(spans(options, raw), vec![]);
+    }
+
+    #[test]
+    fn test_unknown_hook_name() {
+        let options = NoUnknownHookNamesOptions::default().with_hook_names(names(&["tooltipHook"]));
+        // The content contains the name as well, which must not be reported instead.
+        let raw = "$[tooltipHok](tooltipHok)";
+        assert_eq!(spans(options, raw), vec![Some(14)]);
+    }
+
+    #[test]
+    fn test_unknown_link_handler_with_title() {
+        let options =
+            NoUnknownHookNamesOptions::default().with_link_handler_names(names(&["onClick"]));
+        let raw = "Open [settings](onClik \"title\")";
+        assert_eq!(spans(options, raw), vec![Some(16)]);
+    }
+}
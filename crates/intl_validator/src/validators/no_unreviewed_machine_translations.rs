@@ -0,0 +1,52 @@
+use intl_database_core::{MessageValue, ReviewState};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::validators::validator::Validator;
+use crate::DiagnosticSeverity;
+
+pub struct NoUnreviewedMachineTranslations;
+impl NoUnreviewedMachineTranslations {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Validator for NoUnreviewedMachineTranslations {
+    fn name(&self) -> DiagnosticName {
+        DiagnosticName::NoUnreviewedMachineTranslations
+    }
+
+    fn validate_raw(&mut self, message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
+        if message.review_state != ReviewState::Machine {
+            return None;
+        }
+        Some(vec![ValueDiagnostic {
+            name: DiagnosticName::NoUnreviewedMachineTranslations,
+            span: None,
+            severity: DiagnosticSeverity::Info,
+            description: "This translation was machine translated and hasn't been reviewed yet"
+                .into(),
+            help: Some("Once the translation has been reviewed, change its `reviewState` to `reviewed` or `final`.".into()),
+        }])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn count(review_state: ReviewState) -> Option<usize> {
+        let mut value = MessageValue::from_raw("Bonjour");
+        value.review_state = review_state;
+        NoUnreviewedMachineTranslations::new()
+            .validate_raw(&value)
+            .map(|diagnostics| diagnostics.len())
+    }
+
+    #[test]
+    fn test_review_states() {
+        assert_eq!(count(ReviewState::Machine), Some(1));
+        assert_eq!(count(ReviewState::New), None);
+        assert_eq!(count(ReviewState::Reviewed), None);
+    }
+}
@@ -0,0 +1,70 @@
+use intl_database_core::MessageValue;
+use intl_markdown_visitor::{visit_with_mut, Visit};
+
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
+use crate::DiagnosticSeverity;
+
+/// Finds whether any text in a message has letters in it, rather than only placeholders, numbers
+/// and punctuation.
+#[derive(Default)]
+struct LetterFinder {
+    has_letters: bool,
+}
+
+impl Visit for LetterFinder {
+    fn visit_text(&mut self, node: &String) {
+        self.has_letters |= node.chars().any(char::is_alphabetic);
+    }
+}
+
+/// Translations are sometimes filled in with a copy of the source message as a placeholder and
+/// never actually translated. This reports translations that say exactly the same thing as the
+/// source, even if they're written differently, like with different escapes or whitespace.
+/// Messages without any letters, like `{count}`, are expected to be the same in every locale.
+pub fn validate_untranslated_value(
+    source: &MessageValue,
+    translation: &MessageValue,
+) -> Vec<ValueDiagnostic> {
+    let mut letters = LetterFinder::default();
+    visit_with_mut(source.parsed(), &mut letters);
+    if !letters.has_letters || !translation.is_equivalent(source) {
+        return vec![];
+    }
+
+    vec![ValueDiagnostic {
+        name: DiagnosticName::NoUntranslatedValues,
+        span: None,
+        severity: DiagnosticSeverity::Info,
+        description: "Translation is the same as the source message".into(),
+        help: Some("This is often a copy of the source message that was never translated. If the message really reads the same in this locale, like a product name, this can be ignored.".into()),
+    }]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn is_untranslated(source: &str, translation: &str) -> bool {
+        !validate_untranslated_value(
+            &MessageValue::from_raw(source),
+            &MessageValue::from_raw(translation),
+        )
+        .is_empty()
+    }
+
+    #[test]
+    fn test_translated_values() {
+        assert!(!is_untranslated("Hello {name}", "Bonjour {name}"));
+        // Messages without letters are the same everywhere.
+        assert!(!is_untranslated(
+            "{count, number}: {name}",
+            "{count, number}: {name}"
+        ));
+    }
+
+    #[test]
+    fn test_untranslated_values() {
+        assert!(is_untranslated("Hello {name}", "Hello {name}"));
+        assert!(is_untranslated("Hello *{name}*", "  Hello _{name}_ "));
+    }
+}
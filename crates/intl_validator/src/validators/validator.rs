@@ -1,8 +1,11 @@
 use intl_database_core::MessageValue;
 
-use crate::diagnostic::ValueDiagnostic;
+use crate::diagnostic::{DiagnosticName, ValueDiagnostic};
 
 pub trait Validator {
+    /// The rule that this validator checks, which is used to attribute timings to it.
+    fn name(&self) -> DiagnosticName;
+
     fn validate_raw(&mut self, _message: &MessageValue) -> Option<Vec<ValueDiagnostic>> {
         None
     }
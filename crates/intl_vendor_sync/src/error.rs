@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+use intl_database_core::DatabaseError;
+
+#[derive(Debug, Error)]
+pub enum VendorSyncError {
+    #[error("Request to {url} failed: {message}")]
+    Transport { url: String, message: String },
+    #[error("{vendor} responded to {url} with status {status}: {body}")]
+    UnexpectedStatus {
+        vendor: String,
+        url: String,
+        status: u16,
+        body: String,
+    },
+    #[error("{0} returned a response that could not be read: {1}")]
+    InvalidResponse(String, serde_json::Error),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+pub type VendorSyncResult<T> = Result<T, VendorSyncError>;
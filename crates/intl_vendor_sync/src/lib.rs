@@ -0,0 +1,262 @@
+//! Synchronization between a messages database and a translation vendor. Source messages are
+//! pushed to the vendor for translation, and finished translations are pulled back and inserted
+//! into the database the same way that translation files are, so they can be validated and
+//! exported as usual.
+//!
+//! Vendors are implemented with the [TranslationVendor] trait, and all requests are sent through
+//! an [HttpTransport] given by the caller.
+pub use error::{VendorSyncError, VendorSyncResult};
+pub use lokalise::LokaliseVendor;
+pub use transport::{HttpMethod, HttpRequest, HttpResponse, HttpTransport};
+pub use vendor::{TranslationVendor, VendorSourceString, VendorTranslation};
+
+use intl_database_core::{
+    key_symbol, KeySymbol, MessagesDatabase, RawMessageTranslation, RawPosition,
+};
+use intl_message_database::sources::insert_translations;
+
+mod error;
+mod lokalise;
+#[cfg(test)]
+mod test_transport;
+mod transport;
+mod vendor;
+
+/// How message keys are named on the vendor's side.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VendorKeyFormat {
+    /// The plain message key, like `LOGIN_TITLE`.
+    #[default]
+    MessageKey,
+    /// The hashed message key, which is what translation files use when the plain keys aren't
+    /// meant to be shared.
+    HashedKey,
+}
+
+/// The result of pulling translations for a single locale.
+#[derive(Debug)]
+pub struct PulledTranslations {
+    /// The file that the translations were inserted as.
+    pub file_key: KeySymbol,
+    /// The number of translations that were inserted.
+    pub inserted: usize,
+    /// Vendor ids that didn't match any message in the database. These usually belong to messages
+    /// that have since been deleted.
+    pub unknown_ids: Vec<String>,
+}
+
+/// Return every source message in the database that should be translated, sorted by id.
+pub fn collect_source_strings(
+    database: &MessagesDatabase,
+    key_format: VendorKeyFormat,
+) -> Vec<VendorSourceString> {
+    let mut strings: Vec<VendorSourceString> = database
+        .messages
+        .values()
+        .filter(|message| message.meta().translate)
+        .filter_map(|message| {
+            let value = message.get_source_translation()?;
+            let locale = message.source_locale().as_ref()?;
+            let id = match key_format {
+                VendorKeyFormat::MessageKey => message.key().to_string(),
+                VendorKeyFormat::HashedKey => message.hashed_key().clone(),
+            };
+            Some(VendorSourceString {
+                id,
+                value: value.raw.to_string(),
+                description: message.meta().description.clone(),
+                locale: locale.to_string(),
+            })
+        })
+        .collect();
+    strings.sort_by(|a, b| a.id.cmp(&b.id));
+    strings
+}
+
+/// Push every translatable source message in the database to the vendor.
+pub fn push_source_messages(
+    database: &MessagesDatabase,
+    vendor: &dyn TranslationVendor,
+    transport: &dyn HttpTransport,
+    key_format: VendorKeyFormat,
+) -> VendorSyncResult<()> {
+    let strings = collect_source_strings(database, key_format);
+    vendor.push_source_strings(transport, &strings)
+}
+
+/// Pull every translation for `locale` from the vendor and insert them into the database as the
+/// translation file `file_name`, replacing any translations that file previously held.
+pub fn pull_translations(
+    database: &mut MessagesDatabase,
+    vendor: &dyn TranslationVendor,
+    transport: &dyn HttpTransport,
+    key_format: VendorKeyFormat,
+    file_name: &str,
+    locale: &str,
+) -> VendorSyncResult<PulledTranslations> {
+    let mut translations = vec![];
+    let mut unknown_ids = vec![];
+    for translation in vendor.pull_translations(transport, locale)? {
+        let key = match key_format {
+            VendorKeyFormat::MessageKey => database
+                .get_message(&translation.id)
+                .map(|message| message.key()),
+            VendorKeyFormat::HashedKey => database.hash_lookup.get(&translation.id).copied(),
+        };
+        match key {
+            Some(key) => translations.push(RawMessageTranslation::new(
+                key,
+                RawPosition::default(),
+                translation.value,
+            )),
+            None => unknown_ids.push(translation.id),
+        }
+    }
+
+    let inserted = translations.len();
+    let file_key = insert_translations(
+        database,
+        key_symbol(file_name),
+        key_symbol(locale),
+        translations.into_iter(),
+    )?;
+    Ok(PulledTranslations {
+        file_key,
+        inserted,
+        unknown_ids,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use intl_database_core::{key_symbol, MessagesDatabase};
+    use intl_message_database::public::process_definitions_file_content;
+
+    use crate::test_transport::FakeTransport;
+    use crate::{pull_translations, LokaliseVendor, VendorKeyFormat};
+
+    fn database() -> MessagesDatabase {
+        let mut database = MessagesDatabase::new();
+        process_definitions_file_content(
+            &mut database,
+            "/project/src/app.messages.js",
+            "import {defineMessages} from '@discord/intl';
+            export default defineMessages({ HELLO: 'Hello', BYE: 'Bye' });",
+            Some("en-US"),
+        )
+        .unwrap();
+        database
+    }
+
+    fn translation(database: &MessagesDatabase, key: &str, locale: &str) -> Option<String> {
+        let message = database.get_message(key)?;
+        let value = message.translations().get(&key_symbol(locale))?;
+        Some(value.raw.to_string())
+    }
+
+    #[test]
+    fn test_pull_translations() {
+        let mut database = database();
+        let transport = FakeTransport::default().with_response(
+            200,
+            r#"{"keys": [
+                {"key_id": 1, "key_name": {"web": "HELLO"}, "translations": [
+                    {"language_iso": "fr", "translation": "Bonjour"}
+                ]},
+                {"key_id": 2, "key_name": {"web": "DELETED"}, "translations": [
+                    {"language_iso": "fr", "translation": "Supprimé"}
+                ]}
+            ]}"#,
+        );
+        let vendor = LokaliseVendor::new("project", "token");
+
+        let pulled = pull_translations(
+            &mut database,
+            &vendor,
+            &transport,
+            VendorKeyFormat::MessageKey,
+            "/project/i18n/fr.messages.json",
+            "fr",
+        )
+        .unwrap();
+
+        assert_eq!(pulled.inserted, 1);
+        assert_eq!(pulled.unknown_ids, vec!["DELETED".to_string()]);
+        assert_eq!(
+            translation(&database, "HELLO", "fr").as_deref(),
+            Some("Bonjour")
+        );
+        assert_eq!(translation(&database, "BYE", "fr"), None);
+    }
+
+    #[test]
+    fn test_pull_translations_by_hashed_key() {
+        let mut database = database();
+        let hashed_key = database.get_message("BYE").unwrap().hashed_key().clone();
+        let transport = FakeTransport::default().with_response(
+            200,
+            &format!(
+                r#"{{"keys": [{{"key_id": 1, "key_name": {{"web": "{hashed_key}"}}, "translations": [
+                    {{"language_iso": "pt_BR", "translation": "Tchau"}}
+                ]}}]}}"#
+            ),
+        );
+        let vendor = LokaliseVendor::new("project", "token");
+
+        pull_translations(
+            &mut database,
+            &vendor,
+            &transport,
+            VendorKeyFormat::HashedKey,
+            "/project/i18n/pt-BR.messages.json",
+            "pt-BR",
+        )
+        .unwrap();
+
+        assert_eq!(
+            translation(&database, "BYE", "pt-BR").as_deref(),
+            Some("Tchau")
+        );
+    }
+
+    #[test]
+    fn test_pull_translations_replaces_previous_pull() {
+        let mut database = database();
+        let vendor = LokaliseVendor::new("project", "token");
+        let response = |key: &str| {
+            format!(
+                r#"{{"keys": [{{"key_id": 1, "key_name": {{"web": "{key}"}}, "translations": [
+                    {{"language_iso": "fr", "translation": "Traduit"}}
+                ]}}]}}"#
+            )
+        };
+        let file_name = "/project/i18n/fr.messages.json";
+
+        let transport = FakeTransport::default().with_response(200, &response("HELLO"));
+        pull_translations(
+            &mut database,
+            &vendor,
+            &transport,
+            VendorKeyFormat::MessageKey,
+            file_name,
+            "fr",
+        )
+        .unwrap();
+        let transport = FakeTransport::default().with_response(200, &response("BYE"));
+        pull_translations(
+            &mut database,
+            &vendor,
+            &transport,
+            VendorKeyFormat::MessageKey,
+            file_name,
+            "fr",
+        )
+        .unwrap();
+
+        assert_eq!(translation(&database, "HELLO", "fr"), None);
+        assert_eq!(
+            translation(&database, "BYE", "fr").as_deref(),
+            Some("Traduit")
+        );
+    }
+}
@@ -0,0 +1,358 @@
+//! [TranslationVendor] implementation for the [Lokalise API](https://developers.lokalise.com/reference/lokalise-rest-api).
+//!
+//! Every string is a Lokalise key on the `web` platform, named by its vendor id. Locales are
+//! mapped to Lokalise language codes by replacing hyphens with underscores, like `pt-BR` becoming
+//! `pt_BR`.
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::transport::{HttpMethod, HttpRequest, HttpResponse, HttpTransport};
+use crate::vendor::{TranslationVendor, VendorSourceString, VendorTranslation};
+use crate::{VendorSyncError, VendorSyncResult};
+
+const DEFAULT_BASE_URL: &str = "https://api.lokalise.com/api2";
+/// The most keys that Lokalise accepts or returns in a single request.
+const PAGE_SIZE: usize = 500;
+
+pub struct LokaliseVendor {
+    project_id: String,
+    api_token: String,
+    base_url: String,
+}
+
+impl LokaliseVendor {
+    pub fn new(project_id: &str, api_token: &str) -> Self {
+        Self {
+            project_id: project_id.into(),
+            api_token: api_token.into(),
+            base_url: DEFAULT_BASE_URL.into(),
+        }
+    }
+
+    /// Send requests to a different API host, like a proxy.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').into();
+        self
+    }
+
+    fn keys_url(&self) -> String {
+        format!("{}/projects/{}/keys", self.base_url, self.project_id)
+    }
+
+    fn send(
+        &self,
+        transport: &dyn HttpTransport,
+        request: HttpRequest,
+    ) -> VendorSyncResult<HttpResponse> {
+        let url = request.url.clone();
+        let response = transport.send(request.with_header("X-Api-Token", &self.api_token))?;
+        if !response.is_success() {
+            return Err(VendorSyncError::UnexpectedStatus {
+                vendor: self.name().into(),
+                url,
+                status: response.status,
+                body: response.body,
+            });
+        }
+        Ok(response)
+    }
+
+    /// Fetch every key in the project, following pagination until the last page.
+    fn list_keys(
+        &self,
+        transport: &dyn HttpTransport,
+        include_translations: bool,
+    ) -> VendorSyncResult<Vec<LokaliseKey>> {
+        let mut keys = vec![];
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "{}?include_translations={}&limit={PAGE_SIZE}&page={page}",
+                self.keys_url(),
+                include_translations as u8,
+            );
+            let response = self.send(transport, HttpRequest::new(HttpMethod::Get, url))?;
+            let body: LokaliseKeysResponse = self.parse(&response)?;
+            keys.extend(body.keys);
+
+            let page_count = response
+                .header("X-Pagination-Page-Count")
+                .and_then(|count| count.parse().ok())
+                .unwrap_or(1);
+            if page >= page_count {
+                return Ok(keys);
+            }
+            page += 1;
+        }
+    }
+
+    fn write_keys<T: Serialize>(
+        &self,
+        transport: &dyn HttpTransport,
+        method: HttpMethod,
+        keys: &[T],
+    ) -> VendorSyncResult<()> {
+        for chunk in keys.chunks(PAGE_SIZE) {
+            let body = serde_json::to_string(&LokaliseKeysRequest { keys: chunk })
+                .map_err(|error| self.invalid_response(error))?;
+            let request = HttpRequest::new(method, self.keys_url()).with_json_body(body);
+            self.send(transport, request)?;
+        }
+        Ok(())
+    }
+
+    fn parse<'a, T: Deserialize<'a>>(&self, response: &'a HttpResponse) -> VendorSyncResult<T> {
+        serde_json::from_str(&response.body).map_err(|error| self.invalid_response(error))
+    }
+
+    fn invalid_response(&self, error: serde_json::Error) -> VendorSyncError {
+        VendorSyncError::InvalidResponse(self.name().into(), error)
+    }
+}
+
+impl TranslationVendor for LokaliseVendor {
+    fn name(&self) -> &str {
+        "Lokalise"
+    }
+
+    fn push_source_strings(
+        &self,
+        transport: &dyn HttpTransport,
+        strings: &[VendorSourceString],
+    ) -> VendorSyncResult<()> {
+        // Lokalise only updates keys by their numeric id, so existing keys have to be looked up
+        // first to tell which strings are new.
+        let existing_ids: FxHashMap<String, u64> = self
+            .list_keys(transport, false)?
+            .into_iter()
+            .map(|key| (key.key_name.web, key.key_id))
+            .collect();
+
+        let mut created = vec![];
+        let mut updated = vec![];
+        for string in strings {
+            let translations = vec![LokaliseTranslation {
+                language_iso: language_iso(&string.locale),
+                translation: string.value.clone(),
+            }];
+            match existing_ids.get(&string.id) {
+                Some(key_id) => updated.push(LokaliseKeyUpdate {
+                    key_id: *key_id,
+                    description: string.description.clone(),
+                    translations,
+                }),
+                None => created.push(LokaliseKeyCreate {
+                    key_name: string.id.clone(),
+                    platforms: ["web"],
+                    description: string.description.clone(),
+                    translations,
+                }),
+            }
+        }
+
+        self.write_keys(transport, HttpMethod::Post, &created)?;
+        self.write_keys(transport, HttpMethod::Put, &updated)
+    }
+
+    fn pull_translations(
+        &self,
+        transport: &dyn HttpTransport,
+        locale: &str,
+    ) -> VendorSyncResult<Vec<VendorTranslation>> {
+        let language_iso = language_iso(locale);
+        let translations = self
+            .list_keys(transport, true)?
+            .into_iter()
+            .filter_map(|key| {
+                let translation = key.translations.into_iter().find(|translation| {
+                    translation.language_iso == language_iso && !translation.translation.is_empty()
+                })?;
+                Some(VendorTranslation {
+                    id: key.key_name.web,
+                    value: translation.translation,
+                })
+            })
+            .collect();
+        Ok(translations)
+    }
+}
+
+fn language_iso(locale: &str) -> String {
+    locale.replace('-', "_")
+}
+
+#[derive(Serialize)]
+struct LokaliseKeysRequest<'a, T> {
+    keys: &'a [T],
+}
+
+#[derive(Serialize)]
+struct LokaliseKeyCreate {
+    key_name: String,
+    platforms: [&'static str; 1],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    translations: Vec<LokaliseTranslation>,
+}
+
+#[derive(Serialize)]
+struct LokaliseKeyUpdate {
+    key_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    translations: Vec<LokaliseTranslation>,
+}
+
+#[derive(Deserialize)]
+struct LokaliseKeysResponse {
+    keys: Vec<LokaliseKey>,
+}
+
+#[derive(Deserialize)]
+struct LokaliseKey {
+    key_id: u64,
+    key_name: LokaliseKeyName,
+    #[serde(default)]
+    translations: Vec<LokaliseTranslation>,
+}
+
+/// Lokalise returns a name for every platform, even when they are all the same.
+#[derive(Deserialize)]
+struct LokaliseKeyName {
+    web: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct LokaliseTranslation {
+    language_iso: String,
+    translation: String,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_transport::FakeTransport;
+    use crate::transport::HttpMethod;
+    use crate::vendor::{TranslationVendor, VendorSourceString};
+    use crate::VendorSyncError;
+
+    use super::LokaliseVendor;
+
+    fn source_string(id: &str, value: &str) -> VendorSourceString {
+        VendorSourceString {
+            id: id.into(),
+            value: value.into(),
+            description: None,
+            locale: "en-US".into(),
+        }
+    }
+
+    #[test]
+    fn test_push_creates_and_updates_keys() {
+        let transport = FakeTransport::default()
+            .with_response(
+                200,
+                r#"{"keys": [{"key_id": 7, "key_name": {"web": "EXISTING"}}]}"#,
+            )
+            .with_response(200, "{}")
+            .with_response(200, "{}");
+        let vendor = LokaliseVendor::new("project", "token").with_base_url("https://proxy.test/");
+
+        vendor
+            .push_source_strings(
+                &transport,
+                &[
+                    source_string("EXISTING", "Old"),
+                    source_string("NEW", "New"),
+                ],
+            )
+            .unwrap();
+
+        let requests = transport.requests.borrow();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert_eq!(
+            requests[0].url,
+            "https://proxy.test/projects/project/keys?include_translations=0&limit=500&page=1"
+        );
+        assert!(requests.iter().all(|request| request
+            .headers
+            .contains(&("X-Api-Token".into(), "token".into()))));
+
+        assert_eq!(requests[1].method, HttpMethod::Post);
+        assert_eq!(
+            requests[1].body.as_deref(),
+            Some(
+                r#"{"keys":[{"key_name":"NEW","platforms":["web"],"translations":[{"language_iso":"en_US","translation":"New"}]}]}"#
+            )
+        );
+        assert_eq!(requests[2].method, HttpMethod::Put);
+        assert_eq!(
+            requests[2].body.as_deref(),
+            Some(
+                r#"{"keys":[{"key_id":7,"translations":[{"language_iso":"en_US","translation":"Old"}]}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_pull_follows_pagination() {
+        let transport = FakeTransport::default()
+            .with_paged_response(
+                200,
+                r#"{"keys": [{"key_id": 1, "key_name": {"web": "FIRST"}, "translations": [
+                    {"language_iso": "fr", "translation": "Premier"},
+                    {"language_iso": "de", "translation": "Erste"}
+                ]}]}"#,
+                Some(2),
+            )
+            .with_paged_response(
+                200,
+                r#"{"keys": [
+                    {"key_id": 2, "key_name": {"web": "SECOND"}, "translations": [
+                        {"language_iso": "fr", "translation": "Deuxième"}
+                    ]},
+                    {"key_id": 3, "key_name": {"web": "UNTRANSLATED"}, "translations": [
+                        {"language_iso": "fr", "translation": ""}
+                    ]}
+                ]}"#,
+                Some(2),
+            );
+        let vendor = LokaliseVendor::new("project", "token");
+
+        let translations = vendor.pull_translations(&transport, "fr").unwrap();
+
+        let pulled: Vec<(&str, &str)> = translations
+            .iter()
+            .map(|translation| (translation.id.as_str(), translation.value.as_str()))
+            .collect();
+        assert_eq!(pulled, vec![("FIRST", "Premier"), ("SECOND", "Deuxième")]);
+        let requests = transport.requests.borrow();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1]
+            .url
+            .ends_with("include_translations=1&limit=500&page=2"));
+    }
+
+    #[test]
+    fn test_error_status() {
+        let transport = FakeTransport::default().with_response(401, "Invalid token");
+        let vendor = LokaliseVendor::new("project", "token");
+
+        let error = vendor.pull_translations(&transport, "fr").unwrap_err();
+
+        assert!(matches!(
+            error,
+            VendorSyncError::UnexpectedStatus { status: 401, ref body, .. } if body == "Invalid token"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_response() {
+        let transport = FakeTransport::default().with_response(200, "not json");
+        let vendor = LokaliseVendor::new("project", "token");
+
+        let error = vendor.pull_translations(&transport, "fr").unwrap_err();
+
+        assert!(matches!(error, VendorSyncError::InvalidResponse(..)));
+    }
+}
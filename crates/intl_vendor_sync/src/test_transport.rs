@@ -0,0 +1,50 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::{VendorSyncError, VendorSyncResult};
+
+/// A transport that answers requests with a fixed sequence of responses and records every
+/// request it was sent, so vendors can be tested without a network connection.
+#[derive(Default)]
+pub(crate) struct FakeTransport {
+    responses: RefCell<VecDeque<HttpResponse>>,
+    pub(crate) requests: RefCell<Vec<HttpRequest>>,
+}
+
+impl FakeTransport {
+    pub(crate) fn with_response(self, status: u16, body: &str) -> Self {
+        self.with_paged_response(status, body, None)
+    }
+
+    pub(crate) fn with_paged_response(
+        self,
+        status: u16,
+        body: &str,
+        page_count: Option<usize>,
+    ) -> Self {
+        let headers = page_count
+            .map(|count| vec![("x-pagination-page-count".into(), count.to_string())])
+            .unwrap_or_default();
+        self.responses.borrow_mut().push_back(HttpResponse {
+            status,
+            headers,
+            body: body.into(),
+        });
+        self
+    }
+}
+
+impl HttpTransport for FakeTransport {
+    fn send(&self, request: HttpRequest) -> VendorSyncResult<HttpResponse> {
+        let url = request.url.clone();
+        self.requests.borrow_mut().push(request);
+        self.responses
+            .borrow_mut()
+            .pop_front()
+            .ok_or(VendorSyncError::Transport {
+                url,
+                message: "No response left".into(),
+            })
+    }
+}
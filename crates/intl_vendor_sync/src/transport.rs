@@ -0,0 +1,81 @@
+use crate::VendorSyncResult;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// A JSON body to send with the request. The `Content-Type` header is already included in
+    /// `headers` when this is set.
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new(method: HttpMethod, url: String) -> Self {
+        Self {
+            method,
+            url,
+            headers: vec![],
+            body: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_json_body(self, body: String) -> Self {
+        let mut request = self.with_header("Content-Type", "application/json");
+        request.body = Some(body);
+        request
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Return the value of the first header with the given name, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Sends requests to vendor APIs. This crate doesn't include an HTTP client of its own, so that
+/// callers can use whichever client their tooling already has, and so that vendors can be tested
+/// without a network connection.
+///
+/// Requests are sent one at a time, and a transport should only return an error when no response
+/// was received at all. Responses with error statuses are handled by the vendor.
+pub trait HttpTransport {
+    fn send(&self, request: HttpRequest) -> VendorSyncResult<HttpResponse>;
+}
@@ -0,0 +1,46 @@
+use crate::transport::HttpTransport;
+use crate::VendorSyncResult;
+
+/// A source message as it is sent to a vendor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VendorSourceString {
+    /// The id of the string on the vendor's side, see [crate::VendorKeyFormat].
+    pub id: String,
+    pub value: String,
+    /// Context for translators, taken from the message's `description` meta.
+    pub description: Option<String>,
+    /// The locale that the source message is written in, like `en-US`.
+    pub locale: String,
+}
+
+/// A translated string as it is returned from a vendor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VendorTranslation {
+    /// The id of the string on the vendor's side, see [crate::VendorKeyFormat].
+    pub id: String,
+    pub value: String,
+}
+
+/// A translation management service that source messages can be pushed to and translations can
+/// be pulled from. Implementations only build requests and interpret responses, and every request
+/// is sent through the given `transport`.
+pub trait TranslationVendor {
+    /// The name of the vendor, used in error messages.
+    fn name(&self) -> &str;
+
+    /// Create or update every string in `strings` on the vendor's side. Strings that exist on the
+    /// vendor's side but aren't included are left as they are.
+    fn push_source_strings(
+        &self,
+        transport: &dyn HttpTransport,
+        strings: &[VendorSourceString],
+    ) -> VendorSyncResult<()>;
+
+    /// Return every translated string for the given `locale`. Strings that haven't been
+    /// translated yet are not included.
+    fn pull_translations(
+        &self,
+        transport: &dyn HttpTransport,
+        locale: &str,
+    ) -> VendorSyncResult<Vec<VendorTranslation>>;
+}
@@ -1,6 +1,10 @@
-pub use serializer::{to_string, to_writer, Serializer};
+pub use options::SerializeOptions;
+pub use serializer::{
+    to_string, to_string_with_options, to_writer, to_writer_with_options, Serializer,
+};
 pub use string::write_escaped_str_contents;
 
 mod error;
+mod options;
 mod serializer;
 mod string;
@@ -0,0 +1,28 @@
+/// Options for how values are written. The defaults write the most compact output, which is what
+/// bundles are normally built with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// Write every element of an array or object on its own line, indented by two spaces for each
+    /// level of nesting, so that output can be read while debugging.
+    pub pretty: bool,
+    /// Write floats without a fractional part as integers, like `JSON.stringify` does, and floats
+    /// that aren't finite as `null`, so that each number is always written the same way no
+    /// matter how it was computed.
+    pub stable_floats: bool,
+}
+
+impl SerializeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    pub fn with_stable_floats(mut self, stable_floats: bool) -> Self {
+        self.stable_floats = stable_floats;
+        self
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{ser, Serialize};
 
 use crate::error::{Error, Result};
+use crate::options::SerializeOptions;
 use crate::string::write_escaped_str_contents;
 
 macro_rules! write_byte {
@@ -11,6 +12,35 @@ macro_rules! write_byte {
 
 pub struct Serializer<W> {
     writer: W,
+    options: SerializeOptions,
+    /// How many arrays and objects the value being written is nested in, for indenting it.
+    depth: usize,
+}
+
+impl<W: std::io::Write> Serializer<W> {
+    pub fn new(writer: W) -> Self {
+        Self::with_options(writer, SerializeOptions::default())
+    }
+
+    pub fn with_options(writer: W, options: SerializeOptions) -> Self {
+        Self {
+            writer,
+            options,
+            depth: 0,
+        }
+    }
+
+    /// Start a new line at the current depth when writing pretty output.
+    fn write_line_start(&mut self) -> std::io::Result<()> {
+        if !self.options.pretty {
+            return Ok(());
+        }
+        write_byte!(self.writer, b"\n")?;
+        for _ in 0..self.depth {
+            write_byte!(self.writer, b"  ")?;
+        }
+        Ok(())
+    }
 }
 
 #[inline]
@@ -56,16 +86,35 @@ where
     W: std::io::Write,
     T: ?Sized + Serialize,
 {
-    let mut ser = Serializer { writer };
+    to_writer_with_options(writer, value, SerializeOptions::default())
+}
+
+pub fn to_writer_with_options<W, T>(
+    writer: &mut W,
+    value: &T,
+    options: SerializeOptions,
+) -> Result<()>
+where
+    W: std::io::Write,
+    T: ?Sized + Serialize,
+{
+    let mut ser = Serializer::with_options(writer, options);
     value.serialize(&mut ser)
 }
 
 pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    to_string_with_options(value, SerializeOptions::default())
+}
+
+pub fn to_string_with_options<T>(value: &T, options: SerializeOptions) -> Result<String>
 where
     T: ?Sized + Serialize,
 {
     let mut buffer = Vec::with_capacity(128);
-    to_writer(&mut buffer, value)?;
+    to_writer_with_options(&mut buffer, value, options)?;
     // The output is guaranteed to be UTF-8
     Ok(unsafe { String::from_utf8_unchecked(buffer) })
 }
@@ -144,6 +193,16 @@ impl<'a, W: std::io::Write> ser::Serializer for &'a mut Serializer<W> {
     }
 
     fn serialize_f64(self, v: f64) -> Result<()> {
+        if self.options.stable_floats {
+            if !v.is_finite() {
+                return self.serialize_unit();
+            }
+            // Larger floats can't all be written exactly as integers, so they keep their
+            // exponent.
+            if v.fract() == 0.0 && v.abs() < 1e15 {
+                return self.serialize_i64(v as i64);
+            }
+        }
         let mut buffer = ryu::Buffer::new();
         Ok(self.writer.write_all(buffer.format_finite(v).as_bytes())?)
     }
@@ -240,15 +299,11 @@ impl<'a, W: std::io::Write> ser::Serializer for &'a mut Serializer<W> {
     where
         T: ?Sized + Serialize,
     {
-        write!(&mut self.writer, "[")?;
-        // writer.write_all(ARRAY_START)?;
-        self.serialize_u32(variant_index)?;
-        write!(&mut self.writer, ",")?;
-        // writer.write_all(ARRAY_SEPARATOR)?;
-        value.serialize(&mut *self)?;
-        write!(&mut self.writer, "]")?;
-        // self.writer.write_all(ARRAY_END)?;
-        Ok(())
+        use serde::ser::SerializeSeq;
+        let mut array = self.serialize_seq(Some(2))?;
+        array.serialize_element(&variant_index)?;
+        array.serialize_element(value)?;
+        array.end()
     }
 
     // Now we get to the serialization of compound types.
@@ -292,10 +347,9 @@ impl<'a, W: std::io::Write> ser::Serializer for &'a mut Serializer<W> {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        write_array_start(&mut self.writer)?;
-        self.serialize_u32(variant_index)?;
-        write_element_separator(&mut self.writer)?;
-        Ok(ArraySerializer::new(self))
+        let mut array = self.serialize_seq(None)?;
+        ser::SerializeSeq::serialize_element(&mut array, &variant_index)?;
+        Ok(array)
     }
 
     // Maps in keyless JSON are preserved as JSON objects, since it's the most compact possible
@@ -322,10 +376,9 @@ impl<'a, W: std::io::Write> ser::Serializer for &'a mut Serializer<W> {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        write_array_start(&mut self.writer)?;
-        variant_index.serialize(&mut *self)?;
-        write_element_separator(&mut self.writer)?;
-        Ok(ArraySerializer::new(self))
+        let mut array = self.serialize_seq(None)?;
+        ser::SerializeSeq::serialize_element(&mut array, &variant_index)?;
+        Ok(array)
     }
 }
 
@@ -339,6 +392,7 @@ pub struct ArraySerializer<'a, W: 'a> {
 
 impl<'a, W: std::io::Write> ArraySerializer<'a, W> {
     fn new(serializer: &'a mut Serializer<W>) -> Self {
+        serializer.depth += 1;
         Self {
             serializer,
             after_first: false,
@@ -351,6 +405,18 @@ impl<'a, W: std::io::Write> ArraySerializer<'a, W> {
         } else {
             self.after_first = true;
         }
+        self.serializer.write_line_start()?;
+        Ok(())
+    }
+
+    /// Leave the array or object, putting its closing bracket on its own line in pretty output
+    /// unless it's empty.
+    fn write_end(self, write_close: fn(&mut W) -> std::io::Result<()>) -> Result<()> {
+        self.serializer.depth -= 1;
+        if self.after_first {
+            self.serializer.write_line_start()?;
+        }
+        write_close(&mut self.serializer.writer)?;
         Ok(())
     }
 }
@@ -379,8 +445,7 @@ impl<'a, W: std::io::Write> ser::SerializeSeq for ArraySerializer<'a, W> {
 
     // Close the sequence.
     fn end(self) -> Result<()> {
-        write_array_end(&mut self.serializer.writer)?;
-        Ok(())
+        self.write_end(write_array_end)
     }
 }
 
@@ -457,13 +522,15 @@ impl<'a, W: std::io::Write> ser::SerializeMap for ArraySerializer<'a, W> {
         T: ?Sized + Serialize,
     {
         write_object_separator(&mut self.serializer.writer)?;
+        if self.serializer.options.pretty {
+            write_byte!(self.serializer.writer, b" ")?;
+        }
         value.serialize(&mut *self.serializer)?;
         Ok(())
     }
 
     fn end(self) -> Result<()> {
-        write_object_end(&mut self.serializer.writer)?;
-        Ok(())
+        self.write_end(write_object_end)
     }
 }
 
@@ -510,12 +577,22 @@ mod test {
 
     use serde::Serialize;
 
-    use super::to_string;
+    use crate::options::SerializeOptions;
+
+    use super::{to_string, to_string_with_options};
 
     fn assert_ser<T: ?Sized + Serialize>(value: &T, expected: &str) {
         assert_eq!(to_string(value).unwrap(), expected);
     }
 
+    fn assert_ser_with<T: ?Sized + Serialize>(
+        value: &T,
+        options: SerializeOptions,
+        expected: &str,
+    ) {
+        assert_eq!(to_string_with_options(value, options).unwrap(), expected);
+    }
+
     #[test]
     fn test_bool() {
         // Booleans are encoded as 0/1 to give the shortest representation in bytes
@@ -578,4 +655,47 @@ mod test {
 
         assert_ser(&map, r#"{"first":"value1","second":"value2"}"#);
     }
+
+    #[test]
+    fn test_pretty() {
+        #[derive(Serialize)]
+        enum E {
+            Struct { a: u32, b: Vec<u32> },
+        }
+
+        let mut map = BTreeMap::new();
+        map.insert("first", E::Struct { a: 1, b: vec![] });
+        map.insert("second", E::Struct { a: 2, b: vec![3] });
+
+        let options = SerializeOptions::new().with_pretty(true);
+        assert_ser_with(
+            &map,
+            options,
+            r#"{
+  "first": [
+    0,
+    1,
+    []
+  ],
+  "second": [
+    0,
+    2,
+    [
+      3
+    ]
+  ]
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_stable_floats() {
+        let options = SerializeOptions::new().with_stable_floats(true);
+        assert_ser(&2.0, "2.0");
+        assert_ser_with(&2.0, options, "2");
+        assert_ser_with(&-0.0, options, "0");
+        assert_ser_with(&0.5, options, "0.5");
+        assert_ser_with(&1e20, options, "1e20");
+        assert_ser_with(&f64::NAN, options, "null");
+    }
 }
@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use intl_markdown::{
+    compile_to_format_js, format_to_icu_string, parse_intl_message, CompileArena,
+};
+
+fuzz_target!(|content: &str| {
+    // Messages are parsed both with and without blocks depending on their content, so both modes
+    // need to hold up, along with everything that consumes the resulting document.
+    for include_blocks in [false, true] {
+        let document = parse_intl_message(content, include_blocks);
+        let _ = compile_to_format_js(&document, &CompileArena::new());
+        let _ = format_to_icu_string(&document);
+    }
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use intl_database_core::MessageTranslationSource;
+use intl_database_json_source::JsonMessageSource;
+
+fuzz_target!(|content: &str| {
+    // Parsing each value also covers the message parser with whatever escapes the JSON content
+    // decodes to.
+    if let Ok(translations) =
+        JsonMessageSource.extract_translations("en-US.messages.jsona".into(), content.into())
+    {
+        for translation in translations {
+            let _ = translation.value.variables();
+        }
+    }
+});
@@ -30,7 +30,8 @@ function getAllowedMessageObjectIdentifers(source, state) {
         nativePath.dirname(state.file.opts.filename),
         source,
       );
-  const isDefinition = isMessageDefinitionsFile(importSource);
+  // @ts-expect-error state is untyped but contains `opts` from the config.
+  const isDefinition = isMessageDefinitionsFile(importSource, state.opts?.definitionsExtensions);
   // @ts-expect-error state is untyped but contains `opts` from the config.
   const extraImportSpecifiers = state.opts?.extraImports?.[importSource] ?? [];
   return [extraImportSpecifiers, isDefinition];
@@ -38,8 +39,9 @@ function getAllowedMessageObjectIdentifers(source, state) {
 
 /**
  * Babel visitor for traversing all intl message accesses in a file. This visitor can be applied to
- * every file in a project, but only member expressions of imports from `.messages.js` files and any
- * imports as specified in the `extraImports` configuration are considered and affected.
+ * every file in a project, but only member expressions of imports from `.messages.js` files, files
+ * with any of the `definitionsExtensions` from the configuration, and any imports as specified in
+ * the `extraImports` configuration are considered and affected.
  *
  * Configuration for `extraImports` differs from swc-intl-message-transformer in that
  * paths here are resolved, _absolute_ paths to the desired file, since babel
@@ -1,9 +1,15 @@
 module.exports = {
   rules: {
+    'no-concatenated-fragments': require('./rules/native/no-concatenated-fragments'),
+    'no-excessive-complexity': require('./rules/native/no-excessive-complexity'),
+    'no-mismatched-plural-forms': require('./rules/native/no-mismatched-plural-forms'),
     'no-repeated-plural-names': require('./rules/native/no-repeated-plural-names'),
     'no-repeated-plural-options': require('./rules/native/no-repeated-plural-options'),
+    'no-stray-pound-signs': require('./rules/native/no-stray-pound-signs'),
+    'no-suspicious-apostrophes': require('./rules/native/no-suspicious-apostrophes'),
     'no-trimmable-whitespace': require('./rules/native/no-trimmable-whitespace'),
     'no-unicode-variable-names': require('./rules/native/no-unicode-variable-names'),
+    'no-unknown-hook-names': require('./rules/native/no-unknown-hook-names'),
 
     'use-static-access': require('./rules/use-static-access'),
     'no-opaque-messages-objects': require('./rules/no-opaque-messages-objects'),
@@ -17,6 +23,12 @@ module.exports = {
         '@discord/discord-intl/no-repeated-plural-names': 'error',
         '@discord/discord-intl/no-repeated-plural-options': 'error',
         '@discord/discord-intl/no-unicode-variable-names': 'error',
+        '@discord/discord-intl/no-suspicious-apostrophes': 'warn',
+        '@discord/discord-intl/no-unknown-hook-names': 'error',
+        '@discord/discord-intl/no-excessive-complexity': 'warn',
+        '@discord/discord-intl/no-concatenated-fragments': 'warn',
+        '@discord/discord-intl/no-mismatched-plural-forms': 'warn',
+        '@discord/discord-intl/no-stray-pound-signs': 'warn',
 
         // JS rules
         '@discord/discord-intl/use-static-access': 'error',
@@ -18,9 +18,10 @@ const FILE_VALIDATIONS = new Map();
  *
  * @param {string} fileName
  * @param {string} content
+ * @param {import('@discord/intl-message-database').IntlValidatorOptions=} options
  * @return {Record<string, IntlDiagnostic[]>}
  */
-function processAndValidateNative(fileName, content) {
+function processAndValidateNative(fileName, content, options) {
   const fileKey = crypto.hash('sha1', content);
   const existing = FILE_VALIDATIONS.get(fileKey);
   if (existing != null) return existing;
@@ -33,7 +34,7 @@ function processAndValidateNative(fileName, content) {
   processDefinitionsFile(processingFileName, content, { processTranslations: false });
   /** @type {Record<string, IntlDiagnostic[]>} */
   const validations = {};
-  for (const diagnostic of database.validateMessages()) {
+  for (const diagnostic of database.validateMessages(options)) {
     if (diagnostic.file === processingFileName) {
       (validations[diagnostic.key] ??= []).push(diagnostic);
     }
@@ -52,8 +53,19 @@ function processAndValidateNative(fileName, content) {
  * @returns {RuleListener}
  */
 function traverseAndReportMatchingNativeValidations(context, predicate) {
-  if (!isDefinitionsFile(context.filename, context.sourceCode.text)) return {};
-  const validations = processAndValidateNative(context.filename, context.sourceCode.text);
+  const config = context.settings['@discord/discord-intl'];
+  const definitionsExtensions = config?.definitionsExtensions;
+  if (!isDefinitionsFile(context.filename, context.sourceCode.text, definitionsExtensions)) {
+    return {};
+  }
+  database.setHandlerNames(config?.hookNames, config?.linkHandlerNames);
+  // The file is processed through the shared database, which has to know the extensions too.
+  if (definitionsExtensions != null) database.setDefinitionsExtensions(definitionsExtensions);
+  const validations = processAndValidateNative(context.filename, context.sourceCode.text, {
+    complexity: config?.complexity,
+    fragments: config?.fragments,
+    pluralSuffixes: config?.pluralSuffixes,
+  });
 
   return traverseMessageDefinitions(context, (definition, value, _definition, name) => {
     if (name == null) return;
@@ -22,6 +22,8 @@ const { isMessageDefinitionsFile } = require('@discord/intl-loader-core');
 /**
  * @typedef DiscordIntlPluginConfig
  * @property {Record<string, string[]>} [extraImports]
+ * @property {string[]} [definitionsExtensions] Extensions of definitions files in addition to the
+ * built-in `.messages.js` variants, matching the `definitionsExtensions` of the message database.
  */
 
 /**
@@ -32,7 +34,9 @@ const { isMessageDefinitionsFile } = require('@discord/intl-loader-core');
 function getImportedMessagesObjectSpecifiers(config, path) {
   const importSource = /** @type {string} */ (path.source.value);
   // TODO: Make `isMessageDefinitionsFile` understand this properly.
-  const isDefinition = isMessageDefinitionsFile(importSource) || importSource.endsWith('.messages');
+  const isDefinition =
+    isMessageDefinitionsFile(importSource, config?.definitionsExtensions) ||
+    importSource.endsWith('.messages');
   const extraImportSpecifiers = config?.extraImports?.[importSource] ?? [];
   // This transformer only handles usages of intl messages, so only
   // imports of definitions files and configured extra specifiers need to
@@ -125,9 +129,10 @@ function traverseMessageAccesses(context, callback) {
  *
  * @param {string} fileName
  * @param {string} content
+ * @param {string[]=} definitionsExtensions
  */
-function isDefinitionsFile(fileName, content) {
-  if (isMessageDefinitionsFile(fileName)) return true;
+function isDefinitionsFile(fileName, content, definitionsExtensions) {
+  if (isMessageDefinitionsFile(fileName, definitionsExtensions)) return true;
   // Any file importing `defineMessages` _should_ be a message definitions file with
   // the expected structure.
   return content.match('import { ?defineMessages ?} from [\'"]@discord/intl[\'"]') != null;
@@ -0,0 +1,17 @@
+const { traverseAndReportMatchingNativeValidations } = require('../../lib/native-validation');
+
+module.exports = /** @type {import('eslint').Rule.RuleModule} */ ({
+  meta: {
+    docs: {
+      description:
+        'Disallow messages that look like fragments of a sentence meant to be joined with other messages at runtime, using the patterns in the `fragments` setting',
+      category: 'Best Practices',
+    },
+  },
+  create(context) {
+    return traverseAndReportMatchingNativeValidations(
+      context,
+      (diagnostic) => diagnostic.name === 'NoConcatenatedFragments',
+    );
+  },
+});
@@ -0,0 +1,17 @@
+const { traverseAndReportMatchingNativeValidations } = require('../../lib/native-validation');
+
+module.exports = /** @type {import('eslint').Rule.RuleModule} */ ({
+  meta: {
+    docs: {
+      description:
+        'Disallow messages that are very long or deeply nested, using the thresholds in the `complexity` setting',
+      category: 'Best Practices',
+    },
+  },
+  create(context) {
+    return traverseAndReportMatchingNativeValidations(
+      context,
+      (diagnostic) => diagnostic.name === 'NoExcessiveComplexity',
+    );
+  },
+});
@@ -0,0 +1,17 @@
+const { traverseAndReportMatchingNativeValidations } = require('../../lib/native-validation');
+
+module.exports = /** @type {import('eslint').Rule.RuleModule} */ ({
+  meta: {
+    docs: {
+      description:
+        'Disallow plural options that appear to use the wrong form of a word, like `one {# items}`, using the suffixes in the `pluralSuffixes` setting',
+      category: 'Possible Errors',
+    },
+  },
+  create(context) {
+    return traverseAndReportMatchingNativeValidations(
+      context,
+      (diagnostic) => diagnostic.name === 'NoMismatchedPluralForms',
+    );
+  },
+});
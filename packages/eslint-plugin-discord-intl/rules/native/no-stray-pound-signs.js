@@ -0,0 +1,17 @@
+const { traverseAndReportMatchingNativeValidations } = require('../../lib/native-validation');
+
+module.exports = /** @type {import('eslint').Rule.RuleModule} */ ({
+  meta: {
+    docs: {
+      description:
+        'Disallow `#` outside of plurals in messages with a number variable, where it is rendered literally instead of as the number',
+      category: 'Possible Errors',
+    },
+  },
+  create(context) {
+    return traverseAndReportMatchingNativeValidations(
+      context,
+      (diagnostic) => diagnostic.name === 'NoStrayPoundSigns',
+    );
+  },
+});
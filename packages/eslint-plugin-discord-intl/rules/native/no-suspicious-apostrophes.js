@@ -0,0 +1,17 @@
+const { traverseAndReportMatchingNativeValidations } = require('../../lib/native-validation');
+
+module.exports = /** @type {import('eslint').Rule.RuleModule} */ ({
+  meta: {
+    docs: {
+      description:
+        'Disallow apostrophes that escape placeholders or are doubled, since both are rendered literally',
+      category: 'Best Practices',
+    },
+  },
+  create(context) {
+    return traverseAndReportMatchingNativeValidations(
+      context,
+      (diagnostic) => diagnostic.name === 'NoSuspiciousApostrophes',
+    );
+  },
+});
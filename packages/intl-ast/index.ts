@@ -18,7 +18,13 @@ export enum FormatJsNodeType {
 // Everything in the `@discord/intl` system works with this compressed AST format, but utilities are
 // provided to convert between this and the FormatJS compatible version as needed.
 export type LiteralNode = string;
-export type ArgumentNode = [FormatJsNodeType.Argument, string];
+/**
+ * The third item of an argument is its default value, shown when no value is given for it, from
+ * `{name|default}` in the message source.
+ */
+export type ArgumentNode =
+  | [FormatJsNodeType.Argument, string]
+  | [FormatJsNodeType.Argument, string, string];
 export type NumberNode = [FormatJsNodeType.Number, string, string | undefined];
 export type DateNode = [FormatJsNodeType.Date, string, string | undefined];
 export type TimeNode = [FormatJsNodeType.Time, string, string | undefined];
@@ -49,6 +55,7 @@ export const enum AstNodeIndices {
   Value = 1,
   Style = 2,
   Options = 2,
+  Default = 2,
   Offset = 3,
   PluralType = 4,
   Children = 2,
@@ -67,6 +74,10 @@ export interface FullFormatJsLiteral {
 export interface FullFormatJsArgument {
   type: FormatJsNodeType.Argument;
   value: string;
+  /**
+   * Extension to FormatJS' AST: the text shown when no value is given for the argument.
+   */
+  default?: string;
 }
 
 export interface FullFormatJsNumber {
@@ -172,7 +183,9 @@ function hydrateSingle(keyless: string | Array<any>): FullFormatJsNode {
   const [type] = keyless;
   switch (type) {
     case FormatJsNodeType.Argument:
-      return { type, value: keyless[1] };
+      return keyless.length > 2
+        ? { type, value: keyless[1], default: keyless[2] }
+        : { type, value: keyless[1] };
     case FormatJsNodeType.Number:
     case FormatJsNodeType.Date:
     case FormatJsNodeType.Time:
@@ -256,7 +269,9 @@ export function compressFormatJsToAst(
     case FormatJsNodeType.Literal:
       return node.value;
     case FormatJsNodeType.Argument:
-      return [node.type, node.value];
+      return node.default != null
+        ? [node.type, node.value, node.default]
+        : [node.type, node.value];
     case FormatJsNodeType.Number:
     case FormatJsNodeType.Date:
     case FormatJsNodeType.Time:
@@ -1,17 +1,19 @@
 const {
   hashMessageKey,
-  isMessageDefinitionsFile,
   isMessageTranslationsFile,
+  IntlBundleKeyMode,
   IntlCompiledMessageFormat,
 } = require('@discord/intl-message-database');
 
-const { database } = require('./src/database');
+const { database, isMessageDefinitionsFile } = require('./src/database');
 const {
   findAllDefinitionsFilesForTranslations,
   findAllMessagesFiles,
   filterAllMessagesFiles,
+  getBuildManifest,
   processAllMessagesFiles,
   generateTypeDefinitions,
+  getMessageKeyAliases,
   processDefinitionsFile,
   processTranslationsFile,
   precompileFileForLocale,
@@ -24,12 +26,15 @@ module.exports = {
   // @ts-expect-error This is a const enum, which TypeScript doesn't like letting you export, even
   // though it's a tangible object that can be accessed just fine from normal JS.
   IntlCompiledMessageFormat,
+  // @ts-expect-error Same as above.
+  IntlBundleKeyMode,
   MessageDefinitionsTransformer,
   database,
   findAllTranslationFiles,
   findAllDefinitionsFilesForTranslations,
   getLocaleFromTranslationsFileName,
   generateTypeDefinitions,
+  getMessageKeyAliases,
   hashMessageKey,
   isMessageDefinitionsFile,
   isMessageTranslationsFile,
@@ -38,6 +43,7 @@ module.exports = {
   precompileFileForLocale,
   findAllMessagesFiles,
   filterAllMessagesFiles,
+  getBuildManifest,
   processAllMessagesFiles,
   watcher,
 };
@@ -1,4 +1,7 @@
-const { IntlMessagesDatabase } = require('@discord/intl-message-database');
+const {
+  IntlMessagesDatabase,
+  isMessageDefinitionsFile: isMessageDefinitionsFileWithExtensions,
+} = require('@discord/intl-message-database');
 
 /**
  * A shared message database instance that's used and shared across all parts
@@ -6,4 +9,21 @@ const { IntlMessagesDatabase } = require('@discord/intl-message-database');
  */
 const database = new IntlMessagesDatabase();
 
-module.exports = { database };
+/**
+ * Returns true if `filePath` is a definitions file, including files with any of the extensions
+ * configured for the shared database with `database.setDefinitionsExtensions`, or with any of
+ * `definitionsExtensions` when given. Consumers that don't process messages through the shared
+ * database, like babel and eslint plugins, should pass the extensions they were configured with.
+ *
+ * @param {string} filePath
+ * @param {string[]=} definitionsExtensions
+ * @returns {boolean}
+ */
+function isMessageDefinitionsFile(filePath, definitionsExtensions) {
+  return isMessageDefinitionsFileWithExtensions(
+    filePath,
+    definitionsExtensions ?? database.getDefinitionsExtensions(),
+  );
+}
+
+module.exports = { database, isMessageDefinitionsFile };
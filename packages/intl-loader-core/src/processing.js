@@ -1,7 +1,10 @@
 const path = require('node:path');
 
 const debug = require('debug')('intl:loader-core');
-const { IntlCompiledMessageFormat } = require('@discord/intl-message-database');
+const {
+  IntlCompiledMessageFormat,
+  IntlTypesOutputFormat,
+} = require('@discord/intl-message-database');
 
 const { database } = require('./database');
 const { findAllTranslationFiles, getLocaleFromTranslationsFileName } = require('./util');
@@ -10,6 +13,7 @@ const { findAllTranslationFiles, getLocaleFromTranslationsFileName } = require('
  * @typedef {{
  *   format?: IntlCompiledMessageFormat,
  *   bundleSecrets?: boolean,
+ *   keyMode?: import('@discord/intl-message-database').IntlBundleKeyMode,
  * }} IntlPrecompileOptions
  */
 
@@ -74,12 +78,16 @@ function findAllDefinitionsFilesForTranslations(filePath) {
  * Scan the entire file system within the given `directories` to find all files that can be treated
  * as messages definitions _or_ translations.
  *
+ * Build output and other large directories can be skipped with `options.exclude`, which takes globs
+ * in the same syntax as `.gitignore`.
+ *
  * @param {string[]} directories
  * @param {string} defaultLocale
+ * @param {import('../types').IntlFileDiscoveryOptions=} options
  * @returns {import('../types').IntlMessagesFileDescriptor[]}
  */
-function findAllMessagesFiles(directories, defaultLocale = 'en-US') {
-  return database.findAllMessagesFiles(directories, defaultLocale);
+function findAllMessagesFiles(directories, defaultLocale = 'en-US', options) {
+  return database.findAllMessagesFiles(directories, defaultLocale, options);
 }
 
 /**
@@ -110,6 +118,20 @@ function processAllMessagesFiles(files) {
   return database.processAllMessagesFiles(files);
 }
 
+/**
+ * Return everything needed to build every definitions file known to the database in a single call:
+ * hashed message keys, and the translations file, compiled artifact path, and content hash for
+ * each locale. Artifacts are named the same way `precompileMessageDefinitionsFiles` and the Metro
+ * transformer name them. Plugins can use this once per build instead of querying each file
+ * separately.
+ *
+ * @param {string=} artifactExtension Extension for the names of compiled artifacts, `json` by default.
+ * @returns {import('../types').IntlBuildManifestEntry[]}
+ */
+function getBuildManifest(artifactExtension) {
+  return database.getBuildManifest(artifactExtension);
+}
+
 /**
  * @param {string} sourcePath
  * @param {string=} sourceContent
@@ -195,6 +217,33 @@ function processTranslationsFile(sourcePath, sourceContent, options = {}) {
   };
 }
 
+/**
+ * Return the alias of each of the given hashed message keys, as assigned to the bundles compiled
+ * with `IntlBundleKeyMode.Alias`.
+ *
+ * Aliases are assigned over every message defined in the database, so they are only accurate once
+ * all of the definitions files have been processed, and the bundles have to be compiled from the
+ * same set of definitions.
+ *
+ * @param {Record<string, string>} messageKeys Map of hashed keys to their original values, like
+ * the `messageKeys` returned by `processDefinitionsFile`.
+ * @returns {Record<string, string>}
+ */
+function getMessageKeyAliases(messageKeys) {
+  /** @type {Record<string, string>} */
+  const aliasesByKey = {};
+  for (const [alias, key] of Object.entries(database.getMessageKeyAliases())) {
+    aliasesByKey[key] = alias;
+  }
+
+  /** @type {Record<string, string>} */
+  const aliases = {};
+  for (const [hashedKey, key] of Object.entries(messageKeys)) {
+    if (key in aliasesByKey) aliases[hashedKey] = aliasesByKey[key];
+  }
+  return aliases;
+}
+
 /**
  * Precompile the messages defined in the given `sourcePath` using the value of the translation for
  * that message in the given `locale`. `format` specifies which serialization format the result
@@ -229,29 +278,57 @@ function precompileFileForLocale(sourcePath, locale, outputFile, options = {}) {
  *
  * If not given, `outputFile` will default to the same path as `sourcePath`, with the last extension
  * replaced by `.d.ts`. For example, a file like `SomeMessages.Other.messages.js` would become
- * `SomeMessages.Other.messages.d.ts`.
+ * `SomeMessages.Other.messages.d.ts`. When `options.format` selects Flow, the default is
+ * `SomeMessages.Other.messages.js.flow` instead, and for JSDoc it is
+ * `SomeMessages.Other.messages.types.js`.
+ *
+ * With `localeMap` set in `options`, the definitions also declare a `messageLocales` map listing
+ * the locales that define each message, so coverage can be checked without loading the database.
  *
  * Returns `true` if the types were successfully generated, or `false` otherwise, such as if the
  * source file is not already in the database.
  *
  * @param {string} sourcePath
  * @param {string=} outputFile
+ * @param {import('@discord/intl-message-database').IntlTypesGeneratorOptions} [options]
  * @returns {boolean}
  */
-function generateTypeDefinitions(sourcePath, outputFile) {
+function generateTypeDefinitions(sourcePath, outputFile, options = {}) {
   const paths = database.getAllSourceFilePaths();
   if (!paths.includes(sourcePath)) return false;
 
-  database.generateTypes(sourcePath, outputFile ?? sourcePath.replace(/\.[^.]+$/, '.d.ts'));
+  database.generateTypes(
+    sourcePath,
+    outputFile ?? getDefaultTypesFilePath(sourcePath, options.format),
+    options,
+  );
   return true;
 }
 
+/**
+ * @param {string} sourcePath
+ * @param {IntlTypesOutputFormat=} format
+ * @returns {string}
+ */
+function getDefaultTypesFilePath(sourcePath, format) {
+  switch (format) {
+    case IntlTypesOutputFormat.Flow:
+      return sourcePath + '.flow';
+    case IntlTypesOutputFormat.JsDoc:
+      return sourcePath.replace(/\.[^.]+$/, '.types.js');
+    default:
+      return sourcePath.replace(/\.[^.]+$/, '.d.ts');
+  }
+}
+
 module.exports = {
   findAllDefinitionsFilesForTranslations,
   findAllMessagesFiles,
   filterAllMessagesFiles,
+  getBuildManifest,
   processAllMessagesFiles,
   generateTypeDefinitions,
+  getMessageKeyAliases,
   precompileFileForLocale,
   processDefinitionsFile,
   processTranslationsFile,
@@ -118,23 +118,41 @@ class MessageDefinitionsTransformer {
    * @returns {string[]}
    */
   createLoaderAndBinds() {
+    const aliasLines = this.messageKeyAliasesSetup();
+    const aliasesArgument = aliasLines.length > 0 ? ', _aliases' : '';
     if (this.options.preGenerateBinds) {
       const bindLines = Object.keys(this.options.messageKeys).map(
         (bind) => `"${bind}"(locale) { return ${this.loaderName}.get("${bind}", locale) }`,
       );
       return [
+        ...aliasLines,
         `const binds = {${bindLines.join(',')}};`,
-        `const ${this.loaderName} = createLoader(Object.keys(binds), _locales, _defaultLocale);`,
+        `const ${this.loaderName} = createLoader(Object.keys(binds), _locales, _defaultLocale${aliasesArgument});`,
       ];
     } else {
       return [
+        ...aliasLines,
         `const _keys = ${JSON.stringify(Object.keys(this.options.messageKeys))};`,
-        `const ${this.loaderName} = createLoader(_keys, _locales, _defaultLocale);`,
+        `const ${this.loaderName} = createLoader(_keys, _locales, _defaultLocale${aliasesArgument});`,
         `const binds = ${this.loaderName}.getBinds();`,
       ];
     }
   }
 
+  /**
+   * Return the list of aliases for the message keys, in the same order as the keys given to the
+   * loader, when the translations are compiled with aliased keys.
+   *
+   * @returns {string[]}
+   */
+  messageKeyAliasesSetup() {
+    const { messageKeyAliases } = this.options;
+    if (messageKeyAliases == null) return [];
+
+    const aliases = Object.keys(this.options.messageKeys).map((key) => messageKeyAliases[key]);
+    return [`const _aliases = ${JSON.stringify(aliases)};`];
+  }
+
   /**
    * Return the lines to export fields from this module, as determined by the `exportMode` on this
    * transformer.
@@ -2,12 +2,9 @@ const path = require('node:path');
 
 const chokidar = require('chokidar');
 const debug = require('debug')('intl:loader-core:watcher');
-const {
-  isMessageDefinitionsFile,
-  IntlCompiledMessageFormat,
-} = require('@discord/intl-message-database');
+const { IntlCompiledMessageFormat } = require('@discord/intl-message-database');
 
-const { database } = require('./database');
+const { database, isMessageDefinitionsFile } = require('./database');
 const {
   processDefinitionsFile,
   precompileFileForLocale,
@@ -124,8 +121,11 @@ async function precompileMessageDefinitionsFiles(
   { assetExtension = 'json', precompileOptions = {} } = {},
 ) {
   watcher.on('all', (_, filePath) => {
-    const { format = IntlCompiledMessageFormat.KeylessJson, bundleSecrets = false } =
-      precompileOptions;
+    const {
+      format = IntlCompiledMessageFormat.KeylessJson,
+      bundleSecrets = false,
+      keyMode,
+    } = precompileOptions;
     if (!isMessageDefinitionsFile(filePath)) return;
 
     debug(`Processing file: ${filePath}`);
@@ -139,12 +139,14 @@ async function precompileMessageDefinitionsFiles(
       precompileFileForLocale(filePath, result.locale, undefined, {
         format,
         bundleSecrets,
+        keyMode,
       });
 
       database.processDefinitionsFile(filePath);
       database.precompile(filePath, DEFAULT_LOCALE, outputPath, {
         format: IntlCompiledMessageFormat.KeylessJson,
         bundleSecrets,
+        keyMode,
       });
       debug(`Wrote definitions to: ${outputPath}`);
     } catch (e) {
@@ -9,6 +9,12 @@ export interface MessageDefinitionsTransformerOptions {
    * included in the transformed file to provide context in errors and warnings.
    */
   messageKeys: Record<string, string>;
+  /**
+   * Map of hashed message keys to their aliases, like the result of `getMessageKeyAliases`. Only
+   * needed when the translations are compiled with `IntlBundleKeyMode.Alias`, so that the loader
+   * can look up messages by their aliases.
+   */
+  messageKeyAliases?: Record<string, string>;
   /**
    * Map of locale names to import paths used for loading translations.
    */
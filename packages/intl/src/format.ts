@@ -84,6 +84,15 @@ export function bindFormatValuesWithBuilder<
     }
 
     const variableName = node[AstNodeIndices.Value];
+    // Arguments with a default value, like `{name|Someone}`, show it in place of a missing value.
+    if (
+      nodeType === FormatJsNodeType.Argument &&
+      node[AstNodeIndices.Default] != null &&
+      values[variableName] == null
+    ) {
+      builder.pushLiteralText(node[AstNodeIndices.Default] as string);
+      continue;
+    }
     // Enforce that all required values are provided by the caller, even if the
     // actual value is falsy/undefined.
     if (!(variableName in values) && !isRichTextTag(variableName)) {
@@ -23,6 +23,9 @@ export enum RichTextNodeType {
   Emphasis = 'em',
   Strikethrough = 's',
   Code = 'inlineCode',
+  Emoji = 'emoji',
+  Mention = 'mention',
+  KeyboardKey = 'kbd',
   Link = 'link',
   Paragraph = 'paragraph',
 }
@@ -46,6 +49,17 @@ interface RichTextStrikethroughNode extends RichTextNodeBase<RichTextNode[]> {
 interface RichTextCodeNode extends RichTextNodeBase<RichTextNode[]> {
   type: RichTextNodeType.Code;
 }
+interface RichTextEmojiNode extends RichTextNodeBase<RichTextNode[]> {
+  type: RichTextNodeType.Emoji;
+  name: object;
+}
+interface RichTextMentionNode extends RichTextNodeBase<RichTextNode[]> {
+  type: RichTextNodeType.Mention;
+  sigil: object;
+}
+interface RichTextKeyboardKeyNode extends RichTextNodeBase<RichTextNode[]> {
+  type: RichTextNodeType.KeyboardKey;
+}
 interface RichTextParagraphNode extends RichTextNodeBase<RichTextNode[]> {
   type: RichTextNodeType.Paragraph;
 }
@@ -60,6 +74,9 @@ export type RichTextNode =
   | RichTextEmphasisNode
   | RichTextStrikethroughNode
   | RichTextCodeNode
+  | RichTextEmojiNode
+  | RichTextMentionNode
+  | RichTextKeyboardKeyNode
   | RichTextParagraphNode
   | RichTextLinkNode;
 
@@ -68,6 +85,9 @@ const AST_RICH_TEXT_ELEMENTS: RichTextFormattingMap<AstFunctionTypes['hook']> =
   $i: (content) => ({ type: RichTextNodeType.Emphasis, content }),
   $del: (content) => ({ type: RichTextNodeType.Strikethrough, content }),
   $code: (content) => ({ type: RichTextNodeType.Code, content }),
+  $emoji: (content, _, [name]) => ({ type: RichTextNodeType.Emoji, name, content }),
+  $mention: (content, _, [sigil]) => ({ type: RichTextNodeType.Mention, sigil, content }),
+  $kbd: (content) => ({ type: RichTextNodeType.KeyboardKey, content }),
   $link: (content, _, [target]) => ({
     type: RichTextNodeType.Link,
     target,
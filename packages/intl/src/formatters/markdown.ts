@@ -23,6 +23,9 @@ const MARKDOWN_RICH_TEXT_ELEMENTS: RichTextFormattingMap<MarkdownFunctionTypes['
   $i: (content) => '*' + content.join('') + '*',
   $del: (content) => '~~' + content.join('') + '~~',
   $code: (content) => '`' + content.join('') + '`',
+  $emoji: (content) => content.join(''),
+  $mention: (content, _, [sigil]) => '<' + sigil + content.join('') + '>',
+  $kbd: (content) => '[[' + content.join('') + ']]',
   $link: (content, _, [target]) => '[' + content.join('') + '](' + target + ')',
   $p: (content) => content.join('') + '\n\n',
 };
@@ -31,6 +31,9 @@ export const DEFAULT_REACT_RICH_TEXT_ELEMENTS: RichTextFormattingMap<ReactFuncti
   $i: (content, key) => h('em', { key }, content),
   $del: (content, key) => h('del', { key }, content),
   $code: (content, key) => h('code', { key }, content),
+  $emoji: (content, key, [name]) => h('span', { key, 'data-emoji': name }, content),
+  $mention: (content, key, [sigil]) => h('span', { key, 'data-mention': sigil }, content),
+  $kbd: (content, key) => h('kbd', { key }, content),
   $link: (content, key, [href]) => h('a', { href, key }, content),
   $p: (content, key) => h('p', { key }, content),
 };
@@ -1,4 +1,5 @@
 import { AstNode, FullFormatJsNode } from '@discord/intl-ast';
+import { runtimeHashMessageKey } from './hash';
 import { InternalIntlMessage } from './message';
 
 /**
@@ -9,6 +10,17 @@ import { InternalIntlMessage } from './message';
  */
 type MessagesData = Record<string, AstNode[] | FullFormatJsNode[]>;
 
+/**
+ * Entry in development bundles mapping each hashed message key to its original key.
+ */
+const KEYS_BUNDLE_KEY = '$keys';
+/**
+ * Entry marking bundles that aren't keyed by hashed message keys. Its value is `"original"` for
+ * development bundles keyed by original message keys, or `"alias"` for bundles keyed by short,
+ * sequential aliases from the alias manifest of the messages database.
+ */
+const KEY_MODE_BUNDLE_KEY = '$keyMode';
+
 export type IntlMessageGetter = (this: MessageLoader, locale: LocaleId) => InternalIntlMessage;
 
 export type LocaleId = string;
@@ -17,6 +29,11 @@ export type LocaleImportMap = Record<LocaleId, () => Promise<{ default: Messages
 
 export class MessageLoader {
   messageKeys: string[];
+  /**
+   * Alias of each of the `messageKeys` at the same index, for loading bundles that are keyed by
+   * aliases rather than hashed keys. Only given when the bundles are compiled with aliases.
+   */
+  messageKeyAliases: string[] | undefined;
   /** Map of locale to string key to message content. */
   messages: Record<LocaleId, MessagesData>;
   localeImportMap: LocaleImportMap;
@@ -85,8 +102,14 @@ export class MessageLoader {
    */
   _localeFileMap?: Record<string, string>;
 
-  constructor(messageKeys: string[], localeImportMap: LocaleImportMap, defaultLocale: LocaleId) {
+  constructor(
+    messageKeys: string[],
+    localeImportMap: LocaleImportMap,
+    defaultLocale: LocaleId,
+    messageKeyAliases?: string[],
+  ) {
     this.messageKeys = messageKeys;
+    this.messageKeyAliases = messageKeyAliases;
     this.messages = {};
     this.localeImportMap = localeImportMap;
     this.supportedLocales = Object.keys(localeImportMap);
@@ -240,11 +263,74 @@ export class MessageLoader {
     const current = this.localeImportMap[locale]();
     const isAlreadyInitialized = this._localeLoadingPromises[locale]?.initialized ?? false;
     this._localeLoadingPromises[locale] = { initialized: isAlreadyInitialized, current };
-    this.messages[locale] = (await current).default;
+    this.messages[locale] = this._readMessagesData((await current).default);
     this._localeLoadingPromises[locale] = { initialized: true, current: undefined };
     this.emitChange();
   }
 
+  /**
+   * Prepare the data of a loaded bundle for looking messages up by their hashed keys. Aliased
+   * bundles are re-keyed using the alias of each of this loader's hashed keys. Development bundles can be keyed by original message keys, which are hashed here, or can
+   * carry a map of hashed keys to original keys. Either way, the original keys are added to the
+   * debug key map so that warnings about the messages are readable.
+   */
+  _readMessagesData(data: MessagesData): MessagesData {
+    const entries = data as Record<string, unknown>;
+    if (!(KEYS_BUNDLE_KEY in entries) && !(KEY_MODE_BUNDLE_KEY in entries)) return data;
+
+    const {
+      [KEYS_BUNDLE_KEY]: keys,
+      [KEY_MODE_BUNDLE_KEY]: keyMode,
+      ...messages
+    } = entries as Record<string, any>;
+    if (keyMode === 'alias') return this._resolveAliases(messages);
+
+    const keyMap: Record<string, string> = { ...keys };
+    if (keyMode !== 'original') {
+      this._debugKeyMap = { ...this._debugKeyMap, ...keyMap };
+      return messages;
+    }
+
+    const hashedMessages: MessagesData = {};
+    for (const [key, content] of Object.entries(messages)) {
+      // Other reserved entries, like `$plurals`, are never hashed.
+      if (key.startsWith('$')) {
+        hashedMessages[key] = content;
+        continue;
+      }
+      const hashedKey = runtimeHashMessageKey(key);
+      hashedMessages[hashedKey] = content;
+      keyMap[hashedKey] = key;
+    }
+    this._debugKeyMap = { ...this._debugKeyMap, ...keyMap };
+    return hashedMessages;
+  }
+
+  /**
+   * Re-key a bundle keyed by aliases, using the alias given for each hashed key when this loader
+   * was created.
+   */
+  _resolveAliases(messages: MessagesData): MessagesData {
+    const hashedMessages: MessagesData = {};
+    for (const [key, content] of Object.entries(messages)) {
+      // Other reserved entries, like `$plurals`, are never aliased.
+      if (key.startsWith('$')) hashedMessages[key] = content;
+    }
+    if (this.messageKeyAliases == null) {
+      console.warn(
+        '[@discord/intl] A bundle keyed by aliases was loaded, but no aliases were given for its messages.',
+      );
+      return hashedMessages;
+    }
+    this.messageKeys.forEach((hashedKey, index) => {
+      const alias = this.messageKeyAliases![index];
+      if (alias != null && alias in messages) {
+        hashedMessages[hashedKey] = messages[alias];
+      }
+    });
+    return hashedMessages;
+  }
+
   /**
    * Inform subscribers that the loader state has changed and they should
    * potentially update to get new values for messages.
@@ -330,14 +416,16 @@ export async function waitForAllDefaultIntlMessagesLoaded(): Promise<void> {
 /**
  * Create a new MessageLoader, which handles lazily loading messages for
  * different locales and sanity checks as needed to provide accessors for each
- * message defined in `messageKeys`.
+ * message defined in `messageKeys`. Loaders for bundles keyed by aliases also need the alias of
+ * each key, in the same order as `messageKeys`.
  */
 export function createLoader(
   messageKeys: string[],
   localeImportMap: LocaleImportMap,
   defaultLocale: LocaleId,
+  messageKeyAliases?: string[],
 ) {
-  const loader = new MessageLoader(messageKeys, localeImportMap, defaultLocale);
+  const loader = new MessageLoader(messageKeys, localeImportMap, defaultLocale, messageKeyAliases);
   LOADER_REGISTRY.push(loader);
   return loader;
 }
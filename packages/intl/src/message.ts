@@ -41,7 +41,11 @@ function serializeAst(ast: AstNode[], result: { value: string }) {
 
     switch (node[AstNodeIndices.Type]) {
       case FormatJsNodeType.Argument:
-        result.value += '{' + node[AstNodeIndices.Value] + '}';
+        result.value += '{' + node[AstNodeIndices.Value];
+        if (node[AstNodeIndices.Default] != null) {
+          result.value += '|' + node[AstNodeIndices.Default];
+        }
+        result.value += '}';
         break;
       case FormatJsNodeType.Date:
         result.value += '{' + node[AstNodeIndices.Value] + ', date';
@@ -116,6 +120,21 @@ function serializeAstTag(node: TagNode, result: { value: string }) {
       serializeAst(node[AstNodeIndices.Children], result);
       result.value += '`';
       break;
+    case '$emoji':
+      // Emoji keep their original shortcode as their content.
+      serializeAst(node[AstNodeIndices.Children], result);
+      break;
+    case '$mention':
+      result.value += '<';
+      serializeAst(node[AstNodeIndices.Control], result);
+      serializeAst(node[AstNodeIndices.Children], result);
+      result.value += '>';
+      break;
+    case '$kbd':
+      result.value += '[[';
+      serializeAst(node[AstNodeIndices.Children], result);
+      result.value += ']]';
+      break;
     case '$p':
       serializeAst(node[AstNodeIndices.Children], result);
       result.value += '\n\n';
@@ -270,6 +270,9 @@ export interface RichTextFormattingMap<T = any> {
   $link: T;
   $code: T;
   $del: T;
+  $emoji: T;
+  $mention: T;
+  $kbd: T;
 }
 
 export type RichTextTagNames = keyof RichTextFormattingMap;
@@ -0,0 +1,45 @@
+// Runs against the compiled output in `dist`, so build the package before running these.
+const test = require('node:test');
+const assert = require('node:assert');
+const { FormatJsNodeType } = require('@discord/intl-ast');
+const { FormatBuilder, bindFormatValuesWithBuilder } = require('../dist/format');
+
+class StringBuilder extends FormatBuilder {
+  result = '';
+
+  pushRichTextTag() {}
+
+  pushLiteralText(text) {
+    this.result += text;
+  }
+
+  pushObject(value) {
+    this.result += String(value);
+  }
+
+  finish() {
+    return [this.result];
+  }
+}
+
+function format(nodes, values) {
+  const builder = new StringBuilder();
+  bindFormatValuesWithBuilder(builder, nodes, 'en-US', {}, {}, values);
+  return builder.finish()[0];
+}
+
+const WITH_DEFAULT = ['Hello, ', [FormatJsNodeType.Argument, 'name', 'friend'], '!'];
+const WITHOUT_DEFAULT = ['Hello, ', [FormatJsNodeType.Argument, 'name'], '!'];
+
+test('uses the default when no value is given', () => {
+  assert.strictEqual(format(WITH_DEFAULT, {}), 'Hello, friend!');
+  assert.strictEqual(format(WITH_DEFAULT, { name: undefined }), 'Hello, friend!');
+});
+
+test('prefers a given value over the default', () => {
+  assert.strictEqual(format(WITH_DEFAULT, { name: 'Wumpus' }), 'Hello, Wumpus!');
+});
+
+test('still requires values for arguments without a default', () => {
+  assert.throws(() => format(WITHOUT_DEFAULT, {}));
+});
@@ -0,0 +1,44 @@
+// Runs against the compiled output in `dist`, so build the package before running these.
+const test = require('node:test');
+const assert = require('node:assert');
+const { MessageLoader } = require('../dist/message-loader');
+const { runtimeHashMessageKey } = require('../dist/hash');
+
+const HASHED_KEY = runtimeHashMessageKey('GREETING');
+
+async function load(data, aliases) {
+  const localeImportMap = { 'en-US': async () => ({ default: data }) };
+  const loader = new MessageLoader([HASHED_KEY], localeImportMap, 'en-US', aliases);
+  await loader.waitForDefaultLocale();
+  return loader;
+}
+
+test('reads bundles keyed by hashed keys', async () => {
+  const loader = await load({ [HASHED_KEY]: ['Hello!'] });
+  assert.strictEqual(loader.getMessageValue(HASHED_KEY, 'en-US').reserialize(), 'Hello!');
+  assert.strictEqual(loader._debugKeyMap, undefined);
+});
+
+test('hashes the keys of bundles keyed by original keys', async () => {
+  const loader = await load({ GREETING: ['Hello!'], $keyMode: 'original' });
+  assert.strictEqual(loader.getMessageValue(HASHED_KEY, 'en-US').reserialize(), 'Hello!');
+  assert.strictEqual(loader.getMessageValue('GREETING', 'en-US'), undefined);
+  assert.deepStrictEqual(loader._debugKeyMap, { [HASHED_KEY]: 'GREETING' });
+});
+
+test('reads the original keys of bundles with a key map', async () => {
+  const loader = await load({ [HASHED_KEY]: ['Hello!'], $keys: { [HASHED_KEY]: 'GREETING' } });
+  assert.strictEqual(loader.getMessageValue(HASHED_KEY, 'en-US').reserialize(), 'Hello!');
+  assert.strictEqual(loader.getMessageValue('$keys', 'en-US'), undefined);
+  assert.deepStrictEqual(loader._debugKeyMap, { [HASHED_KEY]: 'GREETING' });
+});
+
+test('resolves hashed keys from the aliases of aliased bundles', async () => {
+  const loader = await load({ a: ['Hello!'], b: ['Other'], $plurals: {}, $keyMode: 'alias' }, [
+    'a',
+  ]);
+  assert.strictEqual(loader.getMessageValue(HASHED_KEY, 'en-US').reserialize(), 'Hello!');
+  assert.strictEqual(loader.getMessageValue('a', 'en-US'), undefined);
+  assert.strictEqual(loader.getMessageValue('b', 'en-US'), undefined);
+  assert.deepStrictEqual(loader.messages['en-US'].$plurals, {});
+});
@@ -7,6 +7,7 @@ const {
   getLocaleFromTranslationsFileName,
   precompileFileForLocale,
   IntlCompiledMessageFormat,
+  database,
 } = require('@discord/intl-loader-core');
 
 module.exports = {
@@ -14,10 +15,15 @@ module.exports = {
   /**
    * @param {string} source
    * @param {string} filePath
-   * @param {object} _config
+   * @param {{transformerConfig?: {definitionsExtensions?: string[]}}} options
    * @returns {string}
    */
-  process(source, filePath, _config) {
+  process(source, filePath, options) {
+    // Extensions of definitions files in addition to the built-in `.messages.js` variants, given
+    // as the options of this transformer in the Jest config.
+    const definitionsExtensions = options?.transformerConfig?.definitionsExtensions;
+    if (definitionsExtensions != null) database.setDefinitionsExtensions(definitionsExtensions);
+
     const precompileOptions = {
       format: IntlCompiledMessageFormat.KeylessJson,
       // Always bundle secrets when running tests
@@ -8,6 +8,7 @@ const {
   processTranslationsFile,
   precompileFileForLocale,
   IntlCompiledMessageFormat,
+  database,
   findAllMessagesFiles,
   processAllMessagesFiles,
 } = require('@discord/intl-loader-core');
@@ -28,11 +29,19 @@ const {
  * Whether messages marked as `secret` will be preserved in the bundled message assets. When false,
  * secret messages have their values replaced with obfuscated text to prevent leaking information.
  *
+ * @property {import('@discord/intl-loader-core').IntlBundleKeyMode=} keyMode
+ * How messages are keyed in the bundled message assets. Defaults to hashed keys. Aliased bundles
+ * are smaller and are resolved by `@discord/intl` at runtime, so call sites are unaffected.
+ *
  * @property {IntlCompiledMessageFormat} format
  * The format to which messages should be compiled during bundling. `Json` will cause the messages
  * to be compiled to a FormatJS-like compatible format, while `KeylessJson` will use a much more
  * compressed, `@discord/intl`-specific format. `KeylessJson` is the default.
  *
+ * @property {string[]=} definitionsExtensions
+ * Extensions of definitions files in addition to the built-in `.messages.js` variants, like
+ * `.messages.mjs`. Must match the `definitionsExtensions` given to the transformer.
+ *
  * @property {string[]} watchFolders
  * The folders Metro is configured to watch. A necessary argument to be able to preload all message
  * definitions and ensure that merged translations files handle secrets appropriately based on
@@ -122,9 +131,18 @@ let hasInitializedAllDefinitions = false;
  */
 async function transformAsset(assetData) {
   const [config, metroConfig] = await pluginConfig;
-  const { cacheDir, assetExtension, format, bundleSecrets, watchFolders } = config;
+  const {
+    cacheDir,
+    assetExtension,
+    format,
+    bundleSecrets,
+    keyMode,
+    definitionsExtensions,
+    watchFolders,
+  } = config;
 
   if (!hasInitializedAllDefinitions) {
+    if (definitionsExtensions != null) database.setDefinitionsExtensions(definitionsExtensions);
     debug('Initializing database with all messages files within watch folders: %O', watchFolders);
     const result = processAllMessagesFiles(findAllMessagesFiles(watchFolders));
     debug('Finished processing all discovered messages files: %O', result);
@@ -160,6 +178,7 @@ async function transformAsset(assetData) {
   precompileFileForLocale(filename, result.locale, outputFile, {
     format,
     bundleSecrets,
+    keyMode,
   });
 
   return {
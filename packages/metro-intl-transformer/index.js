@@ -6,6 +6,9 @@ const {
   processTranslationsFile,
   precompileFileForLocale,
   IntlCompiledMessageFormat,
+  database,
+  getMessageKeyAliases,
+  IntlBundleKeyMode,
 } = require('@discord/intl-loader-core');
 const debug = require('debug')('intl:metro-intl-transformer');
 
@@ -18,7 +21,9 @@ const debug = require('debug')('intl:metro-intl-transformer');
  *  getTranslationImport: (importPath: string) => string,
  *  format?: IntlCompiledMessageFormat,
  *  bundleSecrets?: boolean,
+ *  keyMode?: import('@discord/intl-loader-core').IntlBundleKeyMode,
  *  preGenerateBinds?: boolean,
+ *  definitionsExtensions?: string[],
  * }} options
  * @returns {string | Buffer}
  */
@@ -30,16 +35,22 @@ function transformToString({
   getTranslationImport,
   format = IntlCompiledMessageFormat.KeylessJson,
   bundleSecrets = false,
+  keyMode,
   preGenerateBinds = true,
+  definitionsExtensions,
 }) {
+  // Definitions are processed through the shared database, which has to know the extensions too.
+  if (definitionsExtensions != null) database.setDefinitionsExtensions(definitionsExtensions);
   if (isMessageDefinitionsFile(filename)) {
     debug(`[${filename}] Processing as a definitions file`);
     const result = processDefinitionsFile(filename, src, {
       // TODO: Make this more configurable
       locale: 'en-US',
     });
+    // Everything from the first `.` of the file name is replaced, so this works for any of the
+    // definitions extensions.
     const compiledSourcePath = filename.replace(
-      /\.messages\.js$/,
+      /\.[^/\\]*$/,
       `.compiled.messages.${getTranslationAssetExtension()}`,
     );
 
@@ -51,6 +62,8 @@ function transformToString({
 
     return new MessageDefinitionsTransformer({
       messageKeys: result.messageKeys,
+      messageKeyAliases:
+        keyMode === IntlBundleKeyMode.Alias ? getMessageKeyAliases(result.messageKeys) : undefined,
       localeMap: result.translationsLocaleMap,
       defaultLocale: result.locale,
       getTranslationImport,
@@ -66,6 +79,7 @@ function transformToString({
     return precompileFileForLocale(filename, result.locale, undefined, {
       format,
       bundleSecrets,
+      keyMode,
     });
   }
 
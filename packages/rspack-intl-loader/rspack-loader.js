@@ -12,6 +12,9 @@ const {
   processAllMessagesFiles,
   findAllMessagesFiles,
   findAllDefinitionsFilesForTranslations,
+  database,
+  getMessageKeyAliases,
+  IntlBundleKeyMode,
 } = require('@discord/intl-loader-core');
 const debug = require('debug')('intl:rspack-intl-loader');
 
@@ -41,9 +44,11 @@ let hasInitializedAllDefinitions = false;
  * @this {import('webpack').LoaderContext<{
  *   format: IntlCompiledMessageFormat,
  *   bundleSecrets: boolean,
+ *   keyMode?: import('@discord/intl-loader-core').IntlBundleKeyMode,
  *   jsonExportMode?: 'rspack' | 'webpack',
  *   preGenerateBinds?: boolean,
- *   watchFolders?: string[]
+ *   watchFolders?: string[],
+ *   definitionsExtensions?: string[]
  * }>}
  */
 const intlLoader = function intlLoader(source) {
@@ -51,13 +56,16 @@ const intlLoader = function intlLoader(source) {
   const forceTranslation = this.resourceQuery === '?forceTranslation';
   const {
     bundleSecrets = false,
+    keyMode,
     format = IntlCompiledMessageFormat.KeylessJson,
     jsonExportMode = 'rspack',
     preGenerateBinds,
     watchFolders = [this._compiler?.context ?? process.cwd()],
+    definitionsExtensions,
   } = this.getOptions();
 
   if (!hasInitializedAllDefinitions) {
+    if (definitionsExtensions != null) database.setDefinitionsExtensions(definitionsExtensions);
     debug('Initializing database with all messages files within watch folders: %O', watchFolders);
     const result = processAllMessagesFiles(findAllMessagesFiles(watchFolders));
     debug('Finished processing all discovered messages files: %O', result);
@@ -73,6 +81,13 @@ const intlLoader = function intlLoader(source) {
       locale: 'en-US',
     });
 
+    // Entries that couldn't be extracted are left out of the compiled messages, so make sure
+    // authors know about them rather than finding out at runtime.
+    for (const error of result.sourceFile.errors ?? []) {
+      const { line, col } = error.position;
+      this.emitWarning(new Error(`${sourcePath}:${line}:${col}: ${error.message}`));
+    }
+
     // Ensure that rspack knows to watch all of the translations files, even though they aren't
     // directly imported from a source. Without this, even though the compiled loader references the
     // transpiled message files, it won't trigger refreshes when those files change.
@@ -102,6 +117,8 @@ const intlLoader = function intlLoader(source) {
 
     return new MessageDefinitionsTransformer({
       messageKeys: result.messageKeys,
+      messageKeyAliases:
+        keyMode === IntlBundleKeyMode.Alias ? getMessageKeyAliases(result.messageKeys) : undefined,
       localeMap: result.translationsLocaleMap,
       defaultLocale: result.locale,
       getTranslationImport: (importPath) => `import("${importPath}")`,
@@ -131,6 +148,7 @@ const intlLoader = function intlLoader(source) {
     const compiledResult = precompileFileForLocale(sourcePath, locale, undefined, {
       format,
       bundleSecrets,
+      keyMode,
     });
 
     // Translations are still treated as JS files that need to be pre-parsed.
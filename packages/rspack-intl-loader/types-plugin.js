@@ -8,13 +8,20 @@ const {
  * A plugin that watches for changes to I18n strings and updates messages.d.ts (and its sourcemap) automatically.
  */
 class IntlTypeGeneratorPlugin {
+  /**
+   * @param {import('@discord/intl-message-database').IntlTypesGeneratorOptions} [options]
+   */
+  constructor(options = {}) {
+    this.options = options;
+  }
+
   /**
    * @param {string} filePath
    * @returns {number} How long it took to generate the type definitions file.
    */
   generateTypeDefinitions(filePath) {
     const start = performance.now();
-    generateTypeDefinitions(filePath, undefined);
+    generateTypeDefinitions(filePath, undefined, this.options);
     const end = performance.now();
 
     return end - start;
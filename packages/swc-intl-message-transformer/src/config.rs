@@ -1,11 +1,16 @@
 use std::collections::HashMap;
 
+use intl_message_utils::is_message_definitions_file_with_extensions;
 use serde::Deserialize;
 
 #[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct IntlMessageTransformerConfig {
     pub extra_imports: Option<HashMap<String, Vec<String>>>,
+    /// Extensions of definitions files in addition to the built-in `.messages.js` variants, the
+    /// same as the `definitionsExtensions` the database was created with.
+    #[serde(default)]
+    pub definitions_extensions: Vec<String>,
 }
 
 impl IntlMessageTransformerConfig {
@@ -18,4 +23,8 @@ impl IntlMessageTransformerConfig {
             None => None,
         }
     }
+
+    pub fn is_definitions_import(&self, specifier: &str) -> bool {
+        is_message_definitions_file_with_extensions(specifier, &self.definitions_extensions)
+    }
 }
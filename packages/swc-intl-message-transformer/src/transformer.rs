@@ -6,7 +6,7 @@ use swc_core::ecma::ast::{
 };
 use swc_core::ecma::visit::{VisitMut, VisitMutWith};
 
-use intl_message_utils::{hash_message_key, is_message_definitions_file};
+use intl_message_utils::hash_message_key;
 
 use crate::IntlMessageTransformerConfig;
 
@@ -37,7 +37,7 @@ impl VisitMut for IntlMessageConsumerTransformer {
     fn visit_mut_import_decl(&mut self, import_decl: &mut ImportDecl) {
         let import_source_path = &import_decl.src.value;
 
-        let is_definitions_file = is_message_definitions_file(&import_source_path);
+        let is_definitions_file = self.config.is_definitions_import(import_source_path);
         let extra_import_specifiers = self
             .config
             .get_configured_names_for_import_specifier(import_source_path);